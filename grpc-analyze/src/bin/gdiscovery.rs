@@ -0,0 +1,40 @@
+//! Discovers the UDM repeatedly through a `DiscoveryCache` over gRPC.
+
+use std::time::Instant;
+
+use clap::Parser;
+use nnrf_discovery_server::cache::DiscoveryCache;
+use nnrf_discovery_server::models::{NfType, ServiceName};
+use nnrf_discovery_server::registry::SearchQuery;
+use nrf_grpc::nnrf_disc::nrf_discovery_client::NrfDiscoveryClient;
+
+#[derive(Parser)]
+pub struct Opts {
+    #[clap(short = 'n', long, default_value = "http://127.0.0.1:9090")]
+    pub nrf: String,
+    #[clap(short = 'r', long, default_value = "10")]
+    pub runs: usize,
+}
+
+#[tokio::main]
+async fn main() {
+    env_logger::init();
+
+    let opts = Opts::parse();
+
+    let nrf = NrfDiscoveryClient::connect(opts.nrf).await.unwrap();
+    let cache = DiscoveryCache::new(nrf);
+
+    let mut query = SearchQuery::new(NfType::UDM, NfType::SMF);
+    query.service_names = Some(vec![ServiceName::new("nudm-sdm".into())]);
+
+    for _ in 0..opts.runs {
+        let now = Instant::now();
+        if cache.search(&query).await.is_ok() {
+            println!("discovery,grpc,{},ns", now.elapsed().as_nanos());
+        }
+    }
+
+    let stats = cache.stats();
+    println!("cache,grpc,{},{}", stats.hits, stats.misses);
+}
@@ -3,15 +3,21 @@
 use clap::Parser;
 use hyper::service::{make_service_fn, service_fn};
 use hyper::{Body, Error, Response, Server};
+use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
 use url::Url;
 
+use nnrf_discovery_server::cache::DiscoveryCache;
+use nnrf_discovery_server::models::{NfType, ServiceName};
+use nnrf_discovery_server::registry::SearchQuery;
+use nnrf_discovery_server::selection::{NfSelector, SelectionPolicy};
 use nrf_grpc::nnrf_disc::nrf_discovery_client::NrfDiscoveryClient;
 use nrf_grpc::nnrf_disc::SearchRequest;
 
+use nsfm_pdusession::nas;
 use sfm_grpc::nsfm_pdusession::smf_client::SmfClient;
 use sfm_grpc::nsfm_pdusession::{
     CreateSmContextRequest, Guami, Ncgi, NrLocation, Nssai, PlmnId, Tai, UeLocation,
@@ -35,10 +41,7 @@ async fn establish_session(
 
     // println!("Calling SMF");
 
-    let nas_data = [
-        0x2E, 0x01, 0x01, 0xC1, 0xFF, 0xFF, 0x91, 0xA1, 0x28, 0x01, 0x00, 0x7B, 0x00, 0x07, 0x80,
-        0x00, 0x0A, 0x00, 0x00, 0x0D, 0x00,
-    ];
+    let nas_data = nas::Message::ipv4_establishment_request(1, 1).encode();
 
     let smf_req = CreateSmContextRequest {
         supi: "imsi-001011234567895".into(),
@@ -155,6 +158,12 @@ pub struct Opts {
     pub smf: String,
     #[clap(short = 's', long, default_value = "10")]
     pub runs: usize,
+    /// NRF the SMFs are discovered from, instead of using --smf
+    #[clap(long)]
+    pub nrf: Option<String>,
+    /// How the SMF is chosen: weighted, load-aware or round-robin
+    #[clap(long, default_value = "weighted")]
+    pub selection: SelectionPolicy,
 }
 
 // rt may be unused if there are no examples
@@ -168,7 +177,21 @@ async fn main() {
     let smf_base_url = opts.smf.clone();
 
     let runs = opts.runs;
-    let mut smf_client = SmfClient::connect(smf_base_url).await.unwrap();
+    let mut smf_clients: HashMap<String, SmfClient<Channel>> = HashMap::new();
+    smf_clients.insert(
+        smf_base_url.clone(),
+        SmfClient::connect(smf_base_url.clone()).await.unwrap(),
+    );
+
+    let discovery = match &opts.nrf {
+        Some(nrf) => Some(DiscoveryCache::new(
+            NrfDiscoveryClient::connect(nrf.clone()).await.unwrap(),
+        )),
+        None => None,
+    };
+    let selector = NfSelector::new(opts.selection);
+    let mut query = SearchQuery::new(NfType::SMF, NfType::AMF);
+    query.service_names = Some(vec![ServiceName::new("nsmf-pdusession".into())]);
 
     let flag = Arc::new(AtomicBool::new(false));
 
@@ -182,7 +205,36 @@ async fn main() {
 
     while i < runs {
         let now = Instant::now();
-        match establish_session(&mut smf_client, flag.clone()).await {
+
+        let smf_url = match &discovery {
+            Some(discovery) => match discovery.search(&query).await {
+                Ok(result) => selector
+                    .select(&result.nf_instances, "nsmf-pdusession")
+                    .map(|endpoint| endpoint.uri),
+                Err(e) => {
+                    info!("SMF discovery failed: {}", e);
+                    None
+                }
+            },
+            None => None,
+        }
+        .unwrap_or_else(|| smf_base_url.clone());
+        if !smf_clients.contains_key(&smf_url) {
+            match SmfClient::connect(smf_url.clone()).await {
+                Ok(client) => {
+                    smf_clients.insert(smf_url.clone(), client);
+                }
+                Err(e) => {
+                    info!("Unable to connect to SMF {}: {}", smf_url, e);
+                    continue;
+                }
+            }
+        }
+        let smf_client = smf_clients
+            .get_mut(&smf_url)
+            .expect("SMF client inserted above");
+
+        match establish_session(smf_client, flag.clone()).await {
             Ok(_) => {
                 let delta = now.elapsed();
                 println!("establishment,grpc,{},ns", delta.as_nanos());
@@ -0,0 +1,56 @@
+//! Discovers the UDM repeatedly through a `DiscoveryCache` over HTTP.
+
+use std::time::Instant;
+
+use clap::Parser;
+use nnrf_discovery_server::cache::DiscoveryCache;
+use nnrf_discovery_server::models::{NfType, ServiceName};
+use nnrf_discovery_server::registry::SearchQuery;
+use nnrf_discovery_server::{Client as NRFClient, ContextWrapperExt as NRFContextWrapperExt};
+use swagger::{AuthData, ContextBuilder, EmptyContext, Push, XSpanIdString};
+
+type ClientContext = swagger::make_context_ty!(
+    ContextBuilder,
+    EmptyContext,
+    Option<AuthData>,
+    XSpanIdString
+);
+
+#[derive(Parser)]
+pub struct Opts {
+    #[clap(short = 'n', long, default_value = "http://127.0.0.1:8080")]
+    pub nrf: String,
+    #[clap(short = 'r', long, default_value = "10")]
+    pub runs: usize,
+}
+
+#[tokio::main]
+async fn main() {
+    env_logger::init();
+
+    let opts = Opts::parse();
+
+    let context: ClientContext = swagger::make_context!(
+        ContextBuilder,
+        EmptyContext,
+        None as Option<AuthData>,
+        XSpanIdString::default()
+    );
+    let nrf = NRFClient::try_new_http(&opts.nrf)
+        .expect("Failed to create HTTP client")
+        .with_context(context);
+    let cache = DiscoveryCache::new(nrf);
+
+    let mut query = SearchQuery::new(NfType::UDM, NfType::SMF);
+    query.service_names = Some(vec![ServiceName::new("nudm-sdm".into())]);
+
+    for _ in 0..opts.runs {
+        let now = Instant::now();
+        if cache.search(&query).await.is_ok() {
+            println!("discovery,http,{},ns", now.elapsed().as_nanos());
+        }
+    }
+
+    let stats = cache.stats();
+    println!("cache,http,{},{}", stats.hits, stats.misses);
+}
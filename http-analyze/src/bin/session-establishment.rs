@@ -1,5 +1,6 @@
 #![allow(missing_docs, unused_variables, trivial_casts)]
 
+use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
@@ -8,13 +9,17 @@ use std::time::Instant;
 use clap::Parser;
 use hyper::service::{make_service_fn, service_fn};
 use hyper::{Body, Error, Response, Server};
+use nnrf_discovery_server::cache::DiscoveryCache;
 use nnrf_discovery_server::models::{NfType, ServiceName};
+use nnrf_discovery_server::registry::SearchQuery;
+use nnrf_discovery_server::selection::{NfSelector, SelectionPolicy};
 use nnrf_discovery_server::{
     ApiNoContext as NRFApiNoContext, Client as NRFClient, ContextWrapperExt as NRFContextWrapperExt,
 };
 
 use nsfm_pdusession::{
-    ApiNoContext as SMFApiNoContext, Client as SMFClient, ContextWrapperExt as SMFContextWrapperExt,
+    nas, ApiNoContext as SMFApiNoContext, Client as SMFClient,
+    ContextWrapperExt as SMFContextWrapperExt,
 };
 
 use log::info;
@@ -44,10 +49,7 @@ async fn establish_session(
 
     // println!("Calling SMF");
 
-    let nas_data = [
-        0x2E, 0x01, 0x01, 0xC1, 0xFF, 0xFF, 0x91, 0xA1, 0x28, 0x01, 0x00, 0x7B, 0x00, 0x07, 0x80,
-        0x00, 0x0A, 0x00, 0x00, 0x0D, 0x00,
-    ];
+    let nas_data = nas::Message::ipv4_establishment_request(1, 1).encode();
     let json_request = r#"{
              "supi":	"imsi-001011234567895",
              "pei":	"imeisv-4370816125816151",
@@ -174,6 +176,12 @@ pub struct Opts {
     pub smf: String,
     #[clap(short = 's', long, default_value = "10")]
     pub runs: usize,
+    /// NRF the SMFs are discovered from, instead of using --smf
+    #[clap(long)]
+    pub nrf: Option<String>,
+    /// How the SMF is chosen: weighted, load-aware or round-robin
+    #[clap(long, default_value = "weighted")]
+    pub selection: SelectionPolicy,
 }
 
 // rt may be unused if there are no examples
@@ -195,14 +203,22 @@ async fn main() {
         XSpanIdString::default()
     );
 
-    // SFM client
+    // SFM clients, by SMF URL
 
-    let mut smf_client: Box<dyn SMFApiNoContext<ClientContext>> = {
-        // Using HTTP
-        let client =
-            Box::new(SMFClient::try_new_http(&smf_base_url).expect("Failed to create HTTP client"));
-        Box::new(client.with_context(context.clone()))
-    };
+    let mut smf_clients: HashMap<String, Box<dyn SMFApiNoContext<ClientContext>>> = HashMap::new();
+
+    // SMF discovery and selection
+
+    let discovery = opts.nrf.as_ref().map(|nrf| {
+        DiscoveryCache::new(
+            NRFClient::try_new_http(nrf)
+                .expect("Failed to create HTTP client")
+                .with_context(context.clone()),
+        )
+    });
+    let selector = NfSelector::new(opts.selection);
+    let mut query = SearchQuery::new(NfType::SMF, NfType::AMF);
+    query.service_names = Some(vec![ServiceName::new("nsmf-pdusession".into())]);
 
     let flag = Arc::new(AtomicBool::new(false));
 
@@ -216,7 +232,28 @@ async fn main() {
 
     while i < runs {
         let now = Instant::now();
-        match establish_session(&smf_client, flag.clone()).await {
+
+        let smf_url = match &discovery {
+            Some(discovery) => match discovery.search(&query).await {
+                Ok(result) => selector
+                    .select(&result.nf_instances, "nsmf-pdusession")
+                    .map(|endpoint| endpoint.uri),
+                Err(e) => {
+                    info!("SMF discovery failed: {}", e);
+                    None
+                }
+            },
+            None => None,
+        }
+        .unwrap_or_else(|| smf_base_url.clone());
+        let smf_client = smf_clients.entry(smf_url).or_insert_with_key(|smf_url| {
+            // Using HTTP
+            let client =
+                Box::new(SMFClient::try_new_http(smf_url).expect("Failed to create HTTP client"));
+            Box::new(client.with_context(context.clone()))
+        });
+
+        match establish_session(smf_client, flag.clone()).await {
             Ok(_) => {
                 let delta = now.elapsed();
                 println!("establishment,http,{},ns", delta.as_nanos());
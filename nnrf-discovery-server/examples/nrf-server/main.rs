@@ -3,6 +3,8 @@
 #![allow(missing_docs)]
 
 use clap::Parser;
+use nnrf_discovery_server::registry::NfRegistry;
+use nnrf_discovery_server::scp_domains::ScpDomainTopology;
 use url::Url;
 
 mod server;
@@ -12,6 +14,18 @@ pub struct Opts {
     // public options
     #[clap(short = 'l', long, default_value = "http://127.0.0.1:8080")]
     pub listen: String,
+    /// Static NF profiles, e.g. examples/nrf-server/profiles.json. NFs
+    /// otherwise register themselves through the NFManagement API.
+    #[clap(short = 'p', long)]
+    pub profiles: Option<String>,
+    /// SCP domain topology, e.g. examples/nrf-server/scp-domains.json. The
+    /// file is reloaded, and the subscribers notified, when it changes.
+    #[clap(short = 's', long)]
+    pub scp_domains: Option<String>,
+    /// Registry file, in the format of the static profiles. The NFs it holds
+    /// are registered again at start, and it is rewritten on every change.
+    #[clap(short = 'r', long)]
+    pub registry: Option<String>,
 }
 
 /// Create custom server, wire it to the autogenerated router,
@@ -28,6 +42,16 @@ async fn main() {
         false
     };
 
+    let registry = match opts.profiles {
+        Some(profiles) => NfRegistry::load(&profiles).expect("unable to load NF profiles"),
+        None => NfRegistry::default(),
+    };
+
+    let scp_domains = match &opts.scp_domains {
+        Some(path) => ScpDomainTopology::load(path).expect("unable to load SCP domain topology"),
+        None => ScpDomainTopology::default(),
+    };
+
     server::create(
         &format!(
             "{}:{}",
@@ -35,6 +59,10 @@ async fn main() {
             base_url.port().unwrap()
         ),
         is_https,
+        registry,
+        scp_domains,
+        opts.scp_domains,
+        opts.registry,
     )
     .await;
 }
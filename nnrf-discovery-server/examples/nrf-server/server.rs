@@ -6,35 +6,97 @@ use async_trait::async_trait;
 use futures::{future, Stream, StreamExt, TryFutureExt, TryStreamExt};
 use hyper::server::conn::Http;
 use hyper::service::Service;
-use log::info;
+use log::{info, warn};
 use std::future::Future;
 use std::marker::PhantomData;
 use std::net::SocketAddr;
 use std::sync::{Arc, Mutex};
 use std::task::{Context, Poll};
+use std::time::Duration;
 use swagger::auth::MakeAllowAllAuthenticator;
 use swagger::EmptyContext;
+use swagger::{AuthData, ContextBuilder, Push};
 use swagger::{Has, XSpanIdString};
 use tokio::net::TcpListener;
 
 #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "ios")))]
 use openssl::ssl::{Ssl, SslAcceptor, SslAcceptorBuilder, SslFiletype, SslMethod};
 
-use nnrf_discovery_server::models::{self, NfType, ProblemDetails, SearchResult};
+use nnrf_discovery_server::models::{
+    self, NfType, ProblemDetails, SearchResult, StoredSearchResult,
+};
+use nnrf_discovery_server::registry::{AllowedNfTypes, NfRegistry, SearchQuery};
+use nnrf_discovery_server::scp_domains::ScpDomainTopology;
+use nnrf_discovery_server::search::SearchStore;
+use nnrf_nfmanagement_server::subscriptions::SubscriptionStore;
+
+type ClientContext = swagger::make_context_ty!(
+    ContextBuilder,
+    EmptyContext,
+    Option<AuthData>,
+    XSpanIdString
+);
+
+/// Validity period, in seconds, of the search results sent to the consumers.
+const VALIDITY_PERIOD: i32 = 3600;
+
+/// Heartbeat period assigned to NFs that do not ask for a specific one.
+const HEART_BEAT_TIMER: i32 = 10;
+
+/// Period of the heartbeat supervision of the registered NFs.
+const HEART_BEAT_CHECK_PERIOD: Duration = Duration::from_secs(1);
+
+/// Period of the checks for changes of the SCP domain topology file.
+const SCP_DOMAINS_CHECK_PERIOD: Duration = Duration::from_secs(5);
 
 /// Builds an SSL implementation for Simple HTTPS from some hard-coded file names
-pub async fn create(addr: &str, https: bool) {
+pub async fn create(
+    addr: &str,
+    https: bool,
+    registry: NfRegistry,
+    scp_domains: ScpDomainTopology,
+    scp_domains_path: Option<String>,
+    registry_path: Option<String>,
+) {
+    let api_root = format!("{}://{}", if https { "https" } else { "http" }, addr);
     let addr = addr.parse().expect("Failed to parse bind address");
 
-    let server = Server::new();
+    let subscriptions = SubscriptionStore::new();
+
+    if let Some(path) = registry_path {
+        recover_registry(&registry, &path);
+        tokio::spawn(persist_registry(registry.clone(), path));
+    }
+    tokio::spawn(supervise_heartbeats(registry.clone()));
+    tokio::spawn(notify_subscribers(
+        registry.clone(),
+        subscriptions.clone(),
+        api_root,
+    ));
+    tokio::spawn(notify_scp_domain_subscribers(scp_domains.clone()));
+    if let Some(path) = scp_domains_path {
+        tokio::spawn(reload_scp_domains(scp_domains.clone(), path));
+    }
 
-    let service = MakeService::new(server);
+    let server = Server::new(registry, subscriptions, scp_domains);
 
-    let service = MakeAllowAllAuthenticator::new(service, "cosmo");
+    let discovery = MakeService::new(server.clone());
+    let discovery = MakeAllowAllAuthenticator::new(discovery, "cosmo");
+    let discovery =
+        nnrf_discovery_server::server::context::MakeAddContext::<_, EmptyContext>::new(discovery);
+
+    let management = nnrf_nfmanagement_server::server::MakeService::new(server);
+    let management = MakeAllowAllAuthenticator::new(management, "cosmo");
+    let management =
+        nnrf_nfmanagement_server::server::context::MakeAddContext::<_, EmptyContext>::new(
+            management,
+        );
 
     #[allow(unused_mut)]
-    let mut service =
-        nnrf_discovery_server::server::context::MakeAddContext::<_, EmptyContext>::new(service);
+    let mut service = MakeRouter {
+        discovery,
+        management,
+    };
 
     if https {
         #[cfg(any(target_os = "macos", target_os = "windows", target_os = "ios"))]
@@ -85,24 +147,309 @@ pub async fn create(addr: &str, https: bool) {
     }
 }
 
-#[derive(Copy, Clone)]
+#[derive(Clone)]
 pub struct Server<C> {
     marker: PhantomData<C>,
+    registry: NfRegistry,
+    subscriptions: SubscriptionStore,
+    searches: SearchStore,
+    scp_domains: ScpDomainTopology,
 }
 
 impl<C> Server<C> {
-    pub fn new() -> Self {
+    pub fn new(
+        registry: NfRegistry,
+        subscriptions: SubscriptionStore,
+        scp_domains: ScpDomainTopology,
+    ) -> Self {
         Server {
             marker: PhantomData,
+            registry,
+            subscriptions,
+            searches: SearchStore::new(),
+            scp_domains,
+        }
+    }
+}
+
+type ServiceError = Box<dyn std::error::Error + Send + Sync + 'static>;
+
+/// Makes a [`Router`] for every connection out of the discovery and
+/// management services, so that both APIs are served on the same port.
+pub struct MakeRouter<D, M> {
+    discovery: D,
+    management: M,
+}
+
+impl<Target, D, M> Service<Target> for MakeRouter<D, M>
+where
+    Target: Clone + Send,
+    D: Service<Target, Error = ServiceError>,
+    D::Response: Send,
+    D::Future: Send + 'static,
+    M: Service<Target, Error = ServiceError>,
+    M::Future: Send + 'static,
+{
+    type Response = Router<D::Response, M::Response>;
+    type Error = ServiceError;
+    type Future = future::BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        match self.discovery.poll_ready(cx) {
+            Poll::Ready(Ok(())) => self.management.poll_ready(cx),
+            other => other,
+        }
+    }
+
+    fn call(&mut self, target: Target) -> Self::Future {
+        let discovery = self.discovery.call(target.clone());
+        let management = self.management.call(target);
+
+        Box::pin(async move {
+            Ok(Router {
+                discovery: discovery.await?,
+                management: management.await?,
+            })
+        })
+    }
+}
+
+/// Dispatches the requests on the API base path.
+pub struct Router<D, M> {
+    discovery: D,
+    management: M,
+}
+
+impl<D, M> Service<hyper::Request<hyper::Body>> for Router<D, M>
+where
+    D: Service<
+        hyper::Request<hyper::Body>,
+        Response = hyper::Response<hyper::Body>,
+        Error = ServiceError,
+    >,
+    D::Future: Send + 'static,
+    M: Service<
+        hyper::Request<hyper::Body>,
+        Response = hyper::Response<hyper::Body>,
+        Error = ServiceError,
+    >,
+    M::Future: Send + 'static,
+{
+    type Response = hyper::Response<hyper::Body>;
+    type Error = ServiceError;
+    type Future = future::BoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        match self.discovery.poll_ready(cx) {
+            Poll::Ready(Ok(())) => self.management.poll_ready(cx),
+            other => other,
+        }
+    }
+
+    fn call(&mut self, request: hyper::Request<hyper::Body>) -> Self::Future {
+        if request
+            .uri()
+            .path()
+            .starts_with(nnrf_nfmanagement_server::BASE_PATH)
+        {
+            Box::pin(self.management.call(request))
+        } else {
+            Box::pin(self.discovery.call(request))
+        }
+    }
+}
+
+/// Suspends, then removes, the NFs that stopped sending heartbeats.
+async fn supervise_heartbeats(registry: NfRegistry) {
+    let mut interval = tokio::time::interval(HEART_BEAT_CHECK_PERIOD);
+    loop {
+        interval.tick().await;
+
+        let expired = registry.expire();
+        for profile in expired.suspended {
+            warn!(
+                "{:?} {} missed its heartbeat, suspended",
+                profile.nf_type, profile.nf_instance_id
+            );
+        }
+        for profile in expired.removed {
+            warn!(
+                "{:?} {} stopped sending heartbeats, deregistered",
+                profile.nf_type, profile.nf_instance_id
+            );
+        }
+    }
+}
+
+/// Sends the NF status notifications owed to the subscribers for every change
+/// of the registry, in the order of the changes.
+async fn notify_subscribers(
+    registry: NfRegistry,
+    subscriptions: SubscriptionStore,
+    api_root: String,
+) {
+    let client = nnrf_nfmanagement_server::server::callbacks::Client::new_http();
+    let context: ClientContext = swagger::make_context!(
+        ContextBuilder,
+        EmptyContext,
+        None as Option<AuthData>,
+        XSpanIdString::default()
+    );
+
+    let mut events = registry.watch();
+    while let Some(event) = events.next().await {
+        let notifications = subscriptions
+            .notifications(&event, &api_root)
+            .into_iter()
+            .map(|(uri, notification)| {
+                info!(
+                    "Notifying {} of {} for {}",
+                    uri,
+                    notification.event,
+                    event.profile().nf_instance_id
+                );
+                let client = client.clone();
+                let context = context.clone();
+                async move {
+                    match client
+                        .on_nf_status_event_post(uri.clone(), notification, &context)
+                        .await
+                    {
+                        Ok(OnNfStatusEventPostResponse::ExpectedResponseToASuccessfulCallbackProcessing) => {}
+                        Ok(rsp) => warn!("Notification to {} rejected: {:?}", uri, rsp),
+                        Err(e) => warn!("Notification to {} failed: {}", uri, e),
+                    }
+                }
+            });
+        future::join_all(notifications).await;
+    }
+}
+
+/// Registers again the NFs of a saved registry, as if they had just
+/// registered: those that do not send their heartbeats any more are dropped
+/// after the heartbeat timer. The profiles already in the registry are kept.
+fn recover_registry(registry: &NfRegistry, path: &str) {
+    let start = std::time::Instant::now();
+    let profiles = match NfRegistry::read(path) {
+        Ok(profiles) => profiles,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return,
+        Err(e) => {
+            warn!("Unable to recover the registry from {}: {}", path, e);
+            return;
+        }
+    };
+
+    let mut recovered = 0;
+    for profile in profiles {
+        if registry.get(&profile.nf_instance_id).is_none() {
+            registry.register(profile, Some(Duration::from_secs(HEART_BEAT_TIMER as u64)));
+            recovered += 1;
         }
     }
+    info!(
+        "Recovered {} NF profiles from {} in {:?}",
+        recovered,
+        path,
+        start.elapsed()
+    );
+}
+
+/// Saves the registry to its file after every change.
+async fn persist_registry(registry: NfRegistry, path: String) {
+    let mut events = registry.watch();
+    if let Err(e) = registry.save(&path) {
+        warn!("Unable to save the registry to {}: {}", path, e);
+    }
+    while events.next().await.is_some() {
+        if let Err(e) = registry.save(&path) {
+            warn!("Unable to save the registry to {}: {}", path, e);
+        }
+    }
+}
+
+/// Reloads the SCP domain topology whenever its file is modified.
+async fn reload_scp_domains(scp_domains: ScpDomainTopology, path: String) {
+    let modified = |path: &str| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+
+    let mut last_modified = modified(&path);
+    let mut interval = tokio::time::interval(SCP_DOMAINS_CHECK_PERIOD);
+    loop {
+        interval.tick().await;
+
+        let current = modified(&path);
+        if current == last_modified {
+            continue;
+        }
+        last_modified = current;
+
+        match ScpDomainTopology::read(&path) {
+            Ok(routing_info) => {
+                if scp_domains.set(routing_info) {
+                    info!("SCP domain topology reloaded from {}", path);
+                }
+            }
+            Err(e) => warn!(
+                "Unable to reload the SCP domain topology from {}: {}",
+                path, e
+            ),
+        }
+    }
+}
+
+/// Sends the SCP domain routing information change notifications to the
+/// subscribers for every change of the topology.
+async fn notify_scp_domain_subscribers(scp_domains: ScpDomainTopology) {
+    let client = nnrf_discovery_server::server::callbacks::Client::new_http();
+    let context: ClientContext = swagger::make_context!(
+        ContextBuilder,
+        EmptyContext,
+        None as Option<AuthData>,
+        XSpanIdString::default()
+    );
+
+    let mut changes = scp_domains.watch();
+    while let Some(routing_info) = changes.next().await {
+        let notifications = scp_domains
+            .notifications(&routing_info)
+            .into_iter()
+            .map(|(uri, notification)| {
+                info!("Notifying {} of the SCP domain routing information", uri);
+                let client = client.clone();
+                let context = context.clone();
+                async move {
+                    match client
+                        .on_scp_domain_routing_information_change_post(
+                            uri.clone(),
+                            None,
+                            Some(notification),
+                            &context,
+                        )
+                        .await
+                    {
+                        Ok(OnScpDomainRoutingInformationChangePostResponse::ExpectedResponseToASuccessfulCallbackProcessing { .. }) => {}
+                        Ok(rsp) => warn!("Notification to {} rejected: {:?}", uri, rsp),
+                        Err(e) => warn!("Notification to {} failed: {}", uri, e),
+                    }
+                }
+            });
+        future::join_all(notifications).await;
+    }
+}
+
+fn problem(status: u16, cause: &str, detail: String) -> ProblemDetails {
+    let mut problem = ProblemDetails::new();
+    problem.status = Some(status.into());
+    problem.cause = Some(cause.to_string());
+    problem.detail = Some(detail);
+    problem
 }
 
 use nnrf_discovery_server::server::MakeService;
 use nnrf_discovery_server::{
-    Api, RetrieveCompleteSearchResponse, RetrieveStoredSearchResponse,
-    SCpDomainRoutingInfoGetResponse, ScpDomainRoutingInfoSubscribeResponse,
-    ScpDomainRoutingInfoUnsubscribeResponse, SearchNfInstancesResponse,
+    Api, CallbackApi as _, OnScpDomainRoutingInformationChangePostResponse,
+    RetrieveCompleteSearchResponse, RetrieveStoredSearchResponse, SCpDomainRoutingInfoGetResponse,
+    ScpDomainRoutingInfoSubscribeResponse, ScpDomainRoutingInfoUnsubscribeResponse,
+    SearchNfInstancesResponse,
 };
 use std::error::Error;
 use swagger::ApiError;
@@ -125,7 +472,21 @@ where
             accept_encoding,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+        match self.searches.complete(&search_id) {
+            Some((nf_instances, remaining)) => Ok(
+                RetrieveCompleteSearchResponse::ExpectedResponseToAValidRequest {
+                    body: StoredSearchResult::new(nf_instances),
+                    cache_control: Some(format!("max-age={}", remaining.as_secs())),
+                    e_tag: None,
+                    content_encoding: None,
+                },
+            ),
+            None => Ok(RetrieveCompleteSearchResponse::NotFound(problem(
+                404,
+                "RESOURCE_NOT_FOUND",
+                format!("Unknown or expired search {}", search_id),
+            ))),
+        }
     }
 
     /// Deletes a subscription
@@ -140,7 +501,17 @@ where
             subscription_id,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+
+        match self.scp_domains.unsubscribe(&subscription_id) {
+            Some(_) => Ok(
+                ScpDomainRoutingInfoUnsubscribeResponse::ExpectedResponseToASuccessfulSubscriptionRemoval,
+            ),
+            None => Ok(ScpDomainRoutingInfoUnsubscribeResponse::NotFound(problem(
+                404,
+                "SUBSCRIPTION_NOT_FOUND",
+                format!("Unknown subscription {}", subscription_id),
+            ))),
+        }
     }
 
     /// Search a collection of NF Instances
@@ -287,155 +658,56 @@ where
     ) -> Result<SearchNfInstancesResponse, ApiError> {
         let context = context.clone();
         info!("search_nf_instances({:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}) - X-Span-ID: {:?}", target_nf_type, requester_nf_type, accept_encoding, preferred_collocated_nf_types, requester_nf_instance_id, service_names, requester_nf_instance_fqdn, target_plmn_list, requester_plmn_list, target_nf_instance_id, target_nf_fqdn, hnrf_uri, snssais, requester_snssais, plmn_specific_snssai_list, requester_plmn_specific_snssai_list, dnn, ipv4_index, ipv6_index, nsi_list, smf_serving_area, mbsmf_serving_area, tai, amf_region_id, amf_set_id, guami, supi, ue_ipv4_address, ip_domain, ue_ipv6_prefix, pgw_ind, preferred_pgw_ind, pgw, pgw_ip, gpsi, external_group_identity, internal_group_identity, pfd_data, data_set, routing_indicator, group_id_list, dnai_list, pdu_session_types, event_id_list, nwdaf_event_list, supported_features, upf_iwk_eps_ind, chf_supported_plmn, preferred_locality, access_type, limit, required_features, complex_query, max_payload_size, max_payload_size_ext, atsss_capability, upf_ue_ip_addr_ind, client_type, lmf_id, an_node_type, rat_type, preferred_tai, preferred_nf_instances, if_none_match, target_snpn, requester_snpn_list, af_ee_data, w_agf_info, tngf_info, twif_info, target_nf_set_id, target_nf_service_set_id, nef_id, notification_type, n1_msg_class, n2_info_class, serving_scope, imsi, ims_private_identity, ims_public_identity, msisdn, preferred_api_versions, v2x_support_ind, redundant_gtpu, redundant_transport, ipups, scp_domain_list, address_domain, ipv4_addr, ipv6_prefix, served_nf_set_id, remote_plmn_id, remote_snpn_id, data_forwarding, preferred_full_plmn, requester_features, realm_id, storage_id, vsmf_support_ind, ismf_support_ind, nrf_disc_uri, preferred_vendor_specific_features, preferred_vendor_specific_nf_features, required_pfcp_features, home_pub_key_id, prose_support_ind, analytics_aggregation_ind, serving_nf_set_id, serving_nf_type, ml_analytics_info_list, analytics_metadata_prov_ind, nsacf_capability, mbs_session_id_list, area_session_id, gmlc_number, upf_n6_ip, tai_list, preferences_precedence, support_onboarding_capability, uas_nf_functionality_ind, v2x_capability, prose_capability, shared_data_id, target_hni, target_nw_resolution, exclude_nfinst_list, exclude_nfservinst_list, exclude_nfserviceset_list, exclude_nfset_list, preferred_analytics_delays, context.get().0.clone());
-        // Err(ApiError("Generic failure".into()))
-
-        // Here we should lookup for the service that was requested
-
-        match requester_nf_type {
-            NfType::SMF => match target_nf_type {
-                NfType::UDM => {
-                    let body = serde_json::from_str::<SearchResult>(r#"{"validityPeriod":3600,"nfInstances":[{"nfInstanceId":"65a52dac-b832-41ed-ba6e-53c1c0b3ed51","nfType":"UDM","nfStatus":"REGISTERED","heartBeatTimer":10,"ipv4Addresses":["172.22.0.13"],"allowedNfTypes":["AMF","SMF","AUSF","SCP"],"priority":0,"capacity":100,"load":0,"nfServiceList":{"65a54148-b832-41ed-ba6e-53c1c0b3ed51":{"serviceInstanceId":"65a54148-b832-41ed-ba6e-53c1c0b3ed51","serviceName":"nudm-sdm","versions":[{"apiVersionInUri":"v2","apiFullVersion":"2.0.0"}],"scheme":"http","nfServiceStatus":"REGISTERED","ipEndPoints":[{"ipv4Address":"172.22.0.13","port":7777}],"allowedNfTypes":["AMF","SMF"],"priority":0,"capacity":100,"load":0}},"nfProfileChangesSupportInd":true}]}"#).expect("unable to convert from json");
-                    let resp = SearchNfInstancesResponse::ExpectedResponseToAValidRequest {
-                        body,
-                        cache_control: None,
-                        e_tag: None,
-                        content_encoding: Some("application/json".to_string()),
-                    };
-                    Ok(resp)
-                }
-                _ => Ok(SearchNfInstancesResponse::NotImplemented(ProblemDetails {
-                    status: Some(501),
-                    title: Some("Not implemented".to_string()),
-                    r#type: None,
-                    detail: Some("Just mock up!".to_string()),
-                    instance: None,
-                    cause: Some("Not implemented".to_string()),
-                    invalid_params: None,
-                    supported_features: None,
-                    access_token_error: None,
-                    access_token_request: None,
-                    nrf_id: None,
-                })),
-            },
-            NfType::AMF => match target_nf_type {
-                NfType::SMF => {
-                    let body = serde_json::from_str::<SearchResult>(r#"{"validityPeriod":3600,"nfInstances":[{"nfInstanceId":"b3a71a80-b8d5-41ed-b2cc-8bbc6f173b7d","nfType":"SMF","nfStatus":"REGISTERED","heartBeatTimer":10,"ipv4Addresses":["172.22.0.7"],"allowedNfTypes":["AMF","SCP"],"priority":0,"capacity":100,"load":0,"nfServiceList":{"b3c40334-b8d5-41ed-b2cc-8bbc6f173b7d":{"serviceInstanceId":"b3c40334-b8d5-41ed-b2cc-8bbc6f173b7d","serviceName":"nsmf-pdusession","versions":[{"apiVersionInUri":"v1","apiFullVersion":"1.0.0"}],"scheme":"http","nfServiceStatus":"REGISTERED","ipEndPoints":[{"ipv4Address":"172.22.0.7","port":7777}],"allowedNfTypes":["AMF"],"priority":0,"capacity":100,"load":0}},"nfProfileChangesSupportInd":true}]}"#).expect("unable to convert from json");
-
-                    let resp = SearchNfInstancesResponse::ExpectedResponseToAValidRequest {
-                        body,
-                        cache_control: None,
-                        e_tag: None,
-                        content_encoding: Some("application/json".to_string()),
-                    };
-                    Ok(resp)
-                }
-                _ => Ok(SearchNfInstancesResponse::NotImplemented(ProblemDetails {
-                    status: Some(501),
-                    title: Some("Not implemented".to_string()),
-                    r#type: None,
-                    detail: Some("Just mock up!".to_string()),
-                    instance: None,
-                    cause: Some("Not implemented".to_string()),
-                    invalid_params: None,
-                    supported_features: None,
-                    access_token_error: None,
-                    access_token_request: None,
-                    nrf_id: None,
-                })),
-            },
-            _ => Ok(SearchNfInstancesResponse::NotImplemented(ProblemDetails {
-                status: Some(501),
-                title: Some("Not implemented".to_string()),
-                r#type: None,
-                detail: Some("Just mock up!".to_string()),
-                instance: None,
-                cause: Some("Not implemented".to_string()),
-                invalid_params: None,
-                supported_features: None,
-                access_token_error: None,
-                access_token_request: None,
-                nrf_id: None,
-            })),
+        // Here we receive /nnrf-disc/v1/nf-instances?requester-features=20&requester-nf-type=SMF&service-names=nudm-sdm&target-nf-type=UDM
+        // and we answer with the registered profiles matching the query.
+        let query = SearchQuery {
+            target_nf_type,
+            requester_nf_type,
+            service_names: service_names.cloned(),
+            snssais: snssais.cloned(),
+            dnn,
+            target_plmn_list: target_plmn_list.cloned(),
+            tai,
+            limit,
+        };
+
+        // The complete result is kept for the complete search retrieval, the
+        // limit only applies to what is returned now.
+        let complete = self.registry.search(&SearchQuery {
+            limit: None,
+            ..query
+        });
+        let returned = match limit {
+            Some(limit) if limit > 0 => complete.len().min(limit as usize),
+            _ => complete.len(),
+        };
+
+        let expired = self.searches.expire();
+        if expired > 0 {
+            info!("Dropped {} expired stored searches", expired);
         }
 
-        // Here we receive /nnrf-disc/v1/nf-instances?requester-features=20&requester-nf-type=SMF&service-names=nudm-sdm&target-nf-type=UDM
-        // if the requester is a SMF asking for UDM we send
-        // {
-        //     "validityPeriod":	3600,
-        //     "nfInstances":	[{
-        //             "nfInstanceId":	"65a52dac-b832-41ed-ba6e-53c1c0b3ed51",
-        //             "nfType":	"UDM",
-        //             "nfStatus":	"REGISTERED",
-        //             "heartBeatTimer":	10,
-        //             "ipv4Addresses":	["172.22.0.13"],
-        //             "allowedNfTypes":	["AMF", "SMF", "AUSF", "SCP"],
-        //             "priority":	0,
-        //             "capacity":	100,
-        //             "load":	0,
-        //             "nfServiceList":	{
-        //                 "65a54148-b832-41ed-ba6e-53c1c0b3ed51":	{
-        //                     "serviceInstanceId":	"65a54148-b832-41ed-ba6e-53c1c0b3ed51",
-        //                     "serviceName":	"nudm-sdm",
-        //                     "versions":	[{
-        //                             "apiVersionInUri":	"v2",
-        //                             "apiFullVersion":	"2.0.0"
-        //                         }],
-        //                     "scheme":	"http",
-        //                     "nfServiceStatus":	"REGISTERED",
-        //                     "ipEndPoints":	[{
-        //                             "ipv4Address":	"172.22.0.13",
-        //                             "port":	7777
-        //                         }],
-        //                     "allowedNfTypes":	["AMF", "SMF"],
-        //                     "priority":	0,
-        //                     "capacity":	100,
-        //                     "load":	0
-        //                 }
-        //             },
-        //             "nfProfileChangesSupportInd":	true
-        //         }]
-        // }
-        //
-        // SMF looking for PCF
-        // ....
-        // AMF looking for SMF
-        //
-        // Request on: nnrf-disc/v1/nf-instances?requester-nf-type=AMF&service-names=nsmf-pdusession&target-nf-type=SMF&requester-features=20
-        // {
-        // 	"validityPeriod":	3600,
-        // 	"nfInstances":	[{
-        // 			"nfInstanceId":	"b3a71a80-b8d5-41ed-b2cc-8bbc6f173b7d",
-        // 			"nfType":	"SMF",
-        // 			"nfStatus":	"REGISTERED",
-        // 			"heartBeatTimer":	10,
-        // 			"ipv4Addresses":	["172.22.0.7"],
-        // 			"allowedNfTypes":	["AMF", "SCP"],
-        // 			"priority":	0,
-        // 			"capacity":	100,
-        // 			"load":	0,
-        // 			"nfServiceList":	{
-        // 				"b3c40334-b8d5-41ed-b2cc-8bbc6f173b7d":	{
-        // 					"serviceInstanceId":	"b3c40334-b8d5-41ed-b2cc-8bbc6f173b7d",
-        // 					"serviceName":	"nsmf-pdusession",
-        // 					"versions":	[{
-        // 							"apiVersionInUri":	"v1",
-        // 							"apiFullVersion":	"1.0.0"
-        // 						}],
-        // 					"scheme":	"http",
-        // 					"nfServiceStatus":	"REGISTERED",
-        // 					"ipEndPoints":	[{
-        // 							"ipv4Address":	"172.22.0.7",
-        // 							"port":	7777
-        // 						}],
-        // 					"allowedNfTypes":	["AMF"],
-        // 					"priority":	0,
-        // 					"capacity":	100,
-        // 					"load":	0
-        // 				}
-        // 			},
-        // 			"nfProfileChangesSupportInd":	true
-        // 		}]
-        // }
+        let mut body = SearchResult::new(complete[..returned].to_vec());
+        body.validity_period = Some(VALIDITY_PERIOD);
+        body.num_nf_inst_complete = Some(complete.len() as u32);
+        body.nf_instance_list = Some(
+            complete
+                .iter()
+                .map(|p| (p.nf_instance_id.to_string(), models::NfInstanceInfo::new()))
+                .collect(),
+        );
+        body.search_id = Some(self.searches.store(
+            complete,
+            returned,
+            Duration::from_secs(VALIDITY_PERIOD as u64),
+        ));
+
+        Ok(SearchNfInstancesResponse::ExpectedResponseToAValidRequest {
+            body,
+            cache_control: None,
+            e_tag: None,
+            content_encoding: Some("application/json".to_string()),
+        })
     }
 
     async fn scp_domain_routing_info_get(
@@ -451,7 +723,15 @@ where
             accept_encoding,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+
+        // The whole topology is configured on this NRF, so it is also the
+        // local one.
+        Ok(
+            SCpDomainRoutingInfoGetResponse::ExpectedResponseToAValidRequest {
+                body: self.scp_domains.routing_info(),
+                content_encoding: None,
+            },
+        )
     }
 
     /// Create a new subscription
@@ -470,7 +750,42 @@ where
             accept_encoding,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+
+        if let Err(e) = scp_domain_routing_info_subscription
+            .callback_uri
+            .parse::<hyper::Uri>()
+        {
+            return Ok(ScpDomainRoutingInfoSubscribeResponse::BadRequest(problem(
+                400,
+                "MANDATORY_IE_INCORRECT",
+                format!("Invalid callbackUri: {}", e),
+            )));
+        }
+
+        for expired in self.scp_domains.expire() {
+            info!(
+                "SCP domain routing information subscription of {} expired",
+                expired.callback_uri
+            );
+        }
+
+        let subscription_id = self
+            .scp_domains
+            .subscribe(scp_domain_routing_info_subscription.clone());
+        let location = format!(
+            "{}/scp-domain-routing-info-subs/{}",
+            nnrf_discovery_server::BASE_PATH,
+            subscription_id
+        );
+
+        Ok(
+            ScpDomainRoutingInfoSubscribeResponse::ExpectedResponseToAValidRequest {
+                body: scp_domain_routing_info_subscription,
+                location,
+                accept_encoding: None,
+                content_encoding: None,
+            },
+        )
     }
 
     async fn retrieve_stored_search(
@@ -486,6 +801,326 @@ where
             accept_encoding,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+        match self.searches.stored(&search_id) {
+            Some((nf_instances, remaining)) => Ok(
+                RetrieveStoredSearchResponse::ExpectedResponseToAValidRequest {
+                    body: StoredSearchResult::new(nf_instances),
+                    cache_control: Some(format!("max-age={}", remaining.as_secs())),
+                    e_tag: None,
+                    content_encoding: None,
+                },
+            ),
+            None => Ok(RetrieveStoredSearchResponse::NotFound(problem(
+                404,
+                "RESOURCE_NOT_FOUND",
+                format!("Unknown or expired search {}", search_id),
+            ))),
+        }
+    }
+}
+
+use nnrf_nfmanagement_server::{
+    Api as NfManagementApi, CallbackApi, CreateSubscriptionResponse, DeregisterNfInstanceResponse,
+    GetNfInstanceResponse, OnNfStatusEventPostResponse, RegisterNfInstanceResponse,
+    RemoveSubscriptionResponse, UpdateNfInstanceResponse,
+};
+
+impl<C> Server<C> {
+    /// The registered profile with its NFManagement only attributes.
+    fn management_profile(
+        &self,
+        profile: models::NfProfile,
+    ) -> nnrf_nfmanagement_server::models::NfProfile {
+        let heart_beat_timer = self
+            .registry
+            .heart_beat_timer(&profile.nf_instance_id)
+            .map(|t| t.as_secs() as i32);
+        let allowed_nf_types = self
+            .registry
+            .allowed_nf_types(&profile.nf_instance_id)
+            .and_then(|allowed| allowed.profile);
+        let mut profile = nnrf_nfmanagement_server::models::NfProfile::new(profile);
+        profile.heart_beat_timer = heart_beat_timer;
+        profile.allowed_nf_types = allowed_nf_types;
+        profile
+    }
+}
+
+#[async_trait]
+impl<C> NfManagementApi<C> for Server<C>
+where
+    C: Has<XSpanIdString> + Send + Sync,
+{
+    /// Read the profile of a given NF Instance
+    async fn get_nf_instance(
+        &self,
+        nf_instance_id: uuid::Uuid,
+        requester_features: Option<String>,
+        accept_encoding: Option<String>,
+        context: &C,
+    ) -> Result<GetNfInstanceResponse, ApiError> {
+        let context = context.clone();
+        info!(
+            "get_nf_instance({:?}, {:?}, {:?}) - X-Span-ID: {:?}",
+            nf_instance_id,
+            requester_features,
+            accept_encoding,
+            context.get().0.clone()
+        );
+
+        match self.registry.get(&nf_instance_id) {
+            Some(profile) => Ok(GetNfInstanceResponse::ExpectedResponseToAValidRequest {
+                body: self.management_profile(profile),
+                e_tag: None,
+                content_encoding: None,
+            }),
+            None => Ok(GetNfInstanceResponse::NotFound(problem(
+                404,
+                "RESOURCE_NOT_FOUND",
+                format!("NF instance {} is not registered", nf_instance_id),
+            ))),
+        }
+    }
+
+    /// Register a new NF Instance
+    async fn register_nf_instance(
+        &self,
+        nf_instance_id: uuid::Uuid,
+        nf_profile: nnrf_nfmanagement_server::models::NfProfile,
+        content_encoding: Option<String>,
+        accept_encoding: Option<String>,
+        context: &C,
+    ) -> Result<RegisterNfInstanceResponse, ApiError> {
+        let context = context.clone();
+        info!(
+            "register_nf_instance({:?}, {:?}, {:?}, {:?}) - X-Span-ID: {:?}",
+            nf_instance_id,
+            nf_profile,
+            content_encoding,
+            accept_encoding,
+            context.get().0.clone()
+        );
+
+        if nf_profile.profile.nf_instance_id != nf_instance_id {
+            return Ok(RegisterNfInstanceResponse::BadRequest(problem(
+                400,
+                "MANDATORY_IE_INCORRECT",
+                "nfInstanceId does not match the resource URI".to_string(),
+            )));
+        }
+
+        let heart_beat_timer = nf_profile
+            .heart_beat_timer
+            .filter(|t| *t > 0)
+            .unwrap_or(HEART_BEAT_TIMER);
+        let replaced = self.registry.register_with(
+            nf_profile.profile,
+            Some(Duration::from_secs(heart_beat_timer as u64)),
+            AllowedNfTypes {
+                profile: nf_profile.allowed_nf_types,
+                services: Default::default(),
+            },
+        );
+        let body = self.management_profile(
+            self.registry
+                .get(&nf_instance_id)
+                .expect("profile was just registered"),
+        );
+
+        match replaced {
+            Some(_) => Ok(RegisterNfInstanceResponse::OK {
+                body,
+                accept_encoding: None,
+                e_tag: None,
+                content_encoding: None,
+            }),
+            None => Ok(RegisterNfInstanceResponse::Created {
+                body,
+                location: format!(
+                    "{}/nf-instances/{}",
+                    nnrf_nfmanagement_server::BASE_PATH,
+                    nf_instance_id
+                ),
+                accept_encoding: None,
+                e_tag: None,
+                content_encoding: None,
+            }),
+        }
+    }
+
+    /// Update NF Instance profile
+    async fn update_nf_instance(
+        &self,
+        nf_instance_id: uuid::Uuid,
+        patch_item: &Vec<nnrf_nfmanagement_server::models::PatchItem>,
+        content_encoding: Option<String>,
+        accept_encoding: Option<String>,
+        if_match: Option<String>,
+        context: &C,
+    ) -> Result<UpdateNfInstanceResponse, ApiError> {
+        let context = context.clone();
+        info!(
+            "update_nf_instance({:?}, {:?}, {:?}, {:?}, {:?}) - X-Span-ID: {:?}",
+            nf_instance_id,
+            patch_item,
+            content_encoding,
+            accept_encoding,
+            if_match,
+            context.get().0.clone()
+        );
+
+        let current = match self.registry.get(&nf_instance_id) {
+            Some(profile) => profile,
+            None => {
+                return Ok(UpdateNfInstanceResponse::NotFound(problem(
+                    404,
+                    "RESOURCE_NOT_FOUND",
+                    format!("NF instance {} is not registered", nf_instance_id),
+                )))
+            }
+        };
+
+        let mut value = serde_json::to_value(&current)
+            .map_err(|e| ApiError(format!("Unable to serialize profile: {}", e)))?;
+        if let Err(e) = nnrf_nfmanagement_server::models::apply_patch(&mut value, patch_item) {
+            return Ok(UpdateNfInstanceResponse::BadRequest(problem(
+                400,
+                "INVALID_MSG_FORMAT",
+                e,
+            )));
+        }
+        let profile: models::NfProfile = match serde_json::from_value(value) {
+            Ok(profile) => profile,
+            Err(e) => {
+                return Ok(UpdateNfInstanceResponse::BadRequest(problem(
+                    400,
+                    "INVALID_MSG_FORMAT",
+                    e.to_string(),
+                )))
+            }
+        };
+        if profile.nf_instance_id != nf_instance_id {
+            return Ok(UpdateNfInstanceResponse::BadRequest(problem(
+                400,
+                "MANDATORY_IE_INCORRECT",
+                "nfInstanceId cannot be modified".to_string(),
+            )));
+        }
+
+        // Any update refreshes the heartbeat supervision
+        self.registry.update(profile.clone());
+
+        if nnrf_nfmanagement_server::models::PatchItem::is_heartbeat(patch_item) {
+            Ok(UpdateNfInstanceResponse::NoContent)
+        } else {
+            Ok(UpdateNfInstanceResponse::ExpectedResponseToAValidRequest {
+                body: self.management_profile(profile),
+                accept_encoding: None,
+                e_tag: None,
+                content_encoding: None,
+            })
+        }
+    }
+
+    /// Deregisters a given NF Instance
+    async fn deregister_nf_instance(
+        &self,
+        nf_instance_id: uuid::Uuid,
+        context: &C,
+    ) -> Result<DeregisterNfInstanceResponse, ApiError> {
+        let context = context.clone();
+        info!(
+            "deregister_nf_instance({:?}) - X-Span-ID: {:?}",
+            nf_instance_id,
+            context.get().0.clone()
+        );
+
+        match self.registry.remove(&nf_instance_id) {
+            Some(_) => {
+                Ok(DeregisterNfInstanceResponse::ExpectedResponseToASuccessfulDeregistration)
+            }
+            None => Ok(DeregisterNfInstanceResponse::NotFound(problem(
+                404,
+                "RESOURCE_NOT_FOUND",
+                format!("NF instance {} is not registered", nf_instance_id),
+            ))),
+        }
+    }
+
+    /// Create a new subscription
+    async fn create_subscription(
+        &self,
+        subscription_data: nnrf_nfmanagement_server::models::SubscriptionData,
+        content_encoding: Option<String>,
+        accept_encoding: Option<String>,
+        context: &C,
+    ) -> Result<CreateSubscriptionResponse, ApiError> {
+        let context = context.clone();
+        info!(
+            "create_subscription({:?}, {:?}, {:?}) - X-Span-ID: {:?}",
+            subscription_data,
+            content_encoding,
+            accept_encoding,
+            context.get().0.clone()
+        );
+
+        if let Err(e) = subscription_data
+            .nf_status_notification_uri
+            .parse::<hyper::Uri>()
+        {
+            return Ok(CreateSubscriptionResponse::BadRequest(problem(
+                400,
+                "MANDATORY_IE_INCORRECT",
+                format!("Invalid nfStatusNotificationUri: {}", e),
+            )));
+        }
+
+        for expired in self.subscriptions.expire() {
+            info!(
+                "Subscription {:?} of {} expired",
+                expired.subscription_id, expired.nf_status_notification_uri
+            );
+        }
+
+        let body = self.subscriptions.create(subscription_data);
+        let location = format!(
+            "{}/subscriptions/{}",
+            nnrf_nfmanagement_server::BASE_PATH,
+            body.subscription_id.as_deref().unwrap_or_default()
+        );
+
+        Ok(
+            CreateSubscriptionResponse::ExpectedResponseToAValidRequest {
+                body,
+                location,
+                accept_encoding: None,
+                content_encoding: None,
+            },
+        )
+    }
+
+    /// Deletes a subscription
+    async fn remove_subscription(
+        &self,
+        subscription_id: String,
+        context: &C,
+    ) -> Result<RemoveSubscriptionResponse, ApiError> {
+        let context = context.clone();
+        info!(
+            "remove_subscription({:?}) - X-Span-ID: {:?}",
+            subscription_id,
+            context.get().0.clone()
+        );
+
+        match self.subscriptions.remove(&subscription_id) {
+            Some(_) => {
+                Ok(RemoveSubscriptionResponse::ExpectedResponseToASuccessfulSubscriptionRemoval)
+            }
+            None => Ok(RemoveSubscriptionResponse::NotFound(problem(
+                404,
+                "SUBSCRIPTION_NOT_FOUND",
+                format!("Subscription {} does not exist", subscription_id),
+            ))),
+        }
     }
 }
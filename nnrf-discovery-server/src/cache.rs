@@ -0,0 +1,511 @@
+//! Consumer side cache of the NF discovery results.
+//!
+//! [`DiscoveryCache`] answers a discovery query from a previous result of the
+//! same query until the `validityPeriod` of that result has passed. The NRF is
+//! reached through a [`Discover`] implementation, so the same cache is used
+//! over HTTP (any [`ApiNoContext`] client) and over the gRPC and zenoh
+//! transports, which implement [`Discover`] in their own crates.
+//!
+//! Results without a validity period are not cached. The NF status
+//! notifications received by the consumer are applied with
+//! [`DiscoveryCache::invalidate`]. The cache holds at most a given number of
+//! results: the expired ones are dropped first, then those closest to
+//! expiring.
+
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use swagger::{ApiError, ContextWrapper};
+
+use crate::models::{NfProfile, SearchResult};
+use crate::registry::SearchQuery;
+use crate::{Api, ApiNoContext, SearchNfInstancesResponse};
+
+/// A way of sending a discovery query to the NRF.
+#[async_trait]
+pub trait Discover {
+    async fn discover(&self, query: &SearchQuery) -> Result<SearchResult, ApiError>;
+}
+
+#[async_trait]
+impl<T, C> Discover for ContextWrapper<T, C>
+where
+    T: Api<C> + Send + Sync,
+    C: Clone + Send + Sync,
+{
+    async fn discover(&self, query: &SearchQuery) -> Result<SearchResult, ApiError> {
+        let response = search_nf_instances(self, query).await?;
+
+        match response {
+            SearchNfInstancesResponse::ExpectedResponseToAValidRequest { body, .. } => Ok(body),
+            response => Err(ApiError(format!(
+                "Discovery rejected by the NRF: {:?}",
+                response
+            ))),
+        }
+    }
+}
+
+/// Counters of a [`DiscoveryCache`], for comparing the transports.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Queries answered from the cache.
+    pub hits: u64,
+    /// Queries sent to the NRF.
+    pub misses: u64,
+    /// Results dropped because of an NF status notification.
+    pub invalidated: u64,
+}
+
+#[derive(Debug)]
+struct Entry {
+    query: SearchQuery,
+    result: SearchResult,
+    expires: Instant,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    invalidated: AtomicU64,
+}
+
+/// Number of results kept by default.
+pub const DEFAULT_CAPACITY: usize = 1_000;
+
+/// Discovery results by query, shared between the clones of the cache.
+#[derive(Debug, Clone)]
+pub struct DiscoveryCache<D> {
+    discovery: D,
+    entries: Arc<RwLock<HashMap<String, Entry>>>,
+    counters: Arc<Counters>,
+    capacity: usize,
+}
+
+impl<D: Discover + Send + Sync> DiscoveryCache<D> {
+    pub fn new(discovery: D) -> DiscoveryCache<D> {
+        DiscoveryCache::with_capacity(discovery, DEFAULT_CAPACITY)
+    }
+
+    /// A cache keeping at most `capacity` results.
+    pub fn with_capacity(discovery: D, capacity: usize) -> DiscoveryCache<D> {
+        DiscoveryCache {
+            discovery,
+            entries: Arc::default(),
+            counters: Arc::default(),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// The transport the cache sends its queries on.
+    pub fn discovery(&self) -> &D {
+        &self.discovery
+    }
+
+    /// Answers a query from the cache, or from the NRF when there is no valid
+    /// result for it.
+    pub async fn search(&self, query: &SearchQuery) -> Result<SearchResult, ApiError> {
+        let key = cache_key(query);
+        let now = Instant::now();
+
+        let cached = self
+            .entries
+            .read()
+            .expect("cache lock poisoned")
+            .get(&key)
+            .filter(|e| e.expires > now)
+            .map(|e| e.result.clone());
+        if let Some(result) = cached {
+            self.counters.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(result);
+        }
+
+        self.counters.misses.fetch_add(1, Ordering::Relaxed);
+        let result = self.discovery.discover(query).await?;
+
+        let mut entries = self.entries.write().expect("cache lock poisoned");
+        match result.validity_period {
+            Some(validity_period) if validity_period > 0 => {
+                entries.remove(&key);
+                let now = Instant::now();
+                if entries.len() >= self.capacity {
+                    entries.retain(|_, e| e.expires > now);
+                }
+                while entries.len() >= self.capacity {
+                    let first = entries
+                        .iter()
+                        .min_by_key(|(_, e)| e.expires)
+                        .map(|(key, _)| key.clone())
+                        .expect("a full cache has results");
+                    entries.remove(&first);
+                }
+                entries.insert(
+                    key,
+                    Entry {
+                        query: query.clone(),
+                        result: result.clone(),
+                        expires: now + Duration::from_secs(validity_period as u64),
+                    },
+                );
+            }
+            _ => {
+                entries.remove(&key);
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Drops the results an NF status notification may have made wrong: those
+    /// listing the NF instance and, when its new profile is known, those of
+    /// the queries it now matches.
+    pub fn invalidate(&self, nf_instance_id: &uuid::Uuid, profile: Option<&NfProfile>) {
+        let mut entries = self.entries.write().expect("cache lock poisoned");
+        let before = entries.len();
+        entries.retain(|_, e| {
+            !(e.result
+                .nf_instances
+                .iter()
+                .any(|p| p.nf_instance_id == *nf_instance_id)
+                || profile.map(|p| e.query.matches(p)).unwrap_or(false))
+        });
+        let invalidated = before - entries.len();
+        self.counters
+            .invalidated
+            .fetch_add(invalidated as u64, Ordering::Relaxed);
+    }
+
+    /// Drops every result.
+    pub fn clear(&self) {
+        self.entries.write().expect("cache lock poisoned").clear();
+    }
+
+    /// Drops the results whose validity period has passed and returns how
+    /// many were dropped.
+    pub fn expire(&self) -> usize {
+        let now = Instant::now();
+        let mut entries = self.entries.write().expect("cache lock poisoned");
+        let before = entries.len();
+        entries.retain(|_, e| e.expires > now);
+        before - entries.len()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.read().expect("cache lock poisoned").len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.counters.hits.load(Ordering::Relaxed),
+            misses: self.counters.misses.load(Ordering::Relaxed),
+            invalidated: self.counters.invalidated.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// The JSON form of a query identifies it: the filters are serialized in a
+/// fixed order and the absent ones are skipped.
+fn cache_key(query: &SearchQuery) -> String {
+    serde_json::to_string(query).expect("impossible to fail to serialize")
+}
+
+/// Sends a query with an NRF client: the filters of the query go in their
+/// place among the parameters of `search_nf_instances`, the others are left
+/// out.
+async fn search_nf_instances<T, C>(
+    client: &ContextWrapper<T, C>,
+    query: &SearchQuery,
+) -> Result<SearchNfInstancesResponse, ApiError>
+where
+    T: Api<C> + Send + Sync,
+    C: Clone + Send + Sync,
+{
+    let SearchQuery {
+        target_nf_type,
+        requester_nf_type,
+        service_names,
+        snssais,
+        dnn,
+        target_plmn_list,
+        tai,
+        limit,
+    } = query;
+    client
+        .search_nf_instances(
+            target_nf_type.clone(),
+            requester_nf_type.clone(),
+            None, // accept_encoding
+            None, // preferred_collocated_nf_types
+            None, // requester_nf_instance_id
+            service_names.as_ref(),
+            None, // requester_nf_instance_fqdn
+            target_plmn_list.as_ref(),
+            None, // requester_plmn_list
+            None, // target_nf_instance_id
+            None, // target_nf_fqdn
+            None, // hnrf_uri
+            snssais.as_ref(),
+            None, // requester_snssais
+            None, // plmn_specific_snssai_list
+            None, // requester_plmn_specific_snssai_list
+            dnn.clone(),
+            None, // ipv4_index
+            None, // ipv6_index
+            None, // nsi_list
+            None, // smf_serving_area
+            None, // mbsmf_serving_area
+            tai.clone(),
+            None, // amf_region_id
+            None, // amf_set_id
+            None, // guami
+            None, // supi
+            None, // ue_ipv4_address
+            None, // ip_domain
+            None, // ue_ipv6_prefix
+            None, // pgw_ind
+            None, // preferred_pgw_ind
+            None, // pgw
+            None, // pgw_ip
+            None, // gpsi
+            None, // external_group_identity
+            None, // internal_group_identity
+            None, // pfd_data
+            None, // data_set
+            None, // routing_indicator
+            None, // group_id_list
+            None, // dnai_list
+            None, // pdu_session_types
+            None, // event_id_list
+            None, // nwdaf_event_list
+            None, // supported_features
+            None, // upf_iwk_eps_ind
+            None, // chf_supported_plmn
+            None, // preferred_locality
+            None, // access_type
+            *limit,
+            None, // required_features
+            None, // complex_query
+            None, // max_payload_size
+            None, // max_payload_size_ext
+            None, // atsss_capability
+            None, // upf_ue_ip_addr_ind
+            None, // client_type
+            None, // lmf_id
+            None, // an_node_type
+            None, // rat_type
+            None, // preferred_tai
+            None, // preferred_nf_instances
+            None, // if_none_match
+            None, // target_snpn
+            None, // requester_snpn_list
+            None, // af_ee_data
+            None, // w_agf_info
+            None, // tngf_info
+            None, // twif_info
+            None, // target_nf_set_id
+            None, // target_nf_service_set_id
+            None, // nef_id
+            None, // notification_type
+            None, // n1_msg_class
+            None, // n2_info_class
+            None, // serving_scope
+            None, // imsi
+            None, // ims_private_identity
+            None, // ims_public_identity
+            None, // msisdn
+            None, // preferred_api_versions
+            None, // v2x_support_ind
+            None, // redundant_gtpu
+            None, // redundant_transport
+            None, // ipups
+            None, // scp_domain_list
+            None, // address_domain
+            None, // ipv4_addr
+            None, // ipv6_prefix
+            None, // served_nf_set_id
+            None, // remote_plmn_id
+            None, // remote_snpn_id
+            None, // data_forwarding
+            None, // preferred_full_plmn
+            None, // requester_features
+            None, // realm_id
+            None, // storage_id
+            None, // vsmf_support_ind
+            None, // ismf_support_ind
+            None, // nrf_disc_uri
+            None, // preferred_vendor_specific_features
+            None, // preferred_vendor_specific_nf_features
+            None, // required_pfcp_features
+            None, // home_pub_key_id
+            None, // prose_support_ind
+            None, // analytics_aggregation_ind
+            None, // serving_nf_set_id
+            None, // serving_nf_type
+            None, // ml_analytics_info_list
+            None, // analytics_metadata_prov_ind
+            None, // nsacf_capability
+            None, // mbs_session_id_list
+            None, // area_session_id
+            None, // gmlc_number
+            None, // upf_n6_ip
+            None, // tai_list
+            None, // preferences_precedence
+            None, // support_onboarding_capability
+            None, // uas_nf_functionality_ind
+            None, // v2x_capability
+            None, // prose_capability
+            None, // shared_data_id
+            None, // target_hni
+            None, // target_nw_resolution
+            None, // exclude_nfinst_list
+            None, // exclude_nfservinst_list
+            None, // exclude_nfserviceset_list
+            None, // exclude_nfset_list
+            None, // preferred_analytics_delays
+        )
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{NfStatus, NfStatusAnyOf, NfType};
+    use crate::registry::NfRegistry;
+    use futures::executor::block_on;
+
+    /// An NRF answering from a registry, with results valid for the given
+    /// number of seconds.
+    struct Nrf {
+        registry: NfRegistry,
+        validity_period: i32,
+    }
+
+    #[async_trait]
+    impl Discover for Nrf {
+        async fn discover(&self, query: &SearchQuery) -> Result<SearchResult, ApiError> {
+            let mut result = SearchResult::new(self.registry.search(query));
+            result.validity_period = Some(self.validity_period);
+            Ok(result)
+        }
+    }
+
+    fn cache(validity_period: i32, capacity: usize) -> (NfRegistry, DiscoveryCache<Nrf>) {
+        let registry = NfRegistry::new();
+        let nrf = Nrf {
+            registry: registry.clone(),
+            validity_period,
+        };
+        (registry, DiscoveryCache::with_capacity(nrf, capacity))
+    }
+
+    fn profile(nf_type: NfType) -> NfProfile {
+        NfProfile::new(
+            uuid::Uuid::new_v4(),
+            nf_type,
+            NfStatus::new(NfStatusAnyOf::Registered.to_string()),
+        )
+    }
+
+    fn query(target_nf_type: NfType) -> SearchQuery {
+        SearchQuery::new(target_nf_type, NfType::AMF)
+    }
+
+    fn found(cache: &DiscoveryCache<Nrf>, query: &SearchQuery) -> Vec<uuid::Uuid> {
+        block_on(cache.search(query))
+            .unwrap()
+            .nf_instances
+            .iter()
+            .map(|p| p.nf_instance_id)
+            .collect()
+    }
+
+    #[test]
+    fn results_are_served_until_their_validity_period_passes() {
+        let (registry, cache) = cache(1, 10);
+        let smf = profile(NfType::SMF);
+        registry.insert(smf.clone());
+
+        assert_eq!(found(&cache, &query(NfType::SMF)), vec![smf.nf_instance_id]);
+        registry.remove(&smf.nf_instance_id);
+        assert_eq!(found(&cache, &query(NfType::SMF)), vec![smf.nf_instance_id]);
+        assert_eq!(cache.stats().hits, 1);
+
+        std::thread::sleep(Duration::from_millis(1100));
+        assert_eq!(cache.expire(), 1);
+        assert!(found(&cache, &query(NfType::SMF)).is_empty());
+        assert_eq!(cache.stats().misses, 2);
+    }
+
+    #[test]
+    fn results_without_validity_period_are_not_cached() {
+        let (_, cache) = cache(0, 10);
+        found(&cache, &query(NfType::SMF));
+        assert!(cache.is_empty());
+    }
+
+    #[test]
+    fn full_cache_drops_the_result_closest_to_expiring() {
+        let (_, cache) = cache(60, 2);
+        found(&cache, &query(NfType::SMF));
+        found(&cache, &query(NfType::UDM));
+        found(&cache, &query(NfType::UPF));
+        assert_eq!(cache.len(), 2);
+
+        found(&cache, &query(NfType::UPF));
+        found(&cache, &query(NfType::UDM));
+        found(&cache, &query(NfType::SMF));
+        assert_eq!(cache.stats().hits, 2);
+        assert_eq!(cache.stats().misses, 4);
+    }
+
+    #[test]
+    fn registered_profiles_invalidate_the_queries_they_match() {
+        let (registry, cache) = cache(60, 10);
+        assert!(found(&cache, &query(NfType::SMF)).is_empty());
+        found(&cache, &query(NfType::UDM));
+
+        let smf = profile(NfType::SMF);
+        registry.insert(smf.clone());
+        cache.invalidate(&smf.nf_instance_id, Some(&smf));
+        assert_eq!(cache.stats().invalidated, 1);
+        assert_eq!(found(&cache, &query(NfType::SMF)), vec![smf.nf_instance_id]);
+    }
+
+    #[test]
+    fn updated_profiles_invalidate_the_results_listing_them() {
+        let (registry, cache) = cache(60, 10);
+        let smf = profile(NfType::SMF);
+        registry.insert(smf.clone());
+        assert_eq!(found(&cache, &query(NfType::SMF)), vec![smf.nf_instance_id]);
+        found(&cache, &query(NfType::UDM));
+
+        let mut suspended = smf.clone();
+        suspended.nf_status = NfStatus::new(NfStatusAnyOf::Suspended.to_string());
+        registry.update(suspended.clone());
+        cache.invalidate(&smf.nf_instance_id, Some(&suspended));
+        assert_eq!(cache.stats().invalidated, 1);
+        assert!(found(&cache, &query(NfType::SMF)).is_empty());
+    }
+
+    #[test]
+    fn deregistered_profiles_invalidate_the_results_listing_them() {
+        let (registry, cache) = cache(60, 10);
+        let smf = profile(NfType::SMF);
+        registry.insert(smf.clone());
+        assert_eq!(found(&cache, &query(NfType::SMF)), vec![smf.nf_instance_id]);
+        found(&cache, &query(NfType::UDM));
+
+        registry.remove(&smf.nf_instance_id);
+        cache.invalidate(&smf.nf_instance_id, None);
+        assert_eq!(cache.stats().invalidated, 1);
+        assert_eq!(cache.len(), 1);
+        assert!(found(&cache, &query(NfType::SMF)).is_empty());
+    }
+}
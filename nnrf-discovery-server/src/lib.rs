@@ -42,6 +42,8 @@ pub enum RetrieveCompleteSearchResponse {
         body: models::RedirectResponse,
         location: String,
     },
+    /// Not Found
+    NotFound(models::ProblemDetails),
 }
 
 #[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
@@ -217,6 +219,8 @@ pub enum RetrieveStoredSearchResponse {
         body: models::RedirectResponse,
         location: String,
     },
+    /// Not Found
+    NotFound(models::ProblemDetails),
 }
 
 /// API
@@ -1095,5 +1099,15 @@ pub mod context;
 
 pub mod models;
 
+pub mod cache;
+
+pub mod registry;
+
+pub mod scp_domains;
+
+pub mod search;
+
+pub mod selection;
+
 #[cfg(any(feature = "client", feature = "server"))]
 pub(crate) mod header;
@@ -5715,14 +5715,15 @@ impl std::convert::TryFrom<hyper::header::HeaderValue>
     }
 }
 
+/// Either a DNN or the wildcard DNN (`*`)
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 #[cfg_attr(feature = "conversion", derive(frunk::LabelledGeneric))]
-pub struct DnnSmfInfoItemDnn {}
+pub struct DnnSmfInfoItemDnn(String);
 
 impl DnnSmfInfoItemDnn {
     #[allow(clippy::new_without_default)]
-    pub fn new() -> DnnSmfInfoItemDnn {
-        DnnSmfInfoItemDnn {}
+    pub fn new(dnn: String) -> DnnSmfInfoItemDnn {
+        DnnSmfInfoItemDnn(dnn)
     }
 }
 
@@ -5731,9 +5732,7 @@ impl DnnSmfInfoItemDnn {
 /// Should be implemented in a serde serializer
 impl std::string::ToString for DnnSmfInfoItemDnn {
     fn to_string(&self) -> String {
-        let params: Vec<Option<String>> = vec![];
-
-        params.into_iter().flatten().collect::<Vec<_>>().join(",")
+        self.0.clone()
     }
 }
 
@@ -5744,44 +5743,7 @@ impl std::str::FromStr for DnnSmfInfoItemDnn {
     type Err = String;
 
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        /// An intermediate representation of the struct to use for parsing.
-        #[derive(Default)]
-        #[allow(dead_code)]
-        struct IntermediateRep {}
-
-        let mut intermediate_rep = IntermediateRep::default();
-
-        // Parse into intermediate representation
-        let mut string_iter = s.split(',');
-        let mut key_result = string_iter.next();
-
-        while key_result.is_some() {
-            let val = match string_iter.next() {
-                Some(x) => x,
-                None => {
-                    return std::result::Result::Err(
-                        "Missing value while parsing DnnSmfInfoItemDnn".to_string(),
-                    )
-                }
-            };
-
-            if let Some(key) = key_result {
-                #[allow(clippy::match_single_binding)]
-                match key {
-                    _ => {
-                        return std::result::Result::Err(
-                            "Unexpected key while parsing DnnSmfInfoItemDnn".to_string(),
-                        )
-                    }
-                }
-            }
-
-            // Get the next key
-            key_result = string_iter.next();
-        }
-
-        // Use the intermediate representation to return the struct
-        std::result::Result::Ok(DnnSmfInfoItemDnn {})
+        std::result::Result::Ok(DnnSmfInfoItemDnn(s.to_string()))
     }
 }
 
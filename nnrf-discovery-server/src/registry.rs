@@ -0,0 +1,826 @@
+//! In-memory NF profile registry used to answer discovery requests.
+//!
+//! The registry stores `models::NfProfile` entries keyed by NF instance id and
+//! evaluates the subset of the `search_nf_instances` query parameters that
+//! matter for the scenarios in this repository.
+//!
+//! Every change of the registry is reported to the streams returned by
+//! [`NfRegistry::watch`], which is what the NF status notifications are built
+//! on.
+
+use futures::channel::mpsc;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex, RwLock};
+use std::time::{Duration, Instant};
+
+use crate::models::{self, NfProfile, NfService, NfStatus, NfStatusAnyOf, NfType};
+
+/// Discovery filters evaluated against the registered profiles.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchQuery {
+    #[serde(rename = "target-nf-type")]
+    pub target_nf_type: NfType,
+
+    #[serde(rename = "requester-nf-type")]
+    pub requester_nf_type: NfType,
+
+    #[serde(rename = "service-names")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service_names: Option<Vec<models::ServiceName>>,
+
+    #[serde(rename = "snssais")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snssais: Option<Vec<models::Snssai>>,
+
+    #[serde(rename = "dnn")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dnn: Option<String>,
+
+    #[serde(rename = "target-plmn-list")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target_plmn_list: Option<Vec<models::PlmnId>>,
+
+    #[serde(rename = "tai")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tai: Option<models::Tai>,
+
+    #[serde(rename = "limit")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limit: Option<i32>,
+}
+
+impl SearchQuery {
+    pub fn new(target_nf_type: NfType, requester_nf_type: NfType) -> SearchQuery {
+        SearchQuery {
+            target_nf_type,
+            requester_nf_type,
+            service_names: None,
+            snssais: None,
+            dnn: None,
+            target_plmn_list: None,
+            tai: None,
+            limit: None,
+        }
+    }
+
+    /// Returns true if the given profile satisfies every filter of the query.
+    pub fn matches(&self, profile: &NfProfile) -> bool {
+        profile.nf_type == self.target_nf_type
+            && profile.nf_status.to_string() == NfStatusAnyOf::Registered.to_string()
+            && self.matches_services(profile)
+            && self.matches_snssais(profile)
+            && self.matches_dnn(profile)
+            && self.matches_plmn(profile)
+            && self.matches_tai(profile)
+    }
+
+    /// Strips the services that were not asked for from a matching profile.
+    pub fn restrict(&self, mut profile: NfProfile) -> NfProfile {
+        if let Some(names) = &self.service_names {
+            if let Some(services) = profile.nf_services.as_mut() {
+                services.retain(|s| names.contains(&s.service_name));
+            }
+            if let Some(services) = profile.nf_service_list.as_mut() {
+                services.retain(|_, s| names.contains(&s.service_name));
+            }
+        }
+        profile
+    }
+
+    fn matches_services(&self, profile: &NfProfile) -> bool {
+        match &self.service_names {
+            Some(names) if !names.is_empty() => {
+                services(profile).any(|s| names.contains(&s.service_name))
+            }
+            _ => true,
+        }
+    }
+
+    fn matches_snssais(&self, profile: &NfProfile) -> bool {
+        match (&self.snssais, &profile.s_nssais) {
+            (Some(wanted), Some(served)) if !wanted.is_empty() => wanted
+                .iter()
+                .any(|w| served.iter().any(|s| snssai_matches(s, w))),
+            _ => true,
+        }
+    }
+
+    fn matches_dnn(&self, profile: &NfProfile) -> bool {
+        let dnn = match &self.dnn {
+            Some(dnn) => dnn,
+            None => return true,
+        };
+
+        let dnns: Vec<String> = match profile.nf_type {
+            NfType::SMF => smf_infos(profile)
+                .flat_map(|i| i.s_nssai_smf_info_list.iter())
+                .flat_map(|i| i.dnn_smf_info_list.iter())
+                .map(|d| d.dnn.to_string())
+                .collect(),
+            NfType::UPF => upf_infos(profile)
+                .flat_map(|i| i.s_nssai_upf_info_list.iter())
+                .flat_map(|i| i.dnn_upf_info_list.iter())
+                .map(|d| d.dnn.clone())
+                .collect(),
+            // DNN is not a discovery criterion for the other NF types
+            _ => return true,
+        };
+
+        dnns.is_empty() || dnns.iter().any(|d| d == "*" || d.eq_ignore_ascii_case(dnn))
+    }
+
+    fn matches_plmn(&self, profile: &NfProfile) -> bool {
+        match (&self.target_plmn_list, &profile.plmn_list) {
+            (Some(wanted), Some(served)) if !wanted.is_empty() => {
+                wanted.iter().any(|w| served.contains(w))
+            }
+            _ => true,
+        }
+    }
+
+    fn matches_tai(&self, profile: &NfProfile) -> bool {
+        let tai = match &self.tai {
+            Some(tai) => tai,
+            None => return true,
+        };
+
+        let mut tai_lists: Vec<&Vec<models::Tai>> = vec![];
+        let mut tai_ranges: Vec<&Vec<models::TaiRange>> = vec![];
+        for info in amf_infos(profile) {
+            tai_lists.extend(info.tai_list.iter());
+            tai_ranges.extend(info.tai_range_list.iter());
+        }
+        for info in smf_infos(profile) {
+            tai_lists.extend(info.tai_list.iter());
+            tai_ranges.extend(info.tai_range_list.iter());
+        }
+        for info in upf_infos(profile) {
+            tai_lists.extend(info.tai_list.iter());
+            tai_ranges.extend(info.tai_range_list.iter());
+        }
+
+        // A profile without any TAI information serves every tracking area
+        if tai_lists.is_empty() && tai_ranges.is_empty() {
+            return true;
+        }
+
+        tai_lists
+            .iter()
+            .flat_map(|l| l.iter())
+            .any(|t| t.plmn_id == tai.plmn_id && t.tac.eq_ignore_ascii_case(&tai.tac))
+            || tai_ranges
+                .iter()
+                .flat_map(|l| l.iter())
+                .any(|r| tai_in_range(tai, r))
+    }
+}
+
+/// Number of consecutive heartbeat periods an NF may miss before its profile
+/// is removed from the registry. After the first missed period the profile is
+/// only marked as SUSPENDED.
+pub const MISSED_HEARTBEATS_BEFORE_REMOVAL: u32 = 3;
+
+/// NF types allowed to access an NF and its services, as given by the NF at
+/// registration. They are NFManagement attributes, which the discovered
+/// profiles do not have. Kept as strings, like in the NFManagement profile.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AllowedNfTypes {
+    /// `None` if every NF type is allowed
+    pub profile: Option<Vec<String>>,
+    /// By service instance id, for the services restricting them further
+    pub services: HashMap<String, Vec<String>>,
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    profile: NfProfile,
+    /// Expected heartbeat period, `None` for statically provisioned profiles
+    heart_beat_timer: Option<Duration>,
+    allowed_nf_types: AllowedNfTypes,
+    last_seen: Instant,
+}
+
+impl Entry {
+    fn new(
+        profile: NfProfile,
+        heart_beat_timer: Option<Duration>,
+        allowed_nf_types: AllowedNfTypes,
+    ) -> Entry {
+        Entry {
+            profile,
+            heart_beat_timer,
+            allowed_nf_types,
+            last_seen: Instant::now(),
+        }
+    }
+}
+
+/// Profiles whose status changed during a heartbeat check.
+#[derive(Debug, Clone, Default)]
+pub struct Expired {
+    /// Profiles that missed their heartbeat and are now SUSPENDED
+    pub suspended: Vec<NfProfile>,
+    /// Profiles that stayed silent for too long and were deregistered
+    pub removed: Vec<NfProfile>,
+}
+
+/// Change of a registered profile, as reported to the registry watchers.
+#[derive(Debug, Clone, PartialEq)]
+pub enum RegistryEvent {
+    Registered(NfProfile),
+    ProfileChanged(NfProfile),
+    Deregistered(NfProfile),
+}
+
+impl RegistryEvent {
+    /// The profile after the change, or the removed one for a deregistration.
+    pub fn profile(&self) -> &NfProfile {
+        match self {
+            RegistryEvent::Registered(profile)
+            | RegistryEvent::ProfileChanged(profile)
+            | RegistryEvent::Deregistered(profile) => profile,
+        }
+    }
+}
+
+/// Thread-safe handle to the set of registered NF profiles.
+#[derive(Debug, Clone, Default)]
+pub struct NfRegistry {
+    entries: Arc<RwLock<HashMap<uuid::Uuid, Entry>>>,
+    watchers: Arc<Mutex<Vec<mpsc::UnboundedSender<RegistryEvent>>>>,
+}
+
+impl NfRegistry {
+    pub fn new() -> NfRegistry {
+        NfRegistry::default()
+    }
+
+    pub fn from_profiles(profiles: Vec<NfProfile>) -> NfRegistry {
+        let registry = NfRegistry::new();
+        for profile in profiles {
+            registry.insert(profile);
+        }
+        registry
+    }
+
+    /// Reads a JSON array of NF profiles, the format of the seed files of the
+    /// NRF examples and of the saved registries.
+    pub fn read<P: AsRef<Path>>(path: P) -> std::io::Result<Vec<NfProfile>> {
+        let data = std::fs::read(path)?;
+        serde_json::from_slice(&data)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    /// Loads a JSON array of NF profiles, e.g. the seed file of the NRF examples.
+    pub fn load<P: AsRef<Path>>(path: P) -> std::io::Result<NfRegistry> {
+        Ok(NfRegistry::from_profiles(NfRegistry::read(path)?))
+    }
+
+    /// Writes the registered profiles as a JSON array, which [`NfRegistry::read`]
+    /// reads back. The file is replaced at once, never left half written.
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> std::io::Result<()> {
+        let mut profiles = self.profiles();
+        profiles.sort_by_key(|p| p.nf_instance_id);
+        write_profiles(path, &profiles)
+    }
+
+    /// Stores a statically provisioned profile, which is never subject to
+    /// heartbeat supervision. Returns the profile previously registered with
+    /// the same id.
+    pub fn insert(&self, profile: NfProfile) -> Option<NfProfile> {
+        self.register(profile, None)
+    }
+
+    /// Stores a profile registered by the NF itself. When `heart_beat_timer` is
+    /// set the NF is expected to refresh its registration within that period.
+    pub fn register(
+        &self,
+        profile: NfProfile,
+        heart_beat_timer: Option<Duration>,
+    ) -> Option<NfProfile> {
+        self.register_with(profile, heart_beat_timer, AllowedNfTypes::default())
+    }
+
+    /// Stores a profile registered by the NF itself, with the NF types it
+    /// allows.
+    pub fn register_with(
+        &self,
+        profile: NfProfile,
+        heart_beat_timer: Option<Duration>,
+        allowed_nf_types: AllowedNfTypes,
+    ) -> Option<NfProfile> {
+        let previous = self
+            .entries
+            .write()
+            .expect("registry lock poisoned")
+            .insert(
+                profile.nf_instance_id,
+                Entry::new(profile.clone(), heart_beat_timer, allowed_nf_types),
+            )
+            .map(|e| e.profile);
+
+        match previous {
+            None => self.notify(RegistryEvent::Registered(profile)),
+            Some(ref previous) if *previous != profile => {
+                self.notify(RegistryEvent::ProfileChanged(profile))
+            }
+            Some(_) => {}
+        }
+        previous
+    }
+
+    /// Replaces a registered profile, keeping its heartbeat supervision.
+    /// Returns `None` if no profile is registered under that id.
+    pub fn update(&self, profile: NfProfile) -> Option<NfProfile> {
+        let previous = {
+            let mut entries = self.entries.write().expect("registry lock poisoned");
+            let entry = entries.get_mut(&profile.nf_instance_id)?;
+            entry.last_seen = Instant::now();
+            std::mem::replace(&mut entry.profile, profile.clone())
+        };
+
+        if previous != profile {
+            self.notify(RegistryEvent::ProfileChanged(profile));
+        }
+        Some(previous)
+    }
+
+    /// Records a heartbeat from an NF. A SUSPENDED profile becomes REGISTERED
+    /// again. Returns the current profile, or `None` if the NF is unknown.
+    pub fn heartbeat(&self, nf_instance_id: &uuid::Uuid) -> Option<NfProfile> {
+        let (profile, resumed) = {
+            let mut entries = self.entries.write().expect("registry lock poisoned");
+            let entry = entries.get_mut(nf_instance_id)?;
+            entry.last_seen = Instant::now();
+            let resumed =
+                entry.profile.nf_status.to_string() == NfStatusAnyOf::Suspended.to_string();
+            if resumed {
+                entry.profile.nf_status = NfStatus::new(NfStatusAnyOf::Registered.to_string());
+            }
+            (entry.profile.clone(), resumed)
+        };
+
+        if resumed {
+            self.notify(RegistryEvent::ProfileChanged(profile.clone()));
+        }
+        Some(profile)
+    }
+
+    /// Suspends the profiles that missed their heartbeat and removes those that
+    /// missed [`MISSED_HEARTBEATS_BEFORE_REMOVAL`] of them.
+    pub fn expire(&self) -> Expired {
+        let mut expired = Expired::default();
+        let mut entries = self.entries.write().expect("registry lock poisoned");
+        let now = Instant::now();
+
+        entries.retain(|_, entry| {
+            let timer = match entry.heart_beat_timer {
+                Some(timer) => timer,
+                None => return true,
+            };
+            let silence = now.duration_since(entry.last_seen);
+
+            if silence > timer * MISSED_HEARTBEATS_BEFORE_REMOVAL {
+                expired.removed.push(entry.profile.clone());
+                return false;
+            }
+            if silence > timer
+                && entry.profile.nf_status.to_string() == NfStatusAnyOf::Registered.to_string()
+            {
+                entry.profile.nf_status = NfStatus::new(NfStatusAnyOf::Suspended.to_string());
+                expired.suspended.push(entry.profile.clone());
+            }
+            true
+        });
+        drop(entries);
+
+        for profile in &expired.suspended {
+            self.notify(RegistryEvent::ProfileChanged(profile.clone()));
+        }
+        for profile in &expired.removed {
+            self.notify(RegistryEvent::Deregistered(profile.clone()));
+        }
+        expired
+    }
+
+    pub fn remove(&self, nf_instance_id: &uuid::Uuid) -> Option<NfProfile> {
+        let removed = self
+            .entries
+            .write()
+            .expect("registry lock poisoned")
+            .remove(nf_instance_id)
+            .map(|e| e.profile);
+
+        if let Some(ref profile) = removed {
+            self.notify(RegistryEvent::Deregistered(profile.clone()));
+        }
+        removed
+    }
+
+    /// Returns a stream of the changes made to the registry from now on. The
+    /// stream ends when the registry is dropped.
+    pub fn watch(&self) -> mpsc::UnboundedReceiver<RegistryEvent> {
+        let (sender, receiver) = mpsc::unbounded();
+        self.watchers
+            .lock()
+            .expect("registry lock poisoned")
+            .push(sender);
+        receiver
+    }
+
+    /// Sends an event to the watchers, forgetting those that went away.
+    fn notify(&self, event: RegistryEvent) {
+        self.watchers
+            .lock()
+            .expect("registry lock poisoned")
+            .retain(|watcher| watcher.unbounded_send(event.clone()).is_ok());
+    }
+
+    pub fn get(&self, nf_instance_id: &uuid::Uuid) -> Option<NfProfile> {
+        self.entries
+            .read()
+            .expect("registry lock poisoned")
+            .get(nf_instance_id)
+            .map(|e| e.profile.clone())
+    }
+
+    /// Heartbeat period the NF was registered with, if it is supervised.
+    pub fn heart_beat_timer(&self, nf_instance_id: &uuid::Uuid) -> Option<Duration> {
+        self.entries
+            .read()
+            .expect("registry lock poisoned")
+            .get(nf_instance_id)
+            .and_then(|e| e.heart_beat_timer)
+    }
+
+    /// NF types the NF allowed at registration.
+    pub fn allowed_nf_types(&self, nf_instance_id: &uuid::Uuid) -> Option<AllowedNfTypes> {
+        self.entries
+            .read()
+            .expect("registry lock poisoned")
+            .get(nf_instance_id)
+            .map(|e| e.allowed_nf_types.clone())
+    }
+
+    pub fn profiles(&self) -> Vec<NfProfile> {
+        self.entries
+            .read()
+            .expect("registry lock poisoned")
+            .values()
+            .map(|e| e.profile.clone())
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.read().expect("registry lock poisoned").len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Evaluates a discovery query, most preferred (lowest priority value) first.
+    pub fn search(&self, query: &SearchQuery) -> Vec<NfProfile> {
+        let mut found: Vec<NfProfile> = self
+            .entries
+            .read()
+            .expect("registry lock poisoned")
+            .values()
+            .filter(|e| query.matches(&e.profile))
+            .map(|e| query.restrict(e.profile.clone()))
+            .collect();
+
+        found.sort_by(|a, b| {
+            a.priority
+                .unwrap_or(0)
+                .cmp(&b.priority.unwrap_or(0))
+                .then_with(|| a.nf_instance_id.cmp(&b.nf_instance_id))
+        });
+
+        if let Some(limit) = query.limit {
+            if limit > 0 {
+                found.truncate(limit as usize);
+            }
+        }
+
+        found
+    }
+}
+
+/// Writes NF profiles in the format of [`NfRegistry::read`], through a
+/// temporary file renamed over the previous one.
+pub fn write_profiles<P: AsRef<Path>>(path: P, profiles: &[NfProfile]) -> std::io::Result<()> {
+    let path = path.as_ref();
+    let data = serde_json::to_vec_pretty(profiles).expect("impossible to fail to serialize");
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+    std::fs::write(&tmp, data)?;
+    std::fs::rename(&tmp, path)
+}
+
+fn services(profile: &NfProfile) -> impl Iterator<Item = &NfService> {
+    profile
+        .nf_services
+        .iter()
+        .flat_map(|s| s.iter())
+        .chain(profile.nf_service_list.iter().flat_map(|s| s.values()))
+}
+
+fn amf_infos(profile: &NfProfile) -> impl Iterator<Item = &models::AmfInfo> {
+    profile
+        .amf_info
+        .iter()
+        .chain(profile.amf_info_list.iter().flat_map(|l| l.values()))
+}
+
+fn smf_infos(profile: &NfProfile) -> impl Iterator<Item = &models::SmfInfo> {
+    profile
+        .smf_info
+        .iter()
+        .chain(profile.smf_info_list.iter().flat_map(|l| l.values()))
+}
+
+fn upf_infos(profile: &NfProfile) -> impl Iterator<Item = &models::UpfInfo> {
+    profile
+        .upf_info
+        .iter()
+        .chain(profile.upf_info_list.iter().flat_map(|l| l.values()))
+}
+
+fn snssai_matches(served: &models::ExtSnssai, wanted: &models::Snssai) -> bool {
+    if served.sst != wanted.sst {
+        return false;
+    }
+    if served.wildcard_sd == Some(true) {
+        return true;
+    }
+
+    match &wanted.sd {
+        None => served.sd.is_none(),
+        Some(sd) => {
+            served
+                .sd
+                .as_ref()
+                .map(|s| s.eq_ignore_ascii_case(sd))
+                .unwrap_or(false)
+                || served
+                    .sd_ranges
+                    .iter()
+                    .flat_map(|r| r.iter())
+                    .any(|r| hex_in_range(sd, r.start.as_deref(), r.end.as_deref()))
+        }
+    }
+}
+
+fn tai_in_range(tai: &models::Tai, range: &models::TaiRange) -> bool {
+    range.plmn_id == tai.plmn_id
+        && range.tac_range_list.iter().any(|r| match &r.pattern {
+            Some(pattern) => regex_free_match(pattern, &tai.tac),
+            None => hex_in_range(&tai.tac, r.start.as_deref(), r.end.as_deref()),
+        })
+}
+
+fn hex_in_range(value: &str, start: Option<&str>, end: Option<&str>) -> bool {
+    let parse = |s: &str| u64::from_str_radix(s, 16).ok();
+    match (parse(value), start.and_then(parse), end.and_then(parse)) {
+        (Some(v), Some(s), Some(e)) => s <= v && v <= e,
+        _ => false,
+    }
+}
+
+/// TAC range patterns are regular expressions in the spec; the ones seen in
+/// practice are either a literal TAC or `.*`, which is all we support here.
+fn regex_free_match(pattern: &str, tac: &str) -> bool {
+    pattern == ".*" || pattern.trim_start_matches('^').trim_end_matches('$') == tac
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    const SMF_ID: &str = "b3a71a80-b8d5-41ed-b2cc-8bbc6f173b7d";
+
+    /// A registered SMF serving `internet` on 1/000001 and the tracking
+    /// areas 000010 to 00001f and 000100 of PLMN 001/01.
+    fn smf_data() -> serde_json::Value {
+        json!({
+            "nfInstanceId": SMF_ID,
+            "nfType": "SMF",
+            "nfStatus": "REGISTERED",
+            "plmnList": [{"mcc": "001", "mnc": "01"}],
+            "sNssais": [{"sst": 1, "sd": "000001"}],
+            "nfServices": [{
+                "serviceInstanceId": "1",
+                "serviceName": "nsmf-pdusession",
+                "versions": [{"apiVersionInUri": "v1", "apiFullVersion": "1.0.0"}],
+                "scheme": "http",
+                "nfServiceStatus": "REGISTERED"
+            }, {
+                "serviceInstanceId": "2",
+                "serviceName": "nsmf-event-exposure",
+                "versions": [{"apiVersionInUri": "v1", "apiFullVersion": "1.0.0"}],
+                "scheme": "http",
+                "nfServiceStatus": "REGISTERED"
+            }],
+            "smfInfo": {
+                "sNssaiSmfInfoList": [{
+                    "sNssai": {"sst": 1, "sd": "000001"},
+                    "dnnSmfInfoList": [{"dnn": "internet"}]
+                }],
+                "taiList": [{"plmnId": {"mcc": "001", "mnc": "01"}, "tac": "000100"}],
+                "taiRangeList": [{
+                    "plmnId": {"mcc": "001", "mnc": "01"},
+                    "tacRangeList": [{"start": "000010", "end": "00001F"}]
+                }]
+            }
+        })
+    }
+
+    fn profile(value: serde_json::Value) -> NfProfile {
+        serde_json::from_value(value).unwrap()
+    }
+
+    fn query() -> SearchQuery {
+        SearchQuery::new(NfType::SMF, NfType::AMF)
+    }
+
+    fn plmn(mcc: &str, mnc: &str) -> models::PlmnId {
+        models::PlmnId::new(mcc.to_string(), mnc.to_string())
+    }
+
+    fn snssai(sst: u8, sd: Option<&str>) -> models::Snssai {
+        let mut snssai = models::Snssai::new(sst);
+        snssai.sd = sd.map(str::to_string);
+        snssai
+    }
+
+    fn tai(tac: &str) -> models::Tai {
+        models::Tai::new(plmn("001", "01"), tac.to_string())
+    }
+
+    fn tac_range(
+        start: Option<&str>,
+        end: Option<&str>,
+        pattern: Option<&str>,
+    ) -> models::TacRange {
+        let mut range = models::TacRange::new();
+        range.start = start.map(str::to_string);
+        range.end = end.map(str::to_string);
+        range.pattern = pattern.map(str::to_string);
+        range
+    }
+
+    #[test]
+    fn nf_type_and_status() {
+        let smf = profile(smf_data());
+        assert!(query().matches(&smf));
+        assert!(!SearchQuery::new(NfType::UDM, NfType::AMF).matches(&smf));
+
+        let mut suspended = smf_data();
+        suspended["nfStatus"] = json!("SUSPENDED");
+        assert!(!query().matches(&profile(suspended)));
+    }
+
+    #[test]
+    fn service_names() {
+        let smf = profile(smf_data());
+        let mut query = query();
+        query.service_names = Some(vec![models::ServiceName::new("nsmf-pdusession".into())]);
+        assert!(query.matches(&smf));
+        let restricted = query.restrict(smf.clone());
+        assert_eq!(restricted.nf_services.map(|s| s.len()), Some(1));
+
+        query.service_names = Some(vec![models::ServiceName::new("nudm-sdm".into())]);
+        assert!(!query.matches(&smf));
+        query.service_names = Some(vec![]);
+        assert!(query.matches(&smf));
+    }
+
+    #[test]
+    fn snssais() {
+        let smf = profile(smf_data());
+        let mut query = query();
+        query.snssais = Some(vec![snssai(2, None), snssai(1, Some("000001"))]);
+        assert!(query.matches(&smf));
+        query.snssais = Some(vec![snssai(1, Some("000002"))]);
+        assert!(!query.matches(&smf));
+        query.snssais = Some(vec![snssai(1, None)]);
+        assert!(!query.matches(&smf));
+
+        let mut ranges = smf_data();
+        ranges["sNssais"] = json!([{"sst": 1, "sdRanges": [{"start": "000000", "end": "0000ff"}]}]);
+        query.snssais = Some(vec![snssai(1, Some("0000FF"))]);
+        assert!(query.matches(&profile(ranges.clone())));
+        query.snssais = Some(vec![snssai(1, Some("000100"))]);
+        assert!(!query.matches(&profile(ranges)));
+
+        let mut wildcard = smf_data();
+        wildcard["sNssais"] = json!([{"sst": 1, "wildcardSd": true}]);
+        assert!(query.matches(&profile(wildcard)));
+    }
+
+    #[test]
+    fn dnn() {
+        let smf = profile(smf_data());
+        let mut query = query();
+        query.dnn = Some("Internet".to_string());
+        assert!(query.matches(&smf));
+        query.dnn = Some("ims".to_string());
+        assert!(!query.matches(&smf));
+
+        let mut wildcard = smf_data();
+        wildcard["smfInfo"]["sNssaiSmfInfoList"][0]["dnnSmfInfoList"] = json!([{"dnn": "*"}]);
+        assert!(query.matches(&profile(wildcard)));
+
+        // DNN is no criterion for the NF types without DNN information
+        let mut udm = smf_data();
+        udm["nfType"] = json!("UDM");
+        let mut udm_query = SearchQuery::new(NfType::UDM, NfType::SMF);
+        udm_query.dnn = Some("ims".to_string());
+        assert!(udm_query.matches(&profile(udm)));
+    }
+
+    #[test]
+    fn target_plmn_list() {
+        let smf = profile(smf_data());
+        let mut query = query();
+        query.target_plmn_list = Some(vec![plmn("999", "70"), plmn("001", "01")]);
+        assert!(query.matches(&smf));
+        query.target_plmn_list = Some(vec![plmn("999", "70")]);
+        assert!(!query.matches(&smf));
+
+        let mut any_plmn = smf_data();
+        any_plmn.as_object_mut().unwrap().remove("plmnList");
+        assert!(query.matches(&profile(any_plmn)));
+    }
+
+    #[test]
+    fn tai_list_and_tac_range_boundaries() {
+        let smf = profile(smf_data());
+        let mut query = query();
+        for (tac, served) in [
+            ("000100", true),
+            ("00000f", false),
+            ("000010", true),
+            ("00001f", true),
+            ("000020", false),
+        ] {
+            query.tai = Some(tai(tac));
+            assert_eq!(query.matches(&smf), served, "TAC {}", tac);
+        }
+
+        query.tai = Some(models::Tai::new(plmn("999", "70"), "000010".to_string()));
+        assert!(!query.matches(&smf));
+
+        let mut any_tai = smf_data();
+        any_tai["smfInfo"] = json!({"sNssaiSmfInfoList": []});
+        assert!(query.matches(&profile(any_tai)));
+    }
+
+    #[test]
+    fn tac_ranges() {
+        let range = |tac_range_list| models::TaiRange::new(plmn("001", "01"), tac_range_list);
+        let pattern = range(vec![tac_range(None, None, Some("^000123$"))]);
+        assert!(tai_in_range(&tai("000123"), &pattern));
+        assert!(!tai_in_range(&tai("000124"), &pattern));
+        assert!(tai_in_range(
+            &tai("abcdef"),
+            &range(vec![tac_range(None, None, Some(".*"))])
+        ));
+
+        // An open or malformed range serves no TAC
+        assert!(!tai_in_range(
+            &tai("000010"),
+            &range(vec![tac_range(Some("000001"), None, None)])
+        ));
+        assert!(!tai_in_range(
+            &tai("00001g"),
+            &range(vec![tac_range(Some("000001"), Some("0000ff"), None)])
+        ));
+        assert!(hex_in_range("0000FF", Some("0000ff"), Some("0000ff")));
+    }
+
+    #[test]
+    fn limit_keeps_the_most_preferred() {
+        let profiles: Vec<NfProfile> = [(3, "a"), (1, "b"), (2, "c")]
+            .iter()
+            .map(|(priority, id)| {
+                let mut smf = smf_data();
+                smf["nfInstanceId"] = json!(format!("00000000-0000-0000-0000-00000000000{}", id));
+                smf["priority"] = json!(priority);
+                profile(smf)
+            })
+            .collect();
+        let registry = NfRegistry::from_profiles(profiles);
+
+        let mut query = query();
+        query.limit = Some(2);
+        let found: Vec<Option<u16>> = registry.search(&query).iter().map(|p| p.priority).collect();
+        assert_eq!(found, vec![Some(1), Some(2)]);
+        query.limit = Some(0);
+        assert_eq!(registry.search(&query).len(), 3);
+    }
+}
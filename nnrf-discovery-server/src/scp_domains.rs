@@ -0,0 +1,143 @@
+//! SCP domain topology served by the NRF.
+//!
+//! The topology is the `ScpDomainRoutingInformation` of the deployment: for
+//! each SCP domain, the SCP domains it is connected to. It is configured, not
+//! learnt, so every domain is local to this NRF. Replacing the topology is
+//! reported to the streams returned by [`ScpDomainTopology::watch`], from which
+//! the routing information change notifications are sent to the subscribers.
+
+use futures::channel::mpsc;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::models::{
+    ScpDomainRoutingInfoNotification, ScpDomainRoutingInfoSubscription, ScpDomainRoutingInformation,
+};
+
+/// Thread-safe handle to the SCP domain topology and to the subscriptions to
+/// its changes, keyed by subscription id.
+#[derive(Debug, Clone)]
+pub struct ScpDomainTopology {
+    routing_info: Arc<RwLock<ScpDomainRoutingInformation>>,
+    subscriptions: Arc<RwLock<HashMap<String, ScpDomainRoutingInfoSubscription>>>,
+    watchers: Arc<Mutex<Vec<mpsc::UnboundedSender<ScpDomainRoutingInformation>>>>,
+}
+
+impl Default for ScpDomainTopology {
+    fn default() -> Self {
+        ScpDomainTopology::new(ScpDomainRoutingInformation::new(HashMap::new()))
+    }
+}
+
+impl ScpDomainTopology {
+    pub fn new(routing_info: ScpDomainRoutingInformation) -> ScpDomainTopology {
+        ScpDomainTopology {
+            routing_info: Arc::new(RwLock::new(routing_info)),
+            subscriptions: Arc::default(),
+            watchers: Arc::default(),
+        }
+    }
+
+    /// Reads a JSON `ScpDomainRoutingInformation` document.
+    pub fn read<P: AsRef<Path>>(path: P) -> std::io::Result<ScpDomainRoutingInformation> {
+        let data = std::fs::read(path)?;
+        serde_json::from_slice(&data)
+            .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))
+    }
+
+    /// Loads the topology from a JSON `ScpDomainRoutingInformation` document.
+    pub fn load<P: AsRef<Path>>(path: P) -> std::io::Result<ScpDomainTopology> {
+        Ok(ScpDomainTopology::new(ScpDomainTopology::read(path)?))
+    }
+
+    pub fn routing_info(&self) -> ScpDomainRoutingInformation {
+        self.routing_info
+            .read()
+            .expect("topology lock poisoned")
+            .clone()
+    }
+
+    /// Replaces the topology. Returns `false`, and notifies nobody, when it
+    /// did not change.
+    pub fn set(&self, routing_info: ScpDomainRoutingInformation) -> bool {
+        {
+            let mut current = self.routing_info.write().expect("topology lock poisoned");
+            if *current == routing_info {
+                return false;
+            }
+            *current = routing_info.clone();
+        }
+
+        self.watchers
+            .lock()
+            .expect("watchers lock poisoned")
+            .retain(|w| w.unbounded_send(routing_info.clone()).is_ok());
+        true
+    }
+
+    /// Stream of the topologies set from now on.
+    pub fn watch(&self) -> mpsc::UnboundedReceiver<ScpDomainRoutingInformation> {
+        let (tx, rx) = mpsc::unbounded();
+        self.watchers
+            .lock()
+            .expect("watchers lock poisoned")
+            .push(tx);
+        rx
+    }
+
+    /// Stores a subscription under a new id and returns the id.
+    pub fn subscribe(&self, subscription: ScpDomainRoutingInfoSubscription) -> String {
+        let subscription_id = uuid::Uuid::new_v4().simple().to_string();
+        self.subscriptions
+            .write()
+            .expect("subscription lock poisoned")
+            .insert(subscription_id.clone(), subscription);
+        subscription_id
+    }
+
+    pub fn unsubscribe(&self, subscription_id: &str) -> Option<ScpDomainRoutingInfoSubscription> {
+        self.subscriptions
+            .write()
+            .expect("subscription lock poisoned")
+            .remove(subscription_id)
+    }
+
+    /// Removes and returns the subscriptions whose validity time has passed.
+    pub fn expire(&self) -> Vec<ScpDomainRoutingInfoSubscription> {
+        let now = chrono::Utc::now();
+        let mut expired = Vec::new();
+        self.subscriptions
+            .write()
+            .expect("subscription lock poisoned")
+            .retain(|_, subscription| match subscription.validity_time {
+                Some(validity_time) if validity_time <= now => {
+                    expired.push(subscription.clone());
+                    false
+                }
+                _ => true,
+            });
+        expired
+    }
+
+    /// Builds the notifications of a new topology, as pairs of callback URI
+    /// and payload.
+    pub fn notifications(
+        &self,
+        routing_info: &ScpDomainRoutingInformation,
+    ) -> Vec<(String, ScpDomainRoutingInfoNotification)> {
+        let now = chrono::Utc::now();
+
+        self.subscriptions
+            .read()
+            .expect("subscription lock poisoned")
+            .values()
+            .filter(|s| s.validity_time.map(|t| t > now).unwrap_or(true))
+            .map(|s| {
+                let mut notification = ScpDomainRoutingInfoNotification::new(routing_info.clone());
+                notification.local_ind = Some(s.local_ind.unwrap_or(false));
+                (s.callback_uri.clone(), notification)
+            })
+            .collect()
+    }
+}
@@ -0,0 +1,161 @@
+//! Discovery results kept by the NRF for the stored and complete search
+//! retrievals.
+//!
+//! Every answer to `search_nf_instances` is stored under a `searchId` for the
+//! validity period of the result. The stored search is the list that was
+//! returned to the consumer, the complete search is the list before the
+//! `limit` of the query was applied. The store holds at most a given number
+//! of searches: the expired ones are dropped first, then those closest to
+//! expiring.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+
+use crate::models::NfProfile;
+
+#[derive(Debug, Clone)]
+struct StoredSearch {
+    complete: Vec<NfProfile>,
+    returned: usize,
+    expires: Instant,
+}
+
+/// Number of searches kept by default.
+pub const DEFAULT_CAPACITY: usize = 10_000;
+
+/// Thread-safe handle to the stored search results, keyed by search id.
+#[derive(Debug, Clone)]
+pub struct SearchStore {
+    searches: Arc<RwLock<HashMap<String, StoredSearch>>>,
+    capacity: usize,
+}
+
+impl Default for SearchStore {
+    fn default() -> SearchStore {
+        SearchStore::with_capacity(DEFAULT_CAPACITY)
+    }
+}
+
+impl SearchStore {
+    pub fn new() -> SearchStore {
+        SearchStore::default()
+    }
+
+    /// A store keeping at most `capacity` searches.
+    pub fn with_capacity(capacity: usize) -> SearchStore {
+        SearchStore {
+            searches: Arc::default(),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Stores the complete result of a search, of which the first `returned`
+    /// profiles were sent to the consumer, and returns its search id.
+    pub fn store(&self, complete: Vec<NfProfile>, returned: usize, validity: Duration) -> String {
+        let search_id = uuid::Uuid::new_v4().simple().to_string();
+        let now = Instant::now();
+        let search = StoredSearch {
+            returned: returned.min(complete.len()),
+            complete,
+            expires: now + validity,
+        };
+
+        let mut searches = self.searches.write().expect("search lock poisoned");
+        if searches.len() >= self.capacity {
+            searches.retain(|_, s| s.expires > now);
+        }
+        while searches.len() >= self.capacity {
+            let first = searches
+                .iter()
+                .min_by_key(|(_, s)| s.expires)
+                .map(|(id, _)| id.clone())
+                .expect("a full store has searches");
+            searches.remove(&first);
+        }
+        searches.insert(search_id.clone(), search);
+        search_id
+    }
+
+    /// The profiles returned by a search, with the time left before it expires.
+    pub fn stored(&self, search_id: &str) -> Option<(Vec<NfProfile>, Duration)> {
+        self.lookup(search_id, |s| s.complete[..s.returned].to_vec())
+    }
+
+    /// All the profiles matching a search, with the time left before it expires.
+    pub fn complete(&self, search_id: &str) -> Option<(Vec<NfProfile>, Duration)> {
+        self.lookup(search_id, |s| s.complete.clone())
+    }
+
+    pub fn len(&self) -> usize {
+        self.searches.read().expect("search lock poisoned").len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Drops the searches whose validity period has passed and returns how
+    /// many were dropped.
+    pub fn expire(&self) -> usize {
+        let now = Instant::now();
+        let mut searches = self.searches.write().expect("search lock poisoned");
+        let before = searches.len();
+        searches.retain(|_, s| s.expires > now);
+        before - searches.len()
+    }
+
+    fn lookup<F>(&self, search_id: &str, profiles: F) -> Option<(Vec<NfProfile>, Duration)>
+    where
+        F: FnOnce(&StoredSearch) -> Vec<NfProfile>,
+    {
+        let now = Instant::now();
+        self.searches
+            .read()
+            .expect("search lock poisoned")
+            .get(search_id)
+            .filter(|s| s.expires > now)
+            .map(|s| (profiles(s), s.expires - now))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn expired_searches_are_not_served() {
+        let searches = SearchStore::new();
+        let id = searches.store(Vec::new(), 0, Duration::from_secs(0));
+        assert!(searches.stored(&id).is_none());
+        assert!(searches.complete(&id).is_none());
+        assert_eq!(searches.expire(), 1);
+        assert!(searches.is_empty());
+    }
+
+    #[test]
+    fn full_store_drops_the_search_closest_to_expiring() {
+        let searches = SearchStore::with_capacity(2);
+        let first = searches.store(Vec::new(), 0, Duration::from_secs(60));
+        let second = searches.store(Vec::new(), 0, Duration::from_secs(30));
+        let third = searches.store(Vec::new(), 0, Duration::from_secs(90));
+
+        assert_eq!(searches.len(), 2);
+        assert!(searches.stored(&first).is_some());
+        assert!(searches.stored(&second).is_none());
+        assert!(searches.stored(&third).is_some());
+    }
+
+    #[test]
+    fn full_store_drops_expired_searches_first() {
+        let searches = SearchStore::with_capacity(2);
+        let expired = searches.store(Vec::new(), 0, Duration::from_secs(0));
+        let live = searches.store(Vec::new(), 0, Duration::from_secs(60));
+        let new = searches.store(Vec::new(), 0, Duration::from_secs(30));
+
+        assert_eq!(searches.len(), 2);
+        assert!(searches.complete(&expired).is_none());
+        assert!(searches.complete(&live).is_some());
+        assert!(searches.complete(&new).is_some());
+    }
+}
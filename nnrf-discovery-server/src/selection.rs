@@ -0,0 +1,372 @@
+//! Selection of a service endpoint among the NF instances of a discovery
+//! result.
+//!
+//! Only the registered instances offering the service are candidates, and
+//! only those of the most preferred priority class (lowest `priority`) are
+//! considered. Within that class the [`SelectionPolicy`] decides:
+//!
+//! - `Weighted` picks an instance at random, weighted by its `capacity`;
+//! - `LoadAware` does the same with the capacity scaled by the free part of
+//!   the `load` the instance reported;
+//! - `RoundRobin` cycles over the instances.
+//!
+//! The attributes of the service take precedence over those of the NF
+//! profile, as in TS 29.510.
+
+use rand::Rng;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crate::models::{NfProfile, NfService, NfStatusAnyOf};
+
+/// Capacity of an instance that does not advertise one.
+const DEFAULT_CAPACITY: u16 = 100;
+
+/// How an instance is chosen among those of the most preferred priority.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SelectionPolicy {
+    #[default]
+    Weighted,
+    LoadAware,
+    RoundRobin,
+}
+
+impl std::fmt::Display for SelectionPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            SelectionPolicy::Weighted => write!(f, "weighted"),
+            SelectionPolicy::LoadAware => write!(f, "load-aware"),
+            SelectionPolicy::RoundRobin => write!(f, "round-robin"),
+        }
+    }
+}
+
+impl std::str::FromStr for SelectionPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "weighted" => Ok(SelectionPolicy::Weighted),
+            "load-aware" => Ok(SelectionPolicy::LoadAware),
+            "round-robin" => Ok(SelectionPolicy::RoundRobin),
+            _ => Err(format!("unknown selection policy {}", s)),
+        }
+    }
+}
+
+/// The service instance chosen, and where to reach it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ServiceEndpoint {
+    pub nf_instance_id: uuid::Uuid,
+    pub service_instance_id: String,
+    /// `{scheme}://{host}:{port}`, without the API name and version.
+    pub uri: String,
+}
+
+impl ServiceEndpoint {
+    /// Endpoint of a service, from its first IP endpoint or else from the
+    /// addresses of the NF. `None` if neither gives a host.
+    pub fn new(profile: &NfProfile, service: &NfService) -> Option<ServiceEndpoint> {
+        let endpoint = service.ip_end_points.iter().flatten().next();
+        let host = endpoint
+            .and_then(|e| e.ipv4_address.clone())
+            .or_else(|| service.fqdn.clone())
+            .or_else(|| {
+                profile
+                    .ipv4_addresses
+                    .iter()
+                    .flatten()
+                    .next()
+                    .map(|a| a.to_string())
+            })
+            .or_else(|| profile.fqdn.clone())?;
+        let scheme = service.scheme.to_string();
+        let port = endpoint
+            .and_then(|e| e.port)
+            .unwrap_or(if scheme == "https" { 443 } else { 80 });
+
+        Some(ServiceEndpoint {
+            nf_instance_id: profile.nf_instance_id,
+            service_instance_id: service.service_instance_id.clone(),
+            uri: format!("{}://{}:{}", scheme, host, port),
+        })
+    }
+}
+
+struct Candidate<'a> {
+    profile: &'a NfProfile,
+    service: &'a NfService,
+}
+
+impl Candidate<'_> {
+    fn priority(&self) -> u16 {
+        self.service.priority.or(self.profile.priority).unwrap_or(0)
+    }
+
+    fn capacity(&self) -> u64 {
+        self.service
+            .capacity
+            .or(self.profile.capacity)
+            .unwrap_or(DEFAULT_CAPACITY)
+            .into()
+    }
+
+    /// Load in percent of the capacity.
+    fn load(&self) -> u64 {
+        u64::from(self.service.load.or(self.profile.load).unwrap_or(0)).min(100)
+    }
+
+    fn weight(&self, policy: SelectionPolicy) -> u64 {
+        match policy {
+            SelectionPolicy::LoadAware => self.capacity() * (100 - self.load()),
+            _ => self.capacity(),
+        }
+    }
+}
+
+/// Picks service endpoints out of discovery results. The clones of a
+/// selector share its round-robin position.
+#[derive(Debug, Clone, Default)]
+pub struct NfSelector {
+    policy: SelectionPolicy,
+    next: Arc<AtomicUsize>,
+}
+
+impl NfSelector {
+    pub fn new(policy: SelectionPolicy) -> NfSelector {
+        NfSelector {
+            policy,
+            next: Arc::default(),
+        }
+    }
+
+    pub fn policy(&self) -> SelectionPolicy {
+        self.policy
+    }
+
+    /// Chooses an instance of the service among the profiles, `None` if no
+    /// registered instance offers it.
+    pub fn select(&self, profiles: &[NfProfile], service_name: &str) -> Option<ServiceEndpoint> {
+        let registered = NfStatusAnyOf::Registered.to_string();
+        let candidates: Vec<Candidate> = profiles
+            .iter()
+            .filter(|p| p.nf_status.to_string() == registered)
+            .flat_map(|profile| {
+                services(profile)
+                    .filter(|s| s.service_name.to_string() == service_name)
+                    .filter(|s| s.nf_service_status.to_string() == registered)
+                    .map(move |service| Candidate { profile, service })
+            })
+            .collect();
+
+        let best = candidates.iter().map(Candidate::priority).min()?;
+        let mut candidates: Vec<Candidate> = candidates
+            .into_iter()
+            .filter(|c| c.priority() == best)
+            .collect();
+        // A stable order, for the round robin to visit every instance
+        candidates.sort_by(|a, b| {
+            (a.profile.nf_instance_id, &a.service.service_instance_id)
+                .cmp(&(b.profile.nf_instance_id, &b.service.service_instance_id))
+        });
+
+        let chosen = match self.policy {
+            SelectionPolicy::RoundRobin => {
+                &candidates[self.next.fetch_add(1, Ordering::Relaxed) % candidates.len()]
+            }
+            policy => weighted(&candidates, policy, &mut rand::thread_rng()),
+        };
+
+        ServiceEndpoint::new(chosen.profile, chosen.service)
+    }
+}
+
+/// Random choice weighted by the policy; uniform if every weight is zero.
+fn weighted<'a, 'b, R: Rng>(
+    candidates: &'b [Candidate<'a>],
+    policy: SelectionPolicy,
+    rng: &mut R,
+) -> &'b Candidate<'a> {
+    let total: u64 = candidates.iter().map(|c| c.weight(policy)).sum();
+    if total == 0 {
+        return &candidates[rng.gen_range(0..candidates.len())];
+    }
+
+    let mut draw = rng.gen_range(0..total);
+    for candidate in candidates {
+        let weight = candidate.weight(policy);
+        if draw < weight {
+            return candidate;
+        }
+        draw -= weight;
+    }
+    unreachable!("the draw is below the total weight")
+}
+
+fn services(profile: &NfProfile) -> impl Iterator<Item = &NfService> {
+    profile
+        .nf_services
+        .iter()
+        .flat_map(|s| s.iter())
+        .chain(profile.nf_service_list.iter().flat_map(|s| s.values()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::NfStatus;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    const DRAWS: usize = 10_000;
+
+    /// A registered SMF offering `nsmf-pdusession` at 10.0.0.`host`.
+    fn smf(host: u8, priority: u16, capacity: Option<u16>, load: Option<u16>) -> NfProfile {
+        serde_json::from_value(serde_json::json!({
+            "nfInstanceId": uuid::Uuid::from_u128(host.into()),
+            "nfType": "SMF",
+            "nfStatus": "REGISTERED",
+            "ipv4Addresses": [format!("10.0.0.{}", host)],
+            "priority": priority,
+            "capacity": capacity,
+            "load": load,
+            "nfServices": [{
+                "serviceInstanceId": host.to_string(),
+                "serviceName": "nsmf-pdusession",
+                "versions": [{"apiVersionInUri": "v1", "apiFullVersion": "1.0.0"}],
+                "scheme": "http",
+                "nfServiceStatus": "REGISTERED"
+            }]
+        }))
+        .unwrap()
+    }
+
+    fn candidates(profiles: &[NfProfile]) -> Vec<Candidate<'_>> {
+        profiles
+            .iter()
+            .map(|profile| Candidate {
+                profile,
+                service: &profile.nf_services.as_ref().unwrap()[0],
+            })
+            .collect()
+    }
+
+    /// How many times each candidate is drawn out of [`DRAWS`].
+    fn draws(profiles: &[NfProfile], policy: SelectionPolicy) -> Vec<usize> {
+        let candidates = candidates(profiles);
+        let mut rng = StdRng::seed_from_u64(5);
+        let mut counts = vec![0; candidates.len()];
+        for _ in 0..DRAWS {
+            let chosen = weighted(&candidates, policy, &mut rng);
+            let index = candidates
+                .iter()
+                .position(|c| std::ptr::eq(c, chosen))
+                .unwrap();
+            counts[index] += 1;
+        }
+        counts
+    }
+
+    /// Whether a count is within 5% of the expected share of the draws.
+    fn near(count: usize, share: f64) -> bool {
+        (count as f64 / DRAWS as f64 - share).abs() < 0.05
+    }
+
+    #[test]
+    fn weighted_draws_follow_the_capacities() {
+        let profiles = [smf(1, 0, Some(100), None), smf(2, 0, Some(300), None)];
+        let counts = draws(&profiles, SelectionPolicy::Weighted);
+        assert!(near(counts[0], 0.25), "{:?}", counts);
+        assert!(near(counts[1], 0.75), "{:?}", counts);
+
+        // The load does not matter, nor does a missing capacity
+        let profiles = [smf(1, 0, None, Some(90)), smf(2, 0, Some(100), Some(0))];
+        let counts = draws(&profiles, SelectionPolicy::Weighted);
+        assert!(near(counts[0], 0.5), "{:?}", counts);
+    }
+
+    #[test]
+    fn load_aware_draws_follow_the_free_capacities() {
+        let profiles = [
+            smf(1, 0, Some(100), Some(50)),
+            smf(2, 0, Some(100), Some(0)),
+            smf(3, 0, Some(100), Some(100)),
+            smf(4, 0, Some(0), Some(0)),
+        ];
+        let counts = draws(&profiles, SelectionPolicy::LoadAware);
+        assert!(near(counts[0], 1.0 / 3.0), "{:?}", counts);
+        assert!(near(counts[1], 2.0 / 3.0), "{:?}", counts);
+        assert_eq!(counts[2], 0);
+        assert_eq!(counts[3], 0);
+    }
+
+    #[test]
+    fn zero_weights_give_a_uniform_draw() {
+        let profiles = [smf(1, 0, Some(100), Some(100)), smf(2, 0, Some(0), Some(0))];
+        let counts = draws(&profiles, SelectionPolicy::LoadAware);
+        assert!(near(counts[0], 0.5), "{:?}", counts);
+        assert!(near(counts[1], 0.5), "{:?}", counts);
+
+        let counts = draws(&profiles[1..], SelectionPolicy::Weighted);
+        assert_eq!(counts, vec![DRAWS]);
+    }
+
+    #[test]
+    fn round_robin_cycles_in_a_stable_order() {
+        let profiles = [
+            smf(3, 0, None, None),
+            smf(1, 0, None, None),
+            smf(2, 0, None, None),
+        ];
+        let selector = NfSelector::new(SelectionPolicy::RoundRobin);
+        let uris: Vec<String> = (0..4)
+            .map(|_| selector.select(&profiles, "nsmf-pdusession").unwrap().uri)
+            .collect();
+        assert_eq!(
+            uris,
+            vec![
+                "http://10.0.0.1:80",
+                "http://10.0.0.2:80",
+                "http://10.0.0.3:80",
+                "http://10.0.0.1:80"
+            ]
+        );
+
+        // The clones share the position
+        let clone = selector.clone();
+        assert_eq!(
+            clone.select(&profiles, "nsmf-pdusession").unwrap().uri,
+            "http://10.0.0.2:80"
+        );
+    }
+
+    #[test]
+    fn only_the_most_preferred_priority_is_selected() {
+        let mut suspended = smf(1, 0, None, None);
+        suspended.nf_status = NfStatus::new(NfStatusAnyOf::Suspended.to_string());
+        let profiles = [
+            suspended,
+            smf(2, 2, Some(1000), None),
+            smf(3, 1, Some(1), None),
+            smf(4, 1, Some(1), None),
+        ];
+        for policy in [
+            SelectionPolicy::Weighted,
+            SelectionPolicy::LoadAware,
+            SelectionPolicy::RoundRobin,
+        ] {
+            let selector = NfSelector::new(policy);
+            for _ in 0..100 {
+                let chosen = selector.select(&profiles, "nsmf-pdusession").unwrap();
+                assert!(
+                    chosen.uri == "http://10.0.0.3:80" || chosen.uri == "http://10.0.0.4:80",
+                    "{} chose {}",
+                    policy,
+                    chosen.uri
+                );
+            }
+        }
+
+        assert_eq!(NfSelector::default().select(&profiles, "nudm-sdm"), None);
+    }
+}
@@ -396,6 +396,17 @@ where
                                     .expect("impossible to fail to serialize");
                                 *response.body_mut() = Body::from(body);
                             }
+                            RetrieveCompleteSearchResponse::NotFound(body) => {
+                                *response.status_mut() = StatusCode::from_u16(404)
+                                    .expect("Unable to turn 404 into a StatusCode");
+                                response.headers_mut().insert(
+                                                        CONTENT_TYPE,
+                                                        HeaderValue::from_str("application/problem+json")
+                                                            .expect("Unable to create Content-Type header for RETRIEVE_COMPLETE_SEARCH_NOT_FOUND"));
+                                let body = serde_json::to_string(&body)
+                                    .expect("impossible to fail to serialize");
+                                *response.body_mut() = Body::from(body);
+                            }
                         },
                         Err(_) => {
                             // Application code returned an error. This should not happen, as the implementation should
@@ -4393,6 +4404,17 @@ where
                                     .expect("impossible to fail to serialize");
                                 *response.body_mut() = Body::from(body);
                             }
+                            RetrieveStoredSearchResponse::NotFound(body) => {
+                                *response.status_mut() = StatusCode::from_u16(404)
+                                    .expect("Unable to turn 404 into a StatusCode");
+                                response.headers_mut().insert(
+                                                        CONTENT_TYPE,
+                                                        HeaderValue::from_str("application/problem+json")
+                                                            .expect("Unable to create Content-Type header for RETRIEVE_STORED_SEARCH_NOT_FOUND"));
+                                let body = serde_json::to_string(&body)
+                                    .expect("impossible to fail to serialize");
+                                *response.body_mut() = Body::from(body);
+                            }
                         },
                         Err(_) => {
                             // Application code returned an error. This should not happen, as the implementation should
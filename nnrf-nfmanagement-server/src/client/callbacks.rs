@@ -0,0 +1,307 @@
+use futures::{future, future::BoxFuture, future::FutureExt, stream, stream::TryStreamExt, Stream};
+use hyper::header::{HeaderName, HeaderValue, CONTENT_TYPE};
+use hyper::{Body, HeaderMap, Request, Response, StatusCode};
+use log::warn;
+#[allow(unused_imports)]
+use std::convert::{TryFrom, TryInto};
+use std::error::Error;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::task::{Context, Poll};
+pub use swagger::auth::Authorization;
+use swagger::auth::Scopes;
+use swagger::{ApiError, BodyExt, Has, RequestParser, XSpanIdString};
+use url::form_urlencoded;
+
+use crate::header;
+#[allow(unused_imports)]
+use crate::models;
+
+pub use crate::context;
+
+type ServiceFuture = BoxFuture<'static, Result<Response<Body>, crate::ServiceError>>;
+
+use crate::CallbackApi as Api;
+use crate::OnNfStatusEventPostResponse;
+
+mod paths {
+    use lazy_static::lazy_static;
+
+    // The notification URI is chosen by the subscriber, so any path is accepted.
+    lazy_static! {
+        pub static ref GLOBAL_REGEX_SET: regex::RegexSet =
+            regex::RegexSet::new(vec![r"^/(?P<request_body_nf_status_notification_uri>.*)$"])
+                .expect("Unable to create global regex set");
+    }
+    pub(crate) static ID_REQUEST_BODY_NFSTATUSNOTIFICATIONURI: usize = 0;
+    lazy_static! {
+        pub static ref REGEX_REQUEST_BODY_NFSTATUSNOTIFICATIONURI: regex::Regex =
+            #[allow(clippy::invalid_regex)]
+            regex::Regex::new(r"^/(?P<request_body_nf_status_notification_uri>.*)$")
+                .expect("Unable to create regex for REQUEST_BODY_NFSTATUSNOTIFICATIONURI");
+    }
+}
+
+pub struct MakeService<T, C>
+where
+    T: Api<C> + Clone + Send + 'static,
+    C: Has<XSpanIdString> + Has<Option<Authorization>> + Send + Sync + 'static,
+{
+    api_impl: T,
+    marker: PhantomData<C>,
+}
+
+impl<T, C> MakeService<T, C>
+where
+    T: Api<C> + Clone + Send + 'static,
+    C: Has<XSpanIdString> + Has<Option<Authorization>> + Send + Sync + 'static,
+{
+    pub fn new(api_impl: T) -> Self {
+        MakeService {
+            api_impl,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, C, Target> hyper::service::Service<Target> for MakeService<T, C>
+where
+    T: Api<C> + Clone + Send + 'static,
+    C: Has<XSpanIdString> + Has<Option<Authorization>> + Send + Sync + 'static,
+{
+    type Response = Service<T, C>;
+    type Error = crate::ServiceError;
+    type Future = future::Ready<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, target: Target) -> Self::Future {
+        futures::future::ok(Service::new(self.api_impl.clone()))
+    }
+}
+
+fn method_not_allowed() -> Result<Response<Body>, crate::ServiceError> {
+    Ok(Response::builder()
+        .status(StatusCode::METHOD_NOT_ALLOWED)
+        .body(Body::empty())
+        .expect("Unable to create Method Not Allowed response"))
+}
+
+fn bad_request(message: String) -> Result<Response<Body>, crate::ServiceError> {
+    Ok(Response::builder()
+        .status(StatusCode::BAD_REQUEST)
+        .body(Body::from(message))
+        .expect("Unable to create Bad Request response"))
+}
+
+fn set_problem(response: &mut Response<Body>, status: u16, body: &models::ProblemDetails) {
+    *response.status_mut() =
+        StatusCode::from_u16(status).expect("Unable to turn into a StatusCode");
+    response.headers_mut().insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static("application/problem+json"),
+    );
+    let body = serde_json::to_string(body).expect("impossible to fail to serialize");
+    *response.body_mut() = Body::from(body);
+}
+
+pub struct Service<T, C>
+where
+    T: Api<C> + Clone + Send + 'static,
+    C: Has<XSpanIdString> + Has<Option<Authorization>> + Send + Sync + 'static,
+{
+    api_impl: T,
+    marker: PhantomData<C>,
+}
+
+impl<T, C> Service<T, C>
+where
+    T: Api<C> + Clone + Send + 'static,
+    C: Has<XSpanIdString> + Has<Option<Authorization>> + Send + Sync + 'static,
+{
+    pub fn new(api_impl: T) -> Self {
+        Service {
+            api_impl,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, C> Clone for Service<T, C>
+where
+    T: Api<C> + Clone + Send + 'static,
+    C: Has<XSpanIdString> + Has<Option<Authorization>> + Send + Sync + 'static,
+{
+    fn clone(&self) -> Self {
+        Service {
+            api_impl: self.api_impl.clone(),
+            marker: self.marker,
+        }
+    }
+}
+
+impl<T, C> hyper::service::Service<(Request<Body>, C)> for Service<T, C>
+where
+    T: Api<C> + Clone + Send + Sync + 'static,
+    C: Has<XSpanIdString> + Has<Option<Authorization>> + Send + Sync + 'static,
+{
+    type Response = Response<Body>;
+    type Error = crate::ServiceError;
+    type Future = ServiceFuture;
+
+    fn poll_ready(&mut self, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        self.api_impl.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: (Request<Body>, C)) -> Self::Future {
+        async fn run<T, C>(
+            mut api_impl: T,
+            req: (Request<Body>, C),
+        ) -> Result<Response<Body>, crate::ServiceError>
+        where
+            T: Api<C> + Clone + Send + 'static,
+            C: Has<XSpanIdString> + Has<Option<Authorization>> + Send + Sync + 'static,
+        {
+            let (request, context) = req;
+            let (parts, body) = request.into_parts();
+            let (method, uri, headers) = (parts.method, parts.uri, parts.headers);
+            let path = paths::GLOBAL_REGEX_SET.matches(uri.path());
+
+            match method {
+                // OnNFStatusEventPost - POST /{$request.body#/nfStatusNotificationUri}
+                hyper::Method::POST
+                    if path.matched(paths::ID_REQUEST_BODY_NFSTATUSNOTIFICATIONURI) =>
+                {
+                    // Path parameters
+                    let path: &str = uri.path();
+                    let path_params = paths::REGEX_REQUEST_BODY_NFSTATUSNOTIFICATIONURI
+                        .captures(path)
+                        .unwrap_or_else(|| {
+                            panic!(
+                                "Path {} matched RE REQUEST_BODY_NFSTATUSNOTIFICATIONURI in set but failed match against \"{}\"",
+                                path,
+                                paths::REGEX_REQUEST_BODY_NFSTATUSNOTIFICATIONURI.as_str()
+                            )
+                        });
+
+                    let callback_request_body_nf_status_notification_uri =
+                        path_params["request_body_nf_status_notification_uri"].to_string();
+
+                    // Body parameters. Produce warning header and logs for any unused fields.
+                    let body = match body.into_raw().await {
+                        Ok(body) => body,
+                        Err(e) => {
+                            return bad_request(format!(
+                                "Couldn't read body parameter NotificationData: {}",
+                                e
+                            ))
+                        }
+                    };
+                    let mut unused_elements = Vec::new();
+                    let deserializer = &mut serde_json::Deserializer::from_slice(&*body);
+                    let param_notification_data: models::NotificationData =
+                        match serde_ignored::deserialize(deserializer, |path| {
+                            warn!("Ignoring unknown field in body: {}", path);
+                            unused_elements.push(path.to_string());
+                        }) {
+                            Ok(param_notification_data) => param_notification_data,
+                            Err(e) => {
+                                return bad_request(format!(
+                                    "Couldn't parse body parameter NotificationData - doesn't match schema: {}",
+                                    e
+                                ))
+                            }
+                        };
+
+                    let result = api_impl
+                        .on_nf_status_event_post(
+                            callback_request_body_nf_status_notification_uri,
+                            param_notification_data,
+                            &context,
+                        )
+                        .await;
+                    let mut response = Response::new(Body::empty());
+                    response.headers_mut().insert(
+                        HeaderName::from_static("x-span-id"),
+                        HeaderValue::from_str(
+                            (&context as &dyn Has<XSpanIdString>)
+                                .get()
+                                .0
+                                .clone()
+                                .as_str(),
+                        )
+                        .expect("Unable to create X-Span-ID header value"),
+                    );
+
+                    if !unused_elements.is_empty() {
+                        response.headers_mut().insert(
+                            HeaderName::from_static("warning"),
+                            HeaderValue::from_str(
+                                format!("Ignoring unknown fields in body: {:?}", unused_elements)
+                                    .as_str(),
+                            )
+                            .expect("Unable to create Warning header value"),
+                        );
+                    }
+
+                    match result {
+                        Ok(rsp) => match rsp {
+                            OnNfStatusEventPostResponse::ExpectedResponseToASuccessfulCallbackProcessing => {
+                                *response.status_mut() = StatusCode::NO_CONTENT;
+                            }
+                            OnNfStatusEventPostResponse::BadRequest(body) => {
+                                set_problem(&mut response, 400, &body)
+                            }
+                            OnNfStatusEventPostResponse::NotFound(body) => {
+                                set_problem(&mut response, 404, &body)
+                            }
+                            OnNfStatusEventPostResponse::InternalServerError(body) => {
+                                set_problem(&mut response, 500, &body)
+                            }
+                            OnNfStatusEventPostResponse::ServiceUnavailable(body) => {
+                                set_problem(&mut response, 503, &body)
+                            }
+                            OnNfStatusEventPostResponse::GenericError => {
+                                *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+                            }
+                        },
+                        Err(_) => {
+                            // Application code returned an error. This should not happen, as the implementation should
+                            // return a valid response.
+                            *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+                            *response.body_mut() = Body::from("An internal error occurred");
+                        }
+                    }
+
+                    Ok(response)
+                }
+
+                _ if path.matched(paths::ID_REQUEST_BODY_NFSTATUSNOTIFICATIONURI) => {
+                    method_not_allowed()
+                }
+                _ => Ok(Response::builder()
+                    .status(StatusCode::NOT_FOUND)
+                    .body(Body::empty())
+                    .expect("Unable to create Not Found response")),
+            }
+        }
+        Box::pin(run(self.api_impl.clone(), req))
+    }
+}
+
+/// Request parser for `Api`.
+pub struct ApiRequestParser;
+impl<T> RequestParser<T> for ApiRequestParser {
+    fn parse_operation_id(request: &Request<T>) -> Option<&'static str> {
+        let path = paths::GLOBAL_REGEX_SET.matches(request.uri().path());
+        match *request.method() {
+            // OnNFStatusEventPost - POST /{$request.body#/nfStatusNotificationUri}
+            hyper::Method::POST if path.matched(paths::ID_REQUEST_BODY_NFSTATUSNOTIFICATIONURI) => {
+                Some("OnNFStatusEventPost")
+            }
+            _ => None,
+        }
+    }
+}
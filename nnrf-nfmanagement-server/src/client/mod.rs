@@ -0,0 +1,915 @@
+use async_trait::async_trait;
+use futures::{
+    future, future::BoxFuture, future::FutureExt, future::TryFutureExt, stream, stream::StreamExt,
+    Stream,
+};
+use hyper::header::{HeaderName, HeaderValue, CONTENT_TYPE};
+use hyper::{service::Service, Body, Request, Response, Uri};
+use percent_encoding::{utf8_percent_encode, AsciiSet};
+use std::borrow::Cow;
+use std::convert::TryInto;
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::io::{ErrorKind, Read};
+use std::marker::PhantomData;
+use std::path::Path;
+use std::str;
+use std::str::FromStr;
+use std::string::ToString;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use swagger::{ApiError, AuthData, BodyExt, Connector, DropContextService, Has, XSpanIdString};
+use url::form_urlencoded;
+
+use crate::header;
+use crate::models;
+
+/// https://url.spec.whatwg.org/#fragment-percent-encode-set
+#[allow(dead_code)]
+const FRAGMENT_ENCODE_SET: &AsciiSet = &percent_encoding::CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'<')
+    .add(b'>')
+    .add(b'`');
+
+/// This encode set is used for object IDs
+///
+/// Aside from the special characters defined in the `PATH_SEGMENT_ENCODE_SET`,
+/// the vertical bar (|) is encoded.
+#[allow(dead_code)]
+const ID_ENCODE_SET: &AsciiSet = &FRAGMENT_ENCODE_SET.add(b'|');
+
+use crate::{
+    Api, CreateSubscriptionResponse, DeregisterNfInstanceResponse, GetNfInstanceResponse,
+    RegisterNfInstanceResponse, RemoveSubscriptionResponse, UpdateNfInstanceResponse,
+};
+
+pub mod callbacks;
+
+/// Convert input into a base path, e.g. "http://example:123". Also checks the scheme as it goes.
+fn into_base_path(
+    input: impl TryInto<Uri, Error = hyper::http::uri::InvalidUri>,
+    correct_scheme: Option<&'static str>,
+) -> Result<String, ClientInitError> {
+    // First convert to Uri, since a base path is a subset of Uri.
+    let uri = input.try_into()?;
+
+    let scheme = uri.scheme_str().ok_or(ClientInitError::InvalidScheme)?;
+
+    // Check the scheme if necessary
+    if let Some(correct_scheme) = correct_scheme {
+        if scheme != correct_scheme {
+            return Err(ClientInitError::InvalidScheme);
+        }
+    }
+
+    let host = uri.host().ok_or(ClientInitError::MissingHost)?;
+    let port = uri
+        .port_u16()
+        .map(|x| format!(":{}", x))
+        .unwrap_or_default();
+    Ok(format!(
+        "{}://{}{}{}",
+        scheme,
+        host,
+        port,
+        uri.path().trim_end_matches('/')
+    ))
+}
+
+/// A client that implements the API by making HTTP calls out to a server.
+pub struct Client<S, C>
+where
+    S: Service<(Request<Body>, C), Response = Response<Body>> + Clone + Sync + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: Into<crate::ServiceError> + fmt::Display,
+    C: Clone + Send + Sync + 'static,
+{
+    /// Inner service
+    client_service: S,
+
+    /// Base path of the API
+    base_path: String,
+
+    /// Marker
+    marker: PhantomData<fn(C)>,
+}
+
+impl<S, C> fmt::Debug for Client<S, C>
+where
+    S: Service<(Request<Body>, C), Response = Response<Body>> + Clone + Sync + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: Into<crate::ServiceError> + fmt::Display,
+    C: Clone + Send + Sync + 'static,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Client {{ base_path: {} }}", self.base_path)
+    }
+}
+
+impl<S, C> Clone for Client<S, C>
+where
+    S: Service<(Request<Body>, C), Response = Response<Body>> + Clone + Sync + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: Into<crate::ServiceError> + fmt::Display,
+    C: Clone + Send + Sync + 'static,
+{
+    fn clone(&self) -> Self {
+        Self {
+            client_service: self.client_service.clone(),
+            base_path: self.base_path.clone(),
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<Connector, C> Client<DropContextService<hyper::client::Client<Connector, Body>, C>, C>
+where
+    Connector: hyper::client::connect::Connect + Clone + Send + Sync + 'static,
+    C: Clone + Send + Sync + 'static,
+{
+    /// Create a client with a custom implementation of hyper::client::Connect.
+    ///
+    /// Intended for use with custom implementations of connect for e.g. protocol logging
+    /// or similar functionality which requires wrapping the transport layer. When wrapping a TCP connection,
+    /// this function should be used in conjunction with `swagger::Connector::builder()`.
+    ///
+    /// For ordinary tcp connections, prefer the use of `try_new_http`, `try_new_https`
+    /// and `try_new_https_mutual`, to avoid introducing a dependency on the underlying transport layer.
+    ///
+    /// # Arguments
+    ///
+    /// * `base_path` - base path of the client API, i.e. "http://www.my-api-implementation.com"
+    /// * `protocol` - Which protocol to use when constructing the request url, e.g. `Some("http")`
+    /// * `connector` - Implementation of `hyper::client::Connect` to use for the client
+    pub fn try_new_with_connector(
+        base_path: &str,
+        protocol: Option<&'static str>,
+        connector: Connector,
+    ) -> Result<Self, ClientInitError> {
+        let client_service = hyper::client::Client::builder().build(connector);
+        let client_service = DropContextService::new(client_service);
+
+        Ok(Self {
+            client_service,
+            base_path: into_base_path(base_path, protocol)?,
+            marker: PhantomData,
+        })
+    }
+}
+
+#[derive(Debug, Clone)]
+pub enum HyperClient {
+    Http(hyper::client::Client<hyper::client::HttpConnector, Body>),
+    Https(hyper::client::Client<HttpsConnector, Body>),
+}
+
+impl Service<Request<Body>> for HyperClient {
+    type Response = Response<Body>;
+    type Error = hyper::Error;
+    type Future = hyper::client::ResponseFuture;
+
+    fn poll_ready(&mut self, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        match self {
+            HyperClient::Http(client) => client.poll_ready(cx),
+            HyperClient::Https(client) => client.poll_ready(cx),
+        }
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        match self {
+            HyperClient::Http(client) => client.call(req),
+            HyperClient::Https(client) => client.call(req),
+        }
+    }
+}
+
+impl<C> Client<DropContextService<HyperClient, C>, C>
+where
+    C: Clone + Send + Sync + 'static,
+{
+    /// Create an HTTP client.
+    ///
+    /// # Arguments
+    /// * `base_path` - base path of the client API, i.e. "http://www.my-api-implementation.com"
+    pub fn try_new(base_path: &str) -> Result<Self, ClientInitError> {
+        let uri = Uri::from_str(base_path)?;
+
+        let scheme = uri.scheme_str().ok_or(ClientInitError::InvalidScheme)?;
+        let scheme = scheme.to_ascii_lowercase();
+
+        let connector = Connector::builder();
+
+        let client_service = match scheme.as_str() {
+            "http" => HyperClient::Http(hyper::client::Client::builder().build(connector.build())),
+            "https" => {
+                let connector = connector
+                    .https()
+                    .build()
+                    .map_err(ClientInitError::SslError)?;
+                HyperClient::Https(hyper::client::Client::builder().build(connector))
+            }
+            _ => {
+                return Err(ClientInitError::InvalidScheme);
+            }
+        };
+
+        let client_service = DropContextService::new(client_service);
+
+        Ok(Self {
+            client_service,
+            base_path: into_base_path(base_path, None)?,
+            marker: PhantomData,
+        })
+    }
+}
+
+impl<C> Client<DropContextService<hyper::client::Client<hyper::client::HttpConnector, Body>, C>, C>
+where
+    C: Clone + Send + Sync + 'static,
+{
+    /// Create an HTTP client.
+    ///
+    /// # Arguments
+    /// * `base_path` - base path of the client API, i.e. "http://www.my-api-implementation.com"
+    pub fn try_new_http(base_path: &str) -> Result<Self, ClientInitError> {
+        let http_connector = Connector::builder().build();
+
+        Self::try_new_with_connector(base_path, Some("http"), http_connector)
+    }
+}
+
+#[cfg(any(target_os = "macos", target_os = "windows", target_os = "ios"))]
+type HttpsConnector = hyper_tls::HttpsConnector<hyper::client::HttpConnector>;
+
+#[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "ios")))]
+type HttpsConnector = hyper_openssl::HttpsConnector<hyper::client::HttpConnector>;
+
+impl<C> Client<DropContextService<hyper::client::Client<HttpsConnector, Body>, C>, C>
+where
+    C: Clone + Send + Sync + 'static,
+{
+    /// Create a client with a TLS connection to the server
+    ///
+    /// # Arguments
+    /// * `base_path` - base path of the client API, i.e. "https://www.my-api-implementation.com"
+    pub fn try_new_https(base_path: &str) -> Result<Self, ClientInitError> {
+        let https_connector = Connector::builder()
+            .https()
+            .build()
+            .map_err(ClientInitError::SslError)?;
+        Self::try_new_with_connector(base_path, Some("https"), https_connector)
+    }
+
+    /// Create a client with a TLS connection to the server using a pinned certificate
+    ///
+    /// # Arguments
+    /// * `base_path` - base path of the client API, i.e. "https://www.my-api-implementation.com"
+    /// * `ca_certificate` - Path to CA certificate used to authenticate the server
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "ios")))]
+    pub fn try_new_https_pinned<CA>(
+        base_path: &str,
+        ca_certificate: CA,
+    ) -> Result<Self, ClientInitError>
+    where
+        CA: AsRef<Path>,
+    {
+        let https_connector = Connector::builder()
+            .https()
+            .pin_server_certificate(ca_certificate)
+            .build()
+            .map_err(ClientInitError::SslError)?;
+        Self::try_new_with_connector(base_path, Some("https"), https_connector)
+    }
+
+    /// Create a client with a mutually authenticated TLS connection to the server.
+    ///
+    /// # Arguments
+    /// * `base_path` - base path of the client API, i.e. "https://www.my-api-implementation.com"
+    /// * `ca_certificate` - Path to CA certificate used to authenticate the server
+    /// * `client_key` - Path to the client private key
+    /// * `client_certificate` - Path to the client's public certificate associated with the private key
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "ios")))]
+    pub fn try_new_https_mutual<CA, K, D>(
+        base_path: &str,
+        ca_certificate: CA,
+        client_key: K,
+        client_certificate: D,
+    ) -> Result<Self, ClientInitError>
+    where
+        CA: AsRef<Path>,
+        K: AsRef<Path>,
+        D: AsRef<Path>,
+    {
+        let https_connector = Connector::builder()
+            .https()
+            .pin_server_certificate(ca_certificate)
+            .client_authentication(client_key, client_certificate)
+            .build()
+            .map_err(ClientInitError::SslError)?;
+        Self::try_new_with_connector(base_path, Some("https"), https_connector)
+    }
+}
+
+impl<S, C> Client<S, C>
+where
+    S: Service<(Request<Body>, C), Response = Response<Body>> + Clone + Sync + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: Into<crate::ServiceError> + fmt::Display,
+    C: Clone + Send + Sync + 'static,
+{
+    /// Constructor for creating a `Client` by passing in a pre-made `hyper::service::Service` /
+    /// `tower::Service`
+    ///
+    /// This allows adding custom wrappers around the underlying transport, for example for logging.
+    pub fn try_new_with_client_service(
+        client_service: S,
+        base_path: &str,
+    ) -> Result<Self, ClientInitError> {
+        Ok(Self {
+            client_service,
+            base_path: into_base_path(base_path, None)?,
+            marker: PhantomData,
+        })
+    }
+}
+
+/// Error type failing to create a Client
+#[derive(Debug)]
+pub enum ClientInitError {
+    /// Invalid URL Scheme
+    InvalidScheme,
+
+    /// Invalid URI
+    InvalidUri(hyper::http::uri::InvalidUri),
+
+    /// Missing Hostname
+    MissingHost,
+
+    /// SSL Connection Error
+    #[cfg(any(target_os = "macos", target_os = "windows", target_os = "ios"))]
+    SslError(native_tls::Error),
+
+    /// SSL Connection Error
+    #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "ios")))]
+    SslError(openssl::error::ErrorStack),
+}
+
+impl From<hyper::http::uri::InvalidUri> for ClientInitError {
+    fn from(err: hyper::http::uri::InvalidUri) -> ClientInitError {
+        ClientInitError::InvalidUri(err)
+    }
+}
+
+impl fmt::Display for ClientInitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s: &dyn fmt::Debug = self;
+        s.fmt(f)
+    }
+}
+
+impl Error for ClientInitError {
+    fn description(&self) -> &str {
+        "Failed to produce a hyper client."
+    }
+}
+
+impl<S, C> Client<S, C>
+where
+    S: Service<(Request<Body>, C), Response = Response<Body>> + Clone + Sync + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: Into<crate::ServiceError> + fmt::Display,
+    C: Has<XSpanIdString> + Has<Option<AuthData>> + Clone + Send + Sync + 'static,
+{
+    /// Builds the request for a resource below the base path, adding the span
+    /// id, authorization and optional string headers, and sends it.
+    async fn send(
+        &self,
+        method: &str,
+        path: String,
+        query_string: String,
+        body: Option<(String, &'static str)>,
+        headers: Vec<(&'static str, Option<String>)>,
+        context: &C,
+    ) -> Result<Response<Body>, ApiError> {
+        let mut client_service = self.client_service.clone();
+        let mut uri = format!("{}{}", self.base_path, path);
+        if !query_string.is_empty() {
+            uri += "?";
+            uri += &query_string;
+        }
+
+        let uri = match Uri::from_str(&uri) {
+            Ok(uri) => uri,
+            Err(err) => return Err(ApiError(format!("Unable to build URI: {}", err))),
+        };
+
+        let mut request = match Request::builder()
+            .method(method)
+            .uri(uri)
+            .body(Body::empty())
+        {
+            Ok(req) => req,
+            Err(e) => return Err(ApiError(format!("Unable to create request: {}", e))),
+        };
+
+        // Body parameter
+        if let Some((body, content_type)) = body {
+            *request.body_mut() = Body::from(body);
+            request
+                .headers_mut()
+                .insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+        }
+
+        let header = HeaderValue::from_str(Has::<XSpanIdString>::get(context).0.as_str());
+        request.headers_mut().insert(
+            HeaderName::from_static("x-span-id"),
+            match header {
+                Ok(h) => h,
+                Err(e) => {
+                    return Err(ApiError(format!(
+                        "Unable to create X-Span ID header value: {}",
+                        e
+                    )))
+                }
+            },
+        );
+
+        #[allow(clippy::collapsible_match)]
+        if let Some(auth_data) = Has::<Option<AuthData>>::get(context).as_ref() {
+            // Currently only authentication with Basic and Bearer are supported
+            #[allow(clippy::single_match, clippy::match_single_binding)]
+            match auth_data {
+                &AuthData::Bearer(ref bearer_header) => {
+                    let auth = swagger::auth::Header(bearer_header.clone());
+                    let header = match HeaderValue::from_str(&format!("{}", auth)) {
+                        Ok(h) => h,
+                        Err(e) => {
+                            return Err(ApiError(format!(
+                                "Unable to create Authorization header: {}",
+                                e
+                            )))
+                        }
+                    };
+                    request
+                        .headers_mut()
+                        .insert(hyper::header::AUTHORIZATION, header);
+                }
+                _ => {}
+            }
+        }
+
+        // Header parameters
+        for (name, value) in headers {
+            if let Some(value) = value {
+                request.headers_mut().append(
+                    HeaderName::from_static(name),
+                    match header::IntoHeaderValue(value).try_into() {
+                        Ok(header) => header,
+                        Err(e) => {
+                            return Err(ApiError(format!("Invalid header {} - {}", name, e)));
+                        }
+                    },
+                );
+            }
+        }
+
+        client_service
+            .call((request, context.clone()))
+            .map_err(|e| ApiError(format!("No response received: {}", e)))
+            .await
+    }
+}
+
+/// Path of an individual NF instance resource.
+fn nf_instance_path(nf_instance_id: uuid::Uuid) -> String {
+    format!(
+        "/nnrf-nfm/v1/nf-instances/{nf_instance_id}",
+        nf_instance_id = utf8_percent_encode(&nf_instance_id.to_string(), ID_ENCODE_SET)
+    )
+}
+
+/// Reads an optional string header of a response.
+fn response_header(
+    response: &Response<Body>,
+    name: &'static str,
+) -> Result<Option<String>, ApiError> {
+    match response.headers().get(HeaderName::from_static(name)) {
+        Some(value) => match TryInto::<header::IntoHeaderValue<String>>::try_into(value.clone()) {
+            Ok(value) => Ok(Some(value.0)),
+            Err(e) => Err(ApiError(format!(
+                "Invalid response header {} for response {} - {}",
+                name,
+                response.status().as_u16(),
+                e
+            ))),
+        },
+        None => Ok(None),
+    }
+}
+
+/// Reads and deserializes a JSON response body.
+async fn response_body<T: serde::de::DeserializeOwned>(
+    response: Response<Body>,
+) -> Result<T, ApiError> {
+    let body = response.into_body();
+    let body = body
+        .into_raw()
+        .map_err(|e| ApiError(format!("Failed to read response: {}", e)))
+        .await?;
+    let body = str::from_utf8(&body)
+        .map_err(|e| ApiError(format!("Response was not valid UTF8: {}", e)))?;
+    serde_json::from_str::<T>(body)
+        .map_err(|e| ApiError(format!("Response body did not match the schema: {}", e)))
+}
+
+/// Builds the error returned for an undocumented status code.
+async fn unexpected_response(response: Response<Body>) -> ApiError {
+    let code = response.status().as_u16();
+    let headers = response.headers().clone();
+    let body = response.into_body().take(100).into_raw().await;
+    ApiError(format!(
+        "Unexpected response code {}:\n{:?}\n\n{}",
+        code,
+        headers,
+        match body {
+            Ok(body) => match String::from_utf8(body) {
+                Ok(body) => body,
+                Err(e) => format!("<Body was not UTF8: {:?}>", e),
+            },
+            Err(e) => format!("<Failed to read body: {}>", e),
+        }
+    ))
+}
+
+#[async_trait]
+impl<S, C> Api<C> for Client<S, C>
+where
+    S: Service<(Request<Body>, C), Response = Response<Body>> + Clone + Sync + Send + 'static,
+    S::Future: Send + 'static,
+    S::Error: Into<crate::ServiceError> + fmt::Display,
+    C: Has<XSpanIdString> + Has<Option<AuthData>> + Clone + Send + Sync + 'static,
+{
+    fn poll_ready(&self, cx: &mut Context) -> Poll<Result<(), crate::ServiceError>> {
+        match self.client_service.clone().poll_ready(cx) {
+            Poll::Ready(Err(e)) => Poll::Ready(Err(e.into())),
+            Poll::Ready(Ok(o)) => Poll::Ready(Ok(o)),
+            Poll::Pending => Poll::Pending,
+        }
+    }
+
+    async fn get_nf_instance(
+        &self,
+        param_nf_instance_id: uuid::Uuid,
+        param_requester_features: Option<String>,
+        param_accept_encoding: Option<String>,
+        context: &C,
+    ) -> Result<GetNfInstanceResponse, ApiError> {
+        // Query parameters
+        let query_string = {
+            let mut query_string = form_urlencoded::Serializer::new("".to_owned());
+            if let Some(param_requester_features) = param_requester_features {
+                query_string.append_pair("requester-features", &param_requester_features);
+            }
+            query_string.finish()
+        };
+
+        let response = self
+            .send(
+                "GET",
+                nf_instance_path(param_nf_instance_id),
+                query_string,
+                None,
+                vec![("accept-encoding", param_accept_encoding)],
+                context,
+            )
+            .await?;
+
+        match response.status().as_u16() {
+            200 => {
+                let e_tag = response_header(&response, "etag")?;
+                let content_encoding = response_header(&response, "content-encoding")?;
+                Ok(GetNfInstanceResponse::ExpectedResponseToAValidRequest {
+                    body: response_body(response).await?,
+                    e_tag,
+                    content_encoding,
+                })
+            }
+            400 => Ok(GetNfInstanceResponse::BadRequest(
+                response_body(response).await?,
+            )),
+            401 => Ok(GetNfInstanceResponse::Unauthorized(
+                response_body(response).await?,
+            )),
+            403 => Ok(GetNfInstanceResponse::Forbidden(
+                response_body(response).await?,
+            )),
+            404 => Ok(GetNfInstanceResponse::NotFound(
+                response_body(response).await?,
+            )),
+            500 => Ok(GetNfInstanceResponse::InternalServerError(
+                response_body(response).await?,
+            )),
+            503 => Ok(GetNfInstanceResponse::ServiceUnavailable(
+                response_body(response).await?,
+            )),
+            _ => Err(unexpected_response(response).await),
+        }
+    }
+
+    async fn register_nf_instance(
+        &self,
+        param_nf_instance_id: uuid::Uuid,
+        param_nf_profile: models::NfProfile,
+        param_content_encoding: Option<String>,
+        param_accept_encoding: Option<String>,
+        context: &C,
+    ) -> Result<RegisterNfInstanceResponse, ApiError> {
+        let body =
+            serde_json::to_string(&param_nf_profile).expect("impossible to fail to serialize");
+
+        let response = self
+            .send(
+                "PUT",
+                nf_instance_path(param_nf_instance_id),
+                String::new(),
+                Some((body, "application/json")),
+                vec![
+                    ("content-encoding", param_content_encoding),
+                    ("accept-encoding", param_accept_encoding),
+                ],
+                context,
+            )
+            .await?;
+
+        match response.status().as_u16() {
+            200 => {
+                let accept_encoding = response_header(&response, "accept-encoding")?;
+                let e_tag = response_header(&response, "etag")?;
+                let content_encoding = response_header(&response, "content-encoding")?;
+                Ok(RegisterNfInstanceResponse::OK {
+                    body: response_body(response).await?,
+                    accept_encoding,
+                    e_tag,
+                    content_encoding,
+                })
+            }
+            201 => {
+                let location = response_header(&response, "location")?.ok_or_else(|| {
+                    ApiError(String::from(
+                        "Required response header Location for response 201 was not found.",
+                    ))
+                })?;
+                let accept_encoding = response_header(&response, "accept-encoding")?;
+                let e_tag = response_header(&response, "etag")?;
+                let content_encoding = response_header(&response, "content-encoding")?;
+                Ok(RegisterNfInstanceResponse::Created {
+                    body: response_body(response).await?,
+                    location,
+                    accept_encoding,
+                    e_tag,
+                    content_encoding,
+                })
+            }
+            400 => Ok(RegisterNfInstanceResponse::BadRequest(
+                response_body(response).await?,
+            )),
+            401 => Ok(RegisterNfInstanceResponse::Unauthorized(
+                response_body(response).await?,
+            )),
+            403 => Ok(RegisterNfInstanceResponse::Forbidden(
+                response_body(response).await?,
+            )),
+            404 => Ok(RegisterNfInstanceResponse::NotFound(
+                response_body(response).await?,
+            )),
+            500 => Ok(RegisterNfInstanceResponse::InternalServerError(
+                response_body(response).await?,
+            )),
+            503 => Ok(RegisterNfInstanceResponse::ServiceUnavailable(
+                response_body(response).await?,
+            )),
+            _ => Err(unexpected_response(response).await),
+        }
+    }
+
+    async fn update_nf_instance(
+        &self,
+        param_nf_instance_id: uuid::Uuid,
+        param_patch_item: &Vec<models::PatchItem>,
+        param_content_encoding: Option<String>,
+        param_accept_encoding: Option<String>,
+        param_if_match: Option<String>,
+        context: &C,
+    ) -> Result<UpdateNfInstanceResponse, ApiError> {
+        let body =
+            serde_json::to_string(param_patch_item).expect("impossible to fail to serialize");
+
+        let response = self
+            .send(
+                "PATCH",
+                nf_instance_path(param_nf_instance_id),
+                String::new(),
+                Some((body, "application/json-patch+json")),
+                vec![
+                    ("content-encoding", param_content_encoding),
+                    ("accept-encoding", param_accept_encoding),
+                    ("if-match", param_if_match),
+                ],
+                context,
+            )
+            .await?;
+
+        match response.status().as_u16() {
+            200 => {
+                let accept_encoding = response_header(&response, "accept-encoding")?;
+                let e_tag = response_header(&response, "etag")?;
+                let content_encoding = response_header(&response, "content-encoding")?;
+                Ok(UpdateNfInstanceResponse::ExpectedResponseToAValidRequest {
+                    body: response_body(response).await?,
+                    accept_encoding,
+                    e_tag,
+                    content_encoding,
+                })
+            }
+            204 => Ok(UpdateNfInstanceResponse::NoContent),
+            400 => Ok(UpdateNfInstanceResponse::BadRequest(
+                response_body(response).await?,
+            )),
+            401 => Ok(UpdateNfInstanceResponse::Unauthorized(
+                response_body(response).await?,
+            )),
+            403 => Ok(UpdateNfInstanceResponse::Forbidden(
+                response_body(response).await?,
+            )),
+            404 => Ok(UpdateNfInstanceResponse::NotFound(
+                response_body(response).await?,
+            )),
+            409 => Ok(UpdateNfInstanceResponse::Conflict(
+                response_body(response).await?,
+            )),
+            412 => Ok(UpdateNfInstanceResponse::PreconditionFailed(
+                response_body(response).await?,
+            )),
+            500 => Ok(UpdateNfInstanceResponse::InternalServerError(
+                response_body(response).await?,
+            )),
+            503 => Ok(UpdateNfInstanceResponse::ServiceUnavailable(
+                response_body(response).await?,
+            )),
+            _ => Err(unexpected_response(response).await),
+        }
+    }
+
+    async fn deregister_nf_instance(
+        &self,
+        param_nf_instance_id: uuid::Uuid,
+        context: &C,
+    ) -> Result<DeregisterNfInstanceResponse, ApiError> {
+        let response = self
+            .send(
+                "DELETE",
+                nf_instance_path(param_nf_instance_id),
+                String::new(),
+                None,
+                vec![],
+                context,
+            )
+            .await?;
+
+        match response.status().as_u16() {
+            204 => Ok(DeregisterNfInstanceResponse::ExpectedResponseToASuccessfulDeregistration),
+            400 => Ok(DeregisterNfInstanceResponse::BadRequest(
+                response_body(response).await?,
+            )),
+            401 => Ok(DeregisterNfInstanceResponse::Unauthorized(
+                response_body(response).await?,
+            )),
+            403 => Ok(DeregisterNfInstanceResponse::Forbidden(
+                response_body(response).await?,
+            )),
+            404 => Ok(DeregisterNfInstanceResponse::NotFound(
+                response_body(response).await?,
+            )),
+            500 => Ok(DeregisterNfInstanceResponse::InternalServerError(
+                response_body(response).await?,
+            )),
+            503 => Ok(DeregisterNfInstanceResponse::ServiceUnavailable(
+                response_body(response).await?,
+            )),
+            _ => Err(unexpected_response(response).await),
+        }
+    }
+
+    async fn create_subscription(
+        &self,
+        param_subscription_data: models::SubscriptionData,
+        param_content_encoding: Option<String>,
+        param_accept_encoding: Option<String>,
+        context: &C,
+    ) -> Result<CreateSubscriptionResponse, ApiError> {
+        let body = serde_json::to_string(&param_subscription_data)
+            .expect("impossible to fail to serialize");
+
+        let response = self
+            .send(
+                "POST",
+                "/nnrf-nfm/v1/subscriptions".to_string(),
+                String::new(),
+                Some((body, "application/json")),
+                vec![
+                    ("content-encoding", param_content_encoding),
+                    ("accept-encoding", param_accept_encoding),
+                ],
+                context,
+            )
+            .await?;
+
+        match response.status().as_u16() {
+            201 => {
+                let location = response_header(&response, "location")?.ok_or_else(|| {
+                    ApiError(String::from(
+                        "Required response header Location for response 201 was not found.",
+                    ))
+                })?;
+                let accept_encoding = response_header(&response, "accept-encoding")?;
+                let content_encoding = response_header(&response, "content-encoding")?;
+                Ok(
+                    CreateSubscriptionResponse::ExpectedResponseToAValidRequest {
+                        body: response_body(response).await?,
+                        location,
+                        accept_encoding,
+                        content_encoding,
+                    },
+                )
+            }
+            400 => Ok(CreateSubscriptionResponse::BadRequest(
+                response_body(response).await?,
+            )),
+            401 => Ok(CreateSubscriptionResponse::Unauthorized(
+                response_body(response).await?,
+            )),
+            403 => Ok(CreateSubscriptionResponse::Forbidden(
+                response_body(response).await?,
+            )),
+            404 => Ok(CreateSubscriptionResponse::NotFound(
+                response_body(response).await?,
+            )),
+            500 => Ok(CreateSubscriptionResponse::InternalServerError(
+                response_body(response).await?,
+            )),
+            501 => Ok(CreateSubscriptionResponse::NotImplemented(
+                response_body(response).await?,
+            )),
+            503 => Ok(CreateSubscriptionResponse::ServiceUnavailable(
+                response_body(response).await?,
+            )),
+            _ => Err(unexpected_response(response).await),
+        }
+    }
+
+    async fn remove_subscription(
+        &self,
+        param_subscription_id: String,
+        context: &C,
+    ) -> Result<RemoveSubscriptionResponse, ApiError> {
+        let response = self
+            .send(
+                "DELETE",
+                format!(
+                    "/nnrf-nfm/v1/subscriptions/{subscription_id}",
+                    subscription_id = utf8_percent_encode(&param_subscription_id, ID_ENCODE_SET)
+                ),
+                String::new(),
+                None,
+                vec![],
+                context,
+            )
+            .await?;
+
+        match response.status().as_u16() {
+            204 => Ok(RemoveSubscriptionResponse::ExpectedResponseToASuccessfulSubscriptionRemoval),
+            400 => Ok(RemoveSubscriptionResponse::BadRequest(
+                response_body(response).await?,
+            )),
+            401 => Ok(RemoveSubscriptionResponse::Unauthorized(
+                response_body(response).await?,
+            )),
+            403 => Ok(RemoveSubscriptionResponse::Forbidden(
+                response_body(response).await?,
+            )),
+            404 => Ok(RemoveSubscriptionResponse::NotFound(
+                response_body(response).await?,
+            )),
+            500 => Ok(RemoveSubscriptionResponse::InternalServerError(
+                response_body(response).await?,
+            )),
+            503 => Ok(RemoveSubscriptionResponse::ServiceUnavailable(
+                response_body(response).await?,
+            )),
+            _ => Err(unexpected_response(response).await),
+        }
+    }
+}
@@ -0,0 +1,555 @@
+#![allow(
+    missing_docs,
+    trivial_casts,
+    unused_variables,
+    unused_mut,
+    unused_imports,
+    unused_extern_crates,
+    non_camel_case_types
+)]
+#![allow(unused_imports, unused_attributes)]
+#![allow(clippy::derive_partial_eq_without_eq, clippy::blacklisted_name)]
+
+use async_trait::async_trait;
+use futures::Stream;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::task::{Context, Poll};
+use swagger::{ApiError, ContextWrapper};
+
+type ServiceError = Box<dyn Error + Send + Sync + 'static>;
+
+pub const BASE_PATH: &str = "/nnrf-nfm/v1";
+pub const API_VERSION: &str = "1.2.2";
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[must_use]
+pub enum GetNfInstanceResponse {
+    /// Expected response to a valid request
+    ExpectedResponseToAValidRequest {
+        body: models::NfProfile,
+        e_tag: Option<String>,
+        content_encoding: Option<String>,
+    },
+    /// Bad request
+    BadRequest(models::ProblemDetails),
+    /// Unauthorized
+    Unauthorized(models::ProblemDetails),
+    /// Forbidden
+    Forbidden(models::ProblemDetails),
+    /// Not Found
+    NotFound(models::ProblemDetails),
+    /// Internal Server Error
+    InternalServerError(models::ProblemDetails),
+    /// Service Unavailable
+    ServiceUnavailable(models::ProblemDetails),
+    /// Generic Error
+    GenericError,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[must_use]
+pub enum RegisterNfInstanceResponse {
+    /// OK (Profile Replaced)
+    OK {
+        body: models::NfProfile,
+        accept_encoding: Option<String>,
+        e_tag: Option<String>,
+        content_encoding: Option<String>,
+    },
+    /// Created (NF Instance Registered)
+    Created {
+        body: models::NfProfile,
+        location: String,
+        accept_encoding: Option<String>,
+        e_tag: Option<String>,
+        content_encoding: Option<String>,
+    },
+    /// Bad request
+    BadRequest(models::ProblemDetails),
+    /// Unauthorized
+    Unauthorized(models::ProblemDetails),
+    /// Forbidden
+    Forbidden(models::ProblemDetails),
+    /// Not Found
+    NotFound(models::ProblemDetails),
+    /// Internal Server Error
+    InternalServerError(models::ProblemDetails),
+    /// Service Unavailable
+    ServiceUnavailable(models::ProblemDetails),
+    /// Generic Error
+    GenericError,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[must_use]
+pub enum UpdateNfInstanceResponse {
+    /// Expected response to a valid request
+    ExpectedResponseToAValidRequest {
+        body: models::NfProfile,
+        accept_encoding: Option<String>,
+        e_tag: Option<String>,
+        content_encoding: Option<String>,
+    },
+    /// Expected response to a successful heartbeat
+    NoContent,
+    /// Bad request
+    BadRequest(models::ProblemDetails),
+    /// Unauthorized
+    Unauthorized(models::ProblemDetails),
+    /// Forbidden
+    Forbidden(models::ProblemDetails),
+    /// Not Found
+    NotFound(models::ProblemDetails),
+    /// Conflict
+    Conflict(models::ProblemDetails),
+    /// Precondition Failed
+    PreconditionFailed(models::ProblemDetails),
+    /// Internal Server Error
+    InternalServerError(models::ProblemDetails),
+    /// Service Unavailable
+    ServiceUnavailable(models::ProblemDetails),
+    /// Generic Error
+    GenericError,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[must_use]
+pub enum DeregisterNfInstanceResponse {
+    /// Expected response to a successful deregistration
+    ExpectedResponseToASuccessfulDeregistration,
+    /// Bad request
+    BadRequest(models::ProblemDetails),
+    /// Unauthorized
+    Unauthorized(models::ProblemDetails),
+    /// Forbidden
+    Forbidden(models::ProblemDetails),
+    /// Not Found
+    NotFound(models::ProblemDetails),
+    /// Internal Server Error
+    InternalServerError(models::ProblemDetails),
+    /// Service Unavailable
+    ServiceUnavailable(models::ProblemDetails),
+    /// Generic Error
+    GenericError,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[must_use]
+pub enum CreateSubscriptionResponse {
+    /// Expected response to a valid request
+    ExpectedResponseToAValidRequest {
+        body: models::SubscriptionData,
+        location: String,
+        accept_encoding: Option<String>,
+        content_encoding: Option<String>,
+    },
+    /// Bad request
+    BadRequest(models::ProblemDetails),
+    /// Unauthorized
+    Unauthorized(models::ProblemDetails),
+    /// Forbidden
+    Forbidden(models::ProblemDetails),
+    /// Not Found
+    NotFound(models::ProblemDetails),
+    /// Internal Server Error
+    InternalServerError(models::ProblemDetails),
+    /// Not Implemented
+    NotImplemented(models::ProblemDetails),
+    /// Service Unavailable
+    ServiceUnavailable(models::ProblemDetails),
+    /// Generic Error
+    GenericError,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize, Clone)]
+#[must_use]
+pub enum RemoveSubscriptionResponse {
+    /// Expected response to a successful subscription removal
+    ExpectedResponseToASuccessfulSubscriptionRemoval,
+    /// Bad request
+    BadRequest(models::ProblemDetails),
+    /// Unauthorized
+    Unauthorized(models::ProblemDetails),
+    /// Forbidden
+    Forbidden(models::ProblemDetails),
+    /// Not Found
+    NotFound(models::ProblemDetails),
+    /// Internal Server Error
+    InternalServerError(models::ProblemDetails),
+    /// Service Unavailable
+    ServiceUnavailable(models::ProblemDetails),
+    /// Generic Error
+    GenericError,
+}
+
+/// API
+#[async_trait]
+#[allow(clippy::too_many_arguments, clippy::ptr_arg)]
+pub trait Api<C: Send + Sync> {
+    fn poll_ready(
+        &self,
+        _cx: &mut Context,
+    ) -> Poll<Result<(), Box<dyn Error + Send + Sync + 'static>>> {
+        Poll::Ready(Ok(()))
+    }
+
+    /// Read the profile of a given NF Instance
+    async fn get_nf_instance(
+        &self,
+        nf_instance_id: uuid::Uuid,
+        requester_features: Option<String>,
+        accept_encoding: Option<String>,
+        context: &C,
+    ) -> Result<GetNfInstanceResponse, ApiError>;
+
+    /// Register a new NF Instance
+    async fn register_nf_instance(
+        &self,
+        nf_instance_id: uuid::Uuid,
+        nf_profile: models::NfProfile,
+        content_encoding: Option<String>,
+        accept_encoding: Option<String>,
+        context: &C,
+    ) -> Result<RegisterNfInstanceResponse, ApiError>;
+
+    /// Update NF Instance profile
+    async fn update_nf_instance(
+        &self,
+        nf_instance_id: uuid::Uuid,
+        patch_item: &Vec<models::PatchItem>,
+        content_encoding: Option<String>,
+        accept_encoding: Option<String>,
+        if_match: Option<String>,
+        context: &C,
+    ) -> Result<UpdateNfInstanceResponse, ApiError>;
+
+    /// Deregisters a given NF Instance
+    async fn deregister_nf_instance(
+        &self,
+        nf_instance_id: uuid::Uuid,
+        context: &C,
+    ) -> Result<DeregisterNfInstanceResponse, ApiError>;
+
+    /// Create a new subscription
+    async fn create_subscription(
+        &self,
+        subscription_data: models::SubscriptionData,
+        content_encoding: Option<String>,
+        accept_encoding: Option<String>,
+        context: &C,
+    ) -> Result<CreateSubscriptionResponse, ApiError>;
+
+    /// Deletes a subscription
+    async fn remove_subscription(
+        &self,
+        subscription_id: String,
+        context: &C,
+    ) -> Result<RemoveSubscriptionResponse, ApiError>;
+}
+
+/// API where `Context` isn't passed on every API call
+#[async_trait]
+#[allow(clippy::too_many_arguments, clippy::ptr_arg)]
+pub trait ApiNoContext<C: Send + Sync> {
+    fn poll_ready(
+        &self,
+        _cx: &mut Context,
+    ) -> Poll<Result<(), Box<dyn Error + Send + Sync + 'static>>>;
+
+    fn context(&self) -> &C;
+
+    /// Read the profile of a given NF Instance
+    async fn get_nf_instance(
+        &self,
+        nf_instance_id: uuid::Uuid,
+        requester_features: Option<String>,
+        accept_encoding: Option<String>,
+    ) -> Result<GetNfInstanceResponse, ApiError>;
+
+    /// Register a new NF Instance
+    async fn register_nf_instance(
+        &self,
+        nf_instance_id: uuid::Uuid,
+        nf_profile: models::NfProfile,
+        content_encoding: Option<String>,
+        accept_encoding: Option<String>,
+    ) -> Result<RegisterNfInstanceResponse, ApiError>;
+
+    /// Update NF Instance profile
+    async fn update_nf_instance(
+        &self,
+        nf_instance_id: uuid::Uuid,
+        patch_item: &Vec<models::PatchItem>,
+        content_encoding: Option<String>,
+        accept_encoding: Option<String>,
+        if_match: Option<String>,
+    ) -> Result<UpdateNfInstanceResponse, ApiError>;
+
+    /// Deregisters a given NF Instance
+    async fn deregister_nf_instance(
+        &self,
+        nf_instance_id: uuid::Uuid,
+    ) -> Result<DeregisterNfInstanceResponse, ApiError>;
+
+    /// Create a new subscription
+    async fn create_subscription(
+        &self,
+        subscription_data: models::SubscriptionData,
+        content_encoding: Option<String>,
+        accept_encoding: Option<String>,
+    ) -> Result<CreateSubscriptionResponse, ApiError>;
+
+    /// Deletes a subscription
+    async fn remove_subscription(
+        &self,
+        subscription_id: String,
+    ) -> Result<RemoveSubscriptionResponse, ApiError>;
+}
+
+/// Trait to extend an API to make it easy to bind it to a context.
+pub trait ContextWrapperExt<C: Send + Sync>
+where
+    Self: Sized,
+{
+    /// Binds this API to a context.
+    fn with_context(self, context: C) -> ContextWrapper<Self, C>;
+}
+
+impl<T: Api<C> + Send + Sync, C: Clone + Send + Sync> ContextWrapperExt<C> for T {
+    fn with_context(self: T, context: C) -> ContextWrapper<T, C> {
+        ContextWrapper::<T, C>::new(self, context)
+    }
+}
+
+#[async_trait]
+impl<T: Api<C> + Send + Sync, C: Clone + Send + Sync> ApiNoContext<C> for ContextWrapper<T, C> {
+    fn poll_ready(&self, cx: &mut Context) -> Poll<Result<(), ServiceError>> {
+        self.api().poll_ready(cx)
+    }
+
+    fn context(&self) -> &C {
+        ContextWrapper::context(self)
+    }
+
+    /// Read the profile of a given NF Instance
+    async fn get_nf_instance(
+        &self,
+        nf_instance_id: uuid::Uuid,
+        requester_features: Option<String>,
+        accept_encoding: Option<String>,
+    ) -> Result<GetNfInstanceResponse, ApiError> {
+        let context = self.context().clone();
+        self.api()
+            .get_nf_instance(
+                nf_instance_id,
+                requester_features,
+                accept_encoding,
+                &context,
+            )
+            .await
+    }
+
+    /// Register a new NF Instance
+    async fn register_nf_instance(
+        &self,
+        nf_instance_id: uuid::Uuid,
+        nf_profile: models::NfProfile,
+        content_encoding: Option<String>,
+        accept_encoding: Option<String>,
+    ) -> Result<RegisterNfInstanceResponse, ApiError> {
+        let context = self.context().clone();
+        self.api()
+            .register_nf_instance(
+                nf_instance_id,
+                nf_profile,
+                content_encoding,
+                accept_encoding,
+                &context,
+            )
+            .await
+    }
+
+    /// Update NF Instance profile
+    async fn update_nf_instance(
+        &self,
+        nf_instance_id: uuid::Uuid,
+        patch_item: &Vec<models::PatchItem>,
+        content_encoding: Option<String>,
+        accept_encoding: Option<String>,
+        if_match: Option<String>,
+    ) -> Result<UpdateNfInstanceResponse, ApiError> {
+        let context = self.context().clone();
+        self.api()
+            .update_nf_instance(
+                nf_instance_id,
+                patch_item,
+                content_encoding,
+                accept_encoding,
+                if_match,
+                &context,
+            )
+            .await
+    }
+
+    /// Deregisters a given NF Instance
+    async fn deregister_nf_instance(
+        &self,
+        nf_instance_id: uuid::Uuid,
+    ) -> Result<DeregisterNfInstanceResponse, ApiError> {
+        let context = self.context().clone();
+        self.api()
+            .deregister_nf_instance(nf_instance_id, &context)
+            .await
+    }
+
+    /// Create a new subscription
+    async fn create_subscription(
+        &self,
+        subscription_data: models::SubscriptionData,
+        content_encoding: Option<String>,
+        accept_encoding: Option<String>,
+    ) -> Result<CreateSubscriptionResponse, ApiError> {
+        let context = self.context().clone();
+        self.api()
+            .create_subscription(
+                subscription_data,
+                content_encoding,
+                accept_encoding,
+                &context,
+            )
+            .await
+    }
+
+    /// Deletes a subscription
+    async fn remove_subscription(
+        &self,
+        subscription_id: String,
+    ) -> Result<RemoveSubscriptionResponse, ApiError> {
+        let context = self.context().clone();
+        self.api()
+            .remove_subscription(subscription_id, &context)
+            .await
+    }
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[must_use]
+pub enum OnNfStatusEventPostResponse {
+    /// Expected response to a successful callback processing
+    ExpectedResponseToASuccessfulCallbackProcessing,
+    /// Bad request
+    BadRequest(models::ProblemDetails),
+    /// Not Found
+    NotFound(models::ProblemDetails),
+    /// Internal Server Error
+    InternalServerError(models::ProblemDetails),
+    /// Service Unavailable
+    ServiceUnavailable(models::ProblemDetails),
+    /// Generic Error
+    GenericError,
+}
+
+/// Callback API
+#[async_trait]
+pub trait CallbackApi<C: Send + Sync> {
+    fn poll_ready(
+        &self,
+        _cx: &mut Context,
+    ) -> Poll<Result<(), Box<dyn Error + Send + Sync + 'static>>> {
+        Poll::Ready(Ok(()))
+    }
+
+    async fn on_nf_status_event_post(
+        &self,
+        callback_request_body_nf_status_notification_uri: String,
+        notification_data: models::NotificationData,
+        context: &C,
+    ) -> Result<OnNfStatusEventPostResponse, ApiError>;
+}
+
+/// Callback API without a `Context`
+#[async_trait]
+pub trait CallbackApiNoContext<C: Send + Sync> {
+    fn poll_ready(
+        &self,
+        _cx: &mut Context,
+    ) -> Poll<Result<(), Box<dyn Error + Send + Sync + 'static>>>;
+
+    fn context(&self) -> &C;
+
+    async fn on_nf_status_event_post(
+        &self,
+        callback_request_body_nf_status_notification_uri: String,
+        notification_data: models::NotificationData,
+    ) -> Result<OnNfStatusEventPostResponse, ApiError>;
+}
+
+pub trait CallbackContextWrapperExt<C: Send + Sync>
+where
+    Self: Sized,
+{
+    /// Binds this API to a context.
+    fn with_context(self, context: C) -> ContextWrapper<Self, C>;
+}
+
+impl<T: CallbackApi<C> + Send + Sync, C: Clone + Send + Sync> CallbackContextWrapperExt<C> for T {
+    fn with_context(self: T, context: C) -> ContextWrapper<T, C> {
+        ContextWrapper::<T, C>::new(self, context)
+    }
+}
+
+#[async_trait]
+impl<T: CallbackApi<C> + Send + Sync, C: Clone + Send + Sync> CallbackApiNoContext<C>
+    for ContextWrapper<T, C>
+{
+    fn poll_ready(&self, cx: &mut Context) -> Poll<Result<(), ServiceError>> {
+        self.api().poll_ready(cx)
+    }
+
+    fn context(&self) -> &C {
+        ContextWrapper::context(self)
+    }
+
+    async fn on_nf_status_event_post(
+        &self,
+        callback_request_body_nf_status_notification_uri: String,
+        notification_data: models::NotificationData,
+    ) -> Result<OnNfStatusEventPostResponse, ApiError> {
+        let context = self.context().clone();
+        self.api()
+            .on_nf_status_event_post(
+                callback_request_body_nf_status_notification_uri,
+                notification_data,
+                &context,
+            )
+            .await
+    }
+}
+
+#[cfg(feature = "client")]
+pub mod client;
+
+// Re-export Client as a top-level name
+#[cfg(feature = "client")]
+pub use client::Client;
+
+#[cfg(feature = "server")]
+pub mod server;
+
+// Re-export router() as a top-level name
+#[cfg(feature = "server")]
+pub use self::server::Service;
+
+#[cfg(any(feature = "client", feature = "server"))]
+pub mod context;
+
+pub mod models;
+
+pub mod subscriptions;
+
+#[cfg(feature = "client")]
+pub mod registration;
+
+#[cfg(any(feature = "client", feature = "server"))]
+pub(crate) mod header;
@@ -0,0 +1,469 @@
+#![allow(unused_qualifications)]
+
+//! NFManagement data types.
+//!
+//! Everything shared with the discovery service is re-exported from
+//! `nnrf_discovery_server::models`; only the NFManagement specific types are
+//! defined here.
+
+#[cfg(any(feature = "client", feature = "server"))]
+use crate::header;
+use crate::models;
+
+pub use nnrf_discovery_server::models::*;
+
+/// Information of an NF Instance registered in the NRF
+///
+/// This is the discovery `NfProfile` extended with the attributes that only
+/// make sense between an NF and the NRF it registers with.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct NfProfile {
+    #[serde(flatten)]
+    pub profile: nnrf_discovery_server::models::NfProfile,
+
+    /// Heartbeat period, in seconds, expected by the NRF
+    #[serde(rename = "heartBeatTimer")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heart_beat_timer: Option<i32>,
+
+    /// Kept as strings: `NfType` only lists the NF types of this repository
+    /// while registering NFs commonly allow AUSF, SCP, NEF...
+    #[serde(rename = "allowedNfTypes")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allowed_nf_types: Option<Vec<String>>,
+
+    #[serde(rename = "nfProfileChangesSupportInd")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nf_profile_changes_support_ind: Option<bool>,
+
+    #[serde(rename = "nfProfileChangesInd")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nf_profile_changes_ind: Option<bool>,
+}
+
+impl NfProfile {
+    #[allow(clippy::new_without_default)]
+    pub fn new(profile: nnrf_discovery_server::models::NfProfile) -> NfProfile {
+        NfProfile {
+            profile,
+            heart_beat_timer: None,
+            allowed_nf_types: None,
+            nf_profile_changes_support_ind: None,
+            nf_profile_changes_ind: None,
+        }
+    }
+}
+
+/// Converts Query Parameters representation (style=form, explode=false) to a NfProfile value
+/// as specified in https://swagger.io/docs/specification/serialization/
+/// Should be implemented in a serde deserializer
+impl std::str::FromStr for NfProfile {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        serde_json::from_str(s).map_err(|e| format!("Unable to parse NfProfile: {}", e))
+    }
+}
+
+/// Types of events that trigger an NF status notification
+#[allow(non_camel_case_types)]
+#[repr(C)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+pub enum NotificationEventType {
+    #[serde(rename = "NF_REGISTERED")]
+    Registered,
+    #[serde(rename = "NF_DEREGISTERED")]
+    Deregistered,
+    #[serde(rename = "NF_PROFILE_CHANGED")]
+    ProfileChanged,
+}
+
+impl std::fmt::Display for NotificationEventType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            NotificationEventType::Registered => write!(f, "NF_REGISTERED"),
+            NotificationEventType::Deregistered => write!(f, "NF_DEREGISTERED"),
+            NotificationEventType::ProfileChanged => write!(f, "NF_PROFILE_CHANGED"),
+        }
+    }
+}
+
+impl std::str::FromStr for NotificationEventType {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "NF_REGISTERED" => std::result::Result::Ok(NotificationEventType::Registered),
+            "NF_DEREGISTERED" => std::result::Result::Ok(NotificationEventType::Deregistered),
+            "NF_PROFILE_CHANGED" => std::result::Result::Ok(NotificationEventType::ProfileChanged),
+            _ => std::result::Result::Err(format!("Value not valid: {}", s)),
+        }
+    }
+}
+
+/// Condition on the NF instances a subscription is about.
+///
+/// TS 29.510 defines it as a oneOf of single-attribute conditions; they are
+/// merged here so that each of them (de)serializes unchanged. When several
+/// attributes are set, all of them must match.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SubscrCond {
+    #[serde(rename = "nfInstanceId")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nf_instance_id: Option<uuid::Uuid>,
+
+    #[serde(rename = "nfType")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nf_type: Option<models::NfType>,
+
+    #[serde(rename = "serviceName")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service_name: Option<models::ServiceName>,
+}
+
+impl SubscrCond {
+    /// Returns true if the profile satisfies the condition.
+    pub fn matches(&self, profile: &nnrf_discovery_server::models::NfProfile) -> bool {
+        if let Some(ref nf_instance_id) = self.nf_instance_id {
+            if *nf_instance_id != profile.nf_instance_id {
+                return false;
+            }
+        }
+        if let Some(ref nf_type) = self.nf_type {
+            if *nf_type != profile.nf_type {
+                return false;
+            }
+        }
+        if let Some(ref service_name) = self.service_name {
+            let offered = profile
+                .nf_services
+                .iter()
+                .flatten()
+                .chain(profile.nf_service_list.iter().flat_map(|l| l.values()))
+                .any(|s| s.service_name == *service_name);
+            if !offered {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// Information of a subscription to notifications to NRF events, included in subscription requests and responses
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct SubscriptionData {
+    /// String providing an URI formatted according to RFC 3986.
+    #[serde(rename = "nfStatusNotificationUri")]
+    pub nf_status_notification_uri: String,
+
+    /// String uniquely identifying a NF instance. The format of the NF Instance ID shall be a  Universally Unique Identifier (UUID) version 4, as described in IETF RFC 4122.
+    #[serde(rename = "reqNfInstanceId")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub req_nf_instance_id: Option<uuid::Uuid>,
+
+    #[serde(rename = "subscrCond")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscr_cond: Option<models::SubscrCond>,
+
+    /// Assigned by the NRF when the subscription is created
+    #[serde(rename = "subscriptionId")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscription_id: Option<String>,
+
+    /// string with format 'date-time' as defined in OpenAPI.
+    #[serde(rename = "validityTime")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub validity_time: Option<chrono::DateTime<chrono::Utc>>,
+
+    #[serde(rename = "reqNotifEvents")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub req_notif_events: Option<Vec<models::NotificationEventType>>,
+
+    #[serde(rename = "reqNfType")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub req_nf_type: Option<models::NfType>,
+}
+
+impl SubscriptionData {
+    #[allow(clippy::new_without_default)]
+    pub fn new(nf_status_notification_uri: String) -> SubscriptionData {
+        SubscriptionData {
+            nf_status_notification_uri,
+            req_nf_instance_id: None,
+            subscr_cond: None,
+            subscription_id: None,
+            validity_time: None,
+            req_notif_events: None,
+            req_nf_type: None,
+        }
+    }
+
+    /// Returns true if the subscriber asked to be told about this event on
+    /// this profile.
+    pub fn wants(
+        &self,
+        event: NotificationEventType,
+        profile: &nnrf_discovery_server::models::NfProfile,
+    ) -> bool {
+        self.req_notif_events
+            .as_ref()
+            .map(|events| events.contains(&event))
+            .unwrap_or(true)
+            && self
+                .subscr_cond
+                .as_ref()
+                .map(|cond| cond.matches(profile))
+                .unwrap_or(true)
+    }
+}
+
+/// Converts Query Parameters representation (style=form, explode=false) to a SubscriptionData value
+/// as specified in https://swagger.io/docs/specification/serialization/
+/// Should be implemented in a serde deserializer
+impl std::str::FromStr for SubscriptionData {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        serde_json::from_str(s).map_err(|e| format!("Unable to parse SubscriptionData: {}", e))
+    }
+}
+
+/// Data sent in a NF status notification
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct NotificationData {
+    #[serde(rename = "event")]
+    pub event: models::NotificationEventType,
+
+    /// String providing an URI formatted according to RFC 3986.
+    #[serde(rename = "nfInstanceUri")]
+    pub nf_instance_uri: String,
+
+    /// Profile after the change; absent for NF_DEREGISTERED
+    #[serde(rename = "nfProfile")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nf_profile: Option<models::NfProfile>,
+}
+
+impl NotificationData {
+    #[allow(clippy::new_without_default)]
+    pub fn new(event: models::NotificationEventType, nf_instance_uri: String) -> NotificationData {
+        NotificationData {
+            event,
+            nf_instance_uri,
+            nf_profile: None,
+        }
+    }
+}
+
+/// Operations of a JSON Patch document (IETF RFC 6902)
+#[allow(non_camel_case_types)]
+#[repr(C)]
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, serde::Serialize, serde::Deserialize,
+)]
+pub enum PatchOperation {
+    #[serde(rename = "add")]
+    Add,
+    #[serde(rename = "copy")]
+    Copy,
+    #[serde(rename = "move")]
+    Move,
+    #[serde(rename = "remove")]
+    Remove,
+    #[serde(rename = "replace")]
+    Replace,
+    #[serde(rename = "test")]
+    Test,
+}
+
+impl std::fmt::Display for PatchOperation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            PatchOperation::Add => write!(f, "add"),
+            PatchOperation::Copy => write!(f, "copy"),
+            PatchOperation::Move => write!(f, "move"),
+            PatchOperation::Remove => write!(f, "remove"),
+            PatchOperation::Replace => write!(f, "replace"),
+            PatchOperation::Test => write!(f, "test"),
+        }
+    }
+}
+
+impl std::str::FromStr for PatchOperation {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s {
+            "add" => std::result::Result::Ok(PatchOperation::Add),
+            "copy" => std::result::Result::Ok(PatchOperation::Copy),
+            "move" => std::result::Result::Ok(PatchOperation::Move),
+            "remove" => std::result::Result::Ok(PatchOperation::Remove),
+            "replace" => std::result::Result::Ok(PatchOperation::Replace),
+            "test" => std::result::Result::Ok(PatchOperation::Test),
+            _ => std::result::Result::Err(format!("Value not valid: {}", s)),
+        }
+    }
+}
+
+/// it contains information on data to be changed
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct PatchItem {
+    #[serde(rename = "op")]
+    pub op: models::PatchOperation,
+
+    /// contains a JSON pointer value (as defined in IETF RFC 6901) that references a location of a resource on which the patch operation shall be performed.
+    #[serde(rename = "path")]
+    pub path: String,
+
+    /// indicates the path of the source JSON element (according to JSON Pointer syntax) being moved or copied to the location indicated by the \"path\" attribute.
+    #[serde(rename = "from")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+
+    #[serde(rename = "value")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<serde_json::Value>,
+}
+
+impl PatchItem {
+    #[allow(clippy::new_without_default)]
+    pub fn new(op: models::PatchOperation, path: String) -> PatchItem {
+        PatchItem {
+            op,
+            path,
+            from: None,
+            value: None,
+        }
+    }
+
+    /// The patch document an NF sends as heartbeat (TS 29.510, 5.2.2.3.2).
+    pub fn heartbeat() -> Vec<PatchItem> {
+        vec![PatchItem {
+            op: PatchOperation::Replace,
+            path: "/nfStatus".to_string(),
+            from: None,
+            value: Some(serde_json::Value::String(
+                NfStatusAnyOf::Registered.to_string(),
+            )),
+        }]
+    }
+
+    /// Returns true if the patch document is a plain heartbeat, i.e. it does
+    /// not change anything but the NF status or load.
+    pub fn is_heartbeat(patch: &[PatchItem]) -> bool {
+        patch.iter().all(|item| {
+            item.op == PatchOperation::Replace
+                && matches!(item.path.as_str(), "/nfStatus" | "/load" | "/loadTimeStamp")
+        })
+    }
+}
+
+/// Applies a JSON Patch document (IETF RFC 6902) to a JSON value.
+///
+/// The document is applied atomically: on error the value is left untouched.
+pub fn apply_patch(value: &mut serde_json::Value, patch: &[PatchItem]) -> Result<(), String> {
+    let mut patched = value.clone();
+
+    for item in patch {
+        match item.op {
+            PatchOperation::Add => {
+                let new = item
+                    .value
+                    .clone()
+                    .ok_or_else(|| format!("Missing value for add on {}", item.path))?;
+                pointer_insert(&mut patched, &item.path, new, true)?;
+            }
+            PatchOperation::Replace => {
+                let new = item
+                    .value
+                    .clone()
+                    .ok_or_else(|| format!("Missing value for replace on {}", item.path))?;
+                pointer_insert(&mut patched, &item.path, new, false)?;
+            }
+            PatchOperation::Remove => {
+                pointer_remove(&mut patched, &item.path)?;
+            }
+            PatchOperation::Copy | PatchOperation::Move => {
+                let from = item
+                    .from
+                    .as_deref()
+                    .ok_or_else(|| format!("Missing from for {} on {}", item.op, item.path))?;
+                let new = patched
+                    .pointer(from)
+                    .cloned()
+                    .ok_or_else(|| format!("Path not found: {}", from))?;
+                if item.op == PatchOperation::Move {
+                    pointer_remove(&mut patched, from)?;
+                }
+                pointer_insert(&mut patched, &item.path, new, true)?;
+            }
+            PatchOperation::Test => {
+                if patched.pointer(&item.path) != item.value.as_ref() {
+                    return Err(format!("Test failed on {}", item.path));
+                }
+            }
+        }
+    }
+
+    *value = patched;
+    Ok(())
+}
+
+fn split_pointer(path: &str) -> Result<(&str, String), String> {
+    let index = path
+        .rfind('/')
+        .ok_or_else(|| format!("Invalid JSON pointer: {}", path))?;
+    let key = path[index + 1..].replace("~1", "/").replace("~0", "~");
+    Ok((&path[..index], key))
+}
+
+fn pointer_insert(
+    value: &mut serde_json::Value,
+    path: &str,
+    new: serde_json::Value,
+    add: bool,
+) -> Result<(), String> {
+    let (parent, key) = split_pointer(path)?;
+    let parent = value
+        .pointer_mut(parent)
+        .ok_or_else(|| format!("Path not found: {}", path))?;
+
+    match parent {
+        serde_json::Value::Object(map) => {
+            if !add && !map.contains_key(&key) {
+                return Err(format!("Path not found: {}", path));
+            }
+            map.insert(key, new);
+        }
+        serde_json::Value::Array(list) if add && key == "-" => list.push(new),
+        serde_json::Value::Array(list) => {
+            let index = key
+                .parse::<usize>()
+                .map_err(|_| format!("Invalid array index in {}", path))?;
+            match (add, index) {
+                (true, i) if i <= list.len() => list.insert(i, new),
+                (false, i) if i < list.len() => list[i] = new,
+                _ => return Err(format!("Array index out of bounds in {}", path)),
+            }
+        }
+        _ => return Err(format!("Path not found: {}", path)),
+    }
+    Ok(())
+}
+
+fn pointer_remove(value: &mut serde_json::Value, path: &str) -> Result<(), String> {
+    let (parent, key) = split_pointer(path)?;
+    let removed = match value.pointer_mut(parent) {
+        Some(serde_json::Value::Object(map)) => map.remove(&key),
+        Some(serde_json::Value::Array(list)) => match key.parse::<usize>() {
+            Ok(index) if index < list.len() => Some(list.remove(index)),
+            _ => None,
+        },
+        _ => None,
+    };
+    removed
+        .map(|_| ())
+        .ok_or_else(|| format!("Path not found: {}", path))
+}
@@ -0,0 +1,138 @@
+//! Registration of an NF with the NRF, kept alive by heartbeats.
+//!
+//! An NF builds its profile with [`nf_profile`] and spawns [`maintain`] with a
+//! client bound to the NRF. The task registers the profile, sends the
+//! heartbeats, and registers again if the NRF lost the profile (NRF restart or
+//! missed heartbeats).
+
+use log::{info, warn};
+use std::time::Duration;
+
+use crate::models::{self, PatchItem};
+use crate::{ApiNoContext, RegisterNfInstanceResponse, UpdateNfInstanceResponse};
+
+/// Heartbeat period requested when registering.
+pub const HEART_BEAT_TIMER: i32 = 10;
+
+/// Delay before retrying a registration the NRF did not accept.
+const RETRY_PERIOD: Duration = Duration::from_secs(5);
+
+/// Builds the profile of an NF exposing a single service on `host:port`.
+#[allow(clippy::too_many_arguments)]
+pub fn nf_profile(
+    nf_instance_id: uuid::Uuid,
+    nf_type: models::NfType,
+    service_name: &str,
+    api_version_in_uri: &str,
+    api_full_version: &str,
+    scheme: &str,
+    host: &str,
+    port: u16,
+) -> models::NfProfile {
+    let mut service = models::NfService::new(
+        uuid::Uuid::new_v4().to_string(),
+        models::ServiceName::new(service_name.to_string()),
+        vec![models::NfServiceVersion::new(
+            api_version_in_uri.to_string(),
+            api_full_version.to_string(),
+        )],
+        models::UriScheme::new(scheme.to_string()),
+        models::NfServiceStatus::new(models::NfStatusAnyOf::Registered.to_string()),
+    );
+    let mut endpoint = models::IpEndPoint::new();
+    endpoint.ipv4_address = Some(host.to_string());
+    endpoint.port = Some(port);
+    service.ip_end_points = Some(vec![endpoint]);
+
+    let mut profile = nnrf_discovery_server::models::NfProfile::new(
+        nf_instance_id,
+        nf_type,
+        models::NfStatus::new(models::NfStatusAnyOf::Registered.to_string()),
+    );
+    profile.ipv4_addresses = Some(vec![models::Ipv4Addr::from(host.to_string())]);
+    profile.priority = Some(0);
+    profile.capacity = Some(100);
+    profile.load = Some(0);
+    profile.nf_services = Some(vec![service]);
+
+    let mut profile = models::NfProfile::new(profile);
+    profile.heart_beat_timer = Some(HEART_BEAT_TIMER);
+    profile.nf_profile_changes_support_ind = Some(true);
+    profile
+}
+
+/// Registers `profile` and keeps the registration alive. Never returns.
+///
+/// Heartbeats are sent every half period granted by the NRF, so a single lost
+/// heartbeat does not get the NF suspended.
+pub async fn maintain<A, C>(nrf: A, profile: models::NfProfile)
+where
+    A: ApiNoContext<C> + Send + Sync,
+    C: Send + Sync,
+{
+    let nf_instance_id = profile.profile.nf_instance_id;
+
+    loop {
+        let heart_beat_timer = match register(&nrf, &profile).await {
+            Some(timer) => timer,
+            None => {
+                tokio::time::sleep(RETRY_PERIOD).await;
+                continue;
+            }
+        };
+        let period = Duration::from_secs(heart_beat_timer.max(1) as u64) / 2;
+
+        loop {
+            tokio::time::sleep(period).await;
+
+            match nrf
+                .update_nf_instance(nf_instance_id, &PatchItem::heartbeat(), None, None, None)
+                .await
+            {
+                Ok(UpdateNfInstanceResponse::NoContent)
+                | Ok(UpdateNfInstanceResponse::ExpectedResponseToAValidRequest { .. }) => {}
+                Ok(UpdateNfInstanceResponse::NotFound(_)) => {
+                    warn!("NRF lost NF instance {}, registering again", nf_instance_id);
+                    break;
+                }
+                Ok(rsp) => warn!("Unexpected heartbeat response: {:?}", rsp),
+                Err(e) => warn!("Heartbeat failed: {}", e),
+            }
+        }
+    }
+}
+
+/// Registers the profile, returning the heartbeat period granted by the NRF.
+async fn register<A, C>(nrf: &A, profile: &models::NfProfile) -> Option<i32>
+where
+    A: ApiNoContext<C> + Send + Sync,
+    C: Send + Sync,
+{
+    let nf_instance_id = profile.profile.nf_instance_id;
+
+    match nrf
+        .register_nf_instance(nf_instance_id, profile.clone(), None, None)
+        .await
+    {
+        Ok(RegisterNfInstanceResponse::Created { body, .. })
+        | Ok(RegisterNfInstanceResponse::OK { body, .. }) => {
+            info!(
+                "Registered {:?} {} with the NRF",
+                body.profile.nf_type, nf_instance_id
+            );
+            Some(
+                body.heart_beat_timer
+                    .or(profile.heart_beat_timer)
+                    .unwrap_or(HEART_BEAT_TIMER),
+            )
+        }
+        Ok(rsp) => {
+            warn!("NRF rejected the registration: {:?}", rsp);
+            None
+        }
+        Err(e) => {
+            warn!("Unable to register with the NRF: {}", e);
+            None
+        }
+    }
+}
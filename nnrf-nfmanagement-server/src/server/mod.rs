@@ -0,0 +1,953 @@
+use futures::{future, future::BoxFuture, future::FutureExt, stream, stream::TryStreamExt, Stream};
+use hyper::header::{HeaderName, HeaderValue, CONTENT_TYPE};
+use hyper::{Body, HeaderMap, Request, Response, StatusCode};
+use log::warn;
+#[allow(unused_imports)]
+use std::convert::{TryFrom, TryInto};
+use std::error::Error;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::task::{Context, Poll};
+pub use swagger::auth::Authorization;
+use swagger::auth::Scopes;
+use swagger::{ApiError, BodyExt, Has, RequestParser, XSpanIdString};
+use url::form_urlencoded;
+
+use crate::header;
+#[allow(unused_imports)]
+use crate::models;
+
+pub use crate::context;
+
+type ServiceFuture = BoxFuture<'static, Result<Response<Body>, crate::ServiceError>>;
+
+use crate::{
+    Api, CreateSubscriptionResponse, DeregisterNfInstanceResponse, GetNfInstanceResponse,
+    RegisterNfInstanceResponse, RemoveSubscriptionResponse, UpdateNfInstanceResponse,
+};
+
+pub mod callbacks;
+
+mod paths {
+    use lazy_static::lazy_static;
+
+    lazy_static! {
+        pub static ref GLOBAL_REGEX_SET: regex::RegexSet = regex::RegexSet::new(vec![
+            r"^/nnrf-nfm/v1/nf-instances/(?P<nfInstanceID>[^/?#]*)$",
+            r"^/nnrf-nfm/v1/subscriptions$",
+            r"^/nnrf-nfm/v1/subscriptions/(?P<subscriptionID>[^/?#]*)$"
+        ])
+        .expect("Unable to create global regex set");
+    }
+    pub(crate) static ID_NF_INSTANCES_NFINSTANCEID: usize = 0;
+    lazy_static! {
+        pub static ref REGEX_NF_INSTANCES_NFINSTANCEID: regex::Regex =
+            #[allow(clippy::invalid_regex)]
+            regex::Regex::new(r"^/nnrf-nfm/v1/nf-instances/(?P<nfInstanceID>[^/?#]*)$")
+                .expect("Unable to create regex for NF_INSTANCES_NFINSTANCEID");
+    }
+    pub(crate) static ID_SUBSCRIPTIONS: usize = 1;
+    pub(crate) static ID_SUBSCRIPTIONS_SUBSCRIPTIONID: usize = 2;
+    lazy_static! {
+        pub static ref REGEX_SUBSCRIPTIONS_SUBSCRIPTIONID: regex::Regex =
+            #[allow(clippy::invalid_regex)]
+            regex::Regex::new(r"^/nnrf-nfm/v1/subscriptions/(?P<subscriptionID>[^/?#]*)$")
+                .expect("Unable to create regex for SUBSCRIPTIONS_SUBSCRIPTIONID");
+    }
+}
+
+pub struct MakeService<T, C>
+where
+    T: Api<C> + Clone + Send + 'static,
+    C: Has<XSpanIdString> + Has<Option<Authorization>> + Send + Sync + 'static,
+{
+    api_impl: T,
+    marker: PhantomData<C>,
+}
+
+impl<T, C> MakeService<T, C>
+where
+    T: Api<C> + Clone + Send + 'static,
+    C: Has<XSpanIdString> + Has<Option<Authorization>> + Send + Sync + 'static,
+{
+    pub fn new(api_impl: T) -> Self {
+        MakeService {
+            api_impl,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, C, Target> hyper::service::Service<Target> for MakeService<T, C>
+where
+    T: Api<C> + Clone + Send + 'static,
+    C: Has<XSpanIdString> + Has<Option<Authorization>> + Send + Sync + 'static,
+{
+    type Response = Service<T, C>;
+    type Error = crate::ServiceError;
+    type Future = future::Ready<Result<Self::Response, Self::Error>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, target: Target) -> Self::Future {
+        futures::future::ok(Service::new(self.api_impl.clone()))
+    }
+}
+
+fn method_not_allowed() -> Result<Response<Body>, crate::ServiceError> {
+    Ok(Response::builder()
+        .status(StatusCode::METHOD_NOT_ALLOWED)
+        .body(Body::empty())
+        .expect("Unable to create Method Not Allowed response"))
+}
+
+fn bad_request(message: String) -> Result<Response<Body>, crate::ServiceError> {
+    Ok(Response::builder()
+        .status(StatusCode::BAD_REQUEST)
+        .body(Body::from(message))
+        .expect("Unable to create Bad Request response"))
+}
+
+/// Checks that the request carries the `nnrf-nfm` scope when OAuth2 is in use.
+fn authorize<C>(context: &C) -> Option<Response<Body>>
+where
+    C: Has<Option<Authorization>>,
+{
+    let authorization = match *(context as &dyn Has<Option<Authorization>>).get() {
+        Some(ref authorization) => authorization,
+        None => {
+            return Some(
+                Response::builder()
+                    .status(StatusCode::FORBIDDEN)
+                    .body(Body::from("Unauthenticated"))
+                    .expect("Unable to create Authentication Forbidden response"),
+            )
+        }
+    };
+
+    // Authorization
+    if let Scopes::Some(ref scopes) = authorization.scopes {
+        let required_scopes: std::collections::BTreeSet<String> = vec![
+            "nnrf-nfm".to_string(), // Access to the Nnrf_NFManagement API
+        ]
+        .into_iter()
+        .collect();
+
+        if !required_scopes.is_subset(scopes) {
+            let missing_scopes = required_scopes.difference(scopes);
+            return Some(
+                Response::builder()
+                    .status(StatusCode::FORBIDDEN)
+                    .body(Body::from(missing_scopes.fold(
+                        "Insufficient authorization, missing scopes".to_string(),
+                        |s, scope| format!("{} {}", s, scope),
+                    )))
+                    .expect("Unable to create Authentication Insufficient response"),
+            );
+        }
+    }
+
+    None
+}
+
+/// Reads an optional string header parameter.
+fn header_param(headers: &HeaderMap, name: &'static str) -> Result<Option<String>, String> {
+    match headers.get(HeaderName::from_static(name)) {
+        Some(v) => match header::IntoHeaderValue::<String>::try_from((*v).clone()) {
+            Ok(result) => Ok(Some(result.0)),
+            Err(err) => Err(format!("Invalid header {} - {}", name, err)),
+        },
+        None => Ok(None),
+    }
+}
+
+/// Sets an optional string response header.
+fn set_header(
+    response: &mut Response<Body>,
+    name: &'static str,
+    value: Option<String>,
+) -> Result<(), Response<Body>> {
+    if let Some(value) = value {
+        let value = match header::IntoHeaderValue(value).try_into() {
+            Ok(val) => val,
+            Err(e) => {
+                return Err(Response::builder()
+                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .body(Body::from(format!(
+                        "An internal server error occurred handling {} header - {}",
+                        name, e
+                    )))
+                    .expect("Unable to create Internal Server Error for invalid response header"))
+            }
+        };
+        response
+            .headers_mut()
+            .insert(HeaderName::from_static(name), value);
+    }
+    Ok(())
+}
+
+fn set_json_body<T: serde::Serialize>(
+    response: &mut Response<Body>,
+    status: u16,
+    content_type: &'static str,
+    body: &T,
+) {
+    *response.status_mut() =
+        StatusCode::from_u16(status).expect("Unable to turn into a StatusCode");
+    response
+        .headers_mut()
+        .insert(CONTENT_TYPE, HeaderValue::from_static(content_type));
+    let body = serde_json::to_string(body).expect("impossible to fail to serialize");
+    *response.body_mut() = Body::from(body);
+}
+
+fn set_problem(response: &mut Response<Body>, status: u16, body: &models::ProblemDetails) {
+    set_json_body(response, status, "application/problem+json", body)
+}
+
+pub struct Service<T, C>
+where
+    T: Api<C> + Clone + Send + 'static,
+    C: Has<XSpanIdString> + Has<Option<Authorization>> + Send + Sync + 'static,
+{
+    api_impl: T,
+    marker: PhantomData<C>,
+}
+
+impl<T, C> Service<T, C>
+where
+    T: Api<C> + Clone + Send + 'static,
+    C: Has<XSpanIdString> + Has<Option<Authorization>> + Send + Sync + 'static,
+{
+    pub fn new(api_impl: T) -> Self {
+        Service {
+            api_impl,
+            marker: PhantomData,
+        }
+    }
+}
+
+impl<T, C> Clone for Service<T, C>
+where
+    T: Api<C> + Clone + Send + 'static,
+    C: Has<XSpanIdString> + Has<Option<Authorization>> + Send + Sync + 'static,
+{
+    fn clone(&self) -> Self {
+        Service {
+            api_impl: self.api_impl.clone(),
+            marker: self.marker,
+        }
+    }
+}
+
+impl<T, C> hyper::service::Service<(Request<Body>, C)> for Service<T, C>
+where
+    T: Api<C> + Clone + Send + Sync + 'static,
+    C: Has<XSpanIdString> + Has<Option<Authorization>> + Send + Sync + 'static,
+{
+    type Response = Response<Body>;
+    type Error = crate::ServiceError;
+    type Future = ServiceFuture;
+
+    fn poll_ready(&mut self, cx: &mut Context) -> Poll<Result<(), Self::Error>> {
+        self.api_impl.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: (Request<Body>, C)) -> Self::Future {
+        async fn run<T, C>(
+            mut api_impl: T,
+            req: (Request<Body>, C),
+        ) -> Result<Response<Body>, crate::ServiceError>
+        where
+            T: Api<C> + Clone + Send + 'static,
+            C: Has<XSpanIdString> + Has<Option<Authorization>> + Send + Sync + 'static,
+        {
+            let (request, context) = req;
+            let (parts, body) = request.into_parts();
+            let (method, uri, headers) = (parts.method, parts.uri, parts.headers);
+            let path = paths::GLOBAL_REGEX_SET.matches(uri.path());
+
+            if !path.matched_any() {
+                return Ok(Response::builder()
+                    .status(StatusCode::NOT_FOUND)
+                    .body(Body::empty())
+                    .expect("Unable to create Not Found response"));
+            }
+
+            if let Some(response) = authorize(&context) {
+                return Ok(response);
+            }
+
+            if !path.matched(paths::ID_NF_INSTANCES_NFINSTANCEID) {
+                return subscriptions(api_impl, method, uri, headers, body, context).await;
+            }
+
+            // Path parameters
+            let path: &str = uri.path();
+            let path_params = paths::REGEX_NF_INSTANCES_NFINSTANCEID
+                .captures(path)
+                .unwrap_or_else(|| {
+                    panic!(
+                        "Path {} matched RE NF_INSTANCES_NFINSTANCEID in set but failed match against \"{}\"",
+                        path,
+                        paths::REGEX_NF_INSTANCES_NFINSTANCEID.as_str()
+                    )
+                });
+
+            let param_nf_instance_id =
+                match percent_encoding::percent_decode(path_params["nfInstanceID"].as_bytes())
+                    .decode_utf8()
+                {
+                    Ok(param_nf_instance_id) => match param_nf_instance_id.parse::<uuid::Uuid>() {
+                        Ok(param_nf_instance_id) => param_nf_instance_id,
+                        Err(e) => {
+                            return bad_request(format!(
+                                "Couldn't parse path parameter nfInstanceID: {}",
+                                e
+                            ))
+                        }
+                    },
+                    Err(_) => {
+                        return bad_request(format!(
+                            "Couldn't percent-decode path parameter as UTF-8: {}",
+                            &path_params["nfInstanceID"]
+                        ))
+                    }
+                };
+
+            // Header parameters
+            let param_content_encoding = match header_param(&headers, "content-encoding") {
+                Ok(value) => value,
+                Err(e) => return bad_request(e),
+            };
+            let param_accept_encoding = match header_param(&headers, "accept-encoding") {
+                Ok(value) => value,
+                Err(e) => return bad_request(e),
+            };
+            let param_if_match = match header_param(&headers, "if-match") {
+                Ok(value) => value,
+                Err(e) => return bad_request(e),
+            };
+
+            let mut response = Response::new(Body::empty());
+            response.headers_mut().insert(
+                HeaderName::from_static("x-span-id"),
+                HeaderValue::from_str(
+                    (&context as &dyn Has<XSpanIdString>)
+                        .get()
+                        .0
+                        .clone()
+                        .as_str(),
+                )
+                .expect("Unable to create X-Span-ID header value"),
+            );
+
+            match method {
+                // GetNFInstance - GET /nf-instances/{nfInstanceID}
+                hyper::Method::GET => {
+                    // Query parameters (note that non-required or collection query parameters will ignore garbage values, rather than causing a 400 response)
+                    let query_params =
+                        form_urlencoded::parse(uri.query().unwrap_or_default().as_bytes())
+                            .collect::<Vec<_>>();
+                    let param_requester_features = query_params
+                        .iter()
+                        .find(|e| e.0 == "requester-features")
+                        .map(|e| e.1.to_string());
+
+                    let result = api_impl
+                        .get_nf_instance(
+                            param_nf_instance_id,
+                            param_requester_features,
+                            param_accept_encoding,
+                            &context,
+                        )
+                        .await;
+
+                    match result {
+                        Ok(rsp) => match rsp {
+                            GetNfInstanceResponse::ExpectedResponseToAValidRequest {
+                                body,
+                                e_tag,
+                                content_encoding,
+                            } => {
+                                if let Err(response) = set_header(&mut response, "etag", e_tag)
+                                    .and_then(|_| {
+                                        set_header(
+                                            &mut response,
+                                            "content-encoding",
+                                            content_encoding,
+                                        )
+                                    })
+                                {
+                                    return Ok(response);
+                                }
+                                set_json_body(&mut response, 200, "application/json", &body);
+                            }
+                            GetNfInstanceResponse::BadRequest(body) => {
+                                set_problem(&mut response, 400, &body)
+                            }
+                            GetNfInstanceResponse::Unauthorized(body) => {
+                                set_problem(&mut response, 401, &body)
+                            }
+                            GetNfInstanceResponse::Forbidden(body) => {
+                                set_problem(&mut response, 403, &body)
+                            }
+                            GetNfInstanceResponse::NotFound(body) => {
+                                set_problem(&mut response, 404, &body)
+                            }
+                            GetNfInstanceResponse::InternalServerError(body) => {
+                                set_problem(&mut response, 500, &body)
+                            }
+                            GetNfInstanceResponse::ServiceUnavailable(body) => {
+                                set_problem(&mut response, 503, &body)
+                            }
+                            GetNfInstanceResponse::GenericError => {
+                                *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+                            }
+                        },
+                        Err(_) => {
+                            // Application code returned an error. This should not happen, as the implementation should
+                            // return a valid response.
+                            *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+                            *response.body_mut() = Body::from("An internal error occurred");
+                        }
+                    }
+
+                    Ok(response)
+                }
+
+                // RegisterNFInstance - PUT /nf-instances/{nfInstanceID}
+                hyper::Method::PUT => {
+                    // Body parameters (note that non-required body parameters will ignore garbage
+                    // values, rather than causing a 400 response). Produce warning header and logs for
+                    // any unused fields.
+                    let body = match body.into_raw().await {
+                        Ok(body) => body,
+                        Err(e) => {
+                            return bad_request(format!(
+                                "Couldn't read body parameter NfProfile: {}",
+                                e
+                            ))
+                        }
+                    };
+                    let mut unused_elements = Vec::new();
+                    let deserializer = &mut serde_json::Deserializer::from_slice(&*body);
+                    let param_nf_profile: models::NfProfile =
+                        match serde_ignored::deserialize(deserializer, |path| {
+                            warn!("Ignoring unknown field in body: {}", path);
+                            unused_elements.push(path.to_string());
+                        }) {
+                            Ok(param_nf_profile) => param_nf_profile,
+                            Err(e) => {
+                                return bad_request(format!(
+                            "Couldn't parse body parameter NfProfile - doesn't match schema: {}",
+                            e
+                        ))
+                            }
+                        };
+
+                    let result = api_impl
+                        .register_nf_instance(
+                            param_nf_instance_id,
+                            param_nf_profile,
+                            param_content_encoding,
+                            param_accept_encoding,
+                            &context,
+                        )
+                        .await;
+
+                    if !unused_elements.is_empty() {
+                        response.headers_mut().insert(
+                            HeaderName::from_static("warning"),
+                            HeaderValue::from_str(
+                                format!("Ignoring unknown fields in body: {:?}", unused_elements)
+                                    .as_str(),
+                            )
+                            .expect("Unable to create Warning header value"),
+                        );
+                    }
+
+                    match result {
+                        Ok(rsp) => match rsp {
+                            RegisterNfInstanceResponse::OK {
+                                body,
+                                accept_encoding,
+                                e_tag,
+                                content_encoding,
+                            } => {
+                                if let Err(response) =
+                                    set_header(&mut response, "accept-encoding", accept_encoding)
+                                        .and_then(|_| set_header(&mut response, "etag", e_tag))
+                                        .and_then(|_| {
+                                            set_header(
+                                                &mut response,
+                                                "content-encoding",
+                                                content_encoding,
+                                            )
+                                        })
+                                {
+                                    return Ok(response);
+                                }
+                                set_json_body(&mut response, 200, "application/json", &body);
+                            }
+                            RegisterNfInstanceResponse::Created {
+                                body,
+                                location,
+                                accept_encoding,
+                                e_tag,
+                                content_encoding,
+                            } => {
+                                if let Err(response) =
+                                    set_header(&mut response, "location", Some(location))
+                                        .and_then(|_| {
+                                            set_header(
+                                                &mut response,
+                                                "accept-encoding",
+                                                accept_encoding,
+                                            )
+                                        })
+                                        .and_then(|_| set_header(&mut response, "etag", e_tag))
+                                        .and_then(|_| {
+                                            set_header(
+                                                &mut response,
+                                                "content-encoding",
+                                                content_encoding,
+                                            )
+                                        })
+                                {
+                                    return Ok(response);
+                                }
+                                set_json_body(&mut response, 201, "application/json", &body);
+                            }
+                            RegisterNfInstanceResponse::BadRequest(body) => {
+                                set_problem(&mut response, 400, &body)
+                            }
+                            RegisterNfInstanceResponse::Unauthorized(body) => {
+                                set_problem(&mut response, 401, &body)
+                            }
+                            RegisterNfInstanceResponse::Forbidden(body) => {
+                                set_problem(&mut response, 403, &body)
+                            }
+                            RegisterNfInstanceResponse::NotFound(body) => {
+                                set_problem(&mut response, 404, &body)
+                            }
+                            RegisterNfInstanceResponse::InternalServerError(body) => {
+                                set_problem(&mut response, 500, &body)
+                            }
+                            RegisterNfInstanceResponse::ServiceUnavailable(body) => {
+                                set_problem(&mut response, 503, &body)
+                            }
+                            RegisterNfInstanceResponse::GenericError => {
+                                *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+                            }
+                        },
+                        Err(_) => {
+                            // Application code returned an error. This should not happen, as the implementation should
+                            // return a valid response.
+                            *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+                            *response.body_mut() = Body::from("An internal error occurred");
+                        }
+                    }
+
+                    Ok(response)
+                }
+
+                // UpdateNFInstance - PATCH /nf-instances/{nfInstanceID}
+                hyper::Method::PATCH => {
+                    let body = match body.into_raw().await {
+                        Ok(body) => body,
+                        Err(e) => {
+                            return bad_request(format!(
+                                "Couldn't read body parameter PatchItem: {}",
+                                e
+                            ))
+                        }
+                    };
+                    let param_patch_item: Vec<models::PatchItem> =
+                        match serde_json::from_slice(&body) {
+                            Ok(param_patch_item) => param_patch_item,
+                            Err(e) => {
+                                return bad_request(format!(
+                            "Couldn't parse body parameter PatchItem - doesn't match schema: {}",
+                            e
+                        ))
+                            }
+                        };
+
+                    let result = api_impl
+                        .update_nf_instance(
+                            param_nf_instance_id,
+                            param_patch_item.as_ref(),
+                            param_content_encoding,
+                            param_accept_encoding,
+                            param_if_match,
+                            &context,
+                        )
+                        .await;
+
+                    match result {
+                        Ok(rsp) => match rsp {
+                            UpdateNfInstanceResponse::ExpectedResponseToAValidRequest {
+                                body,
+                                accept_encoding,
+                                e_tag,
+                                content_encoding,
+                            } => {
+                                if let Err(response) =
+                                    set_header(&mut response, "accept-encoding", accept_encoding)
+                                        .and_then(|_| set_header(&mut response, "etag", e_tag))
+                                        .and_then(|_| {
+                                            set_header(
+                                                &mut response,
+                                                "content-encoding",
+                                                content_encoding,
+                                            )
+                                        })
+                                {
+                                    return Ok(response);
+                                }
+                                set_json_body(&mut response, 200, "application/json", &body);
+                            }
+                            UpdateNfInstanceResponse::NoContent => {
+                                *response.status_mut() = StatusCode::NO_CONTENT;
+                            }
+                            UpdateNfInstanceResponse::BadRequest(body) => {
+                                set_problem(&mut response, 400, &body)
+                            }
+                            UpdateNfInstanceResponse::Unauthorized(body) => {
+                                set_problem(&mut response, 401, &body)
+                            }
+                            UpdateNfInstanceResponse::Forbidden(body) => {
+                                set_problem(&mut response, 403, &body)
+                            }
+                            UpdateNfInstanceResponse::NotFound(body) => {
+                                set_problem(&mut response, 404, &body)
+                            }
+                            UpdateNfInstanceResponse::Conflict(body) => {
+                                set_problem(&mut response, 409, &body)
+                            }
+                            UpdateNfInstanceResponse::PreconditionFailed(body) => {
+                                set_problem(&mut response, 412, &body)
+                            }
+                            UpdateNfInstanceResponse::InternalServerError(body) => {
+                                set_problem(&mut response, 500, &body)
+                            }
+                            UpdateNfInstanceResponse::ServiceUnavailable(body) => {
+                                set_problem(&mut response, 503, &body)
+                            }
+                            UpdateNfInstanceResponse::GenericError => {
+                                *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+                            }
+                        },
+                        Err(_) => {
+                            // Application code returned an error. This should not happen, as the implementation should
+                            // return a valid response.
+                            *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+                            *response.body_mut() = Body::from("An internal error occurred");
+                        }
+                    }
+
+                    Ok(response)
+                }
+
+                // DeregisterNFInstance - DELETE /nf-instances/{nfInstanceID}
+                hyper::Method::DELETE => {
+                    let result = api_impl
+                        .deregister_nf_instance(param_nf_instance_id, &context)
+                        .await;
+
+                    match result {
+                        Ok(rsp) => match rsp {
+                            DeregisterNfInstanceResponse::ExpectedResponseToASuccessfulDeregistration => {
+                                *response.status_mut() = StatusCode::NO_CONTENT;
+                            }
+                            DeregisterNfInstanceResponse::BadRequest(body) => {
+                                set_problem(&mut response, 400, &body)
+                            }
+                            DeregisterNfInstanceResponse::Unauthorized(body) => {
+                                set_problem(&mut response, 401, &body)
+                            }
+                            DeregisterNfInstanceResponse::Forbidden(body) => {
+                                set_problem(&mut response, 403, &body)
+                            }
+                            DeregisterNfInstanceResponse::NotFound(body) => {
+                                set_problem(&mut response, 404, &body)
+                            }
+                            DeregisterNfInstanceResponse::InternalServerError(body) => {
+                                set_problem(&mut response, 500, &body)
+                            }
+                            DeregisterNfInstanceResponse::ServiceUnavailable(body) => {
+                                set_problem(&mut response, 503, &body)
+                            }
+                            DeregisterNfInstanceResponse::GenericError => {
+                                *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+                            }
+                        },
+                        Err(_) => {
+                            // Application code returned an error. This should not happen, as the implementation should
+                            // return a valid response.
+                            *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+                            *response.body_mut() = Body::from("An internal error occurred");
+                        }
+                    }
+
+                    Ok(response)
+                }
+
+                _ => method_not_allowed(),
+            }
+        }
+        Box::pin(run(self.api_impl.clone(), req))
+    }
+}
+
+/// Handles the requests on the subscriptions collection and its items.
+async fn subscriptions<T, C>(
+    api_impl: T,
+    method: hyper::Method,
+    uri: hyper::Uri,
+    headers: HeaderMap,
+    body: Body,
+    context: C,
+) -> Result<Response<Body>, crate::ServiceError>
+where
+    T: Api<C> + Clone + Send + 'static,
+    C: Has<XSpanIdString> + Has<Option<Authorization>> + Send + Sync + 'static,
+{
+    let path = paths::GLOBAL_REGEX_SET.matches(uri.path());
+
+    let mut response = Response::new(Body::empty());
+    response.headers_mut().insert(
+        HeaderName::from_static("x-span-id"),
+        HeaderValue::from_str(
+            (&context as &dyn Has<XSpanIdString>)
+                .get()
+                .0
+                .clone()
+                .as_str(),
+        )
+        .expect("Unable to create X-Span-ID header value"),
+    );
+
+    match method {
+        // CreateSubscription - POST /subscriptions
+        hyper::Method::POST if path.matched(paths::ID_SUBSCRIPTIONS) => {
+            // Header parameters
+            let param_content_encoding = match header_param(&headers, "content-encoding") {
+                Ok(value) => value,
+                Err(e) => return bad_request(e),
+            };
+            let param_accept_encoding = match header_param(&headers, "accept-encoding") {
+                Ok(value) => value,
+                Err(e) => return bad_request(e),
+            };
+
+            // Body parameters. Produce warning header and logs for any unused fields.
+            let body = match body.into_raw().await {
+                Ok(body) => body,
+                Err(e) => {
+                    return bad_request(format!(
+                        "Couldn't read body parameter SubscriptionData: {}",
+                        e
+                    ))
+                }
+            };
+            let mut unused_elements = Vec::new();
+            let deserializer = &mut serde_json::Deserializer::from_slice(&*body);
+            let param_subscription_data: models::SubscriptionData =
+                match serde_ignored::deserialize(deserializer, |path| {
+                    warn!("Ignoring unknown field in body: {}", path);
+                    unused_elements.push(path.to_string());
+                }) {
+                    Ok(param_subscription_data) => param_subscription_data,
+                    Err(e) => return bad_request(format!(
+                        "Couldn't parse body parameter SubscriptionData - doesn't match schema: {}",
+                        e
+                    )),
+                };
+
+            let result = api_impl
+                .create_subscription(
+                    param_subscription_data,
+                    param_content_encoding,
+                    param_accept_encoding,
+                    &context,
+                )
+                .await;
+
+            if !unused_elements.is_empty() {
+                response.headers_mut().insert(
+                    HeaderName::from_static("warning"),
+                    HeaderValue::from_str(
+                        format!("Ignoring unknown fields in body: {:?}", unused_elements).as_str(),
+                    )
+                    .expect("Unable to create Warning header value"),
+                );
+            }
+
+            match result {
+                Ok(rsp) => match rsp {
+                    CreateSubscriptionResponse::ExpectedResponseToAValidRequest {
+                        body,
+                        location,
+                        accept_encoding,
+                        content_encoding,
+                    } => {
+                        if let Err(response) = set_header(&mut response, "location", Some(location))
+                            .and_then(|_| {
+                                set_header(&mut response, "accept-encoding", accept_encoding)
+                            })
+                            .and_then(|_| {
+                                set_header(&mut response, "content-encoding", content_encoding)
+                            })
+                        {
+                            return Ok(response);
+                        }
+                        set_json_body(&mut response, 201, "application/json", &body);
+                    }
+                    CreateSubscriptionResponse::BadRequest(body) => {
+                        set_problem(&mut response, 400, &body)
+                    }
+                    CreateSubscriptionResponse::Unauthorized(body) => {
+                        set_problem(&mut response, 401, &body)
+                    }
+                    CreateSubscriptionResponse::Forbidden(body) => {
+                        set_problem(&mut response, 403, &body)
+                    }
+                    CreateSubscriptionResponse::NotFound(body) => {
+                        set_problem(&mut response, 404, &body)
+                    }
+                    CreateSubscriptionResponse::InternalServerError(body) => {
+                        set_problem(&mut response, 500, &body)
+                    }
+                    CreateSubscriptionResponse::NotImplemented(body) => {
+                        set_problem(&mut response, 501, &body)
+                    }
+                    CreateSubscriptionResponse::ServiceUnavailable(body) => {
+                        set_problem(&mut response, 503, &body)
+                    }
+                    CreateSubscriptionResponse::GenericError => {
+                        *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+                    }
+                },
+                Err(_) => {
+                    // Application code returned an error. This should not happen, as the implementation should
+                    // return a valid response.
+                    *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+                    *response.body_mut() = Body::from("An internal error occurred");
+                }
+            }
+
+            Ok(response)
+        }
+
+        // RemoveSubscription - DELETE /subscriptions/{subscriptionID}
+        hyper::Method::DELETE if path.matched(paths::ID_SUBSCRIPTIONS_SUBSCRIPTIONID) => {
+            // Path parameters
+            let path: &str = uri.path();
+            let path_params = paths::REGEX_SUBSCRIPTIONS_SUBSCRIPTIONID
+                .captures(path)
+                .unwrap_or_else(|| {
+                    panic!(
+                        "Path {} matched RE SUBSCRIPTIONS_SUBSCRIPTIONID in set but failed match against \"{}\"",
+                        path,
+                        paths::REGEX_SUBSCRIPTIONS_SUBSCRIPTIONID.as_str()
+                    )
+                });
+
+            let param_subscription_id =
+                match percent_encoding::percent_decode(path_params["subscriptionID"].as_bytes())
+                    .decode_utf8()
+                {
+                    Ok(param_subscription_id) => param_subscription_id.to_string(),
+                    Err(_) => {
+                        return bad_request(format!(
+                            "Couldn't percent-decode path parameter as UTF-8: {}",
+                            &path_params["subscriptionID"]
+                        ))
+                    }
+                };
+
+            let result = api_impl
+                .remove_subscription(param_subscription_id, &context)
+                .await;
+
+            match result {
+                Ok(rsp) => match rsp {
+                    RemoveSubscriptionResponse::ExpectedResponseToASuccessfulSubscriptionRemoval => {
+                        *response.status_mut() = StatusCode::NO_CONTENT;
+                    }
+                    RemoveSubscriptionResponse::BadRequest(body) => {
+                        set_problem(&mut response, 400, &body)
+                    }
+                    RemoveSubscriptionResponse::Unauthorized(body) => {
+                        set_problem(&mut response, 401, &body)
+                    }
+                    RemoveSubscriptionResponse::Forbidden(body) => {
+                        set_problem(&mut response, 403, &body)
+                    }
+                    RemoveSubscriptionResponse::NotFound(body) => {
+                        set_problem(&mut response, 404, &body)
+                    }
+                    RemoveSubscriptionResponse::InternalServerError(body) => {
+                        set_problem(&mut response, 500, &body)
+                    }
+                    RemoveSubscriptionResponse::ServiceUnavailable(body) => {
+                        set_problem(&mut response, 503, &body)
+                    }
+                    RemoveSubscriptionResponse::GenericError => {
+                        *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+                    }
+                },
+                Err(_) => {
+                    // Application code returned an error. This should not happen, as the implementation should
+                    // return a valid response.
+                    *response.status_mut() = StatusCode::INTERNAL_SERVER_ERROR;
+                    *response.body_mut() = Body::from("An internal error occurred");
+                }
+            }
+
+            Ok(response)
+        }
+
+        _ => method_not_allowed(),
+    }
+}
+
+/// Request parser for `Api`.
+pub struct ApiRequestParser;
+impl<T> RequestParser<T> for ApiRequestParser {
+    fn parse_operation_id(request: &Request<T>) -> Option<&'static str> {
+        let path = paths::GLOBAL_REGEX_SET.matches(request.uri().path());
+        match *request.method() {
+            // GetNFInstance - GET /nf-instances/{nfInstanceID}
+            hyper::Method::GET if path.matched(paths::ID_NF_INSTANCES_NFINSTANCEID) => {
+                Some("GetNFInstance")
+            }
+            // RegisterNFInstance - PUT /nf-instances/{nfInstanceID}
+            hyper::Method::PUT if path.matched(paths::ID_NF_INSTANCES_NFINSTANCEID) => {
+                Some("RegisterNFInstance")
+            }
+            // UpdateNFInstance - PATCH /nf-instances/{nfInstanceID}
+            hyper::Method::PATCH if path.matched(paths::ID_NF_INSTANCES_NFINSTANCEID) => {
+                Some("UpdateNFInstance")
+            }
+            // DeregisterNFInstance - DELETE /nf-instances/{nfInstanceID}
+            hyper::Method::DELETE if path.matched(paths::ID_NF_INSTANCES_NFINSTANCEID) => {
+                Some("DeregisterNFInstance")
+            }
+            // CreateSubscription - POST /subscriptions
+            hyper::Method::POST if path.matched(paths::ID_SUBSCRIPTIONS) => {
+                Some("CreateSubscription")
+            }
+            // RemoveSubscription - DELETE /subscriptions/{subscriptionID}
+            hyper::Method::DELETE if path.matched(paths::ID_SUBSCRIPTIONS_SUBSCRIPTIONID) => {
+                Some("RemoveSubscription")
+            }
+            _ => None,
+        }
+    }
+}
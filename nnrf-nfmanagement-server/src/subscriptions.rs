@@ -0,0 +1,120 @@
+//! NF status subscriptions held by the NRF.
+//!
+//! The store keeps the subscriptions created through the NFManagement API and
+//! turns each change of the NF registry into the notifications owed to the
+//! subscribers.
+
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use nnrf_discovery_server::registry::RegistryEvent;
+
+use crate::models::{NfProfile, NotificationData, NotificationEventType, SubscriptionData};
+
+/// Thread-safe handle to the NF status subscriptions, keyed by subscription id.
+#[derive(Debug, Clone, Default)]
+pub struct SubscriptionStore {
+    subscriptions: Arc<RwLock<HashMap<String, SubscriptionData>>>,
+}
+
+impl SubscriptionStore {
+    pub fn new() -> SubscriptionStore {
+        SubscriptionStore::default()
+    }
+
+    /// Stores a subscription under a new id and returns it as stored.
+    pub fn create(&self, mut subscription: SubscriptionData) -> SubscriptionData {
+        let subscription_id = uuid::Uuid::new_v4().simple().to_string();
+        subscription.subscription_id = Some(subscription_id.clone());
+        self.subscriptions
+            .write()
+            .expect("subscription lock poisoned")
+            .insert(subscription_id, subscription.clone());
+        subscription
+    }
+
+    pub fn remove(&self, subscription_id: &str) -> Option<SubscriptionData> {
+        self.subscriptions
+            .write()
+            .expect("subscription lock poisoned")
+            .remove(subscription_id)
+    }
+
+    pub fn get(&self, subscription_id: &str) -> Option<SubscriptionData> {
+        self.subscriptions
+            .read()
+            .expect("subscription lock poisoned")
+            .get(subscription_id)
+            .cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.subscriptions
+            .read()
+            .expect("subscription lock poisoned")
+            .len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes and returns the subscriptions whose validity time has passed.
+    pub fn expire(&self) -> Vec<SubscriptionData> {
+        let now = chrono::Utc::now();
+        let mut expired = Vec::new();
+        self.subscriptions
+            .write()
+            .expect("subscription lock poisoned")
+            .retain(|_, subscription| match subscription.validity_time {
+                Some(validity_time) if validity_time <= now => {
+                    expired.push(subscription.clone());
+                    false
+                }
+                _ => true,
+            });
+        expired
+    }
+
+    /// Builds the notifications for a registry change, as pairs of
+    /// notification URI and payload. `api_root` is the NRF apiRoot used to
+    /// build the URI of the NF instance.
+    pub fn notifications(
+        &self,
+        event: &RegistryEvent,
+        api_root: &str,
+    ) -> Vec<(String, NotificationData)> {
+        let (event_type, nf_profile) = match event {
+            RegistryEvent::Registered(profile) => (
+                NotificationEventType::Registered,
+                Some(NfProfile::new(profile.clone())),
+            ),
+            RegistryEvent::ProfileChanged(profile) => (
+                NotificationEventType::ProfileChanged,
+                Some(NfProfile::new(profile.clone())),
+            ),
+            RegistryEvent::Deregistered(_) => (NotificationEventType::Deregistered, None),
+        };
+        let profile = event.profile();
+        let nf_instance_uri = format!(
+            "{}{}/nf-instances/{}",
+            api_root,
+            crate::BASE_PATH,
+            profile.nf_instance_id
+        );
+        let now = chrono::Utc::now();
+
+        self.subscriptions
+            .read()
+            .expect("subscription lock poisoned")
+            .values()
+            .filter(|s| s.validity_time.map(|t| t > now).unwrap_or(true))
+            .filter(|s| s.wants(event_type, profile))
+            .map(|s| {
+                let mut data = NotificationData::new(event_type, nf_instance_uri.clone());
+                data.nf_profile = nf_profile.clone();
+                (s.nf_status_notification_uri.clone(), data)
+            })
+            .collect()
+    }
+}
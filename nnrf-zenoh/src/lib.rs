@@ -1,11 +1,173 @@
+use async_trait::async_trait;
+use nnrf_discovery_server::cache::Discover;
+use nnrf_discovery_server::registry::SearchQuery;
 use nnrf_discovery_server::{
     models, RetrieveCompleteSearchResponse, RetrieveStoredSearchResponse,
     SCpDomainRoutingInfoGetResponse, ScpDomainRoutingInfoSubscribeResponse,
     ScpDomainRoutingInfoUnsubscribeResponse, SearchNfInstancesResponse,
 };
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Arc;
 use uuid::Uuid;
+use zenoh::prelude::r#async::*;
 
+pub mod liveliness;
+pub mod registration;
+pub mod storage;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ApiError(pub String);
+
+/// Key expression of the NF discovery queryable.
+pub const NF_INSTANCES: &str = "nnrf-disc/v1/nf-instances";
+
+/// Sends the queries of a `DiscoveryCache` to the zenoh discovery queryable.
+///
+/// The filters of the query are put in the selector, the structured ones
+/// (`snssais`, `target-plmn-list` and `tai`) in JSON as in the HTTP API.
+#[derive(Clone)]
+pub struct ZenohDiscovery {
+    session: Arc<Session>,
+}
+
+impl ZenohDiscovery {
+    pub fn new(session: Arc<Session>) -> Self {
+        Self { session }
+    }
+
+    fn selector(query: &SearchQuery) -> String {
+        let mut parameters = vec![
+            ("target-nf-type", nf_type_to_string(&query.target_nf_type)),
+            (
+                "requester-nf-type",
+                nf_type_to_string(&query.requester_nf_type),
+            ),
+        ];
+        if let Some(service_names) = &query.service_names {
+            let service_names: Vec<String> = service_names.iter().map(|s| s.to_string()).collect();
+            parameters.push(("service-names", service_names.join(",")));
+        }
+        if let Some(snssais) = &query.snssais {
+            parameters.push(("snssais", to_json(snssais)));
+        }
+        if let Some(dnn) = &query.dnn {
+            parameters.push(("dnn", dnn.clone()));
+        }
+        if let Some(target_plmn_list) = &query.target_plmn_list {
+            parameters.push(("target-plmn-list", to_json(target_plmn_list)));
+        }
+        if let Some(tai) = &query.tai {
+            parameters.push(("tai", to_json(tai)));
+        }
+        if let Some(limit) = query.limit {
+            parameters.push(("limit", limit.to_string()));
+        }
+
+        // The values are form encoded, as the queryable decodes them
+        let mut selector = Selector::try_from(NF_INSTANCES).expect("valid key expression");
+        selector.extend(&parameters);
+        selector.to_string()
+    }
+}
+
+#[async_trait]
+impl Discover for ZenohDiscovery {
+    async fn discover(
+        &self,
+        query: &SearchQuery,
+    ) -> Result<models::SearchResult, swagger::ApiError> {
+        let replies = self
+            .session
+            .get(&ZenohDiscovery::selector(query))
+            .res()
+            .await
+            .map_err(|e| swagger::ApiError(format!("Discovery failed: {}", e)))?;
+        let reply = replies
+            .recv_async()
+            .await
+            .map_err(|e| swagger::ApiError(format!("No discovery reply: {}", e)))?;
+        let sample = reply
+            .sample
+            .map_err(|e| swagger::ApiError(format!("Discovery rejected: {}", e)))?;
+
+        serde_json::from_slice(&sample.value.payload.contiguous())
+            .map_err(|e| swagger::ApiError(format!("Invalid discovery reply: {}", e)))
+    }
+}
+
+/// Reads back the query a `ZenohDiscovery` put in a selector.
+pub fn search_query(selector: &Selector) -> Result<SearchQuery, String> {
+    let parameters = selector
+        .parameters_cowmap()
+        .map_err(|e| format!("Invalid selector: {}", e))?;
+    let nf_type = |name: &str| {
+        let value = parameters
+            .get(name)
+            .ok_or_else(|| format!("Missing {}", name))?;
+        serde_json::from_value::<models::NfType>(serde_json::Value::String(value.to_string()))
+            .map_err(|e| format!("Invalid {}: {}", name, e))
+    };
+
+    let mut query = SearchQuery::new(nf_type("target-nf-type")?, nf_type("requester-nf-type")?);
+    query.service_names = parameters.get("service-names").map(|s| {
+        s.split(',')
+            .map(|s| models::ServiceName::new(s.to_string()))
+            .collect()
+    });
+    query.snssais = json_parameter(&parameters, "snssais")?;
+    query.dnn = parameters.get("dnn").map(|s| s.to_string());
+    query.target_plmn_list = json_parameter(&parameters, "target-plmn-list")?;
+    query.tai = json_parameter(&parameters, "tai")?;
+    query.limit = parameters
+        .get("limit")
+        .map(|s| s.parse().map_err(|e| format!("Invalid limit: {}", e)))
+        .transpose()?;
+    Ok(query)
+}
+
+/// A parameter of a selector holding JSON, as the structured filters do.
+fn json_parameter<T: serde::de::DeserializeOwned>(
+    parameters: &HashMap<Cow<str>, Cow<str>>,
+    name: &str,
+) -> Result<Option<T>, String> {
+    parameters
+        .get(name)
+        .map(|value| serde_json::from_str(value).map_err(|e| format!("Invalid {}: {}", name, e)))
+        .transpose()
+}
+
+fn to_json<T: Serialize>(value: &T) -> String {
+    serde_json::to_string(value).expect("impossible to fail to serialize")
+}
+
+fn nf_type_to_string(nf_type: &models::NfType) -> String {
+    match serde_json::to_value(nf_type) {
+        Ok(serde_json::Value::String(s)) => s,
+        _ => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn selector_holds_every_filter() {
+        let plmn = models::PlmnId::new("001".to_string(), "01".to_string());
+        let mut snssai = models::Snssai::new(1);
+        snssai.sd = Some("000001".to_string());
+        let mut query = SearchQuery::new(models::NfType::UDM, models::NfType::SMF);
+        query.service_names = Some(vec![models::ServiceName::new("nudm-sdm".to_string())]);
+        query.snssais = Some(vec![snssai]);
+        query.dnn = Some("internet".to_string());
+        query.target_plmn_list = Some(vec![plmn.clone()]);
+        query.tai = Some(models::Tai::new(plmn, "000001".to_string()));
+        query.limit = Some(3);
+
+        let selector = ZenohDiscovery::selector(&query);
+        let selector = Selector::try_from(selector.as_str()).unwrap();
+        assert_eq!(search_query(&selector), Ok(query));
+    }
+}
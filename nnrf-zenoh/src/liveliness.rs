@@ -0,0 +1,180 @@
+//! Liveliness tokens on zenoh 0.7.
+//!
+//! The zenoh release the project is pinned to has no liveliness API, so the
+//! tokens are made of what it has. A token is a value put under
+//! `liveliness/{key}`, holding the zenoh id of the session declaring it, and a
+//! queryable on the same key answering with it. The token is gone when it is
+//! dropped, which deletes the value, or when the transport to its session
+//! closes, which each session reports in its admin space under
+//! `@/session/{zid}/transport/unicast/{peer_zid}`.
+//!
+//! Only the transports of the session itself are seen: a token declared
+//! behind a router is gone when it is dropped or when the router is.
+
+use futures::{select, FutureExt};
+use log::warn;
+use std::collections::HashMap;
+use std::sync::Arc;
+use zenoh::prelude::r#async::*;
+use zenoh::queryable::Queryable;
+use zenoh::Result as ZResult;
+
+/// Key expression prefix of the tokens.
+pub const TOKENS: &str = "liveliness";
+
+fn token_key(key: &str) -> String {
+    format!("{}/{}", TOKENS, key)
+}
+
+/// The key a token is declared on, from the key it is kept under.
+fn declared_key(token_key: &KeyExpr) -> Option<KeyExpr<'static>> {
+    let key = token_key.as_str().strip_prefix(TOKENS)?.strip_prefix('/')?;
+    KeyExpr::try_from(key.to_string()).ok()
+}
+
+/// A live token. Dropping it deletes it.
+pub struct LivelinessToken {
+    session: Arc<Session>,
+    key: String,
+    _queryable: Queryable<'static, ()>,
+}
+
+impl Drop for LivelinessToken {
+    fn drop(&mut self) {
+        if let Err(e) = zenoh::prelude::sync::SyncResolve::res_sync(self.session.delete(&self.key))
+        {
+            warn!("Unable to delete the liveliness token {}: {}", self.key, e);
+        }
+    }
+}
+
+/// Declares a token on `key`, alive until it is dropped or the session
+/// closes.
+pub async fn declare_token(session: Arc<Session>, key: &str) -> ZResult<LivelinessToken> {
+    let key = token_key(key);
+    let zid = session.zid().to_string();
+
+    let owner = zid.clone();
+    let queryable = session
+        .declare_queryable(&key)
+        .callback(move |query| {
+            let ke = query.key_expr().clone();
+            if let Err(e) = zenoh::prelude::sync::SyncResolve::res_sync(
+                query.reply(Ok(Sample::new(ke, owner.clone()))),
+            ) {
+                warn!("Unable to answer a liveliness query: {}", e);
+            }
+        })
+        .res()
+        .await?;
+    session.put(&key, zid).res().await?;
+
+    Ok(LivelinessToken {
+        session,
+        key,
+        _queryable: queryable,
+    })
+}
+
+/// The tokens alive on `selector`, as samples on the keys they are declared
+/// on.
+pub async fn get(session: &Session, selector: &str) -> ZResult<Vec<Sample>> {
+    let replies = session.get(&token_key(selector)).res().await?;
+    let mut tokens = Vec::new();
+    while let Ok(reply) = replies.recv_async().await {
+        if let Ok(sample) = reply.sample {
+            if let Some(key_expr) = declared_key(&sample.key_expr) {
+                tokens.push(Sample::new(key_expr, sample.value));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+/// Receives a put for each token alive on `selector`, then for each token
+/// appearing, and a delete when one is gone, on the key it is declared on.
+pub async fn declare_subscriber(
+    session: Arc<Session>,
+    selector: &str,
+) -> ZResult<flume::Receiver<Sample>> {
+    let tokens = session
+        .declare_subscriber(&token_key(selector))
+        .res()
+        .await?;
+    let transports = session
+        .declare_subscriber(&format!("@/session/{}/transport/unicast/*", session.zid()))
+        .res()
+        .await?;
+    let (sender, receiver) = flume::unbounded();
+
+    // The zenoh id of the session of each live token
+    let mut owners: HashMap<String, String> = HashMap::new();
+    for sample in get(&session, selector).await? {
+        let owner = String::try_from(&sample.value).unwrap_or_default();
+        owners.insert(sample.key_expr.to_string(), owner);
+        let _ = sender.send(sample);
+    }
+
+    async_std::task::spawn(async move {
+        loop {
+            select! {
+                token = tokens.recv_async().fuse() => {
+                    let token = match token {
+                        Ok(token) => token,
+                        Err(_) => return,
+                    };
+                    let key_expr = match declared_key(&token.key_expr) {
+                        Some(key_expr) => key_expr,
+                        None => continue,
+                    };
+                    match token.kind {
+                        SampleKind::Put => {
+                            let owner = String::try_from(&token.value).unwrap_or_default();
+                            owners.insert(key_expr.to_string(), owner);
+                        }
+                        SampleKind::Delete => {
+                            owners.remove(key_expr.as_str());
+                        }
+                    }
+                    let mut sample = Sample::new(key_expr, token.value);
+                    sample.kind = token.kind;
+                    if sender.send_async(sample).await.is_err() {
+                        return;
+                    }
+                }
+                transport = transports.recv_async().fuse() => {
+                    let transport = match transport {
+                        Ok(transport) => transport,
+                        Err(_) => return,
+                    };
+                    if transport.kind != SampleKind::Delete {
+                        continue;
+                    }
+                    let peer = match transport.key_expr.as_str().rsplit('/').next() {
+                        Some(peer) => peer.to_string(),
+                        None => continue,
+                    };
+                    let gone: Vec<String> = owners
+                        .iter()
+                        .filter(|(_, owner)| **owner == peer)
+                        .map(|(key, _)| key.clone())
+                        .collect();
+                    for key in gone {
+                        owners.remove(&key);
+                        let key_expr = match KeyExpr::try_from(key) {
+                            Ok(key_expr) => key_expr,
+                            Err(_) => continue,
+                        };
+                        let mut sample = Sample::new(key_expr, Vec::<u8>::new());
+                        sample.kind = SampleKind::Delete;
+                        if sender.send_async(sample).await.is_err() {
+                            return;
+                        }
+                    }
+                }
+            }
+        }
+    });
+
+    Ok(receiver)
+}
@@ -0,0 +1,203 @@
+//! NF registration over zenoh, without heartbeats.
+//!
+//! An NF registers by publishing its `NfProfile` under
+//! `nnrf-nfm/v1/nf-instances/{nfInstanceId}`, answering the queries on that key
+//! with it, and declaring a liveliness token on the same key. The NF is
+//! registered for as long as its token is alive: the token disappears when the
+//! NF deregisters, exits or loses its zenoh session.
+//!
+//! [`track`] keeps an `NfRegistry` in line with the live tokens, so that the
+//! NRF queryable, or any consumer, derives the registered instances from them.
+
+use futures::{select, FutureExt};
+use log::{info, warn};
+use std::collections::HashSet;
+use std::sync::{Arc, RwLock};
+use uuid::Uuid;
+use zenoh::prelude::r#async::*;
+use zenoh::queryable::{Query, Queryable};
+use zenoh::Result as ZResult;
+
+use nnrf_discovery_server::models::{
+    NfProfile, NfService, NfServiceStatus, NfServiceVersion, NfStatus, NfStatusAnyOf, NfType,
+    ServiceName, UriScheme,
+};
+use nnrf_discovery_server::registry::NfRegistry;
+
+use crate::liveliness::{self, LivelinessToken};
+
+/// Key expression prefix of the NF instances.
+pub const NF_INSTANCES: &str = "nnrf-nfm/v1/nf-instances";
+
+pub fn nf_instance_key(nf_instance_id: &Uuid) -> String {
+    format!("{}/{}", NF_INSTANCES, nf_instance_id)
+}
+
+/// Builds the profile of an NF exposing a single service over zenoh. The
+/// service is reached through its key expressions, so it has no IP endpoint.
+pub fn nf_profile(
+    nf_instance_id: Uuid,
+    nf_type: NfType,
+    service_name: &str,
+    api_version_in_uri: &str,
+    api_full_version: &str,
+) -> NfProfile {
+    let registered = NfStatusAnyOf::Registered.to_string();
+    let service = NfService::new(
+        Uuid::new_v4().to_string(),
+        ServiceName::new(service_name.to_string()),
+        vec![NfServiceVersion::new(
+            api_version_in_uri.to_string(),
+            api_full_version.to_string(),
+        )],
+        UriScheme::new("http".to_string()),
+        NfServiceStatus::new(registered.clone()),
+    );
+
+    let mut profile = NfProfile::new(nf_instance_id, nf_type, NfStatus::new(registered));
+    profile.priority = Some(0);
+    profile.capacity = Some(100);
+    profile.load = Some(0);
+    profile.nf_services = Some(vec![service]);
+    profile
+}
+
+/// A live registration. Dropping it deregisters the NF.
+pub struct Registration {
+    profile: Arc<RwLock<NfProfile>>,
+    session: Arc<Session>,
+    _queryable: Queryable<'static, flume::Receiver<Query>>,
+    _token: LivelinessToken,
+}
+
+impl Registration {
+    pub fn profile(&self) -> NfProfile {
+        self.profile.read().expect("profile lock poisoned").clone()
+    }
+
+    /// Publishes a new version of the profile.
+    pub async fn update(&self, profile: NfProfile) -> ZResult<()> {
+        let key = nf_instance_key(&profile.nf_instance_id);
+        let value = serde_json::to_vec(&profile).expect("impossible to fail to serialize");
+        *self.profile.write().expect("profile lock poisoned") = profile;
+        self.session.put(&key, value).res().await
+    }
+}
+
+/// Registers an NF: publishes its profile, serves it on its key and declares
+/// its liveliness token.
+pub async fn register(session: Arc<Session>, profile: NfProfile) -> ZResult<Registration> {
+    let key = nf_instance_key(&profile.nf_instance_id);
+    let value = serde_json::to_vec(&profile).expect("impossible to fail to serialize");
+    let profile = Arc::new(RwLock::new(profile));
+
+    let queryable = session.declare_queryable(&key).res().await?;
+    let queries = queryable.receiver.clone();
+    let served = profile.clone();
+    async_std::task::spawn(async move {
+        // Ends when the queryable is undeclared, with the registration
+        while let Ok(query) = queries.recv_async().await {
+            let value = serde_json::to_vec(&*served.read().expect("profile lock poisoned"))
+                .expect("impossible to fail to serialize");
+            let ke = query.key_expr().clone();
+            if let Err(e) = query.reply(Ok(Sample::new(ke, value))).res().await {
+                warn!("Unable to serve the NF profile: {}", e);
+            }
+        }
+    });
+
+    session.put(&key, value).res().await?;
+    let token = liveliness::declare_token(session.clone(), &key).await?;
+    info!("Registered {}", key);
+
+    Ok(Registration {
+        profile,
+        session,
+        _queryable: queryable,
+        _token: token,
+    })
+}
+
+pub(crate) fn nf_instance_id(key_expr: &KeyExpr) -> Option<Uuid> {
+    key_expr
+        .as_str()
+        .strip_prefix(NF_INSTANCES)?
+        .strip_prefix('/')?
+        .parse()
+        .ok()
+}
+
+/// Fetches the profile an NF serves on its key.
+async fn fetch(session: &Session, nf_instance_id: &Uuid) -> Option<NfProfile> {
+    let replies = session
+        .get(&nf_instance_key(nf_instance_id))
+        .res()
+        .await
+        .ok()?;
+    while let Ok(reply) = replies.recv_async().await {
+        if let Ok(sample) = reply.sample {
+            match serde_json::from_slice(&sample.value.payload.contiguous()) {
+                Ok(profile) => return Some(profile),
+                Err(e) => warn!("Invalid NF profile on {}: {}", sample.key_expr, e),
+            }
+        }
+    }
+    None
+}
+
+/// Keeps `registry` holding the profiles of the NFs whose liveliness token is
+/// alive. Returns only if the zenoh declarations fail.
+pub async fn track(session: Arc<Session>, registry: NfRegistry) -> ZResult<()> {
+    let selector = format!("{}/*", NF_INSTANCES);
+    let profiles = session.declare_subscriber(&selector).res().await?;
+    // Starts with the NFs registered before us
+    let tokens = liveliness::declare_subscriber(session.clone(), &selector).await?;
+    let mut live = HashSet::new();
+
+    loop {
+        select! {
+            token = tokens.recv_async().fuse() => {
+                let token = match token {
+                    Ok(token) => token,
+                    Err(_) => return Ok(()),
+                };
+                let id = match nf_instance_id(&token.key_expr) {
+                    Some(id) => id,
+                    None => continue,
+                };
+                match token.kind {
+                    SampleKind::Put => {
+                        info!("NF {} is alive", id);
+                        live.insert(id);
+                        if let Some(profile) = fetch(&session, &id).await {
+                            registry.insert(profile);
+                        }
+                    }
+                    SampleKind::Delete => {
+                        info!("NF {} is gone", id);
+                        live.remove(&id);
+                        registry.remove(&id);
+                    }
+                }
+            }
+            sample = profiles.recv_async().fuse() => {
+                let sample = match sample {
+                    Ok(sample) => sample,
+                    Err(_) => return Ok(()),
+                };
+                // Profiles are only taken from live NFs
+                match nf_instance_id(&sample.key_expr) {
+                    Some(id) if live.contains(&id) && sample.kind == SampleKind::Put => {
+                        match serde_json::from_slice::<NfProfile>(&sample.value.payload.contiguous()) {
+                            Ok(profile) => {
+                                registry.insert(profile);
+                            }
+                            Err(e) => warn!("Invalid NF profile on {}: {}", sample.key_expr, e),
+                        }
+                    }
+                    _ => {}
+                }
+            }
+        }
+    }
+}
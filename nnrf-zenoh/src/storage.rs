@@ -0,0 +1,220 @@
+//! Storage of the registered NF profiles, from which a restarted NRF recovers
+//! its registry and late joiners get the whole set of NFs.
+//!
+//! The storage runs in the process of the NRF, like a zenoh storage: it holds
+//! the profiles of the registry, kept in line by [`NfStorage::run`], and
+//! answers the queries on `nnrf-nfm/v1/stored-nf-instances/*`. It does not
+//! answer on the keys of the NF instances, so that a stored profile never
+//! stands in for the one a live NF serves.
+//! The memory backend lives as long as the NRF; the filesystem backend keeps
+//! the profiles in a file in the format of `NfRegistry::read`, which the HTTP
+//! NRF saves its registry in as well.
+
+use futures::{select, FutureExt, StreamExt};
+use log::{info, warn};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+use std::time::Instant;
+use uuid::Uuid;
+use zenoh::prelude::r#async::*;
+use zenoh::Result as ZResult;
+
+use nnrf_discovery_server::models::NfProfile;
+use nnrf_discovery_server::registry::{write_profiles, NfRegistry, RegistryEvent};
+
+use crate::liveliness;
+use crate::registration::{nf_instance_id, NF_INSTANCES};
+
+/// Key expression prefix of the stored NF profiles.
+pub const STORED_NF_INSTANCES: &str = "nnrf-nfm/v1/stored-nf-instances";
+
+pub fn stored_nf_instance_key(nf_instance_id: &Uuid) -> String {
+    format!("{}/{}", STORED_NF_INSTANCES, nf_instance_id)
+}
+
+/// Where the stored profiles are kept.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Backend {
+    Memory,
+    /// JSON array of NF profiles
+    Filesystem(PathBuf),
+}
+
+impl std::str::FromStr for Backend {
+    type Err = String;
+
+    /// `memory`, or the path of the file of the filesystem backend.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "memory" => Ok(Backend::Memory),
+            "" => Err("empty storage path".to_string()),
+            path => Ok(Backend::Filesystem(PathBuf::from(path))),
+        }
+    }
+}
+
+/// Thread-safe handle to the stored profiles, keyed by NF instance id.
+#[derive(Debug, Clone)]
+pub struct NfStorage {
+    backend: Backend,
+    profiles: Arc<RwLock<HashMap<Uuid, NfProfile>>>,
+}
+
+impl NfStorage {
+    /// Opens a storage, with the profiles saved in its file if it exists.
+    pub fn open(backend: Backend) -> std::io::Result<NfStorage> {
+        let profiles = match &backend {
+            Backend::Filesystem(path) if path.exists() => NfRegistry::read(path)?,
+            _ => Vec::new(),
+        };
+
+        Ok(NfStorage {
+            backend,
+            profiles: Arc::new(RwLock::new(
+                profiles
+                    .into_iter()
+                    .map(|p| (p.nf_instance_id, p))
+                    .collect(),
+            )),
+        })
+    }
+
+    pub fn profiles(&self) -> Vec<NfProfile> {
+        let mut profiles: Vec<NfProfile> = self
+            .profiles
+            .read()
+            .expect("storage lock poisoned")
+            .values()
+            .cloned()
+            .collect();
+        profiles.sort_by_key(|p| p.nf_instance_id);
+        profiles
+    }
+
+    pub fn len(&self) -> usize {
+        self.profiles.read().expect("storage lock poisoned").len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Puts the stored profiles of the NFs whose liveliness token is alive in
+    /// the registry, and forgets the others. Returns how many were recovered.
+    pub async fn recover(&self, session: &Session, registry: &NfRegistry) -> ZResult<usize> {
+        let start = Instant::now();
+
+        let live: HashSet<Uuid> = liveliness::get(session, &format!("{}/*", NF_INSTANCES))
+            .await?
+            .iter()
+            .filter_map(|token| nf_instance_id(&token.key_expr))
+            .collect();
+
+        let recovered = {
+            let mut profiles = self.profiles.write().expect("storage lock poisoned");
+            profiles.retain(|id, _| live.contains(id));
+            for profile in profiles.values() {
+                registry.insert(profile.clone());
+            }
+            profiles.len()
+        };
+        self.persist();
+
+        info!(
+            "Recovered {} NF profiles in {:?}",
+            recovered,
+            start.elapsed()
+        );
+        Ok(recovered)
+    }
+
+    /// Stores the changes of the registry and answers the queries on the
+    /// stored NF profiles. Returns only if the zenoh declarations fail.
+    pub async fn run(self, session: Arc<Session>, registry: NfRegistry) -> ZResult<()> {
+        let mut events = registry.watch();
+        for profile in registry.profiles() {
+            self.put(profile);
+        }
+        self.persist();
+
+        let queryable = session
+            .declare_queryable(&format!("{}/*", STORED_NF_INSTANCES))
+            .res()
+            .await?;
+
+        loop {
+            select! {
+                event = events.next() => {
+                    match event {
+                        Some(RegistryEvent::Registered(profile))
+                        | Some(RegistryEvent::ProfileChanged(profile)) => self.put(profile),
+                        Some(RegistryEvent::Deregistered(profile)) => {
+                            self.profiles
+                                .write()
+                                .expect("storage lock poisoned")
+                                .remove(&profile.nf_instance_id);
+                        }
+                        None => return Ok(()),
+                    }
+                    self.persist();
+                }
+                query = queryable.recv_async().fuse() => {
+                    let query = match query {
+                        Ok(query) => query,
+                        Err(_) => return Ok(()),
+                    };
+                    for profile in self.profiles() {
+                        let key = KeyExpr::try_from(stored_nf_instance_key(&profile.nf_instance_id))?;
+                        if !query.key_expr().intersects(&key) {
+                            continue;
+                        }
+                        let value =
+                            serde_json::to_vec(&profile).expect("impossible to fail to serialize");
+                        if let Err(e) = query.reply(Ok(Sample::new(key, value))).res().await {
+                            warn!("Unable to serve the stored NF profile: {}", e);
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn put(&self, profile: NfProfile) {
+        self.profiles
+            .write()
+            .expect("storage lock poisoned")
+            .insert(profile.nf_instance_id, profile);
+    }
+
+    fn persist(&self) {
+        if let Backend::Filesystem(path) = &self.backend {
+            if let Err(e) = write_profiles(path, &self.profiles()) {
+                warn!(
+                    "Unable to save the NF profiles to {}: {}",
+                    path.display(),
+                    e
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::registration::nf_instance_key;
+
+    #[test]
+    fn stored_profiles_are_apart_from_the_live_ones() {
+        let id = Uuid::new_v4();
+        let stored = KeyExpr::try_from(stored_nf_instance_key(&id)).unwrap();
+        let live = KeyExpr::try_from(nf_instance_key(&id)).unwrap();
+        let nf_instances = KeyExpr::try_from(format!("{}/*", NF_INSTANCES)).unwrap();
+        let stored_nf_instances = KeyExpr::try_from(format!("{}/*", STORED_NF_INSTANCES)).unwrap();
+
+        assert!(!nf_instances.intersects(&stored));
+        assert!(!stored_nf_instances.intersects(&live));
+        assert!(stored_nf_instances.intersects(&stored));
+    }
+}
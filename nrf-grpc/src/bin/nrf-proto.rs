@@ -1,23 +1,46 @@
 use clap::Parser;
+use nnrf_discovery_server::registry::NfRegistry;
 use nrf_grpc::nnrf_disc::nrf_discovery_server::NrfDiscoveryServer;
+use nrf_grpc::nnrf_disc::nrf_management_server::NrfManagementServer;
 use nrf_grpc::MyNRF;
+use std::time::Duration;
 use tonic::transport::Server;
 
+/// Interval between two checks of the NF heartbeats.
+const HEART_BEAT_CHECK_PERIOD: Duration = Duration::from_secs(1);
+
 #[derive(Parser)]
 pub struct Opts {
     // public options
     #[clap(short = 'l', long, default_value = "127.0.0.1:9090")]
     pub listen: String,
+    #[clap(
+        short = 'p',
+        long,
+        default_value = "nnrf-discovery-server/examples/nrf-server/profiles.json"
+    )]
+    pub profiles: String,
 }
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let opts = Opts::parse();
     let addr = opts.listen.parse()?;
-    let nrf = MyNRF::default();
+    let registry = NfRegistry::load(&opts.profiles)?;
+    let nrf = MyNRF::new(registry.clone());
+
+    // Suspends, then deregisters, the NFs that stop sending heartbeats
+    tokio::spawn(async move {
+        let mut interval = tokio::time::interval(HEART_BEAT_CHECK_PERIOD);
+        loop {
+            interval.tick().await;
+            registry.expire();
+        }
+    });
 
     Server::builder()
-        .add_service(NrfDiscoveryServer::new(nrf))
+        .add_service(NrfDiscoveryServer::new(nrf.clone()))
+        .add_service(NrfManagementServer::new(nrf))
         .serve(addr)
         .await?;
 
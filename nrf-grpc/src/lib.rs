@@ -1,16 +1,81 @@
+use futures::{future, Stream, StreamExt};
+use std::pin::Pin;
+use std::time::Duration;
+use swagger::ApiError;
+use tonic::transport::Channel;
 use tonic::{Request, Response, Status};
+use uuid::Uuid;
 
+use nnrf_disc::nf_instance_event::Event;
+use nnrf_disc::nrf_discovery_client::NrfDiscoveryClient;
 use nnrf_disc::nrf_discovery_server::NrfDiscovery;
-use nnrf_disc::{IpEndpoints, NfProfile, NfService, NfVersion, SearchRequest, SearchResult};
+use nnrf_disc::nrf_management_server::NrfManagement;
+use nnrf_disc::{
+    AmfInfo, DeregisterResponse, ExtSnssai, HeartbeatResponse, IpEndpoints, NfInstanceEvent,
+    NfInstanceId, NfProfile, NfService, NfVersion, PlmnId, SdRange, SearchRequest, SearchResult,
+    SmfInfo, Snssai, SnssaiInfoItem, Tai, UpfInfo, WatchRequest,
+};
 
-use std::collections::HashMap;
+use nnrf_discovery_server::cache::Discover;
+use nnrf_discovery_server::models;
+use nnrf_discovery_server::registry::{AllowedNfTypes, NfRegistry, RegistryEvent, SearchQuery};
 
 pub mod nnrf_disc {
     tonic::include_proto!("fiveg_proto"); // The string specified here must match the proto package name
 }
 
-#[derive(Debug, Default)]
-pub struct MyNRF {}
+/// Validity period, in seconds, of the search results sent to the consumers.
+const VALIDITY_PERIOD: i32 = 3600;
+
+/// Heartbeat timer, in seconds, granted to the NFs that do not ask for one.
+pub const HEART_BEAT_TIMER: i32 = 10;
+
+/// Serves both the discovery and the management services on the same
+/// registry; the clones share it.
+#[derive(Debug, Clone, Default)]
+pub struct MyNRF {
+    registry: NfRegistry,
+}
+
+impl MyNRF {
+    pub fn new(registry: NfRegistry) -> Self {
+        Self { registry }
+    }
+
+    /// A registered profile with the heartbeat timer granted to the NF.
+    fn registered_profile(&self, nf_instance_id: &Uuid) -> Result<NfProfile, Status> {
+        let profile = self
+            .registry
+            .get(nf_instance_id)
+            .ok_or_else(|| not_found(nf_instance_id))?;
+        Ok(self.nf_profile(&profile))
+    }
+
+    /// The gRPC form of a profile, with the NFManagement attributes the
+    /// registry keeps apart: the heartbeat timer and the allowed NF types.
+    fn nf_profile(&self, profile: &models::NfProfile) -> NfProfile {
+        let mut result = NfProfile::from(profile);
+        result.heartbeat_timer = self.heartbeat_timer(&profile.nf_instance_id);
+        if let Some(allowed_nf_types) = self.registry.allowed_nf_types(&profile.nf_instance_id) {
+            result.allowed_nf_types = allowed_nf_types.profile.unwrap_or_default();
+            for (id, service) in result.nf_service_list.iter_mut() {
+                service.allowed_nf_types = allowed_nf_types
+                    .services
+                    .get(id)
+                    .cloned()
+                    .unwrap_or_default();
+            }
+        }
+        result
+    }
+
+    fn heartbeat_timer(&self, nf_instance_id: &Uuid) -> i32 {
+        self.registry
+            .heart_beat_timer(nf_instance_id)
+            .map(|t| t.as_secs() as i32)
+            .unwrap_or(0)
+    }
+}
 
 #[tonic::async_trait]
 impl NrfDiscovery for MyNRF {
@@ -18,86 +83,787 @@ impl NrfDiscovery for MyNRF {
         &self,
         request: Request<SearchRequest>,
     ) -> Result<Response<SearchResult>, Status> {
-        let req: SearchRequest = request.into_inner();
-
-        let reply = if req.target_nf_type == "UDM" {
-            SearchResult {
-                validity_period: 3600,
-                nf_instances: vec![NfProfile {
-                    nf_instance_id: "65a52dac-b832-41ed-ba6e-53c1c0b3ed51".into(),
-                    nf_type: "UDM".into(),
-                    nf_status: "REGISTERED".into(),
-                    heartbeat_timer: 10,
-                    ipv4_addresses: vec!["172.22.0.13".into()],
-                    allowed_nf_types: vec!["AMF".into(), "SMF".into(), "AUSF".into(), "SCP".into()],
-                    priority: 0,
-                    capacity: 100,
-                    load: 0,
-                    nf_service_list: HashMap::from([(
-                        "65a54148-b832-41ed-ba6e-53c1c0b3ed51".into(),
-                        NfService {
-                            service_instance_id: "65a54148-b832-41ed-ba6e-53c1c0b3ed51".into(),
-                            service_name: "nudm-sdm".into(),
-                            versions: vec![NfVersion {
-                                api_version_in_url: "v2".into(),
-                                api_full_version: "2.0.0".into(),
-                            }],
-                            scheme: "http".into(),
-                            nf_service_status: "REGISTERED".into(),
-                            ip_endpoints: vec![IpEndpoints {
-                                ipv4_address: "172.22.0.13".into(),
-                                port: 7777,
-                            }],
-                            allowed_nf_types: vec!["AMF".into(), "SMF".into()],
-                            priority: 0,
-                            capacity: 100,
-                            load: 0,
-                        },
-                    )]),
-                    nf_profile_changes_support_ind: true,
-                }],
-            }
-        } else if req.target_nf_type == "SMF" {
-            SearchResult {
-                validity_period: 3600,
-                nf_instances: vec![NfProfile {
-                    nf_instance_id: "b3a71a80-b8d5-41ed-b2cc-8bbc6f173b7d".into(),
-                    nf_type: "SMF".into(),
-                    nf_status: "REGISTERED".into(),
-                    heartbeat_timer: 10,
-                    ipv4_addresses: vec!["172.22.0.7".into()],
-                    allowed_nf_types: vec!["AMF".into(), "SCP".into()],
-                    priority: 0,
-                    capacity: 100,
-                    load: 0,
-                    nf_service_list: HashMap::from([(
-                        "b3a71a80-b8d5-41ed-b2cc-8bbc6f173b7d".into(),
-                        NfService {
-                            service_instance_id: "b3a71a80-b8d5-41ed-b2cc-8bbc6f173b7d".into(),
-                            service_name: "nsmf-pdusession".into(),
-                            versions: vec![NfVersion {
-                                api_version_in_url: "v1".into(),
-                                api_full_version: "1.0.0".into(),
-                            }],
-                            scheme: "http".into(),
-                            nf_service_status: "REGISTERED".into(),
-                            ip_endpoints: vec![IpEndpoints {
-                                ipv4_address: "172.22.0.7".into(),
-                                port: 7777,
-                            }],
-                            allowed_nf_types: vec!["AMF".into()],
-                            priority: 0,
-                            capacity: 100,
-                            load: 0,
-                        },
-                    )]),
-                    nf_profile_changes_support_ind: true,
-                }],
-            }
-        } else {
-            panic!("Neither UDM nor SMF, other are not mockup");
+        let query = SearchQuery::try_from(request.into_inner())?;
+
+        let reply = SearchResult {
+            validity_period: VALIDITY_PERIOD,
+            nf_instances: self
+                .registry
+                .search(&query)
+                .iter()
+                .map(|profile| self.nf_profile(profile))
+                .collect(),
         };
 
         Ok(Response::new(reply))
     }
 }
+
+type WatchStream = Pin<Box<dyn Stream<Item = Result<NfInstanceEvent, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl NrfManagement for MyNRF {
+    async fn register(&self, request: Request<NfProfile>) -> Result<Response<NfProfile>, Status> {
+        let profile = request.into_inner();
+        let heart_beat_timer = if profile.heartbeat_timer > 0 {
+            profile.heartbeat_timer
+        } else {
+            HEART_BEAT_TIMER
+        };
+        let allowed_nf_types = AllowedNfTypes::from(&profile);
+        let profile = models::NfProfile::try_from(&profile).map_err(Status::invalid_argument)?;
+        let nf_instance_id = profile.nf_instance_id;
+
+        self.registry.register_with(
+            profile,
+            Some(Duration::from_secs(heart_beat_timer as u64)),
+            allowed_nf_types,
+        );
+
+        Ok(Response::new(self.registered_profile(&nf_instance_id)?))
+    }
+
+    async fn update(&self, request: Request<NfProfile>) -> Result<Response<NfProfile>, Status> {
+        let profile =
+            models::NfProfile::try_from(&request.into_inner()).map_err(Status::invalid_argument)?;
+        let nf_instance_id = profile.nf_instance_id;
+
+        self.registry
+            .update(profile)
+            .ok_or_else(|| not_found(&nf_instance_id))?;
+
+        Ok(Response::new(self.registered_profile(&nf_instance_id)?))
+    }
+
+    async fn heartbeat(
+        &self,
+        request: Request<NfInstanceId>,
+    ) -> Result<Response<HeartbeatResponse>, Status> {
+        let nf_instance_id = nf_instance_id_from_str(&request.into_inner().nf_instance_id)?;
+
+        self.registry
+            .heartbeat(&nf_instance_id)
+            .ok_or_else(|| not_found(&nf_instance_id))?;
+
+        Ok(Response::new(HeartbeatResponse {
+            heartbeat_timer: self.heartbeat_timer(&nf_instance_id),
+        }))
+    }
+
+    async fn deregister(
+        &self,
+        request: Request<NfInstanceId>,
+    ) -> Result<Response<DeregisterResponse>, Status> {
+        let nf_instance_id = nf_instance_id_from_str(&request.into_inner().nf_instance_id)?;
+
+        self.registry
+            .remove(&nf_instance_id)
+            .ok_or_else(|| not_found(&nf_instance_id))?;
+
+        Ok(Response::new(DeregisterResponse {}))
+    }
+
+    type WatchNfInstancesStream = WatchStream;
+
+    async fn watch_nf_instances(
+        &self,
+        request: Request<WatchRequest>,
+    ) -> Result<Response<Self::WatchNfInstancesStream>, Status> {
+        let req = request.into_inner();
+        let nf_type = match req.nf_type.as_str() {
+            "" => None,
+            nf_type => Some(nf_type_from_str(nf_type)?),
+        };
+        let service_name = non_empty(&req.service_name);
+
+        let nrf = self.clone();
+        let events = self.registry.watch().filter_map(move |event| {
+            let profile = event.profile();
+            let wanted = nf_type.as_ref().map_or(true, |t| profile.nf_type == *t)
+                && service_name.as_ref().map_or(true, |name| {
+                    services(profile).any(|s| s.service_name.to_string() == *name)
+                });
+            future::ready(wanted.then(|| {
+                let mut message = NfInstanceEvent::from(&event);
+                message.profile = Some(nrf.nf_profile(profile));
+                Ok(message)
+            }))
+        });
+
+        Ok(Response::new(Box::pin(events)))
+    }
+}
+
+fn not_found(nf_instance_id: &Uuid) -> Status {
+    Status::not_found(format!("NF instance {nf_instance_id} is not registered"))
+}
+
+fn nf_instance_id_from_str(nf_instance_id: &str) -> Result<Uuid, Status> {
+    nf_instance_id
+        .parse()
+        .map_err(|_| Status::invalid_argument(format!("invalid NF instance id {nf_instance_id}")))
+}
+
+fn non_empty(s: &str) -> Option<String> {
+    (!s.is_empty()).then(|| s.to_string())
+}
+
+fn sst(sst: i32) -> Result<u8, String> {
+    u8::try_from(sst).map_err(|_| format!("invalid SST {sst}"))
+}
+
+fn services(profile: &models::NfProfile) -> impl Iterator<Item = &models::NfService> {
+    profile
+        .nf_services
+        .iter()
+        .flatten()
+        .chain(profile.nf_service_list.iter().flat_map(|l| l.values()))
+}
+
+pub fn nf_type_from_str(nf_type: &str) -> Result<models::NfType, Status> {
+    serde_json::from_value(serde_json::Value::String(nf_type.to_uppercase()))
+        .map_err(|_| Status::invalid_argument(format!("unknown NF type {nf_type}")))
+}
+
+pub fn nf_type_to_string(nf_type: &models::NfType) -> String {
+    match serde_json::to_value(nf_type) {
+        Ok(serde_json::Value::String(s)) => s,
+        _ => String::new(),
+    }
+}
+
+impl From<&models::NfProfile> for NfProfile {
+    fn from(profile: &models::NfProfile) -> Self {
+        NfProfile {
+            nf_instance_id: profile.nf_instance_id.to_string(),
+            nf_type: nf_type_to_string(&profile.nf_type),
+            nf_status: profile.nf_status.to_string(),
+            heartbeat_timer: 0,
+            ipv4_addresses: profile
+                .ipv4_addresses
+                .iter()
+                .flatten()
+                .map(|a| a.to_string())
+                .collect(),
+            allowed_nf_types: vec![],
+            priority: profile.priority.unwrap_or(0).into(),
+            capacity: profile.capacity.unwrap_or(0).into(),
+            load: profile.load.unwrap_or(0).into(),
+            nf_service_list: profile
+                .nf_service_list
+                .iter()
+                .flatten()
+                .map(|(id, s)| (id.clone(), NfService::from(s)))
+                .chain(
+                    profile
+                        .nf_services
+                        .iter()
+                        .flatten()
+                        .map(|s| (s.service_instance_id.clone(), NfService::from(s))),
+                )
+                .collect(),
+            nf_profile_changes_support_ind: true,
+            s_nssais: profile
+                .s_nssais
+                .iter()
+                .flatten()
+                .map(ExtSnssai::from)
+                .collect(),
+            plmn_list: profile
+                .plmn_list
+                .iter()
+                .flatten()
+                .map(PlmnId::from)
+                .collect(),
+            amf_info: profile
+                .amf_info
+                .as_ref()
+                .or_else(|| profile.amf_info_list.iter().flat_map(|l| l.values()).next())
+                .map(AmfInfo::from),
+            smf_info: profile
+                .smf_info
+                .as_ref()
+                .or_else(|| profile.smf_info_list.iter().flat_map(|l| l.values()).next())
+                .map(SmfInfo::from),
+            upf_info: profile
+                .upf_info
+                .as_ref()
+                .or_else(|| profile.upf_info_list.iter().flat_map(|l| l.values()).next())
+                .map(UpfInfo::from),
+        }
+    }
+}
+
+/// The allowed NF types of a gRPC profile, which its discovery form has no
+/// place for.
+impl From<&NfProfile> for AllowedNfTypes {
+    fn from(profile: &NfProfile) -> Self {
+        AllowedNfTypes {
+            profile: (!profile.allowed_nf_types.is_empty())
+                .then(|| profile.allowed_nf_types.clone()),
+            services: profile
+                .nf_service_list
+                .iter()
+                .filter(|(_, s)| !s.allowed_nf_types.is_empty())
+                .map(|(id, s)| (id.clone(), s.allowed_nf_types.clone()))
+                .collect(),
+        }
+    }
+}
+
+impl From<&models::NfService> for NfService {
+    fn from(service: &models::NfService) -> Self {
+        NfService {
+            service_instance_id: service.service_instance_id.clone(),
+            service_name: service.service_name.to_string(),
+            versions: service
+                .versions
+                .iter()
+                .map(|v| NfVersion {
+                    api_version_in_url: v.api_version_in_uri.clone(),
+                    api_full_version: v.api_full_version.clone(),
+                })
+                .collect(),
+            scheme: service.scheme.to_string(),
+            nf_service_status: service.nf_service_status.to_string(),
+            ip_endpoints: service
+                .ip_end_points
+                .iter()
+                .flatten()
+                .map(|e| IpEndpoints {
+                    ipv4_address: e.ipv4_address.clone().unwrap_or_default(),
+                    port: e.port.unwrap_or(0).into(),
+                })
+                .collect(),
+            allowed_nf_types: vec![],
+            priority: service.priority.unwrap_or(0).into(),
+            capacity: service.capacity.unwrap_or(0).into(),
+            load: service.load.unwrap_or(0).into(),
+        }
+    }
+}
+
+/// Sends the queries of a `DiscoveryCache` over gRPC.
+#[tonic::async_trait]
+impl Discover for NrfDiscoveryClient<Channel> {
+    async fn discover(&self, query: &SearchQuery) -> Result<models::SearchResult, ApiError> {
+        let reply = self
+            .clone()
+            .search(SearchRequest::from(query))
+            .await
+            .map_err(|e| ApiError(format!("Discovery failed: {}", e)))?
+            .into_inner();
+
+        let mut result = models::SearchResult::new(
+            reply
+                .nf_instances
+                .iter()
+                .map(models::NfProfile::try_from)
+                .collect::<Result<_, _>>()
+                .map_err(ApiError)?,
+        );
+        result.validity_period = Some(reply.validity_period);
+        Ok(result)
+    }
+}
+
+impl From<&SearchQuery> for SearchRequest {
+    fn from(query: &SearchQuery) -> Self {
+        SearchRequest {
+            requester_nf_type: nf_type_to_string(&query.requester_nf_type),
+            service_names: query
+                .service_names
+                .iter()
+                .flatten()
+                .map(|s| s.to_string())
+                .collect(),
+            target_nf_type: nf_type_to_string(&query.target_nf_type),
+            requester_features: String::new(),
+            snssais: query.snssais.iter().flatten().map(Snssai::from).collect(),
+            dnn: query.dnn.clone().unwrap_or_default(),
+            target_plmn_list: query
+                .target_plmn_list
+                .iter()
+                .flatten()
+                .map(PlmnId::from)
+                .collect(),
+            tai: query.tai.as_ref().map(Tai::from),
+            limit: query.limit.unwrap_or(0),
+        }
+    }
+}
+
+impl TryFrom<SearchRequest> for SearchQuery {
+    type Error = Status;
+
+    fn try_from(req: SearchRequest) -> Result<Self, Self::Error> {
+        let mut query = SearchQuery::new(
+            nf_type_from_str(&req.target_nf_type)?,
+            nf_type_from_str(&req.requester_nf_type)?,
+        );
+
+        let service_names: Vec<models::ServiceName> = req
+            .service_names
+            .iter()
+            .filter(|s| !s.is_empty())
+            .map(|s| models::ServiceName::new(s.clone()))
+            .collect();
+        if !service_names.is_empty() {
+            query.service_names = Some(service_names);
+        }
+        if !req.snssais.is_empty() {
+            query.snssais = Some(
+                req.snssais
+                    .iter()
+                    .map(models::Snssai::try_from)
+                    .collect::<Result<_, _>>()
+                    .map_err(Status::invalid_argument)?,
+            );
+        }
+        query.dnn = non_empty(&req.dnn);
+        if !req.target_plmn_list.is_empty() {
+            query.target_plmn_list = Some(
+                req.target_plmn_list
+                    .iter()
+                    .map(models::PlmnId::from)
+                    .collect(),
+            );
+        }
+        query.tai = req
+            .tai
+            .as_ref()
+            .map(models::Tai::try_from)
+            .transpose()
+            .map_err(Status::invalid_argument)?;
+        query.limit = (req.limit > 0).then_some(req.limit);
+
+        Ok(query)
+    }
+}
+
+impl From<&RegistryEvent> for NfInstanceEvent {
+    fn from(event: &RegistryEvent) -> Self {
+        let event_type = match event {
+            RegistryEvent::Registered(_) => Event::NfRegistered,
+            RegistryEvent::ProfileChanged(_) => Event::NfProfileChanged,
+            RegistryEvent::Deregistered(_) => Event::NfDeregistered,
+        };
+        NfInstanceEvent {
+            event: event_type as i32,
+            nf_instance_id: event.profile().nf_instance_id.to_string(),
+            profile: Some(NfProfile::from(event.profile())),
+        }
+    }
+}
+
+impl TryFrom<&NfProfile> for models::NfProfile {
+    type Error = String;
+
+    fn try_from(profile: &NfProfile) -> Result<Self, Self::Error> {
+        let nf_instance_id = profile
+            .nf_instance_id
+            .parse()
+            .map_err(|e| format!("invalid NF instance id {}: {e}", profile.nf_instance_id))?;
+        let nf_type = nf_type_from_str(&profile.nf_type).map_err(|e| e.message().to_string())?;
+
+        let mut result = models::NfProfile::new(
+            nf_instance_id,
+            nf_type,
+            models::NfStatus::new(profile.nf_status.clone()),
+        );
+        result.ipv4_addresses = Some(
+            profile
+                .ipv4_addresses
+                .iter()
+                .map(|a| models::Ipv4Addr::from(a.clone()))
+                .collect(),
+        );
+        result.priority = u16::try_from(profile.priority).ok();
+        result.capacity = u16::try_from(profile.capacity).ok();
+        result.load = u8::try_from(profile.load).ok();
+        result.nf_service_list = Some(
+            profile
+                .nf_service_list
+                .iter()
+                .map(|(id, s)| (id.clone(), models::NfService::from(s)))
+                .collect(),
+        );
+        if !profile.s_nssais.is_empty() {
+            result.s_nssais = Some(
+                profile
+                    .s_nssais
+                    .iter()
+                    .map(models::ExtSnssai::try_from)
+                    .collect::<Result<_, _>>()?,
+            );
+        }
+        if !profile.plmn_list.is_empty() {
+            result.plmn_list = Some(profile.plmn_list.iter().map(models::PlmnId::from).collect());
+        }
+        result.amf_info = profile
+            .amf_info
+            .as_ref()
+            .map(models::AmfInfo::try_from)
+            .transpose()?;
+        result.smf_info = profile
+            .smf_info
+            .as_ref()
+            .map(models::SmfInfo::try_from)
+            .transpose()?;
+        result.upf_info = profile
+            .upf_info
+            .as_ref()
+            .map(models::UpfInfo::try_from)
+            .transpose()?;
+        Ok(result)
+    }
+}
+
+impl From<&NfService> for models::NfService {
+    fn from(service: &NfService) -> Self {
+        let mut result = models::NfService::new(
+            service.service_instance_id.clone(),
+            models::ServiceName::new(service.service_name.clone()),
+            service
+                .versions
+                .iter()
+                .map(|v| {
+                    models::NfServiceVersion::new(
+                        v.api_version_in_url.clone(),
+                        v.api_full_version.clone(),
+                    )
+                })
+                .collect(),
+            models::UriScheme::new(service.scheme.clone()),
+            models::NfServiceStatus::new(service.nf_service_status.clone()),
+        );
+        result.ip_end_points = Some(
+            service
+                .ip_endpoints
+                .iter()
+                .map(|e| {
+                    let mut endpoint = models::IpEndPoint::new();
+                    endpoint.ipv4_address = Some(e.ipv4_address.clone());
+                    endpoint.port = u16::try_from(e.port).ok();
+                    endpoint
+                })
+                .collect(),
+        );
+        result.priority = u16::try_from(service.priority).ok();
+        result.capacity = u16::try_from(service.capacity).ok();
+        result.load = u8::try_from(service.load).ok();
+        result
+    }
+}
+
+impl From<&models::Snssai> for Snssai {
+    fn from(snssai: &models::Snssai) -> Self {
+        Snssai {
+            sst: snssai.sst.into(),
+            sd: snssai.sd.clone().unwrap_or_default(),
+        }
+    }
+}
+
+impl TryFrom<&Snssai> for models::Snssai {
+    type Error = String;
+
+    fn try_from(snssai: &Snssai) -> Result<Self, Self::Error> {
+        let mut result = models::Snssai::new(sst(snssai.sst)?);
+        result.sd = non_empty(&snssai.sd);
+        Ok(result)
+    }
+}
+
+impl From<&models::ExtSnssai> for ExtSnssai {
+    fn from(snssai: &models::ExtSnssai) -> Self {
+        ExtSnssai {
+            sst: snssai.sst.into(),
+            sd: snssai.sd.clone().unwrap_or_default(),
+            sd_ranges: snssai
+                .sd_ranges
+                .iter()
+                .flatten()
+                .map(|r| SdRange {
+                    start: r.start.clone().unwrap_or_default(),
+                    end: r.end.clone().unwrap_or_default(),
+                })
+                .collect(),
+            wildcard_sd: snssai.wildcard_sd.unwrap_or(false),
+        }
+    }
+}
+
+impl TryFrom<&ExtSnssai> for models::ExtSnssai {
+    type Error = String;
+
+    fn try_from(snssai: &ExtSnssai) -> Result<Self, Self::Error> {
+        let mut result = models::ExtSnssai::new(sst(snssai.sst)?);
+        result.sd = non_empty(&snssai.sd);
+        if !snssai.sd_ranges.is_empty() {
+            result.sd_ranges = Some(
+                snssai
+                    .sd_ranges
+                    .iter()
+                    .map(|r| {
+                        let mut range = models::SdRange::new();
+                        range.start = non_empty(&r.start);
+                        range.end = non_empty(&r.end);
+                        range
+                    })
+                    .collect(),
+            );
+        }
+        result.wildcard_sd = snssai.wildcard_sd.then_some(true);
+        Ok(result)
+    }
+}
+
+impl From<&models::PlmnId> for PlmnId {
+    fn from(plmn_id: &models::PlmnId) -> Self {
+        PlmnId {
+            mcc: plmn_id.mcc.clone(),
+            mnc: plmn_id.mnc.clone(),
+        }
+    }
+}
+
+impl From<&PlmnId> for models::PlmnId {
+    fn from(plmn_id: &PlmnId) -> Self {
+        models::PlmnId::new(plmn_id.mcc.clone(), plmn_id.mnc.clone())
+    }
+}
+
+impl From<&models::Tai> for Tai {
+    fn from(tai: &models::Tai) -> Self {
+        Tai {
+            plmn_id: Some(PlmnId::from(&tai.plmn_id)),
+            tac: tai.tac.clone(),
+        }
+    }
+}
+
+impl TryFrom<&Tai> for models::Tai {
+    type Error = String;
+
+    fn try_from(tai: &Tai) -> Result<Self, Self::Error> {
+        let plmn_id = tai
+            .plmn_id
+            .as_ref()
+            .ok_or_else(|| format!("TAI {} without PLMN", tai.tac))?;
+        Ok(models::Tai::new(
+            models::PlmnId::from(plmn_id),
+            tai.tac.clone(),
+        ))
+    }
+}
+
+fn tai_list(tai_list: &Option<Vec<models::Tai>>) -> Vec<Tai> {
+    tai_list.iter().flatten().map(Tai::from).collect()
+}
+
+fn try_tai_list(tai_list: &[Tai]) -> Result<Option<Vec<models::Tai>>, String> {
+    if tai_list.is_empty() {
+        return Ok(None);
+    }
+    tai_list
+        .iter()
+        .map(models::Tai::try_from)
+        .collect::<Result<_, _>>()
+        .map(Some)
+}
+
+impl From<&models::AmfInfo> for AmfInfo {
+    fn from(info: &models::AmfInfo) -> Self {
+        AmfInfo {
+            amf_set_id: info.amf_set_id.clone(),
+            amf_region_id: info.amf_region_id.clone(),
+            tai_list: tai_list(&info.tai_list),
+        }
+    }
+}
+
+impl TryFrom<&AmfInfo> for models::AmfInfo {
+    type Error = String;
+
+    fn try_from(info: &AmfInfo) -> Result<Self, Self::Error> {
+        let mut result =
+            models::AmfInfo::new(info.amf_set_id.clone(), info.amf_region_id.clone(), vec![]);
+        result.tai_list = try_tai_list(&info.tai_list)?;
+        Ok(result)
+    }
+}
+
+impl From<&models::SmfInfo> for SmfInfo {
+    fn from(info: &models::SmfInfo) -> Self {
+        SmfInfo {
+            s_nssai_smf_info_list: info
+                .s_nssai_smf_info_list
+                .iter()
+                .map(|i| SnssaiInfoItem {
+                    s_nssai: Some(ExtSnssai::from(&i.s_nssai)),
+                    dnns: i
+                        .dnn_smf_info_list
+                        .iter()
+                        .map(|d| d.dnn.to_string())
+                        .collect(),
+                })
+                .collect(),
+            tai_list: tai_list(&info.tai_list),
+        }
+    }
+}
+
+impl TryFrom<&SmfInfo> for models::SmfInfo {
+    type Error = String;
+
+    fn try_from(info: &SmfInfo) -> Result<Self, Self::Error> {
+        let items = info
+            .s_nssai_smf_info_list
+            .iter()
+            .map(|i| {
+                Ok(models::SnssaiSmfInfoItem::new(
+                    item_snssai(i)?,
+                    i.dnns
+                        .iter()
+                        .map(|d| {
+                            models::DnnSmfInfoItem::new(models::DnnSmfInfoItemDnn::new(d.clone()))
+                        })
+                        .collect(),
+                ))
+            })
+            .collect::<Result<_, String>>()?;
+        let mut result = models::SmfInfo::new(items);
+        result.tai_list = try_tai_list(&info.tai_list)?;
+        Ok(result)
+    }
+}
+
+impl From<&models::UpfInfo> for UpfInfo {
+    fn from(info: &models::UpfInfo) -> Self {
+        UpfInfo {
+            s_nssai_upf_info_list: info
+                .s_nssai_upf_info_list
+                .iter()
+                .map(|i| SnssaiInfoItem {
+                    s_nssai: Some(ExtSnssai::from(&i.s_nssai)),
+                    dnns: i.dnn_upf_info_list.iter().map(|d| d.dnn.clone()).collect(),
+                })
+                .collect(),
+            tai_list: tai_list(&info.tai_list),
+        }
+    }
+}
+
+impl TryFrom<&UpfInfo> for models::UpfInfo {
+    type Error = String;
+
+    fn try_from(info: &UpfInfo) -> Result<Self, Self::Error> {
+        let items = info
+            .s_nssai_upf_info_list
+            .iter()
+            .map(|i| {
+                Ok(models::SnssaiUpfInfoItem::new(
+                    item_snssai(i)?,
+                    i.dnns
+                        .iter()
+                        .map(|d| models::DnnUpfInfoItem::new(d.clone()))
+                        .collect(),
+                ))
+            })
+            .collect::<Result<_, String>>()?;
+        let mut result = models::UpfInfo::new(items);
+        result.tai_list = try_tai_list(&info.tai_list)?;
+        Ok(result)
+    }
+}
+
+fn item_snssai(item: &SnssaiInfoItem) -> Result<models::ExtSnssai, String> {
+    item.s_nssai
+        .as_ref()
+        .ok_or_else(|| "S-NSSAI information without S-NSSAI".to_string())
+        .and_then(models::ExtSnssai::try_from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile() -> NfProfile {
+        let service = NfService {
+            service_instance_id: "1".into(),
+            service_name: "nsmf-pdusession".into(),
+            versions: vec![NfVersion {
+                api_version_in_url: "v1".into(),
+                api_full_version: "1.0.0".into(),
+            }],
+            scheme: "http".into(),
+            nf_service_status: "REGISTERED".into(),
+            ip_endpoints: vec![IpEndpoints {
+                ipv4_address: "10.0.0.1".into(),
+                port: 9092,
+            }],
+            allowed_nf_types: vec!["AMF".into()],
+            priority: 1,
+            capacity: 100,
+            load: 10,
+        };
+        NfProfile {
+            nf_instance_id: Uuid::new_v4().to_string(),
+            nf_type: "SMF".into(),
+            nf_status: "REGISTERED".into(),
+            heartbeat_timer: 30,
+            ipv4_addresses: vec!["10.0.0.1".into()],
+            allowed_nf_types: vec!["AMF".into(), "SCP".into()],
+            priority: 1,
+            capacity: 100,
+            load: 10,
+            nf_service_list: [("1".to_string(), service)].into_iter().collect(),
+            nf_profile_changes_support_ind: true,
+            s_nssais: vec![ExtSnssai {
+                sst: 1,
+                sd: "000001".into(),
+                sd_ranges: vec![],
+                wildcard_sd: false,
+            }],
+            plmn_list: vec![PlmnId {
+                mcc: "001".into(),
+                mnc: "01".into(),
+            }],
+            amf_info: None,
+            smf_info: None,
+            upf_info: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn registered_profiles_round_trip() {
+        let nrf = MyNRF::default();
+        let profile = profile();
+
+        let registered = nrf.register(Request::new(profile.clone())).await.unwrap();
+        assert_eq!(registered.into_inner(), profile);
+
+        let mut query =
+            SearchRequest::from(&SearchQuery::new(models::NfType::SMF, models::NfType::AMF));
+        query.service_names = vec!["nsmf-pdusession".into()];
+        let found = nrf.search(Request::new(query)).await.unwrap().into_inner();
+        assert_eq!(found.nf_instances, vec![profile]);
+    }
+
+    #[tokio::test]
+    async fn nfs_without_heartbeat_timer_get_the_default_one() {
+        let nrf = MyNRF::default();
+        let mut profile = profile();
+        profile.heartbeat_timer = 0;
+        profile.allowed_nf_types = vec![];
+
+        let registered = nrf
+            .register(Request::new(profile))
+            .await
+            .unwrap()
+            .into_inner();
+        assert_eq!(registered.heartbeat_timer, HEART_BEAT_TIMER);
+        assert!(registered.allowed_nf_types.is_empty());
+    }
+}
@@ -150,7 +150,10 @@ fn main() {
          Operation::UpdateSmContext => {
             let result = rt.block_on(client.update_sm_context(
                   "sm_context_ref_example".to_string(),
-                  ???
+                  ???,
+                  Some(swagger::ByteArray(Vec::from("BINARY_DATA_HERE"))),
+                  Some(swagger::ByteArray(Vec::from("BINARY_DATA_HERE"))),
+                  Some(swagger::ByteArray(Vec::from("BINARY_DATA_HERE")))
             ));
             info!("{:?} (X-Span-ID: {:?})", result, (client.context() as &dyn Has<XSpanIdString>).get().clone());
         },
@@ -5,9 +5,17 @@
 use std::str::FromStr;
 
 use clap::Parser;
+use nnrf_discovery_server::cache::DiscoveryCache;
+use nnrf_discovery_server::selection::{NfSelector, SelectionPolicy};
+use nnrf_nfmanagement_server::models::NfType;
+use nnrf_nfmanagement_server::{registration, ContextWrapperExt};
+use nsfm_pdusession::ip_pools::{Backend, IpPools, PoolConfig};
+use swagger::{AuthData, ContextBuilder, EmptyContext, Push, XSpanIdString};
 use url::Url;
 
+mod nf_status;
 mod server;
+mod sm_data;
 
 #[derive(Parser)]
 pub struct Opts {
@@ -20,6 +28,19 @@ pub struct Opts {
     pub udm: String,
     #[clap(short = 'u', long, default_value = "http://127.0.0.1:8083")]
     pub amf: String,
+    /// Where the NRF sends the NF status notifications
+    #[clap(long, default_value = "http://127.0.0.1:8092")]
+    pub nf_status: String,
+    /// How the UDM is chosen: weighted, load-aware or round-robin
+    #[clap(long, default_value = "weighted")]
+    pub selection: SelectionPolicy,
+    /// A pool of UE addresses, `DNN=PREFIX`, as many as needed: an IPv4 and
+    /// an IPv6 one per DNN at most
+    #[clap(long = "ue-pool", default_value = "internet=192.168.100.0/24")]
+    pub ue_pools: Vec<PoolConfig>,
+    /// Where the UE address allocations are kept: `memory`, or a file
+    #[clap(long, default_value = "memory")]
+    pub ue_pool_store: Backend,
 }
 
 /// Create custom server, wire it to the autogenerated router,
@@ -36,6 +57,58 @@ async fn main() {
         false
     };
 
+    let profile = registration::nf_profile(
+        uuid::Uuid::new_v4(),
+        NfType::SMF,
+        "nsmf-pdusession",
+        "v1",
+        "1.0.0",
+        base_url.scheme(),
+        base_url.host_str().unwrap(),
+        base_url.port().unwrap(),
+    );
+    let context: swagger::make_context_ty!(
+        ContextBuilder,
+        EmptyContext,
+        Option<AuthData>,
+        XSpanIdString
+    ) = swagger::make_context!(
+        ContextBuilder,
+        EmptyContext,
+        None as Option<AuthData>,
+        XSpanIdString::default()
+    );
+    let nrf = nnrf_nfmanagement_server::Client::try_new_http(&opts.nrf)
+        .expect("unable to create NRF client")
+        .with_context(context.clone());
+    tokio::spawn(registration::maintain(nrf, profile));
+
+    let nrf_discovery = nnrf_discovery_server::ContextWrapperExt::with_context(
+        nnrf_discovery_server::Client::try_new_http(&opts.nrf)
+            .expect("unable to create NRF client"),
+        context,
+    );
+
+    let nf_status_url = Url::parse(&opts.nf_status).expect("unable to create url");
+    let udm_directory = nf_status::UdmDirectory::new(
+        DiscoveryCache::new(nrf_discovery),
+        NfSelector::new(opts.selection),
+    );
+    let nf_status_addr = format!(
+        "{}:{}",
+        nf_status_url.host_str().unwrap(),
+        nf_status_url.port().unwrap()
+    );
+    let directory = udm_directory.clone();
+    tokio::spawn(async move { nf_status::serve(&nf_status_addr, directory).await });
+    tokio::spawn(nf_status::subscribe(
+        url::Url::from_str(&opts.nrf).expect("unable to create url"),
+        nf_status_url,
+    ));
+
+    let ip_pools = IpPools::new(&opts.ue_pools, opts.ue_pool_store.store())
+        .expect("unable to recover the UE address allocations");
+
     server::create(
         &format!(
             "{}:{}",
@@ -46,6 +119,8 @@ async fn main() {
         url::Url::from_str(&opts.nrf).expect("unable to create url"),
         url::Url::from_str(&opts.udm).expect("unable to create url"),
         url::Url::from_str(&opts.amf).expect("unable to create url"),
+        udm_directory,
+        ip_pools,
     )
     .await;
 }
@@ -0,0 +1,229 @@
+//! UDM instances known from the NF status notifications of the NRF.
+//!
+//! The SMF subscribes to the status of the UDMs and keeps the profile of each
+//! registered one. The notifications are logged on arrival so that the
+//! propagation delay of a profile change can be measured.
+//!
+//! Until a UDM is notified, the UDMs are discovered through a
+//! `DiscoveryCache`, which the notifications invalidate. Either way, the UDM
+//! used is chosen by an `NfSelector`.
+
+use async_trait::async_trait;
+use log::{info, warn};
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use swagger::auth::MakeAllowAllAuthenticator;
+use swagger::{
+    ApiError, AuthData, ContextBuilder, ContextWrapper, DropContextService, EmptyContext, Has,
+    Push, XSpanIdString,
+};
+use uuid::Uuid;
+
+use nnrf_discovery_server::cache::DiscoveryCache;
+use nnrf_discovery_server::registry::SearchQuery;
+use nnrf_discovery_server::selection::NfSelector;
+
+use nnrf_nfmanagement_server::models::{
+    self, NfStatusAnyOf, NfType, NotificationData, NotificationEventType, SubscrCond,
+    SubscriptionData,
+};
+use nnrf_nfmanagement_server::{
+    Api, CallbackApi, CreateSubscriptionResponse, OnNfStatusEventPostResponse,
+};
+
+/// Service the SMF consumes from the UDM.
+const UDM_SERVICE: &str = "nudm-sdm";
+
+/// Delay before retrying a subscription the NRF did not accept.
+const RETRY_PERIOD: Duration = Duration::from_secs(5);
+
+type ClientContext = swagger::make_context_ty!(
+    ContextBuilder,
+    EmptyContext,
+    Option<AuthData>,
+    XSpanIdString
+);
+
+/// HTTP client of the NRF discovery service.
+pub type NrfDiscovery = ContextWrapper<
+    nnrf_discovery_server::Client<
+        DropContextService<hyper::client::Client<hyper::client::HttpConnector>, ClientContext>,
+        ClientContext,
+    >,
+    ClientContext,
+>;
+
+/// Profiles of the registered UDMs, by NF instance id.
+#[derive(Clone)]
+pub struct UdmDirectory {
+    instances: Arc<RwLock<BTreeMap<Uuid, nnrf_discovery_server::models::NfProfile>>>,
+    discovery: DiscoveryCache<NrfDiscovery>,
+    selector: NfSelector,
+}
+
+impl UdmDirectory {
+    pub fn new(discovery: DiscoveryCache<NrfDiscovery>, selector: NfSelector) -> Self {
+        UdmDirectory {
+            instances: Arc::default(),
+            discovery,
+            selector,
+        }
+    }
+
+    /// URI of a registered UDM, chosen among those the NRF notified, or else
+    /// among those it returns to a discovery.
+    pub async fn url(&self) -> Option<url::Url> {
+        let notified: Vec<_> = self
+            .instances
+            .read()
+            .expect("directory lock poisoned")
+            .values()
+            .cloned()
+            .collect();
+        if let Some(endpoint) = self.selector.select(&notified, UDM_SERVICE) {
+            return url::Url::parse(&endpoint.uri).ok();
+        }
+
+        let mut query = SearchQuery::new(
+            nnrf_discovery_server::models::NfType::UDM,
+            nnrf_discovery_server::models::NfType::SMF,
+        );
+        query.service_names = Some(vec![nnrf_discovery_server::models::ServiceName::new(
+            UDM_SERVICE.to_string(),
+        )]);
+        let result = self.discovery.search(&query).await;
+        info!("UDM discovery, cache {:?}", self.discovery.stats());
+
+        match result {
+            Ok(result) => self
+                .selector
+                .select(&result.nf_instances, UDM_SERVICE)
+                .and_then(|endpoint| url::Url::parse(&endpoint.uri).ok()),
+            Err(e) => {
+                warn!("UDM discovery failed: {}", e);
+                None
+            }
+        }
+    }
+
+    fn update(
+        &self,
+        nf_instance_id: Uuid,
+        profile: Option<nnrf_discovery_server::models::NfProfile>,
+    ) {
+        let mut instances = self.instances.write().expect("directory lock poisoned");
+        match profile {
+            Some(profile) => instances.insert(nf_instance_id, profile),
+            None => instances.remove(&nf_instance_id),
+        };
+    }
+}
+
+#[async_trait]
+impl<C> CallbackApi<C> for UdmDirectory
+where
+    C: Has<XSpanIdString> + Send + Sync,
+{
+    async fn on_nf_status_event_post(
+        &self,
+        callback_request_body_nf_status_notification_uri: String,
+        notification_data: NotificationData,
+        context: &C,
+    ) -> Result<OnNfStatusEventPostResponse, ApiError> {
+        info!(
+            "NF status notification {} for {} - X-Span-ID: {:?}",
+            notification_data.event,
+            notification_data.nf_instance_uri,
+            context.get().0.clone()
+        );
+
+        let nf_instance_id = match notification_data
+            .nf_instance_uri
+            .rsplit('/')
+            .next()
+            .and_then(|id| id.parse::<Uuid>().ok())
+        {
+            Some(id) => id,
+            None => {
+                let mut problem = models::ProblemDetails::new();
+                problem.status = Some(400);
+                problem.cause = Some("MANDATORY_IE_INCORRECT".to_string());
+                problem.detail = Some(format!(
+                    "Invalid nfInstanceUri {}",
+                    notification_data.nf_instance_uri
+                ));
+                return Ok(OnNfStatusEventPostResponse::BadRequest(problem));
+            }
+        };
+
+        let profile = notification_data.nf_profile.as_ref().map(|p| &p.profile);
+        self.discovery.invalidate(&nf_instance_id, profile);
+
+        let profile = match notification_data.event {
+            NotificationEventType::Deregistered => None,
+            NotificationEventType::Registered | NotificationEventType::ProfileChanged => profile
+                .filter(|p| p.nf_status.to_string() == NfStatusAnyOf::Registered.to_string())
+                .cloned(),
+        };
+        self.update(nf_instance_id, profile);
+
+        Ok(OnNfStatusEventPostResponse::ExpectedResponseToASuccessfulCallbackProcessing)
+    }
+}
+
+/// Subscribes to the status of the UDMs, retrying until the NRF accepts.
+pub async fn subscribe(nrf_url: url::Url, notification_url: url::Url) {
+    let client = nnrf_nfmanagement_server::Client::try_new_http(nrf_url.as_str())
+        .expect("Failed to create HTTP client");
+    let context: swagger::make_context_ty!(
+        ContextBuilder,
+        EmptyContext,
+        Option<AuthData>,
+        XSpanIdString
+    ) = swagger::make_context!(
+        ContextBuilder,
+        EmptyContext,
+        None as Option<AuthData>,
+        XSpanIdString::default()
+    );
+
+    let mut subscription = SubscriptionData::new(notification_url.to_string());
+    subscription.req_nf_type = Some(NfType::SMF);
+    subscription.subscr_cond = Some(SubscrCond {
+        nf_type: Some(NfType::UDM),
+        ..SubscrCond::default()
+    });
+
+    loop {
+        match client
+            .create_subscription(subscription.clone(), None, None, &context)
+            .await
+        {
+            Ok(CreateSubscriptionResponse::ExpectedResponseToAValidRequest {
+                location, ..
+            }) => {
+                info!("Subscribed to the UDM status: {}", location);
+                return;
+            }
+            Ok(rsp) => warn!("NRF rejected the subscription: {:?}", rsp),
+            Err(e) => warn!("Unable to subscribe to the NRF: {}", e),
+        }
+        tokio::time::sleep(RETRY_PERIOD).await;
+    }
+}
+
+/// Serves the NF status notifications sent by the NRF.
+pub async fn serve(addr: &str, directory: UdmDirectory) {
+    let addr = addr.parse().expect("Failed to parse bind address");
+
+    let service = nnrf_nfmanagement_server::client::callbacks::MakeService::new(directory);
+    let service = MakeAllowAllAuthenticator::new(service, "cosmo");
+    let service =
+        nnrf_nfmanagement_server::context::MakeAddContext::<_, EmptyContext>::new(service);
+
+    hyper::server::Server::bind(&addr)
+        .serve(service)
+        .await
+        .unwrap()
+}
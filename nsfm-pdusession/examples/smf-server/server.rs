@@ -11,7 +11,7 @@ use log::info;
 use nudm_sdm::models::Snssai;
 use std::future::Future;
 use std::marker::PhantomData;
-use std::net::SocketAddr;
+use std::net::{Ipv4Addr, SocketAddr};
 use std::str::FromStr;
 use std::sync::{Arc, Mutex};
 use std::task::{Context, Poll};
@@ -43,9 +43,20 @@ use nudm_sdm::{Api as UDMApi, ApiNoContext as UDMApiNoContext, Client as UDMClie
 #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "ios")))]
 use openssl::ssl::{Ssl, SslAcceptor, SslAcceptorBuilder, SslFiletype, SslMethod};
 
+use nsfm_pdusession::ip_pools::IpPools;
 use nsfm_pdusession::models::{
     self, ExtProblemDetails, SmContextCreateError, SmContextCreatedData,
 };
+use nsfm_pdusession::ngap;
+use nsfm_pdusession::sm_contexts::{PduSession, SmContextError, SmContextStore};
+
+use crate::nf_status::UdmDirectory;
+use crate::sm_data::SmDataCache;
+
+/// The DNS servers given to the UEs asking for them
+const DNS_SERVERS: [Ipv4Addr; 2] = [Ipv4Addr::new(8, 8, 8, 8), Ipv4Addr::new(8, 8, 4, 4)];
+/// The N3 address of the UPF the sessions go through
+const UPF_N3_ADDRESS: Ipv4Addr = Ipv4Addr::new(172, 22, 0, 8);
 
 /// Builds an SSL implementation for Simple HTTPS from some hard-coded file names
 pub async fn create(
@@ -54,10 +65,12 @@ pub async fn create(
     nrf_url: url::Url,
     udm_url: url::Url,
     amf_url: url::Url,
+    udm_directory: UdmDirectory,
+    ip_pools: IpPools,
 ) {
     let addr = addr.parse().expect("Failed to parse bind address");
 
-    let server = Server::new(nrf_url, udm_url, amf_url);
+    let server = Server::new(nrf_url, udm_url, amf_url, udm_directory, ip_pools);
 
     let service = MakeService::new(server);
 
@@ -124,6 +137,9 @@ pub struct Server<C> {
     nfr_url: url::Url,
     udm_url: url::Url,
     amf_url: url::Url,
+    udm_directory: UdmDirectory,
+    sm_data: SmDataCache,
+    sm_contexts: SmContextStore,
 }
 
 unsafe impl<C> Send for Server<C> {}
@@ -132,7 +148,13 @@ unsafe impl<C> Sync for Server<C> {}
 // impl<C> Copy for Server<C> { }
 
 impl<C> Server<C> {
-    pub fn new(nfr_url: url::Url, udm_url: url::Url, amf_url: url::Url) -> Self {
+    pub fn new(
+        nfr_url: url::Url,
+        udm_url: url::Url,
+        amf_url: url::Url,
+        udm_directory: UdmDirectory,
+        ip_pools: IpPools,
+    ) -> Self {
         let client_ctx: ClientContext = swagger::make_context!(
             ContextBuilder,
             EmptyContext,
@@ -167,8 +189,19 @@ impl<C> Server<C> {
             nfr_url,
             udm_url,
             amf_url,
+            udm_directory,
+            sm_data: SmDataCache::new(),
+            sm_contexts: SmContextStore::with_pools(ip_pools),
         }
     }
+
+    /// A UDM the NRF told us about, or the configured one.
+    async fn udm_url(&self) -> url::Url {
+        self.udm_directory
+            .url()
+            .await
+            .unwrap_or_else(|| self.udm_url.clone())
+    }
 }
 
 use nsfm_pdusession::server::MakeService;
@@ -269,7 +302,20 @@ where
             sm_context_release_data,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+
+        match self.sm_contexts.release(&sm_context_ref) {
+            Ok(session) => {
+                info!(
+                    "SM context {} of {} PDU session {} released, {} left",
+                    sm_context_ref,
+                    session.supi,
+                    session.pdu_session_id,
+                    self.sm_contexts.len()
+                );
+                Ok(ReleaseSmContextResponse::SuccessfulReleaseOfAnSMContextWithoutContentInTheResponse)
+            }
+            Err(e) => Ok(ReleaseSmContextResponse::NotFound(e.problem())),
+        }
     }
 
     /// Retrieve SM Context
@@ -286,7 +332,20 @@ where
             sm_context_retrieve_data,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+
+        match self.sm_contexts.get(&sm_context_ref) {
+            Ok(session) => {
+                // Without EPS interworking there is no PDN connection to
+                // hand over to an MME: the SM context is sent whatever the
+                // smContextType asked for
+                let mut body = models::SmContextRetrievedData::new(String::new());
+                body.sm_context = Some(session.sm_context());
+                Ok(RetrieveSmContextResponse::SuccessfulRetrievalOfAnSMContext(
+                    body,
+                ))
+            }
+            Err(e) => Ok(RetrieveSmContextResponse::NotFound(e.problem())),
+        }
     }
 
     /// Send MO Data
@@ -313,18 +372,25 @@ where
         &self,
         sm_context_ref: String,
         sm_context_update_data: models::SmContextUpdateData,
+        binary_data_n1_sm_message: Option<swagger::ByteArray>,
+        binary_data_n2_sm_information: Option<swagger::ByteArray>,
+        binary_data_n2_sm_information_ext1: Option<swagger::ByteArray>,
         context: &C,
     ) -> Result<UpdateSmContextResponse, ApiError> {
         let context = context.clone();
         info!(
-            "update_sm_context(\"{}\", {:?}) - X-Span-ID: {:?}",
+            "update_sm_context(\"{}\", {:?}, {:?}, {:?}, {:?}) - X-Span-ID: {:?}",
             sm_context_ref,
             sm_context_update_data,
+            binary_data_n1_sm_message,
+            binary_data_n2_sm_information,
+            binary_data_n2_sm_information_ext1,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
-        // Here we receive
 
+        // Here we receive, once the NG-RAN has set up the resources of the
+        // PDU session
+        //
         // Request
         // {
         //     "n2SmInfo":	{
@@ -334,9 +400,81 @@ where
         // }
         // NGAP
         // 0000   00 03 e0 ac 16 00 17 00 00 00 04 00 01
-
+        //
         // Reply is empty: 204
-        // So: UpdateSmContextResponse::SuccessfulUpdateOfAnSMContextWithoutContentInTheResponse
+        //
+        // The N1 SM message of the UE, e.g. a PDU Session Release Request,
+        // and the N2 SM information of the NG-RAN, e.g. the transfer with
+        // its N3 endpoint, drive the transition of the session like over
+        // gRPC and zenoh
+        let n1_sm_msg = binary_data_n1_sm_message
+            .as_ref()
+            .filter(|_| sm_context_update_data.n1_sm_msg.is_some())
+            .map(|n1_sm_msg| n1_sm_msg.0.as_slice());
+        let n2_sm_info = binary_data_n2_sm_information
+            .as_ref()
+            .filter(|_| sm_context_update_data.n2_sm_info.is_some())
+            .map(|n2_sm_info| n2_sm_info.0.as_slice());
+        match self.sm_contexts.update(
+            &sm_context_ref,
+            &sm_context_update_data,
+            n1_sm_msg,
+            n2_sm_info,
+        ) {
+            Ok((session, updated)) => {
+                info!(
+                    "SM context {} of {} PDU session {}: {}, UP {}",
+                    sm_context_ref,
+                    session.supi,
+                    session.pdu_session_id,
+                    session.state,
+                    session.up_cnx_state
+                );
+                // The N1 SM message and N2 SM information the AMF forwards
+                // are sent as the parts the content references
+                let n1_sm_msg = updated
+                    .n1_sm_msg
+                    .as_ref()
+                    .map(|message| swagger::ByteArray(message.encode()));
+                let n2_sm_info = updated
+                    .n2_sm_info_type
+                    .and_then(|info_type| session.n2_sm_info_transfer(info_type))
+                    .map(swagger::ByteArray);
+                Ok(match updated.updated_data() {
+                    Some(mut body) if n1_sm_msg.is_some() || n2_sm_info.is_some() => {
+                        if n2_sm_info.is_none() {
+                            body.n2_sm_info = None;
+                        }
+                        UpdateSmContextResponse::SuccessfulUpdateOfAnSMContextWithContentAndBinaryDataInTheResponse {
+                            body,
+                            binary_data_n1_sm_message: n1_sm_msg,
+                            binary_data_n2_sm_information: n2_sm_info,
+                        }
+                    }
+                    Some(body) => {
+                        UpdateSmContextResponse::SuccessfulUpdateOfAnSMContextWithContentInTheResponse(body)
+                    }
+                    None => {
+                        UpdateSmContextResponse::SuccessfulUpdateOfAnSMContextWithoutContentInTheResponse
+                    }
+                })
+            }
+            Err(e) => {
+                let error = models::SmContextUpdateError::new(e.problem());
+                Ok(match e {
+                    SmContextError::NotFound(_) => {
+                        UpdateSmContextResponse::UnsuccessfulUpdateOfAnSMContext_3(error)
+                    }
+                    SmContextError::UnexpectedN2SmInfo { .. }
+                    | SmContextError::UnexpectedN1SmMsg(_)
+                    | SmContextError::InvalidN1SmMsg(_)
+                    | SmContextError::InvalidN2SmInfo(_) => {
+                        UpdateSmContextResponse::UnsuccessfulUpdateOfAnSMContext_2(error)
+                    }
+                    _ => UpdateSmContextResponse::UnsuccessfulUpdateOfAnSMContext(error),
+                })
+            }
+        }
     }
 
     /// Create
@@ -428,23 +566,43 @@ where
         // 0000   2e 01 01 c1 ff ff 91 a1 28 01 00 7b 00 07 80 00
         // 0010   0a 00 00 0d 00
 
-        let udm_client =
-            UDMClient::try_new_http(&self.udm_url.as_str()).expect("Failed to create HTTP client");
+        let mut session = match json_data
+            .as_ref()
+            .ok_or(SmContextError::MissingIe("jsonData"))
+            .and_then(PduSession::from_create_data)
+        {
+            Ok(session) => session,
+            Err(e) => return Ok(create_error(&e)),
+        };
+        let request = match binary_data_n1_sm_message
+            .as_ref()
+            .ok_or(SmContextError::MissingIe("n1SmMsg"))
+            .and_then(|n1_sm_msg| session.establishment_request(&n1_sm_msg.0))
+        {
+            Ok(request) => request,
+            Err(e) => return Ok(create_error(&e)),
+        };
+
+        let udm_client = UDMClient::try_new_http(self.udm_url().await.as_str())
+            .expect("Failed to create HTTP client");
         // We get the context from the UDM
         // Open5gs call is to /nudm-sdm/v2/imsi-001011234567895/sm-data?single-nssai=%7B%0A%09%22sst%22%3A%091%0A%7D&dnn=internet
 
-        let resp = UDMApi::get_sm_data(
-            &udm_client,
-            json_data.expect("no data!").supi.expect("No supi!"),
-            None,
-            Some(Snssai { sst: 1, sd: None }),
-            Some("internet".to_string()),
-            None,
-            None,
-            None,
-            &self.client_ctx,
-        )
-        .await?;
+        // Served from the cache while fresh, revalidated with the UDM afterwards
+        let single_nssai = Snssai {
+            sst: session.s_nssai.sst,
+            sd: session.s_nssai.sd.clone(),
+        };
+        let resp = self
+            .sm_data
+            .get_sm_data(
+                &udm_client,
+                session.supi.clone(),
+                single_nssai.clone(),
+                session.dnn.clone(),
+                &self.client_ctx,
+            )
+            .await?;
 
         // Here we should receive
         //
@@ -481,11 +639,66 @@ where
 
         match resp {
             nudm_sdm::GetSmDataResponse::ExpectedResponseToAValidRequest {
-                body: _,
+                body,
                 cache_control: _,
                 e_tag: _,
                 last_modified: _,
             } => {
+                // The DNN configuration may come from the shared data only
+                let sm_data = self
+                    .sm_data
+                    .merged(&udm_client, &body, &self.client_ctx)
+                    .await?;
+                let dnn_configuration = sm_data
+                    .iter()
+                    .filter(|data| data.single_nssai == single_nssai)
+                    .flat_map(|data| data.dnn_configurations.iter().flatten())
+                    .find(|(name, _)| name.eq_ignore_ascii_case(&session.dnn))
+                    .map(|(_, configuration)| configuration.clone());
+                let dnn_configuration = match dnn_configuration {
+                    Some(configuration) => configuration,
+                    None => {
+                        return Ok(make_error(format!(
+                            "UDM: DNN {} not subscribed in slice {single_nssai:?}",
+                            session.dnn
+                        )))
+                    }
+                };
+
+                session.apply_dnn_configuration(&dnn_configuration);
+                session.ul_tunnel = Some(ngap::GtpTunnel {
+                    transport_layer_address: UPF_N3_ADDRESS.into(),
+                    gtp_teid: self.sm_contexts.allocate_teid(),
+                });
+
+                let (session, replaced) = match self.sm_contexts.create(session) {
+                    Ok(created) => created,
+                    // The UE is answered with a PDU Session Establishment Reject
+                    Err(e) => {
+                        let mut error = SmContextCreateError::new(e.problem());
+                        error.n1_sm_msg = Some(models::RefToBinaryData::new(
+                            "binaryDataN1SmMessage".to_string(),
+                        ));
+                        return Ok(
+                            PostSmContextsResponse::UnsuccessfulCreationOfAnSMContextWithN1SmMessage_4 {
+                                body: error,
+                                binary_data_n1_sm_message: swagger::ByteArray(
+                                    e.establishment_reject(&request).encode(),
+                                ),
+                            },
+                        );
+                    }
+                };
+                if let Some(replaced) = replaced {
+                    info!(
+                        "SM context {} replaced by {}: {} PDU session {} established again",
+                        replaced.sm_context_ref,
+                        session.sm_context_ref,
+                        session.supi,
+                        session.pdu_session_id
+                    );
+                }
+
                 let response = PostSmContextsResponse::SuccessfulCreationOfAnSMContext {
                     body: SmContextCreatedData {
                         h_smf_uri: None,
@@ -505,38 +718,49 @@ where
                         selected_old_smf_id: None,
                         inter_plmn_api_root: None,
                     },
-                    location: "nsmf-pdusession/v1/sm-contexts/4".to_string(),
+                    location: format!("nsmf-pdusession/v1/sm-contexts/{}", session.sm_context_ref),
                 };
 
                 let c_amf_url = self.amf_url.clone();
+                let c_supi = session.supi.clone();
+                let nas = session
+                    .establishment_accept(&request, &DNS_SERVERS)
+                    .encode();
+                let ngap = session.n2_sm_info_transfer(models::N2SmInfoTypeAnyOf::PduResSetupReq);
+                // The N2 SM information only when there is a transfer for
+                // the NG-RAN
+                let mut data = serde_json::json!({
+                    "n1MessageContainer": {
+                        "n1MessageClass": "SM",
+                        "n1MessageContent": { "contentId": "5gnas-sm" }
+                    },
+                    "pduSessionId": session.pdu_session_id
+                });
+                if ngap.is_some() {
+                    data["n2InfoContainer"] = serde_json::json!({
+                        "n2InformationClass": "SM",
+                        "smInfo": {
+                            "pduSessionId": session.pdu_session_id,
+                            "n2InfoContent": {
+                                "ngapIeType": "PDU_RES_SETUP_REQ",
+                                "ngapData": { "contentId": "ngap-sm" }
+                            }
+                        }
+                    });
+                }
                 // Async callback to the AMF
                 tokio::task::spawn(async move {
-                    let data = r#"{"n1MessageContainer":{"n1MessageClass":"SM","n1MessageContent":{"contentId":"5gnas-sm"}},"n2InfoContainer":{"n2InformationClass":"SM","smInfo":{"pduSessionId":1,"n2InfoContent":{"ngapIeType":"PDU_RES_SETUP_REQ","ngapData":{"contentId":"ngap-sm"}}}},"pduSessionId":1}"#.as_bytes();
-                    let nas = [
-                        0x2e, 0x01, 0x01, 0xc2, 0x11, 0x00, 0x09, 0x01, 0x00, 0x06, 0x31, 0x31,
-                        0x01, 0x01, 0xff, 0x01, 0x06, 0x0b, 0x00, 0x01, 0x0b, 0x00, 0x01, 0x29,
-                        0x05, 0x01, 0xc0, 0xa8, 0x64, 0x05, 0x22, 0x01, 0x01, 0x79, 0x00, 0x06,
-                        0x01, 0x20, 0x41, 0x01, 0x01, 0x09, 0x7b, 0x00, 0x0f, 0x80, 0x00, 0x0d,
-                        0x04, 0x08, 0x08, 0x08, 0x08, 0x00, 0x0d, 0x04, 0x08, 0x08, 0x04, 0x04,
-                        0x25, 0x09, 0x08, 0x69, 0x6e, 0x74, 0x65, 0x72, 0x6e, 0x65, 0x74,
-                    ];
-                    let ngap = [
-                        0x00, 0x00, 0x04, 0x00, 0x82, 0x00, 0x0a, 0x0c, 0x40, 0x00, 0x00, 0x00,
-                        0x30, 0x40, 0x00, 0x00, 0x00, 0x00, 0x8b, 0x00, 0x0a, 0x01, 0xf0, 0xac,
-                        0x16, 0x00, 0x08, 0x00, 0x00, 0x00, 0x0e, 0x00, 0x86, 0x00, 0x01, 0x00,
-                        0x00, 0x88, 0x00, 0x07, 0x00, 0x01, 0x00, 0x00, 0x09, 0x1c, 0x00,
-                    ];
-
-                    let mut body: Vec<u8> = vec![];
-                    body.extend_from_slice(data);
+                    let mut body =
+                        serde_json::to_vec(&data).expect("impossible to fail to serialize");
                     body.extend_from_slice(&nas);
-                    body.extend_from_slice(&ngap);
+                    body.extend_from_slice(ngap.as_deref().unwrap_or_default());
 
                     let client = reqwest::Client::new();
                     let _res = client
                         .post(format!(
-                            "{}/namf-comm/v1/ue-contexts/imsi-001011234567895/n1-n2-messages",
-                            c_amf_url.as_str()
+                            "{}/namf-comm/v1/ue-contexts/{}/n1-n2-messages",
+                            c_amf_url.as_str(),
+                            c_supi
                         ))
                         .body(body)
                         .send()
@@ -593,6 +817,31 @@ where
     }
 }
 
+/// The answer to a create request the SMF rejects before the session is
+/// set up.
+fn create_error(e: &SmContextError) -> PostSmContextsResponse {
+    let error = SmContextCreateError::new(e.problem());
+    match e {
+        SmContextError::NotFound(_) => {
+            PostSmContextsResponse::UnsuccessfulCreationOfAnSMContext_3(error)
+        }
+        SmContextError::MissingIe(_)
+        | SmContextError::InvalidIe { .. }
+        | SmContextError::InvalidTransition { .. } => {
+            PostSmContextsResponse::UnsuccessfulCreationOfAnSMContext(error)
+        }
+        SmContextError::UnexpectedN2SmInfo { .. }
+        | SmContextError::UnexpectedN1SmMsg(_)
+        | SmContextError::InvalidN1SmMsg(_)
+        | SmContextError::InvalidN2SmInfo(_) => {
+            PostSmContextsResponse::UnsuccessfulCreationOfAnSMContext_2(error)
+        }
+        SmContextError::InsufficientResources(_) => {
+            PostSmContextsResponse::UnsuccessfulCreationOfAnSMContext_4(error)
+        }
+    }
+}
+
 fn make_error(title: String) -> PostSmContextsResponse {
     PostSmContextsResponse::UnsuccessfulCreationOfAnSMContext(SmContextCreateError {
         error: ExtProblemDetails {
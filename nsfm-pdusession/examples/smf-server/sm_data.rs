@@ -0,0 +1,377 @@
+//! Cache of the session management subscription data retrieved from the UDM.
+//!
+//! The UDM sends its data sets with an entity tag, the last modification time
+//! and a Cache-Control directive. A cached data set is reused as is while it
+//! is fresh (`max-age`), and afterwards revalidated with a conditional
+//! retrieval: a 304 Not Modified from the UDM keeps it for another period,
+//! anything else replaces it.
+//!
+//! The SMF supports the SharedData feature: the data sets only reference the
+//! shared data, such as the DNN configurations common to many subscribers,
+//! which is retrieved separately and cached by shared data id for the
+//! `max-age` the UDM gives.
+//!
+//! Both caches hold at most a given number of entries: when full, those that
+//! stopped being fresh the longest ago are evicted first.
+
+use log::info;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+use std::time::{Duration, Instant};
+use swagger::ApiError;
+
+use nudm_sdm::models::{SessionManagementSubscriptionData, SharedData, SmSubsData, Snssai};
+use nudm_sdm::shared_data::{merge_sm_subs_data, sm_subs_data_references};
+use nudm_sdm::{Api as UDMApi, GetSharedDataResponse, GetSmDataResponse};
+
+/// Features of Nudm_SDM the SMF supports: SharedData (1).
+const SUPPORTED_FEATURES: &str = "1";
+
+/// Number of data sets, and of shared data records, cached by default.
+pub const DEFAULT_CAPACITY: usize = 10_000;
+
+/// Counters of a [`SmDataCache`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Retrievals answered from a fresh cached data set.
+    pub hits: u64,
+    /// Cached data sets the UDM confirmed with 304 Not Modified.
+    pub revalidated: u64,
+    /// Retrievals the UDM answered with a data set.
+    pub misses: u64,
+    /// Shared data records retrieved from the UDM.
+    pub shared_data: u64,
+    /// Data sets and shared data records evicted from the full cache.
+    pub evicted: u64,
+}
+
+#[derive(Debug)]
+struct Entry {
+    body: SmSubsData,
+    cache_control: Option<String>,
+    e_tag: Option<String>,
+    last_modified: Option<String>,
+    fresh_until: Instant,
+}
+
+#[derive(Debug)]
+struct SharedEntry {
+    data: SharedData,
+    fresh_until: Instant,
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    hits: AtomicU64,
+    revalidated: AtomicU64,
+    misses: AtomicU64,
+    shared_data: AtomicU64,
+    evicted: AtomicU64,
+}
+
+/// SM subscription data by SUPI, S-NSSAI and DNN, and the shared data it
+/// references by id, shared between the clones of the cache.
+#[derive(Debug, Clone)]
+pub struct SmDataCache {
+    entries: Arc<RwLock<HashMap<String, Entry>>>,
+    shared: Arc<RwLock<HashMap<String, SharedEntry>>>,
+    counters: Arc<Counters>,
+    capacity: usize,
+}
+
+impl Default for SmDataCache {
+    fn default() -> SmDataCache {
+        SmDataCache::with_capacity(DEFAULT_CAPACITY)
+    }
+}
+
+impl SmDataCache {
+    pub fn new() -> SmDataCache {
+        SmDataCache::default()
+    }
+
+    /// A cache holding at most `capacity` data sets and as many shared data
+    /// records.
+    pub fn with_capacity(capacity: usize) -> SmDataCache {
+        SmDataCache {
+            entries: Arc::default(),
+            shared: Arc::default(),
+            counters: Arc::default(),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Retrieves the SM subscription data of a UE for a slice and a DNN,
+    /// from the cache while fresh and else from the UDM, revalidating the
+    /// cached data set if there is one. A revalidated data set is returned
+    /// as if the UDM had sent it again.
+    pub async fn get_sm_data<T, C>(
+        &self,
+        udm: &T,
+        supi: String,
+        single_nssai: Snssai,
+        dnn: String,
+        context: &C,
+    ) -> Result<GetSmDataResponse, ApiError>
+    where
+        T: UDMApi<C> + Send + Sync,
+        C: Send + Sync,
+    {
+        let key = format!(
+            "{}/{}-{}/{}",
+            supi,
+            single_nssai.sst,
+            single_nssai.sd.as_deref().unwrap_or(""),
+            dnn
+        );
+
+        let (if_none_match, if_modified_since) = {
+            let entries = self.entries.read().expect("cache lock poisoned");
+            match entries.get(&key) {
+                Some(entry) if entry.fresh_until > Instant::now() => {
+                    self.counters.hits.fetch_add(1, Ordering::Relaxed);
+                    return Ok(entry.response());
+                }
+                Some(entry) => (entry.e_tag.clone(), entry.last_modified.clone()),
+                None => (None, None),
+            }
+        };
+
+        let response = udm
+            .get_sm_data(
+                supi,
+                Some(SUPPORTED_FEATURES.to_string()),
+                Some(single_nssai),
+                Some(dnn),
+                None,
+                if_none_match,
+                if_modified_since,
+                context,
+            )
+            .await?;
+
+        let mut entries = self.entries.write().expect("cache lock poisoned");
+        match response {
+            GetSmDataResponse::NotModified {
+                cache_control,
+                e_tag,
+                last_modified,
+            } => match entries.get_mut(&key) {
+                Some(entry) => {
+                    self.counters.revalidated.fetch_add(1, Ordering::Relaxed);
+                    entry.fresh_until = fresh_until(cache_control.as_deref());
+                    entry.cache_control = cache_control;
+                    entry.e_tag = e_tag.or_else(|| entry.e_tag.take());
+                    entry.last_modified = last_modified.or_else(|| entry.last_modified.take());
+                    info!("SM data of {} revalidated, cache {:?}", key, self.stats());
+                    Ok(entry.response())
+                }
+                // Dropped in between: the 304 cannot be answered from the cache
+                None => Err(ApiError(format!(
+                    "UDM: not modified SM data of {} no longer cached",
+                    key
+                ))),
+            },
+            GetSmDataResponse::ExpectedResponseToAValidRequest {
+                body,
+                cache_control,
+                e_tag,
+                last_modified,
+            } => {
+                self.counters.misses.fetch_add(1, Ordering::Relaxed);
+                let storable = !directives(cache_control.as_deref()).any(|d| d == "no-store")
+                    && (e_tag.is_some() || last_modified.is_some());
+                if storable {
+                    if !entries.contains_key(&key) {
+                        self.make_room(&mut entries, |entry| entry.fresh_until);
+                    }
+                    entries.insert(
+                        key.clone(),
+                        Entry {
+                            body: body.clone(),
+                            fresh_until: fresh_until(cache_control.as_deref()),
+                            cache_control: cache_control.clone(),
+                            e_tag: e_tag.clone(),
+                            last_modified: last_modified.clone(),
+                        },
+                    );
+                } else {
+                    entries.remove(&key);
+                }
+                info!("SM data of {} retrieved, cache {:?}", key, self.stats());
+                Ok(GetSmDataResponse::ExpectedResponseToAValidRequest {
+                    body,
+                    cache_control,
+                    e_tag,
+                    last_modified,
+                })
+            }
+            response => {
+                entries.remove(&key);
+                Ok(response)
+            }
+        }
+    }
+
+    /// The SM subscription data as the UE is subscribed to it: the
+    /// individual data merged with the shared data it references, the
+    /// individual data taking precedence. The shared data records missing
+    /// from the cache, or no longer fresh, are retrieved in one request.
+    pub async fn merged<T, C>(
+        &self,
+        udm: &T,
+        sm_data: &SmSubsData,
+        context: &C,
+    ) -> Result<Vec<SessionManagementSubscriptionData>, ApiError>
+    where
+        T: UDMApi<C> + Send + Sync,
+        C: Send + Sync,
+    {
+        let references = sm_subs_data_references(sm_data);
+        let (mut shared, stale): (Vec<SharedData>, Vec<String>) = {
+            let cached = self.shared.read().expect("cache lock poisoned");
+            let now = Instant::now();
+            let mut shared = Vec::new();
+            let mut stale = Vec::new();
+            for id in references {
+                match cached.get(&id) {
+                    Some(entry) if entry.fresh_until > now => shared.push(entry.data.clone()),
+                    _ => stale.push(id),
+                }
+            }
+            (shared, stale)
+        };
+
+        if !stale.is_empty() {
+            let response = udm
+                .get_shared_data(
+                    &stale.iter().cloned().map(Into::into).collect(),
+                    Some(SUPPORTED_FEATURES.to_string()),
+                    None,
+                    None,
+                    None,
+                    context,
+                )
+                .await?;
+            let (records, cache_control) = match response {
+                GetSharedDataResponse::ExpectedResponseToAValidRequest {
+                    body,
+                    cache_control,
+                    ..
+                } => (body, cache_control),
+                response => {
+                    return Err(ApiError(format!(
+                        "UDM: shared data {} not retrieved: {:?}",
+                        stale.join(","),
+                        response
+                    )))
+                }
+            };
+            self.counters
+                .shared_data
+                .fetch_add(records.len() as u64, Ordering::Relaxed);
+
+            let mut cached = self.shared.write().expect("cache lock poisoned");
+            for data in records {
+                if !directives(cache_control.as_deref()).any(|d| d == "no-store") {
+                    if !cached.contains_key(&data.shared_data_id) {
+                        self.make_room(&mut cached, |entry| entry.fresh_until);
+                    }
+                    cached.insert(
+                        data.shared_data_id.clone(),
+                        SharedEntry {
+                            data: data.clone(),
+                            fresh_until: fresh_until(cache_control.as_deref()),
+                        },
+                    );
+                }
+                shared.push(data);
+            }
+            info!(
+                "Shared data {} retrieved, cache {:?}",
+                stale.join(","),
+                self.stats()
+            );
+        }
+
+        Ok(merge_sm_subs_data(sm_data, &shared))
+    }
+
+    /// Evicts from a full cache the entries that stopped being fresh the
+    /// longest ago, or else are the closest to, until one more fits.
+    fn make_room<V, F>(&self, entries: &mut HashMap<String, V>, fresh_until: F)
+    where
+        F: Fn(&V) -> Instant,
+    {
+        while entries.len() >= self.capacity {
+            let first = entries
+                .iter()
+                .min_by_key(|(_, entry)| fresh_until(entry))
+                .map(|(key, _)| key.clone())
+                .expect("a full cache has entries");
+            entries.remove(&first);
+            self.counters.evicted.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.counters.hits.load(Ordering::Relaxed),
+            revalidated: self.counters.revalidated.load(Ordering::Relaxed),
+            misses: self.counters.misses.load(Ordering::Relaxed),
+            shared_data: self.counters.shared_data.load(Ordering::Relaxed),
+            evicted: self.counters.evicted.load(Ordering::Relaxed),
+        }
+    }
+}
+
+impl Entry {
+    fn response(&self) -> GetSmDataResponse {
+        GetSmDataResponse::ExpectedResponseToAValidRequest {
+            body: self.body.clone(),
+            cache_control: self.cache_control.clone(),
+            e_tag: self.e_tag.clone(),
+            last_modified: self.last_modified.clone(),
+        }
+    }
+}
+
+/// The directives of a Cache-Control header, lowercased.
+fn directives(cache_control: Option<&str>) -> impl Iterator<Item = String> + '_ {
+    cache_control
+        .unwrap_or("")
+        .split(',')
+        .map(|d| d.trim().to_ascii_lowercase())
+        .filter(|d| !d.is_empty())
+}
+
+/// Longest a data set is reused without revalidation, whatever its `max-age`.
+const MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Until when a data set may be reused without revalidation: never for
+/// `no-cache` or without `max-age`, and at most for [`MAX_AGE`].
+fn fresh_until(cache_control: Option<&str>) -> Instant {
+    let max_age = if directives(cache_control).any(|d| d == "no-cache") {
+        0
+    } else {
+        directives(cache_control)
+            .find_map(|d| {
+                let max_age = d.strip_prefix("max-age=")?.trim_matches('"');
+                match max_age.parse() {
+                    Ok(max_age) => Some(max_age),
+                    // Ages too large to count are the largest one, as in RFC 9111, 1.2.2
+                    Err(_)
+                        if !max_age.is_empty() && max_age.bytes().all(|b| b.is_ascii_digit()) =>
+                    {
+                        Some(u64::MAX)
+                    }
+                    Err(_) => None,
+                }
+            })
+            .unwrap_or(0)
+    };
+    let now = Instant::now();
+    now.checked_add(Duration::from_secs(max_age).min(MAX_AGE))
+        .unwrap_or(now)
+}
@@ -23,7 +23,8 @@ use swagger::{ApiError, AuthData, BodyExt, Connector, DropContextService, Has, X
 use url::form_urlencoded;
 
 use hyper_0_10::header::{ContentType, Headers};
-use mime_multipart::{generate_boundary, write_multipart, Node, Part};
+use mime_0_2::Mime as Mime2;
+use mime_multipart::{generate_boundary, read_multipart_body, write_multipart, Node, Part};
 
 use crate::header;
 use crate::models;
@@ -3266,6 +3267,9 @@ where
         &self,
         param_sm_context_ref: String,
         param_sm_context_update_data: models::SmContextUpdateData,
+        param_binary_data_n1_sm_message: Option<swagger::ByteArray>,
+        param_binary_data_n2_sm_information: Option<swagger::ByteArray>,
+        param_binary_data_n2_sm_information_ext1: Option<swagger::ByteArray>,
         context: &C,
     ) -> Result<UpdateSmContextResponse, ApiError> {
         let mut client_service = self.client_service.clone();
@@ -3299,22 +3303,112 @@ where
             Err(e) => return Err(ApiError(format!("Unable to create request: {}", e))),
         };
 
-        let body = serde_json::to_string(&param_sm_context_update_data)
-            .expect("impossible to fail to serialize");
+        // The update data is sent alone as JSON, or with binary data as
+        // multipart/related: each binary part has the Content-ID the update
+        // data references it with.
+        let binary_parts: Vec<(&str, String, swagger::ByteArray)> = vec![
+            (
+                "application/vnd.3gpp.5gnas",
+                param_sm_context_update_data
+                    .n1_sm_msg
+                    .as_ref()
+                    .map_or("binaryDataN1SmMessage".to_string(), |r| {
+                        r.content_id.clone()
+                    }),
+                param_binary_data_n1_sm_message,
+            ),
+            (
+                "application/vnd.3gpp.ngap",
+                param_sm_context_update_data
+                    .n2_sm_info
+                    .as_ref()
+                    .map_or("binaryDataN2SmInformation".to_string(), |r| {
+                        r.content_id.clone()
+                    }),
+                param_binary_data_n2_sm_information,
+            ),
+            (
+                "application/vnd.3gpp.ngap",
+                param_sm_context_update_data
+                    .n2_sm_info_ext1
+                    .as_ref()
+                    .map_or("binaryDataN2SmInformationExt1".to_string(), |r| {
+                        r.content_id.clone()
+                    }),
+                param_binary_data_n2_sm_information_ext1,
+            ),
+        ]
+        .into_iter()
+        .filter_map(|(content_type, content_id, binary_data)| {
+            binary_data.map(|binary_data| (content_type, content_id, binary_data))
+        })
+        .collect();
 
-        *request.body_mut() = Body::from(body);
+        let header = if binary_parts.is_empty() {
+            let body = serde_json::to_string(&param_sm_context_update_data)
+                .expect("impossible to fail to serialize");
 
-        let header = "application/json";
+            *request.body_mut() = Body::from(body);
+
+            HeaderValue::from_str("application/json")
+        } else {
+            // Construct the Body for a multipart/related request. The mime 0.2.6 library
+            // does not parse quoted-string parameters correctly. The boundary doesn't
+            // need to be a quoted string if it does not contain a '/', hence ensure
+            // no such boundary is used.
+            let mut boundary = generate_boundary();
+            for b in boundary.iter_mut() {
+                if b == &(b'/') {
+                    *b = b'=';
+                }
+            }
+
+            let mut body_parts = vec![Node::Part(Part {
+                headers: {
+                    let mut h = Headers::new();
+                    h.set(ContentType("application/json".parse().unwrap()));
+                    h.set_raw("Content-ID", vec![b"jsonData".to_vec()]);
+                    h
+                },
+                body: serde_json::to_string(&param_sm_context_update_data)
+                    .expect("Impossible to fail to serialize")
+                    .into_bytes(),
+            })];
+
+            for (content_type, content_id, binary_data) in binary_parts {
+                body_parts.push(Node::Part(Part {
+                    headers: {
+                        let mut h = Headers::new();
+                        h.set(ContentType(content_type.parse().unwrap()));
+                        h.set_raw("Content-ID", vec![content_id.into_bytes()]);
+                        h
+                    },
+                    body: binary_data.0,
+                }));
+            }
+
+            // Write the body into a vec.
+            let mut body: Vec<u8> = vec![];
+            write_multipart(&mut body, &boundary, &body_parts)
+                .expect("Failed to write multipart body");
+
+            // Add the message body to the request object.
+            *request.body_mut() = Body::from(body);
+
+            HeaderValue::from_bytes(
+                &[
+                    "multipart/related; boundary=".as_bytes(),
+                    &boundary,
+                    "; type=\"application/json\"".as_bytes(),
+                ]
+                .concat(),
+            )
+        };
         request.headers_mut().insert(
             CONTENT_TYPE,
-            match HeaderValue::from_str(header) {
+            match header {
                 Ok(h) => h,
-                Err(e) => {
-                    return Err(ApiError(format!(
-                        "Unable to create header: {} - {}",
-                        header, e
-                    )))
-                }
+                Err(e) => return Err(ApiError(format!("Unable to create header: {}", e))),
             },
         );
 
@@ -3363,22 +3457,88 @@ where
 
         match response.status().as_u16() {
             200 => {
+                let content_type = response
+                    .headers()
+                    .get(CONTENT_TYPE)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.parse::<Mime2>().ok());
                 let body = response.into_body();
                 let body = body
                     .into_raw()
                     .map_err(|e| ApiError(format!("Failed to read response: {}", e)))
                     .await?;
-                let body = str::from_utf8(&body)
-                    .map_err(|e| ApiError(format!("Response was not valid UTF8: {}", e)))?;
-                let body =
-                    serde_json::from_str::<models::SmContextUpdatedData>(body).map_err(|e| {
-                        ApiError(format!("Response body did not match the schema: {}", e))
-                    })?;
-                Ok(
-                    UpdateSmContextResponse::SuccessfulUpdateOfAnSMContextWithContentInTheResponse(
-                        body,
-                    ),
-                )
+                match content_type {
+                    Some(content_type) if content_type.to_string().starts_with("multipart/") => {
+                        // Insert top-level content type header into a Headers object.
+                        let mut multi_part_headers = Headers::new();
+                        multi_part_headers.set(ContentType(content_type));
+                        // &*body expresses the body as a byteslice, &mut provides a
+                        // mutable reference to that byteslice.
+                        let nodes = read_multipart_body(&mut &*body, &multi_part_headers, false)
+                            .map_err(|e| {
+                                ApiError(format!("Could not read multipart response: {}", e))
+                            })?;
+
+                        let mut json_data = None;
+                        let mut binary_data_n1_sm_message = None;
+                        let mut binary_data_n2_sm_information = None;
+                        for node in nodes {
+                            if let Node::Part(part) = node {
+                                let content_type = part.content_type().map(|x| format!("{}", x));
+                                match content_type.as_deref() {
+                                    Some("application/json") if json_data.is_none() => {
+                                        json_data = Some(
+                                            serde_json::from_slice::<models::SmContextUpdatedData>(
+                                                &part.body,
+                                            )
+                                            .map_err(|e| {
+                                                ApiError(format!(
+                                                    "Response body did not match the schema: {}",
+                                                    e
+                                                ))
+                                            })?,
+                                        );
+                                    }
+                                    Some("application/vnd.3gpp.5gnas")
+                                        if binary_data_n1_sm_message.is_none() =>
+                                    {
+                                        binary_data_n1_sm_message =
+                                            Some(swagger::ByteArray(part.body));
+                                    }
+                                    Some("application/vnd.3gpp.ngap")
+                                        if binary_data_n2_sm_information.is_none() =>
+                                    {
+                                        binary_data_n2_sm_information =
+                                            Some(swagger::ByteArray(part.body));
+                                    }
+                                    _ => {}
+                                }
+                            }
+                        }
+
+                        let body = json_data.ok_or_else(|| {
+                            ApiError("Missing JSON part in multipart response".to_string())
+                        })?;
+                        Ok(UpdateSmContextResponse::SuccessfulUpdateOfAnSMContextWithContentAndBinaryDataInTheResponse {
+                            body,
+                            binary_data_n1_sm_message,
+                            binary_data_n2_sm_information,
+                        })
+                    }
+                    _ => {
+                        let body = str::from_utf8(&body)
+                            .map_err(|e| ApiError(format!("Response was not valid UTF8: {}", e)))?;
+                        let body = serde_json::from_str::<models::SmContextUpdatedData>(body)
+                            .map_err(|e| {
+                                ApiError(format!("Response body did not match the schema: {}", e))
+                            })?;
+                        Ok(
+                            UpdateSmContextResponse::SuccessfulUpdateOfAnSMContextWithContentInTheResponse(
+                                body,
+                            ),
+                        )
+                    }
+                }
             }
             204 => Ok(
                 UpdateSmContextResponse::SuccessfulUpdateOfAnSMContextWithoutContentInTheResponse,
@@ -4531,20 +4691,83 @@ where
                 Ok(PostSmContextsResponse::TooManyRequests(body))
             }
             500 => {
+                let content_type = response
+                    .headers()
+                    .get(CONTENT_TYPE)
+                    .and_then(|v| v.to_str().ok())
+                    .and_then(|v| v.parse::<Mime2>().ok());
                 let body = response.into_body();
                 let body = body
                     .into_raw()
                     .map_err(|e| ApiError(format!("Failed to read response: {}", e)))
                     .await?;
-                let body = str::from_utf8(&body)
-                    .map_err(|e| ApiError(format!("Response was not valid UTF8: {}", e)))?;
-                let body =
-                    serde_json::from_str::<models::SmContextCreateError>(body).map_err(|e| {
-                        ApiError(format!("Response body did not match the schema: {}", e))
-                    })?;
-                Ok(PostSmContextsResponse::UnsuccessfulCreationOfAnSMContext_4(
-                    body,
-                ))
+                match content_type {
+                    Some(content_type) if content_type.to_string().starts_with("multipart/") => {
+                        // Insert top-level content type header into a Headers object.
+                        let mut multi_part_headers = Headers::new();
+                        multi_part_headers.set(ContentType(content_type));
+                        // &*body expresses the body as a byteslice, &mut provides a
+                        // mutable reference to that byteslice.
+                        let nodes = read_multipart_body(&mut &*body, &multi_part_headers, false)
+                            .map_err(|e| {
+                                ApiError(format!("Could not read multipart response: {}", e))
+                            })?;
+
+                        let mut json_data = None;
+                        let mut binary_data_n1_sm_message = None;
+                        for node in nodes {
+                            if let Node::Part(part) = node {
+                                let content_type = part.content_type().map(|x| format!("{}", x));
+                                match content_type.as_deref() {
+                                    Some("application/json") if json_data.is_none() => {
+                                        json_data = Some(
+                                            serde_json::from_slice::<models::SmContextCreateError>(
+                                                &part.body,
+                                            )
+                                            .map_err(|e| {
+                                                ApiError(format!(
+                                                    "Response body did not match the schema: {}",
+                                                    e
+                                                ))
+                                            })?,
+                                        );
+                                    }
+                                    Some("application/vnd.3gpp.5gnas")
+                                        if binary_data_n1_sm_message.is_none() =>
+                                    {
+                                        binary_data_n1_sm_message =
+                                            Some(swagger::ByteArray(part.body));
+                                    }
+                                    _ => {}
+                                }
+                            }
+                        }
+
+                        let body = json_data.ok_or_else(|| {
+                            ApiError("Missing JSON part in multipart response".to_string())
+                        })?;
+                        Ok(match binary_data_n1_sm_message {
+                            Some(binary_data_n1_sm_message) => {
+                                PostSmContextsResponse::UnsuccessfulCreationOfAnSMContextWithN1SmMessage_4 {
+                                    body,
+                                    binary_data_n1_sm_message,
+                                }
+                            }
+                            None => PostSmContextsResponse::UnsuccessfulCreationOfAnSMContext_4(body),
+                        })
+                    }
+                    _ => {
+                        let body = str::from_utf8(&body)
+                            .map_err(|e| ApiError(format!("Response was not valid UTF8: {}", e)))?;
+                        let body = serde_json::from_str::<models::SmContextCreateError>(body)
+                            .map_err(|e| {
+                                ApiError(format!("Response body did not match the schema: {}", e))
+                            })?;
+                        Ok(PostSmContextsResponse::UnsuccessfulCreationOfAnSMContext_4(
+                            body,
+                        ))
+                    }
+                }
             }
             503 => {
                 let body = response.into_body();
@@ -0,0 +1,617 @@
+//! UE IP address pools of the SMF.
+//!
+//! Each DNN has an IPv4 pool and an IPv6 prefix pool, from which the SMF
+//! gives addresses to the UE of each PDU session it establishes, and takes
+//! them back when the session is released (TS 23.501 clause 5.8.2.2). The UE
+//! of an IPv6 PDU session gets a /64 prefix, and in the NAS the interface
+//! identifier of its link-local address.
+//!
+//! The allocations are kept in an [`AllocationStore`], which a restarted SMF
+//! recovers them from: no UE gets an address still in use, and a UE that
+//! establishes again a PDU session it had gets its addresses back.
+
+use log::warn;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+
+use crate::nas;
+
+/// Length of the IPv6 prefixes given to the UEs
+pub const IPV6_PREFIX_LEN: u8 = 64;
+
+/// Interface identifier of the IPv6 link-local address of the UEs
+const INTERFACE_IDENTIFIER: [u8; 8] = [0, 0, 0, 0, 0, 0, 0, 1];
+
+/// A pool of a DNN as configured, `DNN=PREFIX`, e.g. `internet=10.45.0.0/16`
+/// or `internet=2001:db8:cafe::/48`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PoolConfig {
+    pub dnn: String,
+    pub prefix: IpAddr,
+    pub prefix_len: u8,
+}
+
+impl std::str::FromStr for PoolConfig {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Value not valid: {}", s);
+        let (dnn, prefix) = s.split_once('=').ok_or_else(invalid)?;
+        let (prefix, prefix_len) = prefix.split_once('/').ok_or_else(invalid)?;
+        let prefix: IpAddr = prefix.parse().map_err(|_| invalid())?;
+        let prefix_len: u8 = prefix_len.parse().map_err(|_| invalid())?;
+        // An IPv4 pool has a network and a broadcast address besides the UEs
+        let max_prefix_len = match prefix {
+            IpAddr::V4(_) => 30,
+            IpAddr::V6(_) => IPV6_PREFIX_LEN,
+        };
+        if dnn.is_empty() || prefix_len > max_prefix_len {
+            return Err(invalid());
+        }
+        Ok(PoolConfig {
+            dnn: dnn.to_string(),
+            prefix,
+            prefix_len,
+        })
+    }
+}
+
+/// The addresses given to the UE of a PDU session.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Allocation {
+    /// The PDU session, as `{supi}/{pduSessionId}`
+    #[serde(rename = "owner")]
+    pub owner: String,
+
+    #[serde(rename = "dnn")]
+    pub dnn: String,
+
+    #[serde(rename = "ipv4Addr")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipv4_addr: Option<Ipv4Addr>,
+
+    #[serde(rename = "ipv6Prefix")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipv6_prefix: Option<Ipv6Addr>,
+}
+
+impl Allocation {
+    /// The PDU address sent to the UE, none for the sessions that are not
+    /// IP ones.
+    pub fn pdu_address(&self) -> Option<nas::PduAddress> {
+        match (self.ipv4_addr, self.ipv6_prefix) {
+            (Some(ipv4), None) => Some(nas::PduAddress::Ipv4(ipv4)),
+            (None, Some(_)) => Some(nas::PduAddress::Ipv6 {
+                interface_identifier: INTERFACE_IDENTIFIER,
+            }),
+            (Some(ipv4), Some(_)) => Some(nas::PduAddress::Ipv4v6 {
+                interface_identifier: INTERFACE_IDENTIFIER,
+                ipv4,
+            }),
+            (None, None) => None,
+        }
+    }
+
+    /// Whether it gives the UE the addresses of a session type.
+    fn serves(&self, dnn: &str, session_type: nas::PduSessionType) -> bool {
+        self.dnn.eq_ignore_ascii_case(dnn)
+            && match session_type {
+                nas::PduSessionType::Ipv4 => self.ipv4_addr.is_some() && self.ipv6_prefix.is_none(),
+                nas::PduSessionType::Ipv6 => self.ipv4_addr.is_none() && self.ipv6_prefix.is_some(),
+                nas::PduSessionType::Ipv4v6 => {
+                    self.ipv4_addr.is_some() || self.ipv6_prefix.is_some()
+                }
+                nas::PduSessionType::Unstructured | nas::PduSessionType::Ethernet => {
+                    self.ipv4_addr.is_none() && self.ipv6_prefix.is_none()
+                }
+            }
+    }
+}
+
+/// Where the allocations are kept across restarts of the SMF.
+pub trait AllocationStore: std::fmt::Debug + Send + Sync {
+    fn load(&self) -> std::io::Result<Vec<Allocation>>;
+
+    /// Saves a new allocation, or the new addresses of its owner.
+    fn insert(&self, allocation: &Allocation) -> std::io::Result<()>;
+
+    /// Forgets the allocation of an owner.
+    fn remove(&self, owner: &str) -> std::io::Result<()>;
+}
+
+/// Allocations living as long as the SMF.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MemoryStore;
+
+impl AllocationStore for MemoryStore {
+    fn load(&self) -> std::io::Result<Vec<Allocation>> {
+        Ok(Vec::new())
+    }
+
+    fn insert(&self, _allocation: &Allocation) -> std::io::Result<()> {
+        Ok(())
+    }
+
+    fn remove(&self, _owner: &str) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+/// A change of the allocations, as logged by the [`FileStore`].
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+enum Change {
+    Insert(Allocation),
+    Remove(String),
+}
+
+/// Allocations kept in a file, as a log of their changes with one JSON
+/// object per line. Each change is appended to the log; the log is compacted
+/// to the allocations still in use when they are loaded.
+#[derive(Debug, Clone)]
+pub struct FileStore(pub PathBuf);
+
+impl FileStore {
+    fn append(&self, change: &Change) -> std::io::Result<()> {
+        let mut line = serde_json::to_vec(change)?;
+        line.push(b'\n');
+        std::fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.0)?
+            .write_all(&line)
+    }
+}
+
+impl AllocationStore for FileStore {
+    fn load(&self) -> std::io::Result<Vec<Allocation>> {
+        if !self.0.exists() {
+            return Ok(Vec::new());
+        }
+        let log = std::fs::read_to_string(&self.0)?;
+        let mut allocations = HashMap::new();
+        for (number, line) in log.lines().enumerate() {
+            // The last change may have been left half written
+            let change = match serde_json::from_str(line) {
+                Ok(change) => change,
+                Err(e) => {
+                    warn!(
+                        "Skipping line {} of {}: {}",
+                        number + 1,
+                        self.0.display(),
+                        e
+                    );
+                    continue;
+                }
+            };
+            match change {
+                Change::Insert(allocation) => {
+                    allocations.insert(allocation.owner.clone(), allocation);
+                }
+                Change::Remove(owner) => {
+                    allocations.remove(&owner);
+                }
+            }
+        }
+        let allocations: Vec<Allocation> = allocations.into_values().collect();
+
+        // Replaces the log at once, so that it is never left half written
+        let mut compacted = Vec::new();
+        for allocation in &allocations {
+            serde_json::to_writer(&mut compacted, &Change::Insert(allocation.clone()))?;
+            compacted.push(b'\n');
+        }
+        let mut tmp = self.0.clone().into_os_string();
+        tmp.push(".tmp");
+        std::fs::write(&tmp, compacted)?;
+        std::fs::rename(&tmp, &self.0)?;
+        Ok(allocations)
+    }
+
+    fn insert(&self, allocation: &Allocation) -> std::io::Result<()> {
+        self.append(&Change::Insert(allocation.clone()))
+    }
+
+    fn remove(&self, owner: &str) -> std::io::Result<()> {
+        self.append(&Change::Remove(owner.to_string()))
+    }
+}
+
+/// The store of the allocations as given on the command line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Backend {
+    Memory,
+    Filesystem(PathBuf),
+}
+
+impl std::str::FromStr for Backend {
+    type Err = String;
+
+    /// `memory`, or the path of the file of the filesystem backend.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "memory" => Ok(Backend::Memory),
+            "" => Err("empty storage path".to_string()),
+            path => Ok(Backend::Filesystem(PathBuf::from(path))),
+        }
+    }
+}
+
+impl Backend {
+    pub fn store(self) -> Arc<dyn AllocationStore> {
+        match self {
+            Backend::Memory => Arc::new(MemoryStore),
+            Backend::Filesystem(path) => Arc::new(FileStore(path)),
+        }
+    }
+}
+
+/// The addresses of a pool: the UE addresses of an IPv4 network, or the /64
+/// prefixes of an IPv6 one.
+#[derive(Debug)]
+struct Pool {
+    first: u128,
+    step: u128,
+    size: u128,
+    /// Where the search of a free address starts, after the last allocated
+    next: u128,
+}
+
+impl Pool {
+    fn new(prefix: IpAddr, prefix_len: u8) -> Pool {
+        match prefix {
+            IpAddr::V4(prefix) => {
+                let host_bits = 32 - u32::from(prefix_len);
+                let network = u32::from(prefix) & u32::MAX.checked_shl(host_bits).unwrap_or(0);
+                Pool {
+                    first: u128::from(network) + 1,
+                    step: 1,
+                    size: (1 << host_bits) - 2,
+                    next: 0,
+                }
+            }
+            IpAddr::V6(prefix) => {
+                let prefix_bits = u32::from(IPV6_PREFIX_LEN - prefix_len);
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(prefix_len))
+                    .unwrap_or(0);
+                Pool {
+                    first: u128::from(prefix) & mask,
+                    step: 1 << (128 - u32::from(IPV6_PREFIX_LEN)),
+                    size: 1 << prefix_bits,
+                    next: 0,
+                }
+            }
+        }
+    }
+
+    /// The first address not in use from where the last search ended.
+    fn allocate(&mut self, in_use: impl Fn(u128) -> bool) -> Option<u128> {
+        let index = (0..self.size)
+            .map(|i| (self.next + i) % self.size)
+            .find(|index| !in_use(self.first + index * self.step))?;
+        self.next = (index + 1) % self.size;
+        Some(self.first + index * self.step)
+    }
+}
+
+#[derive(Debug, Default)]
+struct DnnPools {
+    ipv4: Option<Pool>,
+    ipv6: Option<Pool>,
+}
+
+#[derive(Debug, Default)]
+struct Pools {
+    /// By DNN, in lower case
+    dnns: HashMap<String, DnnPools>,
+    /// By owner
+    allocations: HashMap<String, Allocation>,
+    ipv4_in_use: HashSet<Ipv4Addr>,
+    ipv6_in_use: HashSet<Ipv6Addr>,
+}
+
+impl Pools {
+    fn insert(&mut self, allocation: Allocation) {
+        self.ipv4_in_use.extend(allocation.ipv4_addr);
+        self.ipv6_in_use.extend(allocation.ipv6_prefix);
+        self.allocations
+            .insert(allocation.owner.clone(), allocation);
+    }
+
+    /// Free addresses of the pools of a DNN for a session type, `None` when
+    /// there are none left.
+    fn free_addresses(
+        &mut self,
+        dnn: &str,
+        session_type: nas::PduSessionType,
+    ) -> Option<(Option<Ipv4Addr>, Option<Ipv6Addr>)> {
+        let (ipv4_pool, ipv6_pool) = match self.dnns.get_mut(&dnn.to_lowercase()) {
+            Some(DnnPools { ipv4, ipv6 }) => (ipv4.as_mut(), ipv6.as_mut()),
+            None => (None, None),
+        };
+        let (wants_ipv4, wants_ipv6) = match session_type {
+            nas::PduSessionType::Ipv4 => (true, false),
+            nas::PduSessionType::Ipv6 => (false, true),
+            // The addresses of the pools the DNN has, at least one
+            nas::PduSessionType::Ipv4v6 => (
+                ipv4_pool.is_some() || ipv6_pool.is_none(),
+                ipv6_pool.is_some(),
+            ),
+            nas::PduSessionType::Unstructured | nas::PduSessionType::Ethernet => (false, false),
+        };
+
+        let ipv4_in_use = &self.ipv4_in_use;
+        let ipv4_addr = if wants_ipv4 {
+            let address = ipv4_pool?
+                .allocate(|address| ipv4_in_use.contains(&Ipv4Addr::from(address as u32)))?;
+            Some(Ipv4Addr::from(address as u32))
+        } else {
+            None
+        };
+        let ipv6_in_use = &self.ipv6_in_use;
+        let ipv6_prefix = if wants_ipv6 {
+            let prefix =
+                ipv6_pool?.allocate(|prefix| ipv6_in_use.contains(&Ipv6Addr::from(prefix)))?;
+            Some(Ipv6Addr::from(prefix))
+        } else {
+            None
+        };
+        Some((ipv4_addr, ipv6_prefix))
+    }
+
+    fn remove(&mut self, owner: &str) -> Option<Allocation> {
+        let allocation = self.allocations.remove(owner)?;
+        if let Some(ipv4) = &allocation.ipv4_addr {
+            self.ipv4_in_use.remove(ipv4);
+        }
+        if let Some(ipv6) = &allocation.ipv6_prefix {
+            self.ipv6_in_use.remove(ipv6);
+        }
+        Some(allocation)
+    }
+}
+
+/// Thread-safe handle to the pools of the DNNs and their allocations.
+#[derive(Debug, Clone)]
+pub struct IpPools {
+    pools: Arc<RwLock<Pools>>,
+    store: Arc<dyn AllocationStore>,
+}
+
+impl Default for IpPools {
+    /// No pools, and so only non-IP sessions.
+    fn default() -> Self {
+        IpPools {
+            pools: Arc::default(),
+            store: Arc::new(MemoryStore),
+        }
+    }
+}
+
+impl IpPools {
+    /// The pools of the DNNs, with the allocations of the store still in
+    /// use.
+    pub fn new(
+        configs: &[PoolConfig],
+        store: Arc<dyn AllocationStore>,
+    ) -> std::io::Result<IpPools> {
+        let mut pools = Pools::default();
+        for config in configs {
+            let dnn_pools = pools.dnns.entry(config.dnn.to_lowercase()).or_default();
+            let pool = Some(Pool::new(config.prefix, config.prefix_len));
+            match config.prefix {
+                IpAddr::V4(_) => dnn_pools.ipv4 = pool,
+                IpAddr::V6(_) => dnn_pools.ipv6 = pool,
+            }
+        }
+        for allocation in store.load()? {
+            pools.insert(allocation);
+        }
+        Ok(IpPools {
+            pools: Arc::new(RwLock::new(pools)),
+            store,
+        })
+    }
+
+    /// Gives addresses of the pools of the DNN to the UE of a PDU session,
+    /// the ones it already has if any; `None` when the pools have no address
+    /// left. An IPv4v6 session gets the addresses of the pools the DNN has.
+    pub fn allocate(
+        &self,
+        owner: &str,
+        dnn: &str,
+        session_type: nas::PduSessionType,
+    ) -> Option<Allocation> {
+        let mut pools = self.pools.write().expect("IP pool lock poisoned");
+        if let Some(allocation) = pools.allocations.get(owner) {
+            if allocation.serves(dnn, session_type) {
+                return Some(allocation.clone());
+            }
+        }
+        // Taken back before looking for free addresses: they are no longer
+        // in use if the pools have none left
+        let previous = pools.remove(owner);
+        let (ipv4_addr, ipv6_prefix) = match pools.free_addresses(dnn, session_type) {
+            Some(addresses) => addresses,
+            None => {
+                if let Some(previous) = previous {
+                    pools.insert(previous);
+                }
+                return None;
+            }
+        };
+        let allocation = Allocation {
+            owner: owner.to_string(),
+            dnn: dnn.to_string(),
+            ipv4_addr,
+            ipv6_prefix,
+        };
+        pools.insert(allocation.clone());
+        if let Err(e) = self.store.insert(&allocation) {
+            warn!(
+                "Unable to save the UE address allocation of {}: {}",
+                owner, e
+            );
+        }
+        Some(allocation)
+    }
+
+    /// Takes back the addresses of a PDU session.
+    pub fn release(&self, owner: &str) -> Option<Allocation> {
+        let mut pools = self.pools.write().expect("IP pool lock poisoned");
+        let allocation = pools.remove(owner)?;
+        if let Err(e) = self.store.remove(owner) {
+            warn!("Unable to save the UE address release of {}: {}", owner, e);
+        }
+        Some(allocation)
+    }
+
+    pub fn get(&self, owner: &str) -> Option<Allocation> {
+        self.pools
+            .read()
+            .expect("IP pool lock poisoned")
+            .allocations
+            .get(owner)
+            .cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.pools
+            .read()
+            .expect("IP pool lock poisoned")
+            .allocations
+            .len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pools(configs: &[&str]) -> IpPools {
+        let configs: Vec<PoolConfig> = configs.iter().map(|c| c.parse().unwrap()).collect();
+        IpPools::new(&configs, Arc::new(MemoryStore)).unwrap()
+    }
+
+    #[test]
+    fn ipv4_addresses_are_distinct_until_exhausted() {
+        let pools = pools(&["internet=10.45.0.0/30"]);
+        let first = pools.allocate("imsi-1/1", "internet", nas::PduSessionType::Ipv4);
+        let second = pools.allocate("imsi-2/1", "Internet", nas::PduSessionType::Ipv4);
+        assert_eq!(
+            first.and_then(|a| a.ipv4_addr),
+            Some(Ipv4Addr::new(10, 45, 0, 1))
+        );
+        assert_eq!(
+            second.and_then(|a| a.ipv4_addr),
+            Some(Ipv4Addr::new(10, 45, 0, 2))
+        );
+        assert_eq!(
+            pools.allocate("imsi-3/1", "internet", nas::PduSessionType::Ipv4),
+            None
+        );
+        assert_eq!(
+            pools.allocate("imsi-3/1", "ims", nas::PduSessionType::Ipv4),
+            None
+        );
+
+        pools.release("imsi-1/1");
+        let third = pools.allocate("imsi-3/1", "internet", nas::PduSessionType::Ipv4);
+        assert_eq!(
+            third.and_then(|a| a.ipv4_addr),
+            Some(Ipv4Addr::new(10, 45, 0, 1))
+        );
+        assert_eq!(pools.len(), 2);
+    }
+
+    #[test]
+    fn a_session_established_again_keeps_its_addresses() {
+        let pools = pools(&["internet=10.45.0.0/16", "internet=2001:db8:cafe::/48"]);
+        let allocation = pools
+            .allocate("imsi-1/1", "internet", nas::PduSessionType::Ipv4v6)
+            .unwrap();
+        assert_eq!(allocation.ipv4_addr, Some(Ipv4Addr::new(10, 45, 0, 1)));
+        assert_eq!(
+            allocation.ipv6_prefix,
+            Some("2001:db8:cafe::".parse().unwrap())
+        );
+        assert_eq!(
+            pools.allocate("imsi-1/1", "internet", nas::PduSessionType::Ipv4v6),
+            Some(allocation)
+        );
+
+        let ipv6 = pools
+            .allocate("imsi-2/1", "internet", nas::PduSessionType::Ipv6)
+            .unwrap();
+        assert_eq!(ipv6.ipv6_prefix, Some("2001:db8:cafe:1::".parse().unwrap()));
+        assert_eq!(
+            ipv6.pdu_address(),
+            Some(nas::PduAddress::Ipv6 {
+                interface_identifier: INTERFACE_IDENTIFIER
+            })
+        );
+    }
+
+    #[test]
+    fn allocations_survive_a_restart() {
+        let path = std::env::temp_dir().join(format!(
+            "ip-pools-{}-{:?}.json",
+            std::process::id(),
+            std::thread::current().id()
+        ));
+        let configs = vec!["internet=10.45.0.0/29".parse().unwrap()];
+        let store = || Arc::new(FileStore(path.clone()));
+
+        let pools = IpPools::new(&configs, store()).unwrap();
+        let allocation = pools.allocate("imsi-1/1", "internet", nas::PduSessionType::Ipv4);
+
+        let released = pools.allocate("imsi-2/1", "internet", nas::PduSessionType::Ipv4);
+        pools.release("imsi-2/1");
+
+        let restarted = IpPools::new(&configs, store()).unwrap();
+        assert_eq!(restarted.get("imsi-1/1"), allocation);
+        assert_eq!(restarted.len(), 1);
+        let other = restarted.allocate("imsi-3/1", "internet", nas::PduSessionType::Ipv4);
+        assert_eq!(
+            other.and_then(|a| a.ipv4_addr),
+            released.and_then(|a| a.ipv4_addr)
+        );
+
+        // The log is compacted to the allocations in use, and a change left
+        // half written skipped
+        std::fs::OpenOptions::new()
+            .append(true)
+            .open(&path)
+            .unwrap()
+            .write_all(b"{\"remove\":\"imsi-1")
+            .unwrap();
+        let restarted = IpPools::new(&configs, store()).unwrap();
+        assert_eq!(restarted.len(), 2);
+        assert_eq!(std::fs::read_to_string(&path).unwrap().lines().count(), 2);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn pool_config() {
+        assert_eq!(
+            "internet=10.45.0.0/16".parse(),
+            Ok(PoolConfig {
+                dnn: "internet".to_string(),
+                prefix: IpAddr::V4(Ipv4Addr::new(10, 45, 0, 0)),
+                prefix_len: 16,
+            })
+        );
+        assert!("internet=10.45.0.0/31".parse::<PoolConfig>().is_err());
+        assert!("internet=2001:db8::/80".parse::<PoolConfig>().is_err());
+        assert!("10.45.0.0/16".parse::<PoolConfig>().is_err());
+    }
+}
@@ -314,6 +314,12 @@ pub enum SendMoDataResponse {
 pub enum UpdateSmContextResponse {
     /// successful update of an SM context with content in the response
     SuccessfulUpdateOfAnSMContextWithContentInTheResponse(models::SmContextUpdatedData),
+    /// successful update of an SM context with content in the response, and the N1 SM message or N2 SM information it references
+    SuccessfulUpdateOfAnSMContextWithContentAndBinaryDataInTheResponse {
+        body: models::SmContextUpdatedData,
+        binary_data_n1_sm_message: Option<swagger::ByteArray>,
+        binary_data_n2_sm_information: Option<swagger::ByteArray>,
+    },
     /// successful update of an SM context without content in the response
     SuccessfulUpdateOfAnSMContextWithoutContentInTheResponse,
     /// Temporary Redirect
@@ -428,6 +434,11 @@ pub enum PostSmContextsResponse {
     TooManyRequests(models::ExtProblemDetails),
     /// unsuccessful creation of an SM context - internal server error
     UnsuccessfulCreationOfAnSMContext_4(models::SmContextCreateError),
+    /// unsuccessful creation of an SM context - internal server error, with the N1 SM message
+    UnsuccessfulCreationOfAnSMContextWithN1SmMessage_4 {
+        body: models::SmContextCreateError,
+        binary_data_n1_sm_message: swagger::ByteArray,
+    },
     /// unsuccessful creation of an SM context - service unavailable
     UnsuccessfulCreationOfAnSMContext_5(models::SmContextCreateError),
     /// unsuccessful creation of an SM context - gateway timeout
@@ -510,6 +521,9 @@ pub trait Api<C: Send + Sync> {
         &self,
         sm_context_ref: String,
         sm_context_update_data: models::SmContextUpdateData,
+        binary_data_n1_sm_message: Option<swagger::ByteArray>,
+        binary_data_n2_sm_information: Option<swagger::ByteArray>,
+        binary_data_n2_sm_information_ext1: Option<swagger::ByteArray>,
         context: &C,
     ) -> Result<UpdateSmContextResponse, ApiError>;
 
@@ -598,6 +612,9 @@ pub trait ApiNoContext<C: Send + Sync> {
         &self,
         sm_context_ref: String,
         sm_context_update_data: models::SmContextUpdateData,
+        binary_data_n1_sm_message: Option<swagger::ByteArray>,
+        binary_data_n2_sm_information: Option<swagger::ByteArray>,
+        binary_data_n2_sm_information_ext1: Option<swagger::ByteArray>,
     ) -> Result<UpdateSmContextResponse, ApiError>;
 
     /// Create
@@ -732,10 +749,20 @@ impl<T: Api<C> + Send + Sync, C: Clone + Send + Sync> ApiNoContext<C> for Contex
         &self,
         sm_context_ref: String,
         sm_context_update_data: models::SmContextUpdateData,
+        binary_data_n1_sm_message: Option<swagger::ByteArray>,
+        binary_data_n2_sm_information: Option<swagger::ByteArray>,
+        binary_data_n2_sm_information_ext1: Option<swagger::ByteArray>,
     ) -> Result<UpdateSmContextResponse, ApiError> {
         let context = self.context().clone();
         self.api()
-            .update_sm_context(sm_context_ref, sm_context_update_data, &context)
+            .update_sm_context(
+                sm_context_ref,
+                sm_context_update_data,
+                binary_data_n1_sm_message,
+                binary_data_n2_sm_information,
+                binary_data_n2_sm_information_ext1,
+                &context,
+            )
             .await
     }
 
@@ -1350,7 +1377,15 @@ pub use self::server::Service;
 #[cfg(any(feature = "client", feature = "server"))]
 pub mod context;
 
+pub mod ip_pools;
+
 pub mod models;
 
+pub mod nas;
+
+pub mod ngap;
+
+pub mod sm_contexts;
+
 #[cfg(any(feature = "client", feature = "server"))]
 pub(crate) mod header;
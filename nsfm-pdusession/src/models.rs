@@ -2456,12 +2456,12 @@ impl std::ops::DerefMut for Bytes {
 /// Cause information. Possible values are - REL_DUE_TO_HO - EPS_FALLBACK - REL_DUE_TO_UP_SEC - DNN_CONGESTION - S_NSSAI_CONGESTION - REL_DUE_TO_REACTIVATION - 5G_AN_NOT_RESPONDING - REL_DUE_TO_SLICE_NOT_AVAILABLE - REL_DUE_TO_DUPLICATE_SESSION_ID - PDU_SESSION_STATUS_MISMATCH - HO_FAILURE - INSUFFICIENT_UP_RESOURCES - PDU_SESSION_HANDED_OVER - PDU_SESSION_RESUMED - CN_ASSISTED_RAN_PARAMETER_TUNING - ISMF_CONTEXT_TRANSFER - SMF_CONTEXT_TRANSFER - REL_DUE_TO_PS_TO_CS_HO - REL_DUE_TO_SUBSCRIPTION_CHANGE - HO_CANCEL - REL_DUE_TO_SLICE_NOT_AUTHORIZED - PDU_SESSION_HAND_OVER_FAILURE - DDN_FAILURE_STATUS - REL_DUE_TO_CP_ONLY_NOT_APPLICABLE - NOT_SUPPORTED_WITH_ISMF - CHANGED_ANCHOR_SMF - CHANGED_INTERMEDIATE_SMF - TARGET_DNAI_NOTIFICATION - REL_DUE_TO_VPLMN_QOS_FAILURE - REL_DUE_TO_SMF_NOT_SUPPORT_PSETR - REL_DUE_TO_SNPN_SNPN_MOBILITY - REL_DUE_TO_NO_HR_AGREEMENT - REL_DUE_TO_UNSPECIFIED_REASON
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 #[cfg_attr(feature = "conversion", derive(frunk::LabelledGeneric))]
-pub struct Cause {}
+pub struct Cause(String);
 
 impl Cause {
     #[allow(clippy::new_without_default)]
-    pub fn new() -> Cause {
-        Cause {}
+    pub fn new(s: String) -> Cause {
+        Cause(s)
     }
 }
 
@@ -2470,9 +2470,7 @@ impl Cause {
 /// Should be implemented in a serde serializer
 impl std::string::ToString for Cause {
     fn to_string(&self) -> String {
-        let params: Vec<Option<String>> = vec![];
-
-        params.into_iter().flatten().collect::<Vec<_>>().join(",")
+        self.0.clone()
     }
 }
 
@@ -2483,44 +2481,7 @@ impl std::str::FromStr for Cause {
     type Err = String;
 
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        /// An intermediate representation of the struct to use for parsing.
-        #[derive(Default)]
-        #[allow(dead_code)]
-        struct IntermediateRep {}
-
-        let mut intermediate_rep = IntermediateRep::default();
-
-        // Parse into intermediate representation
-        let mut string_iter = s.split(',');
-        let mut key_result = string_iter.next();
-
-        while key_result.is_some() {
-            let val = match string_iter.next() {
-                Some(x) => x,
-                None => {
-                    return std::result::Result::Err(
-                        "Missing value while parsing Cause".to_string(),
-                    )
-                }
-            };
-
-            if let Some(key) = key_result {
-                #[allow(clippy::match_single_binding)]
-                match key {
-                    _ => {
-                        return std::result::Result::Err(
-                            "Unexpected key while parsing Cause".to_string(),
-                        )
-                    }
-                }
-            }
-
-            // Get the next key
-            key_result = string_iter.next();
-        }
-
-        // Use the intermediate representation to return the struct
-        std::result::Result::Ok(Cause {})
+        std::result::Result::Ok(Cause(s.to_string()))
     }
 }
 
@@ -8221,12 +8182,12 @@ impl std::convert::TryFrom<hyper::header::HeaderValue> for header::IntoHeaderVal
 /// Handover state. Possible values are - NONE - PREPARING - PREPARED - COMPLETED - CANCELLED
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 #[cfg_attr(feature = "conversion", derive(frunk::LabelledGeneric))]
-pub struct HoState {}
+pub struct HoState(String);
 
 impl HoState {
     #[allow(clippy::new_without_default)]
-    pub fn new() -> HoState {
-        HoState {}
+    pub fn new(s: String) -> HoState {
+        HoState(s)
     }
 }
 
@@ -8235,9 +8196,7 @@ impl HoState {
 /// Should be implemented in a serde serializer
 impl std::string::ToString for HoState {
     fn to_string(&self) -> String {
-        let params: Vec<Option<String>> = vec![];
-
-        params.into_iter().flatten().collect::<Vec<_>>().join(",")
+        self.0.clone()
     }
 }
 
@@ -8248,44 +8207,7 @@ impl std::str::FromStr for HoState {
     type Err = String;
 
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        /// An intermediate representation of the struct to use for parsing.
-        #[derive(Default)]
-        #[allow(dead_code)]
-        struct IntermediateRep {}
-
-        let mut intermediate_rep = IntermediateRep::default();
-
-        // Parse into intermediate representation
-        let mut string_iter = s.split(',');
-        let mut key_result = string_iter.next();
-
-        while key_result.is_some() {
-            let val = match string_iter.next() {
-                Some(x) => x,
-                None => {
-                    return std::result::Result::Err(
-                        "Missing value while parsing HoState".to_string(),
-                    )
-                }
-            };
-
-            if let Some(key) = key_result {
-                #[allow(clippy::match_single_binding)]
-                match key {
-                    _ => {
-                        return std::result::Result::Err(
-                            "Unexpected key while parsing HoState".to_string(),
-                        )
-                    }
-                }
-            }
-
-            // Get the next key
-            key_result = string_iter.next();
-        }
-
-        // Use the intermediate representation to return the struct
-        std::result::Result::Ok(HoState {})
+        std::result::Result::Ok(HoState(s.to_string()))
     }
 }
 
@@ -12155,12 +12077,12 @@ impl std::ops::DerefMut for Model5QiPriorityLevel {
 /// N2 SM Information Type. Possible values are - PDU_RES_SETUP_REQ - PDU_RES_SETUP_RSP - PDU_RES_SETUP_FAIL - PDU_RES_REL_CMD - PDU_RES_REL_RSP - PDU_RES_MOD_REQ - PDU_RES_MOD_RSP - PDU_RES_MOD_FAIL - PDU_RES_NTY - PDU_RES_NTY_REL - PDU_RES_MOD_IND - PDU_RES_MOD_CFM - PATH_SWITCH_REQ - PATH_SWITCH_SETUP_FAIL - PATH_SWITCH_REQ_ACK - PATH_SWITCH_REQ_FAIL - HANDOVER_REQUIRED - HANDOVER_CMD - HANDOVER_PREP_FAIL - HANDOVER_REQ_ACK - HANDOVER_RES_ALLOC_FAIL - SECONDARY_RAT_USAGE - PDU_RES_MOD_IND_FAIL - UE_CONTEXT_RESUME_REQ - UE_CONTEXT_RESUME_RSP - UE_CONTEXT_SUSPEND_REQ
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 #[cfg_attr(feature = "conversion", derive(frunk::LabelledGeneric))]
-pub struct N2SmInfoType {}
+pub struct N2SmInfoType(String);
 
 impl N2SmInfoType {
     #[allow(clippy::new_without_default)]
-    pub fn new() -> N2SmInfoType {
-        N2SmInfoType {}
+    pub fn new(s: String) -> N2SmInfoType {
+        N2SmInfoType(s)
     }
 }
 
@@ -12169,9 +12091,7 @@ impl N2SmInfoType {
 /// Should be implemented in a serde serializer
 impl std::string::ToString for N2SmInfoType {
     fn to_string(&self) -> String {
-        let params: Vec<Option<String>> = vec![];
-
-        params.into_iter().flatten().collect::<Vec<_>>().join(",")
+        self.0.clone()
     }
 }
 
@@ -12182,44 +12102,7 @@ impl std::str::FromStr for N2SmInfoType {
     type Err = String;
 
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        /// An intermediate representation of the struct to use for parsing.
-        #[derive(Default)]
-        #[allow(dead_code)]
-        struct IntermediateRep {}
-
-        let mut intermediate_rep = IntermediateRep::default();
-
-        // Parse into intermediate representation
-        let mut string_iter = s.split(',');
-        let mut key_result = string_iter.next();
-
-        while key_result.is_some() {
-            let val = match string_iter.next() {
-                Some(x) => x,
-                None => {
-                    return std::result::Result::Err(
-                        "Missing value while parsing N2SmInfoType".to_string(),
-                    )
-                }
-            };
-
-            if let Some(key) = key_result {
-                #[allow(clippy::match_single_binding)]
-                match key {
-                    _ => {
-                        return std::result::Result::Err(
-                            "Unexpected key while parsing N2SmInfoType".to_string(),
-                        )
-                    }
-                }
-            }
-
-            // Get the next key
-            key_result = string_iter.next();
-        }
-
-        // Use the intermediate representation to return the struct
-        std::result::Result::Ok(N2SmInfoType {})
+        std::result::Result::Ok(N2SmInfoType(s.to_string()))
     }
 }
 
@@ -18035,12 +17918,12 @@ impl std::convert::TryFrom<hyper::header::HeaderValue>
 /// PduSessionType indicates the type of a PDU session. It shall comply with the provisions defined in table 5.4.3.3-1.
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 #[cfg_attr(feature = "conversion", derive(frunk::LabelledGeneric))]
-pub struct PduSessionType {}
+pub struct PduSessionType(String);
 
 impl PduSessionType {
     #[allow(clippy::new_without_default)]
-    pub fn new() -> PduSessionType {
-        PduSessionType {}
+    pub fn new(s: String) -> PduSessionType {
+        PduSessionType(s)
     }
 }
 
@@ -18049,9 +17932,7 @@ impl PduSessionType {
 /// Should be implemented in a serde serializer
 impl std::string::ToString for PduSessionType {
     fn to_string(&self) -> String {
-        let params: Vec<Option<String>> = vec![];
-
-        params.into_iter().flatten().collect::<Vec<_>>().join(",")
+        self.0.clone()
     }
 }
 
@@ -18062,44 +17943,7 @@ impl std::str::FromStr for PduSessionType {
     type Err = String;
 
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        /// An intermediate representation of the struct to use for parsing.
-        #[derive(Default)]
-        #[allow(dead_code)]
-        struct IntermediateRep {}
-
-        let mut intermediate_rep = IntermediateRep::default();
-
-        // Parse into intermediate representation
-        let mut string_iter = s.split(',');
-        let mut key_result = string_iter.next();
-
-        while key_result.is_some() {
-            let val = match string_iter.next() {
-                Some(x) => x,
-                None => {
-                    return std::result::Result::Err(
-                        "Missing value while parsing PduSessionType".to_string(),
-                    )
-                }
-            };
-
-            if let Some(key) = key_result {
-                #[allow(clippy::match_single_binding)]
-                match key {
-                    _ => {
-                        return std::result::Result::Err(
-                            "Unexpected key while parsing PduSessionType".to_string(),
-                        )
-                    }
-                }
-            }
-
-            // Get the next key
-            key_result = string_iter.next();
-        }
-
-        // Use the intermediate representation to return the struct
-        std::result::Result::Ok(PduSessionType {})
+        std::result::Result::Ok(PduSessionType(s.to_string()))
     }
 }
 
@@ -18563,12 +18407,12 @@ impl std::convert::TryFrom<hyper::header::HeaderValue> for header::IntoHeaderVal
 /// The enumeration PreemptionCapability indicates the pre-emption capability of a request on other QoS flows. See clause 5.7.2.2 of 3GPP TS 23.501. It shall comply with the provisions defined in table 5.5.3.1-1.
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 #[cfg_attr(feature = "conversion", derive(frunk::LabelledGeneric))]
-pub struct PreemptionCapability {}
+pub struct PreemptionCapability(String);
 
 impl PreemptionCapability {
     #[allow(clippy::new_without_default)]
-    pub fn new() -> PreemptionCapability {
-        PreemptionCapability {}
+    pub fn new(s: String) -> PreemptionCapability {
+        PreemptionCapability(s)
     }
 }
 
@@ -18577,9 +18421,7 @@ impl PreemptionCapability {
 /// Should be implemented in a serde serializer
 impl std::string::ToString for PreemptionCapability {
     fn to_string(&self) -> String {
-        let params: Vec<Option<String>> = vec![];
-
-        params.into_iter().flatten().collect::<Vec<_>>().join(",")
+        self.0.clone()
     }
 }
 
@@ -18590,44 +18432,7 @@ impl std::str::FromStr for PreemptionCapability {
     type Err = String;
 
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        /// An intermediate representation of the struct to use for parsing.
-        #[derive(Default)]
-        #[allow(dead_code)]
-        struct IntermediateRep {}
-
-        let mut intermediate_rep = IntermediateRep::default();
-
-        // Parse into intermediate representation
-        let mut string_iter = s.split(',');
-        let mut key_result = string_iter.next();
-
-        while key_result.is_some() {
-            let val = match string_iter.next() {
-                Some(x) => x,
-                None => {
-                    return std::result::Result::Err(
-                        "Missing value while parsing PreemptionCapability".to_string(),
-                    )
-                }
-            };
-
-            if let Some(key) = key_result {
-                #[allow(clippy::match_single_binding)]
-                match key {
-                    _ => {
-                        return std::result::Result::Err(
-                            "Unexpected key while parsing PreemptionCapability".to_string(),
-                        )
-                    }
-                }
-            }
-
-            // Get the next key
-            key_result = string_iter.next();
-        }
-
-        // Use the intermediate representation to return the struct
-        std::result::Result::Ok(PreemptionCapability {})
+        std::result::Result::Ok(PreemptionCapability(s.to_string()))
     }
 }
 
@@ -18720,12 +18525,12 @@ impl std::str::FromStr for PreemptionCapabilityAnyOf {
 /// The enumeration PreemptionVulnerability indicates the pre-emption vulnerability of the QoS flow to pre-emption from other QoS flows. See clause 5.7.2.2 of 3GPP TS 23.501. It shall comply with the provisions defined in table 5.5.3.2-1
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 #[cfg_attr(feature = "conversion", derive(frunk::LabelledGeneric))]
-pub struct PreemptionVulnerability {}
+pub struct PreemptionVulnerability(String);
 
 impl PreemptionVulnerability {
     #[allow(clippy::new_without_default)]
-    pub fn new() -> PreemptionVulnerability {
-        PreemptionVulnerability {}
+    pub fn new(s: String) -> PreemptionVulnerability {
+        PreemptionVulnerability(s)
     }
 }
 
@@ -18734,9 +18539,7 @@ impl PreemptionVulnerability {
 /// Should be implemented in a serde serializer
 impl std::string::ToString for PreemptionVulnerability {
     fn to_string(&self) -> String {
-        let params: Vec<Option<String>> = vec![];
-
-        params.into_iter().flatten().collect::<Vec<_>>().join(",")
+        self.0.clone()
     }
 }
 
@@ -18747,44 +18550,7 @@ impl std::str::FromStr for PreemptionVulnerability {
     type Err = String;
 
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        /// An intermediate representation of the struct to use for parsing.
-        #[derive(Default)]
-        #[allow(dead_code)]
-        struct IntermediateRep {}
-
-        let mut intermediate_rep = IntermediateRep::default();
-
-        // Parse into intermediate representation
-        let mut string_iter = s.split(',');
-        let mut key_result = string_iter.next();
-
-        while key_result.is_some() {
-            let val = match string_iter.next() {
-                Some(x) => x,
-                None => {
-                    return std::result::Result::Err(
-                        "Missing value while parsing PreemptionVulnerability".to_string(),
-                    )
-                }
-            };
-
-            if let Some(key) = key_result {
-                #[allow(clippy::match_single_binding)]
-                match key {
-                    _ => {
-                        return std::result::Result::Err(
-                            "Unexpected key while parsing PreemptionVulnerability".to_string(),
-                        )
-                    }
-                }
-            }
-
-            // Get the next key
-            key_result = string_iter.next();
-        }
-
-        // Use the intermediate representation to return the struct
-        std::result::Result::Ok(PreemptionVulnerability {})
+        std::result::Result::Ok(PreemptionVulnerability(s.to_string()))
     }
 }
 
@@ -24219,12 +23985,12 @@ impl std::str::FromStr for RequestIndicationAnyOf {
 /// Request Type in Create (SM context) service operation. Possible values are - INITIAL_REQUEST - EXISTING_PDU_SESSION - INITIAL_EMERGENCY_REQUEST - EXISTING_EMERGENCY_PDU_SESSION
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 #[cfg_attr(feature = "conversion", derive(frunk::LabelledGeneric))]
-pub struct RequestType {}
+pub struct RequestType(String);
 
 impl RequestType {
     #[allow(clippy::new_without_default)]
-    pub fn new() -> RequestType {
-        RequestType {}
+    pub fn new(s: String) -> RequestType {
+        RequestType(s)
     }
 }
 
@@ -24233,9 +23999,7 @@ impl RequestType {
 /// Should be implemented in a serde serializer
 impl std::string::ToString for RequestType {
     fn to_string(&self) -> String {
-        let params: Vec<Option<String>> = vec![];
-
-        params.into_iter().flatten().collect::<Vec<_>>().join(",")
+        self.0.clone()
     }
 }
 
@@ -24246,44 +24010,7 @@ impl std::str::FromStr for RequestType {
     type Err = String;
 
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        /// An intermediate representation of the struct to use for parsing.
-        #[derive(Default)]
-        #[allow(dead_code)]
-        struct IntermediateRep {}
-
-        let mut intermediate_rep = IntermediateRep::default();
-
-        // Parse into intermediate representation
-        let mut string_iter = s.split(',');
-        let mut key_result = string_iter.next();
-
-        while key_result.is_some() {
-            let val = match string_iter.next() {
-                Some(x) => x,
-                None => {
-                    return std::result::Result::Err(
-                        "Missing value while parsing RequestType".to_string(),
-                    )
-                }
-            };
-
-            if let Some(key) = key_result {
-                #[allow(clippy::match_single_binding)]
-                match key {
-                    _ => {
-                        return std::result::Result::Err(
-                            "Unexpected key while parsing RequestType".to_string(),
-                        )
-                    }
-                }
-            }
-
-            // Get the next key
-            key_result = string_iter.next();
-        }
-
-        // Use the intermediate representation to return the struct
-        std::result::Result::Ok(RequestType {})
+        std::result::Result::Ok(RequestType(s.to_string()))
     }
 }
 
@@ -32023,12 +31750,12 @@ impl std::convert::TryFrom<hyper::header::HeaderValue>
 /// Type of SM Context information. Possible values are   - EPS_PDN_CONNECTION   - SM_CONTEXT   - AF_COORDINATION_INFO
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 #[cfg_attr(feature = "conversion", derive(frunk::LabelledGeneric))]
-pub struct SmContextType {}
+pub struct SmContextType(String);
 
 impl SmContextType {
     #[allow(clippy::new_without_default)]
-    pub fn new() -> SmContextType {
-        SmContextType {}
+    pub fn new(s: String) -> SmContextType {
+        SmContextType(s)
     }
 }
 
@@ -32037,9 +31764,7 @@ impl SmContextType {
 /// Should be implemented in a serde serializer
 impl std::string::ToString for SmContextType {
     fn to_string(&self) -> String {
-        let params: Vec<Option<String>> = vec![];
-
-        params.into_iter().flatten().collect::<Vec<_>>().join(",")
+        self.0.clone()
     }
 }
 
@@ -32050,44 +31775,7 @@ impl std::str::FromStr for SmContextType {
     type Err = String;
 
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        /// An intermediate representation of the struct to use for parsing.
-        #[derive(Default)]
-        #[allow(dead_code)]
-        struct IntermediateRep {}
-
-        let mut intermediate_rep = IntermediateRep::default();
-
-        // Parse into intermediate representation
-        let mut string_iter = s.split(',');
-        let mut key_result = string_iter.next();
-
-        while key_result.is_some() {
-            let val = match string_iter.next() {
-                Some(x) => x,
-                None => {
-                    return std::result::Result::Err(
-                        "Missing value while parsing SmContextType".to_string(),
-                    )
-                }
-            };
-
-            if let Some(key) = key_result {
-                #[allow(clippy::match_single_binding)]
-                match key {
-                    _ => {
-                        return std::result::Result::Err(
-                            "Unexpected key while parsing SmContextType".to_string(),
-                        )
-                    }
-                }
-            }
-
-            // Get the next key
-            key_result = string_iter.next();
-        }
-
-        // Use the intermediate representation to return the struct
-        std::result::Result::Ok(SmContextType {})
+        std::result::Result::Ok(SmContextType(s.to_string()))
     }
 }
 
@@ -39215,12 +38903,12 @@ impl std::str::FromStr for UnavailableAccessIndicationAnyOf {
 /// User Plane Connection State. Possible values are - ACTIVATED - DEACTIVATED - ACTIVATING - SUSPENDED
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 #[cfg_attr(feature = "conversion", derive(frunk::LabelledGeneric))]
-pub struct UpCnxState {}
+pub struct UpCnxState(String);
 
 impl UpCnxState {
     #[allow(clippy::new_without_default)]
-    pub fn new() -> UpCnxState {
-        UpCnxState {}
+    pub fn new(s: String) -> UpCnxState {
+        UpCnxState(s)
     }
 }
 
@@ -39229,9 +38917,7 @@ impl UpCnxState {
 /// Should be implemented in a serde serializer
 impl std::string::ToString for UpCnxState {
     fn to_string(&self) -> String {
-        let params: Vec<Option<String>> = vec![];
-
-        params.into_iter().flatten().collect::<Vec<_>>().join(",")
+        self.0.clone()
     }
 }
 
@@ -39242,44 +38928,7 @@ impl std::str::FromStr for UpCnxState {
     type Err = String;
 
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        /// An intermediate representation of the struct to use for parsing.
-        #[derive(Default)]
-        #[allow(dead_code)]
-        struct IntermediateRep {}
-
-        let mut intermediate_rep = IntermediateRep::default();
-
-        // Parse into intermediate representation
-        let mut string_iter = s.split(',');
-        let mut key_result = string_iter.next();
-
-        while key_result.is_some() {
-            let val = match string_iter.next() {
-                Some(x) => x,
-                None => {
-                    return std::result::Result::Err(
-                        "Missing value while parsing UpCnxState".to_string(),
-                    )
-                }
-            };
-
-            if let Some(key) = key_result {
-                #[allow(clippy::match_single_binding)]
-                match key {
-                    _ => {
-                        return std::result::Result::Err(
-                            "Unexpected key while parsing UpCnxState".to_string(),
-                        )
-                    }
-                }
-            }
-
-            // Get the next key
-            key_result = string_iter.next();
-        }
-
-        // Use the intermediate representation to return the struct
-        std::result::Result::Ok(UpCnxState {})
+        std::result::Result::Ok(UpCnxState(s.to_string()))
     }
 }
 
@@ -0,0 +1,1658 @@
+//! 5GS session management (5GSM) messages, the N1 SM messages the UE and
+//! the SMF exchange through the AMF (TS 24.501 clause 8.3).
+//!
+//! A [`Message`] is decoded from and encoded to a plain 5GSM message: the
+//! AMF removes the security protection before forwarding it to the SMF. The
+//! optional IEs the SMF does not use are skipped when decoding, as TS 24.501
+//! clause 7.6 has the receiver ignore the IEs it does not know.
+
+use serde::{Deserialize, Serialize};
+use std::net::Ipv4Addr;
+
+use crate::models;
+
+/// Extended protocol discriminator of the 5GSM messages
+pub const EPD_5GSM: u8 = 0x2e;
+
+// Message types (TS 24.501 clause 9.7)
+const ESTABLISHMENT_REQUEST: u8 = 0xc1;
+const ESTABLISHMENT_ACCEPT: u8 = 0xc2;
+const ESTABLISHMENT_REJECT: u8 = 0xc3;
+const MODIFICATION_REQUEST: u8 = 0xc9;
+const MODIFICATION_REJECT: u8 = 0xca;
+const MODIFICATION_COMMAND: u8 = 0xcb;
+const MODIFICATION_COMPLETE: u8 = 0xcc;
+const MODIFICATION_COMMAND_REJECT: u8 = 0xcd;
+const RELEASE_REQUEST: u8 = 0xd1;
+const RELEASE_REJECT: u8 = 0xd2;
+const RELEASE_COMMAND: u8 = 0xd3;
+const RELEASE_COMPLETE: u8 = 0xd4;
+
+// IEIs of the optional IEs; the ones of type 1 take the upper half octet
+const PDU_SESSION_TYPE: u8 = 0x90;
+const SSC_MODE: u8 = 0xa0;
+const INTEGRITY_PROTECTION_MAXIMUM_DATA_RATE: u8 = 0x13;
+const S_NSSAI: u8 = 0x22;
+const DNN: u8 = 0x25;
+const CAPABILITY: u8 = 0x28;
+const PDU_ADDRESS: u8 = 0x29;
+const SESSION_AMBR: u8 = 0x2a;
+const BACK_OFF_TIMER: u8 = 0x37;
+const MAXIMUM_NUMBER_OF_SUPPORTED_PACKET_FILTERS: u8 = 0x55;
+const RQ_TIMER: u8 = 0x56;
+const CAUSE: u8 = 0x59;
+const QOS_FLOW_DESCRIPTIONS: u8 = 0x79;
+const QOS_RULES: u8 = 0x7a;
+const EXTENDED_PCO: u8 = 0x7b;
+
+/// A 5GSM message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    /// PDU session identity, within the range 1 to 15
+    pub pdu_session_id: u8,
+    /// Procedure transaction identity: the one of the request of the UE
+    /// in the answer of the SMF, 0 in a command of the SMF
+    pub pti: u8,
+    pub body: Body,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Body {
+    EstablishmentRequest(EstablishmentRequest),
+    EstablishmentAccept(EstablishmentAccept),
+    EstablishmentReject(EstablishmentReject),
+    ModificationRequest(ModificationRequest),
+    ModificationReject(ModificationReject),
+    ModificationCommand(ModificationCommand),
+    ModificationComplete(ModificationComplete),
+    ModificationCommandReject(ModificationCommandReject),
+    ReleaseRequest(ReleaseRequest),
+    ReleaseReject(ReleaseReject),
+    ReleaseCommand(ReleaseCommand),
+    ReleaseComplete(ReleaseComplete),
+}
+
+/// PDU session establishment request, UE to network (clause 8.3.1)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EstablishmentRequest {
+    pub integrity_protection_maximum_data_rate: IntegrityProtectionMaximumDataRate,
+    pub pdu_session_type: Option<PduSessionType>,
+    pub ssc_mode: Option<SscMode>,
+    /// 5GSM capability, as sent by the UE
+    pub capability: Option<Vec<u8>>,
+    pub extended_pco: Option<ExtendedPco>,
+}
+
+/// PDU session establishment accept, network to UE (clause 8.3.2)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EstablishmentAccept {
+    pub pdu_session_type: PduSessionType,
+    pub ssc_mode: SscMode,
+    pub authorized_qos_rules: Vec<QosRule>,
+    pub session_ambr: SessionAmbr,
+    pub cause: Option<Cause>,
+    pub pdu_address: Option<PduAddress>,
+    pub s_nssai: Option<Snssai>,
+    /// Left out of the message when empty
+    pub authorized_qos_flow_descriptions: Vec<QosFlowDescription>,
+    pub extended_pco: Option<ExtendedPco>,
+    pub dnn: Option<String>,
+}
+
+/// PDU session establishment reject, network to UE (clause 8.3.3)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EstablishmentReject {
+    pub cause: Cause,
+    /// GPRS timer 3 value (TS 24.008 clause 10.5.7.4a)
+    pub back_off_timer: Option<u8>,
+    pub extended_pco: Option<ExtendedPco>,
+}
+
+/// PDU session modification request, UE to network (clause 8.3.7)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModificationRequest {
+    pub capability: Option<Vec<u8>>,
+    pub cause: Option<Cause>,
+    pub integrity_protection_maximum_data_rate: Option<IntegrityProtectionMaximumDataRate>,
+    /// Left out of the message when empty
+    pub requested_qos_rules: Vec<QosRule>,
+    /// Left out of the message when empty
+    pub requested_qos_flow_descriptions: Vec<QosFlowDescription>,
+    pub extended_pco: Option<ExtendedPco>,
+}
+
+/// PDU session modification reject, network to UE (clause 8.3.8)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModificationReject {
+    pub cause: Cause,
+    /// GPRS timer 3 value (TS 24.008 clause 10.5.7.4a)
+    pub back_off_timer: Option<u8>,
+    pub extended_pco: Option<ExtendedPco>,
+}
+
+/// PDU session modification command, network to UE (clause 8.3.9)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModificationCommand {
+    pub cause: Option<Cause>,
+    pub session_ambr: Option<SessionAmbr>,
+    /// Left out of the message when empty
+    pub authorized_qos_rules: Vec<QosRule>,
+    /// Left out of the message when empty
+    pub authorized_qos_flow_descriptions: Vec<QosFlowDescription>,
+    pub extended_pco: Option<ExtendedPco>,
+}
+
+/// PDU session modification complete, UE to network (clause 8.3.10)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ModificationComplete {
+    pub extended_pco: Option<ExtendedPco>,
+}
+
+/// PDU session modification command reject, UE to network (clause 8.3.11)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModificationCommandReject {
+    pub cause: Cause,
+    pub extended_pco: Option<ExtendedPco>,
+}
+
+/// PDU session release request, UE to network (clause 8.3.12)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReleaseRequest {
+    pub cause: Option<Cause>,
+    pub extended_pco: Option<ExtendedPco>,
+}
+
+/// PDU session release reject, network to UE (clause 8.3.13)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReleaseReject {
+    pub cause: Cause,
+    pub extended_pco: Option<ExtendedPco>,
+}
+
+/// PDU session release command, network to UE (clause 8.3.14)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReleaseCommand {
+    pub cause: Cause,
+    /// GPRS timer 3 value (TS 24.008 clause 10.5.7.4a)
+    pub back_off_timer: Option<u8>,
+    pub extended_pco: Option<ExtendedPco>,
+}
+
+/// PDU session release complete, UE to network (clause 8.3.15)
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReleaseComplete {
+    pub cause: Option<Cause>,
+    pub extended_pco: Option<ExtendedPco>,
+}
+
+/// 5GSM cause (clause 9.11.4.2).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cause(pub u8);
+
+impl Cause {
+    pub const OPERATOR_DETERMINED_BARRING: Cause = Cause(8);
+    pub const INSUFFICIENT_RESOURCES: Cause = Cause(26);
+    pub const MISSING_OR_UNKNOWN_DNN: Cause = Cause(27);
+    pub const UNKNOWN_PDU_SESSION_TYPE: Cause = Cause(28);
+    pub const USER_AUTHENTICATION_OR_AUTHORIZATION_FAILED: Cause = Cause(29);
+    pub const REQUEST_REJECTED_UNSPECIFIED: Cause = Cause(31);
+    pub const SERVICE_OPTION_NOT_SUPPORTED: Cause = Cause(32);
+    pub const REGULAR_DEACTIVATION: Cause = Cause(36);
+    pub const NETWORK_FAILURE: Cause = Cause(38);
+    pub const REACTIVATION_REQUESTED: Cause = Cause(39);
+    pub const INVALID_PDU_SESSION_IDENTITY: Cause = Cause(43);
+    pub const PDU_SESSION_TYPE_IPV4_ONLY_ALLOWED: Cause = Cause(50);
+    pub const PDU_SESSION_TYPE_IPV6_ONLY_ALLOWED: Cause = Cause(51);
+    pub const PDU_SESSION_DOES_NOT_EXIST: Cause = Cause(54);
+    pub const INSUFFICIENT_RESOURCES_FOR_SPECIFIC_SLICE_AND_DNN: Cause = Cause(67);
+    pub const NOT_SUPPORTED_SSC_MODE: Cause = Cause(68);
+    pub const INSUFFICIENT_RESOURCES_FOR_SPECIFIC_SLICE: Cause = Cause(69);
+    pub const MISSING_OR_UNKNOWN_DNN_IN_A_SLICE: Cause = Cause(70);
+    pub const INVALID_PTI_VALUE: Cause = Cause(81);
+    pub const SEMANTICALLY_INCORRECT_MESSAGE: Cause = Cause(95);
+    pub const INVALID_MANDATORY_INFORMATION: Cause = Cause(96);
+    pub const MESSAGE_TYPE_NON_EXISTENT_OR_NOT_IMPLEMENTED: Cause = Cause(97);
+    pub const MESSAGE_TYPE_NOT_COMPATIBLE_WITH_THE_PROTOCOL_STATE: Cause = Cause(98);
+    pub const PROTOCOL_ERROR_UNSPECIFIED: Cause = Cause(111);
+}
+
+/// Integrity protection maximum data rate (clause 9.11.4.7), one of the
+/// `INTEGRITY_PROTECTION_*` values for each direction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IntegrityProtectionMaximumDataRate {
+    pub uplink: u8,
+    pub downlink: u8,
+}
+
+impl IntegrityProtectionMaximumDataRate {
+    pub const INTEGRITY_PROTECTION_64_KBPS: u8 = 0x00;
+    pub const INTEGRITY_PROTECTION_NULL: u8 = 0x01;
+    pub const INTEGRITY_PROTECTION_FULL_DATA_RATE: u8 = 0xff;
+
+    pub const FULL_DATA_RATE: IntegrityProtectionMaximumDataRate =
+        IntegrityProtectionMaximumDataRate {
+            uplink: Self::INTEGRITY_PROTECTION_FULL_DATA_RATE,
+            downlink: Self::INTEGRITY_PROTECTION_FULL_DATA_RATE,
+        };
+
+    fn from_bytes(bytes: &[u8]) -> Result<IntegrityProtectionMaximumDataRate, DecodeError> {
+        match bytes {
+            [uplink, downlink] => Ok(IntegrityProtectionMaximumDataRate {
+                uplink: *uplink,
+                downlink: *downlink,
+            }),
+            _ => Err(DecodeError::InvalidIe(
+                "integrity protection maximum data rate",
+            )),
+        }
+    }
+}
+
+/// PDU session type (clause 9.11.4.11).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PduSessionType {
+    #[serde(rename = "IPV4")]
+    Ipv4 = 1,
+    #[serde(rename = "IPV6")]
+    Ipv6 = 2,
+    #[serde(rename = "IPV4V6")]
+    Ipv4v6 = 3,
+    #[serde(rename = "UNSTRUCTURED")]
+    Unstructured = 4,
+    #[serde(rename = "ETHERNET")]
+    Ethernet = 5,
+}
+
+impl PduSessionType {
+    fn from_u8(value: u8) -> Result<PduSessionType, DecodeError> {
+        match value {
+            1 => Ok(PduSessionType::Ipv4),
+            2 => Ok(PduSessionType::Ipv6),
+            3 => Ok(PduSessionType::Ipv4v6),
+            4 => Ok(PduSessionType::Unstructured),
+            5 => Ok(PduSessionType::Ethernet),
+            _ => Err(DecodeError::InvalidIe("PDU session type")),
+        }
+    }
+}
+
+impl std::fmt::Display for PduSessionType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PduSessionType::Ipv4 => write!(f, "IPV4"),
+            PduSessionType::Ipv6 => write!(f, "IPV6"),
+            PduSessionType::Ipv4v6 => write!(f, "IPV4V6"),
+            PduSessionType::Unstructured => write!(f, "UNSTRUCTURED"),
+            PduSessionType::Ethernet => write!(f, "ETHERNET"),
+        }
+    }
+}
+
+/// Parses the PDU session types of TS 29.571, e.g. `IPV4`.
+impl std::str::FromStr for PduSessionType {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "IPV4" => Ok(PduSessionType::Ipv4),
+            "IPV6" => Ok(PduSessionType::Ipv6),
+            "IPV4V6" => Ok(PduSessionType::Ipv4v6),
+            "UNSTRUCTURED" => Ok(PduSessionType::Unstructured),
+            "ETHERNET" => Ok(PduSessionType::Ethernet),
+            _ => Err(format!("Value not valid: {}", s)),
+        }
+    }
+}
+
+/// SSC mode (clause 9.11.4.16).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SscMode {
+    #[default]
+    #[serde(rename = "SSC_MODE_1")]
+    Mode1 = 1,
+    #[serde(rename = "SSC_MODE_2")]
+    Mode2 = 2,
+    #[serde(rename = "SSC_MODE_3")]
+    Mode3 = 3,
+}
+
+impl SscMode {
+    fn from_u8(value: u8) -> Result<SscMode, DecodeError> {
+        match value {
+            1 => Ok(SscMode::Mode1),
+            2 => Ok(SscMode::Mode2),
+            3 => Ok(SscMode::Mode3),
+            _ => Err(DecodeError::InvalidIe("SSC mode")),
+        }
+    }
+}
+
+/// PDU address (clause 9.11.4.10): the IPv4 address of the UE, and the
+/// interface identifier of its IPv6 link-local address.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PduAddress {
+    Ipv4(Ipv4Addr),
+    Ipv6 {
+        interface_identifier: [u8; 8],
+    },
+    Ipv4v6 {
+        interface_identifier: [u8; 8],
+        ipv4: Ipv4Addr,
+    },
+}
+
+impl PduAddress {
+    fn to_bytes(self) -> Vec<u8> {
+        match self {
+            PduAddress::Ipv4(ipv4) => [&[PduSessionType::Ipv4 as u8][..], &ipv4.octets()].concat(),
+            PduAddress::Ipv6 {
+                interface_identifier,
+            } => [&[PduSessionType::Ipv6 as u8][..], &interface_identifier].concat(),
+            PduAddress::Ipv4v6 {
+                interface_identifier,
+                ipv4,
+            } => [
+                &[PduSessionType::Ipv4v6 as u8][..],
+                &interface_identifier,
+                &ipv4.octets(),
+            ]
+            .concat(),
+        }
+    }
+
+    /// The IPv6 link-local address the SMF may add after the addresses is
+    /// ignored.
+    fn from_bytes(bytes: &[u8]) -> Result<PduAddress, DecodeError> {
+        let invalid = || DecodeError::InvalidIe("PDU address");
+        let (octet, address) = bytes.split_first().ok_or_else(invalid)?;
+        let ipv4 = |b: &[u8]| Ipv4Addr::new(b[0], b[1], b[2], b[3]);
+        let interface_identifier = |b: &[u8]| {
+            let mut id = [0; 8];
+            id.copy_from_slice(&b[..8]);
+            id
+        };
+        match octet & 0x07 {
+            1 if address.len() >= 4 => Ok(PduAddress::Ipv4(ipv4(address))),
+            2 if address.len() >= 8 => Ok(PduAddress::Ipv6 {
+                interface_identifier: interface_identifier(address),
+            }),
+            3 if address.len() >= 12 => Ok(PduAddress::Ipv4v6 {
+                interface_identifier: interface_identifier(address),
+                ipv4: ipv4(&address[8..]),
+            }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+/// S-NSSAI (clause 9.11.2.8), with the one it maps to in the HPLMN when
+/// roaming.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Snssai {
+    pub sst: u8,
+    /// 24 bits
+    pub sd: Option<u32>,
+    pub mapped_hplmn_sst: Option<u8>,
+    /// 24 bits
+    pub mapped_hplmn_sd: Option<u32>,
+}
+
+impl Snssai {
+    fn to_bytes(self) -> Vec<u8> {
+        let mut bytes = vec![self.sst];
+        if let Some(sd) = self.sd {
+            bytes.extend_from_slice(&sd.to_be_bytes()[1..]);
+        }
+        if let Some(sst) = self.mapped_hplmn_sst {
+            bytes.push(sst);
+        }
+        if let Some(sd) = self.mapped_hplmn_sd {
+            bytes.extend_from_slice(&sd.to_be_bytes()[1..]);
+        }
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<Snssai, DecodeError> {
+        let sd = |b: &[u8]| u32::from_be_bytes([0, b[0], b[1], b[2]]);
+        let mut snssai = Snssai {
+            sst: *bytes.first().ok_or(DecodeError::InvalidIe("S-NSSAI"))?,
+            sd: None,
+            mapped_hplmn_sst: None,
+            mapped_hplmn_sd: None,
+        };
+        match bytes.len() {
+            1 => (),
+            2 => snssai.mapped_hplmn_sst = Some(bytes[1]),
+            4 => snssai.sd = Some(sd(&bytes[1..])),
+            5 => {
+                snssai.sd = Some(sd(&bytes[1..]));
+                snssai.mapped_hplmn_sst = Some(bytes[4]);
+            }
+            8 => {
+                snssai.sd = Some(sd(&bytes[1..]));
+                snssai.mapped_hplmn_sst = Some(bytes[4]);
+                snssai.mapped_hplmn_sd = Some(sd(&bytes[5..]));
+            }
+            _ => return Err(DecodeError::InvalidIe("S-NSSAI")),
+        }
+        Ok(snssai)
+    }
+}
+
+impl From<&models::Snssai> for Snssai {
+    fn from(snssai: &models::Snssai) -> Snssai {
+        Snssai {
+            sst: snssai.sst,
+            sd: snssai
+                .sd
+                .as_ref()
+                .and_then(|sd| u32::from_str_radix(sd, 16).ok()),
+            mapped_hplmn_sst: None,
+            mapped_hplmn_sd: None,
+        }
+    }
+}
+
+/// Session-AMBR (clause 9.11.4.14).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SessionAmbr {
+    pub downlink: Bitrate,
+    pub uplink: Bitrate,
+}
+
+impl SessionAmbr {
+    fn to_bytes(self) -> Vec<u8> {
+        let mut bytes = vec![self.downlink.unit];
+        bytes.extend_from_slice(&self.downlink.value.to_be_bytes());
+        bytes.push(self.uplink.unit);
+        bytes.extend_from_slice(&self.uplink.value.to_be_bytes());
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<SessionAmbr, DecodeError> {
+        match bytes {
+            [dl_unit, dl_1, dl_2, ul_unit, ul_1, ul_2] => Ok(SessionAmbr {
+                downlink: Bitrate {
+                    unit: *dl_unit,
+                    value: u16::from_be_bytes([*dl_1, *dl_2]),
+                },
+                uplink: Bitrate {
+                    unit: *ul_unit,
+                    value: u16::from_be_bytes([*ul_1, *ul_2]),
+                },
+            }),
+            _ => Err(DecodeError::InvalidIe("session AMBR")),
+        }
+    }
+}
+
+/// A bit rate of the session AMBR: `value` times the `unit`, 1 Kbps
+/// multiplied by 4 for each unit above 1, e.g. 1 Gbps (0x0b) is 1048576 Kbps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Bitrate {
+    pub unit: u8,
+    pub value: u16,
+}
+
+impl Bitrate {
+    const MAXIMUM_UNIT: u8 = 0x19;
+
+    /// The rate in the largest unit it is a multiple of, or else rounded
+    /// down to the smallest unit it fits in.
+    pub fn from_kbps(kbps: u64) -> Bitrate {
+        let kbps_per_unit = |unit: u8| 4u64.pow(u32::from(unit) - 1);
+        let in_unit = |unit: u8| kbps / kbps_per_unit(unit);
+        let unit = (1..=Self::MAXIMUM_UNIT)
+            .rev()
+            .find(|unit| {
+                in_unit(*unit) * kbps_per_unit(*unit) == kbps
+                    && in_unit(*unit) <= u64::from(u16::MAX)
+            })
+            .or_else(|| (1..=Self::MAXIMUM_UNIT).find(|unit| in_unit(*unit) <= u64::from(u16::MAX)))
+            .unwrap_or(Self::MAXIMUM_UNIT);
+        Bitrate {
+            unit,
+            value: in_unit(unit).min(u64::from(u16::MAX)) as u16,
+        }
+    }
+
+    pub fn kbps(&self) -> u64 {
+        match self.unit {
+            0 => 0,
+            unit => u64::from(self.value)
+                .saturating_mul(4u64.saturating_pow(u32::from(unit.min(Self::MAXIMUM_UNIT)) - 1)),
+        }
+    }
+}
+
+impl std::fmt::Display for Bitrate {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} Kbps", self.kbps())
+    }
+}
+
+/// Parses the bit rates of TS 29.571, e.g. `1048576 Kbps` or `1.5 Mbps`.
+impl std::str::FromStr for Bitrate {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("Value not valid: {}", s);
+        let (value, unit) = s.trim().split_once(' ').ok_or_else(invalid)?;
+        let value = value.parse::<f64>().map_err(|_| invalid())?;
+        let kbps = match unit {
+            "bps" => value / 1000.0,
+            "Kbps" => value,
+            "Mbps" => value * 1e3,
+            "Gbps" => value * 1e6,
+            "Tbps" => value * 1e9,
+            _ => return Err(invalid()),
+        };
+        Ok(Bitrate::from_kbps(kbps.round() as u64))
+    }
+}
+
+/// A QoS rule (clause 9.11.4.13).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QosRule {
+    /// QoS rule identifier, 1 to 255
+    pub identifier: u8,
+    pub operation: RuleOperation,
+    /// Whether this is the default QoS rule of the session
+    pub default: bool,
+    /// Only the identifiers are sent to delete packet filters
+    pub packet_filters: Vec<PacketFilter>,
+    /// QoS rule precedence, absent when the rule is deleted
+    pub precedence: Option<u8>,
+    pub segregation: bool,
+    /// QoS flow identifier, absent when the rule is deleted
+    pub qfi: Option<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuleOperation {
+    Create = 1,
+    Delete = 2,
+    AddPacketFilters = 3,
+    ReplacePacketFilters = 4,
+    DeletePacketFilters = 5,
+    ModifyWithoutPacketFilters = 6,
+}
+
+/// A packet filter of a QoS rule.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PacketFilter {
+    /// Packet filter identifier, 0 to 15
+    pub identifier: u8,
+    pub direction: PacketFilterDirection,
+    /// The packet filter components, as encoded in the rule
+    pub components: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PacketFilterDirection {
+    Downlink = 1,
+    Uplink = 2,
+    Bidirectional = 3,
+}
+
+impl PacketFilter {
+    /// Packet filter component type of a filter matching all the packets
+    pub const MATCH_ALL: u8 = 0x01;
+
+    /// A filter matching all the packets in both directions, the one of the
+    /// default QoS rule.
+    pub fn match_all(identifier: u8) -> PacketFilter {
+        PacketFilter {
+            identifier,
+            direction: PacketFilterDirection::Bidirectional,
+            components: vec![PacketFilter::MATCH_ALL],
+        }
+    }
+}
+
+impl QosRule {
+    fn list_to_bytes(rules: &[QosRule]) -> Vec<u8> {
+        let mut bytes = vec![];
+        for rule in rules {
+            let mut contents = vec![
+                (rule.operation as u8) << 5
+                    | u8::from(rule.default) << 4
+                    | rule.packet_filters.len() as u8 & 0x0f,
+            ];
+            for filter in &rule.packet_filters {
+                if rule.operation == RuleOperation::DeletePacketFilters {
+                    contents.push(filter.identifier & 0x0f);
+                } else {
+                    contents.push((filter.direction as u8) << 4 | filter.identifier & 0x0f);
+                    lv(&mut contents, &filter.components);
+                }
+            }
+            if let Some(precedence) = rule.precedence {
+                contents.push(precedence);
+            }
+            if let Some(qfi) = rule.qfi {
+                contents.push(u8::from(rule.segregation) << 6 | qfi & 0x3f);
+            }
+            bytes.push(rule.identifier);
+            lve(&mut bytes, &contents);
+        }
+        bytes
+    }
+
+    fn list_from_bytes(bytes: &[u8]) -> Result<Vec<QosRule>, DecodeError> {
+        let invalid = DecodeError::InvalidIe("QoS rules");
+        let mut reader = Reader::new(bytes);
+        let mut rules = vec![];
+        while !reader.is_empty() {
+            let identifier = reader.u8()?;
+            let mut contents = Reader::new(reader.lve()?);
+            let octet = contents.u8()?;
+            let operation = match octet >> 5 {
+                1 => RuleOperation::Create,
+                2 => RuleOperation::Delete,
+                3 => RuleOperation::AddPacketFilters,
+                4 => RuleOperation::ReplacePacketFilters,
+                5 => RuleOperation::DeletePacketFilters,
+                6 => RuleOperation::ModifyWithoutPacketFilters,
+                _ => return Err(invalid),
+            };
+            let mut packet_filters = vec![];
+            for _ in 0..octet & 0x0f {
+                let octet = contents.u8()?;
+                let filter = if operation == RuleOperation::DeletePacketFilters {
+                    PacketFilter {
+                        identifier: octet & 0x0f,
+                        direction: PacketFilterDirection::Bidirectional,
+                        components: vec![],
+                    }
+                } else {
+                    PacketFilter {
+                        identifier: octet & 0x0f,
+                        direction: match octet >> 4 & 0x03 {
+                            1 => PacketFilterDirection::Downlink,
+                            2 => PacketFilterDirection::Uplink,
+                            3 => PacketFilterDirection::Bidirectional,
+                            _ => return Err(invalid),
+                        },
+                        components: contents.lv()?.to_vec(),
+                    }
+                };
+                packet_filters.push(filter);
+            }
+            let precedence = contents.optional_u8();
+            let qfi = contents.optional_u8();
+            rules.push(QosRule {
+                identifier,
+                operation,
+                default: octet & 0x10 != 0,
+                packet_filters,
+                precedence,
+                segregation: qfi.is_some_and(|qfi| qfi & 0x40 != 0),
+                qfi: qfi.map(|qfi| qfi & 0x3f),
+            });
+        }
+        Ok(rules)
+    }
+}
+
+/// A QoS flow description (clause 9.11.4.12).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QosFlowDescription {
+    /// QoS flow identifier, 1 to 63
+    pub qfi: u8,
+    pub operation: FlowOperation,
+    /// When modifying the flow, whether the parameters replace all the
+    /// previous ones or extend them
+    pub e_bit: bool,
+    pub parameters: Vec<QosFlowParameter>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FlowOperation {
+    Create = 1,
+    Delete = 2,
+    Modify = 3,
+}
+
+/// A parameter of a QoS flow description, e.g. its 5QI.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QosFlowParameter {
+    pub identifier: u8,
+    pub contents: Vec<u8>,
+}
+
+impl QosFlowParameter {
+    pub const FIVE_QI: u8 = 0x01;
+    pub const GFBR_UPLINK: u8 = 0x02;
+    pub const GFBR_DOWNLINK: u8 = 0x03;
+    pub const MFBR_UPLINK: u8 = 0x04;
+    pub const MFBR_DOWNLINK: u8 = 0x05;
+    pub const AVERAGING_WINDOW: u8 = 0x06;
+    pub const EPS_BEARER_IDENTITY: u8 = 0x07;
+}
+
+impl QosFlowDescription {
+    /// A flow of a standardized 5QI, which defines all its QoS.
+    pub fn create(qfi: u8, five_qi: u8) -> QosFlowDescription {
+        QosFlowDescription {
+            qfi,
+            operation: FlowOperation::Create,
+            e_bit: true,
+            parameters: vec![QosFlowParameter {
+                identifier: QosFlowParameter::FIVE_QI,
+                contents: vec![five_qi],
+            }],
+        }
+    }
+
+    pub fn five_qi(&self) -> Option<u8> {
+        self.parameters
+            .iter()
+            .find(|parameter| parameter.identifier == QosFlowParameter::FIVE_QI)
+            .and_then(|parameter| parameter.contents.first().copied())
+    }
+
+    fn list_to_bytes(descriptions: &[QosFlowDescription]) -> Vec<u8> {
+        let mut bytes = vec![];
+        for description in descriptions {
+            bytes.push(description.qfi & 0x3f);
+            bytes.push((description.operation as u8) << 5);
+            bytes
+                .push(u8::from(description.e_bit) << 6 | description.parameters.len() as u8 & 0x3f);
+            for parameter in &description.parameters {
+                bytes.push(parameter.identifier);
+                lv(&mut bytes, &parameter.contents);
+            }
+        }
+        bytes
+    }
+
+    fn list_from_bytes(bytes: &[u8]) -> Result<Vec<QosFlowDescription>, DecodeError> {
+        let mut reader = Reader::new(bytes);
+        let mut descriptions = vec![];
+        while !reader.is_empty() {
+            let qfi = reader.u8()? & 0x3f;
+            let operation = match reader.u8()? >> 5 {
+                1 => FlowOperation::Create,
+                2 => FlowOperation::Delete,
+                3 => FlowOperation::Modify,
+                _ => return Err(DecodeError::InvalidIe("QoS flow descriptions")),
+            };
+            let octet = reader.u8()?;
+            let mut parameters = vec![];
+            for _ in 0..octet & 0x3f {
+                parameters.push(QosFlowParameter {
+                    identifier: reader.u8()?,
+                    contents: reader.lv()?.to_vec(),
+                });
+            }
+            descriptions.push(QosFlowDescription {
+                qfi,
+                operation,
+                e_bit: octet & 0x40 != 0,
+                parameters,
+            });
+        }
+        Ok(descriptions)
+    }
+}
+
+/// Extended protocol configuration options (clause 9.11.4.6), with the
+/// containers of the configuration protocol of TS 24.008 clause 10.5.6.3.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExtendedPco {
+    pub containers: Vec<PcoContainer>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PcoContainer {
+    pub identifier: u16,
+    pub contents: Vec<u8>,
+}
+
+impl ExtendedPco {
+    pub const P_CSCF_IPV6_ADDRESS: u16 = 0x0001;
+    pub const DNS_SERVER_IPV6_ADDRESS: u16 = 0x0003;
+    pub const IP_ADDRESS_ALLOCATION_VIA_NAS_SIGNALLING: u16 = 0x000a;
+    pub const P_CSCF_IPV4_ADDRESS: u16 = 0x000c;
+    /// A request of the UE when empty
+    pub const DNS_SERVER_IPV4_ADDRESS: u16 = 0x000d;
+    pub const IPV4_LINK_MTU: u16 = 0x0010;
+
+    /// Whether the options have a container, e.g. a request of the UE.
+    pub fn contains(&self, identifier: u16) -> bool {
+        self.containers
+            .iter()
+            .any(|container| container.identifier == identifier)
+    }
+
+    pub fn push(&mut self, identifier: u16, contents: Vec<u8>) {
+        self.containers.push(PcoContainer {
+            identifier,
+            contents,
+        });
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        // Extension bit, configuration protocol 0
+        let mut bytes = vec![0x80];
+        for container in &self.containers {
+            bytes.extend_from_slice(&container.identifier.to_be_bytes());
+            lv(&mut bytes, &container.contents);
+        }
+        bytes
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Result<ExtendedPco, DecodeError> {
+        let mut reader = Reader::new(bytes);
+        reader.u8()?;
+        let mut pco = ExtendedPco::default();
+        while !reader.is_empty() {
+            let identifier = reader.u16()?;
+            pco.push(identifier, reader.lv()?.to_vec());
+        }
+        Ok(pco)
+    }
+}
+
+/// A DNN as the APN labels of TS 23.003 clause 9.1, e.g. `internet`.
+fn dnn_to_bytes(dnn: &str) -> Vec<u8> {
+    let mut bytes = vec![];
+    for label in dnn.split('.') {
+        lv(&mut bytes, label.as_bytes());
+    }
+    bytes
+}
+
+fn dnn_from_bytes(bytes: &[u8]) -> Result<String, DecodeError> {
+    let mut reader = Reader::new(bytes);
+    let mut labels = vec![];
+    while !reader.is_empty() {
+        let label = std::str::from_utf8(reader.lv()?).map_err(|_| DecodeError::InvalidIe("DNN"))?;
+        labels.push(label);
+    }
+    Ok(labels.join("."))
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The message ends within an IE
+    Truncated,
+    /// Not a 5GSM message
+    InvalidEpd(u8),
+    UnknownMessageType(u8),
+    /// An IE has a value the SMF does not know
+    InvalidIe(&'static str),
+}
+
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecodeError::Truncated => write!(f, "Truncated 5GSM message"),
+            DecodeError::InvalidEpd(epd) => write!(f, "Not a 5GSM message: EPD {:#04x}", epd),
+            DecodeError::UnknownMessageType(message_type) => {
+                write!(f, "Unknown 5GSM message type {:#04x}", message_type)
+            }
+            DecodeError::InvalidIe(ie) => write!(f, "Invalid {}", ie),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl Message {
+    pub fn decode(bytes: &[u8]) -> Result<Message, DecodeError> {
+        let mut reader = Reader::new(bytes);
+        let epd = reader.u8()?;
+        if epd != EPD_5GSM {
+            return Err(DecodeError::InvalidEpd(epd));
+        }
+        let pdu_session_id = reader.u8()?;
+        let pti = reader.u8()?;
+        let message_type = reader.u8()?;
+        let r = &mut reader;
+        let body = match message_type {
+            ESTABLISHMENT_REQUEST => Body::EstablishmentRequest(EstablishmentRequest::decode(r)?),
+            ESTABLISHMENT_ACCEPT => Body::EstablishmentAccept(EstablishmentAccept::decode(r)?),
+            ESTABLISHMENT_REJECT => Body::EstablishmentReject(EstablishmentReject::decode(r)?),
+            MODIFICATION_REQUEST => Body::ModificationRequest(ModificationRequest::decode(r)?),
+            MODIFICATION_REJECT => Body::ModificationReject(ModificationReject::decode(r)?),
+            MODIFICATION_COMMAND => Body::ModificationCommand(ModificationCommand::decode(r)?),
+            MODIFICATION_COMPLETE => Body::ModificationComplete(ModificationComplete::decode(r)?),
+            MODIFICATION_COMMAND_REJECT => {
+                Body::ModificationCommandReject(ModificationCommandReject::decode(r)?)
+            }
+            RELEASE_REQUEST => Body::ReleaseRequest(ReleaseRequest::decode(r)?),
+            RELEASE_REJECT => Body::ReleaseReject(ReleaseReject::decode(r)?),
+            RELEASE_COMMAND => Body::ReleaseCommand(ReleaseCommand::decode(r)?),
+            RELEASE_COMPLETE => Body::ReleaseComplete(ReleaseComplete::decode(r)?),
+            _ => return Err(DecodeError::UnknownMessageType(message_type)),
+        };
+        Ok(Message {
+            pdu_session_id,
+            pti,
+            body,
+        })
+    }
+
+    pub fn encode(&self) -> Vec<u8> {
+        let mut bytes = vec![EPD_5GSM, self.pdu_session_id, self.pti];
+        let b = &mut bytes;
+        match &self.body {
+            Body::EstablishmentRequest(m) => m.encode(b),
+            Body::EstablishmentAccept(m) => m.encode(b),
+            Body::EstablishmentReject(m) => m.encode(b),
+            Body::ModificationRequest(m) => m.encode(b),
+            Body::ModificationReject(m) => m.encode(b),
+            Body::ModificationCommand(m) => m.encode(b),
+            Body::ModificationComplete(m) => m.encode(b),
+            Body::ModificationCommandReject(m) => m.encode(b),
+            Body::ReleaseRequest(m) => m.encode(b),
+            Body::ReleaseReject(m) => m.encode(b),
+            Body::ReleaseCommand(m) => m.encode(b),
+            Body::ReleaseComplete(m) => m.encode(b),
+        }
+        bytes
+    }
+
+    /// The PDU session establishment request of an IPv4 session in SSC mode
+    /// 1, asking for the DNS servers, as the benchmark clients send it.
+    pub fn ipv4_establishment_request(pdu_session_id: u8, pti: u8) -> Message {
+        let mut extended_pco = ExtendedPco::default();
+        extended_pco.push(
+            ExtendedPco::IP_ADDRESS_ALLOCATION_VIA_NAS_SIGNALLING,
+            vec![],
+        );
+        extended_pco.push(ExtendedPco::DNS_SERVER_IPV4_ADDRESS, vec![]);
+        Message {
+            pdu_session_id,
+            pti,
+            body: Body::EstablishmentRequest(EstablishmentRequest {
+                integrity_protection_maximum_data_rate:
+                    IntegrityProtectionMaximumDataRate::FULL_DATA_RATE,
+                pdu_session_type: Some(PduSessionType::Ipv4),
+                ssc_mode: Some(SscMode::Mode1),
+                capability: Some(vec![0x00]),
+                extended_pco: Some(extended_pco),
+            }),
+        }
+    }
+
+    /// The PDU session establishment reject of a request of the UE.
+    pub fn establishment_reject(pdu_session_id: u8, pti: u8, cause: Cause) -> Message {
+        Message {
+            pdu_session_id,
+            pti,
+            body: Body::EstablishmentReject(EstablishmentReject {
+                cause,
+                back_off_timer: None,
+                extended_pco: None,
+            }),
+        }
+    }
+}
+
+impl EstablishmentRequest {
+    fn decode(reader: &mut Reader) -> Result<EstablishmentRequest, DecodeError> {
+        let integrity_protection_maximum_data_rate =
+            IntegrityProtectionMaximumDataRate::from_bytes(reader.take(2)?)?;
+        let ies = Ies::decode(reader)?;
+        Ok(EstablishmentRequest {
+            integrity_protection_maximum_data_rate,
+            pdu_session_type: ies
+                .half(PDU_SESSION_TYPE)
+                .map(PduSessionType::from_u8)
+                .transpose()?,
+            ssc_mode: ies.half(SSC_MODE).map(SscMode::from_u8).transpose()?,
+            capability: ies.get(CAPABILITY).map(<[u8]>::to_vec),
+            extended_pco: ies.extended_pco()?,
+        })
+    }
+
+    fn encode(&self, bytes: &mut Vec<u8>) {
+        bytes.push(ESTABLISHMENT_REQUEST);
+        bytes.push(self.integrity_protection_maximum_data_rate.uplink);
+        bytes.push(self.integrity_protection_maximum_data_rate.downlink);
+        if let Some(pdu_session_type) = self.pdu_session_type {
+            bytes.push(PDU_SESSION_TYPE | pdu_session_type as u8);
+        }
+        if let Some(ssc_mode) = self.ssc_mode {
+            bytes.push(SSC_MODE | ssc_mode as u8);
+        }
+        if let Some(capability) = &self.capability {
+            tlv(bytes, CAPABILITY, capability);
+        }
+        encode_extended_pco(bytes, &self.extended_pco);
+    }
+}
+
+impl EstablishmentAccept {
+    fn decode(reader: &mut Reader) -> Result<EstablishmentAccept, DecodeError> {
+        // Selected PDU session type in the lower half octet, selected SSC
+        // mode in the upper one
+        let octet = reader.u8()?;
+        let authorized_qos_rules = QosRule::list_from_bytes(reader.lve()?)?;
+        let session_ambr = SessionAmbr::from_bytes(reader.lv()?)?;
+        let ies = Ies::decode(reader)?;
+        Ok(EstablishmentAccept {
+            pdu_session_type: PduSessionType::from_u8(octet & 0x0f)?,
+            ssc_mode: SscMode::from_u8(octet >> 4 & 0x07)?,
+            authorized_qos_rules,
+            session_ambr,
+            cause: ies.cause(),
+            pdu_address: ies
+                .get(PDU_ADDRESS)
+                .map(PduAddress::from_bytes)
+                .transpose()?,
+            s_nssai: ies.get(S_NSSAI).map(Snssai::from_bytes).transpose()?,
+            authorized_qos_flow_descriptions: ies.qos_flow_descriptions()?,
+            extended_pco: ies.extended_pco()?,
+            dnn: ies.get(DNN).map(dnn_from_bytes).transpose()?,
+        })
+    }
+
+    fn encode(&self, bytes: &mut Vec<u8>) {
+        bytes.push(ESTABLISHMENT_ACCEPT);
+        bytes.push((self.ssc_mode as u8) << 4 | self.pdu_session_type as u8);
+        lve(bytes, &QosRule::list_to_bytes(&self.authorized_qos_rules));
+        lv(bytes, &self.session_ambr.to_bytes());
+        encode_cause(bytes, self.cause);
+        if let Some(pdu_address) = self.pdu_address {
+            tlv(bytes, PDU_ADDRESS, &pdu_address.to_bytes());
+        }
+        if let Some(s_nssai) = self.s_nssai {
+            tlv(bytes, S_NSSAI, &s_nssai.to_bytes());
+        }
+        encode_qos_flow_descriptions(bytes, &self.authorized_qos_flow_descriptions);
+        encode_extended_pco(bytes, &self.extended_pco);
+        if let Some(dnn) = &self.dnn {
+            tlv(bytes, DNN, &dnn_to_bytes(dnn));
+        }
+    }
+}
+
+impl EstablishmentReject {
+    fn decode(reader: &mut Reader) -> Result<EstablishmentReject, DecodeError> {
+        let cause = Cause(reader.u8()?);
+        let ies = Ies::decode(reader)?;
+        Ok(EstablishmentReject {
+            cause,
+            back_off_timer: ies.back_off_timer(),
+            extended_pco: ies.extended_pco()?,
+        })
+    }
+
+    fn encode(&self, bytes: &mut Vec<u8>) {
+        bytes.push(ESTABLISHMENT_REJECT);
+        bytes.push(self.cause.0);
+        encode_back_off_timer(bytes, self.back_off_timer);
+        encode_extended_pco(bytes, &self.extended_pco);
+    }
+}
+
+impl ModificationRequest {
+    fn decode(reader: &mut Reader) -> Result<ModificationRequest, DecodeError> {
+        let ies = Ies::decode(reader)?;
+        Ok(ModificationRequest {
+            capability: ies.get(CAPABILITY).map(<[u8]>::to_vec),
+            cause: ies.cause(),
+            integrity_protection_maximum_data_rate: ies
+                .get(INTEGRITY_PROTECTION_MAXIMUM_DATA_RATE)
+                .map(IntegrityProtectionMaximumDataRate::from_bytes)
+                .transpose()?,
+            requested_qos_rules: ies.qos_rules()?,
+            requested_qos_flow_descriptions: ies.qos_flow_descriptions()?,
+            extended_pco: ies.extended_pco()?,
+        })
+    }
+
+    fn encode(&self, bytes: &mut Vec<u8>) {
+        bytes.push(MODIFICATION_REQUEST);
+        if let Some(capability) = &self.capability {
+            tlv(bytes, CAPABILITY, capability);
+        }
+        encode_cause(bytes, self.cause);
+        if let Some(rate) = self.integrity_protection_maximum_data_rate {
+            bytes.extend_from_slice(&[
+                INTEGRITY_PROTECTION_MAXIMUM_DATA_RATE,
+                rate.uplink,
+                rate.downlink,
+            ]);
+        }
+        encode_qos_rules(bytes, &self.requested_qos_rules);
+        encode_qos_flow_descriptions(bytes, &self.requested_qos_flow_descriptions);
+        encode_extended_pco(bytes, &self.extended_pco);
+    }
+}
+
+impl ModificationReject {
+    fn decode(reader: &mut Reader) -> Result<ModificationReject, DecodeError> {
+        let cause = Cause(reader.u8()?);
+        let ies = Ies::decode(reader)?;
+        Ok(ModificationReject {
+            cause,
+            back_off_timer: ies.back_off_timer(),
+            extended_pco: ies.extended_pco()?,
+        })
+    }
+
+    fn encode(&self, bytes: &mut Vec<u8>) {
+        bytes.push(MODIFICATION_REJECT);
+        bytes.push(self.cause.0);
+        encode_back_off_timer(bytes, self.back_off_timer);
+        encode_extended_pco(bytes, &self.extended_pco);
+    }
+}
+
+impl ModificationCommand {
+    fn decode(reader: &mut Reader) -> Result<ModificationCommand, DecodeError> {
+        let ies = Ies::decode(reader)?;
+        Ok(ModificationCommand {
+            cause: ies.cause(),
+            session_ambr: ies
+                .get(SESSION_AMBR)
+                .map(SessionAmbr::from_bytes)
+                .transpose()?,
+            authorized_qos_rules: ies.qos_rules()?,
+            authorized_qos_flow_descriptions: ies.qos_flow_descriptions()?,
+            extended_pco: ies.extended_pco()?,
+        })
+    }
+
+    fn encode(&self, bytes: &mut Vec<u8>) {
+        bytes.push(MODIFICATION_COMMAND);
+        encode_cause(bytes, self.cause);
+        if let Some(session_ambr) = self.session_ambr {
+            tlv(bytes, SESSION_AMBR, &session_ambr.to_bytes());
+        }
+        encode_qos_rules(bytes, &self.authorized_qos_rules);
+        encode_qos_flow_descriptions(bytes, &self.authorized_qos_flow_descriptions);
+        encode_extended_pco(bytes, &self.extended_pco);
+    }
+}
+
+impl ModificationComplete {
+    fn decode(reader: &mut Reader) -> Result<ModificationComplete, DecodeError> {
+        let ies = Ies::decode(reader)?;
+        Ok(ModificationComplete {
+            extended_pco: ies.extended_pco()?,
+        })
+    }
+
+    fn encode(&self, bytes: &mut Vec<u8>) {
+        bytes.push(MODIFICATION_COMPLETE);
+        encode_extended_pco(bytes, &self.extended_pco);
+    }
+}
+
+impl ModificationCommandReject {
+    fn decode(reader: &mut Reader) -> Result<ModificationCommandReject, DecodeError> {
+        let cause = Cause(reader.u8()?);
+        let ies = Ies::decode(reader)?;
+        Ok(ModificationCommandReject {
+            cause,
+            extended_pco: ies.extended_pco()?,
+        })
+    }
+
+    fn encode(&self, bytes: &mut Vec<u8>) {
+        bytes.push(MODIFICATION_COMMAND_REJECT);
+        bytes.push(self.cause.0);
+        encode_extended_pco(bytes, &self.extended_pco);
+    }
+}
+
+impl ReleaseRequest {
+    fn decode(reader: &mut Reader) -> Result<ReleaseRequest, DecodeError> {
+        let ies = Ies::decode(reader)?;
+        Ok(ReleaseRequest {
+            cause: ies.cause(),
+            extended_pco: ies.extended_pco()?,
+        })
+    }
+
+    fn encode(&self, bytes: &mut Vec<u8>) {
+        bytes.push(RELEASE_REQUEST);
+        encode_cause(bytes, self.cause);
+        encode_extended_pco(bytes, &self.extended_pco);
+    }
+}
+
+impl ReleaseReject {
+    fn decode(reader: &mut Reader) -> Result<ReleaseReject, DecodeError> {
+        let cause = Cause(reader.u8()?);
+        let ies = Ies::decode(reader)?;
+        Ok(ReleaseReject {
+            cause,
+            extended_pco: ies.extended_pco()?,
+        })
+    }
+
+    fn encode(&self, bytes: &mut Vec<u8>) {
+        bytes.push(RELEASE_REJECT);
+        bytes.push(self.cause.0);
+        encode_extended_pco(bytes, &self.extended_pco);
+    }
+}
+
+impl ReleaseCommand {
+    fn decode(reader: &mut Reader) -> Result<ReleaseCommand, DecodeError> {
+        let cause = Cause(reader.u8()?);
+        let ies = Ies::decode(reader)?;
+        Ok(ReleaseCommand {
+            cause,
+            back_off_timer: ies.back_off_timer(),
+            extended_pco: ies.extended_pco()?,
+        })
+    }
+
+    fn encode(&self, bytes: &mut Vec<u8>) {
+        bytes.push(RELEASE_COMMAND);
+        bytes.push(self.cause.0);
+        encode_back_off_timer(bytes, self.back_off_timer);
+        encode_extended_pco(bytes, &self.extended_pco);
+    }
+}
+
+impl ReleaseComplete {
+    fn decode(reader: &mut Reader) -> Result<ReleaseComplete, DecodeError> {
+        let ies = Ies::decode(reader)?;
+        Ok(ReleaseComplete {
+            cause: ies.cause(),
+            extended_pco: ies.extended_pco()?,
+        })
+    }
+
+    fn encode(&self, bytes: &mut Vec<u8>) {
+        bytes.push(RELEASE_COMPLETE);
+        encode_cause(bytes, self.cause);
+        encode_extended_pco(bytes, &self.extended_pco);
+    }
+}
+
+fn encode_cause(bytes: &mut Vec<u8>, cause: Option<Cause>) {
+    if let Some(cause) = cause {
+        bytes.extend_from_slice(&[CAUSE, cause.0]);
+    }
+}
+
+fn encode_back_off_timer(bytes: &mut Vec<u8>, back_off_timer: Option<u8>) {
+    if let Some(back_off_timer) = back_off_timer {
+        tlv(bytes, BACK_OFF_TIMER, &[back_off_timer]);
+    }
+}
+
+fn encode_qos_rules(bytes: &mut Vec<u8>, rules: &[QosRule]) {
+    if !rules.is_empty() {
+        tlve(bytes, QOS_RULES, &QosRule::list_to_bytes(rules));
+    }
+}
+
+fn encode_qos_flow_descriptions(bytes: &mut Vec<u8>, descriptions: &[QosFlowDescription]) {
+    if !descriptions.is_empty() {
+        tlve(
+            bytes,
+            QOS_FLOW_DESCRIPTIONS,
+            &QosFlowDescription::list_to_bytes(descriptions),
+        );
+    }
+}
+
+fn encode_extended_pco(bytes: &mut Vec<u8>, extended_pco: &Option<ExtendedPco>) {
+    if let Some(extended_pco) = extended_pco {
+        tlve(bytes, EXTENDED_PCO, &extended_pco.to_bytes());
+    }
+}
+
+fn lv(bytes: &mut Vec<u8>, value: &[u8]) {
+    bytes.push(value.len() as u8);
+    bytes.extend_from_slice(value);
+}
+
+fn lve(bytes: &mut Vec<u8>, value: &[u8]) {
+    bytes.extend_from_slice(&(value.len() as u16).to_be_bytes());
+    bytes.extend_from_slice(value);
+}
+
+fn tlv(bytes: &mut Vec<u8>, iei: u8, value: &[u8]) {
+    bytes.push(iei);
+    lv(bytes, value);
+}
+
+fn tlve(bytes: &mut Vec<u8>, iei: u8, value: &[u8]) {
+    bytes.push(iei);
+    lve(bytes, value);
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Reader<'a> {
+        Reader { bytes }
+    }
+
+    fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        if self.bytes.len() < len {
+            return Err(DecodeError::Truncated);
+        }
+        let (value, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(value)
+    }
+
+    fn u8(&mut self) -> Result<u8, DecodeError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, DecodeError> {
+        let value = self.take(2)?;
+        Ok(u16::from_be_bytes([value[0], value[1]]))
+    }
+
+    fn optional_u8(&mut self) -> Option<u8> {
+        self.u8().ok()
+    }
+
+    fn lv(&mut self) -> Result<&'a [u8], DecodeError> {
+        let len = self.u8()?;
+        self.take(usize::from(len))
+    }
+
+    fn lve(&mut self) -> Result<&'a [u8], DecodeError> {
+        let len = self.u16()?;
+        self.take(usize::from(len))
+    }
+}
+
+/// The optional IEs of a message, by IEI.
+struct Ies<'a>(Vec<(u8, &'a [u8])>);
+
+impl<'a> Ies<'a> {
+    /// The format of an IE follows from its IEI (TS 24.501 clause 11.2.4):
+    /// a half octet IEI for type 1, 0x7- for TLV-E, and TLV otherwise but
+    /// for the few TV IEs of the 5GSM messages.
+    fn decode(reader: &mut Reader<'a>) -> Result<Ies<'a>, DecodeError> {
+        let mut ies = vec![];
+        while !reader.is_empty() {
+            let bytes = reader.bytes;
+            let iei = reader.u8()?;
+            let ie = match iei {
+                0x80..=0xff => (iei & 0xf0, &bytes[..1]),
+                0x70..=0x7f => (iei, reader.lve()?),
+                CAUSE | RQ_TIMER => (iei, reader.take(1)?),
+                INTEGRITY_PROTECTION_MAXIMUM_DATA_RATE
+                | MAXIMUM_NUMBER_OF_SUPPORTED_PACKET_FILTERS => (iei, reader.take(2)?),
+                _ => (iei, reader.lv()?),
+            };
+            ies.push(ie);
+        }
+        Ok(Ies(ies))
+    }
+
+    fn get(&self, iei: u8) -> Option<&'a [u8]> {
+        self.0
+            .iter()
+            .find(|(i, _)| *i == iei)
+            .map(|(_, value)| *value)
+    }
+
+    /// The value of a type 1 IE.
+    fn half(&self, iei: u8) -> Option<u8> {
+        self.get(iei).map(|value| value[0] & 0x0f)
+    }
+
+    fn cause(&self) -> Option<Cause> {
+        self.get(CAUSE).map(|value| Cause(value[0]))
+    }
+
+    fn back_off_timer(&self) -> Option<u8> {
+        self.get(BACK_OFF_TIMER)
+            .and_then(|value| value.first().copied())
+    }
+
+    fn qos_rules(&self) -> Result<Vec<QosRule>, DecodeError> {
+        self.get(QOS_RULES)
+            .map(QosRule::list_from_bytes)
+            .unwrap_or_else(|| Ok(vec![]))
+    }
+
+    fn qos_flow_descriptions(&self) -> Result<Vec<QosFlowDescription>, DecodeError> {
+        self.get(QOS_FLOW_DESCRIPTIONS)
+            .map(QosFlowDescription::list_from_bytes)
+            .unwrap_or_else(|| Ok(vec![]))
+    }
+
+    fn extended_pco(&self) -> Result<Option<ExtendedPco>, DecodeError> {
+        self.get(EXTENDED_PCO)
+            .map(ExtendedPco::from_bytes)
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The request of the benchmark clients
+    const ESTABLISHMENT_REQUEST_BYTES: [u8; 21] = [
+        0x2e, 0x01, 0x01, 0xc1, 0xff, 0xff, 0x91, 0xa1, 0x28, 0x01, 0x00, 0x7b, 0x00, 0x07, 0x80,
+        0x00, 0x0a, 0x00, 0x00, 0x0d, 0x00,
+    ];
+
+    /// The accept the SMFs answered it with
+    const ESTABLISHMENT_ACCEPT_BYTES: [u8; 71] = [
+        0x2e, 0x01, 0x01, 0xc2, 0x11, 0x00, 0x09, 0x01, 0x00, 0x06, 0x31, 0x31, 0x01, 0x01, 0xff,
+        0x01, 0x06, 0x0b, 0x00, 0x01, 0x0b, 0x00, 0x01, 0x29, 0x05, 0x01, 0xc0, 0xa8, 0x64, 0x05,
+        0x22, 0x01, 0x01, 0x79, 0x00, 0x06, 0x01, 0x20, 0x41, 0x01, 0x01, 0x09, 0x7b, 0x00, 0x0f,
+        0x80, 0x00, 0x0d, 0x04, 0x08, 0x08, 0x08, 0x08, 0x00, 0x0d, 0x04, 0x08, 0x08, 0x04, 0x04,
+        0x25, 0x09, 0x08, 0x69, 0x6e, 0x74, 0x65, 0x72, 0x6e, 0x65, 0x74,
+    ];
+
+    fn round_trip(message: Message) {
+        assert_eq!(Message::decode(&message.encode()), Ok(message));
+    }
+
+    #[test]
+    fn establishment_request() {
+        let mut extended_pco = ExtendedPco::default();
+        extended_pco.push(
+            ExtendedPco::IP_ADDRESS_ALLOCATION_VIA_NAS_SIGNALLING,
+            vec![],
+        );
+        extended_pco.push(ExtendedPco::DNS_SERVER_IPV4_ADDRESS, vec![]);
+        let request = Message {
+            pdu_session_id: 1,
+            pti: 1,
+            body: Body::EstablishmentRequest(EstablishmentRequest {
+                integrity_protection_maximum_data_rate:
+                    IntegrityProtectionMaximumDataRate::FULL_DATA_RATE,
+                pdu_session_type: Some(PduSessionType::Ipv4),
+                ssc_mode: Some(SscMode::Mode1),
+                capability: Some(vec![0x00]),
+                extended_pco: Some(extended_pco),
+            }),
+        };
+
+        assert_eq!(
+            Message::decode(&ESTABLISHMENT_REQUEST_BYTES),
+            Ok(request.clone())
+        );
+        assert_eq!(request.encode(), ESTABLISHMENT_REQUEST_BYTES);
+        assert_eq!(Message::ipv4_establishment_request(1, 1), request);
+    }
+
+    #[test]
+    fn establishment_accept() {
+        let mut extended_pco = ExtendedPco::default();
+        extended_pco.push(ExtendedPco::DNS_SERVER_IPV4_ADDRESS, vec![8, 8, 8, 8]);
+        extended_pco.push(ExtendedPco::DNS_SERVER_IPV4_ADDRESS, vec![8, 8, 4, 4]);
+        let accept = Message {
+            pdu_session_id: 1,
+            pti: 1,
+            body: Body::EstablishmentAccept(EstablishmentAccept {
+                pdu_session_type: PduSessionType::Ipv4,
+                ssc_mode: SscMode::Mode1,
+                authorized_qos_rules: vec![QosRule {
+                    identifier: 1,
+                    operation: RuleOperation::Create,
+                    default: true,
+                    packet_filters: vec![PacketFilter::match_all(1)],
+                    precedence: Some(255),
+                    segregation: false,
+                    qfi: Some(1),
+                }],
+                session_ambr: SessionAmbr {
+                    downlink: "1048576 Kbps".parse().unwrap(),
+                    uplink: "1048576 Kbps".parse().unwrap(),
+                },
+                cause: None,
+                pdu_address: Some(PduAddress::Ipv4(Ipv4Addr::new(192, 168, 100, 5))),
+                s_nssai: Some(Snssai {
+                    sst: 1,
+                    sd: None,
+                    mapped_hplmn_sst: None,
+                    mapped_hplmn_sd: None,
+                }),
+                authorized_qos_flow_descriptions: vec![QosFlowDescription::create(1, 9)],
+                extended_pco: Some(extended_pco),
+                dnn: Some("internet".to_string()),
+            }),
+        };
+
+        assert_eq!(
+            Message::decode(&ESTABLISHMENT_ACCEPT_BYTES),
+            Ok(accept.clone())
+        );
+        assert_eq!(accept.encode(), ESTABLISHMENT_ACCEPT_BYTES);
+    }
+
+    #[test]
+    fn establishment_reject() {
+        round_trip(Message::establishment_reject(
+            1,
+            1,
+            Cause::INSUFFICIENT_RESOURCES_FOR_SPECIFIC_SLICE_AND_DNN,
+        ));
+    }
+
+    #[test]
+    fn modification() {
+        round_trip(Message {
+            pdu_session_id: 5,
+            pti: 2,
+            body: Body::ModificationRequest(ModificationRequest {
+                cause: Some(Cause::REQUEST_REJECTED_UNSPECIFIED),
+                integrity_protection_maximum_data_rate: Some(
+                    IntegrityProtectionMaximumDataRate::FULL_DATA_RATE,
+                ),
+                requested_qos_rules: vec![QosRule {
+                    identifier: 1,
+                    operation: RuleOperation::DeletePacketFilters,
+                    default: false,
+                    packet_filters: vec![PacketFilter {
+                        identifier: 2,
+                        direction: PacketFilterDirection::Bidirectional,
+                        components: vec![],
+                    }],
+                    precedence: None,
+                    segregation: false,
+                    qfi: None,
+                }],
+                ..ModificationRequest::default()
+            }),
+        });
+        round_trip(Message {
+            pdu_session_id: 5,
+            pti: 2,
+            body: Body::ModificationCommand(ModificationCommand {
+                session_ambr: Some(SessionAmbr {
+                    downlink: Bitrate::from_kbps(100_000),
+                    uplink: Bitrate::from_kbps(50_000),
+                }),
+                authorized_qos_flow_descriptions: vec![QosFlowDescription::create(2, 5)],
+                ..ModificationCommand::default()
+            }),
+        });
+        round_trip(Message {
+            pdu_session_id: 5,
+            pti: 2,
+            body: Body::ModificationComplete(ModificationComplete::default()),
+        });
+        round_trip(Message {
+            pdu_session_id: 5,
+            pti: 2,
+            body: Body::ModificationReject(ModificationReject {
+                cause: Cause::INSUFFICIENT_RESOURCES,
+                back_off_timer: Some(0x21),
+                extended_pco: None,
+            }),
+        });
+    }
+
+    #[test]
+    fn release() {
+        round_trip(Message {
+            pdu_session_id: 1,
+            pti: 3,
+            body: Body::ReleaseRequest(ReleaseRequest {
+                cause: Some(Cause::REGULAR_DEACTIVATION),
+                extended_pco: None,
+            }),
+        });
+        round_trip(Message {
+            pdu_session_id: 1,
+            pti: 3,
+            body: Body::ReleaseCommand(ReleaseCommand {
+                cause: Cause::REGULAR_DEACTIVATION,
+                back_off_timer: None,
+                extended_pco: None,
+            }),
+        });
+        round_trip(Message {
+            pdu_session_id: 1,
+            pti: 3,
+            body: Body::ReleaseComplete(ReleaseComplete::default()),
+        });
+    }
+
+    #[test]
+    fn unknown_ies_are_skipped() {
+        // RQ timer, always-on PDU session indication and an unknown TLV
+        let mut bytes = ESTABLISHMENT_ACCEPT_BYTES.to_vec();
+        bytes.extend_from_slice(&[0x56, 0x01, 0x81, 0x3f, 0x02, 0xaa, 0xbb]);
+
+        assert_eq!(
+            Message::decode(&bytes),
+            Message::decode(&ESTABLISHMENT_ACCEPT_BYTES)
+        );
+        assert_eq!(
+            Message::decode(&ESTABLISHMENT_ACCEPT_BYTES[..40]),
+            Err(DecodeError::Truncated)
+        );
+    }
+
+    #[test]
+    fn bitrate() {
+        assert_eq!(
+            Bitrate::from_kbps(1_048_576),
+            Bitrate {
+                unit: 0x0b,
+                value: 1
+            }
+        );
+        assert_eq!(
+            "1 Gbps".parse(),
+            Ok(Bitrate {
+                unit: 0x04,
+                value: 15_625
+            })
+        );
+        assert_eq!("1.5 Mbps".parse::<Bitrate>().unwrap().kbps(), 1500);
+    }
+}
@@ -0,0 +1,992 @@
+//! NGAP SM transfer IEs, the N2 SM information the SMF and the NG-RAN
+//! exchange through the AMF (TS 38.413 clause 9.3.4).
+//!
+//! The transfer IEs are octet strings holding their own ASN.1 encoding,
+//! with the aligned variant of PER (ITU-T X.691), which the AMF forwards as
+//! is. Only the IEs of the transfers the SMF builds or reads are encoded and
+//! decoded: the other IEs of a protocol IE container, and the extensions of
+//! the types, are skipped when decoding.
+
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+use crate::{models, nas};
+
+// Protocol IE ids (TS 38.413 clause 9.4.7)
+const ID_PDU_SESSION_AGGREGATE_MAXIMUM_BIT_RATE: u16 = 130;
+const ID_PDU_SESSION_TYPE: u16 = 134;
+const ID_QOS_FLOW_ADD_OR_MODIFY_REQUEST_LIST: u16 = 135;
+const ID_QOS_FLOW_SETUP_REQUEST_LIST: u16 = 136;
+const ID_QOS_FLOW_TO_RELEASE_LIST: u16 = 137;
+const ID_UL_NGU_UP_TNL_INFORMATION: u16 = 139;
+
+/// Criticality of the protocol IEs the SMF sends: all of them are `reject`
+const CRITICALITY_REJECT: u64 = 0;
+
+/// PDU Session Resource Setup Request Transfer (clause 9.3.4.1), sent for
+/// the NG-RAN to set up the resources of the session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PduSessionResourceSetupRequestTransfer {
+    /// Only sent for the sessions with a non-GBR QoS flow
+    pub pdu_session_aggregate_maximum_bit_rate: Option<PduSessionAggregateMaximumBitRate>,
+    /// The N3 endpoint of the UPF
+    pub ul_ngu_up_tnl_information: GtpTunnel,
+    pub pdu_session_type: nas::PduSessionType,
+    pub qos_flow_setup_request_list: Vec<QosFlowSetupRequestItem>,
+}
+
+/// PDU Session Resource Setup Response Transfer (clause 9.3.4.2), with
+/// which the NG-RAN answers the setup.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PduSessionResourceSetupResponseTransfer {
+    /// The N3 endpoint of the NG-RAN and the QoS flows it set up
+    pub dl_qos_flow_per_tnl_information: QosFlowPerTnlInformation,
+}
+
+/// PDU Session Resource Modify Request Transfer (clause 9.3.4.3).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PduSessionResourceModifyRequestTransfer {
+    pub pdu_session_aggregate_maximum_bit_rate: Option<PduSessionAggregateMaximumBitRate>,
+    pub qos_flow_add_or_modify_request_list: Vec<QosFlowAddOrModifyRequestItem>,
+    pub qos_flow_to_release_list: Vec<QosFlowWithCause>,
+}
+
+/// PDU Session Resource Release Command Transfer (clause 9.3.4.12).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PduSessionResourceReleaseCommandTransfer {
+    pub cause: Cause,
+}
+
+/// PDU Session Aggregate Maximum Bit Rate (clause 9.3.1.102), in bits per
+/// second.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PduSessionAggregateMaximumBitRate {
+    pub downlink: u64,
+    pub uplink: u64,
+}
+
+impl PduSessionAggregateMaximumBitRate {
+    /// The session AMBR of TS 29.571, e.g. `1 Gbps`.
+    pub fn from_ambr(ambr: &models::Ambr) -> Option<PduSessionAggregateMaximumBitRate> {
+        let bps = |bitrate: &str| {
+            bitrate
+                .parse::<nas::Bitrate>()
+                .ok()
+                .map(|b| b.kbps() * 1000)
+        };
+        Some(PduSessionAggregateMaximumBitRate {
+            downlink: bps(&ambr.downlink)?,
+            uplink: bps(&ambr.uplink)?,
+        })
+    }
+}
+
+/// The GTP-U tunnel endpoint of a UP transport layer information (clause
+/// 9.3.2.2): the UPF or NG-RAN address, and the TEID it receives on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GtpTunnel {
+    #[serde(rename = "transportLayerAddress")]
+    pub transport_layer_address: IpAddr,
+    #[serde(rename = "gtpTeid")]
+    pub gtp_teid: u32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QosFlowSetupRequestItem {
+    pub qfi: u8,
+    pub qos_flow_level_qos_parameters: QosFlowLevelQosParameters,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QosFlowAddOrModifyRequestItem {
+    pub qfi: u8,
+    pub qos_flow_level_qos_parameters: Option<QosFlowLevelQosParameters>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QosFlowWithCause {
+    pub qfi: u8,
+    pub cause: Cause,
+}
+
+/// QoS Flow Level QoS Parameters (clause 9.3.1.12) of a non-GBR QoS flow
+/// with a standardized 5QI, the only ones the SMF sets up.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct QosFlowLevelQosParameters {
+    pub five_qi: u8,
+    pub allocation_and_retention_priority: AllocationAndRetentionPriority,
+}
+
+impl QosFlowLevelQosParameters {
+    /// The parameters of a QoS flow profile, with the lowest priority
+    /// when it has no ARP.
+    pub fn from_profile(profile: &models::QosFlowProfile) -> QosFlowLevelQosParameters {
+        let allocation_and_retention_priority = profile
+            .arp
+            .as_ref()
+            .map(|arp| AllocationAndRetentionPriority {
+                priority_level: match arp.priority_level {
+                    swagger::Nullable::Present(priority_level) => priority_level,
+                    swagger::Nullable::Null => 15,
+                },
+                may_trigger_pre_emption: arp.preempt_cap.to_string() == "MAY_PREEMPT",
+                pre_emptable: arp.preempt_vuln.to_string() == "PREEMPTABLE",
+            })
+            .unwrap_or(AllocationAndRetentionPriority {
+                priority_level: 15,
+                may_trigger_pre_emption: false,
+                pre_emptable: true,
+            });
+        QosFlowLevelQosParameters {
+            five_qi: profile.param_5qi,
+            allocation_and_retention_priority,
+        }
+    }
+}
+
+/// Allocation and Retention Priority (clause 9.3.1.19).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AllocationAndRetentionPriority {
+    /// 1 to 15, 1 being the highest priority
+    pub priority_level: u8,
+    pub may_trigger_pre_emption: bool,
+    pub pre_emptable: bool,
+}
+
+/// QoS Flow per TNL Information (clause 9.3.2.8).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QosFlowPerTnlInformation {
+    pub up_transport_layer_information: GtpTunnel,
+    pub associated_qos_flow_list: Vec<AssociatedQosFlowItem>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AssociatedQosFlowItem {
+    pub qfi: u8,
+    /// Whether the QoS flow is only mapped to the uplink or the downlink
+    pub qos_flow_mapping_indication: Option<QosFlowMappingIndication>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum QosFlowMappingIndication {
+    Ul = 0,
+    Dl = 1,
+}
+
+/// Cause (clause 9.3.1.2), by group, with the index of the value in the
+/// root enumeration of the group.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cause {
+    RadioNetwork(u8),
+    Transport(u8),
+    Nas(u8),
+    Protocol(u8),
+    Misc(u8),
+}
+
+impl Cause {
+    pub const RADIO_NETWORK_UNSPECIFIED: Cause = Cause::RadioNetwork(0);
+    pub const NAS_NORMAL_RELEASE: Cause = Cause::Nas(0);
+    pub const NAS_DEREGISTER: Cause = Cause::Nas(2);
+    pub const NAS_UNSPECIFIED: Cause = Cause::Nas(3);
+    pub const MISC_UNSPECIFIED: Cause = Cause::Misc(5);
+
+    /// The choice index and the number of bits of the values of the group
+    fn group(&self) -> (u64, u32, u8) {
+        match *self {
+            Cause::RadioNetwork(value) => (0, 6, value),
+            Cause::Transport(value) => (1, 1, value),
+            Cause::Nas(value) => (2, 2, value),
+            Cause::Protocol(value) => (3, 3, value),
+            Cause::Misc(value) => (4, 3, value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The encoding ends within an IE
+    Truncated,
+    /// A mandatory protocol IE is absent
+    MissingIe(&'static str),
+    /// An IE has a value or an alternative the SMF does not know
+    InvalidIe(&'static str),
+}
+
+impl std::fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DecodeError::Truncated => write!(f, "Truncated NGAP transfer"),
+            DecodeError::MissingIe(ie) => write!(f, "Missing {}", ie),
+            DecodeError::InvalidIe(ie) => write!(f, "Invalid {}", ie),
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl PduSessionResourceSetupRequestTransfer {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut ies = Vec::new();
+        if let Some(ambr) = &self.pdu_session_aggregate_maximum_bit_rate {
+            ies.push((
+                ID_PDU_SESSION_AGGREGATE_MAXIMUM_BIT_RATE,
+                value(|e| ambr.encode(e)),
+            ));
+        }
+        ies.push((
+            ID_UL_NGU_UP_TNL_INFORMATION,
+            value(|e| self.ul_ngu_up_tnl_information.encode(e)),
+        ));
+        ies.push((
+            ID_PDU_SESSION_TYPE,
+            value(|e| encode_pdu_session_type(e, self.pdu_session_type)),
+        ));
+        ies.push((
+            ID_QOS_FLOW_SETUP_REQUEST_LIST,
+            value(|e| {
+                encode_list(e, &self.qos_flow_setup_request_list, |e, item| {
+                    e.bit(false);
+                    e.bits(0, 2);
+                    encode_qfi(e, item.qfi);
+                    item.qos_flow_level_qos_parameters.encode(e);
+                })
+            }),
+        ));
+        encode_protocol_ies(&ies)
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let mut ambr = None;
+        let mut tunnel = None;
+        let mut session_type = None;
+        let mut qos_flows = None;
+        for (id, value) in decode_protocol_ies(bytes)? {
+            let d = &mut Decoder::new(value);
+            match id {
+                ID_PDU_SESSION_AGGREGATE_MAXIMUM_BIT_RATE => {
+                    ambr = Some(PduSessionAggregateMaximumBitRate::decode(d)?)
+                }
+                ID_UL_NGU_UP_TNL_INFORMATION => tunnel = Some(GtpTunnel::decode(d)?),
+                ID_PDU_SESSION_TYPE => session_type = Some(decode_pdu_session_type(d)?),
+                ID_QOS_FLOW_SETUP_REQUEST_LIST => {
+                    qos_flows = Some(decode_list(d, |d| {
+                        let (extended, optional) = (d.bit()?, d.bits(2)?);
+                        let qfi = decode_qfi(d)?;
+                        let qos_flow_level_qos_parameters = QosFlowLevelQosParameters::decode(d)?;
+                        if optional & 0b10 != 0 {
+                            // E-RAB ID
+                            d.extensible_bits(4, "E-RAB ID")?;
+                        }
+                        d.skip_extensions(optional & 0b01 != 0, extended)?;
+                        Ok(QosFlowSetupRequestItem {
+                            qfi,
+                            qos_flow_level_qos_parameters,
+                        })
+                    })?)
+                }
+                _ => {}
+            }
+        }
+        Ok(PduSessionResourceSetupRequestTransfer {
+            pdu_session_aggregate_maximum_bit_rate: ambr,
+            ul_ngu_up_tnl_information: tunnel
+                .ok_or(DecodeError::MissingIe("UL NG-U UP TNL Information"))?,
+            pdu_session_type: session_type.ok_or(DecodeError::MissingIe("PDU Session Type"))?,
+            qos_flow_setup_request_list: qos_flows
+                .ok_or(DecodeError::MissingIe("QoS Flow Setup Request List"))?,
+        })
+    }
+}
+
+impl PduSessionResourceSetupResponseTransfer {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut e = Encoder::default();
+        e.bit(false);
+        e.bits(0, 4);
+        self.dl_qos_flow_per_tnl_information.encode(&mut e);
+        e.finish()
+    }
+
+    /// Only the DL QoS flow per TNL information is decoded, the IEs
+    /// following it are left out.
+    pub fn decode(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let d = &mut Decoder::new(bytes);
+        d.bit()?;
+        d.bits(4)?;
+        Ok(PduSessionResourceSetupResponseTransfer {
+            dl_qos_flow_per_tnl_information: QosFlowPerTnlInformation::decode(d)?,
+        })
+    }
+}
+
+impl PduSessionResourceModifyRequestTransfer {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut ies = Vec::new();
+        if let Some(ambr) = &self.pdu_session_aggregate_maximum_bit_rate {
+            ies.push((
+                ID_PDU_SESSION_AGGREGATE_MAXIMUM_BIT_RATE,
+                value(|e| ambr.encode(e)),
+            ));
+        }
+        if !self.qos_flow_add_or_modify_request_list.is_empty() {
+            ies.push((
+                ID_QOS_FLOW_ADD_OR_MODIFY_REQUEST_LIST,
+                value(|e| {
+                    encode_list(e, &self.qos_flow_add_or_modify_request_list, |e, item| {
+                        e.bit(false);
+                        e.bit(item.qos_flow_level_qos_parameters.is_some());
+                        e.bits(0, 2);
+                        encode_qfi(e, item.qfi);
+                        if let Some(parameters) = &item.qos_flow_level_qos_parameters {
+                            parameters.encode(e);
+                        }
+                    })
+                }),
+            ));
+        }
+        if !self.qos_flow_to_release_list.is_empty() {
+            ies.push((
+                ID_QOS_FLOW_TO_RELEASE_LIST,
+                value(|e| {
+                    encode_list(e, &self.qos_flow_to_release_list, |e, item| {
+                        e.bit(false);
+                        e.bit(false);
+                        encode_qfi(e, item.qfi);
+                        item.cause.encode(e);
+                    })
+                }),
+            ));
+        }
+        encode_protocol_ies(&ies)
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let mut transfer = PduSessionResourceModifyRequestTransfer::default();
+        for (id, value) in decode_protocol_ies(bytes)? {
+            let d = &mut Decoder::new(value);
+            match id {
+                ID_PDU_SESSION_AGGREGATE_MAXIMUM_BIT_RATE => {
+                    transfer.pdu_session_aggregate_maximum_bit_rate =
+                        Some(PduSessionAggregateMaximumBitRate::decode(d)?)
+                }
+                ID_QOS_FLOW_ADD_OR_MODIFY_REQUEST_LIST => {
+                    transfer.qos_flow_add_or_modify_request_list = decode_list(d, |d| {
+                        let (extended, optional) = (d.bit()?, d.bits(3)?);
+                        let qfi = decode_qfi(d)?;
+                        let qos_flow_level_qos_parameters = if optional & 0b100 != 0 {
+                            Some(QosFlowLevelQosParameters::decode(d)?)
+                        } else {
+                            None
+                        };
+                        if optional & 0b010 != 0 {
+                            d.extensible_bits(4, "E-RAB ID")?;
+                        }
+                        d.skip_extensions(optional & 0b001 != 0, extended)?;
+                        Ok(QosFlowAddOrModifyRequestItem {
+                            qfi,
+                            qos_flow_level_qos_parameters,
+                        })
+                    })?
+                }
+                ID_QOS_FLOW_TO_RELEASE_LIST => {
+                    transfer.qos_flow_to_release_list = decode_list(d, |d| {
+                        let (extended, optional) = (d.bit()?, d.bit()?);
+                        let qfi = decode_qfi(d)?;
+                        let cause = Cause::decode(d)?;
+                        d.skip_extensions(optional, extended)?;
+                        Ok(QosFlowWithCause { qfi, cause })
+                    })?
+                }
+                _ => {}
+            }
+        }
+        Ok(transfer)
+    }
+}
+
+impl PduSessionResourceReleaseCommandTransfer {
+    pub fn encode(&self) -> Vec<u8> {
+        let mut e = Encoder::default();
+        e.bit(false);
+        e.bit(false);
+        self.cause.encode(&mut e);
+        e.finish()
+    }
+
+    pub fn decode(bytes: &[u8]) -> Result<Self, DecodeError> {
+        let d = &mut Decoder::new(bytes);
+        let (extended, optional) = (d.bit()?, d.bit()?);
+        let cause = Cause::decode(d)?;
+        d.skip_extensions(optional, extended)?;
+        Ok(PduSessionResourceReleaseCommandTransfer { cause })
+    }
+}
+
+impl PduSessionAggregateMaximumBitRate {
+    fn encode(&self, e: &mut Encoder) {
+        e.bit(false);
+        e.bit(false);
+        encode_bit_rate(e, self.downlink);
+        encode_bit_rate(e, self.uplink);
+    }
+
+    fn decode(d: &mut Decoder) -> Result<Self, DecodeError> {
+        let (extended, optional) = (d.bit()?, d.bit()?);
+        let downlink = decode_bit_rate(d)?;
+        let uplink = decode_bit_rate(d)?;
+        d.skip_extensions(optional, extended)?;
+        Ok(PduSessionAggregateMaximumBitRate { downlink, uplink })
+    }
+}
+
+impl GtpTunnel {
+    /// The gTPTunnel alternative of the UP Transport Layer Information.
+    fn encode(&self, e: &mut Encoder) {
+        e.bit(false);
+        e.bit(false);
+        e.bit(false);
+        let address = match self.transport_layer_address {
+            IpAddr::V4(address) => address.octets().to_vec(),
+            IpAddr::V6(address) => address.octets().to_vec(),
+        };
+        // Transport Layer Address, a BIT STRING (SIZE(1..160, ...))
+        e.bit(false);
+        e.bits(address.len() as u64 * 8 - 1, 8);
+        e.octets(&address);
+        e.octets(&self.gtp_teid.to_be_bytes());
+    }
+
+    /// An IPv4v6 transport layer address is taken for its IPv4 address.
+    fn decode(d: &mut Decoder) -> Result<Self, DecodeError> {
+        if d.bit()? {
+            return Err(DecodeError::InvalidIe("UP Transport Layer Information"));
+        }
+        let (extended, optional) = (d.bit()?, d.bit()?);
+        if d.bit()? {
+            return Err(DecodeError::InvalidIe("Transport Layer Address"));
+        }
+        let len = d.bits(8)? + 1;
+        let transport_layer_address = match len {
+            32 | 160 => {
+                let address = d.octets(len as usize / 8)?;
+                IpAddr::V4(Ipv4Addr::new(
+                    address[0], address[1], address[2], address[3],
+                ))
+            }
+            128 => {
+                let mut address = [0; 16];
+                address.copy_from_slice(d.octets(16)?);
+                IpAddr::V6(Ipv6Addr::from(address))
+            }
+            _ => return Err(DecodeError::InvalidIe("Transport Layer Address")),
+        };
+        let teid = d.octets(4)?;
+        let gtp_teid = u32::from_be_bytes([teid[0], teid[1], teid[2], teid[3]]);
+        d.skip_extensions(optional, extended)?;
+        Ok(GtpTunnel {
+            transport_layer_address,
+            gtp_teid,
+        })
+    }
+}
+
+impl QosFlowLevelQosParameters {
+    fn encode(&self, e: &mut Encoder) {
+        e.bit(false);
+        e.bits(0, 4);
+        // Non Dynamic 5QI Descriptor alternative of the QoS Characteristics
+        e.bits(0, 2);
+        e.bit(false);
+        e.bits(0, 4);
+        e.bit(false);
+        e.octets(&[self.five_qi]);
+        let arp = &self.allocation_and_retention_priority;
+        e.bit(false);
+        e.bit(false);
+        e.bits(u64::from(arp.priority_level.clamp(1, 15) - 1), 4);
+        e.bit(false);
+        e.bit(arp.may_trigger_pre_emption);
+        e.bit(false);
+        e.bit(arp.pre_emptable);
+    }
+
+    /// The parameters of a GBR QoS flow, or with a dynamic 5QI, are not
+    /// decoded.
+    fn decode(d: &mut Decoder) -> Result<Self, DecodeError> {
+        let (extended, optional) = (d.bit()?, d.bits(4)?);
+        if optional & 0b1110 != 0 || d.bits(2)? != 0 {
+            return Err(DecodeError::InvalidIe("QoS Flow Level QoS Parameters"));
+        }
+        let (descriptor_extended, descriptor_optional) = (d.bit()?, d.bits(4)?);
+        if descriptor_optional & 0b1110 != 0 || d.bit()? {
+            return Err(DecodeError::InvalidIe("Non Dynamic 5QI Descriptor"));
+        }
+        let five_qi = d.octets(1)?[0];
+        d.skip_extensions(descriptor_optional & 0b0001 != 0, descriptor_extended)?;
+
+        let (arp_extended, arp_optional) = (d.bit()?, d.bit()?);
+        let priority_level = d.bits(4)? as u8 + 1;
+        let may_trigger_pre_emption = d.extensible_bits(1, "Pre-emption Capability")? == 1;
+        let pre_emptable = d.extensible_bits(1, "Pre-emption Vulnerability")? == 1;
+        d.skip_extensions(arp_optional, arp_extended)?;
+
+        d.skip_extensions(optional & 0b0001 != 0, extended)?;
+        Ok(QosFlowLevelQosParameters {
+            five_qi,
+            allocation_and_retention_priority: AllocationAndRetentionPriority {
+                priority_level,
+                may_trigger_pre_emption,
+                pre_emptable,
+            },
+        })
+    }
+}
+
+impl QosFlowPerTnlInformation {
+    fn encode(&self, e: &mut Encoder) {
+        e.bit(false);
+        e.bit(false);
+        self.up_transport_layer_information.encode(e);
+        encode_list(e, &self.associated_qos_flow_list, |e, item| {
+            e.bit(false);
+            e.bit(item.qos_flow_mapping_indication.is_some());
+            e.bit(false);
+            encode_qfi(e, item.qfi);
+            if let Some(indication) = item.qos_flow_mapping_indication {
+                e.bit(false);
+                e.bits(indication as u64, 1);
+            }
+        });
+    }
+
+    fn decode(d: &mut Decoder) -> Result<Self, DecodeError> {
+        let (extended, optional) = (d.bit()?, d.bit()?);
+        let up_transport_layer_information = GtpTunnel::decode(d)?;
+        let associated_qos_flow_list = decode_list(d, |d| {
+            let (extended, optional) = (d.bit()?, d.bits(2)?);
+            let qfi = decode_qfi(d)?;
+            let qos_flow_mapping_indication = if optional & 0b10 != 0 {
+                match d.extensible_bits(1, "QoS Flow Mapping Indication")? {
+                    0 => Some(QosFlowMappingIndication::Ul),
+                    _ => Some(QosFlowMappingIndication::Dl),
+                }
+            } else {
+                None
+            };
+            d.skip_extensions(optional & 0b01 != 0, extended)?;
+            Ok(AssociatedQosFlowItem {
+                qfi,
+                qos_flow_mapping_indication,
+            })
+        })?;
+        d.skip_extensions(optional, extended)?;
+        Ok(QosFlowPerTnlInformation {
+            up_transport_layer_information,
+            associated_qos_flow_list,
+        })
+    }
+}
+
+impl Cause {
+    fn encode(&self, e: &mut Encoder) {
+        let (choice, bits, value) = self.group();
+        e.bits(choice, 3);
+        e.bit(false);
+        e.bits(u64::from(value), bits);
+    }
+
+    fn decode(d: &mut Decoder) -> Result<Self, DecodeError> {
+        let group: fn(u8) -> Cause = match d.bits(3)? {
+            0 => Cause::RadioNetwork,
+            1 => Cause::Transport,
+            2 => Cause::Nas,
+            3 => Cause::Protocol,
+            4 => Cause::Misc,
+            _ => return Err(DecodeError::InvalidIe("Cause")),
+        };
+        let (_, bits, _) = group(0).group();
+        Ok(group(d.extensible_bits(bits, "Cause")? as u8))
+    }
+}
+
+/// PDU Session Type, ENUMERATED { ipv4, ipv6, ipv4v6, ethernet,
+/// unstructured, ... }
+fn encode_pdu_session_type(e: &mut Encoder, session_type: nas::PduSessionType) {
+    let index = match session_type {
+        nas::PduSessionType::Ipv4 => 0,
+        nas::PduSessionType::Ipv6 => 1,
+        nas::PduSessionType::Ipv4v6 => 2,
+        nas::PduSessionType::Ethernet => 3,
+        nas::PduSessionType::Unstructured => 4,
+    };
+    e.bit(false);
+    e.bits(index, 3);
+}
+
+fn decode_pdu_session_type(d: &mut Decoder) -> Result<nas::PduSessionType, DecodeError> {
+    match d.extensible_bits(3, "PDU Session Type")? {
+        0 => Ok(nas::PduSessionType::Ipv4),
+        1 => Ok(nas::PduSessionType::Ipv6),
+        2 => Ok(nas::PduSessionType::Ipv4v6),
+        3 => Ok(nas::PduSessionType::Ethernet),
+        4 => Ok(nas::PduSessionType::Unstructured),
+        _ => Err(DecodeError::InvalidIe("PDU Session Type")),
+    }
+}
+
+/// QoS Flow Identifier, INTEGER (0..63, ...)
+fn encode_qfi(e: &mut Encoder, qfi: u8) {
+    e.bit(false);
+    e.bits(u64::from(qfi), 6);
+}
+
+fn decode_qfi(d: &mut Decoder) -> Result<u8, DecodeError> {
+    Ok(d.extensible_bits(6, "QoS Flow Identifier")? as u8)
+}
+
+/// Bit Rate, INTEGER (0..4000000000000, ...): the number of octets on 3
+/// bits, then the octets.
+fn encode_bit_rate(e: &mut Encoder, bit_rate: u64) {
+    let bit_rate = bit_rate.min(4_000_000_000_000);
+    let len = (8 - bit_rate.leading_zeros() as usize / 8).max(1);
+    e.bit(false);
+    e.bits(len as u64 - 1, 3);
+    e.octets(&bit_rate.to_be_bytes()[8 - len..]);
+}
+
+fn decode_bit_rate(d: &mut Decoder) -> Result<u64, DecodeError> {
+    let len = d.extensible_bits(3, "Bit Rate")? as usize + 1;
+    Ok(d.octets(len)?
+        .iter()
+        .fold(0, |bit_rate, octet| bit_rate << 8 | u64::from(*octet)))
+}
+
+/// A SEQUENCE (SIZE(1..maxnoofQosFlows)) OF, 64 items at most.
+fn encode_list<T>(e: &mut Encoder, items: &[T], encode: impl Fn(&mut Encoder, &T)) {
+    let items = &items[..items.len().min(64)];
+    e.bits(items.len().max(1) as u64 - 1, 6);
+    for item in items {
+        encode(e, item);
+    }
+}
+
+fn decode_list<T>(
+    d: &mut Decoder,
+    decode: impl Fn(&mut Decoder) -> Result<T, DecodeError>,
+) -> Result<Vec<T>, DecodeError> {
+    let len = d.bits(6)? + 1;
+    (0..len).map(|_| decode(d)).collect()
+}
+
+/// The encoding of the value of a protocol IE, an open type.
+fn value(encode: impl FnOnce(&mut Encoder)) -> Vec<u8> {
+    let mut e = Encoder::default();
+    encode(&mut e);
+    e.finish()
+}
+
+/// A SEQUENCE { protocolIEs ProtocolIE-Container, ... } of IEs by id.
+fn encode_protocol_ies(ies: &[(u16, Vec<u8>)]) -> Vec<u8> {
+    let mut e = Encoder::default();
+    e.bit(false);
+    e.octets(&(ies.len() as u16).to_be_bytes());
+    for (id, value) in ies {
+        e.octets(&id.to_be_bytes());
+        e.bits(CRITICALITY_REJECT, 2);
+        e.open_type(value);
+    }
+    e.finish()
+}
+
+fn decode_protocol_ies(bytes: &[u8]) -> Result<Vec<(u16, &[u8])>, DecodeError> {
+    let d = &mut Decoder::new(bytes);
+    d.bit()?;
+    let count = d.u16()?;
+    (0..count)
+        .map(|_| {
+            let id = d.u16()?;
+            d.bits(2)?;
+            Ok((id, d.open_type()?))
+        })
+        .collect()
+}
+
+/// Writes the bits of an aligned PER encoding, most significant first.
+#[derive(Default)]
+struct Encoder {
+    bytes: Vec<u8>,
+    /// Bits used in the last octet, 0 when aligned
+    used: u32,
+}
+
+impl Encoder {
+    fn bit(&mut self, bit: bool) {
+        if self.used == 0 {
+            self.bytes.push(0);
+        }
+        if bit {
+            *self.bytes.last_mut().expect("octet pushed above") |= 0x80 >> self.used;
+        }
+        self.used = (self.used + 1) % 8;
+    }
+
+    fn bits(&mut self, value: u64, bits: u32) {
+        for i in (0..bits).rev() {
+            self.bit(value >> i & 1 == 1);
+        }
+    }
+
+    /// Octet-aligned octets, after padding the last octet with zeros.
+    fn octets(&mut self, octets: &[u8]) {
+        self.used = 0;
+        self.bytes.extend_from_slice(octets);
+    }
+
+    /// An open type, its length determinant then its encoding.
+    fn open_type(&mut self, value: &[u8]) {
+        let len = value.len();
+        if len < 128 {
+            self.octets(&[len as u8]);
+        } else {
+            self.octets(&[0x80 | (len >> 8) as u8 & 0x3f, len as u8]);
+        }
+        self.octets(value);
+    }
+
+    /// A complete encoding takes at least one octet.
+    fn finish(mut self) -> Vec<u8> {
+        if self.bytes.is_empty() {
+            self.bytes.push(0);
+        }
+        self.bytes
+    }
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+    /// Position in bits
+    position: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn new(bytes: &'a [u8]) -> Decoder<'a> {
+        Decoder { bytes, position: 0 }
+    }
+
+    fn bit(&mut self) -> Result<bool, DecodeError> {
+        let octet = self
+            .bytes
+            .get(self.position / 8)
+            .ok_or(DecodeError::Truncated)?;
+        let bit = octet & 0x80 >> (self.position % 8) != 0;
+        self.position += 1;
+        Ok(bit)
+    }
+
+    fn bits(&mut self, bits: u32) -> Result<u64, DecodeError> {
+        (0..bits).try_fold(0, |value, _| Ok(value << 1 | u64::from(self.bit()?)))
+    }
+
+    /// The root value of an extensible constrained type; the SMF knows none
+    /// of the values of the extensions.
+    fn extensible_bits(&mut self, bits: u32, ie: &'static str) -> Result<u64, DecodeError> {
+        if self.bit()? {
+            return Err(DecodeError::InvalidIe(ie));
+        }
+        self.bits(bits)
+    }
+
+    fn octets(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        let start = self.position.div_ceil(8);
+        let octets = self
+            .bytes
+            .get(start..start + len)
+            .ok_or(DecodeError::Truncated)?;
+        self.position = (start + len) * 8;
+        Ok(octets)
+    }
+
+    fn u16(&mut self) -> Result<u16, DecodeError> {
+        let octets = self.octets(2)?;
+        Ok(u16::from_be_bytes([octets[0], octets[1]]))
+    }
+
+    fn open_type(&mut self) -> Result<&'a [u8], DecodeError> {
+        let first = self.octets(1)?[0];
+        let len = match first {
+            0..=0x7f => usize::from(first),
+            0x80..=0xbf => usize::from(first & 0x3f) << 8 | usize::from(self.octets(1)?[0]),
+            _ => return Err(DecodeError::InvalidIe("fragmented open type")),
+        };
+        self.octets(len)
+    }
+
+    /// Skips the protocol extension container of a SEQUENCE, when present,
+    /// then the additions of its extension, when extended.
+    fn skip_extensions(&mut self, ie_extensions: bool, extended: bool) -> Result<(), DecodeError> {
+        if ie_extensions {
+            let count = usize::from(self.u16()?) + 1;
+            for _ in 0..count {
+                self.u16()?;
+                self.bits(2)?;
+                self.open_type()?;
+            }
+        }
+        if extended {
+            // Normally small length of the bitmap of the additions
+            if self.bit()? {
+                return Err(DecodeError::InvalidIe("extension additions"));
+            }
+            let len = self.bits(6)? + 1;
+            let present = self.bits(len as u32)?;
+            for _ in 0..present.count_ones() {
+                self.open_type()?;
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The transfer the SMFs sent in the N1N2MessageTransfer
+    const SETUP_REQUEST_TRANSFER_BYTES: [u8; 47] = [
+        0x00, 0x00, 0x04, 0x00, 0x82, 0x00, 0x0a, 0x0c, 0x40, 0x00, 0x00, 0x00, 0x30, 0x40, 0x00,
+        0x00, 0x00, 0x00, 0x8b, 0x00, 0x0a, 0x01, 0xf0, 0xac, 0x16, 0x00, 0x08, 0x00, 0x00, 0x00,
+        0x0e, 0x00, 0x86, 0x00, 0x01, 0x00, 0x00, 0x88, 0x00, 0x07, 0x00, 0x01, 0x00, 0x00, 0x09,
+        0x1c, 0x00,
+    ];
+
+    /// The transfer of a gNB answering it
+    const SETUP_RESPONSE_TRANSFER_BYTES: [u8; 13] = [
+        0x00, 0x03, 0xe0, 0xac, 0x16, 0x00, 0x17, 0x00, 0x00, 0x00, 0x04, 0x00, 0x01,
+    ];
+
+    fn default_qos_flow_parameters() -> QosFlowLevelQosParameters {
+        QosFlowLevelQosParameters {
+            five_qi: 9,
+            allocation_and_retention_priority: AllocationAndRetentionPriority {
+                priority_level: 8,
+                may_trigger_pre_emption: false,
+                pre_emptable: false,
+            },
+        }
+    }
+
+    #[test]
+    fn setup_request_transfer() {
+        let transfer = PduSessionResourceSetupRequestTransfer {
+            pdu_session_aggregate_maximum_bit_rate: Some(PduSessionAggregateMaximumBitRate {
+                downlink: 0x4000_0000,
+                uplink: 0x4000_0000,
+            }),
+            ul_ngu_up_tnl_information: GtpTunnel {
+                transport_layer_address: IpAddr::V4(Ipv4Addr::new(172, 22, 0, 8)),
+                gtp_teid: 14,
+            },
+            pdu_session_type: nas::PduSessionType::Ipv4,
+            qos_flow_setup_request_list: vec![QosFlowSetupRequestItem {
+                qfi: 1,
+                qos_flow_level_qos_parameters: default_qos_flow_parameters(),
+            }],
+        };
+        assert_eq!(transfer.encode(), SETUP_REQUEST_TRANSFER_BYTES);
+        assert_eq!(
+            PduSessionResourceSetupRequestTransfer::decode(&SETUP_REQUEST_TRANSFER_BYTES),
+            Ok(transfer)
+        );
+    }
+
+    #[test]
+    fn setup_response_transfer() {
+        let transfer = PduSessionResourceSetupResponseTransfer {
+            dl_qos_flow_per_tnl_information: QosFlowPerTnlInformation {
+                up_transport_layer_information: GtpTunnel {
+                    transport_layer_address: IpAddr::V4(Ipv4Addr::new(172, 22, 0, 23)),
+                    gtp_teid: 4,
+                },
+                associated_qos_flow_list: vec![AssociatedQosFlowItem {
+                    qfi: 1,
+                    qos_flow_mapping_indication: None,
+                }],
+            },
+        };
+        assert_eq!(transfer.encode(), SETUP_RESPONSE_TRANSFER_BYTES);
+        assert_eq!(
+            PduSessionResourceSetupResponseTransfer::decode(&SETUP_RESPONSE_TRANSFER_BYTES),
+            Ok(transfer)
+        );
+    }
+
+    #[test]
+    fn modify_request_transfer() {
+        let transfer = PduSessionResourceModifyRequestTransfer {
+            pdu_session_aggregate_maximum_bit_rate: Some(PduSessionAggregateMaximumBitRate {
+                downlink: 2_000_000_000,
+                uplink: 0,
+            }),
+            qos_flow_add_or_modify_request_list: vec![
+                QosFlowAddOrModifyRequestItem {
+                    qfi: 1,
+                    qos_flow_level_qos_parameters: Some(default_qos_flow_parameters()),
+                },
+                QosFlowAddOrModifyRequestItem {
+                    qfi: 63,
+                    qos_flow_level_qos_parameters: None,
+                },
+            ],
+            qos_flow_to_release_list: vec![QosFlowWithCause {
+                qfi: 2,
+                cause: Cause::NAS_NORMAL_RELEASE,
+            }],
+        };
+        assert_eq!(
+            PduSessionResourceModifyRequestTransfer::decode(&transfer.encode()),
+            Ok(transfer)
+        );
+    }
+
+    #[test]
+    fn release_command_transfer() {
+        for cause in [
+            Cause::RADIO_NETWORK_UNSPECIFIED,
+            Cause::RadioNetwork(44),
+            Cause::Transport(1),
+            Cause::NAS_NORMAL_RELEASE,
+            Cause::Protocol(6),
+            Cause::MISC_UNSPECIFIED,
+        ] {
+            let transfer = PduSessionResourceReleaseCommandTransfer { cause };
+            assert_eq!(
+                PduSessionResourceReleaseCommandTransfer::decode(&transfer.encode()),
+                Ok(transfer)
+            );
+        }
+        assert_eq!(
+            PduSessionResourceReleaseCommandTransfer {
+                cause: Cause::NAS_NORMAL_RELEASE
+            }
+            .encode(),
+            [0x10]
+        );
+    }
+
+    #[test]
+    fn unknown_protocol_ies_are_skipped() {
+        let mut bytes = SETUP_REQUEST_TRANSFER_BYTES.to_vec();
+        // An IE with id 200 appended to the container
+        bytes[2] = 0x05;
+        bytes.extend_from_slice(&[0x00, 0xc8, 0x40, 0x02, 0xab, 0xcd]);
+        assert_eq!(
+            PduSessionResourceSetupRequestTransfer::decode(&bytes),
+            PduSessionResourceSetupRequestTransfer::decode(&SETUP_REQUEST_TRANSFER_BYTES)
+        );
+        assert_eq!(
+            PduSessionResourceSetupRequestTransfer::decode(&bytes[..20]),
+            Err(DecodeError::Truncated)
+        );
+    }
+}
@@ -4,7 +4,7 @@ use hyper::{Body, HeaderMap, Request, Response, StatusCode};
 use hyper_0_10::header::{ContentType, Headers};
 use log::warn;
 use mime_0_2::{Mime as Mime2, SubLevel, TopLevel};
-use mime_multipart::{read_multipart_body, Node, Part};
+use mime_multipart::{generate_boundary, read_multipart_body, write_multipart, Node, Part};
 #[allow(unused_imports)]
 use std::convert::{TryFrom, TryInto};
 use std::error::Error;
@@ -2988,23 +2988,109 @@ where
                         //     match result.await {
                             Ok(body) => {
                                 let mut unused_elements: Vec<String> = vec![];
-                                let param_sm_context_update_data: Option<models::SmContextUpdateData> =
-                                if !body.is_empty() {
+                                let mut param_sm_context_update_data: Option<models::SmContextUpdateData> = None;
+                                let mut param_binary_data_n1_sm_message = None;
+                                let mut param_binary_data_n2_sm_information = None;
+                                let mut param_binary_data_n2_sm_information_ext1 = None;
+
+                                // The update data comes alone as JSON, or with binary data as multipart/related.
+                                let is_multipart = headers
+                                    .get(CONTENT_TYPE)
+                                    .and_then(|v| v.to_str().ok())
+                                    .map_or(false, |v| v.trim_start().to_ascii_lowercase().starts_with("multipart/"));
+
+                                if is_multipart {
+                                    // Get multipart chunks.
+
+                                    // Extract the top-level content type header.
+                                    let content_type_mime = headers
+                                        .get(CONTENT_TYPE)
+                                        .ok_or_else(|| "Missing content-type header".to_string())
+                                        .and_then(|v| v.to_str().map_err(|e| format!("Couldn't read content-type header value for UpdateSmContext: {}", e)))
+                                        .and_then(|v| v.parse::<Mime2>().map_err(|_e| "Couldn't parse content-type header value for UpdateSmContext".to_string()));
+
+                                    // Insert top-level content type header into a Headers object.
+                                    let mut multi_part_headers = Headers::new();
+                                    match content_type_mime {
+                                        Ok(content_type_mime) => {
+                                            multi_part_headers.set(ContentType(content_type_mime));
+                                        },
+                                        Err(e) => {
+                                            return Ok(Response::builder()
+                                                    .status(StatusCode::BAD_REQUEST)
+                                                    .body(Body::from(e))
+                                                    .expect("Unable to create Bad Request response due to unable to read content-type header for UpdateSmContext"));
+                                        }
+                                    }
+
+                                    // &*body expresses the body as a byteslice, &mut provides a
+                                    // mutable reference to that byteslice.
+                                    let nodes = match read_multipart_body(&mut&*body, &multi_part_headers, false) {
+                                        Ok(nodes) => nodes,
+                                        Err(e) => {
+                                            return Ok(Response::builder()
+                                                    .status(StatusCode::BAD_REQUEST)
+                                                    .body(Body::from(format!("Could not read multipart body for UpdateSmContext: {}", e)))
+                                                    .expect("Unable to create Bad Request response due to unable to read multipart body for UpdateSmContext"));
+                                        }
+                                    };
+
+                                    for node in nodes {
+                                        if let Node::Part(part) = node {
+                                            let content_type = part.content_type().map(|x| format!("{}",x));
+                                            match content_type.as_deref() {
+                                                Some("application/json") if param_sm_context_update_data.is_none() => {
+                                                    // Extract JSON part.
+                                                    let deserializer = &mut serde_json::Deserializer::from_slice(part.body.as_slice());
+                                                    let json_data: models::SmContextUpdateData = match serde_ignored::deserialize(deserializer, |path| {
+                                                        warn!("Ignoring unknown field in JSON part: {}", path);
+                                                        unused_elements.push(path.to_string());
+                                                    }) {
+                                                        Ok(json_data) => json_data,
+                                                        Err(e) => return Ok(Response::builder()
+                                                                        .status(StatusCode::BAD_REQUEST)
+                                                                        .body(Body::from(format!("Couldn't parse body parameter models::SmContextUpdateData - doesn't match schema: {}", e)))
+                                                                        .expect("Unable to create Bad Request response for invalid body parameter models::SmContextUpdateData due to schema"))
+                                                    };
+                                                    // Push JSON part to return object.
+                                                    param_sm_context_update_data.get_or_insert(json_data);
+                                                },
+                                                Some("application/vnd.3gpp.5gnas") if param_binary_data_n1_sm_message.is_none() => {
+                                                    param_binary_data_n1_sm_message.get_or_insert(swagger::ByteArray(part.body));
+                                                },
+                                                Some("application/vnd.3gpp.ngap") if param_binary_data_n2_sm_information.is_none() => {
+                                                    param_binary_data_n2_sm_information.get_or_insert(swagger::ByteArray(part.body));
+                                                },
+                                                Some("application/vnd.3gpp.ngap") if param_binary_data_n2_sm_information_ext1.is_none() => {
+                                                    param_binary_data_n2_sm_information_ext1.get_or_insert(swagger::ByteArray(part.body));
+                                                },
+                                                Some(content_type) => {
+                                                    warn!("Ignoring unexpected content type: {}", content_type);
+                                                    unused_elements.push(content_type.to_string());
+                                                },
+                                                None => {
+                                                    warn!("Missing content type");
+                                                },
+                                            }
+                                        } else {
+                                            unimplemented!("No support for handling unexpected parts");
+                                            // unused_elements.push();
+                                        }
+                                    }
+                                } else if !body.is_empty() {
                                     let deserializer =
                                         &mut serde_json::Deserializer::from_slice(&*body);
                                     match serde_ignored::deserialize(deserializer, |path| {
                                             warn!("Ignoring unknown field in body: {}", path);
                                             unused_elements.push(path.to_string());
                                     }) {
-                                        Ok(param_sm_context_update_data) => param_sm_context_update_data,
+                                        Ok(sm_context_update_data) => param_sm_context_update_data = Some(sm_context_update_data),
                                         Err(e) => return Ok(Response::builder()
                                                         .status(StatusCode::BAD_REQUEST)
                                                         .body(Body::from(format!("Couldn't parse body parameter SmContextUpdateData - doesn't match schema: {}", e)))
                                                         .expect("Unable to create Bad Request response for invalid body parameter SmContextUpdateData due to schema")),
                                     }
-                                } else {
-                                    None
-                                };
+                                }
                             let param_sm_context_update_data = match param_sm_context_update_data {
                                     Some(param_sm_context_update_data) => param_sm_context_update_data,
                                     None => return Ok(Response::builder()
@@ -3012,63 +3098,13 @@ where
                                                         .body(Body::from("Missing required body parameter SmContextUpdateData"))
                                                         .expect("Unable to create Bad Request response for missing body parameter SmContextUpdateData")),
                                 };
-                                // Get multipart chunks.
-
-                                // Extract the top-level content type header.
-                                let content_type_mime = headers
-                                    .get(CONTENT_TYPE)
-                                    .ok_or_else(|| "Missing content-type header".to_string())
-                                    .and_then(|v| v.to_str().map_err(|e| format!("Couldn't read content-type header value for UpdateSmContext: {}", e)))
-                                    .and_then(|v| v.parse::<Mime2>().map_err(|_e| "Couldn't parse content-type header value for UpdateSmContext".to_string()));
-
-                                // Insert top-level content type header into a Headers object.
-                                let mut multi_part_headers = Headers::new();
-                                match content_type_mime {
-                                    Ok(content_type_mime) => {
-                                        multi_part_headers.set(ContentType(content_type_mime));
-                                    },
-                                    Err(e) => {
-                                        return Ok(Response::builder()
-                                                .status(StatusCode::BAD_REQUEST)
-                                                .body(Body::from(e))
-                                                .expect("Unable to create Bad Request response due to unable to read content-type header for UpdateSmContext"));
-                                    }
-                                }
-
-                                // &*body expresses the body as a byteslice, &mut provides a
-                                // mutable reference to that byteslice.
-                                let nodes = match read_multipart_body(&mut&*body, &multi_part_headers, false) {
-                                    Ok(nodes) => nodes,
-                                    Err(e) => {
-                                        return Ok(Response::builder()
-                                                .status(StatusCode::BAD_REQUEST)
-                                                .body(Body::from(format!("Could not read multipart body for UpdateSmContext: {}", e)))
-                                                .expect("Unable to create Bad Request response due to unable to read multipart body for UpdateSmContext"));
-                                    }
-                                };
-
-
-                                for node in nodes {
-                                    if let Node::Part(part) = node {
-                                        let content_type = part.content_type().map(|x| format!("{}",x));
-                                        match content_type.as_deref() {
-                                            Some(content_type) => {
-                                                warn!("Ignoring unexpected content type: {}", content_type);
-                                                unused_elements.push(content_type.to_string());
-                                            },
-                                            None => {
-                                                warn!("Missing content type");
-                                            },
-                                        }
-                                    } else {
-                                        unimplemented!("No support for handling unexpected parts");
-                                        // unused_elements.push();
-                                    }
-                                }
 
                                 let result = api_impl.update_sm_context(
                                             param_sm_context_ref,
                                             param_sm_context_update_data,
+                                            param_binary_data_n1_sm_message,
+                                            param_binary_data_n2_sm_information,
+                                            param_binary_data_n2_sm_information_ext1,
                                         &context
                                     ).await;
                                 let mut response = Response::new(Body::empty());
@@ -3097,6 +3133,78 @@ where
                                                     let body = serde_json::to_string(&body).expect("impossible to fail to serialize");
                                                     *response.body_mut() = Body::from(body);
                                                 },
+                                                UpdateSmContextResponse::SuccessfulUpdateOfAnSMContextWithContentAndBinaryDataInTheResponse
+                                                    {
+                                                        body,
+                                                        binary_data_n1_sm_message,
+                                                        binary_data_n2_sm_information
+                                                    }
+                                                => {
+                                                    *response.status_mut() = StatusCode::from_u16(200).expect("Unable to turn 200 into a StatusCode");
+
+                                                    // Construct the Body for a multipart/related response. The mime 0.2.6 library
+                                                    // does not parse quoted-string parameters correctly. The boundary doesn't
+                                                    // need to be a quoted string if it does not contain a '/', hence ensure
+                                                    // no such boundary is used.
+                                                    let mut boundary = generate_boundary();
+                                                    for b in boundary.iter_mut() {
+                                                        if b == &(b'/') {
+                                                            *b = b'=';
+                                                        }
+                                                    }
+
+                                                    // The binary parts have the Content-ID the JSON part references them with.
+                                                    let n1_content_id = body.n1_sm_msg.as_ref().map_or("binaryDataN1SmMessage".to_string(), |r| r.content_id.clone());
+                                                    let n2_content_id = body.n2_sm_info.as_ref().map_or("binaryDataN2SmInformation".to_string(), |r| r.content_id.clone());
+
+                                                    let mut body_parts = vec![
+                                                        Node::Part(Part {
+                                                            headers: {
+                                                                let mut h = Headers::new();
+                                                                h.set(ContentType("application/json".parse().unwrap()));
+                                                                h.set_raw("Content-ID", vec![b"jsonData".to_vec()]);
+                                                                h
+                                                            },
+                                                            body: serde_json::to_string(&body).expect("impossible to fail to serialize").into_bytes(),
+                                                        }),
+                                                    ];
+                                                    if let Some(binary_data_n1_sm_message) = binary_data_n1_sm_message {
+                                                        body_parts.push(Node::Part(Part {
+                                                            headers: {
+                                                                let mut h = Headers::new();
+                                                                h.set(ContentType("application/vnd.3gpp.5gnas".parse().unwrap()));
+                                                                h.set_raw("Content-ID", vec![n1_content_id.into_bytes()]);
+                                                                h
+                                                            },
+                                                            body: binary_data_n1_sm_message.0,
+                                                        }));
+                                                    }
+                                                    if let Some(binary_data_n2_sm_information) = binary_data_n2_sm_information {
+                                                        body_parts.push(Node::Part(Part {
+                                                            headers: {
+                                                                let mut h = Headers::new();
+                                                                h.set(ContentType("application/vnd.3gpp.ngap".parse().unwrap()));
+                                                                h.set_raw("Content-ID", vec![n2_content_id.into_bytes()]);
+                                                                h
+                                                            },
+                                                            body: binary_data_n2_sm_information.0,
+                                                        }));
+                                                    }
+
+                                                    // Write the body into a vec.
+                                                    let mut body: Vec<u8> = vec![];
+                                                    write_multipart(&mut body, &boundary, &body_parts).expect("Failed to write multipart body");
+
+                                                    response.headers_mut().insert(
+                                                        CONTENT_TYPE,
+                                                        HeaderValue::from_bytes(&[
+                                                            "multipart/related; boundary=".as_bytes(),
+                                                            &boundary,
+                                                            "; type=\"application/json\"".as_bytes(),
+                                                        ].concat())
+                                                            .expect("Unable to create Content-Type header for UPDATE_SM_CONTEXT_SUCCESSFUL_UPDATE_OF_AN_SM_CONTEXT_WITH_CONTENT_AND_BINARY_DATA_IN_THE_RESPONSE"));
+                                                    *response.body_mut() = Body::from(body);
+                                                },
                                                 UpdateSmContextResponse::SuccessfulUpdateOfAnSMContextWithoutContentInTheResponse
                                                 => {
                                                     *response.status_mut() = StatusCode::from_u16(204).expect("Unable to turn 204 into a StatusCode");
@@ -4154,6 +4262,60 @@ where
                                                     let body = serde_json::to_string(&body).expect("impossible to fail to serialize");
                                                     *response.body_mut() = Body::from(body);
                                                 },
+                                                PostSmContextsResponse::UnsuccessfulCreationOfAnSMContextWithN1SmMessage_4
+                                                    {
+                                                        body,
+                                                        binary_data_n1_sm_message
+                                                    }
+                                                => {
+                                                    *response.status_mut() = StatusCode::from_u16(500).expect("Unable to turn 500 into a StatusCode");
+
+                                                    // Construct the Body for a multipart/related response. The mime 0.2.6 library
+                                                    // does not parse quoted-string parameters correctly. The boundary doesn't
+                                                    // need to be a quoted string if it does not contain a '/', hence ensure
+                                                    // no such boundary is used.
+                                                    let mut boundary = generate_boundary();
+                                                    for b in boundary.iter_mut() {
+                                                        if b == &(b'/') {
+                                                            *b = b'=';
+                                                        }
+                                                    }
+
+                                                    let body_parts = vec![
+                                                        Node::Part(Part {
+                                                            headers: {
+                                                                let mut h = Headers::new();
+                                                                h.set(ContentType("application/json".parse().unwrap()));
+                                                                h.set_raw("Content-ID", vec![b"jsonData".to_vec()]);
+                                                                h
+                                                            },
+                                                            body: serde_json::to_string(&body).expect("impossible to fail to serialize").into_bytes(),
+                                                        }),
+                                                        Node::Part(Part {
+                                                            headers: {
+                                                                let mut h = Headers::new();
+                                                                h.set(ContentType("application/vnd.3gpp.5gnas".parse().unwrap()));
+                                                                h.set_raw("Content-ID", vec![b"binaryDataN1SmMessage".to_vec()]);
+                                                                h
+                                                            },
+                                                            body: binary_data_n1_sm_message.0,
+                                                        }),
+                                                    ];
+
+                                                    // Write the body into a vec.
+                                                    let mut body: Vec<u8> = vec![];
+                                                    write_multipart(&mut body, &boundary, &body_parts).expect("Failed to write multipart body");
+
+                                                    response.headers_mut().insert(
+                                                        CONTENT_TYPE,
+                                                        HeaderValue::from_bytes(&[
+                                                            "multipart/related; boundary=".as_bytes(),
+                                                            &boundary,
+                                                            "; type=\"application/json\"".as_bytes(),
+                                                        ].concat())
+                                                            .expect("Unable to create Content-Type header for POST_SM_CONTEXTS_UNSUCCESSFUL_CREATION_OF_AN_SM_CONTEXT_WITH_N1_SM_MESSAGE_4"));
+                                                    *response.body_mut() = Body::from(body);
+                                                },
                                                 PostSmContextsResponse::UnsuccessfulCreationOfAnSMContext_5
                                                     (body)
                                                 => {
@@ -0,0 +1,1476 @@
+//! SM contexts held by the SMF.
+//!
+//! The SMF creates an SM context for each PDU session the AMF asks it to
+//! establish (Nsmf_PDUSession_CreateSMContext, TS 29.502 clause 5.2.2.2), and
+//! identifies it by an `smContextRef` it allocates. The AMF then updates,
+//! retrieves and releases the context through the URI returned in the
+//! Location header, `{apiRoot}/nsmf-pdusession/v1/sm-contexts/{smContextRef}`.
+//!
+//! Each PDU session goes through the states of [`SessionState`], driven by
+//! the updates of its SM context (TS 23.502 clauses 4.3.2 to 4.3.4 and 4.9).
+//! An update the state of the session does not allow is rejected with an
+//! [`SmContextError`], which the HTTP, gRPC and zenoh SMFs all answer from.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, Ipv6Addr};
+use std::sync::atomic::{AtomicU32, AtomicU64, Ordering};
+use std::sync::{Arc, RwLock};
+
+use crate::ip_pools::IpPools;
+use crate::models::{self, AccessType, HoStateAnyOf, N2SmInfoTypeAnyOf, UpCnxStateAnyOf};
+use crate::{nas, ngap};
+
+/// The state of a PDU session in the SMF.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SessionState {
+    /// The SM context is created, the NG-RAN is setting up the resources
+    #[serde(rename = "ESTABLISHMENT_PENDING")]
+    EstablishmentPending,
+    #[serde(rename = "ACTIVE")]
+    Active,
+    /// The NG-RAN is modifying the resources of the session
+    #[serde(rename = "MODIFICATION_PENDING")]
+    ModificationPending,
+    /// The NG-RAN is releasing the resources of the session
+    #[serde(rename = "RELEASE_PENDING")]
+    ReleasePending,
+    /// The SM context is removed from the store
+    #[serde(rename = "RELEASED")]
+    Released,
+}
+
+impl std::fmt::Display for SessionState {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SessionState::EstablishmentPending => write!(f, "ESTABLISHMENT_PENDING"),
+            SessionState::Active => write!(f, "ACTIVE"),
+            SessionState::ModificationPending => write!(f, "MODIFICATION_PENDING"),
+            SessionState::ReleasePending => write!(f, "RELEASE_PENDING"),
+            SessionState::Released => write!(f, "RELEASED"),
+        }
+    }
+}
+
+/// A QoS flow of a PDU session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct QosFlow {
+    /// QoS Flow Identifier, within the range 0 to 63
+    #[serde(rename = "qfi")]
+    pub qfi: u8,
+
+    #[serde(rename = "qosFlowProfile")]
+    pub qos_flow_profile: models::QosFlowProfile,
+
+    /// Whether the flow carries the default QoS rule of the session
+    #[serde(rename = "defaultQosRuleInd")]
+    pub default_qos_rule_ind: bool,
+}
+
+/// The SM context of a PDU session.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PduSession {
+    /// Allocated by the store, empty until the context is stored
+    #[serde(rename = "smContextRef")]
+    pub sm_context_ref: String,
+
+    /// e.g. `imsi-001011234567895`
+    #[serde(rename = "supi")]
+    pub supi: String,
+
+    #[serde(rename = "pei")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pei: Option<String>,
+
+    #[serde(rename = "gpsi")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gpsi: Option<String>,
+
+    #[serde(rename = "pduSessionId")]
+    pub pdu_session_id: u8,
+
+    #[serde(rename = "sNssai")]
+    pub s_nssai: models::Snssai,
+
+    #[serde(rename = "dnn")]
+    pub dnn: String,
+
+    #[serde(rename = "anType")]
+    pub an_type: AccessType,
+
+    #[serde(rename = "pduSessionType")]
+    pub pdu_session_type: models::PduSessionType,
+
+    #[serde(rename = "sessionAmbr")]
+    pub session_ambr: models::Ambr,
+
+    #[serde(rename = "sscMode")]
+    pub ssc_mode: nas::SscMode,
+
+    /// Allocated to the UE, none until the SMF gives it one
+    #[serde(rename = "pduAddress")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pdu_address: Option<nas::PduAddress>,
+
+    /// The /64 prefix allocated to the UE of an IPv6 session, advertised to
+    /// it on the user plane rather than in the NAS
+    #[serde(rename = "ipv6Prefix")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipv6_prefix: Option<Ipv6Addr>,
+
+    /// The N3 endpoint of the UPF, none until the SMF selects one
+    #[serde(rename = "ulTunnel")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ul_tunnel: Option<ngap::GtpTunnel>,
+
+    /// The N3 endpoint of the NG-RAN, learned from its PDU Session Resource
+    /// Setup Response Transfer
+    #[serde(rename = "dlTunnel")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dl_tunnel: Option<ngap::GtpTunnel>,
+
+    #[serde(rename = "state")]
+    pub state: SessionState,
+
+    #[serde(rename = "upCnxState")]
+    pub up_cnx_state: UpCnxStateAnyOf,
+
+    /// State of the N2 handover of the session, if any
+    #[serde(rename = "hoState")]
+    pub ho_state: HoStateAnyOf,
+
+    #[serde(rename = "qosFlows")]
+    pub qos_flows: Vec<QosFlow>,
+
+    /// Where the AMF is notified of the status of the context
+    #[serde(rename = "smContextStatusUri")]
+    pub sm_context_status_uri: String,
+
+    #[serde(rename = "created")]
+    pub created: DateTime<Utc>,
+}
+
+/// What the SMF answers an update of an SM context with: the states it
+/// reports to the AMF, the N2 SM information it has the AMF forward to the
+/// NG-RAN, and the N1 SM message it has the AMF forward to the UE. An update
+/// answered with none is answered with 204 No Content.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Updated {
+    pub up_cnx_state: Option<UpCnxStateAnyOf>,
+    pub ho_state: Option<HoStateAnyOf>,
+    pub n2_sm_info_type: Option<N2SmInfoTypeAnyOf>,
+    pub n1_sm_msg: Option<nas::Message>,
+}
+
+impl Updated {
+    /// The content of the response, `None` for 204 No Content. The N2 SM
+    /// information is referenced as the `ngap-sm` part, the N1 SM message as
+    /// the `5gnas-sm` one.
+    pub fn updated_data(&self) -> Option<models::SmContextUpdatedData> {
+        if self == &Updated::default() {
+            return None;
+        }
+        let mut data = models::SmContextUpdatedData::new();
+        data.up_cnx_state = self
+            .up_cnx_state
+            .map(|state| models::UpCnxState::new(state.to_string()));
+        data.ho_state = self
+            .ho_state
+            .map(|state| models::HoState::new(state.to_string()));
+        if let Some(n2_sm_info_type) = self.n2_sm_info_type {
+            data.n2_sm_info = Some(models::RefToBinaryData::new("ngap-sm".to_string()));
+            data.n2_sm_info_type = Some(models::N2SmInfoType::new(n2_sm_info_type.to_string()));
+        }
+        if self.n1_sm_msg.is_some() {
+            data.n1_sm_msg = Some(models::RefToBinaryData::new("5gnas-sm".to_string()));
+        }
+        Some(data)
+    }
+}
+
+impl PduSession {
+    /// A PDU session being established: its user plane is activated once
+    /// the NG-RAN has set up its resources.
+    pub fn new(
+        supi: String,
+        pdu_session_id: u8,
+        s_nssai: models::Snssai,
+        dnn: String,
+        an_type: AccessType,
+        sm_context_status_uri: String,
+    ) -> PduSession {
+        PduSession {
+            sm_context_ref: String::new(),
+            supi,
+            pei: None,
+            gpsi: None,
+            pdu_session_id,
+            s_nssai,
+            dnn,
+            an_type,
+            pdu_session_type: models::PduSessionType::new("IPV4".to_string()),
+            session_ambr: models::Ambr::new("1 Gbps".to_string(), "1 Gbps".to_string()),
+            ssc_mode: nas::SscMode::Mode1,
+            pdu_address: None,
+            ipv6_prefix: None,
+            ul_tunnel: None,
+            dl_tunnel: None,
+            state: SessionState::EstablishmentPending,
+            up_cnx_state: UpCnxStateAnyOf::Activating,
+            ho_state: HoStateAnyOf::None,
+            qos_flows: Vec::new(),
+            sm_context_status_uri,
+            created: Utc::now(),
+        }
+    }
+
+    /// The PDU session the AMF asks for.
+    pub fn from_create_data(
+        data: &models::SmContextCreateData,
+    ) -> Result<PduSession, SmContextError> {
+        let mut session = PduSession::new(
+            data.supi.clone().ok_or(SmContextError::MissingIe("supi"))?,
+            data.pdu_session_id
+                .ok_or(SmContextError::MissingIe("pduSessionId"))?,
+            data.s_nssai
+                .clone()
+                .ok_or(SmContextError::MissingIe("sNssai"))?,
+            data.dnn.clone().ok_or(SmContextError::MissingIe("dnn"))?,
+            data.an_type,
+            data.sm_context_status_uri.clone(),
+        );
+        session.pei = data.pei.clone();
+        session.gpsi = data.gpsi.clone();
+        Ok(session)
+    }
+
+    /// Takes the N1 SM message of the creation of the context, the PDU
+    /// Session Establishment Request of the UE, for the SSC mode it asks
+    /// for. The request is kept to be answered once the session is set up.
+    pub fn establishment_request(
+        &mut self,
+        n1_sm_msg: &[u8],
+    ) -> Result<nas::Message, SmContextError> {
+        let message = nas::Message::decode(n1_sm_msg).map_err(SmContextError::InvalidN1SmMsg)?;
+        match &message.body {
+            nas::Body::EstablishmentRequest(request)
+                if message.pdu_session_id == self.pdu_session_id =>
+            {
+                if let Some(ssc_mode) = request.ssc_mode {
+                    self.ssc_mode = ssc_mode;
+                }
+                Ok(message)
+            }
+            _ => Err(SmContextError::UnexpectedN1SmMsg(self.state)),
+        }
+    }
+
+    /// The PDU Session Establishment Accept of the session as set up, with
+    /// the DNS servers if the UE asks for them.
+    pub fn establishment_accept(
+        &self,
+        request: &nas::Message,
+        dns_servers: &[Ipv4Addr],
+    ) -> nas::Message {
+        let dns_requested = match &request.body {
+            nas::Body::EstablishmentRequest(request) => request
+                .extended_pco
+                .as_ref()
+                .is_some_and(|pco| pco.contains(nas::ExtendedPco::DNS_SERVER_IPV4_ADDRESS)),
+            _ => false,
+        };
+        let extended_pco = (dns_requested && !dns_servers.is_empty()).then(|| {
+            let mut pco = nas::ExtendedPco::default();
+            for dns_server in dns_servers {
+                pco.push(
+                    nas::ExtendedPco::DNS_SERVER_IPV4_ADDRESS,
+                    dns_server.octets().to_vec(),
+                );
+            }
+            pco
+        });
+        nas::Message {
+            pdu_session_id: self.pdu_session_id,
+            pti: request.pti,
+            body: nas::Body::EstablishmentAccept(nas::EstablishmentAccept {
+                pdu_session_type: self.nas_pdu_session_type(),
+                ssc_mode: self.ssc_mode,
+                authorized_qos_rules: self.qos_rules(),
+                session_ambr: self.nas_session_ambr(),
+                cause: None,
+                pdu_address: self.pdu_address,
+                s_nssai: Some(nas::Snssai::from(&self.s_nssai)),
+                authorized_qos_flow_descriptions: self.qos_flow_descriptions(),
+                extended_pco,
+                dnn: Some(self.dnn.clone()),
+            }),
+        }
+    }
+
+    /// The PDU session type of the session, as signalled in the NAS.
+    fn nas_pdu_session_type(&self) -> nas::PduSessionType {
+        self.pdu_session_type
+            .to_string()
+            .parse()
+            .unwrap_or(nas::PduSessionType::Ipv4)
+    }
+
+    /// Who the UE addresses of the session are allocated to.
+    fn owner(&self) -> String {
+        format!("{}/{}", self.supi, self.pdu_session_id)
+    }
+
+    /// The default QoS rule, matching all the packets of the session, for
+    /// the default QoS flow.
+    fn qos_rules(&self) -> Vec<nas::QosRule> {
+        self.qos_flows
+            .iter()
+            .filter(|flow| flow.default_qos_rule_ind)
+            .map(|flow| nas::QosRule {
+                identifier: 1,
+                operation: nas::RuleOperation::Create,
+                default: true,
+                packet_filters: vec![nas::PacketFilter::match_all(1)],
+                precedence: Some(255),
+                segregation: false,
+                qfi: Some(flow.qfi),
+            })
+            .collect()
+    }
+
+    fn qos_flow_descriptions(&self) -> Vec<nas::QosFlowDescription> {
+        self.qos_flows
+            .iter()
+            .map(|flow| nas::QosFlowDescription::create(flow.qfi, flow.qos_flow_profile.param_5qi))
+            .collect()
+    }
+
+    fn nas_session_ambr(&self) -> nas::SessionAmbr {
+        let bitrate = |bitrate: &str| {
+            bitrate
+                .parse()
+                .unwrap_or_else(|_| nas::Bitrate::from_kbps(0))
+        };
+        nas::SessionAmbr {
+            downlink: bitrate(&self.session_ambr.downlink),
+            uplink: bitrate(&self.session_ambr.uplink),
+        }
+    }
+
+    /// The PDU Session Resource Setup Request Transfer of the session, for
+    /// the NG-RAN to set up its resources towards the UPF; `None` until the
+    /// SMF has selected the N3 endpoint of the UPF.
+    pub fn setup_request_transfer(&self) -> Option<ngap::PduSessionResourceSetupRequestTransfer> {
+        Some(ngap::PduSessionResourceSetupRequestTransfer {
+            pdu_session_aggregate_maximum_bit_rate:
+                ngap::PduSessionAggregateMaximumBitRate::from_ambr(&self.session_ambr),
+            ul_ngu_up_tnl_information: self.ul_tunnel?,
+            pdu_session_type: self.nas_pdu_session_type(),
+            qos_flow_setup_request_list: self
+                .qos_flows
+                .iter()
+                .map(|flow| ngap::QosFlowSetupRequestItem {
+                    qfi: flow.qfi,
+                    qos_flow_level_qos_parameters: ngap::QosFlowLevelQosParameters::from_profile(
+                        &flow.qos_flow_profile,
+                    ),
+                })
+                .collect(),
+        })
+    }
+
+    /// The N2 SM information of a type the SMF sends to the NG-RAN, encoded
+    /// as the `ngap-sm` binary part; `None` for the types the SMF does not
+    /// build.
+    pub fn n2_sm_info_transfer(&self, n2_sm_info_type: N2SmInfoTypeAnyOf) -> Option<Vec<u8>> {
+        match n2_sm_info_type {
+            N2SmInfoTypeAnyOf::PduResSetupReq => self
+                .setup_request_transfer()
+                .map(|transfer| transfer.encode()),
+            N2SmInfoTypeAnyOf::PduResModReq => Some(
+                ngap::PduSessionResourceModifyRequestTransfer {
+                    pdu_session_aggregate_maximum_bit_rate:
+                        ngap::PduSessionAggregateMaximumBitRate::from_ambr(&self.session_ambr),
+                    qos_flow_add_or_modify_request_list: self
+                        .qos_flows
+                        .iter()
+                        .map(|flow| ngap::QosFlowAddOrModifyRequestItem {
+                            qfi: flow.qfi,
+                            qos_flow_level_qos_parameters: Some(
+                                ngap::QosFlowLevelQosParameters::from_profile(
+                                    &flow.qos_flow_profile,
+                                ),
+                            ),
+                        })
+                        .collect(),
+                    qos_flow_to_release_list: Vec::new(),
+                }
+                .encode(),
+            ),
+            N2SmInfoTypeAnyOf::PduResRelCmd => Some(
+                ngap::PduSessionResourceReleaseCommandTransfer {
+                    cause: ngap::Cause::NAS_NORMAL_RELEASE,
+                }
+                .encode(),
+            ),
+            _ => None,
+        }
+    }
+
+    /// Applies the subscribed configuration of the DNN: the PDU session
+    /// type, the session AMBR and the default QoS flow.
+    pub fn apply_dnn_configuration(&mut self, configuration: &nudm_sdm::models::DnnConfiguration) {
+        if let Some(session_type) = &configuration.pdu_session_types.default_session_type {
+            self.pdu_session_type = models::PduSessionType::new(session_type.to_string());
+        }
+        if let Some(ambr) = &configuration.session_ambr {
+            self.session_ambr = models::Ambr::new(ambr.uplink.clone(), ambr.downlink.clone());
+        }
+        if let Some(qos) = &configuration.param_5g_qos_profile {
+            let mut qos_flow_profile = models::QosFlowProfile::new(qos.param_5qi);
+            qos_flow_profile.arp = Some(models::Arp::new(
+                qos.arp.priority_level.clone(),
+                models::PreemptionCapability::new(qos.arp.preempt_cap.to_string()),
+                models::PreemptionVulnerability::new(qos.arp.preempt_vuln.to_string()),
+            ));
+            self.set_default_qos_flow(qos_flow_profile);
+        }
+    }
+
+    /// Sets the QoS of the default QoS flow, QFI 1 as in the QoS rules sent
+    /// to the UE.
+    pub fn set_default_qos_flow(&mut self, qos_flow_profile: models::QosFlowProfile) {
+        self.qos_flows.retain(|flow| !flow.default_qos_rule_ind);
+        self.qos_flows.insert(
+            0,
+            QosFlow {
+                qfi: 1,
+                qos_flow_profile,
+                default_qos_rule_ind: true,
+            },
+        );
+    }
+
+    /// The UP connection state as sent to the AMF.
+    pub fn up_cnx_state(&self) -> models::UpCnxState {
+        models::UpCnxState::new(self.up_cnx_state.to_string())
+    }
+
+    /// The SM context as retrieved by an AMF or another SMF. The QoS rules
+    /// are left empty: they are only sent to the UE, in the NAS messages.
+    pub fn sm_context(&self) -> models::SmContext {
+        let qos_flows_list = self
+            .qos_flows
+            .iter()
+            .map(|flow| {
+                let mut item =
+                    models::QosFlowSetupItem::new(flow.qfi, swagger::ByteArray(Vec::new()));
+                item.qos_flow_profile = Some(flow.qos_flow_profile.clone());
+                item.default_qos_rule_ind = Some(flow.default_qos_rule_ind);
+                item
+            })
+            .collect();
+        let mut sm_context = models::SmContext::new(
+            self.pdu_session_id,
+            self.dnn.clone(),
+            self.s_nssai.clone(),
+            self.pdu_session_type.clone(),
+            self.session_ambr.clone(),
+            qos_flows_list,
+        );
+        sm_context.gpsi = self.gpsi.clone();
+        sm_context
+    }
+
+    /// Applies an update of the SM context, with the content of its N1 SM
+    /// message and of its N2 SM information if any. At most one transition
+    /// is taken, from the first of `release`, `hoState`, `n2SmInfoType`,
+    /// `upCnxState` and `n1SmMsg` present; an update without any, e.g. of
+    /// the UE location, leaves the state as is.
+    pub fn update(
+        &mut self,
+        update: &models::SmContextUpdateData,
+        n1_sm_msg: Option<&[u8]>,
+        n2_sm_info: Option<&[u8]>,
+    ) -> Result<Updated, SmContextError> {
+        let n2_sm_info_type = parse(update.n2_sm_info_type.as_ref(), "n2SmInfoType")?;
+        let up_cnx_state = parse(update.up_cnx_state.as_ref(), "upCnxState")?;
+        let ho_state = parse(update.ho_state.as_ref(), "hoState")?;
+
+        if let Some(pei) = &update.pei {
+            self.pei = Some(pei.clone());
+        }
+
+        if update.release == Some(true) {
+            self.release_requested()
+        } else if let Some(ho_state) = ho_state {
+            self.handover(ho_state, n2_sm_info_type)
+        } else if let Some(n2_sm_info_type) = n2_sm_info_type {
+            self.n2_sm_info(n2_sm_info_type, n2_sm_info)
+        } else if let Some(up_cnx_state) = up_cnx_state {
+            self.up_cnx_state_requested(up_cnx_state)
+        } else if let Some(n1_sm_msg) = n1_sm_msg {
+            let message =
+                nas::Message::decode(n1_sm_msg).map_err(SmContextError::InvalidN1SmMsg)?;
+            self.n1_sm_msg(&message)
+        } else if update.n1_sm_msg.is_some() {
+            self.modification_requested()
+        } else {
+            Ok(Updated::default())
+        }
+    }
+
+    /// The AMF asks for the release of the session, e.g. on a PDU Session
+    /// Release Request of the UE (TS 23.502 clause 4.3.4.2). The NG-RAN
+    /// releases its resources first, if it has any.
+    fn release_requested(&mut self) -> Result<Updated, SmContextError> {
+        match self.state {
+            SessionState::EstablishmentPending
+            | SessionState::Active
+            | SessionState::ModificationPending => {
+                if self.up_cnx_state == UpCnxStateAnyOf::Deactivated {
+                    self.state = SessionState::Released;
+                    return Ok(Updated::default());
+                }
+                self.state = SessionState::ReleasePending;
+                Ok(Updated {
+                    n2_sm_info_type: Some(N2SmInfoTypeAnyOf::PduResRelCmd),
+                    ..Updated::default()
+                })
+            }
+            _ => Err(self.invalid_transition("release", "true")),
+        }
+    }
+
+    /// N2 handover of the session from the source to the target NG-RAN
+    /// (TS 23.502 clause 4.9.1.3): preparation, then execution or
+    /// cancellation.
+    fn handover(
+        &mut self,
+        ho_state: HoStateAnyOf,
+        n2_sm_info_type: Option<N2SmInfoTypeAnyOf>,
+    ) -> Result<Updated, SmContextError> {
+        if self.state != SessionState::Active {
+            return Err(self.invalid_transition("hoState", &ho_state.to_string()));
+        }
+        let state = self.state;
+        let expected_n2_sm_info = |expected: N2SmInfoTypeAnyOf| match n2_sm_info_type {
+            Some(n2_sm_info_type) if n2_sm_info_type != expected => {
+                Err(SmContextError::UnexpectedN2SmInfo {
+                    state,
+                    n2_sm_info_type,
+                })
+            }
+            _ => Ok(()),
+        };
+
+        let n2_sm_info_type = match (self.ho_state, ho_state) {
+            (
+                HoStateAnyOf::None | HoStateAnyOf::Completed | HoStateAnyOf::Cancelled,
+                HoStateAnyOf::Preparing,
+            ) => {
+                expected_n2_sm_info(N2SmInfoTypeAnyOf::HandoverRequired)?;
+                Some(N2SmInfoTypeAnyOf::PduResSetupReq)
+            }
+            (HoStateAnyOf::Preparing, HoStateAnyOf::Prepared) => {
+                expected_n2_sm_info(N2SmInfoTypeAnyOf::HandoverReqAck)?;
+                Some(N2SmInfoTypeAnyOf::HandoverCmd)
+            }
+            (HoStateAnyOf::Prepared, HoStateAnyOf::Completed) => None,
+            (HoStateAnyOf::Preparing | HoStateAnyOf::Prepared, HoStateAnyOf::Cancelled) => None,
+            _ => return Err(self.invalid_transition("hoState", &ho_state.to_string())),
+        };
+        self.ho_state = ho_state;
+        Ok(Updated {
+            ho_state: Some(ho_state),
+            n2_sm_info_type,
+            ..Updated::default()
+        })
+    }
+
+    /// The NG-RAN answers a request of the SMF for its resources. Its N3
+    /// endpoint is taken from the setup response transfer, when the SMF has
+    /// the content of it.
+    fn n2_sm_info(
+        &mut self,
+        n2_sm_info_type: N2SmInfoTypeAnyOf,
+        n2_sm_info: Option<&[u8]>,
+    ) -> Result<Updated, SmContextError> {
+        if let (N2SmInfoTypeAnyOf::PduResSetupRsp, Some(n2_sm_info)) = (n2_sm_info_type, n2_sm_info)
+        {
+            let transfer = ngap::PduSessionResourceSetupResponseTransfer::decode(n2_sm_info)
+                .map_err(SmContextError::InvalidN2SmInfo)?;
+            self.dl_tunnel = Some(
+                transfer
+                    .dl_qos_flow_per_tnl_information
+                    .up_transport_layer_information,
+            );
+        }
+        match (self.state, n2_sm_info_type) {
+            // PDU session establishment (TS 23.502 clause 4.3.2.2.1)
+            (SessionState::EstablishmentPending, N2SmInfoTypeAnyOf::PduResSetupRsp) => {
+                self.state = SessionState::Active;
+                self.up_cnx_state = UpCnxStateAnyOf::Activated;
+            }
+            (SessionState::EstablishmentPending, N2SmInfoTypeAnyOf::PduResSetupFail) => {
+                self.state = SessionState::Released;
+                self.up_cnx_state = UpCnxStateAnyOf::Deactivated;
+            }
+            // Service request (TS 23.502 clause 4.2.3)
+            (SessionState::Active, N2SmInfoTypeAnyOf::PduResSetupRsp)
+                if self.up_cnx_state == UpCnxStateAnyOf::Activating =>
+            {
+                self.up_cnx_state = UpCnxStateAnyOf::Activated;
+            }
+            (SessionState::Active, N2SmInfoTypeAnyOf::PduResSetupFail)
+                if self.up_cnx_state == UpCnxStateAnyOf::Activating =>
+            {
+                self.up_cnx_state = UpCnxStateAnyOf::Deactivated;
+            }
+            // Xn based handover (TS 23.502 clause 4.9.1.2)
+            (SessionState::Active, N2SmInfoTypeAnyOf::PathSwitchReq)
+                if self.up_cnx_state == UpCnxStateAnyOf::Activated =>
+            {
+                return Ok(Updated {
+                    n2_sm_info_type: Some(N2SmInfoTypeAnyOf::PathSwitchReqAck),
+                    ..Updated::default()
+                });
+            }
+            // PDU session modification (TS 23.502 clause 4.3.3.2): a failure
+            // leaves the session as it was
+            (
+                SessionState::ModificationPending,
+                N2SmInfoTypeAnyOf::PduResModRsp | N2SmInfoTypeAnyOf::PduResModFail,
+            ) => {
+                self.state = SessionState::Active;
+            }
+            // PDU session release (TS 23.502 clause 4.3.4.2)
+            (SessionState::ReleasePending, N2SmInfoTypeAnyOf::PduResRelRsp) => {
+                self.state = SessionState::Released;
+                self.up_cnx_state = UpCnxStateAnyOf::Deactivated;
+            }
+            (state, n2_sm_info_type) => {
+                return Err(SmContextError::UnexpectedN2SmInfo {
+                    state,
+                    n2_sm_info_type,
+                })
+            }
+        }
+        Ok(Updated::default())
+    }
+
+    /// The AMF deactivates the user plane of the session, on an AN release,
+    /// or activates it again, on a service request (TS 23.502 clauses 4.2.6
+    /// and 4.2.3).
+    fn up_cnx_state_requested(
+        &mut self,
+        up_cnx_state: UpCnxStateAnyOf,
+    ) -> Result<Updated, SmContextError> {
+        match (self.state, up_cnx_state) {
+            // A pending modification is abandoned along with the resources
+            (
+                SessionState::Active | SessionState::ModificationPending,
+                UpCnxStateAnyOf::Deactivated,
+            ) => {
+                self.state = SessionState::Active;
+                self.up_cnx_state = UpCnxStateAnyOf::Deactivated;
+                Ok(Updated {
+                    up_cnx_state: Some(UpCnxStateAnyOf::Deactivated),
+                    ..Updated::default()
+                })
+            }
+            (SessionState::Active, UpCnxStateAnyOf::Activating)
+                if self.up_cnx_state != UpCnxStateAnyOf::Activated =>
+            {
+                self.up_cnx_state = UpCnxStateAnyOf::Activating;
+                Ok(Updated {
+                    up_cnx_state: Some(UpCnxStateAnyOf::Activating),
+                    n2_sm_info_type: Some(N2SmInfoTypeAnyOf::PduResSetupReq),
+                    ..Updated::default()
+                })
+            }
+            _ => Err(self.invalid_transition("upCnxState", &up_cnx_state.to_string())),
+        }
+    }
+
+    /// The UE asks for the modification or the release of the session, or
+    /// answers a command of the SMF.
+    fn n1_sm_msg(&mut self, message: &nas::Message) -> Result<Updated, SmContextError> {
+        if message.pdu_session_id != self.pdu_session_id {
+            return Err(SmContextError::UnexpectedN1SmMsg(self.state));
+        }
+        match &message.body {
+            nas::Body::ModificationRequest(_) => {
+                let mut updated = self.modification_requested()?;
+                updated.n1_sm_msg = Some(nas::Message {
+                    pdu_session_id: self.pdu_session_id,
+                    pti: message.pti,
+                    body: nas::Body::ModificationCommand(nas::ModificationCommand {
+                        session_ambr: Some(self.nas_session_ambr()),
+                        ..nas::ModificationCommand::default()
+                    }),
+                });
+                Ok(updated)
+            }
+            // UE requested PDU session release (TS 23.502 clause 4.3.4.2)
+            nas::Body::ReleaseRequest(_) => {
+                let mut updated = self.release_requested()?;
+                updated.n1_sm_msg = Some(nas::Message {
+                    pdu_session_id: self.pdu_session_id,
+                    pti: message.pti,
+                    body: nas::Body::ReleaseCommand(nas::ReleaseCommand {
+                        cause: nas::Cause::REGULAR_DEACTIVATION,
+                        back_off_timer: None,
+                        extended_pco: None,
+                    }),
+                });
+                Ok(updated)
+            }
+            nas::Body::ModificationComplete(_) | nas::Body::ModificationCommandReject(_)
+                if matches!(
+                    self.state,
+                    SessionState::Active | SessionState::ModificationPending
+                ) =>
+            {
+                Ok(Updated::default())
+            }
+            nas::Body::ReleaseComplete(_) if self.state == SessionState::ReleasePending => {
+                Ok(Updated::default())
+            }
+            _ => Err(SmContextError::UnexpectedN1SmMsg(self.state)),
+        }
+    }
+
+    /// The UE asks for the modification of an active session (TS 23.502
+    /// clause 4.3.3.2). An N1 SM message the SMF does not have the content
+    /// of is taken for a PDU Session Modification Request.
+    fn modification_requested(&mut self) -> Result<Updated, SmContextError> {
+        if self.state != SessionState::Active || self.up_cnx_state != UpCnxStateAnyOf::Activated {
+            return Err(SmContextError::UnexpectedN1SmMsg(self.state));
+        }
+        self.state = SessionState::ModificationPending;
+        Ok(Updated {
+            n2_sm_info_type: Some(N2SmInfoTypeAnyOf::PduResModReq),
+            ..Updated::default()
+        })
+    }
+
+    fn invalid_transition(&self, param: &'static str, value: &str) -> SmContextError {
+        SmContextError::InvalidTransition {
+            state: self.state,
+            param,
+            value: value.to_string(),
+        }
+    }
+}
+
+/// An enumerated IE of an update, `None` if absent.
+fn parse<T, V>(value: Option<&V>, param: &'static str) -> Result<Option<T>, SmContextError>
+where
+    T: std::str::FromStr,
+    V: ToString,
+{
+    value
+        .map(|value| {
+            let value = value.to_string();
+            value
+                .parse()
+                .map_err(|_| SmContextError::InvalidIe { param, value })
+        })
+        .transpose()
+}
+
+/// Thread-safe handle to the SM contexts, keyed by smContextRef, and to the
+/// pools their UE addresses are allocated from.
+#[derive(Debug, Clone, Default)]
+pub struct SmContextStore {
+    contexts: Arc<RwLock<HashMap<String, PduSession>>>,
+    last_ref: Arc<AtomicU64>,
+    last_teid: Arc<AtomicU32>,
+    pools: IpPools,
+}
+
+impl SmContextStore {
+    pub fn new() -> SmContextStore {
+        SmContextStore::default()
+    }
+
+    pub fn with_pools(pools: IpPools) -> SmContextStore {
+        SmContextStore {
+            pools,
+            ..SmContextStore::default()
+        }
+    }
+
+    /// A TEID of the UPF for the N3 tunnel of a new session, never 0.
+    pub fn allocate_teid(&self) -> u32 {
+        loop {
+            let teid = self
+                .last_teid
+                .fetch_add(1, Ordering::Relaxed)
+                .wrapping_add(1);
+            if teid != 0 {
+                return teid;
+            }
+        }
+    }
+
+    /// Stores the context of a new PDU session under a new smContextRef, with
+    /// the addresses allocated to its UE, and returns it as stored along with
+    /// the context it replaces: the one of the PDU session of the UE with the
+    /// same id, which the UE no longer has (TS 23.502 clause 4.3.2.2.1) and
+    /// whose addresses the new one keeps if it can.
+    pub fn create(
+        &self,
+        mut session: PduSession,
+    ) -> Result<(PduSession, Option<PduSession>), SmContextError> {
+        let allocation = self
+            .pools
+            .allocate(
+                &session.owner(),
+                &session.dnn,
+                session.nas_pdu_session_type(),
+            )
+            .ok_or_else(|| SmContextError::InsufficientResources(session.dnn.clone()))?;
+        session.pdu_address = allocation.pdu_address();
+        session.ipv6_prefix = allocation.ipv6_prefix;
+        session.sm_context_ref = (self.last_ref.fetch_add(1, Ordering::Relaxed) + 1).to_string();
+        let mut contexts = self.contexts.write().expect("SM context lock poisoned");
+        let replaced = contexts
+            .iter()
+            .find(|(_, s)| s.supi == session.supi && s.pdu_session_id == session.pdu_session_id)
+            .map(|(sm_context_ref, _)| sm_context_ref.clone())
+            .and_then(|sm_context_ref| contexts.remove(&sm_context_ref));
+        contexts.insert(session.sm_context_ref.clone(), session.clone());
+        Ok((session, replaced))
+    }
+
+    /// Applies an update to a context, and returns the session as updated
+    /// with the answer to the AMF. A released session is removed, and the
+    /// addresses of its UE taken back; a rejected update leaves the session
+    /// as it was.
+    pub fn update(
+        &self,
+        sm_context_ref: &str,
+        update: &models::SmContextUpdateData,
+        n1_sm_msg: Option<&[u8]>,
+        n2_sm_info: Option<&[u8]>,
+    ) -> Result<(PduSession, Updated), SmContextError> {
+        let mut contexts = self.contexts.write().expect("SM context lock poisoned");
+        let stored = contexts
+            .get_mut(sm_context_ref)
+            .ok_or_else(|| SmContextError::NotFound(sm_context_ref.to_string()))?;
+        let mut session = stored.clone();
+        let updated = session.update(update, n1_sm_msg, n2_sm_info)?;
+        if session.state == SessionState::Released {
+            contexts.remove(sm_context_ref);
+            self.pools.release(&session.owner());
+        } else {
+            *stored = session.clone();
+        }
+        Ok((session, updated))
+    }
+
+    /// Releases a session whatever its state, e.g. when the UE deregisters
+    /// (TS 23.502 clause 4.3.4.2): the AMF takes care of the NG-RAN.
+    pub fn release(&self, sm_context_ref: &str) -> Result<PduSession, SmContextError> {
+        let mut session = self
+            .remove(sm_context_ref)
+            .ok_or_else(|| SmContextError::NotFound(sm_context_ref.to_string()))?;
+        session.state = SessionState::Released;
+        Ok(session)
+    }
+
+    /// Removes a context, and takes back the addresses of its UE.
+    pub fn remove(&self, sm_context_ref: &str) -> Option<PduSession> {
+        let session = self
+            .contexts
+            .write()
+            .expect("SM context lock poisoned")
+            .remove(sm_context_ref)?;
+        self.pools.release(&session.owner());
+        Some(session)
+    }
+
+    pub fn get(&self, sm_context_ref: &str) -> Result<PduSession, SmContextError> {
+        self.contexts
+            .read()
+            .expect("SM context lock poisoned")
+            .get(sm_context_ref)
+            .cloned()
+            .ok_or_else(|| SmContextError::NotFound(sm_context_ref.to_string()))
+    }
+
+    /// The context of a PDU session of a UE.
+    pub fn find(&self, supi: &str, pdu_session_id: u8) -> Option<PduSession> {
+        self.contexts
+            .read()
+            .expect("SM context lock poisoned")
+            .values()
+            .find(|s| s.supi == supi && s.pdu_session_id == pdu_session_id)
+            .cloned()
+    }
+
+    pub fn len(&self) -> usize {
+        self.contexts
+            .read()
+            .expect("SM context lock poisoned")
+            .len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SmContextError {
+    /// No SM context has the smContextRef
+    NotFound(String),
+    /// A mandatory IE is missing from the request
+    MissingIe(&'static str),
+    /// An IE has a value the SMF does not know
+    InvalidIe { param: &'static str, value: String },
+    /// The state of the session does not allow the update
+    InvalidTransition {
+        state: SessionState,
+        param: &'static str,
+        value: String,
+    },
+    /// The NG-RAN sent N2 SM information the SMF did not ask for
+    UnexpectedN2SmInfo {
+        state: SessionState,
+        n2_sm_info_type: N2SmInfoTypeAnyOf,
+    },
+    /// The UE sent an N1 SM message the state of the session does not allow
+    UnexpectedN1SmMsg(SessionState),
+    /// The N1 SM message is not a 5GSM message the SMF can decode
+    InvalidN1SmMsg(nas::DecodeError),
+    /// The N2 SM information is not a transfer the SMF can decode
+    InvalidN2SmInfo(ngap::DecodeError),
+    /// The pools of the DNN have no address left for the UE
+    InsufficientResources(String),
+}
+
+impl std::fmt::Display for SmContextError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SmContextError::NotFound(sm_context_ref) => {
+                write!(f, "Unknown SM context {}", sm_context_ref)
+            }
+            SmContextError::MissingIe(param) => write!(f, "Missing {}", param),
+            SmContextError::InvalidIe { param, value } => {
+                write!(f, "Invalid {} {}", param, value)
+            }
+            SmContextError::InvalidTransition {
+                state,
+                param,
+                value,
+            } => write!(f, "{} {} not allowed in state {}", param, value, state),
+            SmContextError::UnexpectedN2SmInfo {
+                state,
+                n2_sm_info_type,
+            } => write!(f, "Unexpected {} in state {}", n2_sm_info_type, state),
+            SmContextError::UnexpectedN1SmMsg(state) => {
+                write!(f, "Unexpected N1 SM message in state {}", state)
+            }
+            SmContextError::InvalidN1SmMsg(e) => write!(f, "Invalid N1 SM message: {}", e),
+            SmContextError::InvalidN2SmInfo(e) => write!(f, "Invalid N2 SM information: {}", e),
+            SmContextError::InsufficientResources(dnn) => {
+                write!(f, "No UE address left for DNN {}", dnn)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SmContextError {}
+
+impl SmContextError {
+    /// The PDU Session Establishment Reject the UE is answered with when the
+    /// creation of its SM context fails.
+    pub fn establishment_reject(&self, request: &nas::Message) -> nas::Message {
+        let cause = match self {
+            SmContextError::InsufficientResources(_) => {
+                nas::Cause::INSUFFICIENT_RESOURCES_FOR_SPECIFIC_SLICE_AND_DNN
+            }
+            _ => nas::Cause::REQUEST_REJECTED_UNSPECIFIED,
+        };
+        nas::Message::establishment_reject(request.pdu_session_id, request.pti, cause)
+    }
+
+    /// The problem the SMF answers with (TS 29.502 clause 5.2.7 and TS
+    /// 29.500 clause 5.2.7.2).
+    pub fn problem(&self) -> models::ExtProblemDetails {
+        let (status, cause, param) = match self {
+            SmContextError::NotFound(_) => (404, "CONTEXT_NOT_FOUND", None),
+            SmContextError::MissingIe(param) => (400, "MANDATORY_IE_MISSING", Some(*param)),
+            SmContextError::InvalidIe { param, .. }
+            | SmContextError::InvalidTransition { param, .. } => {
+                (400, "MANDATORY_IE_INCORRECT", Some(*param))
+            }
+            SmContextError::UnexpectedN2SmInfo { .. } | SmContextError::InvalidN2SmInfo(_) => {
+                (403, "N2_SM_ERROR", None)
+            }
+            SmContextError::UnexpectedN1SmMsg(_) | SmContextError::InvalidN1SmMsg(_) => {
+                (403, "N1_SM_ERROR", None)
+            }
+            SmContextError::InsufficientResources(_) => {
+                (500, "INSUFFICIENT_RESOURCES_SLICE_DNN", None)
+            }
+        };
+        let mut problem = models::ExtProblemDetails::new();
+        problem.status = Some(status);
+        problem.cause = Some(cause.to_string());
+        problem.detail = Some(self.to_string());
+        problem.invalid_params =
+            param.map(|param| vec![models::InvalidParam::new(format!("/{}", param))]);
+        problem
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ip_pools::{MemoryStore, PoolConfig};
+
+    const SUPI: &str = "imsi-001011234567895";
+
+    fn session() -> PduSession {
+        PduSession::new(
+            SUPI.to_string(),
+            1,
+            models::Snssai::new(1),
+            "internet".to_string(),
+            AccessType::Variant3GppAccess,
+            "http://amf/sm-context-status/1".to_string(),
+        )
+    }
+
+    /// A session the NG-RAN has set up the resources of.
+    fn active() -> PduSession {
+        let mut session = session();
+        session
+            .update(&n2(N2SmInfoTypeAnyOf::PduResSetupRsp), None, None)
+            .unwrap();
+        session
+    }
+
+    fn n2(n2_sm_info_type: N2SmInfoTypeAnyOf) -> models::SmContextUpdateData {
+        let mut update = models::SmContextUpdateData::new();
+        update.n2_sm_info = Some(models::RefToBinaryData::new("ngap-sm".to_string()));
+        update.n2_sm_info_type = Some(models::N2SmInfoType::new(n2_sm_info_type.to_string()));
+        update
+    }
+
+    fn up_cnx_state(up_cnx_state: &str) -> models::SmContextUpdateData {
+        let mut update = models::SmContextUpdateData::new();
+        update.up_cnx_state = Some(models::UpCnxState::new(up_cnx_state.to_string()));
+        update
+    }
+
+    fn ho_state(
+        ho_state: HoStateAnyOf,
+        n2_sm_info_type: N2SmInfoTypeAnyOf,
+    ) -> models::SmContextUpdateData {
+        let mut update = n2(n2_sm_info_type);
+        update.ho_state = Some(models::HoState::new(ho_state.to_string()));
+        update
+    }
+
+    fn release() -> models::SmContextUpdateData {
+        let mut update = models::SmContextUpdateData::new();
+        update.release = Some(true);
+        update
+    }
+
+    fn n1(body: nas::Body) -> (models::SmContextUpdateData, Vec<u8>) {
+        let mut update = models::SmContextUpdateData::new();
+        update.n1_sm_msg = Some(models::RefToBinaryData::new("5gnas-sm".to_string()));
+        let message = nas::Message {
+            pdu_session_id: 1,
+            pti: 2,
+            body,
+        };
+        (update, message.encode())
+    }
+
+    /// Asserts the status and cause of the problem an update is rejected
+    /// with.
+    fn assert_problem(result: Result<Updated, SmContextError>, status: i32, cause: &str) {
+        let problem = result.expect_err("update rejected").problem();
+        assert_eq!(problem.status, Some(status));
+        assert_eq!(problem.cause.as_deref(), Some(cause));
+    }
+
+    #[test]
+    fn establishment() {
+        let mut session = session();
+        assert_eq!(session.state, SessionState::EstablishmentPending);
+        assert_eq!(session.up_cnx_state, UpCnxStateAnyOf::Activating);
+
+        let updated = session
+            .update(&n2(N2SmInfoTypeAnyOf::PduResSetupRsp), None, None)
+            .unwrap();
+        assert_eq!(updated.updated_data(), None);
+        assert_eq!(session.state, SessionState::Active);
+        assert_eq!(session.up_cnx_state, UpCnxStateAnyOf::Activated);
+    }
+
+    #[test]
+    fn establishment_failure() {
+        let mut session = session();
+        session
+            .update(&n2(N2SmInfoTypeAnyOf::PduResSetupFail), None, None)
+            .unwrap();
+        assert_eq!(session.state, SessionState::Released);
+        assert_eq!(session.up_cnx_state, UpCnxStateAnyOf::Deactivated);
+    }
+
+    #[test]
+    fn up_cnx_state_deactivation_and_activation() {
+        let mut session = active();
+
+        let updated = session
+            .update(&up_cnx_state("DEACTIVATED"), None, None)
+            .unwrap();
+        assert_eq!(
+            updated,
+            Updated {
+                up_cnx_state: Some(UpCnxStateAnyOf::Deactivated),
+                ..Updated::default()
+            }
+        );
+        assert_eq!(session.state, SessionState::Active);
+        assert_eq!(session.up_cnx_state, UpCnxStateAnyOf::Deactivated);
+
+        let updated = session
+            .update(&up_cnx_state("ACTIVATING"), None, None)
+            .unwrap();
+        assert_eq!(
+            updated,
+            Updated {
+                up_cnx_state: Some(UpCnxStateAnyOf::Activating),
+                n2_sm_info_type: Some(N2SmInfoTypeAnyOf::PduResSetupReq),
+                ..Updated::default()
+            }
+        );
+        assert_eq!(session.up_cnx_state, UpCnxStateAnyOf::Activating);
+
+        session
+            .update(&n2(N2SmInfoTypeAnyOf::PduResSetupRsp), None, None)
+            .unwrap();
+        assert_eq!(session.up_cnx_state, UpCnxStateAnyOf::Activated);
+    }
+
+    #[test]
+    fn handover() {
+        let mut session = active();
+
+        let updated = session
+            .update(
+                &ho_state(HoStateAnyOf::Preparing, N2SmInfoTypeAnyOf::HandoverRequired),
+                None,
+                None,
+            )
+            .unwrap();
+        assert_eq!(updated.ho_state, Some(HoStateAnyOf::Preparing));
+        assert_eq!(
+            updated.n2_sm_info_type,
+            Some(N2SmInfoTypeAnyOf::PduResSetupReq)
+        );
+
+        let updated = session
+            .update(
+                &ho_state(HoStateAnyOf::Prepared, N2SmInfoTypeAnyOf::HandoverReqAck),
+                None,
+                None,
+            )
+            .unwrap();
+        assert_eq!(updated.ho_state, Some(HoStateAnyOf::Prepared));
+        assert_eq!(
+            updated.n2_sm_info_type,
+            Some(N2SmInfoTypeAnyOf::HandoverCmd)
+        );
+
+        let mut update = models::SmContextUpdateData::new();
+        update.ho_state = Some(models::HoState::new("COMPLETED".to_string()));
+        let updated = session.update(&update, None, None).unwrap();
+        assert_eq!(
+            updated,
+            Updated {
+                ho_state: Some(HoStateAnyOf::Completed),
+                ..Updated::default()
+            }
+        );
+        assert_eq!(session.ho_state, HoStateAnyOf::Completed);
+        assert_eq!(session.state, SessionState::Active);
+    }
+
+    #[test]
+    fn release_by_the_amf() {
+        let mut session = active();
+
+        let updated = session.update(&release(), None, None).unwrap();
+        assert_eq!(
+            updated.n2_sm_info_type,
+            Some(N2SmInfoTypeAnyOf::PduResRelCmd)
+        );
+        assert_eq!(session.state, SessionState::ReleasePending);
+
+        let updated = session
+            .update(&n2(N2SmInfoTypeAnyOf::PduResRelRsp), None, None)
+            .unwrap();
+        assert_eq!(updated.updated_data(), None);
+        assert_eq!(session.state, SessionState::Released);
+        assert_eq!(session.up_cnx_state, UpCnxStateAnyOf::Deactivated);
+    }
+
+    #[test]
+    fn release_without_user_plane() {
+        let mut session = active();
+        session
+            .update(&up_cnx_state("DEACTIVATED"), None, None)
+            .unwrap();
+
+        let updated = session.update(&release(), None, None).unwrap();
+        assert_eq!(updated.updated_data(), None);
+        assert_eq!(session.state, SessionState::Released);
+    }
+
+    #[test]
+    fn release_requested_by_the_ue() {
+        let mut session = active();
+        let (update, n1_sm_msg) = n1(nas::Body::ReleaseRequest(nas::ReleaseRequest::default()));
+
+        let updated = session.update(&update, Some(&n1_sm_msg), None).unwrap();
+        assert_eq!(
+            updated.n2_sm_info_type,
+            Some(N2SmInfoTypeAnyOf::PduResRelCmd)
+        );
+        let command = updated.n1_sm_msg.expect("PDU Session Release Command");
+        assert_eq!(command.pti, 2);
+        assert!(matches!(command.body, nas::Body::ReleaseCommand(_)));
+        assert_eq!(session.state, SessionState::ReleasePending);
+    }
+
+    #[test]
+    fn release_of_a_stored_context_requested_by_the_ue() {
+        let config: PoolConfig = "internet=10.45.0.0/30".parse().unwrap();
+        let store =
+            SmContextStore::with_pools(IpPools::new(&[config], Arc::new(MemoryStore)).unwrap());
+        let (created, _) = store.create(session()).unwrap();
+        let sm_context_ref = created.sm_context_ref.clone();
+        store
+            .update(
+                &sm_context_ref,
+                &n2(N2SmInfoTypeAnyOf::PduResSetupRsp),
+                None,
+                None,
+            )
+            .unwrap();
+
+        // The PDU Session Release Request is not taken for a modification
+        let (update, n1_sm_msg) = n1(nas::Body::ReleaseRequest(nas::ReleaseRequest::default()));
+        let (session, updated) = store
+            .update(&sm_context_ref, &update, Some(&n1_sm_msg), None)
+            .unwrap();
+        assert_eq!(session.state, SessionState::ReleasePending);
+        assert_eq!(
+            updated.n2_sm_info_type,
+            Some(N2SmInfoTypeAnyOf::PduResRelCmd)
+        );
+        assert!(matches!(
+            updated.n1_sm_msg.map(|command| command.body),
+            Some(nas::Body::ReleaseCommand(_))
+        ));
+        assert!(session
+            .n2_sm_info_transfer(N2SmInfoTypeAnyOf::PduResRelCmd)
+            .is_some());
+
+        let (session, _) = store
+            .update(
+                &sm_context_ref,
+                &n2(N2SmInfoTypeAnyOf::PduResRelRsp),
+                None,
+                None,
+            )
+            .unwrap();
+        assert_eq!(session.state, SessionState::Released);
+        assert_eq!(
+            store.get(&sm_context_ref),
+            Err(SmContextError::NotFound(sm_context_ref))
+        );
+        assert!(store.is_empty());
+    }
+
+    #[test]
+    fn unknown_context_is_rejected() {
+        let store = SmContextStore::new();
+        let result = store
+            .update("1", &release(), None, None)
+            .map(|(_, updated)| updated);
+        assert_problem(result, 404, "CONTEXT_NOT_FOUND");
+    }
+
+    #[test]
+    fn missing_ie_is_rejected() {
+        let data = models::SmContextCreateData::new(
+            uuid::Uuid::nil(),
+            models::PlmnIdNid::new("001".to_string(), "01".to_string()),
+            AccessType::Variant3GppAccess,
+            "http://amf/sm-context-status/1".to_string(),
+        );
+        let problem = PduSession::from_create_data(&data).unwrap_err().problem();
+        assert_eq!(problem.status, Some(400));
+        assert_eq!(problem.cause.as_deref(), Some("MANDATORY_IE_MISSING"));
+        assert_eq!(
+            problem.invalid_params,
+            Some(vec![models::InvalidParam::new("/supi".to_string())])
+        );
+    }
+
+    #[test]
+    fn invalid_ies_and_transitions_are_rejected() {
+        let incorrect = "MANDATORY_IE_INCORRECT";
+
+        // Not an UP connection state
+        assert_problem(
+            active().update(&up_cnx_state("ON"), None, None),
+            400,
+            incorrect,
+        );
+        // Activated already
+        assert_problem(
+            active().update(&up_cnx_state("ACTIVATING"), None, None),
+            400,
+            incorrect,
+        );
+        // Nothing to deactivate before the establishment
+        assert_problem(
+            session().update(&up_cnx_state("DEACTIVATED"), None, None),
+            400,
+            incorrect,
+        );
+        // Handover of a session being established
+        assert_problem(
+            session().update(
+                &ho_state(HoStateAnyOf::Preparing, N2SmInfoTypeAnyOf::HandoverRequired),
+                None,
+                None,
+            ),
+            400,
+            incorrect,
+        );
+        // Handover prepared without preparation
+        assert_problem(
+            active().update(
+                &ho_state(HoStateAnyOf::Prepared, N2SmInfoTypeAnyOf::HandoverReqAck),
+                None,
+                None,
+            ),
+            400,
+            incorrect,
+        );
+        // Released twice
+        let mut session = active();
+        session.update(&release(), None, None).unwrap();
+        let problem = session
+            .update(&release(), None, None)
+            .unwrap_err()
+            .problem();
+        assert_eq!(problem.status, Some(400));
+        assert_eq!(
+            problem.invalid_params,
+            Some(vec![models::InvalidParam::new("/release".to_string())])
+        );
+    }
+
+    #[test]
+    fn unexpected_n2_sm_info_is_rejected() {
+        let n2_sm_error = "N2_SM_ERROR";
+
+        // Nothing to release
+        assert_problem(
+            active().update(&n2(N2SmInfoTypeAnyOf::PduResRelRsp), None, None),
+            403,
+            n2_sm_error,
+        );
+        // No modification asked for
+        assert_problem(
+            active().update(&n2(N2SmInfoTypeAnyOf::PduResModRsp), None, None),
+            403,
+            n2_sm_error,
+        );
+        // Not the N2 SM information of a handover preparation
+        assert_problem(
+            active().update(
+                &ho_state(HoStateAnyOf::Preparing, N2SmInfoTypeAnyOf::HandoverReqAck),
+                None,
+                None,
+            ),
+            403,
+            n2_sm_error,
+        );
+        // Not a setup response transfer
+        assert_problem(
+            session().update(&n2(N2SmInfoTypeAnyOf::PduResSetupRsp), None, Some(&[0xff])),
+            403,
+            n2_sm_error,
+        );
+    }
+
+    #[test]
+    fn unexpected_n1_sm_msg_is_rejected() {
+        let n1_sm_error = "N1_SM_ERROR";
+
+        // Nothing to complete the release of
+        let (update, n1_sm_msg) = n1(nas::Body::ReleaseComplete(nas::ReleaseComplete::default()));
+        assert_problem(
+            active().update(&update, Some(&n1_sm_msg), None),
+            403,
+            n1_sm_error,
+        );
+        // No user plane to modify
+        let (update, n1_sm_msg) = n1(nas::Body::ModificationRequest(
+            nas::ModificationRequest::default(),
+        ));
+        assert_problem(
+            session().update(&update, Some(&n1_sm_msg), None),
+            403,
+            n1_sm_error,
+        );
+        // Not a 5GSM message
+        assert_problem(
+            active().update(&update, Some(&[0x2e]), None),
+            403,
+            n1_sm_error,
+        );
+        // Of another PDU session
+        let message = nas::Message {
+            pdu_session_id: 2,
+            pti: 2,
+            body: nas::Body::ReleaseRequest(nas::ReleaseRequest::default()),
+        };
+        assert_problem(
+            active().update(&update, Some(&message.encode()), None),
+            403,
+            n1_sm_error,
+        );
+    }
+
+    #[test]
+    fn rejected_update_leaves_the_stored_session() {
+        let config: PoolConfig = "internet=10.45.0.0/30".parse().unwrap();
+        let store =
+            SmContextStore::with_pools(IpPools::new(&[config], Arc::new(MemoryStore)).unwrap());
+        let (created, _) = store.create(session()).unwrap();
+
+        let mut update = n2(N2SmInfoTypeAnyOf::PduResRelRsp);
+        update.pei = Some("imeisv-4370816125816151".to_string());
+        assert!(store
+            .update(&created.sm_context_ref, &update, None, None)
+            .is_err());
+        assert_eq!(store.get(&created.sm_context_ref), Ok(created));
+    }
+}
@@ -161,6 +161,7 @@ fn main() {
                 Some("af_service_id_example".to_string()),
                 Some("mtc_provider_info_example".to_string()),
                 None,
+                None,
                 Some("if_none_match_example".to_string()),
                 Some("if_modified_since_example".to_string()),
             ));
@@ -0,0 +1,267 @@
+//! Provisioning API of the UDM, for changing the subscriber data at run time:
+//!
+//! - `GET /subscribers` lists the SUPIs of the subscribers,
+//! - `GET /subscribers/{supi}` returns the data of a subscriber,
+//! - `PUT /subscribers/{supi}` provisions a subscriber from its JSON data,
+//! - `DELETE /subscribers/{supi}` removes a subscriber,
+//!
+//! and likewise the shared data under `/shared-data/{sharedDataId}`.
+//!
+//! The SDM subscribers are notified of the changes like for those of the
+//! provisioning files. The UE parameter updates are triggered likewise, by
+//! provisioning the new data in the access and mobility data:
+//!
+//! - `POST /subscribers/{supi}/sor-update` sends new SoR information,
+//! - `POST /subscribers/{supi}/upu-update` sends the JSON list of `UpuData`,
+//! - `POST /subscribers/{supi}/snssais-update` subscribes the UE to the JSON
+//!   `Nssai`,
+//! - `GET /subscribers/{supi}/acknowledgements` returns the state of the
+//!   updates, as acknowledged by the UE.
+
+use hyper::service::{make_service_fn, service_fn};
+use hyper::{Body, Method, Request, Response, StatusCode};
+use log::info;
+use std::convert::Infallible;
+use std::net::SocketAddr;
+
+use nudm_sdm::acknowledgements::{AcknowledgementStore, Procedure};
+use nudm_sdm::models::{self, SharedData};
+use nudm_sdm::shared_data::SharedDataStore;
+use nudm_sdm::subscribers::{Subscriber, SubscriberStore};
+
+pub async fn serve(
+    addr: SocketAddr,
+    subscribers: SubscriberStore,
+    shared_data: SharedDataStore,
+    acknowledgements: AcknowledgementStore,
+) {
+    let make_service = make_service_fn(move |_| {
+        let subscribers = subscribers.clone();
+        let shared_data = shared_data.clone();
+        let acknowledgements = acknowledgements.clone();
+        async move {
+            Ok::<_, Infallible>(service_fn(move |request| {
+                let subscribers = subscribers.clone();
+                let shared_data = shared_data.clone();
+                let acknowledgements = acknowledgements.clone();
+                async move {
+                    Ok::<_, Infallible>(
+                        handle(request, subscribers, shared_data, acknowledgements).await,
+                    )
+                }
+            }))
+        }
+    });
+
+    info!("Provisioning API listening on {}", addr);
+    if let Err(e) = hyper::Server::bind(&addr).serve(make_service).await {
+        log::error!("Provisioning API failed: {}", e);
+    }
+}
+
+async fn handle(
+    request: Request<Body>,
+    subscribers: SubscriberStore,
+    shared_data: SharedDataStore,
+    acknowledgements: AcknowledgementStore,
+) -> Response<Body> {
+    let path = request.uri().path().to_string();
+    if let Some(id) = resource(&path, "/shared-data") {
+        return handle_shared_data(request, id, shared_data).await;
+    }
+    if let Some((supi, update)) = path
+        .strip_prefix("/subscribers/")
+        .and_then(|rest| rest.split_once('/'))
+    {
+        return handle_update(request, supi, update, subscribers, acknowledgements).await;
+    }
+    let supi = match resource(&path, "/subscribers") {
+        Some(supi) => supi,
+        None => return status(StatusCode::NOT_FOUND, "Unknown resource"),
+    };
+
+    match (request.method().clone(), supi) {
+        (Method::GET, None) => json(StatusCode::OK, &subscribers.supis()),
+        (Method::GET, Some(supi)) => match subscribers.get(&supi) {
+            Some(subscriber) => json(StatusCode::OK, &subscriber),
+            None => status(StatusCode::NOT_FOUND, "Unknown subscriber"),
+        },
+        (Method::PUT, Some(supi)) => {
+            let body = match hyper::body::to_bytes(request.into_body()).await {
+                Ok(body) => body,
+                Err(e) => return status(StatusCode::BAD_REQUEST, &e.to_string()),
+            };
+            let subscriber: Subscriber = match serde_json::from_slice(&body) {
+                Ok(subscriber) => subscriber,
+                Err(e) => return status(StatusCode::BAD_REQUEST, &e.to_string()),
+            };
+            if subscriber.supi != supi {
+                return status(StatusCode::BAD_REQUEST, "SUPI does not match the path");
+            }
+            info!("Provisioning subscriber {}", supi);
+            match subscribers.insert(subscriber) {
+                Some(_) => status(StatusCode::NO_CONTENT, ""),
+                None => status(StatusCode::CREATED, ""),
+            }
+        }
+        (Method::DELETE, Some(supi)) => {
+            info!("Removing subscriber {}", supi);
+            acknowledgements.remove(&supi);
+            match subscribers.remove(&supi) {
+                Some(_) => status(StatusCode::NO_CONTENT, ""),
+                None => status(StatusCode::NOT_FOUND, "Unknown subscriber"),
+            }
+        }
+        _ => status(StatusCode::METHOD_NOT_ALLOWED, "Method not allowed"),
+    }
+}
+
+async fn handle_update(
+    request: Request<Body>,
+    supi: &str,
+    update: &str,
+    subscribers: SubscriberStore,
+    acknowledgements: AcknowledgementStore,
+) -> Response<Body> {
+    if subscribers.get(supi).is_none() {
+        return status(StatusCode::NOT_FOUND, "Unknown subscriber");
+    }
+    match (request.method().clone(), update) {
+        (Method::GET, "acknowledgements") => {
+            return json(StatusCode::OK, &acknowledgements.states(supi))
+        }
+        (Method::POST, "sor-update")
+        | (Method::POST, "upu-update")
+        | (Method::POST, "snssais-update") => {}
+        (_, "acknowledgements") | (_, "sor-update") | (_, "upu-update") | (_, "snssais-update") => {
+            return status(StatusCode::METHOD_NOT_ALLOWED, "Method not allowed")
+        }
+        _ => return status(StatusCode::NOT_FOUND, "Unknown resource"),
+    }
+    let body = match hyper::body::to_bytes(request.into_body()).await {
+        Ok(body) => body,
+        Err(e) => return status(StatusCode::BAD_REQUEST, &e.to_string()),
+    };
+    let change = match update {
+        "sor-update" => Change::Sor,
+        "upu-update" => match serde_json::from_slice(&body) {
+            Ok(upu_data_list) => Change::Upu(upu_data_list),
+            Err(e) => return status(StatusCode::BAD_REQUEST, &e.to_string()),
+        },
+        _ => match serde_json::from_slice(&body) {
+            Ok(nssai) => Change::SNssais(nssai),
+            Err(e) => return status(StatusCode::BAD_REQUEST, &e.to_string()),
+        },
+    };
+
+    // The data is modified under the lock of the store, so that concurrent
+    // updates of the subscriber are not lost
+    let state = subscribers.update(supi, |subscriber| {
+        let am_data = subscriber.am_data.as_mut()?;
+        Some(match change {
+            Change::Sor => {
+                let state = acknowledgements.trigger(supi, Procedure::Sor);
+                am_data.sor_info = state.sor_info();
+                state
+            }
+            Change::Upu(upu_data_list) => {
+                let state = acknowledgements.trigger(supi, Procedure::Upu);
+                am_data.upu_info = state.upu_info(upu_data_list);
+                state
+            }
+            Change::SNssais(nssai) => {
+                am_data.nssai = Some(swagger::Nullable::Present(nssai.clone()));
+                subscriber.nssai = Some(nssai);
+                acknowledgements.trigger(supi, Procedure::SNssais)
+            }
+        })
+    });
+
+    // The AMF is notified of the new access and mobility data
+    match state {
+        Some(Some(state)) => {
+            info!("Triggering {} of {}", update, supi);
+            json(StatusCode::OK, &state)
+        }
+        Some(None) => status(StatusCode::CONFLICT, "No access and mobility data"),
+        None => status(StatusCode::NOT_FOUND, "Unknown subscriber"),
+    }
+}
+
+/// A UE parameter update, with the new data it sends.
+enum Change {
+    Sor,
+    Upu(Vec<models::UpuData>),
+    SNssais(models::Nssai),
+}
+
+async fn handle_shared_data(
+    request: Request<Body>,
+    id: Option<String>,
+    shared_data: SharedDataStore,
+) -> Response<Body> {
+    match (request.method().clone(), id) {
+        (Method::GET, None) => json(StatusCode::OK, &shared_data.ids()),
+        (Method::GET, Some(id)) => match shared_data.get(&id) {
+            Some(data) => json(StatusCode::OK, &data),
+            None => status(StatusCode::NOT_FOUND, "Unknown shared data"),
+        },
+        (Method::PUT, Some(id)) => {
+            let body = match hyper::body::to_bytes(request.into_body()).await {
+                Ok(body) => body,
+                Err(e) => return status(StatusCode::BAD_REQUEST, &e.to_string()),
+            };
+            let data: SharedData = match serde_json::from_slice(&body) {
+                Ok(data) => data,
+                Err(e) => return status(StatusCode::BAD_REQUEST, &e.to_string()),
+            };
+            if data.shared_data_id != id {
+                return status(
+                    StatusCode::BAD_REQUEST,
+                    "sharedDataId does not match the path",
+                );
+            }
+            info!("Provisioning shared data {}", id);
+            match shared_data.insert(data) {
+                Some(_) => status(StatusCode::NO_CONTENT, ""),
+                None => status(StatusCode::CREATED, ""),
+            }
+        }
+        (Method::DELETE, Some(id)) => {
+            info!("Removing shared data {}", id);
+            match shared_data.remove(&id) {
+                Some(_) => status(StatusCode::NO_CONTENT, ""),
+                None => status(StatusCode::NOT_FOUND, "Unknown shared data"),
+            }
+        }
+        _ => status(StatusCode::METHOD_NOT_ALLOWED, "Method not allowed"),
+    }
+}
+
+/// Matches a path with a collection: `Some(None)` for the collection itself
+/// and `Some(Some(id))` for one of its members.
+fn resource(path: &str, collection: &str) -> Option<Option<String>> {
+    match path.strip_prefix(collection) {
+        Some("") | Some("/") => Some(None),
+        Some(rest) if rest.starts_with('/') && !rest[1..].contains('/') => {
+            Some(Some(rest[1..].to_string()))
+        }
+        _ => None,
+    }
+}
+
+fn json<T: serde::Serialize>(code: StatusCode, body: &T) -> Response<Body> {
+    let body = serde_json::to_vec(body).expect("subscriber data is serializable");
+    Response::builder()
+        .status(code)
+        .header(hyper::header::CONTENT_TYPE, "application/json")
+        .body(Body::from(body))
+        .expect("valid response")
+}
+
+fn status(code: StatusCode, detail: &str) -> Response<Body> {
+    Response::builder()
+        .status(code)
+        .body(Body::from(detail.to_string()))
+        .expect("valid response")
+}
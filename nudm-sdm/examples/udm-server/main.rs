@@ -2,8 +2,16 @@
 
 #![allow(missing_docs)]
 use clap::Parser;
+use nnrf_nfmanagement_server::models::NfType;
+use nnrf_nfmanagement_server::{registration, ContextWrapperExt};
+use nudm_sdm::acknowledgements::AcknowledgementStore;
+use nudm_sdm::shared_data::SharedDataStore;
+use nudm_sdm::subscribers::SubscriberStore;
+use nudm_sdm::suci::HomeNetworkKeys;
+use swagger::{AuthData, ContextBuilder, EmptyContext, Push, XSpanIdString};
 use url::Url;
 
+mod admin;
 mod server;
 
 #[derive(Parser)]
@@ -11,6 +19,33 @@ pub struct Opts {
     // public options
     #[clap(short = 'l', long, default_value = "http://127.0.0.1:8081")]
     pub listen: String,
+    #[clap(short = 'n', long, default_value = "http://127.0.0.1:8080")]
+    pub nrf: String,
+    /// Provisioning file of the subscribers, YAML or JSON
+    #[clap(
+        short = 's',
+        long,
+        default_value = "examples/udm-server/subscribers.yaml"
+    )]
+    pub subscribers: String,
+    /// Provisioning file of the shared data, YAML or JSON
+    #[clap(
+        short = 'd',
+        long,
+        default_value = "examples/udm-server/shared-data.yaml"
+    )]
+    pub shared_data: String,
+    /// Private keys of the home network, YAML or JSON, for de-concealing
+    /// the SUCIs of the ECIES protection schemes
+    #[clap(short = 'k', long)]
+    pub home_network_keys: Option<String>,
+    /// Address of the provisioning API, e.g. 127.0.0.1:8091
+    #[clap(short = 'a', long)]
+    pub admin: Option<String>,
+    /// Seconds the consumers may cache the retrieved data without
+    /// revalidating it with the UDM
+    #[clap(short = 'm', long, default_value = "0")]
+    pub max_age: u64,
 }
 
 /// Create custom server, wire it to the autogenerated router,
@@ -27,6 +62,54 @@ async fn main() {
         false
     };
 
+    let subscribers =
+        SubscriberStore::load(&opts.subscribers).expect("unable to load the subscribers");
+    let shared_data =
+        SharedDataStore::load(&opts.shared_data).expect("unable to load the shared data");
+    let acknowledgements = AcknowledgementStore::new();
+    let keys = match &opts.home_network_keys {
+        Some(path) => HomeNetworkKeys::load(path).expect("unable to load the home network keys"),
+        None => HomeNetworkKeys::default(),
+    };
+
+    let profile = registration::nf_profile(
+        uuid::Uuid::new_v4(),
+        NfType::UDM,
+        "nudm-sdm",
+        "v2",
+        "2.0.0",
+        base_url.scheme(),
+        base_url.host_str().unwrap(),
+        base_url.port().unwrap(),
+    );
+    let context: swagger::make_context_ty!(
+        ContextBuilder,
+        EmptyContext,
+        Option<AuthData>,
+        XSpanIdString
+    ) = swagger::make_context!(
+        ContextBuilder,
+        EmptyContext,
+        None as Option<AuthData>,
+        XSpanIdString::default()
+    );
+    let nrf = nnrf_nfmanagement_server::Client::try_new_http(&opts.nrf)
+        .expect("unable to create NRF client")
+        .with_context(context);
+    tokio::spawn(registration::maintain(nrf, profile));
+
+    if let Some(admin) = &opts.admin {
+        let addr = admin
+            .parse()
+            .expect("Failed to parse provisioning API address");
+        tokio::spawn(admin::serve(
+            addr,
+            subscribers.clone(),
+            shared_data.clone(),
+            acknowledgements.clone(),
+        ));
+    }
+
     server::create(
         &format!(
             "{}:{}",
@@ -34,6 +117,13 @@ async fn main() {
             base_url.port().unwrap()
         ),
         is_https,
+        subscribers,
+        Some(opts.subscribers),
+        shared_data,
+        Some(opts.shared_data),
+        acknowledgements,
+        keys,
+        opts.max_age,
     )
     .await;
 }
@@ -6,27 +6,80 @@ use async_trait::async_trait;
 use futures::{future, Stream, StreamExt, TryFutureExt, TryStreamExt};
 use hyper::server::conn::Http;
 use hyper::service::Service;
-use log::info;
+use log::{info, warn};
 use std::future::Future;
 use std::marker::PhantomData;
 use std::net::SocketAddr;
 use std::sync::{Arc, Mutex};
 use std::task::{Context, Poll};
+use std::time::Duration;
 use swagger::auth::MakeAllowAllAuthenticator;
 use swagger::EmptyContext;
+use swagger::{AuthData, ContextBuilder, Push};
 use swagger::{Has, XSpanIdString};
 use tokio::net::TcpListener;
 
 #[cfg(not(any(target_os = "macos", target_os = "windows", target_os = "ios")))]
 use openssl::ssl::{Ssl, SslAcceptor, SslAcceptorBuilder, SslFiletype, SslMethod};
 
-use nudm_sdm::models::{self, SessionManagementSubscriptionData, SmSubsData};
+use chrono::{DateTime, Utc};
+use nudm_sdm::acknowledgements::{AcknowledgementStore, Procedure};
+use nudm_sdm::models::{self, SmSubsData};
+use nudm_sdm::shared_data::{shared_data_not_found, SharedDataStore};
+use nudm_sdm::subscribers::{
+    data_not_found, plmn_key, user_not_found, Requester, Subscriber, SubscriberStore,
+};
+use nudm_sdm::subscriptions::SdmSubscriptionStore;
+use nudm_sdm::suci::HomeNetworkKeys;
+use nudm_sdm::{CallbackApi, DatachangeNotificationRequestBodyCallbackReferencePostResponse};
+use sha2::{Digest, Sha256};
+
+type ClientContext = swagger::make_context_ty!(
+    ContextBuilder,
+    EmptyContext,
+    Option<AuthData>,
+    XSpanIdString
+);
+
+/// Period of the checks for changes of the provisioning files.
+const SUBSCRIBERS_CHECK_PERIOD: Duration = Duration::from_secs(5);
 
 /// Builds an SSL implementation for Simple HTTPS from some hard-coded file names
-pub async fn create(addr: &str, https: bool) {
+pub async fn create(
+    addr: &str,
+    https: bool,
+    subscribers: SubscriberStore,
+    subscribers_path: Option<String>,
+    shared_data: SharedDataStore,
+    shared_data_path: Option<String>,
+    acknowledgements: AcknowledgementStore,
+    keys: HomeNetworkKeys,
+    max_age: u64,
+) {
     let addr = addr.parse().expect("Failed to parse bind address");
 
-    let server = Server::new();
+    let subscriptions = SdmSubscriptionStore::new();
+
+    tokio::spawn(notify_subscribers(
+        subscribers.clone(),
+        shared_data.clone(),
+        subscriptions.clone(),
+    ));
+    if let Some(path) = subscribers_path {
+        tokio::spawn(reload_subscribers(subscribers.clone(), path));
+    }
+    if let Some(path) = shared_data_path {
+        tokio::spawn(reload_shared_data(shared_data.clone(), path));
+    }
+
+    let server = Server::new(
+        subscribers,
+        shared_data,
+        subscriptions,
+        acknowledgements,
+        keys,
+        max_age,
+    );
 
     let service = MakeService::new(server);
 
@@ -84,17 +137,440 @@ pub async fn create(addr: &str, https: bool) {
     }
 }
 
-#[derive(Copy, Clone)]
+/// Sends the modification notifications owed to the SDM subscribers for every
+/// change of the subscriber data or of the shared data, in the order of the
+/// changes.
+async fn notify_subscribers(
+    subscribers: SubscriberStore,
+    shared_data: SharedDataStore,
+    subscriptions: SdmSubscriptionStore,
+) {
+    let client = nudm_sdm::server::callbacks::Client::new_http();
+    let context: ClientContext = swagger::make_context!(
+        ContextBuilder,
+        EmptyContext,
+        None as Option<AuthData>,
+        XSpanIdString::default()
+    );
+
+    let subscriber_events = subscribers.watch().map(|event| {
+        (
+            event.supi().to_string(),
+            subscriptions.notifications(&event),
+        )
+    });
+    let shared_data_events = shared_data.watch().map(|event| {
+        (
+            format!("shared data {}", event.shared_data_id()),
+            subscriptions.shared_data_notifications(&event),
+        )
+    });
+    let mut events = futures::stream::select(subscriber_events, shared_data_events);
+    while let Some((changed, notifications)) = events.next().await {
+        let notifications = notifications
+            .into_iter()
+            .map(|(uri, notification)| {
+                info!(
+                    "Notifying {} of {} changed resources of {}",
+                    uri,
+                    notification.notify_items.len(),
+                    changed
+                );
+                let client = client.clone();
+                let context = context.clone();
+                async move {
+                    match client
+                        .datachange_notification_request_body_callback_reference_post(
+                            uri.clone(),
+                            notification,
+                            &context,
+                        )
+                        .await
+                    {
+                        Ok(DatachangeNotificationRequestBodyCallbackReferencePostResponse::SuccessfulNotificationResponse) => {}
+                        Ok(rsp) => warn!("Notification to {} rejected: {:?}", uri, rsp),
+                        Err(e) => warn!("Notification to {} failed: {}", uri, e),
+                    }
+                }
+            });
+        future::join_all(notifications).await;
+    }
+}
+
+/// Provisions again the subscribers whenever their file is modified. Only
+/// the subscribers whose data changed are notified.
+async fn reload_subscribers(subscribers: SubscriberStore, path: String) {
+    let modified = |path: &str| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+
+    let mut last_modified = modified(&path);
+    let mut interval = tokio::time::interval(SUBSCRIBERS_CHECK_PERIOD);
+    loop {
+        interval.tick().await;
+
+        let current = modified(&path);
+        if current == last_modified {
+            continue;
+        }
+        last_modified = current;
+
+        match SubscriberStore::read(&path) {
+            Ok(provisioned) => {
+                let changes = subscribers.replace(provisioned);
+                info!("Subscribers reloaded from {}: {} changed", path, changes);
+            }
+            Err(e) => warn!("Unable to reload the subscribers from {}: {}", path, e),
+        }
+    }
+}
+
+/// Provisions again the shared data whenever its file is modified.
+async fn reload_shared_data(shared_data: SharedDataStore, path: String) {
+    let modified = |path: &str| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+
+    let mut last_modified = modified(&path);
+    let mut interval = tokio::time::interval(SUBSCRIBERS_CHECK_PERIOD);
+    loop {
+        interval.tick().await;
+
+        let current = modified(&path);
+        if current == last_modified {
+            continue;
+        }
+        last_modified = current;
+
+        match SharedDataStore::read(&path) {
+            Ok(provisioned) => {
+                let changes = shared_data.replace(provisioned);
+                info!("Shared data reloaded from {}: {} changed", path, changes);
+            }
+            Err(e) => warn!("Unable to reload the shared data from {}: {}", path, e),
+        }
+    }
+}
+
+#[derive(Clone)]
 pub struct Server<C> {
+    subscribers: SubscriberStore,
+    shared_data: SharedDataStore,
+    subscriptions: SdmSubscriptionStore,
+    acknowledgements: AcknowledgementStore,
+    keys: HomeNetworkKeys,
+    /// Seconds the consumers may reuse a retrieved data set without
+    /// revalidating it, 0 for always revalidating
+    max_age: u64,
     marker: PhantomData<C>,
 }
 
 impl<C> Server<C> {
-    pub fn new() -> Self {
+    pub fn new(
+        subscribers: SubscriberStore,
+        shared_data: SharedDataStore,
+        subscriptions: SdmSubscriptionStore,
+        acknowledgements: AcknowledgementStore,
+        keys: HomeNetworkKeys,
+        max_age: u64,
+    ) -> Self {
         Server {
+            subscribers,
+            shared_data,
+            subscriptions,
+            acknowledgements,
+            keys,
+            max_age,
             marker: PhantomData,
         }
     }
+
+    /// The subscriber identified by a SUPI, a SUCI or a GPSI.
+    fn find(&self, ue_id: &str) -> Result<Subscriber, models::ProblemDetails> {
+        let ue_id = self.keys.resolve(ue_id).map_err(|e| e.problem())?;
+        self.subscribers
+            .find(&ue_id)
+            .ok_or_else(|| user_not_found(&ue_id))
+    }
+
+    /// Records the acknowledgement of a UE parameter update. The Info service
+    /// operation has no 404: an unknown subscriber is a bad request.
+    fn acknowledge(
+        &self,
+        supi: &str,
+        procedure: Procedure,
+        acknowledge_info: Option<models::AcknowledgeInfo>,
+    ) -> Result<(), models::ProblemDetails> {
+        let subscriber = self.find(supi).map_err(|mut problem| {
+            problem.status = Some(400);
+            problem
+        })?;
+        let state = self
+            .acknowledgements
+            .acknowledge(&subscriber.supi, procedure, acknowledge_info)
+            .map_err(|e| e.problem())?;
+        info!(
+            "{} update {} of {} acknowledged",
+            procedure,
+            state.counter_hex(),
+            subscriber.supi
+        );
+        Ok(())
+    }
+
+    fn expire_subscriptions(&self) {
+        for expired in self.subscriptions.expire() {
+            info!(
+                "SDM subscription {:?} of {} expired",
+                expired.subscription_id, expired.callback_reference
+            );
+        }
+    }
+
+    /// The subscriber a retrieval is about, as seen from the serving PLMN.
+    /// The shared data it references is merged into its data unless the
+    /// consumer supports the SharedData feature; its last modification
+    /// covers both.
+    fn lookup(
+        &self,
+        ue_id: &str,
+        plmn_key: Option<String>,
+        supported_features: Option<&str>,
+        if_none_match: Option<&str>,
+        if_modified_since: Option<&str>,
+    ) -> Result<Retrieved, models::ProblemDetails> {
+        let subscriber = self.find(ue_id)?;
+        let subscriber = match plmn_key {
+            Some(plmn_key) => subscriber.in_plmn(&plmn_key),
+            None => subscriber,
+        };
+        let shared_data_ids = subscriber.shared_data_ids();
+
+        let last_modified = self
+            .subscribers
+            .last_modified(&subscriber.supi)
+            .into_iter()
+            .chain(self.shared_data.last_modified(&shared_data_ids))
+            .max()
+            .unwrap_or_else(Utc::now);
+
+        Ok(Retrieved {
+            subscriber: if shares_data(supported_features) {
+                subscriber
+            } else {
+                self.shared_data.resolve(&subscriber)
+            },
+            conditions: self.conditions(last_modified, if_none_match, if_modified_since),
+        })
+    }
+
+    /// What the validators of a retrieved resource depend on, besides its
+    /// representation.
+    fn conditions(
+        &self,
+        last_modified: DateTime<Utc>,
+        if_none_match: Option<&str>,
+        if_modified_since: Option<&str>,
+    ) -> Conditions {
+        Conditions {
+            cache_control: match self.max_age {
+                0 => "no-cache".to_string(),
+                max_age => format!("max-age={}", max_age),
+            },
+            last_modified,
+            if_none_match: if_none_match.map(str::to_string),
+            if_modified_since: if_modified_since.map(str::to_string),
+        }
+    }
+}
+
+struct Retrieved {
+    subscriber: Subscriber,
+    conditions: Conditions,
+}
+
+/// The retrieval conditions of the consumer, with the cache directives and
+/// the last modification of the resource.
+struct Conditions {
+    cache_control: String,
+    last_modified: DateTime<Utc>,
+    if_none_match: Option<String>,
+    if_modified_since: Option<String>,
+}
+
+impl Conditions {
+    /// The validators of a representation, and whether the consumer holds
+    /// it already.
+    fn validators<T: serde::Serialize>(self, body: &T) -> Validators {
+        let e_tag = entity_tag(body);
+        Validators {
+            not_modified: not_modified(
+                &e_tag,
+                &self.last_modified,
+                self.if_none_match.as_deref(),
+                self.if_modified_since.as_deref(),
+            ),
+            cache_control: self.cache_control,
+            e_tag,
+            last_modified: http_date(&self.last_modified),
+        }
+    }
+}
+
+struct Validators {
+    cache_control: String,
+    /// Strong entity tag of the representation
+    e_tag: String,
+    last_modified: String,
+    not_modified: bool,
+}
+
+/// Features of Nudm_SDM supported on top of the mandatory ones (TS 29.503
+/// clause 6.1.8), as a hexadecimal bit string: SharedData (1).
+const SUPPORTED_FEATURES: &str = "1";
+
+/// The features supported by both ends, when the consumer announced its own.
+fn negotiate_features(requested: Option<&str>) -> Option<String> {
+    // The last digit holds the features 1 to 4
+    let common: Vec<char> = requested?
+        .chars()
+        .rev()
+        .zip(SUPPORTED_FEATURES.chars().rev())
+        .map(|(a, b)| a.to_digit(16).unwrap_or(0) & b.to_digit(16).unwrap_or(0))
+        .map(|d| std::char::from_digit(d, 16).expect("a hexadecimal digit"))
+        .collect();
+    let common: String = common.into_iter().rev().skip_while(|&d| d == '0').collect();
+    Some(if common.is_empty() {
+        "0".to_string()
+    } else {
+        common
+    })
+}
+
+/// Whether the consumer supports the SharedData feature, i.e. retrieves the
+/// shared data it is referred to by itself.
+fn shares_data(supported_features: Option<&str>) -> bool {
+    negotiate_features(supported_features)
+        .and_then(|common| u32::from_str_radix(&common, 16).ok())
+        .map_or(false, |common| common & 1 != 0)
+}
+
+/// Strong entity tag of a representation: a digest of its JSON form, which
+/// changes with the representation whatever data it is made of.
+fn entity_tag<T: serde::Serialize>(body: &T) -> String {
+    let body = serde_json::to_vec(body).expect("subscriber data is serializable");
+    format!("\"{}\"", hex::encode(&Sha256::digest(&body)[..16]))
+}
+
+/// IMF-fixdate of RFC 7231, e.g. `Sun, 06 Nov 1994 08:49:37 GMT`.
+fn http_date(date: &DateTime<Utc>) -> String {
+    date.format("%a, %d %b %Y %H:%M:%S GMT").to_string()
+}
+
+/// Whether a conditional retrieval is answered with 304 Not Modified.
+/// If-None-Match is matched with the weak comparison of RFC 7232 clause
+/// 2.3.2 and, when present, takes precedence over If-Modified-Since (RFC 7232
+/// clause 6).
+fn not_modified(
+    e_tag: &str,
+    last_modified: &DateTime<Utc>,
+    if_none_match: Option<&str>,
+    if_modified_since: Option<&str>,
+) -> bool {
+    if let Some(if_none_match) = if_none_match {
+        let opaque = |tag: &str| tag.trim().trim_start_matches("W/").to_string();
+        return if_none_match
+            .split(',')
+            .any(|tag| tag.trim() == "*" || opaque(tag) == opaque(e_tag));
+    }
+    if_modified_since
+        .and_then(|since| DateTime::parse_from_rfc2822(since).ok())
+        .map_or(false, |since| {
+            last_modified.timestamp() <= since.timestamp()
+        })
+}
+
+fn bad_request(detail: String) -> models::ProblemDetails {
+    let mut problem = models::ProblemDetails::new();
+    problem.status = Some(400);
+    problem.detail = Some(detail);
+    problem
+}
+
+fn subscription_not_found(subscription_id: &str) -> models::ProblemDetails {
+    let mut problem = models::ProblemDetails::new();
+    problem.status = Some(404);
+    problem.cause = Some("SUBSCRIPTION_NOT_FOUND".to_string());
+    problem.detail = Some(format!("Subscription {} does not exist", subscription_id));
+    problem
+}
+
+/// The answer to the modification of a subscription: the subscription as
+/// modified, with the features negotiated for the modification.
+fn modified(
+    subscription: models::SdmSubscription,
+    supported_features: Option<&str>,
+) -> models::Modify200Response {
+    let mut body = models::Modify200Response::new(
+        subscription.nf_instance_id,
+        subscription.callback_reference,
+        subscription.monitored_resource_uris,
+        vec![],
+    );
+    body.implicit_unsubscribe = subscription.implicit_unsubscribe;
+    body.expires = subscription.expires;
+    body.amf_service_name = subscription.amf_service_name;
+    body.single_nssai = subscription.single_nssai;
+    body.dnn = subscription.dnn;
+    body.subscription_id = subscription.subscription_id;
+    body.plmn_id = subscription.plmn_id;
+    body.immediate_report = subscription.immediate_report;
+    body.supported_features = negotiate_features(supported_features);
+    body.context_info = subscription.context_info;
+    body.nf_change_filter = subscription.nf_change_filter;
+    body.unique_subscription = subscription.unique_subscription;
+    body.reset_ids = subscription.reset_ids;
+    body.ue_con_smf_data_sub_filter = subscription.ue_con_smf_data_sub_filter;
+    body
+}
+
+/// Unwraps the subscriber a retrieval is about, or returns the 404 of an
+/// unknown one.
+macro_rules! lookup {
+    ($response:ident, $lookup:expr) => {
+        match $lookup {
+            Ok(retrieved) => retrieved,
+            Err(problem) if problem.status == Some(400) => {
+                return Ok($response::BadRequest(problem))
+            }
+            Err(problem) => return Ok($response::NotFound(problem)),
+        }
+    };
+}
+
+/// Answers a retrieval with its data set and its validators, 304 if the
+/// consumer holds the current one, or 404 if the subscriber has none.
+macro_rules! respond {
+    ($response:ident, $retrieved:ident, $body:expr) => {
+        match $body {
+            None => Ok($response::NotFound(data_not_found(
+                &$retrieved.subscriber.supi,
+            ))),
+            Some(body) => {
+                let validators = $retrieved.conditions.validators(&body);
+                Ok(if validators.not_modified {
+                    $response::NotModified {
+                        cache_control: Some(validators.cache_control),
+                        e_tag: Some(validators.e_tag),
+                        last_modified: Some(validators.last_modified),
+                    }
+                } else {
+                    $response::ExpectedResponseToAValidRequest {
+                        body,
+                        cache_control: Some(validators.cache_control),
+                        e_tag: Some(validators.e_tag),
+                        last_modified: Some(validators.last_modified),
+                    }
+                })
+            }
+        }
+    };
 }
 
 use nudm_sdm::server::MakeService;
@@ -142,7 +618,21 @@ where
             if_modified_since,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+        let retrieved = lookup!(
+            GetAmDataResponse,
+            self.lookup(
+                &supi,
+                plmn_id.map(|p| plmn_key(&p.mcc, &p.mnc)),
+                supported_features.as_deref(),
+                if_none_match.as_deref(),
+                if_modified_since.as_deref()
+            )
+        );
+        let am_data = retrieved.subscriber.am_data.clone().map(|mut am_data| {
+            am_data.supported_features = negotiate_features(supported_features.as_deref());
+            am_data
+        });
+        respond!(GetAmDataResponse, retrieved, am_data)
     }
 
     /// retrieve a UE's 5MBS Subscription Data
@@ -163,7 +653,17 @@ where
             if_modified_since,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+        let retrieved = lookup!(
+            GetMbsDataResponse,
+            self.lookup(
+                &supi,
+                None,
+                supported_features.as_deref(),
+                if_none_match.as_deref(),
+                if_modified_since.as_deref()
+            )
+        );
+        respond!(GetMbsDataResponse, retrieved, retrieved.subscriber.mbs_data)
     }
 
     /// retrieve a UE's subscribed Enhanced Coverage Restriction Data
@@ -184,7 +684,17 @@ where
             if_modified_since,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+        let retrieved = lookup!(
+            GetEcrDataResponse,
+            self.lookup(
+                &supi,
+                None,
+                supported_features.as_deref(),
+                if_none_match.as_deref(),
+                if_modified_since.as_deref()
+            )
+        );
+        respond!(GetEcrDataResponse, retrieved, retrieved.subscriber.ecr_data)
     }
 
     /// retrieve a UE's SUPI or GPSI
@@ -197,13 +707,65 @@ where
         af_service_id: Option<String>,
         mtc_provider_info: Option<String>,
         requested_gpsi_type: Option<models::GpsiType>,
+        requested_supi: Option<bool>,
         if_none_match: Option<String>,
         if_modified_since: Option<String>,
         context: &C,
     ) -> Result<GetSupiOrGpsiResponse, ApiError> {
         let context = context.clone();
-        info!("get_supi_or_gpsi(\"{}\", {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}) - X-Span-ID: {:?}", ue_id, supported_features, af_id, app_port_id, af_service_id, mtc_provider_info, requested_gpsi_type, if_none_match, if_modified_since, context.get().0.clone());
-        Err(ApiError("Generic failure".into()))
+        info!("get_supi_or_gpsi(\"{}\", {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}, {:?}) - X-Span-ID: {:?}", ue_id, supported_features, af_id, app_port_id, af_service_id, mtc_provider_info, requested_gpsi_type, requested_supi, if_none_match, if_modified_since, context.get().0.clone());
+        let requester = Requester {
+            af_id: af_id.as_deref(),
+            app_port_id: app_port_id.as_ref(),
+            af_service_id: af_service_id.as_deref(),
+            mtc_provider_info: mtc_provider_info.as_deref(),
+        };
+        let gpsi_type = match requested_gpsi_type
+            .map(|t| t.to_string().parse::<models::GpsiTypeAnyOf>())
+            .transpose()
+        {
+            Ok(gpsi_type) => gpsi_type,
+            Err(e) => return Ok(GetSupiOrGpsiResponse::BadRequest(bad_request(e))),
+        };
+        let retrieved = lookup!(
+            GetSupiOrGpsiResponse,
+            self.lookup(
+                &ue_id,
+                None,
+                supported_features.as_deref(),
+                if_none_match.as_deref(),
+                if_modified_since.as_deref()
+            )
+        );
+
+        // A SUCI is translated like a SUPI, and a restricted external
+        // identifier only for its AFs, AF services and MTC providers
+        let subscriber = &retrieved.subscriber;
+        if let Some(external_id) = subscriber.external_ids.iter().find(|id| id.gpsi == ue_id) {
+            if !external_id.allows(&requester) {
+                return Ok(GetSupiOrGpsiResponse::NotFound(user_not_found(&ue_id)));
+            }
+        }
+
+        // The SUPI is always in the translation result, requested or not
+        let result = if !subscriber.is_identified_by(&ue_id) || ue_id == subscriber.supi {
+            subscriber.gpsi(gpsi_type, &requester).map(|gpsi| {
+                let mut result = models::IdTranslationResult::new(subscriber.supi.clone());
+                result.gpsi = Some(gpsi);
+                result
+            })
+        } else {
+            let mut result = models::IdTranslationResult::new(subscriber.supi.clone());
+            result.gpsi = gpsi_type
+                .and_then(|gpsi_type| subscriber.gpsi(Some(gpsi_type), &requester))
+                .or_else(|| Some(ue_id.clone()));
+            Some(result)
+        }
+        .map(|mut result| {
+            result.supported_features = negotiate_features(supported_features.as_deref());
+            result
+        });
+        respond!(GetSupiOrGpsiResponse, retrieved, result)
     }
 
     /// Mapping of Group Identifiers
@@ -230,7 +792,54 @@ where
             if_modified_since,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+        if ext_group_id.is_none() && int_group_id.is_none() {
+            return Ok(GetGroupIdentifiersResponse::BadRequest(bad_request(
+                "One of ext-group-id and int-group-id is required".to_string(),
+            )));
+        }
+        let (identity, members) = match self
+            .subscribers
+            .group(ext_group_id.as_deref(), int_group_id.as_deref())
+        {
+            Some(group) => group,
+            None => {
+                let mut problem = models::ProblemDetails::new();
+                problem.status = Some(404);
+                problem.cause = Some("GROUP_IDENTIFIER_NOT_FOUND".to_string());
+                problem.detail = Some(format!(
+                    "Unknown group {}",
+                    ext_group_id.or(int_group_id).unwrap_or_default()
+                ));
+                return Ok(GetGroupIdentifiersResponse::NotFound(problem));
+            }
+        };
+
+        let mut body = models::GroupIdentifiers::new();
+        body.ext_group_id = identity.ext_group_id;
+        body.int_group_id = Some(identity.int_group_id);
+        if ue_id_ind == Some(true) {
+            body.ue_id_list = Some(
+                members
+                    .into_iter()
+                    .map(|member| {
+                        let mut ue_id = models::UeId::new(member.supi);
+                        if !member.gpsis.is_empty() {
+                            ue_id.gpsi_list =
+                                Some(member.gpsis.into_iter().map(models::Gpsi::from).collect());
+                        }
+                        ue_id
+                    })
+                    .collect(),
+            );
+        }
+        Ok(
+            GetGroupIdentifiersResponse::ExpectedResponseToAValidRequest {
+                body,
+                cache_control: None,
+                e_tag: None,
+                last_modified: None,
+            },
+        )
     }
 
     /// retrieve a UE's LCS Broadcast Assistance Data Types Subscription Data
@@ -253,7 +862,21 @@ where
             if_modified_since,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+        let retrieved = lookup!(
+            GetLcsBcaDataResponse,
+            self.lookup(
+                &supi,
+                plmn_id.map(|p| plmn_key(&p.mcc, &p.mnc)),
+                supported_features.as_deref(),
+                if_none_match.as_deref(),
+                if_modified_since.as_deref()
+            )
+        );
+        respond!(
+            GetLcsBcaDataResponse,
+            retrieved,
+            retrieved.subscriber.lcs_broadcast_assistance_types_data
+        )
     }
 
     /// retrieve a UE's LCS Mobile Originated Subscription Data
@@ -274,7 +897,21 @@ where
             if_modified_since,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+        let retrieved = lookup!(
+            GetLcsMoDataResponse,
+            self.lookup(
+                &supi,
+                None,
+                supported_features.as_deref(),
+                if_none_match.as_deref(),
+                if_modified_since.as_deref()
+            )
+        );
+        respond!(
+            GetLcsMoDataResponse,
+            retrieved,
+            retrieved.subscriber.lcs_mo_data
+        )
     }
 
     /// retrieve a UE's LCS Privacy Subscription Data
@@ -295,7 +932,21 @@ where
             if_modified_since,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+        let retrieved = lookup!(
+            GetLcsPrivacyDataResponse,
+            self.lookup(
+                &ue_id,
+                None,
+                supported_features.as_deref(),
+                if_none_match.as_deref(),
+                if_modified_since.as_deref()
+            )
+        );
+        respond!(
+            GetLcsPrivacyDataResponse,
+            retrieved,
+            retrieved.subscriber.lcs_privacy_data
+        )
     }
 
     /// Mapping of UE Identifiers
@@ -312,7 +963,40 @@ where
             supported_features,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+        if gpsi_list.is_empty() {
+            return Ok(GetMultipleIdentifiersResponse::BadRequest(bad_request(
+                "gpsi-list is required".to_string(),
+            )));
+        }
+
+        // GPSIs of unknown subscribers are left out of the map
+        let body: std::collections::HashMap<String, models::SupiInfo> = gpsi_list
+            .iter()
+            .filter_map(|gpsi| {
+                let subscriber = self.subscribers.find(gpsi)?;
+                Some((
+                    gpsi.to_string(),
+                    models::SupiInfo::new(vec![models::Supi::from(subscriber.supi)]),
+                ))
+            })
+            .collect();
+        if body.is_empty() {
+            return Ok(GetMultipleIdentifiersResponse::NotFound(user_not_found(
+                &gpsi_list
+                    .iter()
+                    .map(|gpsi| gpsi.to_string())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            )));
+        }
+        Ok(
+            GetMultipleIdentifiersResponse::ExpectedResponseToAValidRequest {
+                body,
+                cache_control: None,
+                e_tag: None,
+                last_modified: None,
+            },
+        )
     }
 
     /// retrieve a UE's ProSe Subscription Data
@@ -333,7 +1017,21 @@ where
             if_modified_since,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+        let retrieved = lookup!(
+            GetProseDataResponse,
+            self.lookup(
+                &supi,
+                None,
+                supported_features.as_deref(),
+                if_none_match.as_deref(),
+                if_modified_since.as_deref()
+            )
+        );
+        respond!(
+            GetProseDataResponse,
+            retrieved,
+            retrieved.subscriber.prose_data
+        )
     }
 
     /// Nudm_Sdm Info operation for CAG acknowledgement
@@ -350,7 +1048,10 @@ where
             acknowledge_info,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+        match self.acknowledge(&supi, Procedure::Cag, acknowledge_info) {
+            Ok(()) => Ok(CAgAckResponse::SuccessfulAcknowledgement),
+            Err(problem) => Ok(CAgAckResponse::BadRequest(problem)),
+        }
     }
 
     /// Nudm_Sdm Info operation for S-NSSAIs acknowledgement
@@ -367,7 +1068,10 @@ where
             acknowledge_info,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+        match self.acknowledge(&supi, Procedure::SNssais, acknowledge_info) {
+            Ok(()) => Ok(SNssaisAckResponse::SuccessfulAcknowledgement),
+            Err(problem) => Ok(SNssaisAckResponse::BadRequest(problem)),
+        }
     }
 
     /// Nudm_Sdm Info service operation
@@ -384,7 +1088,10 @@ where
             acknowledge_info,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+        match self.acknowledge(&supi, Procedure::Sor, acknowledge_info) {
+            Ok(()) => Ok(SorAckInfoResponse::SuccessfulAcknowledgement),
+            Err(problem) => Ok(SorAckInfoResponse::BadRequest(problem)),
+        }
     }
 
     /// Nudm_Sdm Info for UPU service operation
@@ -401,7 +1108,10 @@ where
             acknowledge_info,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+        match self.acknowledge(&supi, Procedure::Upu, acknowledge_info) {
+            Ok(()) => Ok(UpuAckResponse::SuccessfulAcknowledgement),
+            Err(problem) => Ok(UpuAckResponse::BadRequest(problem)),
+        }
     }
 
     /// retrieve multiple data sets
@@ -428,7 +1138,38 @@ where
             if_modified_since,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+        let mut names = Vec::new();
+        for name in dataset_names {
+            match name.to_string().parse::<models::DataSetNameAnyOf>() {
+                Ok(name) => names.push(name),
+                Err(e) => return Ok(GetDataSetsResponse::BadRequest(bad_request(e))),
+            }
+        }
+        if names.len() < 2 {
+            return Ok(GetDataSetsResponse::BadRequest(bad_request(
+                "dataset-names holds fewer than 2 data sets".to_string(),
+            )));
+        }
+
+        let retrieved = lookup!(
+            GetDataSetsResponse,
+            self.lookup(
+                &supi,
+                plmn_id.map(|p| plmn_key(&p.mcc, &p.mnc)),
+                supported_features.as_deref(),
+                if_none_match.as_deref(),
+                if_modified_since.as_deref()
+            )
+        );
+        let mut data_sets = retrieved.subscriber.data_sets(&names);
+        let supported_features = negotiate_features(supported_features.as_deref());
+        if let Some(am_data) = data_sets.am_data.as_mut() {
+            am_data.supported_features = supported_features.clone();
+        }
+        if let Some(smf_sel_data) = data_sets.smf_sel_data.as_mut() {
+            smf_sel_data.supported_features = supported_features;
+        }
+        respond!(GetDataSetsResponse, retrieved, Some(data_sets))
     }
 
     /// retrieve shared data
@@ -451,7 +1192,43 @@ where
             if_modified_since,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+        let shared_data_ids: Vec<String> =
+            shared_data_ids.iter().map(|id| id.to_string()).collect();
+        if shared_data_ids.is_empty() {
+            return Ok(GetSharedDataResponse::BadRequest(bad_request(
+                "shared-data-ids is required".to_string(),
+            )));
+        }
+        let body = match self.shared_data.get_all(&shared_data_ids) {
+            Ok(body) => body,
+            Err(id) => return Ok(GetSharedDataResponse::NotFound(shared_data_not_found(&id))),
+        };
+
+        let last_modified = self
+            .shared_data
+            .last_modified(&shared_data_ids)
+            .unwrap_or_else(Utc::now);
+        let validators = self
+            .conditions(
+                last_modified,
+                if_none_match.as_deref(),
+                if_modified_since.as_deref(),
+            )
+            .validators(&body);
+        Ok(if validators.not_modified {
+            GetSharedDataResponse::NotModified {
+                cache_control: Some(validators.cache_control),
+                e_tag: Some(validators.e_tag),
+                last_modified: Some(validators.last_modified),
+            }
+        } else {
+            GetSharedDataResponse::ExpectedResponseToAValidRequest {
+                body,
+                cache_control: Some(validators.cache_control),
+                e_tag: Some(validators.e_tag),
+                last_modified: Some(validators.last_modified),
+            }
+        })
     }
 
     /// retrieve the individual shared data
@@ -472,7 +1249,49 @@ where
             if_modified_since,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+        // The path segment is a single id
+        let shared_data_id = match shared_data_id.as_slice() {
+            [shared_data_id] => shared_data_id.to_string(),
+            _ => {
+                return Ok(GetIndividualSharedDataResponse::BadRequest(bad_request(
+                    "A single sharedDataId is expected".to_string(),
+                )))
+            }
+        };
+        let body = match self.shared_data.get(&shared_data_id) {
+            Some(body) => body,
+            None => {
+                return Ok(GetIndividualSharedDataResponse::NotFound(
+                    shared_data_not_found(&shared_data_id),
+                ))
+            }
+        };
+
+        let last_modified = self
+            .shared_data
+            .last_modified(&[shared_data_id])
+            .unwrap_or_else(Utc::now);
+        let validators = self
+            .conditions(
+                last_modified,
+                if_none_match.as_deref(),
+                if_modified_since.as_deref(),
+            )
+            .validators(&body);
+        Ok(if validators.not_modified {
+            GetIndividualSharedDataResponse::NotModified {
+                cache_control: Some(validators.cache_control),
+                e_tag: Some(validators.e_tag),
+                last_modified: Some(validators.last_modified),
+            }
+        } else {
+            GetIndividualSharedDataResponse::ExpectedResponseToAValidRequest {
+                body,
+                cache_control: Some(validators.cache_control),
+                e_tag: Some(validators.e_tag),
+                last_modified: Some(validators.last_modified),
+            }
+        })
     }
 
     /// retrieve a UE's SMF Selection Subscription Data
@@ -497,7 +1316,21 @@ where
             if_modified_since,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+        let retrieved = lookup!(
+            GetSmfSelDataResponse,
+            self.lookup(
+                &supi,
+                plmn_id.map(|p| plmn_key(&p.mcc, &p.mnc)),
+                supported_features.as_deref(),
+                if_none_match.as_deref(),
+                if_modified_since.as_deref()
+            )
+        );
+        let smf_sel_data = retrieved.subscriber.smf_sel_data.clone().map(|mut data| {
+            data.supported_features = negotiate_features(supported_features.as_deref());
+            data
+        });
+        respond!(GetSmfSelDataResponse, retrieved, smf_sel_data)
     }
 
     /// retrieve a UE's SMS Management Subscription Data
@@ -520,7 +1353,21 @@ where
             if_modified_since,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+        let retrieved = lookup!(
+            GetSmsMngtDataResponse,
+            self.lookup(
+                &supi,
+                plmn_id.map(|p| plmn_key(&p.mcc, &p.mnc)),
+                supported_features.as_deref(),
+                if_none_match.as_deref(),
+                if_modified_since.as_deref()
+            )
+        );
+        let sms_mng_data = retrieved.subscriber.sms_mng_data.clone().map(|mut data| {
+            data.supported_features = negotiate_features(supported_features.as_deref());
+            data
+        });
+        respond!(GetSmsMngtDataResponse, retrieved, sms_mng_data)
     }
 
     /// retrieve a UE's SMS Subscription Data
@@ -543,7 +1390,21 @@ where
             if_modified_since,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+        let retrieved = lookup!(
+            GetSmsDataResponse,
+            self.lookup(
+                &supi,
+                plmn_id.map(|p| plmn_key(&p.mcc, &p.mnc)),
+                supported_features.as_deref(),
+                if_none_match.as_deref(),
+                if_modified_since.as_deref()
+            )
+        );
+        let sms_subs_data = retrieved.subscriber.sms_subs_data.clone().map(|mut data| {
+            data.supported_features = negotiate_features(supported_features.as_deref());
+            data
+        });
+        respond!(GetSmsDataResponse, retrieved, sms_subs_data)
     }
 
     /// retrieve a UE's Session Management Subscription Data
@@ -570,55 +1431,33 @@ where
             if_modified_since,
             context.get().0.clone()
         );
-        // Err(ApiError("Generic failure".into()))
-
-        // Here we will receive
-        // Open5gs call is to /nudm-sdm/v2/imsi-001011234567895/sm-data?single-nssai=%7B%0A%09%22sst%22%3A%091%0A%7D&dnn=internet
-
-        // and we should send
-        //
-        // [{
-        //     "singleNssai":	{
-        //         "sst":	1
-        //     },
-        //     "dnnConfigurations":	{
-        //         "internet":	{
-        //             "pduSessionTypes":	{
-        //                 "defaultSessionType":	"IPV4",
-        //                 "allowedSessionTypes":	["IPV4"]
-        //             },
-        //             "sscModes":	{
-        //                 "defaultSscMode":	"SSC_MODE_1",
-        //                 "allowedSscModes":	["SSC_MODE_1", "SSC_MODE_2", "SSC_MODE_3"]
-        //             },
-        //             "5gQosProfile":	{
-        //                 "5qi":	9,
-        //                 "arp":	{
-        //                     "priorityLevel":	8,
-        //                     "preemptCap":	"NOT_PREEMPT",
-        //                     "preemptVuln":	"NOT_PREEMPTABLE"
-        //                 },
-        //                 "priorityLevel":	8
-        //             },
-        //             "sessionAmbr":	{
-        //                 "uplink":	"1048576 Kbps",
-        //                 "downlink":	"1048576 Kbps"
-        //             }
-        //         }
-        //     }
-        // }]
-
-        let sm_data = serde_json::from_str::<SessionManagementSubscriptionData>(r#"{"singleNssai":{"sst":1},"dnnConfigurations":{"internet":{"pduSessionTypes":{"defaultSessionType":"IPV4","allowedSessionTypes":["IPV4"]},"sscModes":{"defaultSscMode":"SSC_MODE_1","allowedSscModes":["SSC_MODE_1","SSC_MODE_2","SSC_MODE_3"]},"5gQosProfile":{"5qi":9,"arp":{"priorityLevel":8,"preemptCap":"NOT_PREEMPT","preemptVuln":"NOT_PREEMPTABLE"},"priorityLevel":8},"sessionAmbr":{"uplink":"1048576 Kbps","downlink":"1048576 Kbps"}}}}"#).expect("unable to parse json");
+        let retrieved = lookup!(
+            GetSmDataResponse,
+            self.lookup(
+                &supi,
+                plmn_id.map(|p| plmn_key(&p.mcc, &p.mnc)),
+                supported_features.as_deref(),
+                if_none_match.as_deref(),
+                if_modified_since.as_deref()
+            )
+        );
+
+        let supported_features = negotiate_features(supported_features.as_deref());
+        let sm_data = retrieved
+            .subscriber
+            .sm_data(single_nssai.as_ref(), dnn.as_deref())
+            .into_iter()
+            .map(|mut data| {
+                data.supported_features = supported_features.clone();
+                data
+            })
+            .collect();
+        // Without the SharedData feature, the references are resolved already
         let body = SmSubsData {
-            shared_sm_subs_data_ids: vec![],
-            individual_sm_subs_data: Some(vec![sm_data]),
+            shared_sm_subs_data_ids: retrieved.subscriber.shared_sm_subs_data_ids.clone(),
+            individual_sm_subs_data: Some(sm_data),
         };
-        Ok(GetSmDataResponse::ExpectedResponseToAValidRequest {
-            body,
-            cache_control: None,
-            e_tag: None,
-            last_modified: None,
-        })
+        respond!(GetSmDataResponse, retrieved, Some(body))
     }
 
     /// retrieve a UE's subscribed NSSAI
@@ -643,7 +1482,21 @@ where
             if_modified_since,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+        let retrieved = lookup!(
+            GetNssaiResponse,
+            self.lookup(
+                &supi,
+                plmn_id.map(|p| plmn_key(&p.mcc, &p.mnc)),
+                supported_features.as_deref(),
+                if_none_match.as_deref(),
+                if_modified_since.as_deref()
+            )
+        );
+        let nssai = retrieved.subscriber.nssai.clone().map(|mut nssai| {
+            nssai.supported_features = negotiate_features(supported_features.as_deref());
+            nssai
+        });
+        respond!(GetNssaiResponse, retrieved, nssai)
     }
 
     /// subscribe to notifications
@@ -660,7 +1513,24 @@ where
             sdm_subscription,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+
+        if let Err(e) = sdm_subscription.callback_reference.parse::<hyper::Uri>() {
+            return Ok(SubscribeResponse::BadRequest(bad_request(format!(
+                "Invalid callbackReference: {}",
+                e
+            ))));
+        }
+        lookup!(SubscribeResponse, self.find(&ue_id));
+        self.expire_subscriptions();
+
+        let body = self.subscriptions.create(Some(&ue_id), sdm_subscription);
+        let location = format!(
+            "{}/{}/sdm-subscriptions/{}",
+            nudm_sdm::BASE_PATH,
+            ue_id,
+            body.subscription_id.as_deref().unwrap_or_default()
+        );
+        Ok(SubscribeResponse::ExpectedResponseToAValidRequest { body, location })
     }
 
     /// subscribe to notifications for shared data
@@ -675,7 +1545,21 @@ where
             sdm_subscription,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+
+        if let Err(e) = sdm_subscription.callback_reference.parse::<hyper::Uri>() {
+            return Ok(SubscribeToSharedDataResponse::BadRequest(bad_request(
+                format!("Invalid callbackReference: {}", e),
+            )));
+        }
+        self.expire_subscriptions();
+
+        let body = self.subscriptions.create(None, sdm_subscription);
+        let location = format!(
+            "{}/shared-data-subscriptions/{}",
+            nudm_sdm::BASE_PATH,
+            body.subscription_id.as_deref().unwrap_or_default()
+        );
+        Ok(SubscribeToSharedDataResponse::ExpectedResponseToAValidRequest { body, location })
     }
 
     /// unsubscribe from notifications
@@ -692,7 +1576,13 @@ where
             subscription_id,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+
+        match self.subscriptions.remove(Some(&ue_id), &subscription_id) {
+            Some(_) => Ok(UnsubscribeResponse::SuccessfulResponse),
+            None => Ok(UnsubscribeResponse::NotFound(subscription_not_found(
+                &subscription_id,
+            ))),
+        }
     }
 
     /// unsubscribe from notifications for shared data
@@ -707,7 +1597,13 @@ where
             subscription_id,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+
+        match self.subscriptions.remove(None, &subscription_id) {
+            Some(_) => Ok(UnsubscribeForSharedDataResponse::SuccessfulResponse),
+            None => Ok(UnsubscribeForSharedDataResponse::NotFound(
+                subscription_not_found(&subscription_id),
+            )),
+        }
     }
 
     /// modify the subscription
@@ -728,7 +1624,19 @@ where
             supported_features,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+
+        match self
+            .subscriptions
+            .modify(Some(&ue_id), &subscription_id, sdm_subs_modification)
+        {
+            Some(subscription) => Ok(ModifyResponse::ExpectedResponseToAValidRequest(modified(
+                subscription,
+                supported_features.as_deref(),
+            ))),
+            None => Ok(ModifyResponse::NotFound(subscription_not_found(
+                &subscription_id,
+            ))),
+        }
     }
 
     /// modify the subscription
@@ -747,7 +1655,21 @@ where
             supported_features,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+
+        match self
+            .subscriptions
+            .modify(None, &subscription_id, sdm_subs_modification)
+        {
+            Some(subscription) => Ok(
+                ModifySharedDataSubsResponse::ExpectedResponseToAValidRequest(modified(
+                    subscription,
+                    supported_features.as_deref(),
+                )),
+            ),
+            None => Ok(ModifySharedDataSubsResponse::NotFound(
+                subscription_not_found(&subscription_id),
+            )),
+        }
     }
 
     /// retrieve a UE's Trace Configuration Data
@@ -770,7 +1692,23 @@ where
             if_modified_since,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+        let retrieved = lookup!(
+            GetTraceConfigDataResponse,
+            self.lookup(
+                &supi,
+                plmn_id.map(|p| plmn_key(&p.mcc, &p.mnc)),
+                supported_features.as_deref(),
+                if_none_match.as_deref(),
+                if_modified_since.as_deref()
+            )
+        );
+        // A null trace data tells that no trace is active
+        let mut trace = models::TraceDataResponse::new();
+        trace.trace_data = Some(match retrieved.subscriber.trace_data.clone() {
+            Some(trace_data) => swagger::Nullable::Present(trace_data),
+            None => swagger::Nullable::Null,
+        });
+        respond!(GetTraceConfigDataResponse, retrieved, Some(trace))
     }
 
     /// Nudm_Sdm custom operation to trigger SOR info update
@@ -787,7 +1725,27 @@ where
             sor_update_info,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+        if sor_update_info.is_none() {
+            return Ok(UpdateSorInfoResponse::BadRequest(bad_request(
+                "SorUpdateInfo is required".to_string(),
+            )));
+        }
+        let subscriber = lookup!(UpdateSorInfoResponse, self.find(&supi));
+
+        // The SoR information is sent to the UE right away: the update is
+        // pending from now on
+        let state = self
+            .acknowledgements
+            .trigger(&subscriber.supi, Procedure::Sor);
+        info!(
+            "SoR update {} of {} requested",
+            state.counter_hex(),
+            subscriber.supi
+        );
+        let sor_info = state.sor_info().expect("a triggered update is provisioned");
+        Ok(UpdateSorInfoResponse::ExpectedResponseToAValidRequest(
+            sor_info,
+        ))
     }
 
     /// retrieve a UE's UE Context In AMF Data
@@ -804,7 +1762,11 @@ where
             supported_features,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+        let subscriber = lookup!(GetUeCtxInAmfDataResponse, self.find(&supi));
+        Ok(match subscriber.uec_amf_data {
+            Some(data) => GetUeCtxInAmfDataResponse::ExpectedResponseToAValidRequest(data),
+            None => GetUeCtxInAmfDataResponse::NotFound(data_not_found(&supi)),
+        })
     }
 
     /// retrieve a UE's UE Context In SMF Data
@@ -821,7 +1783,11 @@ where
             supported_features,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+        let subscriber = lookup!(GetUeCtxInSmfDataResponse, self.find(&supi));
+        Ok(match subscriber.uec_smf_data {
+            Some(data) => GetUeCtxInSmfDataResponse::ExpectedResponseToAValidRequest(data),
+            None => GetUeCtxInSmfDataResponse::NotFound(data_not_found(&supi)),
+        })
     }
 
     /// retrieve a UE's UE Context In SMSF Data
@@ -838,7 +1804,11 @@ where
             supported_features,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+        let subscriber = lookup!(GetUeCtxInSmsfDataResponse, self.find(&supi));
+        Ok(match subscriber.uec_smsf_data {
+            Some(data) => GetUeCtxInSmsfDataResponse::ExpectedResponseToAValidRequest(data),
+            None => GetUeCtxInSmsfDataResponse::NotFound(data_not_found(&supi)),
+        })
     }
 
     /// retrieve a UE's User Consent Subscription Data
@@ -861,7 +1831,26 @@ where
             if_modified_since,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+        let retrieved = lookup!(
+            GetUcDataResponse,
+            self.lookup(
+                &supi,
+                None,
+                supported_features.as_deref(),
+                if_none_match.as_deref(),
+                if_modified_since.as_deref()
+            )
+        );
+        let uc_data = retrieved.subscriber.uc_data.clone().map(|mut uc_data| {
+            if let (Some(purpose), Some(consents)) =
+                (&uc_purpose, uc_data.user_consent_per_purpose_list.as_mut())
+            {
+                let purpose = purpose.to_string();
+                consents.retain(|p, _| *p == purpose);
+            }
+            uc_data
+        });
+        respond!(GetUcDataResponse, retrieved, uc_data)
     }
 
     /// retrieve a UE's V2X Subscription Data
@@ -882,6 +1871,16 @@ where
             if_modified_since,
             context.get().0.clone()
         );
-        Err(ApiError("Generic failure".into()))
+        let retrieved = lookup!(
+            GetV2xDataResponse,
+            self.lookup(
+                &supi,
+                None,
+                supported_features.as_deref(),
+                if_none_match.as_deref(),
+                if_modified_since.as_deref()
+            )
+        );
+        respond!(GetV2xDataResponse, retrieved, retrieved.subscriber.v2x_data)
     }
 }
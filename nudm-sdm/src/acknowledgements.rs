@@ -0,0 +1,362 @@
+//! UE parameter updates of the UDM and their acknowledgements.
+//!
+//! The UDM updates the parameters of a UE by provisioning new subscriber
+//! data, which the AMF is notified of and forwards to the UE: the Steering
+//! of Roaming information (SoR), the UE Parameters Update data (UPU), the
+//! CAG information and the subscribed S-NSSAIs (TS 23.502 clauses 4.20 and
+//! 5.2.3.3.1). The UE acknowledges the update through the AMF, with the
+//! Nudm_SDM Info service operation (TS 29.503 clause 5.2.2.5).
+//!
+//! The store keeps, per SUPI and procedure, the last update and its
+//! acknowledgement: only the updates it triggered are acknowledged. SoR and
+//! UPU are only acknowledged while pending, matched by the provisioning time
+//! of the update; the CAG information and S-NSSAIs whenever the AMF sees fit.
+
+use chrono::{DateTime, Timelike, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use crate::models::{self, AcknowledgeInfo, SorInfo, UpuData, UpuInfo};
+
+/// A UE parameter update procedure.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Procedure {
+    #[serde(rename = "sor")]
+    Sor,
+    #[serde(rename = "upu")]
+    Upu,
+    #[serde(rename = "cag")]
+    Cag,
+    #[serde(rename = "subscribed-snssais")]
+    SNssais,
+}
+
+impl Procedure {
+    pub const ALL: [Procedure; 4] = [
+        Procedure::Sor,
+        Procedure::Upu,
+        Procedure::Cag,
+        Procedure::SNssais,
+    ];
+
+    /// The resource the UE acknowledges the procedure on, below
+    /// `{supi}/am-data`.
+    pub fn ack_resource(&self) -> &'static str {
+        match self {
+            Procedure::Sor => "sor-ack",
+            Procedure::Upu => "upu-ack",
+            Procedure::Cag => "cag-ack",
+            Procedure::SNssais => "subscribed-snssais-ack",
+        }
+    }
+
+    /// The procedure acknowledged on a resource below `{supi}/am-data`.
+    pub fn from_ack_resource(resource: &str) -> Option<Procedure> {
+        Procedure::ALL
+            .iter()
+            .copied()
+            .find(|p| p.ack_resource() == resource)
+    }
+
+    /// Whether the acknowledgement must match a pending update.
+    fn is_protected(&self) -> bool {
+        matches!(self, Procedure::Sor | Procedure::Upu)
+    }
+}
+
+impl std::fmt::Display for Procedure {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Procedure::Sor => write!(f, "SoR"),
+            Procedure::Upu => write!(f, "UPU"),
+            Procedure::Cag => write!(f, "CAG"),
+            Procedure::SNssais => write!(f, "S-NSSAI"),
+        }
+    }
+}
+
+/// The last update of a procedure for a UE, and its acknowledgement.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct UpdateState {
+    /// Provisioning time of the update, `None` if the UDM triggered none
+    #[serde(rename = "provisioningTime")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provisioning_time: Option<DateTime<Utc>>,
+
+    /// Number of updates triggered: the countersor or counterUpu of the last
+    /// one, which the UE checks for replays
+    #[serde(rename = "counter")]
+    pub counter: u16,
+
+    /// Whether the last update awaits an acknowledgement
+    #[serde(rename = "pending")]
+    pub pending: bool,
+
+    /// When the last acknowledgement was received
+    #[serde(rename = "acknowledged")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub acknowledged: Option<DateTime<Utc>>,
+
+    #[serde(rename = "acknowledgeInfo")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub acknowledge_info: Option<AcknowledgeInfo>,
+}
+
+impl UpdateState {
+    /// The counter of the last update, as the 4 hexadecimal digits of
+    /// `countersor` and `counterUpu`.
+    pub fn counter_hex(&self) -> String {
+        format!("{:04X}", self.counter)
+    }
+
+    /// The SoR information of the last update, asking the UE for an
+    /// acknowledgement. The SoR-MAC-IAUSF is computed by the AUSF, which
+    /// holds the KAUSF: it is left out.
+    pub fn sor_info(&self) -> Option<SorInfo> {
+        let mut sor_info = SorInfo::new(true, self.provisioning_time?);
+        sor_info.countersor = Some(self.counter_hex());
+        Some(sor_info)
+    }
+
+    /// The UPU data of the last update, asking the UE for an
+    /// acknowledgement. Like for SoR, the UPU-MAC-IAUSF is left out.
+    pub fn upu_info(&self, upu_data_list: Vec<UpuData>) -> Option<UpuInfo> {
+        let mut upu_info = UpuInfo::new(self.provisioning_time?);
+        upu_info.upu_data_list = Some(upu_data_list);
+        upu_info.upu_ack_ind = Some(true);
+        upu_info.counter_upu = Some(self.counter_hex());
+        Some(upu_info)
+    }
+}
+
+/// Thread-safe handle to the update states, keyed by SUPI and procedure.
+#[derive(Debug, Clone, Default)]
+pub struct AcknowledgementStore {
+    states: Arc<RwLock<HashMap<(String, Procedure), UpdateState>>>,
+}
+
+impl AcknowledgementStore {
+    pub fn new() -> AcknowledgementStore {
+        AcknowledgementStore::default()
+    }
+
+    /// Records a new update of a procedure for a UE, now, and returns its
+    /// state: pending until acknowledged.
+    pub fn trigger(&self, supi: &str, procedure: Procedure) -> UpdateState {
+        let mut states = self.states.write().expect("acknowledgement lock poisoned");
+        let state = states
+            .entry((supi.to_string(), procedure))
+            .or_insert_with(UpdateState::default);
+        // Echoed by the UE: whole seconds survive any encoding of the AMF
+        state.provisioning_time = Utc::now().with_nanosecond(0);
+        state.counter = state.counter.wrapping_add(1);
+        state.pending = true;
+        state.clone()
+    }
+
+    /// Records the acknowledgement of an update by a UE, and returns the
+    /// state of the update.
+    pub fn acknowledge(
+        &self,
+        supi: &str,
+        procedure: Procedure,
+        acknowledge_info: Option<AcknowledgeInfo>,
+    ) -> Result<UpdateState, AckError> {
+        let mut states = self.states.write().expect("acknowledgement lock poisoned");
+        let state = states
+            .get_mut(&(supi.to_string(), procedure))
+            .ok_or(AckError::NotTriggered(procedure))?;
+
+        if procedure.is_protected() {
+            if !state.pending {
+                return Err(AckError::NotPending(procedure));
+            }
+            let provisioning_time = acknowledge_info
+                .as_ref()
+                .map(|info| info.provisioning_time)
+                .ok_or(AckError::MissingInfo(procedure))?;
+            if Some(provisioning_time) != state.provisioning_time {
+                return Err(AckError::ProvisioningTime(procedure));
+            }
+        } else if let (Some(info), Some(provisioned)) = (&acknowledge_info, state.provisioning_time)
+        {
+            if state.pending && info.provisioning_time != provisioned {
+                return Err(AckError::ProvisioningTime(procedure));
+            }
+        }
+
+        state.pending = false;
+        state.acknowledged = Some(Utc::now());
+        state.acknowledge_info = acknowledge_info;
+        Ok(state.clone())
+    }
+
+    pub fn get(&self, supi: &str, procedure: Procedure) -> Option<UpdateState> {
+        self.states
+            .read()
+            .expect("acknowledgement lock poisoned")
+            .get(&(supi.to_string(), procedure))
+            .cloned()
+    }
+
+    /// The update states of a UE, by procedure.
+    pub fn states(&self, supi: &str) -> HashMap<Procedure, UpdateState> {
+        let states = self.states.read().expect("acknowledgement lock poisoned");
+        Procedure::ALL
+            .iter()
+            .filter_map(|&procedure| {
+                states
+                    .get(&(supi.to_string(), procedure))
+                    .map(|state| (procedure, state.clone()))
+            })
+            .collect()
+    }
+
+    /// Forgets the updates of a UE, e.g. when it is no longer provisioned.
+    pub fn remove(&self, supi: &str) {
+        self.states
+            .write()
+            .expect("acknowledgement lock poisoned")
+            .retain(|(s, _), _| s != supi);
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AckError {
+    /// The UDM triggered no update of the procedure for the UE
+    NotTriggered(Procedure),
+    /// No update of the procedure awaits an acknowledgement
+    NotPending(Procedure),
+    /// The acknowledgement lacks the AcknowledgeInfo
+    MissingInfo(Procedure),
+    /// The acknowledgement is not about the pending update
+    ProvisioningTime(Procedure),
+}
+
+impl std::fmt::Display for AckError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AckError::NotTriggered(procedure) => write!(f, "No {} update of the UE", procedure),
+            AckError::NotPending(procedure) => write!(f, "No pending {} update", procedure),
+            AckError::MissingInfo(procedure) => {
+                write!(f, "Missing acknowledgement of the {} update", procedure)
+            }
+            AckError::ProvisioningTime(procedure) => write!(
+                f,
+                "provisioningTime does not match the pending {} update",
+                procedure
+            ),
+        }
+    }
+}
+
+impl std::error::Error for AckError {}
+
+impl AckError {
+    /// The problem of an acknowledgement the UDM does not accept.
+    pub fn problem(&self) -> models::ProblemDetails {
+        let mut problem = models::ProblemDetails::new();
+        problem.status = Some(400);
+        problem.cause = Some(
+            match self {
+                AckError::MissingInfo(_) => "MANDATORY_IE_MISSING",
+                _ => "MANDATORY_IE_INCORRECT",
+            }
+            .to_string(),
+        );
+        problem.detail = Some(self.to_string());
+        problem
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SUPI: &str = "imsi-001011234567895";
+
+    fn info(provisioning_time: DateTime<Utc>) -> Option<AcknowledgeInfo> {
+        Some(AcknowledgeInfo::new(provisioning_time))
+    }
+
+    #[test]
+    fn updates_that_were_not_triggered_are_not_acknowledged() {
+        let store = AcknowledgementStore::new();
+        store.trigger(SUPI, Procedure::Sor);
+
+        for &procedure in Procedure::ALL.iter() {
+            assert_eq!(
+                store.acknowledge("imsi-001010000000001", procedure, info(Utc::now())),
+                Err(AckError::NotTriggered(procedure))
+            );
+        }
+        assert_eq!(
+            store.acknowledge(SUPI, Procedure::Cag, None),
+            Err(AckError::NotTriggered(Procedure::Cag))
+        );
+        assert!(store.states("imsi-001010000000001").is_empty());
+        assert_eq!(store.get(SUPI, Procedure::Cag), None);
+    }
+
+    #[test]
+    fn protected_updates_are_acknowledged_once_with_their_provisioning_time() {
+        let store = AcknowledgementStore::new();
+        for &procedure in [Procedure::Sor, Procedure::Upu].iter() {
+            let triggered = store.trigger(SUPI, procedure);
+            let provisioning_time = triggered.provisioning_time.unwrap();
+
+            let acknowledged = store
+                .acknowledge(SUPI, procedure, info(provisioning_time))
+                .unwrap();
+            assert!(!acknowledged.pending);
+            assert_eq!(acknowledged.counter, triggered.counter);
+            assert!(acknowledged.acknowledged.is_some());
+            assert_eq!(store.get(SUPI, procedure), Some(acknowledged));
+            assert_eq!(
+                store.acknowledge(SUPI, procedure, info(provisioning_time)),
+                Err(AckError::NotPending(procedure))
+            );
+        }
+    }
+
+    #[test]
+    fn protected_updates_are_not_acknowledged_with_another_provisioning_time() {
+        let store = AcknowledgementStore::new();
+        for &procedure in [Procedure::Sor, Procedure::Upu].iter() {
+            let triggered = store.trigger(SUPI, procedure);
+            let provisioning_time = triggered.provisioning_time.unwrap();
+
+            assert_eq!(
+                store.acknowledge(
+                    SUPI,
+                    procedure,
+                    info(provisioning_time - chrono::Duration::seconds(1))
+                ),
+                Err(AckError::ProvisioningTime(procedure))
+            );
+            assert_eq!(
+                store.acknowledge(SUPI, procedure, None),
+                Err(AckError::MissingInfo(procedure))
+            );
+            assert_eq!(store.get(SUPI, procedure), Some(triggered));
+        }
+    }
+
+    #[test]
+    fn s_nssais_are_acknowledged_whenever() {
+        let store = AcknowledgementStore::new();
+        let triggered = store.trigger(SUPI, Procedure::SNssais);
+
+        assert!(store.acknowledge(SUPI, Procedure::SNssais, None).is_ok());
+        let acknowledged = store
+            .acknowledge(
+                SUPI,
+                Procedure::SNssais,
+                info(triggered.provisioning_time.unwrap()),
+            )
+            .unwrap();
+        assert!(!acknowledged.pending);
+        assert!(acknowledged.acknowledge_info.is_some());
+    }
+}
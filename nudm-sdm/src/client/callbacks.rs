@@ -28,12 +28,19 @@ use crate::DatachangeNotificationRequestBodyCallbackReferencePostResponse;
 mod paths {
     use lazy_static::lazy_static;
 
+    // The callback reference is chosen by the subscriber, so any path is accepted.
     lazy_static! {
         pub static ref GLOBAL_REGEX_SET: regex::RegexSet =
-            regex::RegexSet::new(vec![r"^/nudm-sdm/v2/{request.body#/callbackReference}$"])
+            regex::RegexSet::new(vec![r"^/(?P<request_body_callback_reference>.*)$"])
                 .expect("Unable to create global regex set");
     }
     pub(crate) static ID_REQUEST_BODY_CALLBACKREFERENCE: usize = 0;
+    lazy_static! {
+        pub static ref REGEX_REQUEST_BODY_CALLBACKREFERENCE: regex::Regex =
+            #[allow(clippy::invalid_regex)]
+            regex::Regex::new(r"^/(?P<request_body_callback_reference>.*)$")
+                .expect("Unable to create regex for REQUEST_BODY_CALLBACKREFERENCE");
+    }
 }
 
 pub struct MakeService<T, C>
@@ -148,6 +155,16 @@ where
             match method {
                 // DatachangeNotificationRequestBodyCallbackReferencePost - POST /{request.body#/callbackReference}
                 hyper::Method::POST if path.matched(paths::ID_REQUEST_BODY_CALLBACKREFERENCE) => {
+                    // Path parameters
+                    let path: &str = uri.path();
+                    let path_params =
+                    paths::REGEX_REQUEST_BODY_CALLBACKREFERENCE
+                    .captures(path)
+                    .unwrap_or_else(||
+                        panic!("Path {} matched RE REQUEST_BODY_CALLBACKREFERENCE in set but failed match against \"{}\"", path, paths::REGEX_REQUEST_BODY_CALLBACKREFERENCE.as_str())
+                    );
+
+                    let callback_request_body_callback_reference = path_params["request_body_callback_reference"].to_string();
                     // Body parameters (note that non-required body parameters will ignore garbage
                     // values, rather than causing a 400 response). Produce warning header and logs for
                     // any unused fields.
@@ -179,6 +196,7 @@ where
                                 };
 
                                 let result = api_impl.datachange_notification_request_body_callback_reference_post(
+                                            callback_request_body_callback_reference,
                                             param_modification_notification,
                                         &context
                                     ).await;
@@ -359,6 +377,16 @@ where
 
                 // DatachangeNotificationRequestBodyCallbackReferencePost - POST /{request.body#/callbackReference}
                 hyper::Method::POST if path.matched(paths::ID_REQUEST_BODY_CALLBACKREFERENCE) => {
+                    // Path parameters
+                    let path: &str = uri.path();
+                    let path_params =
+                    paths::REGEX_REQUEST_BODY_CALLBACKREFERENCE
+                    .captures(path)
+                    .unwrap_or_else(||
+                        panic!("Path {} matched RE REQUEST_BODY_CALLBACKREFERENCE in set but failed match against \"{}\"", path, paths::REGEX_REQUEST_BODY_CALLBACKREFERENCE.as_str())
+                    );
+
+                    let callback_request_body_callback_reference = path_params["request_body_callback_reference"].to_string();
                     // Body parameters (note that non-required body parameters will ignore garbage
                     // values, rather than causing a 400 response). Produce warning header and logs for
                     // any unused fields.
@@ -390,6 +418,7 @@ where
                                 };
 
                                 let result = api_impl.datachange_notification_request_body_callback_reference_post(
+                                            callback_request_body_callback_reference,
                                             param_modification_notification,
                                         &context
                                     ).await;
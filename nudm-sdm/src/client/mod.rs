@@ -634,6 +634,73 @@ where
                     last_modified: response_last_modified,
                 })
             }
+            304 => {
+                let response_cache_control = match response
+                    .headers()
+                    .get(HeaderName::from_static("cache-control"))
+                {
+                    Some(response_cache_control) => {
+                        let response_cache_control = response_cache_control.clone();
+                        let response_cache_control =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_cache_control,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Cache-Control for response 304 - {}", e)));
+                                }
+                            };
+                        Some(response_cache_control.0)
+                    }
+                    None => None,
+                };
+
+                let response_e_tag = match response.headers().get(HeaderName::from_static("etag")) {
+                    Some(response_e_tag) => {
+                        let response_e_tag = response_e_tag.clone();
+                        let response_e_tag =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_e_tag,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!(
+                                        "Invalid response header ETag for response 304 - {}",
+                                        e
+                                    )));
+                                }
+                            };
+                        Some(response_e_tag.0)
+                    }
+                    None => None,
+                };
+
+                let response_last_modified = match response
+                    .headers()
+                    .get(HeaderName::from_static("last-modified"))
+                {
+                    Some(response_last_modified) => {
+                        let response_last_modified = response_last_modified.clone();
+                        let response_last_modified =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_last_modified,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Last-Modified for response 304 - {}", e)));
+                                }
+                            };
+                        Some(response_last_modified.0)
+                    }
+                    None => None,
+                };
+
+                Ok(GetAmDataResponse::NotModified {
+                    cache_control: response_cache_control,
+                    e_tag: response_e_tag,
+                    last_modified: response_last_modified,
+                })
+            }
             400 => {
                 let body = response.into_body();
                 let body = body
@@ -907,6 +974,73 @@ where
                     last_modified: response_last_modified,
                 })
             }
+            304 => {
+                let response_cache_control = match response
+                    .headers()
+                    .get(HeaderName::from_static("cache-control"))
+                {
+                    Some(response_cache_control) => {
+                        let response_cache_control = response_cache_control.clone();
+                        let response_cache_control =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_cache_control,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Cache-Control for response 304 - {}", e)));
+                                }
+                            };
+                        Some(response_cache_control.0)
+                    }
+                    None => None,
+                };
+
+                let response_e_tag = match response.headers().get(HeaderName::from_static("etag")) {
+                    Some(response_e_tag) => {
+                        let response_e_tag = response_e_tag.clone();
+                        let response_e_tag =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_e_tag,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!(
+                                        "Invalid response header ETag for response 304 - {}",
+                                        e
+                                    )));
+                                }
+                            };
+                        Some(response_e_tag.0)
+                    }
+                    None => None,
+                };
+
+                let response_last_modified = match response
+                    .headers()
+                    .get(HeaderName::from_static("last-modified"))
+                {
+                    Some(response_last_modified) => {
+                        let response_last_modified = response_last_modified.clone();
+                        let response_last_modified =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_last_modified,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Last-Modified for response 304 - {}", e)));
+                                }
+                            };
+                        Some(response_last_modified.0)
+                    }
+                    None => None,
+                };
+
+                Ok(GetMbsDataResponse::NotModified {
+                    cache_control: response_cache_control,
+                    e_tag: response_e_tag,
+                    last_modified: response_last_modified,
+                })
+            }
             400 => {
                 let body = response.into_body();
                 let body = body
@@ -1180,6 +1314,73 @@ where
                     last_modified: response_last_modified,
                 })
             }
+            304 => {
+                let response_cache_control = match response
+                    .headers()
+                    .get(HeaderName::from_static("cache-control"))
+                {
+                    Some(response_cache_control) => {
+                        let response_cache_control = response_cache_control.clone();
+                        let response_cache_control =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_cache_control,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Cache-Control for response 304 - {}", e)));
+                                }
+                            };
+                        Some(response_cache_control.0)
+                    }
+                    None => None,
+                };
+
+                let response_e_tag = match response.headers().get(HeaderName::from_static("etag")) {
+                    Some(response_e_tag) => {
+                        let response_e_tag = response_e_tag.clone();
+                        let response_e_tag =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_e_tag,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!(
+                                        "Invalid response header ETag for response 304 - {}",
+                                        e
+                                    )));
+                                }
+                            };
+                        Some(response_e_tag.0)
+                    }
+                    None => None,
+                };
+
+                let response_last_modified = match response
+                    .headers()
+                    .get(HeaderName::from_static("last-modified"))
+                {
+                    Some(response_last_modified) => {
+                        let response_last_modified = response_last_modified.clone();
+                        let response_last_modified =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_last_modified,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Last-Modified for response 304 - {}", e)));
+                                }
+                            };
+                        Some(response_last_modified.0)
+                    }
+                    None => None,
+                };
+
+                Ok(GetEcrDataResponse::NotModified {
+                    cache_control: response_cache_control,
+                    e_tag: response_e_tag,
+                    last_modified: response_last_modified,
+                })
+            }
             400 => {
                 let body = response.into_body();
                 let body = body
@@ -1261,6 +1462,7 @@ where
         param_af_service_id: Option<String>,
         param_mtc_provider_info: Option<String>,
         param_requested_gpsi_type: Option<models::GpsiType>,
+        param_requested_supi: Option<bool>,
         param_if_none_match: Option<String>,
         param_if_modified_since: Option<String>,
         context: &C,
@@ -1307,6 +1509,9 @@ where
                     &param_requested_gpsi_type.to_string(),
                 );
             }
+            if let Some(param_requested_supi) = param_requested_supi {
+                query_string.append_pair("requested-supi", &param_requested_supi.to_string());
+            }
             query_string.finish()
         };
         if !query_string.is_empty() {
@@ -1487,6 +1692,73 @@ where
                     last_modified: response_last_modified,
                 })
             }
+            304 => {
+                let response_cache_control = match response
+                    .headers()
+                    .get(HeaderName::from_static("cache-control"))
+                {
+                    Some(response_cache_control) => {
+                        let response_cache_control = response_cache_control.clone();
+                        let response_cache_control =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_cache_control,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Cache-Control for response 304 - {}", e)));
+                                }
+                            };
+                        Some(response_cache_control.0)
+                    }
+                    None => None,
+                };
+
+                let response_e_tag = match response.headers().get(HeaderName::from_static("etag")) {
+                    Some(response_e_tag) => {
+                        let response_e_tag = response_e_tag.clone();
+                        let response_e_tag =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_e_tag,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!(
+                                        "Invalid response header ETag for response 304 - {}",
+                                        e
+                                    )));
+                                }
+                            };
+                        Some(response_e_tag.0)
+                    }
+                    None => None,
+                };
+
+                let response_last_modified = match response
+                    .headers()
+                    .get(HeaderName::from_static("last-modified"))
+                {
+                    Some(response_last_modified) => {
+                        let response_last_modified = response_last_modified.clone();
+                        let response_last_modified =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_last_modified,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Last-Modified for response 304 - {}", e)));
+                                }
+                            };
+                        Some(response_last_modified.0)
+                    }
+                    None => None,
+                };
+
+                Ok(GetSupiOrGpsiResponse::NotModified {
+                    cache_control: response_cache_control,
+                    e_tag: response_e_tag,
+                    last_modified: response_last_modified,
+                })
+            }
             400 => {
                 let body = response.into_body();
                 let body = body
@@ -1788,6 +2060,73 @@ where
                     },
                 )
             }
+            304 => {
+                let response_cache_control = match response
+                    .headers()
+                    .get(HeaderName::from_static("cache-control"))
+                {
+                    Some(response_cache_control) => {
+                        let response_cache_control = response_cache_control.clone();
+                        let response_cache_control =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_cache_control,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Cache-Control for response 304 - {}", e)));
+                                }
+                            };
+                        Some(response_cache_control.0)
+                    }
+                    None => None,
+                };
+
+                let response_e_tag = match response.headers().get(HeaderName::from_static("etag")) {
+                    Some(response_e_tag) => {
+                        let response_e_tag = response_e_tag.clone();
+                        let response_e_tag =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_e_tag,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!(
+                                        "Invalid response header ETag for response 304 - {}",
+                                        e
+                                    )));
+                                }
+                            };
+                        Some(response_e_tag.0)
+                    }
+                    None => None,
+                };
+
+                let response_last_modified = match response
+                    .headers()
+                    .get(HeaderName::from_static("last-modified"))
+                {
+                    Some(response_last_modified) => {
+                        let response_last_modified = response_last_modified.clone();
+                        let response_last_modified =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_last_modified,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Last-Modified for response 304 - {}", e)));
+                                }
+                            };
+                        Some(response_last_modified.0)
+                    }
+                    None => None,
+                };
+
+                Ok(GetGroupIdentifiersResponse::NotModified {
+                    cache_control: response_cache_control,
+                    e_tag: response_e_tag,
+                    last_modified: response_last_modified,
+                })
+            }
             400 => {
                 let body = response.into_body();
                 let body = body
@@ -2089,6 +2428,73 @@ where
                     last_modified: response_last_modified,
                 })
             }
+            304 => {
+                let response_cache_control = match response
+                    .headers()
+                    .get(HeaderName::from_static("cache-control"))
+                {
+                    Some(response_cache_control) => {
+                        let response_cache_control = response_cache_control.clone();
+                        let response_cache_control =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_cache_control,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Cache-Control for response 304 - {}", e)));
+                                }
+                            };
+                        Some(response_cache_control.0)
+                    }
+                    None => None,
+                };
+
+                let response_e_tag = match response.headers().get(HeaderName::from_static("etag")) {
+                    Some(response_e_tag) => {
+                        let response_e_tag = response_e_tag.clone();
+                        let response_e_tag =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_e_tag,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!(
+                                        "Invalid response header ETag for response 304 - {}",
+                                        e
+                                    )));
+                                }
+                            };
+                        Some(response_e_tag.0)
+                    }
+                    None => None,
+                };
+
+                let response_last_modified = match response
+                    .headers()
+                    .get(HeaderName::from_static("last-modified"))
+                {
+                    Some(response_last_modified) => {
+                        let response_last_modified = response_last_modified.clone();
+                        let response_last_modified =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_last_modified,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Last-Modified for response 304 - {}", e)));
+                                }
+                            };
+                        Some(response_last_modified.0)
+                    }
+                    None => None,
+                };
+
+                Ok(GetLcsBcaDataResponse::NotModified {
+                    cache_control: response_cache_control,
+                    e_tag: response_e_tag,
+                    last_modified: response_last_modified,
+                })
+            }
             400 => {
                 let body = response.into_body();
                 let body = body
@@ -2361,6 +2767,73 @@ where
                     last_modified: response_last_modified,
                 })
             }
+            304 => {
+                let response_cache_control = match response
+                    .headers()
+                    .get(HeaderName::from_static("cache-control"))
+                {
+                    Some(response_cache_control) => {
+                        let response_cache_control = response_cache_control.clone();
+                        let response_cache_control =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_cache_control,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Cache-Control for response 304 - {}", e)));
+                                }
+                            };
+                        Some(response_cache_control.0)
+                    }
+                    None => None,
+                };
+
+                let response_e_tag = match response.headers().get(HeaderName::from_static("etag")) {
+                    Some(response_e_tag) => {
+                        let response_e_tag = response_e_tag.clone();
+                        let response_e_tag =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_e_tag,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!(
+                                        "Invalid response header ETag for response 304 - {}",
+                                        e
+                                    )));
+                                }
+                            };
+                        Some(response_e_tag.0)
+                    }
+                    None => None,
+                };
+
+                let response_last_modified = match response
+                    .headers()
+                    .get(HeaderName::from_static("last-modified"))
+                {
+                    Some(response_last_modified) => {
+                        let response_last_modified = response_last_modified.clone();
+                        let response_last_modified =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_last_modified,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Last-Modified for response 304 - {}", e)));
+                                }
+                            };
+                        Some(response_last_modified.0)
+                    }
+                    None => None,
+                };
+
+                Ok(GetLcsMoDataResponse::NotModified {
+                    cache_control: response_cache_control,
+                    e_tag: response_e_tag,
+                    last_modified: response_last_modified,
+                })
+            }
             400 => {
                 let body = response.into_body();
                 let body = body
@@ -2568,7 +3041,85 @@ where
                             ) {
                                 Ok(value) => value,
                                 Err(e) => {
-                                    return Err(ApiError(format!("Invalid response header Cache-Control for response 200 - {}", e)));
+                                    return Err(ApiError(format!("Invalid response header Cache-Control for response 200 - {}", e)));
+                                }
+                            };
+                        Some(response_cache_control.0)
+                    }
+                    None => None,
+                };
+
+                let response_e_tag = match response.headers().get(HeaderName::from_static("etag")) {
+                    Some(response_e_tag) => {
+                        let response_e_tag = response_e_tag.clone();
+                        let response_e_tag =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_e_tag,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!(
+                                        "Invalid response header ETag for response 200 - {}",
+                                        e
+                                    )));
+                                }
+                            };
+                        Some(response_e_tag.0)
+                    }
+                    None => None,
+                };
+
+                let response_last_modified = match response
+                    .headers()
+                    .get(HeaderName::from_static("last-modified"))
+                {
+                    Some(response_last_modified) => {
+                        let response_last_modified = response_last_modified.clone();
+                        let response_last_modified =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_last_modified,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Last-Modified for response 200 - {}", e)));
+                                }
+                            };
+                        Some(response_last_modified.0)
+                    }
+                    None => None,
+                };
+
+                let body = response.into_body();
+                let body = body
+                    .into_raw()
+                    .map_err(|e| ApiError(format!("Failed to read response: {}", e)))
+                    .await?;
+                let body = str::from_utf8(&body)
+                    .map_err(|e| ApiError(format!("Response was not valid UTF8: {}", e)))?;
+                let body = serde_json::from_str::<models::LcsPrivacyData>(body).map_err(|e| {
+                    ApiError(format!("Response body did not match the schema: {}", e))
+                })?;
+                Ok(GetLcsPrivacyDataResponse::ExpectedResponseToAValidRequest {
+                    body,
+                    cache_control: response_cache_control,
+                    e_tag: response_e_tag,
+                    last_modified: response_last_modified,
+                })
+            }
+            304 => {
+                let response_cache_control = match response
+                    .headers()
+                    .get(HeaderName::from_static("cache-control"))
+                {
+                    Some(response_cache_control) => {
+                        let response_cache_control = response_cache_control.clone();
+                        let response_cache_control =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_cache_control,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Cache-Control for response 304 - {}", e)));
                                 }
                             };
                         Some(response_cache_control.0)
@@ -2586,7 +3137,7 @@ where
                                 Ok(value) => value,
                                 Err(e) => {
                                     return Err(ApiError(format!(
-                                        "Invalid response header ETag for response 200 - {}",
+                                        "Invalid response header ETag for response 304 - {}",
                                         e
                                     )));
                                 }
@@ -2608,7 +3159,7 @@ where
                             ) {
                                 Ok(value) => value,
                                 Err(e) => {
-                                    return Err(ApiError(format!("Invalid response header Last-Modified for response 200 - {}", e)));
+                                    return Err(ApiError(format!("Invalid response header Last-Modified for response 304 - {}", e)));
                                 }
                             };
                         Some(response_last_modified.0)
@@ -2616,18 +3167,7 @@ where
                     None => None,
                 };
 
-                let body = response.into_body();
-                let body = body
-                    .into_raw()
-                    .map_err(|e| ApiError(format!("Failed to read response: {}", e)))
-                    .await?;
-                let body = str::from_utf8(&body)
-                    .map_err(|e| ApiError(format!("Response was not valid UTF8: {}", e)))?;
-                let body = serde_json::from_str::<models::LcsPrivacyData>(body).map_err(|e| {
-                    ApiError(format!("Response body did not match the schema: {}", e))
-                })?;
-                Ok(GetLcsPrivacyDataResponse::ExpectedResponseToAValidRequest {
-                    body,
+                Ok(GetLcsPrivacyDataResponse::NotModified {
                     cache_control: response_cache_control,
                     e_tag: response_e_tag,
                     last_modified: response_last_modified,
@@ -3200,6 +3740,73 @@ where
                     last_modified: response_last_modified,
                 })
             }
+            304 => {
+                let response_cache_control = match response
+                    .headers()
+                    .get(HeaderName::from_static("cache-control"))
+                {
+                    Some(response_cache_control) => {
+                        let response_cache_control = response_cache_control.clone();
+                        let response_cache_control =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_cache_control,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Cache-Control for response 304 - {}", e)));
+                                }
+                            };
+                        Some(response_cache_control.0)
+                    }
+                    None => None,
+                };
+
+                let response_e_tag = match response.headers().get(HeaderName::from_static("etag")) {
+                    Some(response_e_tag) => {
+                        let response_e_tag = response_e_tag.clone();
+                        let response_e_tag =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_e_tag,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!(
+                                        "Invalid response header ETag for response 304 - {}",
+                                        e
+                                    )));
+                                }
+                            };
+                        Some(response_e_tag.0)
+                    }
+                    None => None,
+                };
+
+                let response_last_modified = match response
+                    .headers()
+                    .get(HeaderName::from_static("last-modified"))
+                {
+                    Some(response_last_modified) => {
+                        let response_last_modified = response_last_modified.clone();
+                        let response_last_modified =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_last_modified,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Last-Modified for response 304 - {}", e)));
+                                }
+                            };
+                        Some(response_last_modified.0)
+                    }
+                    None => None,
+                };
+
+                Ok(GetProseDataResponse::NotModified {
+                    cache_control: response_cache_control,
+                    e_tag: response_e_tag,
+                    last_modified: response_last_modified,
+                })
+            }
             400 => {
                 let body = response.into_body();
                 let body = body
@@ -4156,6 +4763,73 @@ where
                     last_modified: response_last_modified,
                 })
             }
+            304 => {
+                let response_cache_control = match response
+                    .headers()
+                    .get(HeaderName::from_static("cache-control"))
+                {
+                    Some(response_cache_control) => {
+                        let response_cache_control = response_cache_control.clone();
+                        let response_cache_control =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_cache_control,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Cache-Control for response 304 - {}", e)));
+                                }
+                            };
+                        Some(response_cache_control.0)
+                    }
+                    None => None,
+                };
+
+                let response_e_tag = match response.headers().get(HeaderName::from_static("etag")) {
+                    Some(response_e_tag) => {
+                        let response_e_tag = response_e_tag.clone();
+                        let response_e_tag =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_e_tag,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!(
+                                        "Invalid response header ETag for response 304 - {}",
+                                        e
+                                    )));
+                                }
+                            };
+                        Some(response_e_tag.0)
+                    }
+                    None => None,
+                };
+
+                let response_last_modified = match response
+                    .headers()
+                    .get(HeaderName::from_static("last-modified"))
+                {
+                    Some(response_last_modified) => {
+                        let response_last_modified = response_last_modified.clone();
+                        let response_last_modified =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_last_modified,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Last-Modified for response 304 - {}", e)));
+                                }
+                            };
+                        Some(response_last_modified.0)
+                    }
+                    None => None,
+                };
+
+                Ok(GetDataSetsResponse::NotModified {
+                    cache_control: response_cache_control,
+                    e_tag: response_e_tag,
+                    last_modified: response_last_modified,
+                })
+            }
             400 => {
                 let body = response.into_body();
                 let body = body
@@ -4436,6 +5110,73 @@ where
                     last_modified: response_last_modified,
                 })
             }
+            304 => {
+                let response_cache_control = match response
+                    .headers()
+                    .get(HeaderName::from_static("cache-control"))
+                {
+                    Some(response_cache_control) => {
+                        let response_cache_control = response_cache_control.clone();
+                        let response_cache_control =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_cache_control,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Cache-Control for response 304 - {}", e)));
+                                }
+                            };
+                        Some(response_cache_control.0)
+                    }
+                    None => None,
+                };
+
+                let response_e_tag = match response.headers().get(HeaderName::from_static("etag")) {
+                    Some(response_e_tag) => {
+                        let response_e_tag = response_e_tag.clone();
+                        let response_e_tag =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_e_tag,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!(
+                                        "Invalid response header ETag for response 304 - {}",
+                                        e
+                                    )));
+                                }
+                            };
+                        Some(response_e_tag.0)
+                    }
+                    None => None,
+                };
+
+                let response_last_modified = match response
+                    .headers()
+                    .get(HeaderName::from_static("last-modified"))
+                {
+                    Some(response_last_modified) => {
+                        let response_last_modified = response_last_modified.clone();
+                        let response_last_modified =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_last_modified,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Last-Modified for response 304 - {}", e)));
+                                }
+                            };
+                        Some(response_last_modified.0)
+                    }
+                    None => None,
+                };
+
+                Ok(GetSharedDataResponse::NotModified {
+                    cache_control: response_cache_control,
+                    e_tag: response_e_tag,
+                    last_modified: response_last_modified,
+                })
+            }
             400 => {
                 let body = response.into_body();
                 let body = body
@@ -4713,6 +5454,73 @@ where
                     },
                 )
             }
+            304 => {
+                let response_cache_control = match response
+                    .headers()
+                    .get(HeaderName::from_static("cache-control"))
+                {
+                    Some(response_cache_control) => {
+                        let response_cache_control = response_cache_control.clone();
+                        let response_cache_control =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_cache_control,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Cache-Control for response 304 - {}", e)));
+                                }
+                            };
+                        Some(response_cache_control.0)
+                    }
+                    None => None,
+                };
+
+                let response_e_tag = match response.headers().get(HeaderName::from_static("etag")) {
+                    Some(response_e_tag) => {
+                        let response_e_tag = response_e_tag.clone();
+                        let response_e_tag =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_e_tag,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!(
+                                        "Invalid response header ETag for response 304 - {}",
+                                        e
+                                    )));
+                                }
+                            };
+                        Some(response_e_tag.0)
+                    }
+                    None => None,
+                };
+
+                let response_last_modified = match response
+                    .headers()
+                    .get(HeaderName::from_static("last-modified"))
+                {
+                    Some(response_last_modified) => {
+                        let response_last_modified = response_last_modified.clone();
+                        let response_last_modified =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_last_modified,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Last-Modified for response 304 - {}", e)));
+                                }
+                            };
+                        Some(response_last_modified.0)
+                    }
+                    None => None,
+                };
+
+                Ok(GetIndividualSharedDataResponse::NotModified {
+                    cache_control: response_cache_control,
+                    e_tag: response_e_tag,
+                    last_modified: response_last_modified,
+                })
+            }
             400 => {
                 let body = response.into_body();
                 let body = body
@@ -5008,6 +5816,73 @@ where
                     last_modified: response_last_modified,
                 })
             }
+            304 => {
+                let response_cache_control = match response
+                    .headers()
+                    .get(HeaderName::from_static("cache-control"))
+                {
+                    Some(response_cache_control) => {
+                        let response_cache_control = response_cache_control.clone();
+                        let response_cache_control =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_cache_control,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Cache-Control for response 304 - {}", e)));
+                                }
+                            };
+                        Some(response_cache_control.0)
+                    }
+                    None => None,
+                };
+
+                let response_e_tag = match response.headers().get(HeaderName::from_static("etag")) {
+                    Some(response_e_tag) => {
+                        let response_e_tag = response_e_tag.clone();
+                        let response_e_tag =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_e_tag,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!(
+                                        "Invalid response header ETag for response 304 - {}",
+                                        e
+                                    )));
+                                }
+                            };
+                        Some(response_e_tag.0)
+                    }
+                    None => None,
+                };
+
+                let response_last_modified = match response
+                    .headers()
+                    .get(HeaderName::from_static("last-modified"))
+                {
+                    Some(response_last_modified) => {
+                        let response_last_modified = response_last_modified.clone();
+                        let response_last_modified =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_last_modified,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Last-Modified for response 304 - {}", e)));
+                                }
+                            };
+                        Some(response_last_modified.0)
+                    }
+                    None => None,
+                };
+
+                Ok(GetSmfSelDataResponse::NotModified {
+                    cache_control: response_cache_control,
+                    e_tag: response_e_tag,
+                    last_modified: response_last_modified,
+                })
+            }
             400 => {
                 let body = response.into_body();
                 let body = body
@@ -5296,6 +6171,73 @@ where
                     last_modified: response_last_modified,
                 })
             }
+            304 => {
+                let response_cache_control = match response
+                    .headers()
+                    .get(HeaderName::from_static("cache-control"))
+                {
+                    Some(response_cache_control) => {
+                        let response_cache_control = response_cache_control.clone();
+                        let response_cache_control =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_cache_control,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Cache-Control for response 304 - {}", e)));
+                                }
+                            };
+                        Some(response_cache_control.0)
+                    }
+                    None => None,
+                };
+
+                let response_e_tag = match response.headers().get(HeaderName::from_static("etag")) {
+                    Some(response_e_tag) => {
+                        let response_e_tag = response_e_tag.clone();
+                        let response_e_tag =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_e_tag,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!(
+                                        "Invalid response header ETag for response 304 - {}",
+                                        e
+                                    )));
+                                }
+                            };
+                        Some(response_e_tag.0)
+                    }
+                    None => None,
+                };
+
+                let response_last_modified = match response
+                    .headers()
+                    .get(HeaderName::from_static("last-modified"))
+                {
+                    Some(response_last_modified) => {
+                        let response_last_modified = response_last_modified.clone();
+                        let response_last_modified =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_last_modified,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Last-Modified for response 304 - {}", e)));
+                                }
+                            };
+                        Some(response_last_modified.0)
+                    }
+                    None => None,
+                };
+
+                Ok(GetSmsMngtDataResponse::NotModified {
+                    cache_control: response_cache_control,
+                    e_tag: response_e_tag,
+                    last_modified: response_last_modified,
+                })
+            }
             400 => {
                 let body = response.into_body();
                 let body = body
@@ -5584,6 +6526,73 @@ where
                     last_modified: response_last_modified,
                 })
             }
+            304 => {
+                let response_cache_control = match response
+                    .headers()
+                    .get(HeaderName::from_static("cache-control"))
+                {
+                    Some(response_cache_control) => {
+                        let response_cache_control = response_cache_control.clone();
+                        let response_cache_control =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_cache_control,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Cache-Control for response 304 - {}", e)));
+                                }
+                            };
+                        Some(response_cache_control.0)
+                    }
+                    None => None,
+                };
+
+                let response_e_tag = match response.headers().get(HeaderName::from_static("etag")) {
+                    Some(response_e_tag) => {
+                        let response_e_tag = response_e_tag.clone();
+                        let response_e_tag =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_e_tag,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!(
+                                        "Invalid response header ETag for response 304 - {}",
+                                        e
+                                    )));
+                                }
+                            };
+                        Some(response_e_tag.0)
+                    }
+                    None => None,
+                };
+
+                let response_last_modified = match response
+                    .headers()
+                    .get(HeaderName::from_static("last-modified"))
+                {
+                    Some(response_last_modified) => {
+                        let response_last_modified = response_last_modified.clone();
+                        let response_last_modified =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_last_modified,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Last-Modified for response 304 - {}", e)));
+                                }
+                            };
+                        Some(response_last_modified.0)
+                    }
+                    None => None,
+                };
+
+                Ok(GetSmsDataResponse::NotModified {
+                    cache_control: response_cache_control,
+                    e_tag: response_e_tag,
+                    last_modified: response_last_modified,
+                })
+            }
             400 => {
                 let body = response.into_body();
                 let body = body
@@ -5890,6 +6899,73 @@ where
                     last_modified: response_last_modified,
                 })
             }
+            304 => {
+                let response_cache_control = match response
+                    .headers()
+                    .get(HeaderName::from_static("cache-control"))
+                {
+                    Some(response_cache_control) => {
+                        let response_cache_control = response_cache_control.clone();
+                        let response_cache_control =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_cache_control,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Cache-Control for response 304 - {}", e)));
+                                }
+                            };
+                        Some(response_cache_control.0)
+                    }
+                    None => None,
+                };
+
+                let response_e_tag = match response.headers().get(HeaderName::from_static("etag")) {
+                    Some(response_e_tag) => {
+                        let response_e_tag = response_e_tag.clone();
+                        let response_e_tag =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_e_tag,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!(
+                                        "Invalid response header ETag for response 304 - {}",
+                                        e
+                                    )));
+                                }
+                            };
+                        Some(response_e_tag.0)
+                    }
+                    None => None,
+                };
+
+                let response_last_modified = match response
+                    .headers()
+                    .get(HeaderName::from_static("last-modified"))
+                {
+                    Some(response_last_modified) => {
+                        let response_last_modified = response_last_modified.clone();
+                        let response_last_modified =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_last_modified,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Last-Modified for response 304 - {}", e)));
+                                }
+                            };
+                        Some(response_last_modified.0)
+                    }
+                    None => None,
+                };
+
+                Ok(GetSmDataResponse::NotModified {
+                    cache_control: response_cache_control,
+                    e_tag: response_e_tag,
+                    last_modified: response_last_modified,
+                })
+            }
             400 => {
                 let body = response.into_body();
                 let body = body
@@ -6184,6 +7260,73 @@ where
                     last_modified: response_last_modified,
                 })
             }
+            304 => {
+                let response_cache_control = match response
+                    .headers()
+                    .get(HeaderName::from_static("cache-control"))
+                {
+                    Some(response_cache_control) => {
+                        let response_cache_control = response_cache_control.clone();
+                        let response_cache_control =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_cache_control,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Cache-Control for response 304 - {}", e)));
+                                }
+                            };
+                        Some(response_cache_control.0)
+                    }
+                    None => None,
+                };
+
+                let response_e_tag = match response.headers().get(HeaderName::from_static("etag")) {
+                    Some(response_e_tag) => {
+                        let response_e_tag = response_e_tag.clone();
+                        let response_e_tag =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_e_tag,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!(
+                                        "Invalid response header ETag for response 304 - {}",
+                                        e
+                                    )));
+                                }
+                            };
+                        Some(response_e_tag.0)
+                    }
+                    None => None,
+                };
+
+                let response_last_modified = match response
+                    .headers()
+                    .get(HeaderName::from_static("last-modified"))
+                {
+                    Some(response_last_modified) => {
+                        let response_last_modified = response_last_modified.clone();
+                        let response_last_modified =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_last_modified,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Last-Modified for response 304 - {}", e)));
+                                }
+                            };
+                        Some(response_last_modified.0)
+                    }
+                    None => None,
+                };
+
+                Ok(GetNssaiResponse::NotModified {
+                    cache_control: response_cache_control,
+                    e_tag: response_e_tag,
+                    last_modified: response_last_modified,
+                })
+            }
             400 => {
                 let body = response.into_body();
                 let body = body
@@ -7605,6 +8748,73 @@ where
                     },
                 )
             }
+            304 => {
+                let response_cache_control = match response
+                    .headers()
+                    .get(HeaderName::from_static("cache-control"))
+                {
+                    Some(response_cache_control) => {
+                        let response_cache_control = response_cache_control.clone();
+                        let response_cache_control =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_cache_control,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Cache-Control for response 304 - {}", e)));
+                                }
+                            };
+                        Some(response_cache_control.0)
+                    }
+                    None => None,
+                };
+
+                let response_e_tag = match response.headers().get(HeaderName::from_static("etag")) {
+                    Some(response_e_tag) => {
+                        let response_e_tag = response_e_tag.clone();
+                        let response_e_tag =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_e_tag,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!(
+                                        "Invalid response header ETag for response 304 - {}",
+                                        e
+                                    )));
+                                }
+                            };
+                        Some(response_e_tag.0)
+                    }
+                    None => None,
+                };
+
+                let response_last_modified = match response
+                    .headers()
+                    .get(HeaderName::from_static("last-modified"))
+                {
+                    Some(response_last_modified) => {
+                        let response_last_modified = response_last_modified.clone();
+                        let response_last_modified =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_last_modified,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Last-Modified for response 304 - {}", e)));
+                                }
+                            };
+                        Some(response_last_modified.0)
+                    }
+                    None => None,
+                };
+
+                Ok(GetTraceConfigDataResponse::NotModified {
+                    cache_control: response_cache_control,
+                    e_tag: response_e_tag,
+                    last_modified: response_last_modified,
+                })
+            }
             400 => {
                 let body = response.into_body();
                 let body = body
@@ -8586,6 +9796,73 @@ where
                     last_modified: response_last_modified,
                 })
             }
+            304 => {
+                let response_cache_control = match response
+                    .headers()
+                    .get(HeaderName::from_static("cache-control"))
+                {
+                    Some(response_cache_control) => {
+                        let response_cache_control = response_cache_control.clone();
+                        let response_cache_control =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_cache_control,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Cache-Control for response 304 - {}", e)));
+                                }
+                            };
+                        Some(response_cache_control.0)
+                    }
+                    None => None,
+                };
+
+                let response_e_tag = match response.headers().get(HeaderName::from_static("etag")) {
+                    Some(response_e_tag) => {
+                        let response_e_tag = response_e_tag.clone();
+                        let response_e_tag =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_e_tag,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!(
+                                        "Invalid response header ETag for response 304 - {}",
+                                        e
+                                    )));
+                                }
+                            };
+                        Some(response_e_tag.0)
+                    }
+                    None => None,
+                };
+
+                let response_last_modified = match response
+                    .headers()
+                    .get(HeaderName::from_static("last-modified"))
+                {
+                    Some(response_last_modified) => {
+                        let response_last_modified = response_last_modified.clone();
+                        let response_last_modified =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_last_modified,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Last-Modified for response 304 - {}", e)));
+                                }
+                            };
+                        Some(response_last_modified.0)
+                    }
+                    None => None,
+                };
+
+                Ok(GetUcDataResponse::NotModified {
+                    cache_control: response_cache_control,
+                    e_tag: response_e_tag,
+                    last_modified: response_last_modified,
+                })
+            }
             400 => {
                 let body = response.into_body();
                 let body = body
@@ -8859,6 +10136,73 @@ where
                     last_modified: response_last_modified,
                 })
             }
+            304 => {
+                let response_cache_control = match response
+                    .headers()
+                    .get(HeaderName::from_static("cache-control"))
+                {
+                    Some(response_cache_control) => {
+                        let response_cache_control = response_cache_control.clone();
+                        let response_cache_control =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_cache_control,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Cache-Control for response 304 - {}", e)));
+                                }
+                            };
+                        Some(response_cache_control.0)
+                    }
+                    None => None,
+                };
+
+                let response_e_tag = match response.headers().get(HeaderName::from_static("etag")) {
+                    Some(response_e_tag) => {
+                        let response_e_tag = response_e_tag.clone();
+                        let response_e_tag =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_e_tag,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!(
+                                        "Invalid response header ETag for response 304 - {}",
+                                        e
+                                    )));
+                                }
+                            };
+                        Some(response_e_tag.0)
+                    }
+                    None => None,
+                };
+
+                let response_last_modified = match response
+                    .headers()
+                    .get(HeaderName::from_static("last-modified"))
+                {
+                    Some(response_last_modified) => {
+                        let response_last_modified = response_last_modified.clone();
+                        let response_last_modified =
+                            match TryInto::<header::IntoHeaderValue<String>>::try_into(
+                                response_last_modified,
+                            ) {
+                                Ok(value) => value,
+                                Err(e) => {
+                                    return Err(ApiError(format!("Invalid response header Last-Modified for response 304 - {}", e)));
+                                }
+                            };
+                        Some(response_last_modified.0)
+                    }
+                    None => None,
+                };
+
+                Ok(GetV2xDataResponse::NotModified {
+                    cache_control: response_cache_control,
+                    e_tag: response_e_tag,
+                    last_modified: response_last_modified,
+                })
+            }
             400 => {
                 let body = response.into_body();
                 let body = body
@@ -32,6 +32,12 @@ pub enum GetAmDataResponse {
         e_tag: Option<String>,
         last_modified: Option<String>,
     },
+    /// Not Modified
+    NotModified {
+        cache_control: Option<String>,
+        e_tag: Option<String>,
+        last_modified: Option<String>,
+    },
     /// Bad request
     BadRequest(models::ProblemDetails),
     /// Not Found
@@ -54,6 +60,12 @@ pub enum GetMbsDataResponse {
         e_tag: Option<String>,
         last_modified: Option<String>,
     },
+    /// Not Modified
+    NotModified {
+        cache_control: Option<String>,
+        e_tag: Option<String>,
+        last_modified: Option<String>,
+    },
     /// Bad request
     BadRequest(models::ProblemDetails),
     /// Not Found
@@ -76,6 +88,12 @@ pub enum GetEcrDataResponse {
         e_tag: Option<String>,
         last_modified: Option<String>,
     },
+    /// Not Modified
+    NotModified {
+        cache_control: Option<String>,
+        e_tag: Option<String>,
+        last_modified: Option<String>,
+    },
     /// Bad request
     BadRequest(models::ProblemDetails),
     /// Not Found
@@ -98,6 +116,12 @@ pub enum GetSupiOrGpsiResponse {
         e_tag: Option<String>,
         last_modified: Option<String>,
     },
+    /// Not Modified
+    NotModified {
+        cache_control: Option<String>,
+        e_tag: Option<String>,
+        last_modified: Option<String>,
+    },
     /// Bad request
     BadRequest(models::ProblemDetails),
     /// Forbidden
@@ -122,6 +146,12 @@ pub enum GetGroupIdentifiersResponse {
         e_tag: Option<String>,
         last_modified: Option<String>,
     },
+    /// Not Modified
+    NotModified {
+        cache_control: Option<String>,
+        e_tag: Option<String>,
+        last_modified: Option<String>,
+    },
     /// Bad request
     BadRequest(models::ProblemDetails),
     /// Forbidden
@@ -146,6 +176,12 @@ pub enum GetLcsBcaDataResponse {
         e_tag: Option<String>,
         last_modified: Option<String>,
     },
+    /// Not Modified
+    NotModified {
+        cache_control: Option<String>,
+        e_tag: Option<String>,
+        last_modified: Option<String>,
+    },
     /// Bad request
     BadRequest(models::ProblemDetails),
     /// Not Found
@@ -168,6 +204,12 @@ pub enum GetLcsMoDataResponse {
         e_tag: Option<String>,
         last_modified: Option<String>,
     },
+    /// Not Modified
+    NotModified {
+        cache_control: Option<String>,
+        e_tag: Option<String>,
+        last_modified: Option<String>,
+    },
     /// Bad request
     BadRequest(models::ProblemDetails),
     /// Not Found
@@ -190,6 +232,12 @@ pub enum GetLcsPrivacyDataResponse {
         e_tag: Option<String>,
         last_modified: Option<String>,
     },
+    /// Not Modified
+    NotModified {
+        cache_control: Option<String>,
+        e_tag: Option<String>,
+        last_modified: Option<String>,
+    },
     /// Bad request
     BadRequest(models::ProblemDetails),
     /// Not Found
@@ -244,6 +292,12 @@ pub enum GetProseDataResponse {
         e_tag: Option<String>,
         last_modified: Option<String>,
     },
+    /// Not Modified
+    NotModified {
+        cache_control: Option<String>,
+        e_tag: Option<String>,
+        last_modified: Option<String>,
+    },
     /// Bad request
     BadRequest(models::ProblemDetails),
     /// Not Found
@@ -326,6 +380,12 @@ pub enum GetDataSetsResponse {
         e_tag: Option<String>,
         last_modified: Option<String>,
     },
+    /// Not Modified
+    NotModified {
+        cache_control: Option<String>,
+        e_tag: Option<String>,
+        last_modified: Option<String>,
+    },
     /// Bad request
     BadRequest(models::ProblemDetails),
     /// Not Found
@@ -348,6 +408,12 @@ pub enum GetSharedDataResponse {
         e_tag: Option<String>,
         last_modified: Option<String>,
     },
+    /// Not Modified
+    NotModified {
+        cache_control: Option<String>,
+        e_tag: Option<String>,
+        last_modified: Option<String>,
+    },
     /// Bad request
     BadRequest(models::ProblemDetails),
     /// Not Found
@@ -370,6 +436,12 @@ pub enum GetIndividualSharedDataResponse {
         e_tag: Option<String>,
         last_modified: Option<String>,
     },
+    /// Not Modified
+    NotModified {
+        cache_control: Option<String>,
+        e_tag: Option<String>,
+        last_modified: Option<String>,
+    },
     /// Bad request
     BadRequest(models::ProblemDetails),
     /// Not Found
@@ -392,6 +464,12 @@ pub enum GetSmfSelDataResponse {
         e_tag: Option<String>,
         last_modified: Option<String>,
     },
+    /// Not Modified
+    NotModified {
+        cache_control: Option<String>,
+        e_tag: Option<String>,
+        last_modified: Option<String>,
+    },
     /// Bad request
     BadRequest(models::ProblemDetails),
     /// Not Found
@@ -414,6 +492,12 @@ pub enum GetSmsMngtDataResponse {
         e_tag: Option<String>,
         last_modified: Option<String>,
     },
+    /// Not Modified
+    NotModified {
+        cache_control: Option<String>,
+        e_tag: Option<String>,
+        last_modified: Option<String>,
+    },
     /// Bad request
     BadRequest(models::ProblemDetails),
     /// Not Found
@@ -436,6 +520,12 @@ pub enum GetSmsDataResponse {
         e_tag: Option<String>,
         last_modified: Option<String>,
     },
+    /// Not Modified
+    NotModified {
+        cache_control: Option<String>,
+        e_tag: Option<String>,
+        last_modified: Option<String>,
+    },
     /// Bad request
     BadRequest(models::ProblemDetails),
     /// Not Found
@@ -458,6 +548,12 @@ pub enum GetSmDataResponse {
         e_tag: Option<String>,
         last_modified: Option<String>,
     },
+    /// Not Modified
+    NotModified {
+        cache_control: Option<String>,
+        e_tag: Option<String>,
+        last_modified: Option<String>,
+    },
     /// Bad request
     BadRequest(models::ProblemDetails),
     /// Not Found
@@ -480,6 +576,12 @@ pub enum GetNssaiResponse {
         e_tag: Option<String>,
         last_modified: Option<String>,
     },
+    /// Not Modified
+    NotModified {
+        cache_control: Option<String>,
+        e_tag: Option<String>,
+        last_modified: Option<String>,
+    },
     /// Bad request
     BadRequest(models::ProblemDetails),
     /// Not Found
@@ -612,6 +714,12 @@ pub enum GetTraceConfigDataResponse {
         e_tag: Option<String>,
         last_modified: Option<String>,
     },
+    /// Not Modified
+    NotModified {
+        cache_control: Option<String>,
+        e_tag: Option<String>,
+        last_modified: Option<String>,
+    },
     /// Bad request
     BadRequest(models::ProblemDetails),
     /// Not Found
@@ -702,6 +810,12 @@ pub enum GetUcDataResponse {
         e_tag: Option<String>,
         last_modified: Option<String>,
     },
+    /// Not Modified
+    NotModified {
+        cache_control: Option<String>,
+        e_tag: Option<String>,
+        last_modified: Option<String>,
+    },
     /// Bad request
     BadRequest(models::ProblemDetails),
     /// Not Found
@@ -724,6 +838,12 @@ pub enum GetV2xDataResponse {
         e_tag: Option<String>,
         last_modified: Option<String>,
     },
+    /// Not Modified
+    NotModified {
+        cache_control: Option<String>,
+        e_tag: Option<String>,
+        last_modified: Option<String>,
+    },
     /// Bad request
     BadRequest(models::ProblemDetails),
     /// Not Found
@@ -790,6 +910,7 @@ pub trait Api<C: Send + Sync> {
         af_service_id: Option<String>,
         mtc_provider_info: Option<String>,
         requested_gpsi_type: Option<models::GpsiType>,
+        requested_supi: Option<bool>,
         if_none_match: Option<String>,
         if_modified_since: Option<String>,
         context: &C,
@@ -1147,6 +1268,7 @@ pub trait ApiNoContext<C: Send + Sync> {
         af_service_id: Option<String>,
         mtc_provider_info: Option<String>,
         requested_gpsi_type: Option<models::GpsiType>,
+        requested_supi: Option<bool>,
         if_none_match: Option<String>,
         if_modified_since: Option<String>,
     ) -> Result<GetSupiOrGpsiResponse, ApiError>;
@@ -1522,6 +1644,7 @@ impl<T: Api<C> + Send + Sync, C: Clone + Send + Sync> ApiNoContext<C> for Contex
         af_service_id: Option<String>,
         mtc_provider_info: Option<String>,
         requested_gpsi_type: Option<models::GpsiType>,
+        requested_supi: Option<bool>,
         if_none_match: Option<String>,
         if_modified_since: Option<String>,
     ) -> Result<GetSupiOrGpsiResponse, ApiError> {
@@ -1535,6 +1658,7 @@ impl<T: Api<C> + Send + Sync, C: Clone + Send + Sync> ApiNoContext<C> for Contex
                 af_service_id,
                 mtc_provider_info,
                 requested_gpsi_type,
+                requested_supi,
                 if_none_match,
                 if_modified_since,
                 &context,
@@ -2182,6 +2306,7 @@ pub trait CallbackApi<C: Send + Sync> {
 
     async fn datachange_notification_request_body_callback_reference_post(
         &self,
+        callback_request_body_callback_reference: String,
         modification_notification: models::ModificationNotification,
         context: &C,
     ) -> Result<DatachangeNotificationRequestBodyCallbackReferencePostResponse, ApiError>;
@@ -2199,6 +2324,7 @@ pub trait CallbackApiNoContext<C: Send + Sync> {
 
     async fn datachange_notification_request_body_callback_reference_post(
         &self,
+        callback_request_body_callback_reference: String,
         modification_notification: models::ModificationNotification,
     ) -> Result<DatachangeNotificationRequestBodyCallbackReferencePostResponse, ApiError>;
 
@@ -2236,11 +2362,13 @@ impl<T: CallbackApi<C> + Send + Sync, C: Clone + Send + Sync> CallbackApiNoConte
 
     async fn datachange_notification_request_body_callback_reference_post(
         &self,
+        callback_request_body_callback_reference: String,
         modification_notification: models::ModificationNotification,
     ) -> Result<DatachangeNotificationRequestBodyCallbackReferencePostResponse, ApiError> {
         let context = self.context().clone();
         self.api()
             .datachange_notification_request_body_callback_reference_post(
+                callback_request_body_callback_reference,
                 modification_notification,
                 &context,
             )
@@ -2278,5 +2406,15 @@ pub mod context;
 
 pub mod models;
 
+pub mod acknowledgements;
+
+pub mod shared_data;
+
+pub mod subscribers;
+
+pub mod subscriptions;
+
+pub mod suci;
+
 #[cfg(any(feature = "client", feature = "server"))]
 pub(crate) mod header;
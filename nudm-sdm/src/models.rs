@@ -825,14 +825,15 @@ impl std::convert::TryFrom<hyper::header::HeaderValue>
     }
 }
 
+/// A DNN or the wildcard DNN
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 #[cfg_attr(feature = "conversion", derive(frunk::LabelledGeneric))]
-pub struct AccessAndMobilitySubscriptionDataSubscribedDnnListInner {}
+pub struct AccessAndMobilitySubscriptionDataSubscribedDnnListInner(String);
 
 impl AccessAndMobilitySubscriptionDataSubscribedDnnListInner {
     #[allow(clippy::new_without_default)]
-    pub fn new() -> AccessAndMobilitySubscriptionDataSubscribedDnnListInner {
-        AccessAndMobilitySubscriptionDataSubscribedDnnListInner {}
+    pub fn new(s: String) -> AccessAndMobilitySubscriptionDataSubscribedDnnListInner {
+        AccessAndMobilitySubscriptionDataSubscribedDnnListInner(s)
     }
 }
 
@@ -841,9 +842,7 @@ impl AccessAndMobilitySubscriptionDataSubscribedDnnListInner {
 /// Should be implemented in a serde serializer
 impl std::string::ToString for AccessAndMobilitySubscriptionDataSubscribedDnnListInner {
     fn to_string(&self) -> String {
-        let params: Vec<Option<String>> = vec![];
-
-        params.into_iter().flatten().collect::<Vec<_>>().join(",")
+        self.0.clone()
     }
 }
 
@@ -854,36 +853,7 @@ impl std::str::FromStr for AccessAndMobilitySubscriptionDataSubscribedDnnListInn
     type Err = String;
 
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        /// An intermediate representation of the struct to use for parsing.
-        #[derive(Default)]
-        #[allow(dead_code)]
-        struct IntermediateRep {}
-
-        let mut intermediate_rep = IntermediateRep::default();
-
-        // Parse into intermediate representation
-        let mut string_iter = s.split(',');
-        let mut key_result = string_iter.next();
-
-        while key_result.is_some() {
-            let val = match string_iter.next() {
-                Some(x) => x,
-                None => return std::result::Result::Err("Missing value while parsing AccessAndMobilitySubscriptionDataSubscribedDnnListInner".to_string())
-            };
-
-            if let Some(key) = key_result {
-                #[allow(clippy::match_single_binding)]
-                match key {
-                    _ => return std::result::Result::Err("Unexpected key while parsing AccessAndMobilitySubscriptionDataSubscribedDnnListInner".to_string())
-                }
-            }
-
-            // Get the next key
-            key_result = string_iter.next();
-        }
-
-        // Use the intermediate representation to return the struct
-        std::result::Result::Ok(AccessAndMobilitySubscriptionDataSubscribedDnnListInner {})
+        std::result::Result::Ok(AccessAndMobilitySubscriptionDataSubscribedDnnListInner(s.to_string()))
     }
 }
 
@@ -5251,12 +5221,12 @@ impl std::convert::TryFrom<hyper::header::HeaderValue> for header::IntoHeaderVal
 /// Indicates the type of change to be performed.
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 #[cfg_attr(feature = "conversion", derive(frunk::LabelledGeneric))]
-pub struct ChangeType {}
+pub struct ChangeType(String);
 
 impl ChangeType {
     #[allow(clippy::new_without_default)]
-    pub fn new() -> ChangeType {
-        ChangeType {}
+    pub fn new(s: String) -> ChangeType {
+        ChangeType(s)
     }
 }
 
@@ -5265,9 +5235,7 @@ impl ChangeType {
 /// Should be implemented in a serde serializer
 impl std::string::ToString for ChangeType {
     fn to_string(&self) -> String {
-        let params: Vec<Option<String>> = vec![];
-
-        params.into_iter().flatten().collect::<Vec<_>>().join(",")
+        self.0.clone()
     }
 }
 
@@ -5278,44 +5246,7 @@ impl std::str::FromStr for ChangeType {
     type Err = String;
 
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        /// An intermediate representation of the struct to use for parsing.
-        #[derive(Default)]
-        #[allow(dead_code)]
-        struct IntermediateRep {}
-
-        let mut intermediate_rep = IntermediateRep::default();
-
-        // Parse into intermediate representation
-        let mut string_iter = s.split(',');
-        let mut key_result = string_iter.next();
-
-        while key_result.is_some() {
-            let val = match string_iter.next() {
-                Some(x) => x,
-                None => {
-                    return std::result::Result::Err(
-                        "Missing value while parsing ChangeType".to_string(),
-                    )
-                }
-            };
-
-            if let Some(key) = key_result {
-                #[allow(clippy::match_single_binding)]
-                match key {
-                    _ => {
-                        return std::result::Result::Err(
-                            "Unexpected key while parsing ChangeType".to_string(),
-                        )
-                    }
-                }
-            }
-
-            // Get the next key
-            key_result = string_iter.next();
-        }
-
-        // Use the intermediate representation to return the struct
-        std::result::Result::Ok(ChangeType {})
+        std::result::Result::Ok(ChangeType(s.to_string()))
     }
 }
 
@@ -6987,12 +6918,12 @@ impl std::ops::DerefMut for CounterUpu {
 
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 #[cfg_attr(feature = "conversion", derive(frunk::LabelledGeneric))]
-pub struct DataSetName {}
+pub struct DataSetName(String);
 
 impl DataSetName {
     #[allow(clippy::new_without_default)]
-    pub fn new() -> DataSetName {
-        DataSetName {}
+    pub fn new(s: String) -> DataSetName {
+        DataSetName(s)
     }
 }
 
@@ -7001,9 +6932,7 @@ impl DataSetName {
 /// Should be implemented in a serde serializer
 impl std::string::ToString for DataSetName {
     fn to_string(&self) -> String {
-        let params: Vec<Option<String>> = vec![];
-
-        params.into_iter().flatten().collect::<Vec<_>>().join(",")
+        self.0.clone()
     }
 }
 
@@ -7014,44 +6943,7 @@ impl std::str::FromStr for DataSetName {
     type Err = String;
 
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        /// An intermediate representation of the struct to use for parsing.
-        #[derive(Default)]
-        #[allow(dead_code)]
-        struct IntermediateRep {}
-
-        let mut intermediate_rep = IntermediateRep::default();
-
-        // Parse into intermediate representation
-        let mut string_iter = s.split(',');
-        let mut key_result = string_iter.next();
-
-        while key_result.is_some() {
-            let val = match string_iter.next() {
-                Some(x) => x,
-                None => {
-                    return std::result::Result::Err(
-                        "Missing value while parsing DataSetName".to_string(),
-                    )
-                }
-            };
-
-            if let Some(key) = key_result {
-                #[allow(clippy::match_single_binding)]
-                match key {
-                    _ => {
-                        return std::result::Result::Err(
-                            "Unexpected key while parsing DataSetName".to_string(),
-                        )
-                    }
-                }
-            }
-
-            // Get the next key
-            key_result = string_iter.next();
-        }
-
-        // Use the intermediate representation to return the struct
-        std::result::Result::Ok(DataSetName {})
+        std::result::Result::Ok(DataSetName(s.to_string()))
     }
 }
 
@@ -12700,12 +12592,12 @@ impl std::ops::DerefMut for Gpsi {
 /// Type of GPSI (MSISDN or External-ID)
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 #[cfg_attr(feature = "conversion", derive(frunk::LabelledGeneric))]
-pub struct GpsiType {}
+pub struct GpsiType(String);
 
 impl GpsiType {
     #[allow(clippy::new_without_default)]
-    pub fn new() -> GpsiType {
-        GpsiType {}
+    pub fn new(s: String) -> GpsiType {
+        GpsiType(s)
     }
 }
 
@@ -12714,9 +12606,7 @@ impl GpsiType {
 /// Should be implemented in a serde serializer
 impl std::string::ToString for GpsiType {
     fn to_string(&self) -> String {
-        let params: Vec<Option<String>> = vec![];
-
-        params.into_iter().flatten().collect::<Vec<_>>().join(",")
+        self.0.clone()
     }
 }
 
@@ -12727,44 +12617,7 @@ impl std::str::FromStr for GpsiType {
     type Err = String;
 
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        /// An intermediate representation of the struct to use for parsing.
-        #[derive(Default)]
-        #[allow(dead_code)]
-        struct IntermediateRep {}
-
-        let mut intermediate_rep = IntermediateRep::default();
-
-        // Parse into intermediate representation
-        let mut string_iter = s.split(',');
-        let mut key_result = string_iter.next();
-
-        while key_result.is_some() {
-            let val = match string_iter.next() {
-                Some(x) => x,
-                None => {
-                    return std::result::Result::Err(
-                        "Missing value while parsing GpsiType".to_string(),
-                    )
-                }
-            };
-
-            if let Some(key) = key_result {
-                #[allow(clippy::match_single_binding)]
-                match key {
-                    _ => {
-                        return std::result::Result::Err(
-                            "Unexpected key while parsing GpsiType".to_string(),
-                        )
-                    }
-                }
-            }
-
-            // Get the next key
-            key_result = string_iter.next();
-        }
-
-        // Use the intermediate representation to return the struct
-        std::result::Result::Ok(GpsiType {})
+        std::result::Result::Ok(GpsiType(s.to_string()))
     }
 }
 
@@ -20384,6 +20237,8 @@ pub struct Modify200Response {
 
     /// The execution report contains an array of report items. Each report item indicates one  failed modification.
     #[serde(rename = "report")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
     pub report: Vec<models::ReportItem>,
 
     /// A string used to indicate the features supported by an API that is used as defined in clause  6.6 in 3GPP TS 29.500. The string shall contain a bitmask indicating supported features in  hexadecimal representation Each character in the string shall take a value of \"0\" to \"9\",  \"a\" to \"f\" or \"A\" to \"F\" and shall represent the support of 4 features as described in  table 5.2.2-3. The most significant character representing the highest-numbered features shall  appear first in the string, and the character representing features 1 to 4 shall appear last  in the string. The list of features and their numbering (starting with 1) are defined  separately for each API. If the string contains a lower number of characters than there are  defined features for an API, all features that would be represented by characters that are not  present in the string are not supported.
@@ -33637,18 +33492,15 @@ pub struct SharedDataId(String);
 
 impl SharedDataId {
     pub fn from_vec_str(s: &str) -> Result<Vec<SharedDataId>, std::string::ParseError> {
-        let chunks = s.split(",");
+        let chunks = s.split(",").filter(|x| !x.is_empty());
         Ok(chunks.map(|x| SharedDataId::from_str(x).unwrap()).collect())
     }
 
     pub fn to_vec_str(v: &Vec<SharedDataId>) -> String {
-        let mut res = String::new();
-
-        for sdi in v {
-            res.push_str(&format!("{},", sdi.0));
-        }
-
-        res
+        v.iter()
+            .map(|sdi| sdi.0.as_str())
+            .collect::<Vec<_>>()
+            .join(",")
     }
 }
 
@@ -39542,12 +39394,12 @@ impl std::str::FromStr for TrafficProfileAnyOf {
 /// Indicates the purpose of the user consent.
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 #[cfg_attr(feature = "conversion", derive(frunk::LabelledGeneric))]
-pub struct UcPurpose {}
+pub struct UcPurpose(String);
 
 impl UcPurpose {
     #[allow(clippy::new_without_default)]
-    pub fn new() -> UcPurpose {
-        UcPurpose {}
+    pub fn new(s: String) -> UcPurpose {
+        UcPurpose(s)
     }
 }
 
@@ -39556,9 +39408,7 @@ impl UcPurpose {
 /// Should be implemented in a serde serializer
 impl std::string::ToString for UcPurpose {
     fn to_string(&self) -> String {
-        let params: Vec<Option<String>> = vec![];
-
-        params.into_iter().flatten().collect::<Vec<_>>().join(",")
+        self.0.clone()
     }
 }
 
@@ -39569,44 +39419,7 @@ impl std::str::FromStr for UcPurpose {
     type Err = String;
 
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
-        /// An intermediate representation of the struct to use for parsing.
-        #[derive(Default)]
-        #[allow(dead_code)]
-        struct IntermediateRep {}
-
-        let mut intermediate_rep = IntermediateRep::default();
-
-        // Parse into intermediate representation
-        let mut string_iter = s.split(',');
-        let mut key_result = string_iter.next();
-
-        while key_result.is_some() {
-            let val = match string_iter.next() {
-                Some(x) => x,
-                None => {
-                    return std::result::Result::Err(
-                        "Missing value while parsing UcPurpose".to_string(),
-                    )
-                }
-            };
-
-            if let Some(key) = key_result {
-                #[allow(clippy::match_single_binding)]
-                match key {
-                    _ => {
-                        return std::result::Result::Err(
-                            "Unexpected key while parsing UcPurpose".to_string(),
-                        )
-                    }
-                }
-            }
-
-            // Get the next key
-            key_result = string_iter.next();
-        }
-
-        // Use the intermediate representation to return the struct
-        std::result::Result::Ok(UcPurpose {})
+        std::result::Result::Ok(UcPurpose(s.to_string()))
     }
 }
 
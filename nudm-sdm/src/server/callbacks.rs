@@ -239,28 +239,13 @@ where
 
     async fn datachange_notification_request_body_callback_reference_post(
         &self,
+        callback_request_body_callback_reference: String,
         param_modification_notification: models::ModificationNotification,
         context: &C,
     ) -> Result<DatachangeNotificationRequestBodyCallbackReferencePostResponse, ApiError> {
         let mut client_service = self.client_service.clone();
-        // TODO check where to recover this.
-        let mut uri = format!(
-            //"{request.body#/callbackReference}"
-            "todo{}",
-            "a"
-        );
-
-        // Query parameters
-        let query_string = {
-            let mut query_string = form_urlencoded::Serializer::new("".to_owned());
-            query_string.finish()
-        };
-        if !query_string.is_empty() {
-            uri += "?";
-            uri += &query_string;
-        }
 
-        let uri = match Uri::from_str(&uri) {
+        let uri = match Uri::from_str(&callback_request_body_callback_reference) {
             Ok(uri) => uri,
             Err(err) => return Err(ApiError(format!("Unable to build URI: {}", err))),
         };
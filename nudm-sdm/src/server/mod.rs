@@ -659,6 +659,61 @@ where
                                     .expect("impossible to fail to serialize");
                                 *response.body_mut() = Body::from(body);
                             }
+                            GetAmDataResponse::NotModified {
+                                cache_control,
+                                e_tag,
+                                last_modified,
+                            } => {
+                                if let Some(cache_control) = cache_control {
+                                    let cache_control = match header::IntoHeaderValue(cache_control).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling cache_control header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("cache-control"),
+                                        cache_control,
+                                    );
+                                }
+                                if let Some(e_tag) = e_tag {
+                                    let e_tag = match header::IntoHeaderValue(e_tag).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling e_tag header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response
+                                        .headers_mut()
+                                        .insert(HeaderName::from_static("etag"), e_tag);
+                                }
+                                if let Some(last_modified) = last_modified {
+                                    let last_modified = match header::IntoHeaderValue(last_modified).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling last_modified header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("last-modified"),
+                                        last_modified,
+                                    );
+                                }
+                                *response.status_mut() = StatusCode::from_u16(304)
+                                    .expect("Unable to turn 304 into a StatusCode");
+                            }
                             GetAmDataResponse::BadRequest(body) => {
                                 *response.status_mut() = StatusCode::from_u16(400)
                                     .expect("Unable to turn 400 into a StatusCode");
@@ -926,6 +981,61 @@ where
                                     .expect("impossible to fail to serialize");
                                 *response.body_mut() = Body::from(body);
                             }
+                            GetMbsDataResponse::NotModified {
+                                cache_control,
+                                e_tag,
+                                last_modified,
+                            } => {
+                                if let Some(cache_control) = cache_control {
+                                    let cache_control = match header::IntoHeaderValue(cache_control).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling cache_control header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("cache-control"),
+                                        cache_control,
+                                    );
+                                }
+                                if let Some(e_tag) = e_tag {
+                                    let e_tag = match header::IntoHeaderValue(e_tag).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling e_tag header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response
+                                        .headers_mut()
+                                        .insert(HeaderName::from_static("etag"), e_tag);
+                                }
+                                if let Some(last_modified) = last_modified {
+                                    let last_modified = match header::IntoHeaderValue(last_modified).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling last_modified header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("last-modified"),
+                                        last_modified,
+                                    );
+                                }
+                                *response.status_mut() = StatusCode::from_u16(304)
+                                    .expect("Unable to turn 304 into a StatusCode");
+                            }
                             GetMbsDataResponse::BadRequest(body) => {
                                 *response.status_mut() = StatusCode::from_u16(400)
                                     .expect("Unable to turn 400 into a StatusCode");
@@ -1193,6 +1303,61 @@ where
                                     .expect("impossible to fail to serialize");
                                 *response.body_mut() = Body::from(body);
                             }
+                            GetEcrDataResponse::NotModified {
+                                cache_control,
+                                e_tag,
+                                last_modified,
+                            } => {
+                                if let Some(cache_control) = cache_control {
+                                    let cache_control = match header::IntoHeaderValue(cache_control).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling cache_control header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("cache-control"),
+                                        cache_control,
+                                    );
+                                }
+                                if let Some(e_tag) = e_tag {
+                                    let e_tag = match header::IntoHeaderValue(e_tag).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling e_tag header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response
+                                        .headers_mut()
+                                        .insert(HeaderName::from_static("etag"), e_tag);
+                                }
+                                if let Some(last_modified) = last_modified {
+                                    let last_modified = match header::IntoHeaderValue(last_modified).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling last_modified header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("last-modified"),
+                                        last_modified,
+                                    );
+                                }
+                                *response.status_mut() = StatusCode::from_u16(304)
+                                    .expect("Unable to turn 304 into a StatusCode");
+                            }
                             GetEcrDataResponse::BadRequest(body) => {
                                 *response.status_mut() = StatusCode::from_u16(400)
                                     .expect("Unable to turn 400 into a StatusCode");
@@ -1468,6 +1633,25 @@ where
                         }
                         None => None,
                     };
+                    let param_requested_supi = query_params
+                        .iter()
+                        .filter(|e| e.0 == "requested-supi")
+                        .map(|e| e.1.to_owned())
+                        .next();
+                    let param_requested_supi = match param_requested_supi {
+                        Some(param_requested_supi) => {
+                            let param_requested_supi =
+                                <bool as std::str::FromStr>::from_str(&param_requested_supi);
+                            match param_requested_supi {
+                            Ok(param_requested_supi) => Some(param_requested_supi),
+                            Err(e) => return Ok(Response::builder()
+                                .status(StatusCode::BAD_REQUEST)
+                                .body(Body::from(format!("Couldn't parse query parameter requested-supi - doesn't match schema: {}", e)))
+                                .expect("Unable to create Bad Request response for invalid query parameter requested-supi")),
+                        }
+                        }
+                        None => None,
+                    };
 
                     let result = api_impl
                         .get_supi_or_gpsi(
@@ -1478,6 +1662,7 @@ where
                             param_af_service_id,
                             param_mtc_provider_info,
                             param_requested_gpsi_type,
+                            param_requested_supi,
                             param_if_none_match,
                             param_if_modified_since,
                             &context,
@@ -1561,6 +1746,61 @@ where
                                     .expect("impossible to fail to serialize");
                                 *response.body_mut() = Body::from(body);
                             }
+                            GetSupiOrGpsiResponse::NotModified {
+                                cache_control,
+                                e_tag,
+                                last_modified,
+                            } => {
+                                if let Some(cache_control) = cache_control {
+                                    let cache_control = match header::IntoHeaderValue(cache_control).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling cache_control header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("cache-control"),
+                                        cache_control,
+                                    );
+                                }
+                                if let Some(e_tag) = e_tag {
+                                    let e_tag = match header::IntoHeaderValue(e_tag).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling e_tag header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response
+                                        .headers_mut()
+                                        .insert(HeaderName::from_static("etag"), e_tag);
+                                }
+                                if let Some(last_modified) = last_modified {
+                                    let last_modified = match header::IntoHeaderValue(last_modified).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling last_modified header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("last-modified"),
+                                        last_modified,
+                                    );
+                                }
+                                *response.status_mut() = StatusCode::from_u16(304)
+                                    .expect("Unable to turn 304 into a StatusCode");
+                            }
                             GetSupiOrGpsiResponse::BadRequest(body) => {
                                 *response.status_mut() = StatusCode::from_u16(400)
                                     .expect("Unable to turn 400 into a StatusCode");
@@ -1894,6 +2134,61 @@ where
                                     .expect("impossible to fail to serialize");
                                 *response.body_mut() = Body::from(body);
                             }
+                            GetGroupIdentifiersResponse::NotModified {
+                                cache_control,
+                                e_tag,
+                                last_modified,
+                            } => {
+                                if let Some(cache_control) = cache_control {
+                                    let cache_control = match header::IntoHeaderValue(cache_control).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling cache_control header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("cache-control"),
+                                        cache_control,
+                                    );
+                                }
+                                if let Some(e_tag) = e_tag {
+                                    let e_tag = match header::IntoHeaderValue(e_tag).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling e_tag header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response
+                                        .headers_mut()
+                                        .insert(HeaderName::from_static("etag"), e_tag);
+                                }
+                                if let Some(last_modified) = last_modified {
+                                    let last_modified = match header::IntoHeaderValue(last_modified).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling last_modified header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("last-modified"),
+                                        last_modified,
+                                    );
+                                }
+                                *response.status_mut() = StatusCode::from_u16(304)
+                                    .expect("Unable to turn 304 into a StatusCode");
+                            }
                             GetGroupIdentifiersResponse::BadRequest(body) => {
                                 *response.status_mut() = StatusCode::from_u16(400)
                                     .expect("Unable to turn 400 into a StatusCode");
@@ -2192,6 +2487,61 @@ where
                                     .expect("impossible to fail to serialize");
                                 *response.body_mut() = Body::from(body);
                             }
+                            GetLcsBcaDataResponse::NotModified {
+                                cache_control,
+                                e_tag,
+                                last_modified,
+                            } => {
+                                if let Some(cache_control) = cache_control {
+                                    let cache_control = match header::IntoHeaderValue(cache_control).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling cache_control header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("cache-control"),
+                                        cache_control,
+                                    );
+                                }
+                                if let Some(e_tag) = e_tag {
+                                    let e_tag = match header::IntoHeaderValue(e_tag).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling e_tag header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response
+                                        .headers_mut()
+                                        .insert(HeaderName::from_static("etag"), e_tag);
+                                }
+                                if let Some(last_modified) = last_modified {
+                                    let last_modified = match header::IntoHeaderValue(last_modified).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling last_modified header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("last-modified"),
+                                        last_modified,
+                                    );
+                                }
+                                *response.status_mut() = StatusCode::from_u16(304)
+                                    .expect("Unable to turn 304 into a StatusCode");
+                            }
                             GetLcsBcaDataResponse::BadRequest(body) => {
                                 *response.status_mut() = StatusCode::from_u16(400)
                                     .expect("Unable to turn 400 into a StatusCode");
@@ -2394,10 +2744,72 @@ where
                         .expect("Unable to create X-Span-ID header value"),
                     );
 
-                    match result {
-                        Ok(rsp) => match rsp {
-                            GetLcsMoDataResponse::ExpectedResponseToAValidRequest {
-                                body,
+                    match result {
+                        Ok(rsp) => match rsp {
+                            GetLcsMoDataResponse::ExpectedResponseToAValidRequest {
+                                body,
+                                cache_control,
+                                e_tag,
+                                last_modified,
+                            } => {
+                                if let Some(cache_control) = cache_control {
+                                    let cache_control = match header::IntoHeaderValue(cache_control).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling cache_control header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("cache-control"),
+                                        cache_control,
+                                    );
+                                }
+                                if let Some(e_tag) = e_tag {
+                                    let e_tag = match header::IntoHeaderValue(e_tag).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling e_tag header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response
+                                        .headers_mut()
+                                        .insert(HeaderName::from_static("etag"), e_tag);
+                                }
+                                if let Some(last_modified) = last_modified {
+                                    let last_modified = match header::IntoHeaderValue(last_modified).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling last_modified header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("last-modified"),
+                                        last_modified,
+                                    );
+                                }
+                                *response.status_mut() = StatusCode::from_u16(200)
+                                    .expect("Unable to turn 200 into a StatusCode");
+                                response.headers_mut().insert(
+                                                        CONTENT_TYPE,
+                                                        HeaderValue::from_str("application/json")
+                                                            .expect("Unable to create Content-Type header for GET_LCS_MO_DATA_EXPECTED_RESPONSE_TO_A_VALID_REQUEST"));
+                                let body = serde_json::to_string(&body)
+                                    .expect("impossible to fail to serialize");
+                                *response.body_mut() = Body::from(body);
+                            }
+                            GetLcsMoDataResponse::NotModified {
                                 cache_control,
                                 e_tag,
                                 last_modified,
@@ -2449,15 +2861,8 @@ where
                                         last_modified,
                                     );
                                 }
-                                *response.status_mut() = StatusCode::from_u16(200)
-                                    .expect("Unable to turn 200 into a StatusCode");
-                                response.headers_mut().insert(
-                                                        CONTENT_TYPE,
-                                                        HeaderValue::from_str("application/json")
-                                                            .expect("Unable to create Content-Type header for GET_LCS_MO_DATA_EXPECTED_RESPONSE_TO_A_VALID_REQUEST"));
-                                let body = serde_json::to_string(&body)
-                                    .expect("impossible to fail to serialize");
-                                *response.body_mut() = Body::from(body);
+                                *response.status_mut() = StatusCode::from_u16(304)
+                                    .expect("Unable to turn 304 into a StatusCode");
                             }
                             GetLcsMoDataResponse::BadRequest(body) => {
                                 *response.status_mut() = StatusCode::from_u16(400)
@@ -2726,6 +3131,61 @@ where
                                     .expect("impossible to fail to serialize");
                                 *response.body_mut() = Body::from(body);
                             }
+                            GetLcsPrivacyDataResponse::NotModified {
+                                cache_control,
+                                e_tag,
+                                last_modified,
+                            } => {
+                                if let Some(cache_control) = cache_control {
+                                    let cache_control = match header::IntoHeaderValue(cache_control).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling cache_control header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("cache-control"),
+                                        cache_control,
+                                    );
+                                }
+                                if let Some(e_tag) = e_tag {
+                                    let e_tag = match header::IntoHeaderValue(e_tag).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling e_tag header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response
+                                        .headers_mut()
+                                        .insert(HeaderName::from_static("etag"), e_tag);
+                                }
+                                if let Some(last_modified) = last_modified {
+                                    let last_modified = match header::IntoHeaderValue(last_modified).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling last_modified header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("last-modified"),
+                                        last_modified,
+                                    );
+                                }
+                                *response.status_mut() = StatusCode::from_u16(304)
+                                    .expect("Unable to turn 304 into a StatusCode");
+                            }
                             GetLcsPrivacyDataResponse::BadRequest(body) => {
                                 *response.status_mut() = StatusCode::from_u16(400)
                                     .expect("Unable to turn 400 into a StatusCode");
@@ -2850,7 +3310,9 @@ where
                     let param_gpsi_list = query_params
                         .iter()
                         .filter(|e| e.0 == "gpsi-list")
-                        .map(|e| e.1.to_owned())
+                        .flat_map(|e| {
+                            e.1.split(',').map(ToOwned::to_owned).collect::<Vec<_>>()
+                        })
                         .filter_map(|param_gpsi_list| param_gpsi_list.parse().ok())
                         .collect::<Vec<_>>();
 
@@ -3254,6 +3716,61 @@ where
                                     .expect("impossible to fail to serialize");
                                 *response.body_mut() = Body::from(body);
                             }
+                            GetProseDataResponse::NotModified {
+                                cache_control,
+                                e_tag,
+                                last_modified,
+                            } => {
+                                if let Some(cache_control) = cache_control {
+                                    let cache_control = match header::IntoHeaderValue(cache_control).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling cache_control header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("cache-control"),
+                                        cache_control,
+                                    );
+                                }
+                                if let Some(e_tag) = e_tag {
+                                    let e_tag = match header::IntoHeaderValue(e_tag).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling e_tag header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response
+                                        .headers_mut()
+                                        .insert(HeaderName::from_static("etag"), e_tag);
+                                }
+                                if let Some(last_modified) = last_modified {
+                                    let last_modified = match header::IntoHeaderValue(last_modified).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling last_modified header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("last-modified"),
+                                        last_modified,
+                                    );
+                                }
+                                *response.status_mut() = StatusCode::from_u16(304)
+                                    .expect("Unable to turn 304 into a StatusCode");
+                            }
                             GetProseDataResponse::BadRequest(body) => {
                                 *response.status_mut() = StatusCode::from_u16(400)
                                     .expect("Unable to turn 400 into a StatusCode");
@@ -4060,7 +4577,9 @@ where
                     let param_dataset_names = query_params
                         .iter()
                         .filter(|e| e.0 == "dataset-names")
-                        .map(|e| e.1.to_owned())
+                        .flat_map(|e| {
+                            e.1.split(',').map(ToOwned::to_owned).collect::<Vec<_>>()
+                        })
                         .filter_map(|param_dataset_names| param_dataset_names.parse().ok())
                         .collect::<Vec<_>>();
                     let param_plmn_id = query_params
@@ -4211,6 +4730,61 @@ where
                                     .expect("impossible to fail to serialize");
                                 *response.body_mut() = Body::from(body);
                             }
+                            GetDataSetsResponse::NotModified {
+                                cache_control,
+                                e_tag,
+                                last_modified,
+                            } => {
+                                if let Some(cache_control) = cache_control {
+                                    let cache_control = match header::IntoHeaderValue(cache_control).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling cache_control header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("cache-control"),
+                                        cache_control,
+                                    );
+                                }
+                                if let Some(e_tag) = e_tag {
+                                    let e_tag = match header::IntoHeaderValue(e_tag).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling e_tag header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response
+                                        .headers_mut()
+                                        .insert(HeaderName::from_static("etag"), e_tag);
+                                }
+                                if let Some(last_modified) = last_modified {
+                                    let last_modified = match header::IntoHeaderValue(last_modified).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling last_modified header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("last-modified"),
+                                        last_modified,
+                                    );
+                                }
+                                *response.status_mut() = StatusCode::from_u16(304)
+                                    .expect("Unable to turn 304 into a StatusCode");
+                            }
                             GetDataSetsResponse::BadRequest(body) => {
                                 *response.status_mut() = StatusCode::from_u16(400)
                                     .expect("Unable to turn 400 into a StatusCode");
@@ -4351,7 +4925,9 @@ where
                     let param_shared_data_ids = query_params
                         .iter()
                         .filter(|e| e.0 == "shared-data-ids")
-                        .map(|e| e.1.to_owned())
+                        .flat_map(|e| {
+                            e.1.split(',').map(ToOwned::to_owned).collect::<Vec<_>>()
+                        })
                         .filter_map(|param_shared_data_ids| param_shared_data_ids.parse().ok())
                         .collect::<Vec<_>>();
                     let param_supported_features = query_params
@@ -4471,15 +5047,70 @@ where
                                         last_modified,
                                     );
                                 }
-                                *response.status_mut() = StatusCode::from_u16(200)
-                                    .expect("Unable to turn 200 into a StatusCode");
-                                response.headers_mut().insert(
-                                                        CONTENT_TYPE,
-                                                        HeaderValue::from_str("application/json")
-                                                            .expect("Unable to create Content-Type header for GET_SHARED_DATA_EXPECTED_RESPONSE_TO_A_VALID_REQUEST"));
-                                let body = serde_json::to_string(&body)
-                                    .expect("impossible to fail to serialize");
-                                *response.body_mut() = Body::from(body);
+                                *response.status_mut() = StatusCode::from_u16(200)
+                                    .expect("Unable to turn 200 into a StatusCode");
+                                response.headers_mut().insert(
+                                                        CONTENT_TYPE,
+                                                        HeaderValue::from_str("application/json")
+                                                            .expect("Unable to create Content-Type header for GET_SHARED_DATA_EXPECTED_RESPONSE_TO_A_VALID_REQUEST"));
+                                let body = serde_json::to_string(&body)
+                                    .expect("impossible to fail to serialize");
+                                *response.body_mut() = Body::from(body);
+                            }
+                            GetSharedDataResponse::NotModified {
+                                cache_control,
+                                e_tag,
+                                last_modified,
+                            } => {
+                                if let Some(cache_control) = cache_control {
+                                    let cache_control = match header::IntoHeaderValue(cache_control).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling cache_control header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("cache-control"),
+                                        cache_control,
+                                    );
+                                }
+                                if let Some(e_tag) = e_tag {
+                                    let e_tag = match header::IntoHeaderValue(e_tag).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling e_tag header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response
+                                        .headers_mut()
+                                        .insert(HeaderName::from_static("etag"), e_tag);
+                                }
+                                if let Some(last_modified) = last_modified {
+                                    let last_modified = match header::IntoHeaderValue(last_modified).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling last_modified header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("last-modified"),
+                                        last_modified,
+                                    );
+                                }
+                                *response.status_mut() = StatusCode::from_u16(304)
+                                    .expect("Unable to turn 304 into a StatusCode");
                             }
                             GetSharedDataResponse::BadRequest(body) => {
                                 *response.status_mut() = StatusCode::from_u16(400)
@@ -4748,6 +5379,61 @@ where
                                     .expect("impossible to fail to serialize");
                                 *response.body_mut() = Body::from(body);
                             }
+                            GetIndividualSharedDataResponse::NotModified {
+                                cache_control,
+                                e_tag,
+                                last_modified,
+                            } => {
+                                if let Some(cache_control) = cache_control {
+                                    let cache_control = match header::IntoHeaderValue(cache_control).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling cache_control header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("cache-control"),
+                                        cache_control,
+                                    );
+                                }
+                                if let Some(e_tag) = e_tag {
+                                    let e_tag = match header::IntoHeaderValue(e_tag).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling e_tag header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response
+                                        .headers_mut()
+                                        .insert(HeaderName::from_static("etag"), e_tag);
+                                }
+                                if let Some(last_modified) = last_modified {
+                                    let last_modified = match header::IntoHeaderValue(last_modified).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling last_modified header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("last-modified"),
+                                        last_modified,
+                                    );
+                                }
+                                *response.status_mut() = StatusCode::from_u16(304)
+                                    .expect("Unable to turn 304 into a StatusCode");
+                            }
                             GetIndividualSharedDataResponse::BadRequest(body) => {
                                 *response.status_mut() = StatusCode::from_u16(400)
                                     .expect("Unable to turn 400 into a StatusCode");
@@ -5055,6 +5741,61 @@ where
                                     .expect("impossible to fail to serialize");
                                 *response.body_mut() = Body::from(body);
                             }
+                            GetSmfSelDataResponse::NotModified {
+                                cache_control,
+                                e_tag,
+                                last_modified,
+                            } => {
+                                if let Some(cache_control) = cache_control {
+                                    let cache_control = match header::IntoHeaderValue(cache_control).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling cache_control header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("cache-control"),
+                                        cache_control,
+                                    );
+                                }
+                                if let Some(e_tag) = e_tag {
+                                    let e_tag = match header::IntoHeaderValue(e_tag).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling e_tag header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response
+                                        .headers_mut()
+                                        .insert(HeaderName::from_static("etag"), e_tag);
+                                }
+                                if let Some(last_modified) = last_modified {
+                                    let last_modified = match header::IntoHeaderValue(last_modified).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling last_modified header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("last-modified"),
+                                        last_modified,
+                                    );
+                                }
+                                *response.status_mut() = StatusCode::from_u16(304)
+                                    .expect("Unable to turn 304 into a StatusCode");
+                            }
                             GetSmfSelDataResponse::BadRequest(body) => {
                                 *response.status_mut() = StatusCode::from_u16(400)
                                     .expect("Unable to turn 400 into a StatusCode");
@@ -5342,6 +6083,61 @@ where
                                     .expect("impossible to fail to serialize");
                                 *response.body_mut() = Body::from(body);
                             }
+                            GetSmsMngtDataResponse::NotModified {
+                                cache_control,
+                                e_tag,
+                                last_modified,
+                            } => {
+                                if let Some(cache_control) = cache_control {
+                                    let cache_control = match header::IntoHeaderValue(cache_control).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling cache_control header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("cache-control"),
+                                        cache_control,
+                                    );
+                                }
+                                if let Some(e_tag) = e_tag {
+                                    let e_tag = match header::IntoHeaderValue(e_tag).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling e_tag header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response
+                                        .headers_mut()
+                                        .insert(HeaderName::from_static("etag"), e_tag);
+                                }
+                                if let Some(last_modified) = last_modified {
+                                    let last_modified = match header::IntoHeaderValue(last_modified).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling last_modified header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("last-modified"),
+                                        last_modified,
+                                    );
+                                }
+                                *response.status_mut() = StatusCode::from_u16(304)
+                                    .expect("Unable to turn 304 into a StatusCode");
+                            }
                             GetSmsMngtDataResponse::BadRequest(body) => {
                                 *response.status_mut() = StatusCode::from_u16(400)
                                     .expect("Unable to turn 400 into a StatusCode");
@@ -5629,6 +6425,61 @@ where
                                     .expect("impossible to fail to serialize");
                                 *response.body_mut() = Body::from(body);
                             }
+                            GetSmsDataResponse::NotModified {
+                                cache_control,
+                                e_tag,
+                                last_modified,
+                            } => {
+                                if let Some(cache_control) = cache_control {
+                                    let cache_control = match header::IntoHeaderValue(cache_control).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling cache_control header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("cache-control"),
+                                        cache_control,
+                                    );
+                                }
+                                if let Some(e_tag) = e_tag {
+                                    let e_tag = match header::IntoHeaderValue(e_tag).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling e_tag header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response
+                                        .headers_mut()
+                                        .insert(HeaderName::from_static("etag"), e_tag);
+                                }
+                                if let Some(last_modified) = last_modified {
+                                    let last_modified = match header::IntoHeaderValue(last_modified).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling last_modified header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("last-modified"),
+                                        last_modified,
+                                    );
+                                }
+                                *response.status_mut() = StatusCode::from_u16(304)
+                                    .expect("Unable to turn 304 into a StatusCode");
+                            }
                             GetSmsDataResponse::BadRequest(body) => {
                                 *response.status_mut() = StatusCode::from_u16(400)
                                     .expect("Unable to turn 400 into a StatusCode");
@@ -5927,10 +6778,72 @@ where
                         .expect("Unable to create X-Span-ID header value"),
                     );
 
-                    match result {
-                        Ok(rsp) => match rsp {
-                            GetSmDataResponse::ExpectedResponseToAValidRequest {
-                                body,
+                    match result {
+                        Ok(rsp) => match rsp {
+                            GetSmDataResponse::ExpectedResponseToAValidRequest {
+                                body,
+                                cache_control,
+                                e_tag,
+                                last_modified,
+                            } => {
+                                if let Some(cache_control) = cache_control {
+                                    let cache_control = match header::IntoHeaderValue(cache_control).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling cache_control header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("cache-control"),
+                                        cache_control,
+                                    );
+                                }
+                                if let Some(e_tag) = e_tag {
+                                    let e_tag = match header::IntoHeaderValue(e_tag).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling e_tag header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response
+                                        .headers_mut()
+                                        .insert(HeaderName::from_static("etag"), e_tag);
+                                }
+                                if let Some(last_modified) = last_modified {
+                                    let last_modified = match header::IntoHeaderValue(last_modified).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling last_modified header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("last-modified"),
+                                        last_modified,
+                                    );
+                                }
+                                *response.status_mut() = StatusCode::from_u16(200)
+                                    .expect("Unable to turn 200 into a StatusCode");
+                                response.headers_mut().insert(
+                                                        CONTENT_TYPE,
+                                                        HeaderValue::from_str("application/json")
+                                                            .expect("Unable to create Content-Type header for GET_SM_DATA_EXPECTED_RESPONSE_TO_A_VALID_REQUEST"));
+                                let body = serde_json::to_string(&body)
+                                    .expect("impossible to fail to serialize");
+                                *response.body_mut() = Body::from(body);
+                            }
+                            GetSmDataResponse::NotModified {
                                 cache_control,
                                 e_tag,
                                 last_modified,
@@ -5982,15 +6895,8 @@ where
                                         last_modified,
                                     );
                                 }
-                                *response.status_mut() = StatusCode::from_u16(200)
-                                    .expect("Unable to turn 200 into a StatusCode");
-                                response.headers_mut().insert(
-                                                        CONTENT_TYPE,
-                                                        HeaderValue::from_str("application/json")
-                                                            .expect("Unable to create Content-Type header for GET_SM_DATA_EXPECTED_RESPONSE_TO_A_VALID_REQUEST"));
-                                let body = serde_json::to_string(&body)
-                                    .expect("impossible to fail to serialize");
-                                *response.body_mut() = Body::from(body);
+                                *response.status_mut() = StatusCode::from_u16(304)
+                                    .expect("Unable to turn 304 into a StatusCode");
                             }
                             GetSmDataResponse::BadRequest(body) => {
                                 *response.status_mut() = StatusCode::from_u16(400)
@@ -6300,6 +7206,61 @@ where
                                     .expect("impossible to fail to serialize");
                                 *response.body_mut() = Body::from(body);
                             }
+                            GetNssaiResponse::NotModified {
+                                cache_control,
+                                e_tag,
+                                last_modified,
+                            } => {
+                                if let Some(cache_control) = cache_control {
+                                    let cache_control = match header::IntoHeaderValue(cache_control).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling cache_control header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("cache-control"),
+                                        cache_control,
+                                    );
+                                }
+                                if let Some(e_tag) = e_tag {
+                                    let e_tag = match header::IntoHeaderValue(e_tag).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling e_tag header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response
+                                        .headers_mut()
+                                        .insert(HeaderName::from_static("etag"), e_tag);
+                                }
+                                if let Some(last_modified) = last_modified {
+                                    let last_modified = match header::IntoHeaderValue(last_modified).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling last_modified header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("last-modified"),
+                                        last_modified,
+                                    );
+                                }
+                                *response.status_mut() = StatusCode::from_u16(304)
+                                    .expect("Unable to turn 304 into a StatusCode");
+                            }
                             GetNssaiResponse::BadRequest(body) => {
                                 *response.status_mut() = StatusCode::from_u16(400)
                                     .expect("Unable to turn 400 into a StatusCode");
@@ -7733,6 +8694,61 @@ where
                                     .expect("impossible to fail to serialize");
                                 *response.body_mut() = Body::from(body);
                             }
+                            GetTraceConfigDataResponse::NotModified {
+                                cache_control,
+                                e_tag,
+                                last_modified,
+                            } => {
+                                if let Some(cache_control) = cache_control {
+                                    let cache_control = match header::IntoHeaderValue(cache_control).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling cache_control header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("cache-control"),
+                                        cache_control,
+                                    );
+                                }
+                                if let Some(e_tag) = e_tag {
+                                    let e_tag = match header::IntoHeaderValue(e_tag).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling e_tag header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response
+                                        .headers_mut()
+                                        .insert(HeaderName::from_static("etag"), e_tag);
+                                }
+                                if let Some(last_modified) = last_modified {
+                                    let last_modified = match header::IntoHeaderValue(last_modified).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling last_modified header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("last-modified"),
+                                        last_modified,
+                                    );
+                                }
+                                *response.status_mut() = StatusCode::from_u16(304)
+                                    .expect("Unable to turn 304 into a StatusCode");
+                            }
                             GetTraceConfigDataResponse::BadRequest(body) => {
                                 *response.status_mut() = StatusCode::from_u16(400)
                                     .expect("Unable to turn 400 into a StatusCode");
@@ -8722,6 +9738,61 @@ where
                                     .expect("impossible to fail to serialize");
                                 *response.body_mut() = Body::from(body);
                             }
+                            GetUcDataResponse::NotModified {
+                                cache_control,
+                                e_tag,
+                                last_modified,
+                            } => {
+                                if let Some(cache_control) = cache_control {
+                                    let cache_control = match header::IntoHeaderValue(cache_control).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling cache_control header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("cache-control"),
+                                        cache_control,
+                                    );
+                                }
+                                if let Some(e_tag) = e_tag {
+                                    let e_tag = match header::IntoHeaderValue(e_tag).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling e_tag header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response
+                                        .headers_mut()
+                                        .insert(HeaderName::from_static("etag"), e_tag);
+                                }
+                                if let Some(last_modified) = last_modified {
+                                    let last_modified = match header::IntoHeaderValue(last_modified).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling last_modified header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("last-modified"),
+                                        last_modified,
+                                    );
+                                }
+                                *response.status_mut() = StatusCode::from_u16(304)
+                                    .expect("Unable to turn 304 into a StatusCode");
+                            }
                             GetUcDataResponse::BadRequest(body) => {
                                 *response.status_mut() = StatusCode::from_u16(400)
                                     .expect("Unable to turn 400 into a StatusCode");
@@ -8989,6 +10060,61 @@ where
                                     .expect("impossible to fail to serialize");
                                 *response.body_mut() = Body::from(body);
                             }
+                            GetV2xDataResponse::NotModified {
+                                cache_control,
+                                e_tag,
+                                last_modified,
+                            } => {
+                                if let Some(cache_control) = cache_control {
+                                    let cache_control = match header::IntoHeaderValue(cache_control).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling cache_control header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("cache-control"),
+                                        cache_control,
+                                    );
+                                }
+                                if let Some(e_tag) = e_tag {
+                                    let e_tag = match header::IntoHeaderValue(e_tag).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling e_tag header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response
+                                        .headers_mut()
+                                        .insert(HeaderName::from_static("etag"), e_tag);
+                                }
+                                if let Some(last_modified) = last_modified {
+                                    let last_modified = match header::IntoHeaderValue(last_modified).try_into() {
+                                                        Ok(val) => val,
+                                                        Err(e) => {
+                                                            return Ok(Response::builder()
+                                                                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                                                                    .body(Body::from(format!("An internal server error occurred handling last_modified header - {}", e)))
+                                                                    .expect("Unable to create Internal Server Error for invalid response header"))
+                                                        }
+                                                    };
+
+                                    response.headers_mut().insert(
+                                        HeaderName::from_static("last-modified"),
+                                        last_modified,
+                                    );
+                                }
+                                *response.status_mut() = StatusCode::from_u16(304)
+                                    .expect("Unable to turn 304 into a StatusCode");
+                            }
                             GetV2xDataResponse::BadRequest(body) => {
                                 *response.status_mut() = StatusCode::from_u16(400)
                                     .expect("Unable to turn 400 into a StatusCode");
@@ -0,0 +1,664 @@
+//! Shared subscription data of the UDM.
+//!
+//! Data common to many subscribers, such as the DNN configurations of a
+//! slice, is provisioned once as a [`models::SharedData`] record and
+//! referenced from the subscriber data by its id: the
+//! `sharedSmSubsDataIds` of a [`Subscriber`], and the `sharedAmDataIds`,
+//! `sharedDnnConfigurationsId`, `sharedTraceDataId`, `sharedSnssaiInfosId`,
+//! `sharedSmsSubsDataId` and `sharedSmsMngDataIds` of its data sets.
+//!
+//! Consumers supporting the SharedData feature get the references and
+//! retrieve the records separately, then merge them with [`merge`] and
+//! [`merge_sm_subs_data`]; the others get the data already merged by
+//! [`SharedDataStore::resolve`]. Either way, the individual data takes
+//! precedence over the shared data (TS 29.503 clause 5.2.2.2.1).
+
+use chrono::{DateTime, Utc};
+use futures::channel::mpsc;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::models::{self, SessionManagementSubscriptionData, SharedData, SmSubsData};
+use crate::subscribers::Subscriber;
+
+#[derive(Debug, Clone)]
+struct Provisioned {
+    data: SharedData,
+    last_modified: DateTime<Utc>,
+}
+
+/// Change of a shared data record, as reported to the store watchers.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SharedDataEvent {
+    Provisioned(SharedData),
+    Modified { old: SharedData, new: SharedData },
+    Removed(SharedData),
+}
+
+impl SharedDataEvent {
+    pub fn shared_data_id(&self) -> &str {
+        match self {
+            SharedDataEvent::Provisioned(data)
+            | SharedDataEvent::Modified { new: data, .. }
+            | SharedDataEvent::Removed(data) => &data.shared_data_id,
+        }
+    }
+}
+
+/// Thread-safe handle to the shared data, keyed by shared data id.
+#[derive(Debug, Clone, Default)]
+pub struct SharedDataStore {
+    records: Arc<RwLock<HashMap<String, Provisioned>>>,
+    watchers: Arc<Mutex<Vec<mpsc::UnboundedSender<SharedDataEvent>>>>,
+}
+
+impl SharedDataStore {
+    pub fn new() -> SharedDataStore {
+        SharedDataStore::default()
+    }
+
+    pub fn from_shared_data(records: Vec<SharedData>) -> SharedDataStore {
+        let store = SharedDataStore::new();
+        for data in records {
+            store.insert(data);
+        }
+        store
+    }
+
+    /// Reads a provisioning file of shared data records, JSON if its
+    /// extension is `.json` and YAML otherwise.
+    pub fn read<P: AsRef<Path>>(path: P) -> std::io::Result<Vec<SharedData>> {
+        let path = path.as_ref();
+        let data = std::fs::read(path)?;
+        let invalid = |e: String| std::io::Error::new(std::io::ErrorKind::InvalidData, e);
+
+        if path.extension().map_or(false, |e| e == "json") {
+            serde_json::from_slice(&data).map_err(|e| invalid(e.to_string()))
+        } else {
+            serde_yaml::from_slice(&data).map_err(|e| invalid(e.to_string()))
+        }
+    }
+
+    /// Loads the shared data of a provisioning file.
+    pub fn load<P: AsRef<Path>>(path: P) -> std::io::Result<SharedDataStore> {
+        Ok(SharedDataStore::from_shared_data(SharedDataStore::read(
+            path,
+        )?))
+    }
+
+    /// Provisions a shared data record, returning the one it replaces. The
+    /// record is modified only if it differs from the replaced one.
+    pub fn insert(&self, data: SharedData) -> Option<SharedData> {
+        let (replaced, event) = {
+            let mut records = self.records.write().expect("shared data lock poisoned");
+            let (last_modified, event) = match records.get(&data.shared_data_id) {
+                Some(provisioned) if provisioned.data == data => (provisioned.last_modified, None),
+                Some(provisioned) => (
+                    Utc::now(),
+                    Some(SharedDataEvent::Modified {
+                        old: provisioned.data.clone(),
+                        new: data.clone(),
+                    }),
+                ),
+                None => (Utc::now(), Some(SharedDataEvent::Provisioned(data.clone()))),
+            };
+            let replaced = records
+                .insert(
+                    data.shared_data_id.clone(),
+                    Provisioned {
+                        data,
+                        last_modified,
+                    },
+                )
+                .map(|p| p.data);
+            (replaced, event)
+        };
+        if let Some(event) = event {
+            self.notify(event);
+        }
+        replaced
+    }
+
+    pub fn remove(&self, shared_data_id: &str) -> Option<SharedData> {
+        let removed = self
+            .records
+            .write()
+            .expect("shared data lock poisoned")
+            .remove(shared_data_id)
+            .map(|p| p.data);
+        if let Some(data) = &removed {
+            self.notify(SharedDataEvent::Removed(data.clone()));
+        }
+        removed
+    }
+
+    /// Provisions exactly the given records: the others are removed, and
+    /// those that did not change are left untouched. Returns how many records
+    /// were provisioned, modified or removed.
+    pub fn replace(&self, records: Vec<SharedData>) -> usize {
+        let ids: HashSet<String> = records.iter().map(|d| d.shared_data_id.clone()).collect();
+        let mut changes = 0;
+        for id in self.ids() {
+            if !ids.contains(&id) && self.remove(&id).is_some() {
+                changes += 1;
+            }
+        }
+        for data in records {
+            if self.insert(data.clone()).as_ref() != Some(&data) {
+                changes += 1;
+            }
+        }
+        changes
+    }
+
+    /// Returns a stream of the changes made to the shared data from now on.
+    /// The stream ends when the store is dropped.
+    pub fn watch(&self) -> mpsc::UnboundedReceiver<SharedDataEvent> {
+        let (sender, receiver) = mpsc::unbounded();
+        self.watchers
+            .lock()
+            .expect("shared data lock poisoned")
+            .push(sender);
+        receiver
+    }
+
+    fn notify(&self, event: SharedDataEvent) {
+        self.watchers
+            .lock()
+            .expect("shared data lock poisoned")
+            .retain(|watcher| watcher.unbounded_send(event.clone()).is_ok());
+    }
+
+    pub fn get(&self, shared_data_id: &str) -> Option<SharedData> {
+        self.records
+            .read()
+            .expect("shared data lock poisoned")
+            .get(shared_data_id)
+            .map(|p| p.data.clone())
+    }
+
+    /// The records of several ids, in the same order, or the first id that
+    /// is not provisioned.
+    pub fn get_all<S: AsRef<str>>(&self, shared_data_ids: &[S]) -> Result<Vec<SharedData>, String> {
+        let records = self.records.read().expect("shared data lock poisoned");
+        shared_data_ids
+            .iter()
+            .map(|id| {
+                records
+                    .get(id.as_ref())
+                    .map(|p| p.data.clone())
+                    .ok_or_else(|| id.as_ref().to_string())
+            })
+            .collect()
+    }
+
+    /// The latest modification time among the provisioned records of several
+    /// ids.
+    pub fn last_modified<S: AsRef<str>>(&self, shared_data_ids: &[S]) -> Option<DateTime<Utc>> {
+        let records = self.records.read().expect("shared data lock poisoned");
+        shared_data_ids
+            .iter()
+            .filter_map(|id| records.get(id.as_ref()))
+            .map(|p| p.last_modified)
+            .max()
+    }
+
+    /// Those of several ids whose record holds data of a kind, e.g. the
+    /// shared AM data ids among the shared data ids of a subscriber.
+    pub fn ids_with<S, F>(&self, shared_data_ids: &[S], holds: F) -> Vec<models::SharedDataId>
+    where
+        S: AsRef<str>,
+        F: Fn(&SharedData) -> bool,
+    {
+        let records = self.records.read().expect("shared data lock poisoned");
+        shared_data_ids
+            .iter()
+            .filter(|id| records.get(id.as_ref()).map_or(false, |p| holds(&p.data)))
+            .map(|id| models::SharedDataId::from(id.as_ref().to_string()))
+            .collect()
+    }
+
+    pub fn ids(&self) -> Vec<String> {
+        self.records
+            .read()
+            .expect("shared data lock poisoned")
+            .keys()
+            .cloned()
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.records
+            .read()
+            .expect("shared data lock poisoned")
+            .len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The subscriber with the shared data it references merged into its
+    /// individual data, and the references removed. References to records
+    /// that are not provisioned are dropped.
+    pub fn resolve(&self, subscriber: &Subscriber) -> Subscriber {
+        let records = self.records.read().expect("shared data lock poisoned");
+        let shared = |id: &str| records.get(id).map(|p| &p.data);
+        let mut resolved = subscriber.clone();
+
+        if let Some(am_data) = &mut resolved.am_data {
+            for id in am_data.shared_am_data_ids.take().into_iter().flatten() {
+                if let Some(shared_am_data) =
+                    shared(id.as_str()).and_then(|d| d.shared_am_data.as_ref())
+                {
+                    *am_data = merge(am_data, shared_am_data);
+                }
+            }
+            am_data.shared_am_data_ids = None;
+        }
+
+        resolved.sm_data = merge_sm_data(
+            &resolved.sm_data,
+            &std::mem::take(&mut resolved.shared_sm_subs_data_ids),
+            &shared,
+        );
+
+        if let Some(smf_sel_data) = &mut resolved.smf_sel_data {
+            if let Some(id) = smf_sel_data.shared_snssai_infos_id.take() {
+                if let Some(infos) =
+                    shared(id.as_str()).and_then(|d| d.shared_snssai_infos.as_ref())
+                {
+                    smf_sel_data.subscribed_snssai_infos = Some(merge(
+                        &smf_sel_data
+                            .subscribed_snssai_infos
+                            .clone()
+                            .unwrap_or_default(),
+                        infos,
+                    ));
+                }
+            }
+        }
+
+        if let Some(sms_subs_data) = &mut resolved.sms_subs_data {
+            if let Some(id) = sms_subs_data.shared_sms_subs_data_id.take() {
+                if let Some(shared_data) =
+                    shared(id.as_str()).and_then(|d| d.shared_sms_subs_data.as_ref())
+                {
+                    *sms_subs_data = merge(sms_subs_data, shared_data);
+                }
+                sms_subs_data.shared_sms_subs_data_id = None;
+            }
+        }
+
+        if let Some(sms_mng_data) = &mut resolved.sms_mng_data {
+            for id in sms_mng_data
+                .shared_sms_mng_data_ids
+                .take()
+                .into_iter()
+                .flatten()
+            {
+                if let Some(shared_data) =
+                    shared(id.as_str()).and_then(|d| d.shared_sms_mng_subs_data.as_ref())
+                {
+                    *sms_mng_data = merge(sms_mng_data, shared_data);
+                }
+            }
+            sms_mng_data.shared_sms_mng_data_ids = None;
+        }
+
+        resolved
+    }
+}
+
+/// Merges shared data into individual data of the same type: the members of
+/// the shared data missing from the individual data are added, the objects
+/// present in both being merged member by member, so that the individual
+/// data takes precedence. Arrays are not merged. Data that cannot be merged
+/// is logged and left individual.
+pub fn merge<T>(individual: &T, shared: &T) -> T
+where
+    T: Serialize + DeserializeOwned + Clone,
+{
+    let merged = serde_json::to_value(individual).and_then(|mut merged| {
+        merge_values(&mut merged, &serde_json::to_value(shared)?);
+        serde_json::from_value(merged)
+    });
+    merged.unwrap_or_else(|e| {
+        log::warn!(
+            "Unable to merge shared {} into the individual data: {}",
+            std::any::type_name::<T>(),
+            e
+        );
+        individual.clone()
+    })
+}
+
+fn merge_values(individual: &mut serde_json::Value, shared: &serde_json::Value) {
+    if let (serde_json::Value::Object(individual), serde_json::Value::Object(shared)) =
+        (individual, shared)
+    {
+        for (name, value) in shared {
+            match individual.get_mut(name) {
+                Some(present) => merge_values(present, value),
+                None => {
+                    individual.insert(name.clone(), value.clone());
+                }
+            }
+        }
+    }
+}
+
+/// The SM data of a UE as retrieved with the SharedData feature, merged
+/// with the shared data records it references: one entry per S-NSSAI.
+pub fn merge_sm_subs_data(
+    sm_data: &SmSubsData,
+    shared: &[SharedData],
+) -> Vec<SessionManagementSubscriptionData> {
+    let shared: HashMap<&str, &SharedData> = shared
+        .iter()
+        .map(|data| (data.shared_data_id.as_str(), data))
+        .collect();
+    merge_sm_data(
+        sm_data
+            .individual_sm_subs_data
+            .as_deref()
+            .unwrap_or_default(),
+        &sm_data.shared_sm_subs_data_ids,
+        &|id| shared.get(id).copied(),
+    )
+}
+
+/// The ids of the shared data records SM data references, for retrieving
+/// them before [`merge_sm_subs_data`].
+pub fn sm_subs_data_references(sm_data: &SmSubsData) -> Vec<String> {
+    let mut ids: Vec<String> = sm_data
+        .shared_sm_subs_data_ids
+        .iter()
+        .map(|id| id.to_string())
+        .collect();
+    for data in sm_data.individual_sm_subs_data.iter().flatten() {
+        for id in data
+            .shared_dnn_configurations_id
+            .iter()
+            .chain(data.shared_trace_data_id.iter())
+        {
+            if !ids.contains(id) {
+                ids.push(id.clone());
+            }
+        }
+    }
+    ids
+}
+
+/// Resolves the shared DNN configurations and trace data of individual SM
+/// data, then merges the shared SM data into the individual data of the same
+/// S-NSSAI, or adds it for the other S-NSSAIs.
+fn merge_sm_data<'a>(
+    individual: &[SessionManagementSubscriptionData],
+    shared_sm_subs_data_ids: &[models::SharedDataId],
+    shared: &dyn Fn(&str) -> Option<&'a SharedData>,
+) -> Vec<SessionManagementSubscriptionData> {
+    let resolve = |data: &SessionManagementSubscriptionData| {
+        let mut data = data.clone();
+        if let Some(id) = data.shared_dnn_configurations_id.take() {
+            if let Some(configurations) =
+                shared(id.as_str()).and_then(|d| d.shared_dnn_configurations.as_ref())
+            {
+                data.dnn_configurations = Some(merge(
+                    &data.dnn_configurations.clone().unwrap_or_default(),
+                    configurations,
+                ));
+            }
+        }
+        if let Some(id) = data.shared_trace_data_id.take() {
+            if data.trace_data.is_none() {
+                data.trace_data = shared(id.as_str()).and_then(|d| d.shared_trace_data.clone());
+            }
+        }
+        data
+    };
+
+    let mut merged: Vec<SessionManagementSubscriptionData> =
+        individual.iter().map(resolve).collect();
+    for id in shared_sm_subs_data_ids {
+        let shared_data = match shared(id.as_str()).and_then(|d| d.shared_sm_subs_data.as_ref()) {
+            Some(shared_data) => resolve(shared_data),
+            None => continue,
+        };
+        match merged
+            .iter_mut()
+            .find(|data| data.single_nssai == shared_data.single_nssai)
+        {
+            Some(data) => *data = merge(data, &shared_data),
+            None => merged.push(shared_data),
+        }
+    }
+    merged
+}
+
+/// The problem of a request about shared data that is not provisioned.
+pub fn shared_data_not_found(shared_data_id: &str) -> models::ProblemDetails {
+    let mut problem = models::ProblemDetails::new();
+    problem.status = Some(404);
+    problem.cause = Some("DATA_NOT_FOUND".to_string());
+    problem.detail = Some(format!("Unknown shared data {}", shared_data_id));
+    problem
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::collections::BTreeMap;
+
+    fn dnn_configuration(ssc_mode: &str) -> serde_json::Value {
+        json!({
+            "pduSessionTypes": { "defaultSessionType": "IPV4" },
+            "sscModes": { "defaultSscMode": ssc_mode }
+        })
+    }
+
+    fn shared_data(value: serde_json::Value) -> SharedData {
+        serde_json::from_value(value).expect("valid shared data")
+    }
+
+    fn sm_subs_data(value: serde_json::Value) -> SmSubsData {
+        serde_json::from_value(value).expect("valid SM data")
+    }
+
+    fn ssc_mode(data: &SessionManagementSubscriptionData, dnn: &str) -> String {
+        data.dnn_configurations
+            .as_ref()
+            .expect("DNN configurations")[dnn]
+            .ssc_modes
+            .default_ssc_mode
+            .to_string()
+    }
+
+    #[test]
+    fn merge_adds_the_missing_members_of_the_shared_data() {
+        let individual: models::AccessAndMobilitySubscriptionData = serde_json::from_value(json!({
+            "gpsis": ["msisdn-33600000001"],
+            "subscribedUeAmbr": { "uplink": "1 Gbps", "downlink": "2 Gbps" }
+        }))
+        .unwrap();
+        let shared = serde_json::from_value(json!({
+            "gpsis": ["msisdn-33600000002", "msisdn-33600000003"],
+            "subscribedUeAmbr": { "uplink": "5 Gbps", "downlink": "5 Gbps" },
+            "mpsPriority": true
+        }))
+        .unwrap();
+
+        let merged = merge(&individual, &shared);
+        assert_eq!(merged.gpsis, individual.gpsis);
+        assert_eq!(merged.subscribed_ue_ambr, individual.subscribed_ue_ambr);
+        assert_eq!(merged.mps_priority, Some(true));
+    }
+
+    #[test]
+    fn merge_merges_the_objects_member_by_member() {
+        let individual: HashMap<String, models::DnnConfiguration> =
+            serde_json::from_value(json!({ "internet": dnn_configuration("SSC_MODE_1") })).unwrap();
+        let mut internet = dnn_configuration("SSC_MODE_2");
+        internet["iwkEpsInd"] = json!(true);
+        let shared = serde_json::from_value(json!({
+            "internet": internet,
+            "ims": dnn_configuration("SSC_MODE_3")
+        }))
+        .unwrap();
+
+        let merged = merge(&individual, &shared);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(
+            merged["internet"].ssc_modes.default_ssc_mode.to_string(),
+            "SSC_MODE_1"
+        );
+        assert_eq!(merged["internet"].iwk_eps_ind, Some(true));
+        assert_eq!(
+            merged["ims"].ssc_modes.default_ssc_mode.to_string(),
+            "SSC_MODE_3"
+        );
+    }
+
+    #[test]
+    fn merge_keeps_the_individual_data_it_cannot_serialise() {
+        // JSON objects only have string keys
+        let individual: BTreeMap<(u8, u8), u8> = vec![((1, 1), 1)].into_iter().collect();
+        let shared = vec![((2, 2), 2)].into_iter().collect();
+
+        assert_eq!(merge(&individual, &shared), individual);
+    }
+
+    #[test]
+    fn individual_sm_data_takes_precedence_over_the_shared_records() {
+        let sm_data = sm_subs_data(json!({
+            "sharedSmSubsDataIds": ["sm-slice-1", "sm-slice-2"],
+            "individualSmSubsData": [{
+                "singleNssai": { "sst": 1 },
+                "sharedDnnConfigurationsId": "dnns",
+                "dnnConfigurations": { "internet": dnn_configuration("SSC_MODE_1") }
+            }]
+        }));
+        let shared = vec![
+            shared_data(json!({
+                "sharedDataId": "dnns",
+                "sharedDnnConfigurations": {
+                    "internet": dnn_configuration("SSC_MODE_2"),
+                    "ims": dnn_configuration("SSC_MODE_2")
+                }
+            })),
+            shared_data(json!({
+                "sharedDataId": "sm-slice-1",
+                "sharedSmSubsData": {
+                    "singleNssai": { "sst": 1 },
+                    "dnnConfigurations": {
+                        "ims": dnn_configuration("SSC_MODE_3"),
+                        "edge": dnn_configuration("SSC_MODE_3")
+                    }
+                }
+            })),
+            shared_data(json!({
+                "sharedDataId": "sm-slice-2",
+                "sharedSmSubsData": {
+                    "singleNssai": { "sst": 2 },
+                    "dnnConfigurations": { "internet": dnn_configuration("SSC_MODE_3") }
+                }
+            })),
+        ];
+
+        let merged = merge_sm_subs_data(&sm_data, &shared);
+        assert_eq!(merged.len(), 2);
+        let slice_1 = &merged[0];
+        assert_eq!(slice_1.single_nssai.sst, 1);
+        assert_eq!(slice_1.shared_dnn_configurations_id, None);
+        assert_eq!(slice_1.dnn_configurations.as_ref().unwrap().len(), 3);
+        assert_eq!(ssc_mode(slice_1, "internet"), "SSC_MODE_1");
+        assert_eq!(ssc_mode(slice_1, "ims"), "SSC_MODE_2");
+        assert_eq!(ssc_mode(slice_1, "edge"), "SSC_MODE_3");
+        let slice_2 = &merged[1];
+        assert_eq!(slice_2.single_nssai.sst, 2);
+        assert_eq!(ssc_mode(slice_2, "internet"), "SSC_MODE_3");
+    }
+
+    #[test]
+    fn individual_trace_data_takes_precedence_over_the_shared_one() {
+        let trace_data = |trace_ref: &str| {
+            json!({
+                "traceRef": trace_ref,
+                "traceDepth": {},
+                "neTypeList": "01",
+                "eventList": "01"
+            })
+        };
+        let sm_data = sm_subs_data(json!({
+            "sharedSmSubsDataIds": [],
+            "individualSmSubsData": [
+                {
+                    "singleNssai": { "sst": 1 },
+                    "sharedTraceDataId": "trace",
+                    "traceData": trace_data("001010-000001")
+                },
+                { "singleNssai": { "sst": 2 }, "sharedTraceDataId": "trace" }
+            ]
+        }));
+        let shared = vec![shared_data(json!({
+            "sharedDataId": "trace",
+            "sharedTraceData": trace_data("001010-000002")
+        }))];
+
+        let trace_refs: Vec<String> = merge_sm_subs_data(&sm_data, &shared)
+            .into_iter()
+            .map(|data| {
+                assert_eq!(data.shared_trace_data_id, None);
+                match data.trace_data {
+                    Some(swagger::Nullable::Present(trace_data)) => trace_data.trace_ref,
+                    other => panic!("no trace data: {:?}", other),
+                }
+            })
+            .collect();
+        assert_eq!(trace_refs, vec!["001010-000001", "001010-000002"]);
+    }
+
+    #[test]
+    fn unprovisioned_references_are_ignored() {
+        let sm_data = sm_subs_data(json!({
+            "sharedSmSubsDataIds": ["missing"],
+            "individualSmSubsData": [{
+                "singleNssai": { "sst": 1 },
+                "sharedDnnConfigurationsId": "missing",
+                "dnnConfigurations": { "internet": dnn_configuration("SSC_MODE_1") }
+            }]
+        }));
+
+        let merged = merge_sm_subs_data(&sm_data, &[]);
+        assert_eq!(merged.len(), 1);
+        assert_eq!(merged[0].dnn_configurations.as_ref().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn references_are_listed_once_in_order() {
+        let sm_data = sm_subs_data(json!({
+            "sharedSmSubsDataIds": ["sm-slice-1", "dnns"],
+            "individualSmSubsData": [
+                {
+                    "singleNssai": { "sst": 1 },
+                    "sharedDnnConfigurationsId": "dnns",
+                    "sharedTraceDataId": "trace"
+                },
+                {
+                    "singleNssai": { "sst": 2 },
+                    "sharedDnnConfigurationsId": "dnns-2",
+                    "sharedTraceDataId": "trace"
+                }
+            ]
+        }));
+
+        assert_eq!(
+            sm_subs_data_references(&sm_data),
+            vec!["sm-slice-1", "dnns", "trace", "dnns-2"]
+        );
+    }
+}
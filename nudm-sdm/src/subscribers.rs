@@ -0,0 +1,888 @@
+//! Subscriber database of the UDM.
+//!
+//! The subscribers are provisioned from a YAML or JSON file holding a list of
+//! [`Subscriber`] entries, and looked up by SUPI. The HTTP, gRPC and zenoh
+//! front-ends of the UDM all answer from a [`SubscriberStore`]; a SUPI that is
+//! not in the store is answered with [`user_not_found`].
+
+use chrono::{DateTime, Utc};
+use futures::channel::mpsc;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::{Arc, Mutex, RwLock};
+
+use crate::models;
+
+/// The subscription data of one subscriber.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Subscriber {
+    /// e.g. `imsi-001011234567895`
+    #[serde(rename = "supi")]
+    pub supi: String,
+
+    /// e.g. `msisdn-33600000001`
+    #[serde(rename = "gpsis")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub gpsis: Vec<String>,
+
+    /// External identifiers that only some AFs, or only some application
+    /// ports, translate to and from the SUPI
+    #[serde(rename = "externalIds")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub external_ids: Vec<ExternalIdentifier>,
+
+    #[serde(rename = "groups")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<GroupIdentity>,
+
+    #[serde(rename = "amData")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub am_data: Option<models::AccessAndMobilitySubscriptionData>,
+
+    /// One entry per S-NSSAI, with the configurations of its DNNs
+    #[serde(rename = "smData")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub sm_data: Vec<models::SessionManagementSubscriptionData>,
+
+    /// Shared data holding SM data of the subscriber, the individual SM data
+    /// of the same S-NSSAI taking precedence
+    #[serde(rename = "sharedSmSubsDataIds")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub shared_sm_subs_data_ids: Vec<models::SharedDataId>,
+
+    #[serde(rename = "smfSelData")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub smf_sel_data: Option<models::SmfSelectionSubscriptionData>,
+
+    #[serde(rename = "nssai")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nssai: Option<models::Nssai>,
+
+    #[serde(rename = "uecAmfData")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uec_amf_data: Option<models::UeContextInAmfData>,
+
+    #[serde(rename = "uecSmfData")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uec_smf_data: Option<models::UeContextInSmfData>,
+
+    #[serde(rename = "uecSmsfData")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uec_smsf_data: Option<models::UeContextInSmsfData>,
+
+    #[serde(rename = "smsSubsData")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sms_subs_data: Option<models::SmsSubscriptionData>,
+
+    #[serde(rename = "smsMngData")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub sms_mng_data: Option<models::SmsManagementSubscriptionData>,
+
+    #[serde(rename = "traceData")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trace_data: Option<models::TraceData>,
+
+    #[serde(rename = "lcsPrivacyData")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lcs_privacy_data: Option<models::LcsPrivacyData>,
+
+    #[serde(rename = "lcsMoData")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lcs_mo_data: Option<models::LcsMoData>,
+
+    #[serde(rename = "lcsBroadcastAssistanceTypesData")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lcs_broadcast_assistance_types_data: Option<models::LcsBroadcastAssistanceTypesData>,
+
+    #[serde(rename = "v2xData")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub v2x_data: Option<models::V2xSubscriptionData>,
+
+    #[serde(rename = "proseData")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prose_data: Option<models::ProseSubscriptionData>,
+
+    #[serde(rename = "mbsData")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mbs_data: Option<models::MbsSubscriptionData>,
+
+    #[serde(rename = "ucData")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uc_data: Option<models::UcSubscriptionData>,
+
+    #[serde(rename = "ecrData")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ecr_data: Option<models::EnhancedCoverageRestrictionData>,
+
+    /// Data sets applying when the UE is served by another PLMN, keyed by
+    /// [`plmn_key`]. Those they hold replace the data sets above.
+    #[serde(rename = "servingPlmns")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub serving_plmns: HashMap<String, models::SubscriptionDataSets>,
+}
+
+impl Subscriber {
+    pub fn new(supi: String) -> Subscriber {
+        Subscriber {
+            supi,
+            gpsis: vec![],
+            external_ids: vec![],
+            groups: vec![],
+            am_data: None,
+            sm_data: vec![],
+            shared_sm_subs_data_ids: vec![],
+            smf_sel_data: None,
+            nssai: None,
+            uec_amf_data: None,
+            uec_smf_data: None,
+            uec_smsf_data: None,
+            sms_subs_data: None,
+            sms_mng_data: None,
+            trace_data: None,
+            lcs_privacy_data: None,
+            lcs_mo_data: None,
+            lcs_broadcast_assistance_types_data: None,
+            v2x_data: None,
+            prose_data: None,
+            mbs_data: None,
+            uc_data: None,
+            ecr_data: None,
+            serving_plmns: HashMap::new(),
+        }
+    }
+
+    /// Whether a SUPI or a GPSI, restricted external identifiers included,
+    /// identifies the subscriber.
+    pub fn is_identified_by(&self, ue_id: &str) -> bool {
+        self.supi == ue_id
+            || self.gpsis.iter().any(|gpsi| gpsi == ue_id)
+            || self.external_ids.iter().any(|id| id.gpsi == ue_id)
+    }
+
+    /// The GPSI of the subscriber translated for a requester: of the
+    /// requested type, or else the MSISDN for requests not naming an AF. The
+    /// external identifiers restricted to the requester are preferred to the
+    /// unrestricted ones.
+    pub fn gpsi(
+        &self,
+        gpsi_type: Option<models::GpsiTypeAnyOf>,
+        requester: &Requester,
+    ) -> Option<String> {
+        use models::GpsiTypeAnyOf as GpsiType;
+
+        let of_kind = |prefix: &str| {
+            self.gpsis
+                .iter()
+                .find(|gpsi| gpsi.starts_with(prefix))
+                .cloned()
+        };
+        let external_id = || {
+            self.external_ids
+                .iter()
+                .find(|id| id.is_restricted() && id.allows(requester))
+                .or_else(|| self.external_ids.iter().find(|id| !id.is_restricted()))
+                .map(|id| id.gpsi.clone())
+                .or_else(|| of_kind("extid-"))
+        };
+
+        match gpsi_type {
+            Some(GpsiType::Msisdn) => of_kind("msisdn-"),
+            Some(GpsiType::ExtId) => external_id(),
+            Some(GpsiType::ExtGroupId) => self
+                .groups
+                .iter()
+                .find_map(|group| group.ext_group_id.clone()),
+            None if requester.is_af() => external_id(),
+            None => of_kind("msisdn-").or_else(external_id),
+        }
+    }
+
+    /// The ids of the shared data the data of the subscriber references, in
+    /// the order of the references and without duplicates.
+    pub fn shared_data_ids(&self) -> Vec<String> {
+        let mut ids: Vec<String> = Vec::new();
+        let mut reference = |id: &str| {
+            if !ids.iter().any(|known| known == id) {
+                ids.push(id.to_string());
+            }
+        };
+
+        for id in self.shared_sm_subs_data_ids.iter() {
+            reference(id);
+        }
+        for data in self.sm_data.iter() {
+            for id in data
+                .shared_dnn_configurations_id
+                .iter()
+                .chain(data.shared_trace_data_id.iter())
+            {
+                reference(id);
+            }
+        }
+        if let Some(am_data) = &self.am_data {
+            for id in am_data.shared_am_data_ids.iter().flatten() {
+                reference(id);
+            }
+        }
+        if let Some(id) = self
+            .smf_sel_data
+            .as_ref()
+            .and_then(|data| data.shared_snssai_infos_id.as_ref())
+        {
+            reference(id);
+        }
+        if let Some(id) = self
+            .sms_subs_data
+            .as_ref()
+            .and_then(|data| data.shared_sms_subs_data_id.as_ref())
+        {
+            reference(id);
+        }
+        if let Some(sms_mng_data) = &self.sms_mng_data {
+            for id in sms_mng_data.shared_sms_mng_data_ids.iter().flatten() {
+                reference(id);
+            }
+        }
+        ids
+    }
+
+    /// The session management data of the subscriber, restricted to a slice
+    /// and to a DNN when they are given. The DNN of a slice whose DNN
+    /// configurations are shared may be among them: the slice is kept, with
+    /// its individual configurations left out.
+    pub fn sm_data(
+        &self,
+        single_nssai: Option<&models::Snssai>,
+        dnn: Option<&str>,
+    ) -> Vec<models::SessionManagementSubscriptionData> {
+        self.sm_data
+            .iter()
+            .filter(|data| single_nssai.map_or(true, |s| snssai_matches(&data.single_nssai, s)))
+            .filter_map(|data| match dnn {
+                None => Some(data.clone()),
+                Some(dnn) => {
+                    let configuration = data
+                        .dnn_configurations
+                        .iter()
+                        .flatten()
+                        .find(|(name, _)| name.eq_ignore_ascii_case(dnn));
+                    let mut data = data.clone();
+                    data.dnn_configurations = match configuration {
+                        Some((name, configuration)) => {
+                            Some(HashMap::from([(name.clone(), configuration.clone())]))
+                        }
+                        None if data.shared_dnn_configurations_id.is_some() => None,
+                        None => return None,
+                    };
+                    Some(data)
+                }
+            })
+            .collect()
+    }
+
+    /// The subscriber as seen from a serving PLMN: the data sets provisioned
+    /// for that PLMN replace the home ones, and the subscribed NSSAI is the
+    /// one of its access and mobility data.
+    pub fn in_plmn(&self, plmn_key: &str) -> Subscriber {
+        let mut subscriber = self.clone();
+        let serving = match self.serving_plmns.get(plmn_key) {
+            Some(serving) => serving.clone(),
+            None => return subscriber,
+        };
+
+        if let Some(am_data) = serving.am_data {
+            if let Some(swagger::Nullable::Present(nssai)) = &am_data.nssai {
+                subscriber.nssai = Some(nssai.clone());
+            }
+            subscriber.am_data = Some(am_data);
+        }
+        if let Some(sm_data) = serving.sm_data {
+            subscriber.sm_data = sm_data.individual_sm_subs_data.unwrap_or_default();
+            subscriber.shared_sm_subs_data_ids = sm_data.shared_sm_subs_data_ids;
+        }
+        if let Some(trace_data) = serving.trace_data {
+            subscriber.trace_data = match trace_data {
+                swagger::Nullable::Present(trace_data) => Some(trace_data),
+                swagger::Nullable::Null => None,
+            };
+        }
+        macro_rules! replace {
+            ($($field:ident),*) => {
+                $(
+                    if serving.$field.is_some() {
+                        subscriber.$field = serving.$field;
+                    }
+                )*
+            };
+        }
+        replace!(
+            smf_sel_data,
+            uec_amf_data,
+            uec_smf_data,
+            uec_smsf_data,
+            sms_subs_data,
+            sms_mng_data,
+            lcs_privacy_data,
+            lcs_mo_data,
+            lcs_broadcast_assistance_types_data,
+            v2x_data,
+            prose_data,
+            mbs_data,
+            uc_data
+        );
+        subscriber
+    }
+
+    /// The JSON content of a resource of the subscriber data, named by its
+    /// path below the subscriber as in [`RESOURCES`]; `None` when the
+    /// subscriber is not provisioned with it.
+    pub fn resource(&self, resource: &str) -> Option<serde_json::Value> {
+        let value = match resource {
+            "am-data" => serde_json::to_value(&self.am_data),
+            "am-data/ecr-data" => serde_json::to_value(&self.ecr_data),
+            "smf-select-data" => serde_json::to_value(&self.smf_sel_data),
+            "sm-data" if self.sm_data.is_empty() => return None,
+            "sm-data" => serde_json::to_value(&self.sm_data),
+            "nssai" => serde_json::to_value(&self.nssai),
+            "sms-data" => serde_json::to_value(&self.sms_subs_data),
+            "sms-mng-data" => serde_json::to_value(&self.sms_mng_data),
+            "trace-data" => serde_json::to_value(&self.trace_data),
+            "ue-context-in-amf-data" => serde_json::to_value(&self.uec_amf_data),
+            "ue-context-in-smf-data" => serde_json::to_value(&self.uec_smf_data),
+            "ue-context-in-smsf-data" => serde_json::to_value(&self.uec_smsf_data),
+            "lcs-privacy-data" => serde_json::to_value(&self.lcs_privacy_data),
+            "lcs-mo-data" => serde_json::to_value(&self.lcs_mo_data),
+            "lcs-bca-data" => serde_json::to_value(&self.lcs_broadcast_assistance_types_data),
+            "v2x-data" => serde_json::to_value(&self.v2x_data),
+            "prose-data" => serde_json::to_value(&self.prose_data),
+            "5mbs-data" => serde_json::to_value(&self.mbs_data),
+            "uc-data" => serde_json::to_value(&self.uc_data),
+            _ => return None,
+        };
+        value.ok().filter(|value| !value.is_null())
+    }
+
+    /// The data sets of the subscriber, those not named left out.
+    pub fn data_sets(&self, names: &[models::DataSetNameAnyOf]) -> models::SubscriptionDataSets {
+        use models::DataSetNameAnyOf as Name;
+
+        let mut sets = models::SubscriptionDataSets::new();
+        for name in names {
+            match name {
+                Name::Am => sets.am_data = self.am_data.clone(),
+                Name::SmfSel => sets.smf_sel_data = self.smf_sel_data.clone(),
+                Name::UecAmf => sets.uec_amf_data = self.uec_amf_data.clone(),
+                Name::UecSmf => sets.uec_smf_data = self.uec_smf_data.clone(),
+                Name::UecSmsf => sets.uec_smsf_data = self.uec_smsf_data.clone(),
+                Name::SmsSub => sets.sms_subs_data = self.sms_subs_data.clone(),
+                Name::Sm => {
+                    sets.sm_data = Some(models::SmSubsData {
+                        shared_sm_subs_data_ids: self.shared_sm_subs_data_ids.clone(),
+                        individual_sm_subs_data: Some(self.sm_data.clone()),
+                    })
+                }
+                Name::Trace => {
+                    sets.trace_data = self.trace_data.clone().map(swagger::Nullable::Present)
+                }
+                Name::SmsMng => sets.sms_mng_data = self.sms_mng_data.clone(),
+                Name::LcsPrivacy => sets.lcs_privacy_data = self.lcs_privacy_data.clone(),
+                Name::LcsMo => sets.lcs_mo_data = self.lcs_mo_data.clone(),
+                Name::V2X => sets.v2x_data = self.v2x_data.clone(),
+                Name::LcsBca => {
+                    sets.lcs_broadcast_assistance_types_data =
+                        self.lcs_broadcast_assistance_types_data.clone()
+                }
+                Name::Prose => sets.prose_data = self.prose_data.clone(),
+                Name::Uc => sets.uc_data = self.uc_data.clone(),
+                Name::Mbs => sets.mbs_data = self.mbs_data.clone(),
+            }
+        }
+        sets
+    }
+}
+
+/// An external identifier of a subscriber, possibly restricted to some AFs,
+/// AF services, MTC providers and application ports.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ExternalIdentifier {
+    /// e.g. `extid-device1@af.example.com`
+    #[serde(rename = "gpsi")]
+    pub gpsi: String,
+
+    /// The AFs the identifier is translated for, all of them if empty
+    #[serde(rename = "afIds")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub af_ids: Vec<String>,
+
+    /// The application ports the identifier is used on, all of them if empty
+    #[serde(rename = "appPortIds")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub app_port_ids: Vec<models::AppPortId>,
+
+    /// The AF services the identifier is translated for, all of them if
+    /// empty
+    #[serde(rename = "afServiceIds")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub af_service_ids: Vec<String>,
+
+    /// The MTC providers the identifier is translated for, all of them if
+    /// empty
+    #[serde(rename = "mtcProviderInfos")]
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub mtc_provider_infos: Vec<String>,
+}
+
+impl ExternalIdentifier {
+    pub fn is_restricted(&self) -> bool {
+        !self.af_ids.is_empty()
+            || !self.app_port_ids.is_empty()
+            || !self.af_service_ids.is_empty()
+            || !self.mtc_provider_infos.is_empty()
+    }
+
+    /// Whether the identifier is translated for a requester. The AF, AF
+    /// service and MTC provider must be named if the identifier is
+    /// restricted to some of them; the application port only has to match
+    /// when it is named.
+    pub fn allows(&self, requester: &Requester) -> bool {
+        fn named(allowed: &[String], named: Option<&str>) -> bool {
+            allowed.is_empty() || named.map_or(false, |named| allowed.iter().any(|a| a == named))
+        }
+        let port_allowed = match requester.app_port_id {
+            Some(app_port_id) => {
+                self.app_port_ids.is_empty() || self.app_port_ids.contains(app_port_id)
+            }
+            None => true,
+        };
+        named(&self.af_ids, requester.af_id)
+            && named(&self.af_service_ids, requester.af_service_id)
+            && named(&self.mtc_provider_infos, requester.mtc_provider_info)
+            && port_allowed
+    }
+}
+
+/// Who an identifier is translated for, as named by the query of a
+/// translation request.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Requester<'a> {
+    pub af_id: Option<&'a str>,
+    pub app_port_id: Option<&'a models::AppPortId>,
+    pub af_service_id: Option<&'a str>,
+    pub mtc_provider_info: Option<&'a str>,
+}
+
+impl Requester<'_> {
+    /// Whether the request comes from an AF, through the NEF.
+    pub fn is_af(&self) -> bool {
+        self.af_id.is_some()
+            || self.app_port_id.is_some()
+            || self.af_service_id.is_some()
+            || self.mtc_provider_info.is_some()
+    }
+}
+
+/// A group of subscribers: its internal group identifier and, when it is
+/// exposed to AFs, its external one.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct GroupIdentity {
+    /// e.g. `00000001-001-01-01`
+    #[serde(rename = "intGroupId")]
+    pub int_group_id: String,
+
+    /// e.g. `extgroupid-fleet@af.example.com`
+    #[serde(rename = "extGroupId")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ext_group_id: Option<String>,
+}
+
+/// Paths, relative to `/nudm-sdm/v2/{supi}`, of the resources of the
+/// subscriber data.
+pub const RESOURCES: &[&str] = &[
+    "am-data",
+    "am-data/ecr-data",
+    "smf-select-data",
+    "sm-data",
+    "nssai",
+    "sms-data",
+    "sms-mng-data",
+    "trace-data",
+    "ue-context-in-amf-data",
+    "ue-context-in-smf-data",
+    "ue-context-in-smsf-data",
+    "lcs-privacy-data",
+    "lcs-mo-data",
+    "lcs-bca-data",
+    "v2x-data",
+    "prose-data",
+    "5mbs-data",
+    "uc-data",
+];
+
+/// Key of the data sets of a serving PLMN, e.g. `00101` or `310410`.
+pub fn plmn_key(mcc: &str, mnc: &str) -> String {
+    format!("{}{}", mcc, mnc)
+}
+
+/// An S-NSSAI without SD matches only the slices without SD.
+fn snssai_matches(served: &models::Snssai, wanted: &models::Snssai) -> bool {
+    served.sst == wanted.sst
+        && match (&served.sd, &wanted.sd) {
+            (Some(served), Some(wanted)) => served.eq_ignore_ascii_case(wanted),
+            (None, None) => true,
+            _ => false,
+        }
+}
+
+#[derive(Debug, Clone)]
+struct Provisioned {
+    subscriber: Subscriber,
+    last_modified: DateTime<Utc>,
+}
+
+/// Change of the data of a subscriber, as reported to the store watchers.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SubscriberEvent {
+    Provisioned(Subscriber),
+    Modified { old: Subscriber, new: Subscriber },
+    Removed(Subscriber),
+}
+
+impl SubscriberEvent {
+    pub fn supi(&self) -> &str {
+        match self {
+            SubscriberEvent::Provisioned(subscriber)
+            | SubscriberEvent::Modified {
+                new: subscriber, ..
+            }
+            | SubscriberEvent::Removed(subscriber) => &subscriber.supi,
+        }
+    }
+}
+
+/// Thread-safe handle to the subscribers, keyed by SUPI.
+#[derive(Debug, Clone, Default)]
+pub struct SubscriberStore {
+    subscribers: Arc<RwLock<HashMap<String, Provisioned>>>,
+    watchers: Arc<Mutex<Vec<mpsc::UnboundedSender<SubscriberEvent>>>>,
+}
+
+impl SubscriberStore {
+    pub fn new() -> SubscriberStore {
+        SubscriberStore::default()
+    }
+
+    pub fn from_subscribers(subscribers: Vec<Subscriber>) -> SubscriberStore {
+        let store = SubscriberStore::new();
+        for subscriber in subscribers {
+            store.insert(subscriber);
+        }
+        store
+    }
+
+    /// Reads a provisioning file, JSON if its extension is `.json` and YAML
+    /// otherwise.
+    pub fn read<P: AsRef<Path>>(path: P) -> std::io::Result<Vec<Subscriber>> {
+        let path = path.as_ref();
+        let data = std::fs::read(path)?;
+        let invalid = |e: String| std::io::Error::new(std::io::ErrorKind::InvalidData, e);
+
+        if path.extension().map_or(false, |e| e == "json") {
+            serde_json::from_slice(&data).map_err(|e| invalid(e.to_string()))
+        } else {
+            serde_yaml::from_slice(&data).map_err(|e| invalid(e.to_string()))
+        }
+    }
+
+    /// Loads the subscribers of a provisioning file.
+    pub fn load<P: AsRef<Path>>(path: P) -> std::io::Result<SubscriberStore> {
+        Ok(SubscriberStore::from_subscribers(SubscriberStore::read(
+            path,
+        )?))
+    }
+
+    /// Provisions a subscriber, returning the one it replaces. The data of
+    /// the subscriber is modified only if it differs from the replaced one.
+    pub fn insert(&self, subscriber: Subscriber) -> Option<Subscriber> {
+        let (replaced, event) = {
+            let mut subscribers = self.subscribers.write().expect("subscriber lock poisoned");
+            let (last_modified, event) = match subscribers.get(&subscriber.supi) {
+                Some(provisioned) if provisioned.subscriber == subscriber => {
+                    (provisioned.last_modified, None)
+                }
+                Some(provisioned) => (
+                    Utc::now(),
+                    Some(SubscriberEvent::Modified {
+                        old: provisioned.subscriber.clone(),
+                        new: subscriber.clone(),
+                    }),
+                ),
+                None => (
+                    Utc::now(),
+                    Some(SubscriberEvent::Provisioned(subscriber.clone())),
+                ),
+            };
+            let replaced = subscribers
+                .insert(
+                    subscriber.supi.clone(),
+                    Provisioned {
+                        subscriber,
+                        last_modified,
+                    },
+                )
+                .map(|p| p.subscriber);
+            (replaced, event)
+        };
+
+        if let Some(event) = event {
+            self.notify(event);
+        }
+        replaced
+    }
+
+    /// Modifies the data of a subscriber in place, returning what the
+    /// modification returns, or `None` for an unknown subscriber. No other
+    /// change of the subscriber can come in between, as with a `get`
+    /// followed by an `insert`. The SUPI is kept as is.
+    pub fn update<F, T>(&self, supi: &str, modify: F) -> Option<T>
+    where
+        F: FnOnce(&mut Subscriber) -> T,
+    {
+        let (result, event) = {
+            let mut subscribers = self.subscribers.write().expect("subscriber lock poisoned");
+            let provisioned = subscribers.get_mut(supi)?;
+            let mut subscriber = provisioned.subscriber.clone();
+            let result = modify(&mut subscriber);
+            subscriber.supi = provisioned.subscriber.supi.clone();
+            let event = if subscriber != provisioned.subscriber {
+                provisioned.last_modified = Utc::now();
+                let old = std::mem::replace(&mut provisioned.subscriber, subscriber.clone());
+                Some(SubscriberEvent::Modified {
+                    old,
+                    new: subscriber,
+                })
+            } else {
+                None
+            };
+            (result, event)
+        };
+
+        if let Some(event) = event {
+            self.notify(event);
+        }
+        Some(result)
+    }
+
+    pub fn remove(&self, supi: &str) -> Option<Subscriber> {
+        let removed = self
+            .subscribers
+            .write()
+            .expect("subscriber lock poisoned")
+            .remove(supi)
+            .map(|p| p.subscriber);
+        if let Some(subscriber) = &removed {
+            self.notify(SubscriberEvent::Removed(subscriber.clone()));
+        }
+        removed
+    }
+
+    /// Provisions exactly the given subscribers: the others are removed, and
+    /// those whose data did not change are left untouched. Returns how many
+    /// subscribers were provisioned, modified or removed.
+    pub fn replace(&self, subscribers: Vec<Subscriber>) -> usize {
+        let supis: HashSet<String> = subscribers.iter().map(|s| s.supi.clone()).collect();
+        let mut changes = 0;
+        for supi in self.supis() {
+            if !supis.contains(&supi) && self.remove(&supi).is_some() {
+                changes += 1;
+            }
+        }
+        for subscriber in subscribers {
+            if self.insert(subscriber.clone()).as_ref() != Some(&subscriber) {
+                changes += 1;
+            }
+        }
+        changes
+    }
+
+    /// Returns a stream of the changes made to the subscribers from now on.
+    /// The stream ends when the store is dropped.
+    pub fn watch(&self) -> mpsc::UnboundedReceiver<SubscriberEvent> {
+        let (sender, receiver) = mpsc::unbounded();
+        self.watchers
+            .lock()
+            .expect("subscriber lock poisoned")
+            .push(sender);
+        receiver
+    }
+
+    /// Sends an event to the watchers, forgetting those that went away.
+    fn notify(&self, event: SubscriberEvent) {
+        self.watchers
+            .lock()
+            .expect("subscriber lock poisoned")
+            .retain(|watcher| watcher.unbounded_send(event.clone()).is_ok());
+    }
+
+    pub fn get(&self, supi: &str) -> Option<Subscriber> {
+        self.subscribers
+            .read()
+            .expect("subscriber lock poisoned")
+            .get(supi)
+            .map(|p| p.subscriber.clone())
+    }
+
+    /// Looks a subscriber up by SUPI, or by one of its GPSIs.
+    pub fn find(&self, ue_id: &str) -> Option<Subscriber> {
+        let subscribers = self.subscribers.read().expect("subscriber lock poisoned");
+        subscribers
+            .get(ue_id)
+            .or_else(|| {
+                subscribers
+                    .values()
+                    .find(|p| p.subscriber.is_identified_by(ue_id))
+            })
+            .map(|p| p.subscriber.clone())
+    }
+
+    /// The members of the group with the given external and internal group
+    /// identifiers, at least one of them being given, and the identity of
+    /// the group. `None` if the group has no members.
+    pub fn group(
+        &self,
+        ext_group_id: Option<&str>,
+        int_group_id: Option<&str>,
+    ) -> Option<(GroupIdentity, Vec<Subscriber>)> {
+        if ext_group_id.is_none() && int_group_id.is_none() {
+            return None;
+        }
+        let matches = |group: &GroupIdentity| {
+            ext_group_id.map_or(true, |id| group.ext_group_id.as_deref() == Some(id))
+                && int_group_id.map_or(true, |id| group.int_group_id == id)
+        };
+
+        let mut identity = None;
+        let mut members = Vec::new();
+        for provisioned in self
+            .subscribers
+            .read()
+            .expect("subscriber lock poisoned")
+            .values()
+        {
+            if let Some(group) = provisioned.subscriber.groups.iter().find(|g| matches(g)) {
+                identity.get_or_insert_with(|| group.clone());
+                members.push(provisioned.subscriber.clone());
+            }
+        }
+        members.sort_by(|a, b| a.supi.cmp(&b.supi));
+        identity.map(|identity| (identity, members))
+    }
+
+    /// When the data of a subscriber was last provisioned.
+    pub fn last_modified(&self, supi: &str) -> Option<DateTime<Utc>> {
+        self.subscribers
+            .read()
+            .expect("subscriber lock poisoned")
+            .get(supi)
+            .map(|p| p.last_modified)
+    }
+
+    pub fn supis(&self) -> Vec<String> {
+        self.subscribers
+            .read()
+            .expect("subscriber lock poisoned")
+            .keys()
+            .cloned()
+            .collect()
+    }
+
+    pub fn len(&self) -> usize {
+        self.subscribers
+            .read()
+            .expect("subscriber lock poisoned")
+            .len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// The problem of a request about a subscriber that is not provisioned.
+pub fn user_not_found(supi: &str) -> models::ProblemDetails {
+    let mut problem = models::ProblemDetails::new();
+    problem.status = Some(404);
+    problem.cause = Some("USER_NOT_FOUND".to_string());
+    problem.detail = Some(format!("Unknown subscriber {}", supi));
+    problem
+}
+
+/// The problem of a request about a data set the subscriber is not
+/// provisioned with.
+pub fn data_not_found(supi: &str) -> models::ProblemDetails {
+    let mut problem = models::ProblemDetails::new();
+    problem.status = Some(404);
+    problem.cause = Some("DATA_NOT_FOUND".to_string());
+    problem.detail = Some(format!("No such data for subscriber {}", supi));
+    problem
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subscriber(supi: &str) -> Subscriber {
+        serde_json::from_value(serde_json::json!({ "supi": supi })).unwrap()
+    }
+
+    #[test]
+    fn update_modifies_in_place() {
+        let store = SubscriberStore::from_subscribers(vec![subscriber("imsi-001011234567895")]);
+        let mut events = store.watch();
+
+        let gpsis = store.update("imsi-001011234567895", |subscriber| {
+            subscriber.gpsis.push("msisdn-33600000001".to_string());
+            subscriber.gpsis.len()
+        });
+        assert_eq!(gpsis, Some(1));
+        let updated = store.get("imsi-001011234567895").unwrap();
+        assert_eq!(updated.gpsis, vec!["msisdn-33600000001".to_string()]);
+        assert_eq!(
+            events.try_next().unwrap(),
+            Some(SubscriberEvent::Modified {
+                old: subscriber("imsi-001011234567895"),
+                new: updated,
+            })
+        );
+    }
+
+    #[test]
+    fn update_keeps_the_supi() {
+        let store = SubscriberStore::from_subscribers(vec![subscriber("imsi-001011234567895")]);
+        let mut events = store.watch();
+
+        store.update("imsi-001011234567895", |subscriber| {
+            subscriber.supi = "imsi-001011234567896".to_string();
+        });
+        assert_eq!(store.supis(), vec!["imsi-001011234567895".to_string()]);
+        // Nothing else changed
+        assert!(events.try_next().is_err());
+    }
+
+    #[test]
+    fn update_of_unknown_subscriber() {
+        let store = SubscriberStore::new();
+        assert_eq!(store.update("imsi-001011234567895", |_| ()), None);
+        assert!(store.get("imsi-001011234567895").is_none());
+    }
+}
@@ -0,0 +1,416 @@
+//! SDM subscriptions held by the UDM.
+//!
+//! The store keeps the subscriptions created through the Nudm_SDM API and
+//! turns each change of the subscriber data, or of the shared data, into the
+//! modification notifications owed to the subscribers.
+
+use chrono::{Duration, Utc};
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
+use crate::models::{
+    ChangeItem, ChangeType, ModificationNotification, NotifyItem, SdmSubsModification,
+    SdmSubscription,
+};
+use crate::shared_data::SharedDataEvent;
+use crate::subscribers::SubscriberEvent;
+
+/// Longest validity granted to a subscription, in seconds. Subscriptions
+/// asking for a longer one, or none, expire after it.
+pub const MAX_VALIDITY: i64 = 24 * 3600;
+
+#[derive(Debug, Clone)]
+struct Subscription {
+    /// The UE the subscription is about, `None` for shared data.
+    ue_id: Option<String>,
+    subscription: SdmSubscription,
+}
+
+/// Thread-safe handle to the SDM subscriptions, keyed by subscription id.
+#[derive(Debug, Clone, Default)]
+pub struct SdmSubscriptionStore {
+    subscriptions: Arc<RwLock<HashMap<String, Subscription>>>,
+}
+
+impl SdmSubscriptionStore {
+    pub fn new() -> SdmSubscriptionStore {
+        SdmSubscriptionStore::default()
+    }
+
+    /// Stores a subscription to the data of a UE, or to shared data if
+    /// `ue_id` is `None`, under a new id and returns it as stored.
+    pub fn create(
+        &self,
+        ue_id: Option<&str>,
+        mut subscription: SdmSubscription,
+    ) -> SdmSubscription {
+        let subscription_id = uuid::Uuid::new_v4().simple().to_string();
+        subscription.subscription_id = Some(subscription_id.clone());
+        subscription.expires = Some(capped(subscription.expires));
+        self.subscriptions
+            .write()
+            .expect("subscription lock poisoned")
+            .insert(
+                subscription_id,
+                Subscription {
+                    ue_id: ue_id.map(str::to_string),
+                    subscription: subscription.clone(),
+                },
+            );
+        subscription
+    }
+
+    /// Applies a modification to the subscription of a UE, or to a shared
+    /// data subscription if `ue_id` is `None`, and returns it as stored.
+    pub fn modify(
+        &self,
+        ue_id: Option<&str>,
+        subscription_id: &str,
+        modification: SdmSubsModification,
+    ) -> Option<SdmSubscription> {
+        let mut subscriptions = self
+            .subscriptions
+            .write()
+            .expect("subscription lock poisoned");
+        let stored = subscriptions
+            .get_mut(subscription_id)
+            .filter(|s| s.ue_id.as_deref() == ue_id)?;
+        if let Some(expires) = modification.expires {
+            stored.subscription.expires = Some(capped(Some(expires)));
+        }
+        if let Some(monitored_resource_uris) = modification.monitored_resource_uris {
+            stored.subscription.monitored_resource_uris = monitored_resource_uris;
+        }
+        Some(stored.subscription.clone())
+    }
+
+    pub fn remove(&self, ue_id: Option<&str>, subscription_id: &str) -> Option<SdmSubscription> {
+        let mut subscriptions = self
+            .subscriptions
+            .write()
+            .expect("subscription lock poisoned");
+        match subscriptions.get(subscription_id) {
+            Some(stored) if stored.ue_id.as_deref() == ue_id => subscriptions
+                .remove(subscription_id)
+                .map(|s| s.subscription),
+            _ => None,
+        }
+    }
+
+    pub fn get(&self, ue_id: Option<&str>, subscription_id: &str) -> Option<SdmSubscription> {
+        self.subscriptions
+            .read()
+            .expect("subscription lock poisoned")
+            .get(subscription_id)
+            .filter(|s| s.ue_id.as_deref() == ue_id)
+            .map(|s| s.subscription.clone())
+    }
+
+    pub fn len(&self) -> usize {
+        self.subscriptions
+            .read()
+            .expect("subscription lock poisoned")
+            .len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Removes and returns the subscriptions whose expiry time has passed.
+    pub fn expire(&self) -> Vec<SdmSubscription> {
+        let now = Utc::now();
+        let mut expired = Vec::new();
+        self.subscriptions
+            .write()
+            .expect("subscription lock poisoned")
+            .retain(|_, stored| match stored.subscription.expires {
+                Some(expires) if expires <= now => {
+                    expired.push(stored.subscription.clone());
+                    false
+                }
+                _ => true,
+            });
+        expired
+    }
+
+    /// Builds the notifications for a change of subscriber data, as pairs of
+    /// callback reference and payload. A subscription is notified of the
+    /// monitored resources of the subscriber, named by its SUPI or one of its
+    /// GPSIs, whose content changed.
+    pub fn notifications(
+        &self,
+        event: &SubscriberEvent,
+    ) -> Vec<(String, ModificationNotification)> {
+        let (old, new) = match event {
+            SubscriberEvent::Provisioned(subscriber) => (None, Some(subscriber)),
+            SubscriberEvent::Modified { old, new } => (Some(old), Some(new)),
+            SubscriberEvent::Removed(subscriber) => (Some(subscriber), None),
+        };
+        let identifies = |ue_id: &str| {
+            old.iter()
+                .chain(new.iter())
+                .any(|s| s.is_identified_by(ue_id))
+        };
+
+        self.changes(|resource| {
+            let resource = match resource {
+                MonitoredResource::Ue(ue_id, resource) if identifies(ue_id) => resource,
+                _ => return None,
+            };
+            Some((
+                old.and_then(|subscriber| subscriber.resource(resource)),
+                new.and_then(|subscriber| subscriber.resource(resource)),
+            ))
+        })
+    }
+
+    /// Builds the notifications for a change of shared data, as pairs of
+    /// callback reference and payload: a subscription is notified if it
+    /// monitors `shared-data/{sharedDataId}`.
+    pub fn shared_data_notifications(
+        &self,
+        event: &SharedDataEvent,
+    ) -> Vec<(String, ModificationNotification)> {
+        let (old, new) = match event {
+            SharedDataEvent::Provisioned(data) => (None, Some(data)),
+            SharedDataEvent::Modified { old, new } => (Some(old), Some(new)),
+            SharedDataEvent::Removed(data) => (Some(data), None),
+        };
+        let value = |data: Option<&crate::models::SharedData>| {
+            data.and_then(|data| serde_json::to_value(data).ok())
+        };
+
+        self.changes(|resource| match resource {
+            MonitoredResource::SharedData(id) if id == event.shared_data_id() => {
+                Some((value(old), value(new)))
+            }
+            _ => None,
+        })
+    }
+
+    /// The notifications of the subscriptions monitoring changed resources.
+    /// `values` gives the content of a monitored resource before and after
+    /// the change, or `None` if the change is not about it.
+    fn changes<F>(&self, values: F) -> Vec<(String, ModificationNotification)>
+    where
+        F: Fn(MonitoredResource) -> Option<(Option<serde_json::Value>, Option<serde_json::Value>)>,
+    {
+        let now = Utc::now();
+
+        self.subscriptions
+            .read()
+            .expect("subscription lock poisoned")
+            .values()
+            .map(|stored| &stored.subscription)
+            .filter(|s| s.expires.map(|t| t > now).unwrap_or(true))
+            .filter_map(|s| {
+                let notify_items: Vec<NotifyItem> = s
+                    .monitored_resource_uris
+                    .iter()
+                    .filter_map(|uri| {
+                        let (orig_value, new_value) = values(monitored_resource(uri)?)?;
+                        if orig_value == new_value {
+                            return None;
+                        }
+                        let op = match (&orig_value, &new_value) {
+                            (None, _) => "ADD",
+                            (_, None) => "REMOVE",
+                            _ => "REPLACE",
+                        };
+                        let mut change =
+                            ChangeItem::new(ChangeType::new(op.to_string()), String::new());
+                        change.orig_value = orig_value;
+                        change.new_value = new_value;
+                        Some(NotifyItem::new(uri.to_string(), vec![change]))
+                    })
+                    .collect();
+                if notify_items.is_empty() {
+                    None
+                } else {
+                    Some((
+                        s.callback_reference.clone(),
+                        ModificationNotification::new(notify_items),
+                    ))
+                }
+            })
+            .collect()
+    }
+}
+
+/// The expiry time granted for the one asked for.
+fn capped(expires: Option<chrono::DateTime<Utc>>) -> chrono::DateTime<Utc> {
+    let max = Utc::now() + Duration::seconds(MAX_VALIDITY);
+    match expires {
+        Some(expires) if expires < max => expires,
+        _ => max,
+    }
+}
+
+/// A resource a subscription monitors.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum MonitoredResource<'a> {
+    /// The UE id and the path of the resource below it
+    Ue(&'a str, &'a str),
+    /// The shared data id
+    SharedData(&'a str),
+}
+
+/// Reads a monitored resource URI, absolute or relative to the apiRoot or to
+/// the API base path: `/nudm-sdm/v2/imsi-001011234567895/am-data` is
+/// `am-data` of `imsi-001011234567895`, `/nudm-sdm/v2/shared-data/{id}` is
+/// shared data.
+fn monitored_resource(uri: &str) -> Option<MonitoredResource<'_>> {
+    let path = uri.split(['?', '#']).next()?;
+    let path = match path.find(crate::BASE_PATH) {
+        Some(i) => &path[i + crate::BASE_PATH.len()..],
+        None => path,
+    };
+    match path.trim_start_matches('/').split_once('/')? {
+        ("shared-data", id) => Some(MonitoredResource::SharedData(id)),
+        (ue_id, resource) => Some(MonitoredResource::Ue(ue_id, resource)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AccessAndMobilitySubscriptionData, SharedData, Uri};
+    use crate::subscribers::Subscriber;
+
+    const SUPI: &str = "imsi-001011234567895";
+
+    fn subscription(uris: &[&str]) -> SdmSubscription {
+        SdmSubscription::new(
+            uuid::Uuid::new_v4(),
+            "http://127.0.0.1:9093/notify".to_string(),
+            uris.iter().map(|uri| Uri::from(uri.to_string())).collect(),
+        )
+    }
+
+    fn subscriber(supported_features: Option<&str>) -> Subscriber {
+        let mut am_data = AccessAndMobilitySubscriptionData::new();
+        am_data.supported_features = supported_features.map(str::to_string);
+        let mut subscriber = Subscriber::new(SUPI.to_string());
+        subscriber.am_data = Some(am_data);
+        subscriber
+    }
+
+    #[test]
+    fn create_assigns_an_id_and_caps_the_expiry() {
+        let store = SdmSubscriptionStore::new();
+        let mut asked = subscription(&["/nudm-sdm/v2/imsi-001011234567895/am-data"]);
+        asked.expires = Some(Utc::now() + Duration::seconds(2 * MAX_VALIDITY));
+
+        let created = store.create(Some(SUPI), asked);
+        let id = created.subscription_id.clone().expect("a subscription id");
+        assert!(created.expires.unwrap() <= Utc::now() + Duration::seconds(MAX_VALIDITY));
+        assert_eq!(store.get(Some(SUPI), &id), Some(created));
+        assert_eq!(store.get(None, &id), None);
+        assert_eq!(store.len(), 1);
+    }
+
+    #[test]
+    fn modify_only_applies_to_the_subscriptions_of_the_ue() {
+        let store = SdmSubscriptionStore::new();
+        let created = store.create(Some(SUPI), subscription(&["am-data"]));
+        let id = created.subscription_id.unwrap();
+        let expires = Utc::now() + Duration::seconds(60);
+        let mut modification = SdmSubsModification::new();
+        modification.expires = Some(expires);
+        modification.monitored_resource_uris =
+            Some(vec![Uri::from("imsi-001011234567895/nssai".to_string())]);
+
+        assert_eq!(
+            store.modify(Some("imsi-001010000000001"), &id, modification.clone()),
+            None
+        );
+        let modified = store.modify(Some(SUPI), &id, modification).unwrap();
+        assert_eq!(modified.expires, Some(expires));
+        assert_eq!(
+            modified.monitored_resource_uris,
+            vec![Uri::from("imsi-001011234567895/nssai".to_string())]
+        );
+        assert_eq!(
+            store.modify(Some(SUPI), "unknown", SdmSubsModification::new()),
+            None
+        );
+    }
+
+    #[test]
+    fn expire_removes_the_expired_subscriptions() {
+        let store = SdmSubscriptionStore::new();
+        let mut expired = subscription(&["am-data"]);
+        expired.expires = Some(Utc::now() - Duration::seconds(1));
+        let expired = store.create(Some(SUPI), expired);
+        let live = store.create(Some(SUPI), subscription(&["am-data"]));
+
+        assert_eq!(store.expire(), vec![expired]);
+        assert_eq!(store.len(), 1);
+        assert!(store
+            .get(Some(SUPI), live.subscription_id.as_ref().unwrap())
+            .is_some());
+        assert!(store.expire().is_empty());
+    }
+
+    #[test]
+    fn notifications_carry_the_changed_resources_of_the_ue() {
+        let store = SdmSubscriptionStore::new();
+        store.create(
+            Some(SUPI),
+            subscription(&[
+                "http://udm/nudm-sdm/v2/imsi-001011234567895/am-data",
+                "/nudm-sdm/v2/imsi-001011234567895/nssai",
+                "/nudm-sdm/v2/imsi-001010000000001/am-data",
+            ]),
+        );
+        let event = SubscriberEvent::Modified {
+            old: subscriber(None),
+            new: subscriber(Some("ab")),
+        };
+
+        let notifications = store.notifications(&event);
+        assert_eq!(notifications.len(), 1);
+        let (callback_reference, notification) = &notifications[0];
+        assert_eq!(callback_reference, "http://127.0.0.1:9093/notify");
+        assert_eq!(notification.notify_items.len(), 1);
+        let item = &notification.notify_items[0];
+        assert_eq!(
+            item.resource_id,
+            "http://udm/nudm-sdm/v2/imsi-001011234567895/am-data"
+        );
+        assert_eq!(item.changes[0].op, ChangeType::new("REPLACE".to_string()));
+        assert_eq!(
+            item.changes[0].new_value.as_ref().unwrap()["supportedFeatures"],
+            "ab"
+        );
+
+        let removed = store.notifications(&SubscriberEvent::Removed(subscriber(None)));
+        let op = &removed[0].1.notify_items[0].changes[0].op;
+        assert_eq!(*op, ChangeType::new("REMOVE".to_string()));
+    }
+
+    #[test]
+    fn shared_data_is_not_taken_for_a_ue() {
+        let store = SdmSubscriptionStore::new();
+        store.create(None, subscription(&["/nudm-sdm/v2/shared-data/am-1"]));
+        // Not even for a UE that would be identified as such
+        let mut subscriber = subscriber(None);
+        subscriber.gpsis = vec!["shared-data".to_string()];
+        let event = SubscriberEvent::Provisioned(subscriber);
+        assert!(store.notifications(&event).is_empty());
+
+        let mut data = SharedData::new("am-1".to_string());
+        data.shared_am_data = Some(AccessAndMobilitySubscriptionData::new());
+        let notifications =
+            store.shared_data_notifications(&SharedDataEvent::Provisioned(data.clone()));
+        assert_eq!(notifications.len(), 1);
+        let item = &notifications[0].1.notify_items[0];
+        assert_eq!(item.resource_id, "/nudm-sdm/v2/shared-data/am-1");
+        assert_eq!(item.changes[0].op, ChangeType::new("ADD".to_string()));
+
+        data.shared_data_id = "am-2".to_string();
+        let event = SharedDataEvent::Provisioned(data);
+        assert!(store.shared_data_notifications(&event).is_empty());
+    }
+}
@@ -0,0 +1,468 @@
+//! De-concealment of the Subscription Concealed Identifiers (SUCI) sent by
+//! the UEs, as done by the SIDF of the UDM.
+//!
+//! A SUCI is taken in the form of TS 29.503 clause 6.1.6.3.2, e.g.
+//! `suci-0-001-01-0000-1-1-<scheme output>`. The null scheme and the ECIES
+//! profiles A (X25519) and B (secp256r1) of TS 33.501 annex C.3 are
+//! supported, the ephemeral public keys of profile B being compressed or
+//! not; the ECIES schemes use the private keys of the home network,
+//! read from a YAML or JSON file of [`HomeNetworkKey`] entries.
+
+use aes::cipher::{KeyIvInit, StreamCipher};
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::convert::TryInto;
+use std::path::Path;
+
+use crate::models;
+
+type Aes128Ctr = ctr::Ctr128BE<aes::Aes128>;
+
+/// Length of the MAC tag of the ECIES scheme outputs.
+const MAC_TAG_LEN: usize = 8;
+
+/// Protection scheme of a SUCI (TS 33.501 annex C.1).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ProtectionScheme {
+    #[serde(rename = "null")]
+    Null,
+    #[serde(rename = "profileA")]
+    ProfileA,
+    #[serde(rename = "profileB")]
+    ProfileB,
+}
+
+impl ProtectionScheme {
+    fn from_id(id: &str) -> Option<ProtectionScheme> {
+        match id {
+            "0" => Some(ProtectionScheme::Null),
+            "1" => Some(ProtectionScheme::ProfileA),
+            "2" => Some(ProtectionScheme::ProfileB),
+            _ => None,
+        }
+    }
+}
+
+/// A SUCI, as parsed from its string form.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Suci {
+    /// The home network identifier of an IMSI, `None` for a NAI.
+    pub plmn: Option<(String, String)>,
+    /// The realm of a NAI, `None` for an IMSI.
+    pub realm: Option<String>,
+    pub routing_indicator: String,
+    pub protection_scheme: ProtectionScheme,
+    pub home_network_public_key_id: u8,
+    /// The MSIN or username for the null scheme, the hexadecimal ECIES
+    /// output otherwise.
+    pub scheme_output: String,
+}
+
+impl std::str::FromStr for Suci {
+    type Err = SuciError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || SuciError::Invalid(s.to_string());
+        let rest = s.strip_prefix("suci-").ok_or_else(invalid)?;
+        let (supi_type, rest) = rest.split_once('-').ok_or_else(invalid)?;
+
+        // The realm of a NAI may hold dashes: the fields are taken from the end
+        let mut fields = rest.rsplitn(5, '-');
+        let scheme_output = fields.next().ok_or_else(invalid)?;
+        let key_id = fields.next().ok_or_else(invalid)?;
+        let scheme_id = fields.next().ok_or_else(invalid)?;
+        let routing_indicator = fields.next().ok_or_else(invalid)?;
+        let home_network_id = fields.next().ok_or_else(invalid)?;
+
+        let (plmn, realm) = match supi_type {
+            "0" => {
+                let (mcc, mnc) = home_network_id.split_once('-').ok_or_else(invalid)?;
+                let digits = |s: &str, lengths: &[usize]| {
+                    lengths.contains(&s.len()) && s.bytes().all(|b| b.is_ascii_digit())
+                };
+                if !digits(mcc, &[3]) || !digits(mnc, &[2, 3]) {
+                    return Err(invalid());
+                }
+                (Some((mcc.to_string(), mnc.to_string())), None)
+            }
+            "1" if !home_network_id.is_empty() => (None, Some(home_network_id.to_string())),
+            _ => return Err(invalid()),
+        };
+
+        Ok(Suci {
+            plmn,
+            realm,
+            routing_indicator: routing_indicator.to_string(),
+            protection_scheme: ProtectionScheme::from_id(scheme_id).ok_or_else(invalid)?,
+            home_network_public_key_id: key_id.parse().map_err(|_| invalid())?,
+            scheme_output: scheme_output.to_string(),
+        })
+    }
+}
+
+impl Suci {
+    /// The SUPI concealed by a SUCI of the null scheme. The other schemes
+    /// need the private key of the home network: see
+    /// [`HomeNetworkKeys::deconceal`].
+    pub fn supi(&self, msin_or_username: &str) -> String {
+        match (&self.plmn, &self.realm) {
+            (Some((mcc, mnc)), _) => format!("imsi-{}{}{}", mcc, mnc, msin_or_username),
+            (None, Some(realm)) => format!("nai-{}@{}", msin_or_username, realm),
+            (None, None) => unreachable!("a SUCI has a home network identifier"),
+        }
+    }
+}
+
+/// A private key of the home network.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct HomeNetworkKey {
+    /// The Home Network Public Key Identifier the UEs conceal with
+    #[serde(rename = "id")]
+    pub id: u8,
+
+    #[serde(rename = "scheme")]
+    pub scheme: ProtectionScheme,
+
+    /// Hexadecimal private key: 32 bytes for both profiles
+    #[serde(rename = "privateKey")]
+    pub private_key: String,
+}
+
+/// The private keys of the home network, by identifier and scheme.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HomeNetworkKeys {
+    keys: Vec<HomeNetworkKey>,
+}
+
+impl HomeNetworkKeys {
+    pub fn new(keys: Vec<HomeNetworkKey>) -> HomeNetworkKeys {
+        HomeNetworkKeys { keys }
+    }
+
+    /// Reads a key file, JSON if its extension is `.json` and YAML otherwise.
+    pub fn load<P: AsRef<Path>>(path: P) -> std::io::Result<HomeNetworkKeys> {
+        let path = path.as_ref();
+        let data = std::fs::read(path)?;
+        let invalid = |e: String| std::io::Error::new(std::io::ErrorKind::InvalidData, e);
+
+        let keys = if path.extension().map_or(false, |e| e == "json") {
+            serde_json::from_slice(&data).map_err(|e| invalid(e.to_string()))?
+        } else {
+            serde_yaml::from_slice(&data).map_err(|e| invalid(e.to_string()))?
+        };
+        Ok(HomeNetworkKeys::new(keys))
+    }
+
+    /// The SUPI a UE identifier stands for: the SUPI concealed by a SUCI,
+    /// or else the identifier itself.
+    pub fn resolve(&self, ue_id: &str) -> Result<String, SuciError> {
+        if ue_id.starts_with("suci-") {
+            self.deconceal(&ue_id.parse()?)
+        } else {
+            Ok(ue_id.to_string())
+        }
+    }
+
+    /// The SUPI concealed by a SUCI.
+    pub fn deconceal(&self, suci: &Suci) -> Result<String, SuciError> {
+        if suci.protection_scheme == ProtectionScheme::Null {
+            return Ok(suci.supi(&suci.scheme_output));
+        }
+
+        let key = self
+            .keys
+            .iter()
+            .find(|key| {
+                key.id == suci.home_network_public_key_id && key.scheme == suci.protection_scheme
+            })
+            .ok_or(SuciError::UnknownKey(suci.home_network_public_key_id))?;
+        let private_key =
+            hex::decode(&key.private_key).map_err(|_| SuciError::InvalidKey(key.id))?;
+        let output = hex::decode(&suci.scheme_output).map_err(|_| SuciError::Decryption)?;
+
+        let plaintext = match suci.protection_scheme {
+            ProtectionScheme::ProfileA => decrypt(&output, 32, |public_key| {
+                let private_key: [u8; 32] = private_key.as_slice().try_into().ok()?;
+                let public_key: [u8; 32] = public_key.try_into().ok()?;
+                let secret = x25519_dalek::StaticSecret::from(private_key);
+                let shared = secret.diffie_hellman(&x25519_dalek::PublicKey::from(public_key));
+                Some(shared.as_bytes().to_vec())
+            }),
+            // The ephemeral public key is compressed, or else uncompressed
+            ProtectionScheme::ProfileB => {
+                let public_key_len = match output.first() {
+                    Some(0x04) => 65,
+                    _ => 33,
+                };
+                decrypt(&output, public_key_len, |public_key| {
+                    let secret = p256::SecretKey::from_slice(&private_key).ok()?;
+                    let public_key = p256::PublicKey::from_sec1_bytes(public_key).ok()?;
+                    let shared = p256::ecdh::diffie_hellman(
+                        secret.to_nonzero_scalar(),
+                        public_key.as_affine(),
+                    );
+                    Some(shared.raw_secret_bytes().to_vec())
+                })
+            }
+            ProtectionScheme::Null => unreachable!("the null scheme needs no decryption"),
+        }?;
+
+        match suci.plmn {
+            // The MSIN is BCD coded, the first digit in the low nibble
+            Some(_) => {
+                let mut msin = String::with_capacity(plaintext.len() * 2);
+                for octet in plaintext {
+                    for digit in [octet & 0x0f, octet >> 4] {
+                        match digit {
+                            0..=9 => msin.push(char::from(b'0' + digit)),
+                            0x0f => break,
+                            _ => return Err(SuciError::Decryption),
+                        }
+                    }
+                }
+                Ok(suci.supi(&msin))
+            }
+            None => {
+                let username = String::from_utf8(plaintext).map_err(|_| SuciError::Decryption)?;
+                Ok(suci.supi(&username))
+            }
+        }
+    }
+}
+
+/// Decrypts an ECIES scheme output: the ephemeral public key of the UE, of
+/// `public_key_len` bytes, the ciphertext and the MAC tag. `agree` computes
+/// the shared secret with the ephemeral public key.
+fn decrypt<F>(output: &[u8], public_key_len: usize, agree: F) -> Result<Vec<u8>, SuciError>
+where
+    F: FnOnce(&[u8]) -> Option<Vec<u8>>,
+{
+    if output.len() <= public_key_len + MAC_TAG_LEN {
+        return Err(SuciError::Decryption);
+    }
+    let (public_key, rest) = output.split_at(public_key_len);
+    let (ciphertext, tag) = rest.split_at(rest.len() - MAC_TAG_LEN);
+
+    let shared = agree(public_key).ok_or(SuciError::Decryption)?;
+    let keys = kdf(&shared, public_key, 64);
+    let (enc_key, rest) = keys.split_at(16);
+    let (icb, mac_key) = rest.split_at(16);
+
+    let mut mac = Hmac::<Sha256>::new_from_slice(mac_key).expect("HMAC takes any key length");
+    mac.update(ciphertext);
+    mac.verify_truncated_left(tag)
+        .map_err(|_| SuciError::Decryption)?;
+
+    let mut plaintext = ciphertext.to_vec();
+    Aes128Ctr::new(enc_key.into(), icb.into()).apply_keystream(&mut plaintext);
+    Ok(plaintext)
+}
+
+/// ANSI X9.63 key derivation with SHA-256.
+fn kdf(shared: &[u8], shared_info: &[u8], len: usize) -> Vec<u8> {
+    let mut keys = Vec::with_capacity(len);
+    let mut counter: u32 = 1;
+    while keys.len() < len {
+        let mut hash = Sha256::new();
+        hash.update(shared);
+        hash.update(counter.to_be_bytes());
+        hash.update(shared_info);
+        keys.extend_from_slice(&hash.finalize());
+        counter += 1;
+    }
+    keys.truncate(len);
+    keys
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SuciError {
+    /// Not a SUCI
+    Invalid(String),
+    /// No private key with the Home Network Public Key Identifier
+    UnknownKey(u8),
+    /// A private key of the configuration is not valid
+    InvalidKey(u8),
+    /// The scheme output does not decrypt with the private key
+    Decryption,
+}
+
+impl std::fmt::Display for SuciError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SuciError::Invalid(suci) => write!(f, "Invalid SUCI {}", suci),
+            SuciError::UnknownKey(id) => write!(f, "Unknown home network public key {}", id),
+            SuciError::InvalidKey(id) => write!(f, "Invalid home network private key {}", id),
+            SuciError::Decryption => write!(f, "Unable to de-conceal the SUCI"),
+        }
+    }
+}
+
+impl std::error::Error for SuciError {}
+
+impl SuciError {
+    /// The problem of a request on a SUCI that cannot be de-concealed: the
+    /// UE identifier is taken as incorrect, whatever the reason.
+    pub fn problem(&self) -> models::ProblemDetails {
+        let mut problem = models::ProblemDetails::new();
+        problem.status = Some(400);
+        problem.cause = Some("MANDATORY_IE_INCORRECT".to_string());
+        problem.detail = Some(self.to_string());
+        problem
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // TS 33.501 annex C.4
+    const PROFILE_A_PRIVATE_KEY: &str =
+        "c53c22208b61860b06c62e5406a7b330c2b577aa5558981510d128247d38bd1d";
+    const PROFILE_A_OUTPUT: &str = "b2e92f836055a255837debf850b528997ce0201cb82adfe4be1f587d07d8457dcb02352410cddd9e730ef3fa87";
+    const PROFILE_B_PRIVATE_KEY: &str =
+        "f1ab1074477ebcc7f554ea1c5fc368b1616730155e0041ac447d6301975fecda";
+    const PROFILE_B_OUTPUT: &str = "039aab8376597021e855679a9778ea0b67396e68c66df32c0f41e9acca2da9b9d146a33fc2716ac7dae96aa30a4d";
+    /// The SUPI whose MSIN the scheme outputs of the annex conceal
+    const SUPI: &str = "imsi-00101001002086";
+
+    fn keys() -> HomeNetworkKeys {
+        HomeNetworkKeys::new(vec![
+            HomeNetworkKey {
+                id: 1,
+                scheme: ProtectionScheme::ProfileA,
+                private_key: PROFILE_A_PRIVATE_KEY.to_string(),
+            },
+            HomeNetworkKey {
+                id: 2,
+                scheme: ProtectionScheme::ProfileB,
+                private_key: PROFILE_B_PRIVATE_KEY.to_string(),
+            },
+        ])
+    }
+
+    fn scheme_output(scheme: u8, key_id: u8, output: &str) -> Suci {
+        format!("suci-0-001-01-0000-{}-{}-{}", scheme, key_id, output)
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn null_scheme() {
+        let suci = scheme_output(0, 0, "0001002086");
+        assert_eq!(
+            keys().deconceal(&suci),
+            Ok("imsi-001010001002086".to_string())
+        );
+
+        let nai: Suci = "suci-1-example.com-0000-0-0-user1".parse().unwrap();
+        assert_eq!(
+            keys().deconceal(&nai),
+            Ok("nai-user1@example.com".to_string())
+        );
+    }
+
+    #[test]
+    fn profile_a() {
+        let suci = scheme_output(1, 1, PROFILE_A_OUTPUT);
+        assert_eq!(keys().deconceal(&suci), Ok(SUPI.to_string()));
+    }
+
+    #[test]
+    fn profile_b() {
+        let suci = scheme_output(2, 2, PROFILE_B_OUTPUT);
+        assert_eq!(keys().deconceal(&suci), Ok(SUPI.to_string()));
+    }
+
+    #[test]
+    fn profile_b_uncompressed_public_key() {
+        use p256::elliptic_curve::sec1::ToEncodedPoint;
+
+        // The ephemeral key of the annex, uncompressed
+        let output = hex::decode(PROFILE_B_OUTPUT).unwrap();
+        let public_key = p256::PublicKey::from_sec1_bytes(&output[..33]).unwrap();
+        let public_key = public_key.to_encoded_point(false).as_bytes().to_vec();
+        assert_eq!(public_key.len(), 65);
+
+        let secret =
+            p256::SecretKey::from_slice(&hex::decode(PROFILE_B_PRIVATE_KEY).unwrap()).unwrap();
+        let shared = p256::ecdh::diffie_hellman(
+            secret.to_nonzero_scalar(),
+            p256::PublicKey::from_sec1_bytes(&public_key)
+                .unwrap()
+                .as_affine(),
+        );
+        let output = encrypt(
+            shared.raw_secret_bytes(),
+            &public_key,
+            &[0x00, 0x01, 0x20, 0x80, 0xf6],
+        );
+
+        let suci = scheme_output(2, 2, &hex::encode(output));
+        assert_eq!(keys().deconceal(&suci), Ok(SUPI.to_string()));
+    }
+
+    /// The ECIES scheme output of a plaintext, as a UE computes it.
+    fn encrypt(shared: &[u8], public_key: &[u8], plaintext: &[u8]) -> Vec<u8> {
+        let keys = kdf(shared, public_key, 64);
+        let (enc_key, rest) = keys.split_at(16);
+        let (icb, mac_key) = rest.split_at(16);
+
+        let mut ciphertext = plaintext.to_vec();
+        Aes128Ctr::new(enc_key.into(), icb.into()).apply_keystream(&mut ciphertext);
+        let mut mac = Hmac::<Sha256>::new_from_slice(mac_key).unwrap();
+        mac.update(&ciphertext);
+        let tag = mac.finalize().into_bytes();
+
+        [public_key, &ciphertext, &tag[..MAC_TAG_LEN]].concat()
+    }
+
+    #[test]
+    fn mac_mismatch() {
+        let mut output = hex::decode(PROFILE_A_OUTPUT).unwrap();
+        *output.last_mut().unwrap() ^= 0x01;
+        let suci = scheme_output(1, 1, &hex::encode(output));
+        assert_eq!(keys().deconceal(&suci), Err(SuciError::Decryption));
+
+        let mut output = hex::decode(PROFILE_B_OUTPUT).unwrap();
+        output[33] ^= 0x01;
+        let suci = scheme_output(2, 2, &hex::encode(output));
+        assert_eq!(keys().deconceal(&suci), Err(SuciError::Decryption));
+    }
+
+    #[test]
+    fn truncated_ciphertext() {
+        // Public key and MAC tag, without ciphertext
+        let output = hex::decode(PROFILE_A_OUTPUT).unwrap();
+        let truncated = [&output[..32], &output[output.len() - MAC_TAG_LEN..]].concat();
+        let suci = scheme_output(1, 1, &hex::encode(truncated));
+        assert_eq!(keys().deconceal(&suci), Err(SuciError::Decryption));
+
+        // Shorter than a public key
+        let suci = scheme_output(2, 2, &PROFILE_B_OUTPUT[..40]);
+        assert_eq!(keys().deconceal(&suci), Err(SuciError::Decryption));
+
+        // Not hexadecimal
+        let suci = scheme_output(1, 1, "b2e92f83zz");
+        assert_eq!(keys().deconceal(&suci), Err(SuciError::Decryption));
+    }
+
+    #[test]
+    fn bad_scheme() {
+        let bad = "suci-0-001-01-0000-3-1-0001002086";
+        assert_eq!(
+            bad.parse::<Suci>(),
+            Err(SuciError::Invalid(bad.to_string()))
+        );
+        assert_eq!(
+            keys().resolve(bad),
+            Err(SuciError::Invalid(bad.to_string()))
+        );
+
+        // A key of another scheme, or none
+        let suci = scheme_output(2, 1, PROFILE_B_OUTPUT);
+        assert_eq!(keys().deconceal(&suci), Err(SuciError::UnknownKey(1)));
+        let suci = scheme_output(1, 9, PROFILE_A_OUTPUT);
+        assert_eq!(keys().deconceal(&suci), Err(SuciError::UnknownKey(9)));
+    }
+}
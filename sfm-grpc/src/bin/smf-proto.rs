@@ -1,4 +1,6 @@
 use clap::Parser;
+use nnrf_discovery_server::selection::SelectionPolicy;
+use nsfm_pdusession::ip_pools::{Backend, IpPools, PoolConfig};
 use sfm_grpc::nsfm_pdusession::smf_server::SmfServer;
 use sfm_grpc::MySmf;
 use tonic::transport::Server;
@@ -12,6 +14,23 @@ pub struct Opts {
     pub udm: String,
     #[clap(short = 'u', long, default_value = "http://127.0.0.1:9093")]
     pub amf: String,
+    /// NRF the UDMs are discovered from, instead of using --udm
+    #[clap(long)]
+    pub nrf: Option<String>,
+    /// Port of the gRPC servers of the discovered UDMs, whose profiles give
+    /// the HTTP endpoints of their services
+    #[clap(long, default_value = "9091")]
+    pub udm_grpc_port: u16,
+    /// How the UDM is chosen: weighted, load-aware or round-robin
+    #[clap(long, default_value = "weighted")]
+    pub selection: SelectionPolicy,
+    /// A pool of UE addresses, `DNN=PREFIX`, as many as needed: an IPv4 and
+    /// an IPv6 one per DNN at most
+    #[clap(long = "ue-pool", default_value = "internet=192.168.100.0/24")]
+    pub ue_pools: Vec<PoolConfig>,
+    /// Where the UE address allocations are kept: `memory`, or a file
+    #[clap(long, default_value = "memory")]
+    pub ue_pool_store: Backend,
 }
 
 #[tokio::main]
@@ -19,7 +38,21 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let opts = Opts::parse();
 
     let addr = opts.listen.parse()?;
-    let smf = MySmf::new(opts.udm.into(), opts.amf).await;
+    let ip_pools = IpPools::new(&opts.ue_pools, opts.ue_pool_store.store())?;
+    let smf = match opts.nrf {
+        Some(nrf) => {
+            MySmf::with_discovery(
+                nrf,
+                opts.udm,
+                opts.udm_grpc_port,
+                opts.amf,
+                opts.selection,
+                ip_pools,
+            )
+            .await
+        }
+        None => MySmf::new(opts.udm, opts.amf, ip_pools).await,
+    };
 
     Server::builder()
         .add_service(SmfServer::new(smf))
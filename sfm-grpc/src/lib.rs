@@ -1,14 +1,27 @@
-use nrf_grpc::nnrf_disc::SearchRequest;
-use tonic::transport::Channel;
+use tonic::transport::{Channel, Uri};
 use tonic::{Request, Response, Status};
 
 use nsfm_pdusession::smf_server::Smf;
-use nsfm_pdusession::{CreateSmContextRequest, CreateSmContextResult};
+use nsfm_pdusession::{
+    CreateSmContextRequest, CreateSmContextResult, QosFlow, ReleaseSmContextResult, SmContext,
+    SmContextRef, UpdateSmContextRequest, UpdateSmContextResult,
+};
 
+use ::nsfm_pdusession::ip_pools::IpPools;
+use ::nsfm_pdusession::models::{self, AccessType};
+use ::nsfm_pdusession::ngap;
+use ::nsfm_pdusession::sm_contexts::{PduSession, SmContextError, SmContextStore};
+
+use nnrf_discovery_server::cache::DiscoveryCache;
+use nnrf_discovery_server::models::{NfType, ServiceName};
+use nnrf_discovery_server::registry::SearchQuery;
+use nnrf_discovery_server::selection::{NfSelector, SelectionPolicy, ServiceEndpoint};
 use nrf_grpc::nnrf_disc::nrf_discovery_client::NrfDiscoveryClient;
 use udm_grpc::nudm_sdm::udm_client::UdmClient;
 use udm_grpc::nudm_sdm::{GetSmDataRequest, Nssai};
 
+use std::collections::HashMap;
+use std::net::Ipv4Addr;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
@@ -16,26 +29,123 @@ pub mod nsfm_pdusession {
     tonic::include_proto!("fiveg_proto"); // The string specified here must match the proto package name
 }
 
+/// Service the SMF consumes from the UDM.
+const UDM_SERVICE: &str = "nudm-sdm";
+
+/// The DNS servers given to the UEs asking for them
+const DNS_SERVERS: [Ipv4Addr; 2] = [Ipv4Addr::new(8, 8, 8, 8), Ipv4Addr::new(8, 8, 4, 4)];
+/// The N3 address of the UPF the sessions go through
+const UPF_N3_ADDRESS: Ipv4Addr = Ipv4Addr::new(172, 22, 0, 8);
+
 #[derive(Debug)]
 struct SmfState {
-    udm_client: UdmClient<Channel>,
+    /// The UDM used when none is discovered.
+    udm: String,
+    udm_clients: HashMap<String, UdmClient<Channel>>,
+    discovery: Option<Discovery>,
+}
+
+/// How the UDMs are discovered. The NRF returns the HTTP endpoints of their
+/// services: a UDM is reached over gRPC on the same host, at the port its
+/// gRPC server is configured with.
+#[derive(Debug)]
+struct Discovery {
+    cache: DiscoveryCache<NrfDiscoveryClient<Channel>>,
+    selector: NfSelector,
+    udm_grpc_port: u16,
+}
+
+impl Discovery {
+    /// The gRPC endpoint of the UDM serving at an HTTP endpoint.
+    fn grpc_endpoint(&self, endpoint: &ServiceEndpoint) -> Option<String> {
+        let uri: Uri = endpoint.uri.parse().ok()?;
+        Some(format!("http://{}:{}", uri.host()?, self.udm_grpc_port))
+    }
+}
+
+impl SmfState {
+    /// Client of a UDM chosen among the discovered ones, or of the configured
+    /// one.
+    async fn udm_client(&mut self) -> Result<&mut UdmClient<Channel>, Status> {
+        let discovered = match &self.discovery {
+            Some(discovery) => {
+                let mut query = SearchQuery::new(NfType::UDM, NfType::SMF);
+                query.service_names = Some(vec![ServiceName::new(UDM_SERVICE.into())]);
+                discovery
+                    .cache
+                    .search(&query)
+                    .await
+                    .ok()
+                    .and_then(|result| discovery.selector.select(&result.nf_instances, UDM_SERVICE))
+                    .and_then(|endpoint| discovery.grpc_endpoint(&endpoint))
+            }
+            None => None,
+        };
+        let udm = discovered.unwrap_or_else(|| self.udm.clone());
+
+        if !self.udm_clients.contains_key(&udm) {
+            let client = UdmClient::connect(udm.clone())
+                .await
+                .map_err(|e| Status::unavailable(format!("UDM {udm}: {e}")))?;
+            self.udm_clients.insert(udm.clone(), client);
+        }
+        Ok(self
+            .udm_clients
+            .get_mut(&udm)
+            .expect("UDM client inserted above"))
+    }
 }
 
 #[derive(Debug, Default)]
 pub struct MySmf {
     state: Option<Arc<Mutex<SmfState>>>,
     amf: String,
+    sm_contexts: SmContextStore,
 }
 
 impl MySmf {
-    pub async fn new(udm: String, amf: String) -> Self {
-        let udm_client = UdmClient::connect(udm).await.unwrap();
+    pub async fn new(udm: String, amf: String, ip_pools: IpPools) -> Self {
+        let udm_client = UdmClient::connect(udm.clone()).await.unwrap();
+
+        let state = SmfState {
+            udm_clients: HashMap::from([(udm.clone(), udm_client)]),
+            udm,
+            discovery: None,
+        };
+
+        Self {
+            state: Some(Arc::new(Mutex::new(state))),
+            amf,
+            sm_contexts: SmContextStore::with_pools(ip_pools),
+        }
+    }
 
-        let state = SmfState { udm_client };
+    /// An SMF choosing its UDM among those the NRF returns, reached over
+    /// gRPC at `udm_grpc_port`, falling back to `udm` when there are none.
+    pub async fn with_discovery(
+        nrf: String,
+        udm: String,
+        udm_grpc_port: u16,
+        amf: String,
+        policy: SelectionPolicy,
+        ip_pools: IpPools,
+    ) -> Self {
+        let nrf_client = NrfDiscoveryClient::connect(nrf).await.unwrap();
+
+        let state = SmfState {
+            udm,
+            udm_clients: HashMap::new(),
+            discovery: Some(Discovery {
+                cache: DiscoveryCache::new(nrf_client),
+                selector: NfSelector::new(policy),
+                udm_grpc_port,
+            }),
+        };
 
         Self {
             state: Some(Arc::new(Mutex::new(state))),
             amf,
+            sm_contexts: SmContextStore::with_pools(ip_pools),
         }
     }
 }
@@ -46,43 +156,70 @@ impl Smf for MySmf {
         &self,
         request: Request<CreateSmContextRequest>,
     ) -> Result<Response<CreateSmContextResult>, Status> {
-        let _req: CreateSmContextRequest = request.into_inner();
+        let req: CreateSmContextRequest = request.into_inner();
 
         match &self.state {
             Some(state) => {
                 let mut guard_state = state.lock().await;
 
+                let mut session = pdu_session(&req)?;
+                if req.n1_sm_msg.is_empty() {
+                    return Err(Status::invalid_argument(
+                        "MANDATORY_IE_MISSING: n1SmMsg is mandatory",
+                    ));
+                }
+                let establishment_request = session
+                    .establishment_request(&req.n1_sm_msg)
+                    .map_err(status)?;
+
                 let udm_req = GetSmDataRequest {
-                    dnn: "internet".into(),
+                    dnn: session.dnn.clone(),
                     single_nssai: Some(Nssai {
-                        sst: 1,
-                        sd: "".into(),
+                        sst: session.s_nssai.sst.into(),
+                        sd: session.s_nssai.sd.clone().unwrap_or_default(),
                     }),
+                    supi: session.supi.clone(),
                 };
 
-                let _udm_reply = guard_state.udm_client.get_sm_data(udm_req).await.unwrap();
+                // An unknown subscriber is reported to the AMF as is
+                let udm_reply = guard_state
+                    .udm_client()
+                    .await?
+                    .get_sm_data(udm_req)
+                    .await?
+                    .into_inner();
+                let configuration = udm_reply
+                    .dnn_configurations
+                    .iter()
+                    .find(|(dnn, _)| dnn.eq_ignore_ascii_case(&session.dnn))
+                    .map(|(_, configuration)| configuration)
+                    .ok_or_else(|| {
+                        Status::permission_denied(format!(
+                            "DNN_DENIED: DNN {} not subscribed in slice {:?}",
+                            session.dnn, session.s_nssai
+                        ))
+                    })?;
+                session.apply_dnn_configuration(&configuration.into());
+                session.ul_tunnel = Some(ngap::GtpTunnel {
+                    transport_layer_address: UPF_N3_ADDRESS.into(),
+                    gtp_teid: self.sm_contexts.allocate_teid(),
+                });
 
+                let (session, _replaced) = self.sm_contexts.create(session).map_err(status)?;
                 let reply = CreateSmContextResult {
-                    location: "nsmf-pdusession/v1/sm-contexts/4".into(),
+                    location: format!("nsmf-pdusession/v1/sm-contexts/{}", session.sm_context_ref),
                 };
 
                 let c_amf_url = self.amf.clone();
+                let c_supi = session.supi.clone();
+                let nas = session
+                    .establishment_accept(&establishment_request, &DNS_SERVERS)
+                    .encode();
+                let ngap = session
+                    .n2_sm_info_transfer(models::N2SmInfoTypeAnyOf::PduResSetupReq)
+                    .unwrap_or_default();
                 tokio::task::spawn(async move {
                     let data = r#"{"n1MessageContainer":{"n1MessageClass":"SM","n1MessageContent":{"contentId":"5gnas-sm"}},"n2InfoContainer":{"n2InformationClass":"SM","smInfo":{"pduSessionId":1,"n2InfoContent":{"ngapIeType":"PDU_RES_SETUP_REQ","ngapData":{"contentId":"ngap-sm"}}}},"pduSessionId":1}"#.as_bytes();
-                    let nas = [
-                        0x2e, 0x01, 0x01, 0xc2, 0x11, 0x00, 0x09, 0x01, 0x00, 0x06, 0x31, 0x31,
-                        0x01, 0x01, 0xff, 0x01, 0x06, 0x0b, 0x00, 0x01, 0x0b, 0x00, 0x01, 0x29,
-                        0x05, 0x01, 0xc0, 0xa8, 0x64, 0x05, 0x22, 0x01, 0x01, 0x79, 0x00, 0x06,
-                        0x01, 0x20, 0x41, 0x01, 0x01, 0x09, 0x7b, 0x00, 0x0f, 0x80, 0x00, 0x0d,
-                        0x04, 0x08, 0x08, 0x08, 0x08, 0x00, 0x0d, 0x04, 0x08, 0x08, 0x04, 0x04,
-                        0x25, 0x09, 0x08, 0x69, 0x6e, 0x74, 0x65, 0x72, 0x6e, 0x65, 0x74,
-                    ];
-                    let ngap = [
-                        0x00, 0x00, 0x04, 0x00, 0x82, 0x00, 0x0a, 0x0c, 0x40, 0x00, 0x00, 0x00,
-                        0x30, 0x40, 0x00, 0x00, 0x00, 0x00, 0x8b, 0x00, 0x0a, 0x01, 0xf0, 0xac,
-                        0x16, 0x00, 0x08, 0x00, 0x00, 0x00, 0x0e, 0x00, 0x86, 0x00, 0x01, 0x00,
-                        0x00, 0x88, 0x00, 0x07, 0x00, 0x01, 0x00, 0x00, 0x09, 0x1c, 0x00,
-                    ];
 
                     let mut body: Vec<u8> = vec![];
                     body.extend_from_slice(data);
@@ -92,8 +229,9 @@ impl Smf for MySmf {
                     let client = reqwest::Client::new();
                     let _res = client
                         .post(format!(
-                            "{}/namf-comm/v1/ue-contexts/imsi-001011234567895/n1-n2-messages",
-                            c_amf_url.as_str()
+                            "{}/namf-comm/v1/ue-contexts/{}/n1-n2-messages",
+                            c_amf_url.as_str(),
+                            c_supi
                         ))
                         .body(body)
                         .send()
@@ -105,4 +243,176 @@ impl Smf for MySmf {
             None => panic!("No state WTF?"),
         }
     }
+
+    async fn update_sm_context(
+        &self,
+        request: Request<UpdateSmContextRequest>,
+    ) -> Result<Response<UpdateSmContextResult>, Status> {
+        let req = request.into_inner();
+
+        let mut update = models::SmContextUpdateData::new();
+        update.pei = (!req.pei.is_empty()).then_some(req.pei);
+        update.up_cnx_state =
+            (!req.up_cnx_state.is_empty()).then(|| models::UpCnxState::new(req.up_cnx_state));
+        update.ho_state = (!req.ho_state.is_empty()).then(|| models::HoState::new(req.ho_state));
+        update.n2_sm_info_type = (!req.n2_sm_info_type.is_empty())
+            .then(|| models::N2SmInfoType::new(req.n2_sm_info_type));
+        update.release = req.release.then_some(true);
+        update.n1_sm_msg = (!req.n1_sm_msg.is_empty())
+            .then(|| models::RefToBinaryData::new("5gnas-sm".to_string()));
+        update.n2_sm_info = (!req.n2_sm_info.is_empty())
+            .then(|| models::RefToBinaryData::new("ngap-sm".to_string()));
+
+        let (session, updated) = self
+            .sm_contexts
+            .update(
+                &req.sm_context_ref,
+                &update,
+                (!req.n1_sm_msg.is_empty()).then_some(req.n1_sm_msg.as_slice()),
+                (!req.n2_sm_info.is_empty()).then_some(req.n2_sm_info.as_slice()),
+            )
+            .map_err(status)?;
+
+        Ok(Response::new(UpdateSmContextResult {
+            up_cnx_state: updated
+                .up_cnx_state
+                .map(|state| state.to_string())
+                .unwrap_or_default(),
+            ho_state: updated
+                .ho_state
+                .map(|state| state.to_string())
+                .unwrap_or_default(),
+            n2_sm_info_type: updated
+                .n2_sm_info_type
+                .map(|info_type| info_type.to_string())
+                .unwrap_or_default(),
+            state: session.state.to_string(),
+            n2_sm_info: updated
+                .n2_sm_info_type
+                .and_then(|info_type| session.n2_sm_info_transfer(info_type))
+                .unwrap_or_default(),
+            n1_sm_msg: updated
+                .n1_sm_msg
+                .map(|message| message.encode())
+                .unwrap_or_default(),
+        }))
+    }
+
+    async fn retrieve_sm_context(
+        &self,
+        request: Request<SmContextRef>,
+    ) -> Result<Response<SmContext>, Status> {
+        let req = request.into_inner();
+        let session = self.sm_contexts.get(&req.sm_context_ref).map_err(status)?;
+        Ok(Response::new(SmContext::from(&session)))
+    }
+
+    async fn release_sm_context(
+        &self,
+        request: Request<SmContextRef>,
+    ) -> Result<Response<ReleaseSmContextResult>, Status> {
+        let req = request.into_inner();
+        let session = self
+            .sm_contexts
+            .release(&req.sm_context_ref)
+            .map_err(status)?;
+        Ok(Response::new(ReleaseSmContextResult {
+            state: session.state.to_string(),
+        }))
+    }
+}
+
+/// The PDU session the AMF asks for.
+fn pdu_session(req: &CreateSmContextRequest) -> Result<PduSession, Status> {
+    if req.supi.is_empty() || req.dnn.is_empty() {
+        return Err(Status::invalid_argument(
+            "MANDATORY_IE_MISSING: supi and dnn are mandatory",
+        ));
+    }
+    let pdu_session_id = u8::try_from(req.pdu_session_id)
+        .ok()
+        .filter(|id| (1..=15).contains(id))
+        .ok_or_else(|| {
+            Status::invalid_argument(format!(
+                "MANDATORY_IE_INCORRECT: PDU session id {}",
+                req.pdu_session_id
+            ))
+        })?;
+    let nssai = req
+        .s_nnssai
+        .as_ref()
+        .ok_or_else(|| Status::invalid_argument("MANDATORY_IE_MISSING: sNssai is mandatory"))?;
+    let mut s_nssai = models::Snssai::new(u8::try_from(nssai.sst).map_err(|_| {
+        Status::invalid_argument(format!("MANDATORY_IE_INCORRECT: SST {}", nssai.sst))
+    })?);
+    s_nssai.sd = (!nssai.sd.is_empty()).then(|| nssai.sd.clone());
+    let an_type = if req.an_type.is_empty() {
+        AccessType::Variant3GppAccess
+    } else {
+        req.an_type
+            .parse()
+            .map_err(|e| Status::invalid_argument(format!("MANDATORY_IE_INCORRECT: {e}")))?
+    };
+
+    let mut session = PduSession::new(
+        req.supi.clone(),
+        pdu_session_id,
+        s_nssai,
+        req.dnn.clone(),
+        an_type,
+        req.sm_context_status_uri.clone(),
+    );
+    session.pei = (!req.pei.is_empty()).then(|| req.pei.clone());
+    Ok(session)
+}
+
+/// The status of a request the SM context store rejects.
+fn status(e: SmContextError) -> Status {
+    let message = format!("{}: {e}", e.problem().cause.unwrap_or_default());
+    match e {
+        SmContextError::NotFound(_) => Status::not_found(message),
+        SmContextError::UnexpectedN2SmInfo { .. }
+        | SmContextError::UnexpectedN1SmMsg(_)
+        | SmContextError::InvalidN1SmMsg(_)
+        | SmContextError::InvalidN2SmInfo(_) => Status::failed_precondition(message),
+        SmContextError::InsufficientResources(_) => Status::resource_exhausted(message),
+        _ => Status::invalid_argument(message),
+    }
+}
+
+impl From<&PduSession> for SmContext {
+    fn from(session: &PduSession) -> Self {
+        SmContext {
+            sm_context_ref: session.sm_context_ref.clone(),
+            supi: session.supi.clone(),
+            pdu_session_id: session.pdu_session_id.into(),
+            s_nssai: Some(nsfm_pdusession::Nssai {
+                sst: session.s_nssai.sst.into(),
+                sd: session.s_nssai.sd.clone().unwrap_or_default(),
+            }),
+            dnn: session.dnn.clone(),
+            pdu_session_type: session.pdu_session_type.to_string(),
+            state: session.state.to_string(),
+            up_cnx_state: session.up_cnx_state.to_string(),
+            ho_state: session.ho_state.to_string(),
+            qos_flows: session
+                .qos_flows
+                .iter()
+                .map(|flow| QosFlow {
+                    qfi: flow.qfi.into(),
+                    qi: flow.qos_flow_profile.param_5qi.into(),
+                    arp_priority_level: match flow
+                        .qos_flow_profile
+                        .arp
+                        .as_ref()
+                        .map(|arp| &arp.priority_level)
+                    {
+                        Some(swagger::Nullable::Present(level)) => (*level).into(),
+                        _ => 0,
+                    },
+                    default_qos_rule: flow.default_qos_rule_ind,
+                })
+                .collect(),
+        }
+    }
 }
@@ -1,13 +1,36 @@
 use clap::Parser;
+use nnrf_discovery_server::models::NfType;
+use nnrf_zenoh::registration;
+use nsfm_pdusession::ip_pools::{Backend, IpPools, PoolConfig};
+use nsfm_pdusession::models::N2SmInfoTypeAnyOf;
+use nsfm_pdusession::ngap;
+use nsfm_pdusession::sm_contexts::{PduSession, SmContextStore};
+use nudm_sdm::models::SessionManagementSubscriptionData;
+use percent_encoding::{utf8_percent_encode, NON_ALPHANUMERIC};
+use smf_zenoh::SM_CONTEXTS;
+use std::net::Ipv4Addr;
 use std::{str::FromStr, sync::Arc};
+use uuid::Uuid;
 use zenoh::prelude::r#async::*;
 use zenoh_config::{EndPoint, ListenConfig};
 
+/// The DNS servers given to the UEs asking for them
+const DNS_SERVERS: [Ipv4Addr; 2] = [Ipv4Addr::new(8, 8, 8, 8), Ipv4Addr::new(8, 8, 4, 4)];
+/// The N3 address of the UPF the sessions go through
+const UPF_N3_ADDRESS: Ipv4Addr = Ipv4Addr::new(172, 22, 0, 8);
+
 #[derive(Parser)]
 pub struct Opts {
     // public options
     #[clap(short = 'l', long, default_value = "tcp/127.0.0.1:7072")]
     pub listen: String,
+    /// A pool of UE addresses, `DNN=PREFIX`, as many as needed: an IPv4 and
+    /// an IPv6 one per DNN at most
+    #[clap(long = "ue-pool", default_value = "internet=192.168.100.0/24")]
+    pub ue_pools: Vec<PoolConfig>,
+    /// Where the UE address allocations are kept: `memory`, or a file
+    #[clap(long, default_value = "memory")]
+    pub ue_pool_store: Backend,
 }
 
 #[async_std::main]
@@ -31,7 +54,23 @@ async fn main() {
         .unwrap();
     let session = Arc::new(zenoh::open(config).res().await.unwrap());
 
-    let ke = format!("nsmf-pdusession/v1/sm-contexts");
+    // Registered with the NRF for as long as the session lives
+    let profile = registration::nf_profile(
+        Uuid::new_v4(),
+        NfType::SMF,
+        "nsmf-pdusession",
+        "v1",
+        "1.0.0",
+    );
+    let _registration = registration::register(session.clone(), profile)
+        .await
+        .unwrap();
+
+    let ip_pools = IpPools::new(&opts.ue_pools, opts.ue_pool_store.store())
+        .expect("unable to recover the UE address allocations");
+    let sm_contexts = SmContextStore::with_pools(ip_pools);
+    // `**` also matches the collection itself, on which the contexts are created
+    let ke = format!("{}/**", SM_CONTEXTS);
     let queryable = session.declare_queryable(&ke).res().await.unwrap();
 
     async_std::task::sleep(std::time::Duration::from_secs(5)).await;
@@ -39,29 +78,116 @@ async fn main() {
     loop {
         match queryable.recv_async().await {
             Ok(query) => {
-                let ke = query.key_expr();
+                let ke = query.key_expr().clone();
+                let payload = query.value().map(|v| v.payload.contiguous().to_vec());
 
-                // call udm
-                let _udm_res = session.get("nudm-sdm/v2/imsi-001011234567895/sm-data?single-nssai=%7B%0A%09%22sst%22%3A%091%0A%7D&dnn=internet").res().await.unwrap();
-                // return to AMF
+                if ke.as_str() != SM_CONTEXTS {
+                    let reply = match smf_zenoh::sm_context(
+                        &sm_contexts,
+                        ke.as_str(),
+                        payload.as_deref(),
+                    ) {
+                        Some(Ok(value)) => Ok(Sample::new(ke, value)),
+                        Some(Err(problem)) => Err(Value::from(problem)),
+                        None => Err(Value::from("unknown resource")),
+                    };
+                    query.reply(reply).res().await.unwrap();
+                    continue;
+                }
 
-                let value = "nsmf-pdusession/v1/sm-contexts/4".as_bytes();
-                query
-                    .reply(Ok(Sample::new(ke.clone(), value)))
-                    .res()
-                    .await
-                    .unwrap();
+                match create(&session, &sm_contexts, &payload.unwrap_or_default()).await {
+                    Ok((created, n1_n2_message)) => {
+                        // return to AMF
+                        let value = format!("{}/{}", SM_CONTEXTS, created.sm_context_ref);
+                        query.reply(Ok(Sample::new(ke, value))).res().await.unwrap();
 
-                // callback to AMF
-                let _amf_res = session
-                    .get("namf-comm/v1/ue-contexts/imsi-001011234567895/n1-n2-messages")
-                    .res()
-                    .await
-                    .unwrap();
-
-                // let value = serde_json::from_str::<SessionManagementSubscriptionData>(r#"{"singleNssai":{"sst":1},"dnnConfigurations":{"internet":{"pduSessionTypes":{"defaultSessionType":"IPV4","allowedSessionTypes":["IPV4"]},"sscModes":{"defaultSscMode":"SSC_MODE_1","allowedSscModes":["SSC_MODE_1","SSC_MODE_2","SSC_MODE_3"]},"5gQosProfile":{"5qi":9,"arp":{"priorityLevel":8,"preemptCap":"NOT_PREEMPT","preemptVuln":"NOT_PREEMPTABLE"},"priorityLevel":8},"sessionAmbr":{"uplink":"1048576 Kbps","downlink":"1048576 Kbps"}}}}"#).expect("unable to parse json");
+                        // callback to AMF, with the PDU Session Establishment Accept
+                        // and the PDU Session Resource Setup Request Transfer
+                        let _amf_res = session
+                            .get(&format!(
+                                "namf-comm/v1/ue-contexts/{}/n1-n2-messages",
+                                created.supi
+                            ))
+                            .with_value(n1_n2_message)
+                            .res()
+                            .await
+                            .unwrap();
+                    }
+                    Err(problem) => query.reply(Err(Value::from(problem))).res().await.unwrap(),
+                }
             }
             Err(_) => (),
         }
     }
 }
+
+/// Creates the SM context of a PDU session, with the DNN configuration the
+/// UDM has for the UE, and encodes the answer to its establishment request
+/// followed by the N2 SM information for the NG-RAN.
+async fn create(
+    session: &zenoh::Session,
+    sm_contexts: &SmContextStore,
+    payload: &[u8],
+) -> Result<(PduSession, Vec<u8>), Vec<u8>> {
+    let (mut pdu_session, request) = smf_zenoh::create_data(payload)?;
+
+    // call udm
+    let single_nssai =
+        serde_json::to_string(&pdu_session.s_nssai).expect("impossible to fail to serialize");
+    let selector = format!(
+        "nudm-sdm/v2/{}/sm-data?single-nssai={}&dnn={}",
+        pdu_session.supi,
+        utf8_percent_encode(&single_nssai, NON_ALPHANUMERIC),
+        utf8_percent_encode(&pdu_session.dnn, NON_ALPHANUMERIC)
+    );
+    let udm_res = session
+        .get(&selector)
+        .res()
+        .await
+        .map_err(|e| e.to_string().into_bytes())?
+        .recv_async()
+        .await
+        .map_err(|e| e.to_string().into_bytes())?;
+    let sm_data = match udm_res.sample {
+        Ok(sample) => serde_json::from_slice::<Vec<SessionManagementSubscriptionData>>(
+            &sample.value.payload.contiguous(),
+        )
+        .map_err(|e| e.to_string().into_bytes())?,
+        Err(problem) => return Err(problem.payload.contiguous().to_vec()),
+    };
+    let dnn_configuration = sm_data
+        .iter()
+        .flat_map(|data| data.dnn_configurations.iter().flatten())
+        .find(|(name, _)| name.eq_ignore_ascii_case(&pdu_session.dnn))
+        .map(|(_, configuration)| configuration)
+        .ok_or_else(|| {
+            format!(
+                "DNN {} not subscribed by {}",
+                pdu_session.dnn, pdu_session.supi
+            )
+            .into_bytes()
+        })?;
+    pdu_session.apply_dnn_configuration(dnn_configuration);
+    pdu_session.ul_tunnel = Some(ngap::GtpTunnel {
+        transport_layer_address: UPF_N3_ADDRESS.into(),
+        gtp_teid: sm_contexts.allocate_teid(),
+    });
+
+    let (pdu_session, replaced) = sm_contexts
+        .create(pdu_session)
+        .map_err(|e| smf_zenoh::create_error(&e, &request))?;
+    if let Some(replaced) = replaced {
+        log::info!(
+            "SM context {} replaced by {}",
+            replaced.sm_context_ref,
+            pdu_session.sm_context_ref
+        );
+    }
+    let mut n1_n2_message = pdu_session
+        .establishment_accept(&request, &DNS_SERVERS)
+        .encode();
+    if let Some(ngap) = pdu_session.n2_sm_info_transfer(N2SmInfoTypeAnyOf::PduResSetupReq) {
+        n1_n2_message.extend_from_slice(&ngap);
+    }
+    Ok((pdu_session, n1_n2_message))
+}
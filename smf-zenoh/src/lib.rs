@@ -1,12 +1,108 @@
-use nsfm_pdusession::{
-    models, PostPduSessionsResponse, PostSmContextsResponse, ReleasePduSessionResponse,
-    ReleaseSmContextResponse, RetrievePduSessionResponse, RetrieveSmContextResponse,
-    SendMoDataResponse, TransferMoDataResponse, UpdatePduSessionResponse, UpdateSmContextResponse,
-};
+use nsfm_pdusession::sm_contexts::{PduSession, SmContextError, SmContextStore};
+use nsfm_pdusession::{models, nas};
 use serde::{Deserialize, Serialize};
-use uuid::Uuid;
-
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ApiError(pub String);
 
+/// Key expression of the SM context collection: the AMF creates a context
+/// with a get on it, then updates, retrieves and releases it with a get on
+/// `{SM_CONTEXTS}/{smContextRef}/modify`, `/retrieve` and `/release`. The
+/// binary data follows the JSON of the requests and of the responses: a
+/// modify request carries the N1 SM message it references, or else the N2
+/// SM information, and its response the N1 SM message then the N2 SM
+/// information. A create rejected once the UE request is decoded is
+/// answered with the problem then the PDU Session Establishment Reject.
+pub const SM_CONTEXTS: &str = "nsmf-pdusession/v1/sm-contexts";
+
+/// The PDU session of a create request, the SmContextCreateData JSON, and
+/// the PDU Session Establishment Request following it.
+pub fn create_data(payload: &[u8]) -> Result<(PduSession, nas::Message), Vec<u8>> {
+    let (data, n1_sm_msg) = json_data::<models::SmContextCreateData>(payload)
+        .ok_or_else(|| problem(&SmContextError::MissingIe("jsonData").problem()))?;
+    let mut session = PduSession::from_create_data(&data).map_err(|e| problem(&e.problem()))?;
+    if n1_sm_msg.is_empty() {
+        return Err(problem(&SmContextError::MissingIe("n1SmMsg").problem()));
+    }
+    let request = session
+        .establishment_request(n1_sm_msg)
+        .map_err(|e| problem(&e.problem()))?;
+    Ok((session, request))
+}
+
+/// The answer to a create the store rejects: the problem, and the PDU
+/// Session Establishment Reject the AMF forwards to the UE.
+pub fn create_error(e: &SmContextError, request: &nas::Message) -> Vec<u8> {
+    let mut value = problem(&e.problem());
+    value.extend_from_slice(&e.establishment_reject(request).encode());
+    value
+}
+
+/// Answers a query on an SM context, or `None` when the key expression is
+/// not one of an SM context.
+pub fn sm_context(
+    store: &SmContextStore,
+    key_expr: &str,
+    payload: Option<&[u8]>,
+) -> Option<Result<Vec<u8>, Vec<u8>>> {
+    let (sm_context_ref, operation) = key_expr
+        .strip_prefix(SM_CONTEXTS)?
+        .strip_prefix('/')?
+        .split_once('/')?;
+    let res = match operation {
+        "modify" => {
+            let data = payload
+                .and_then(json_data::<models::SmContextUpdateData>)
+                .ok_or(SmContextError::MissingIe("jsonData"));
+            data.and_then(|(data, binary)| {
+                let binary = (!binary.is_empty()).then_some(binary);
+                let (n1_sm_msg, n2_sm_info) = match data.n1_sm_msg {
+                    Some(_) => (binary, None),
+                    None => (None, binary.filter(|_| data.n2_sm_info.is_some())),
+                };
+                store.update(sm_context_ref, &data, n1_sm_msg, n2_sm_info)
+            })
+            .map(|(session, updated)| {
+                log::info!(
+                    "SM context {} is {} with user plane {}",
+                    sm_context_ref,
+                    session.state,
+                    session.up_cnx_state
+                );
+                let mut value = updated
+                    .updated_data()
+                    .map(|data| serde_json::to_vec(&data).expect("impossible to fail to serialize"))
+                    .unwrap_or_default();
+                if let Some(n1_sm_msg) = &updated.n1_sm_msg {
+                    value.extend_from_slice(&n1_sm_msg.encode());
+                }
+                if let Some(n2_sm_info) = updated
+                    .n2_sm_info_type
+                    .and_then(|info_type| session.n2_sm_info_transfer(info_type))
+                {
+                    value.extend_from_slice(&n2_sm_info);
+                }
+                value
+            })
+        }
+        "retrieve" => store.get(sm_context_ref).map(|session| {
+            let mut retrieved = models::SmContextRetrievedData::new(String::new());
+            retrieved.sm_context = Some(session.sm_context());
+            serde_json::to_vec(&retrieved).expect("impossible to fail to serialize")
+        }),
+        "release" => store.release(sm_context_ref).map(|_| vec![]),
+        _ => return None,
+    };
+    Some(res.map_err(|e| problem(&e.problem())))
+}
+
+/// The JSON at the start of a payload, and the binary data following it.
+fn json_data<'a, T: serde::de::DeserializeOwned>(payload: &'a [u8]) -> Option<(T, &'a [u8])> {
+    let mut stream = serde_json::Deserializer::from_slice(payload).into_iter::<T>();
+    let data = stream.next()?.ok()?;
+    Some((data, &payload[stream.byte_offset()..]))
+}
+
+fn problem(problem: &models::ExtProblemDetails) -> Vec<u8> {
+    serde_json::to_vec(problem).expect("impossible to fail to serialize")
+}
@@ -1,4 +1,8 @@
 use clap::Parser;
+use nudm_sdm::acknowledgements::AcknowledgementStore;
+use nudm_sdm::shared_data::SharedDataStore;
+use nudm_sdm::subscribers::SubscriberStore;
+use nudm_sdm::suci::HomeNetworkKeys;
 use tonic::transport::Server;
 use udm_grpc::nudm_sdm::udm_server::UdmServer;
 use udm_grpc::MyUDM;
@@ -8,6 +12,24 @@ pub struct Opts {
     // public options
     #[clap(short = 'l', long, default_value = "127.0.0.1:9091")]
     pub listen: String,
+    /// Provisioning file of the subscribers, YAML or JSON
+    #[clap(
+        short = 's',
+        long,
+        default_value = "nudm-sdm/examples/udm-server/subscribers.yaml"
+    )]
+    pub subscribers: String,
+    /// Provisioning file of the shared data, YAML or JSON
+    #[clap(
+        short = 'd',
+        long,
+        default_value = "nudm-sdm/examples/udm-server/shared-data.yaml"
+    )]
+    pub shared_data: String,
+    /// Private keys of the home network, YAML or JSON, for de-concealing
+    /// the SUCIs of the ECIES protection schemes
+    #[clap(short = 'k', long)]
+    pub home_network_keys: Option<String>,
 }
 
 #[tokio::main]
@@ -15,7 +37,16 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     let opts = Opts::parse();
 
     let addr = opts.listen.parse()?;
-    let udm = MyUDM::default();
+    let keys = match &opts.home_network_keys {
+        Some(path) => HomeNetworkKeys::load(path)?,
+        None => HomeNetworkKeys::default(),
+    };
+    let udm = MyUDM::new(
+        SubscriberStore::load(&opts.subscribers)?,
+        SharedDataStore::load(&opts.shared_data)?,
+        AcknowledgementStore::new(),
+        keys,
+    );
 
     Server::builder()
         .add_service(UdmServer::new(udm))
@@ -1,19 +1,75 @@
+use futures::{stream, Stream, StreamExt};
+use std::pin::Pin;
 use tonic::{Request, Response, Status};
 
+use nudm_sdm::acknowledge_request::Procedure as AckProcedure;
+use nudm_sdm::sdm_notification::Operation;
 use nudm_sdm::udm_server::Udm;
 use nudm_sdm::{
-    Arp, DnnConfiguration, GetSmDataRequest, GetSmDataResult, Nssai, PduSessionTypes, QosProfile,
-    SessionAmbr, SscModes,
+    AcknowledgeRequest, AcknowledgeResult, Ambr, Arp, DnnConfiguration, DnnInfo, GetAmDataRequest,
+    GetAmDataResult, GetDataSetsRequest, GetDataSetsResult, GetNssaiRequest, GetNssaiResult,
+    GetSmDataRequest, GetSmDataResult, GetSmfSelDataRequest, GetSmfSelDataResult, Nssai,
+    PduSessionTypes, PlmnId, QosProfile, SdmNotification, SessionAmbr, SnssaiInfo, SscModes,
+    SubscribeSdmRequest, UpdateSorInfoRequest, UpdateSorInfoResult,
 };
 
-use std::collections::HashMap;
+use ::nudm_sdm::acknowledgements::{AcknowledgementStore, Procedure};
+use ::nudm_sdm::models;
+use ::nudm_sdm::shared_data::SharedDataStore;
+use ::nudm_sdm::subscribers::{plmn_key, Subscriber, SubscriberEvent, SubscriberStore, RESOURCES};
+use ::nudm_sdm::suci::HomeNetworkKeys;
 
 pub mod nudm_sdm {
     tonic::include_proto!("fiveg_proto"); // The string specified here must match the proto package name
 }
 
 #[derive(Debug, Default)]
-pub struct MyUDM {}
+pub struct MyUDM {
+    subscribers: SubscriberStore,
+    shared_data: SharedDataStore,
+    acknowledgements: AcknowledgementStore,
+    keys: HomeNetworkKeys,
+}
+
+impl MyUDM {
+    pub fn new(
+        subscribers: SubscriberStore,
+        shared_data: SharedDataStore,
+        acknowledgements: AcknowledgementStore,
+        keys: HomeNetworkKeys,
+    ) -> Self {
+        Self {
+            subscribers,
+            shared_data,
+            acknowledgements,
+            keys,
+        }
+    }
+
+    /// The SUPI of a UE, which may be concealed in a SUCI.
+    fn resolve(&self, supi: &str) -> Result<String, Status> {
+        self.keys
+            .resolve(supi)
+            .map_err(|e| Status::invalid_argument(format!("MANDATORY_IE_INCORRECT: {e}")))
+    }
+
+    /// The subscriber a retrieval is about, as seen from the serving PLMN,
+    /// with the shared data it references merged into its data.
+    fn subscriber(&self, supi: &str, plmn_id: Option<&PlmnId>) -> Result<Subscriber, Status> {
+        let supi = self.resolve(supi)?;
+        let subscriber = self
+            .subscribers
+            .get(&supi)
+            .ok_or_else(|| user_not_found(&supi))?;
+        let subscriber = match plmn_id {
+            Some(plmn_id) => subscriber.in_plmn(&plmn_key(&plmn_id.mcc, &plmn_id.mnc)),
+            None => subscriber,
+        };
+        Ok(self.shared_data.resolve(&subscriber))
+    }
+}
+
+type SdmStream = Pin<Box<dyn Stream<Item = Result<SdmNotification, Status>> + Send>>;
 
 #[tonic::async_trait]
 impl Udm for MyUDM {
@@ -21,45 +77,493 @@ impl Udm for MyUDM {
         &self,
         request: Request<GetSmDataRequest>,
     ) -> Result<Response<GetSmDataResult>, Status> {
-        let _req: GetSmDataRequest = request.into_inner();
+        let req: GetSmDataRequest = request.into_inner();
 
-        let reply = GetSmDataResult {
-            single_nssai: Some(Nssai {
-                sst: 1,
-                sd: "".into(),
-            }),
-            dnn_configurations: HashMap::from([(
-                "internet".into(),
-                DnnConfiguration {
-                    pdu_session_types: Some(PduSessionTypes {
-                        default_session_type: "IPV4".into(),
-                        allowed_session_types: vec!["IPV4".into()],
-                    }),
-                    ssc_modes: Some(SscModes {
-                        default_ssc_mode: "SSC_MODE_1".into(),
-                        allowed_ssc_modes: vec![
-                            "SSC_MODE_1".into(),
-                            "SSC_MODE_2".into(),
-                            "SSC_MODE_3".into(),
-                        ],
-                    }),
-                    qos_profile: Some(QosProfile {
-                        qi: 9,
-                        arp: Some(Arp {
-                            priority_level: 8,
-                            preempt_cap: "NOT_PREEMPT".into(),
-                            preempt_vuln: "NOT_PREEMPTABLE".into(),
-                        }),
-                        priority_level: 8,
-                    }),
-                    session_ambr: Some(SessionAmbr {
-                        uplink: "1048576 Kbps".into(),
-                        downlink: "1048576 Kbps".into(),
-                    }),
-                },
-            )]),
+        let subscriber = self.subscriber(&req.supi, None)?;
+
+        let single_nssai = req.single_nssai.as_ref().map(models::Snssai::from);
+        let dnn = (!req.dnn.is_empty()).then_some(req.dnn.as_str());
+        let sm_data = subscriber.sm_data(single_nssai.as_ref(), dnn);
+
+        // One slice per reply: the first one matching the request
+        let reply = sm_data
+            .first()
+            .map(GetSmDataResult::from)
+            .ok_or_else(|| data_not_found(&req.supi))?;
+
+        Ok(Response::new(reply))
+    }
+
+    async fn get_am_data(
+        &self,
+        request: Request<GetAmDataRequest>,
+    ) -> Result<Response<GetAmDataResult>, Status> {
+        let req = request.into_inner();
+        let subscriber = self.subscriber(&req.supi, req.plmn_id.as_ref())?;
+
+        let reply = subscriber
+            .am_data
+            .as_ref()
+            .map(GetAmDataResult::from)
+            .ok_or_else(|| data_not_found(&req.supi))?;
+
+        Ok(Response::new(reply))
+    }
+
+    async fn get_nssai(
+        &self,
+        request: Request<GetNssaiRequest>,
+    ) -> Result<Response<GetNssaiResult>, Status> {
+        let req = request.into_inner();
+        let subscriber = self.subscriber(&req.supi, req.plmn_id.as_ref())?;
+
+        let reply = subscriber
+            .nssai
+            .as_ref()
+            .map(GetNssaiResult::from)
+            .ok_or_else(|| data_not_found(&req.supi))?;
+
+        Ok(Response::new(reply))
+    }
+
+    async fn get_smf_sel_data(
+        &self,
+        request: Request<GetSmfSelDataRequest>,
+    ) -> Result<Response<GetSmfSelDataResult>, Status> {
+        let req = request.into_inner();
+        let subscriber = self.subscriber(&req.supi, req.plmn_id.as_ref())?;
+
+        let reply = subscriber
+            .smf_sel_data
+            .as_ref()
+            .map(GetSmfSelDataResult::from)
+            .ok_or_else(|| data_not_found(&req.supi))?;
+
+        Ok(Response::new(reply))
+    }
+
+    async fn get_data_sets(
+        &self,
+        request: Request<GetDataSetsRequest>,
+    ) -> Result<Response<GetDataSetsResult>, Status> {
+        use models::DataSetNameAnyOf as Name;
+
+        let req = request.into_inner();
+        let names = req
+            .dataset_names
+            .iter()
+            .map(|name| match name.parse::<Name>() {
+                Ok(name @ (Name::Am | Name::SmfSel | Name::Sm)) => Ok(name),
+                _ => Err(Status::invalid_argument(format!(
+                    "unsupported data set {name}"
+                ))),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if names.len() < 2 {
+            return Err(Status::invalid_argument(
+                "dataset_names needs at least two data sets",
+            ));
+        }
+        let subscriber = self.subscriber(&req.supi, req.plmn_id.as_ref())?;
+
+        let sets = subscriber.data_sets(&names);
+        let reply = GetDataSetsResult {
+            am_data: sets.am_data.as_ref().map(GetAmDataResult::from),
+            smf_sel_data: sets.smf_sel_data.as_ref().map(GetSmfSelDataResult::from),
+            sm_data: sets
+                .sm_data
+                .iter()
+                .flat_map(|sm_data| sm_data.individual_sm_subs_data.iter().flatten())
+                .map(GetSmDataResult::from)
+                .collect(),
         };
 
         Ok(Response::new(reply))
     }
+
+    type SubscribeSdmStream = SdmStream;
+
+    async fn subscribe_sdm(
+        &self,
+        request: Request<SubscribeSdmRequest>,
+    ) -> Result<Response<Self::SubscribeSdmStream>, Status> {
+        let req = request.into_inner();
+        if let Some(unknown) = req
+            .monitored_resources
+            .iter()
+            .find(|r| !RESOURCES.contains(&r.as_str()))
+        {
+            return Err(Status::invalid_argument(format!(
+                "unknown monitored resource {unknown}"
+            )));
+        }
+        let resources: Vec<String> = if req.monitored_resources.is_empty() {
+            RESOURCES.iter().map(|r| r.to_string()).collect()
+        } else {
+            req.monitored_resources
+        };
+        let supi = self.subscriber(&req.supi, None)?.supi;
+        let shared_data = self.shared_data.clone();
+
+        let notifications = self.subscribers.watch().flat_map(move |event| {
+            let notifications = if event.supi() == supi {
+                let resolved = match event {
+                    SubscriberEvent::Provisioned(subscriber) => {
+                        SubscriberEvent::Provisioned(shared_data.resolve(&subscriber))
+                    }
+                    SubscriberEvent::Modified { old, new } => SubscriberEvent::Modified {
+                        old: shared_data.resolve(&old),
+                        new: shared_data.resolve(&new),
+                    },
+                    SubscriberEvent::Removed(subscriber) => {
+                        SubscriberEvent::Removed(shared_data.resolve(&subscriber))
+                    }
+                };
+                notifications(&resolved, &resources)
+            } else {
+                vec![]
+            };
+            stream::iter(notifications.into_iter().map(Ok))
+        });
+
+        Ok(Response::new(Box::pin(notifications)))
+    }
+
+    async fn update_sor_info(
+        &self,
+        request: Request<UpdateSorInfoRequest>,
+    ) -> Result<Response<UpdateSorInfoResult>, Status> {
+        let req = request.into_inner();
+        if req.vplmn_id.is_none() {
+            return Err(Status::invalid_argument(
+                "MANDATORY_IE_MISSING: vplmn_id is required",
+            ));
+        }
+        let subscriber = self.subscriber(&req.supi, None)?;
+
+        // Pending from now on, until the UE acknowledges it
+        let state = self
+            .acknowledgements
+            .trigger(&subscriber.supi, Procedure::Sor);
+        let sor_info = state.sor_info().expect("a triggered update is provisioned");
+
+        Ok(Response::new(UpdateSorInfoResult {
+            ack_ind: sor_info.ack_ind,
+            countersor: sor_info.countersor.unwrap_or_default(),
+            provisioning_time: sor_info.provisioning_time.to_rfc3339(),
+        }))
+    }
+
+    async fn acknowledge(
+        &self,
+        request: Request<AcknowledgeRequest>,
+    ) -> Result<Response<AcknowledgeResult>, Status> {
+        let req = request.into_inner();
+        let procedure = match AckProcedure::from_i32(req.procedure) {
+            Some(AckProcedure::Sor) => Procedure::Sor,
+            Some(AckProcedure::Upu) => Procedure::Upu,
+            Some(AckProcedure::Cag) => Procedure::Cag,
+            Some(AckProcedure::SubscribedSnssais) => Procedure::SNssais,
+            None => {
+                return Err(Status::invalid_argument(format!(
+                    "MANDATORY_IE_INCORRECT: unknown procedure {}",
+                    req.procedure
+                )))
+            }
+        };
+        let acknowledge_info = if req.provisioning_time.is_empty() {
+            None
+        } else {
+            let provisioning_time = chrono::DateTime::parse_from_rfc3339(&req.provisioning_time)
+                .map_err(|e| {
+                    Status::invalid_argument(format!(
+                        "MANDATORY_IE_INCORRECT: provisioning_time: {e}"
+                    ))
+                })?;
+            let mut info = models::AcknowledgeInfo::new(provisioning_time.into());
+            info.sor_mac_iue = (!req.sor_mac_iue.is_empty()).then_some(req.sor_mac_iue);
+            info.upu_mac_iue = (!req.upu_mac_iue.is_empty()).then_some(req.upu_mac_iue);
+            info.ue_not_reachable = req.ue_not_reachable.then_some(true);
+            Some(info)
+        };
+        let subscriber = self.subscriber(&req.supi, None)?;
+
+        self.acknowledgements
+            .acknowledge(&subscriber.supi, procedure, acknowledge_info)
+            .map_err(|e| {
+                Status::invalid_argument(format!("{}: {e}", e.problem().cause.unwrap_or_default()))
+            })?;
+        Ok(Response::new(AcknowledgeResult {}))
+    }
+}
+
+/// The notifications of the changes of the monitored resources of a
+/// subscriber, one per resource whose content changed.
+fn notifications(event: &SubscriberEvent, resources: &[String]) -> Vec<SdmNotification> {
+    let (old, new) = match event {
+        SubscriberEvent::Provisioned(subscriber) => (None, Some(subscriber)),
+        SubscriberEvent::Modified { old, new } => (Some(old), Some(new)),
+        SubscriberEvent::Removed(subscriber) => (Some(subscriber), None),
+    };
+
+    resources
+        .iter()
+        .filter_map(|resource| {
+            let orig_value = old.and_then(|subscriber| subscriber.resource(resource));
+            let new_value = new.and_then(|subscriber| subscriber.resource(resource));
+            if orig_value == new_value {
+                return None;
+            }
+            let operation = match (&orig_value, &new_value) {
+                (None, _) => Operation::Add,
+                (_, None) => Operation::Remove,
+                _ => Operation::Replace,
+            };
+            let json =
+                |value: Option<serde_json::Value>| value.map(|v| v.to_string()).unwrap_or_default();
+            Some(SdmNotification {
+                supi: event.supi().to_string(),
+                resource: resource.clone(),
+                operation: operation as i32,
+                orig_value: json(orig_value),
+                new_value: json(new_value),
+            })
+        })
+        .collect()
+}
+
+/// The status of a request about a subscriber that is not provisioned.
+pub fn user_not_found(supi: &str) -> Status {
+    Status::not_found(format!("USER_NOT_FOUND: unknown subscriber {supi}"))
+}
+
+/// The status of a request for data the subscriber is not provisioned with.
+pub fn data_not_found(supi: &str) -> Status {
+    Status::not_found(format!("DATA_NOT_FOUND: no such data for {supi}"))
+}
+
+impl From<&Nssai> for models::Snssai {
+    fn from(nssai: &Nssai) -> Self {
+        let mut snssai = models::Snssai::new(u8::try_from(nssai.sst).unwrap_or_default());
+        snssai.sd = (!nssai.sd.is_empty()).then(|| nssai.sd.clone());
+        snssai
+    }
+}
+
+impl From<&models::Snssai> for Nssai {
+    fn from(snssai: &models::Snssai) -> Self {
+        Nssai {
+            sst: snssai.sst.into(),
+            sd: snssai.sd.clone().unwrap_or_default(),
+        }
+    }
+}
+
+impl From<&models::SessionManagementSubscriptionData> for GetSmDataResult {
+    fn from(data: &models::SessionManagementSubscriptionData) -> Self {
+        GetSmDataResult {
+            single_nssai: Some(Nssai::from(&data.single_nssai)),
+            dnn_configurations: data
+                .dnn_configurations
+                .iter()
+                .flatten()
+                .map(|(dnn, configuration)| (dnn.clone(), DnnConfiguration::from(configuration)))
+                .collect(),
+        }
+    }
+}
+
+impl From<&models::DnnConfiguration> for DnnConfiguration {
+    fn from(configuration: &models::DnnConfiguration) -> Self {
+        let pdu_session_types = &configuration.pdu_session_types;
+        let ssc_modes = &configuration.ssc_modes;
+
+        DnnConfiguration {
+            pdu_session_types: Some(PduSessionTypes {
+                default_session_type: pdu_session_types
+                    .default_session_type
+                    .as_ref()
+                    .map(|t| t.to_string())
+                    .unwrap_or_default(),
+                allowed_session_types: pdu_session_types
+                    .allowed_session_types
+                    .iter()
+                    .flatten()
+                    .map(|t| t.to_string())
+                    .collect(),
+            }),
+            ssc_modes: Some(SscModes {
+                default_ssc_mode: ssc_modes.default_ssc_mode.to_string(),
+                allowed_ssc_modes: ssc_modes
+                    .allowed_ssc_modes
+                    .iter()
+                    .flatten()
+                    .map(|m| m.to_string())
+                    .collect(),
+            }),
+            qos_profile: configuration
+                .param_5g_qos_profile
+                .as_ref()
+                .map(|qos| QosProfile {
+                    qi: qos.param_5qi.into(),
+                    arp: Some(Arp {
+                        priority_level: match qos.arp.priority_level {
+                            swagger::Nullable::Present(level) => level.into(),
+                            swagger::Nullable::Null => 0,
+                        },
+                        preempt_cap: qos.arp.preempt_cap.to_string(),
+                        preempt_vuln: qos.arp.preempt_vuln.to_string(),
+                    }),
+                    priority_level: qos.priority_level.unwrap_or(0).into(),
+                }),
+            session_ambr: configuration.session_ambr.as_ref().map(|ambr| SessionAmbr {
+                uplink: ambr.uplink.clone(),
+                downlink: ambr.downlink.clone(),
+            }),
+        }
+    }
+}
+
+impl From<&DnnConfiguration> for models::DnnConfiguration {
+    fn from(configuration: &DnnConfiguration) -> Self {
+        let mut pdu_session_types = models::PduSessionTypes::new();
+        let mut ssc_modes = models::SscModes::new(models::SscMode::new(String::new()));
+        if let Some(types) = &configuration.pdu_session_types {
+            pdu_session_types.default_session_type = (!types.default_session_type.is_empty())
+                .then(|| models::PduSessionType::new(types.default_session_type.clone()));
+            pdu_session_types.allowed_session_types = (!types.allowed_session_types.is_empty())
+                .then(|| {
+                    types
+                        .allowed_session_types
+                        .iter()
+                        .map(|t| models::PduSessionType::new(t.clone()))
+                        .collect()
+                });
+        }
+        if let Some(modes) = &configuration.ssc_modes {
+            ssc_modes.default_ssc_mode = models::SscMode::new(modes.default_ssc_mode.clone());
+            ssc_modes.allowed_ssc_modes = (!modes.allowed_ssc_modes.is_empty()).then(|| {
+                modes
+                    .allowed_ssc_modes
+                    .iter()
+                    .map(|m| models::SscMode::new(m.clone()))
+                    .collect()
+            });
+        }
+
+        let mut dnn_configuration = models::DnnConfiguration::new(pdu_session_types, ssc_modes);
+        dnn_configuration.param_5g_qos_profile = configuration.qos_profile.as_ref().map(|qos| {
+            let arp = qos.arp.clone().unwrap_or_default();
+            let mut profile = models::SubscribedDefaultQos::new(
+                u8::try_from(qos.qi).unwrap_or_default(),
+                models::Arp::new(
+                    swagger::Nullable::Present(
+                        u8::try_from(arp.priority_level).unwrap_or_default(),
+                    ),
+                    models::PreemptionCapability::new(arp.preempt_cap),
+                    models::PreemptionVulnerability::new(arp.preempt_vuln),
+                ),
+            );
+            profile.priority_level = u8::try_from(qos.priority_level)
+                .ok()
+                .filter(|&level| level != 0);
+            profile
+        });
+        dnn_configuration.session_ambr = configuration
+            .session_ambr
+            .as_ref()
+            .map(|ambr| models::Ambr::new(ambr.uplink.clone(), ambr.downlink.clone()));
+        dnn_configuration
+    }
+}
+
+impl From<&models::AccessAndMobilitySubscriptionData> for GetAmDataResult {
+    fn from(am_data: &models::AccessAndMobilitySubscriptionData) -> Self {
+        GetAmDataResult {
+            gpsis: am_data
+                .gpsis
+                .iter()
+                .flatten()
+                .map(|gpsi| gpsi.to_string())
+                .collect(),
+            internal_group_ids: am_data
+                .internal_group_ids
+                .iter()
+                .flatten()
+                .map(|id| id.to_string())
+                .collect(),
+            subscribed_ue_ambr: am_data.subscribed_ue_ambr.as_ref().map(|ambr| Ambr {
+                uplink: ambr.uplink.clone(),
+                downlink: ambr.downlink.clone(),
+            }),
+            nssai: match &am_data.nssai {
+                Some(swagger::Nullable::Present(nssai)) => Some(GetNssaiResult::from(nssai)),
+                _ => None,
+            },
+            rfsp_index: match am_data.rfsp_index {
+                Some(swagger::Nullable::Present(index)) => index.into(),
+                _ => 0,
+            },
+            subs_reg_timer: match am_data.subs_reg_timer {
+                Some(swagger::Nullable::Present(timer)) => timer,
+                _ => 0,
+            },
+            ue_usage_type: am_data.ue_usage_type.unwrap_or(0),
+            mico_allowed: am_data.mico_allowed.unwrap_or(false),
+            subscribed_dnn_list: am_data
+                .subscribed_dnn_list
+                .iter()
+                .flatten()
+                .map(|dnn| dnn.to_string())
+                .collect(),
+        }
+    }
+}
+
+impl From<&models::Nssai> for GetNssaiResult {
+    fn from(nssai: &models::Nssai) -> Self {
+        GetNssaiResult {
+            default_single_nssais: nssai
+                .default_single_nssais
+                .iter()
+                .map(Nssai::from)
+                .collect(),
+            single_nssais: nssai
+                .single_nssais
+                .iter()
+                .flatten()
+                .map(Nssai::from)
+                .collect(),
+        }
+    }
+}
+
+impl From<&models::SmfSelectionSubscriptionData> for GetSmfSelDataResult {
+    fn from(data: &models::SmfSelectionSubscriptionData) -> Self {
+        GetSmfSelDataResult {
+            subscribed_snssai_infos: data
+                .subscribed_snssai_infos
+                .iter()
+                .flatten()
+                .map(|(snssai, info)| {
+                    let info = SnssaiInfo {
+                        dnn_infos: info
+                            .dnn_infos
+                            .iter()
+                            .map(|dnn_info| DnnInfo {
+                                dnn: dnn_info.dnn.to_string(),
+                                default_dnn_indicator: dnn_info
+                                    .default_dnn_indicator
+                                    .unwrap_or(false),
+                                lbo_roaming_allowed: dnn_info.lbo_roaming_allowed.unwrap_or(false),
+                                iwk_eps_ind: dnn_info.iwk_eps_ind.unwrap_or(false),
+                                dnn_barred: dnn_info.dnn_barred.unwrap_or(false),
+                            })
+                            .collect(),
+                    };
+                    (snssai.clone(), info)
+                })
+                .collect(),
+        }
+    }
 }
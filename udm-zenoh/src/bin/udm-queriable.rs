@@ -1,13 +1,43 @@
 use clap::Parser;
-use std::{str::FromStr, sync::Arc};
+use log::{info, warn};
+use nnrf_discovery_server::models::NfType;
+use nnrf_zenoh::registration;
+use nudm_sdm::acknowledgements::AcknowledgementStore;
+use nudm_sdm::shared_data::SharedDataStore;
+use nudm_sdm::subscribers::SubscriberStore;
+use nudm_sdm::suci::HomeNetworkKeys;
+use std::{str::FromStr, sync::Arc, time::Duration};
+use udm_zenoh::{publish, resources, sdm, ue_parameter_update, SDM};
+use uuid::Uuid;
 use zenoh::prelude::r#async::*;
 use zenoh_config::{EndPoint, ListenConfig};
 
+/// Period of the checks for changes of the provisioning files.
+const SUBSCRIBERS_CHECK_PERIOD: Duration = Duration::from_secs(5);
+
 #[derive(Parser)]
 pub struct Opts {
     // public options
     #[clap(short = 'l', long, default_value = "tcp/127.0.0.1:7071")]
     pub listen: String,
+    /// Provisioning file of the subscribers, YAML or JSON
+    #[clap(
+        short = 's',
+        long,
+        default_value = "nudm-sdm/examples/udm-server/subscribers.yaml"
+    )]
+    pub subscribers: String,
+    /// Provisioning file of the shared data, YAML or JSON
+    #[clap(
+        short = 'd',
+        long,
+        default_value = "nudm-sdm/examples/udm-server/shared-data.yaml"
+    )]
+    pub shared_data: String,
+    /// Private keys of the home network, YAML or JSON, for de-concealing
+    /// the SUCIs of the ECIES protection schemes
+    #[clap(short = 'k', long)]
+    pub home_network_keys: Option<String>,
 }
 
 #[async_std::main]
@@ -15,6 +45,15 @@ async fn main() {
     env_logger::init();
 
     let opts = Opts::parse();
+    let subscribers =
+        SubscriberStore::load(&opts.subscribers).expect("unable to load the subscribers");
+    let shared_data =
+        SharedDataStore::load(&opts.shared_data).expect("unable to load the shared data");
+    let acknowledgements = AcknowledgementStore::new();
+    let keys = match &opts.home_network_keys {
+        Some(path) => HomeNetworkKeys::load(path).expect("unable to load the home network keys"),
+        None => HomeNetworkKeys::default(),
+    };
 
     let mut config = zenoh::config::Config::default();
     config
@@ -31,8 +70,31 @@ async fn main() {
         .unwrap();
     let session = Arc::new(zenoh::open(config).res().await.unwrap());
 
-    let ke = format!("nudm-sdm/v2/*/sm-data");
-    let queryable = session.declare_queryable(&ke).res().await.unwrap();
+    // Registered with the NRF for as long as the session lives
+    let profile = registration::nf_profile(Uuid::new_v4(), NfType::UDM, "nudm-sdm", "v2", "2.0.0");
+    let _registration = registration::register(session.clone(), profile)
+        .await
+        .unwrap();
+
+    let queryable = session.declare_queryable(SDM).res().await.unwrap();
+
+    // The subscriber data, and then its changes, are published on their keys
+    let publisher = session.clone();
+    let published = subscribers.clone();
+    let published_shared_data = shared_data.clone();
+    async_std::task::spawn(async move {
+        if let Err(e) = publish(publisher, published, published_shared_data).await {
+            warn!("Unable to publish the subscriber data: {}", e);
+        }
+    });
+    async_std::task::spawn(reload_subscribers(
+        subscribers.clone(),
+        opts.subscribers.clone(),
+    ));
+    async_std::task::spawn(reload_shared_data(
+        shared_data.clone(),
+        opts.shared_data.clone(),
+    ));
 
     async_std::task::sleep(std::time::Duration::from_secs(5)).await;
 
@@ -40,15 +102,88 @@ async fn main() {
         match queryable.recv_async().await {
             Ok(query) => {
                 let ke = query.key_expr();
-                // let value = serde_json::from_str::<SessionManagementSubscriptionData>(r#"{"singleNssai":{"sst":1},"dnnConfigurations":{"internet":{"pduSessionTypes":{"defaultSessionType":"IPV4","allowedSessionTypes":["IPV4"]},"sscModes":{"defaultSscMode":"SSC_MODE_1","allowedSscModes":["SSC_MODE_1","SSC_MODE_2","SSC_MODE_3"]},"5gQosProfile":{"5qi":9,"arp":{"priorityLevel":8,"preemptCap":"NOT_PREEMPT","preemptVuln":"NOT_PREEMPTABLE"},"priorityLevel":8},"sessionAmbr":{"uplink":"1048576 Kbps","downlink":"1048576 Kbps"}}}}"#).expect("unable to parse json");
-                let value = r#"{"singleNssai":{"sst":1},"dnnConfigurations":{"internet":{"pduSessionTypes":{"defaultSessionType":"IPV4","allowedSessionTypes":["IPV4"]},"sscModes":{"defaultSscMode":"SSC_MODE_1","allowedSscModes":["SSC_MODE_1","SSC_MODE_2","SSC_MODE_3"]},"5gQosProfile":{"5qi":9,"arp":{"priorityLevel":8,"preemptCap":"NOT_PREEMPT","preemptVuln":"NOT_PREEMPTABLE"},"priorityLevel":8},"sessionAmbr":{"uplink":"1048576 Kbps","downlink":"1048576 Kbps"}}}}"#.as_bytes();
-                query
-                    .reply(Ok(Sample::new(ke.clone(), value)))
-                    .res()
-                    .await
-                    .unwrap();
+                if ke.as_str().contains('*') {
+                    for (key, value) in resources(&subscribers, &shared_data, ke) {
+                        let key = KeyExpr::try_from(key).unwrap();
+                        query
+                            .reply(Ok(Sample::new(key, value)))
+                            .res()
+                            .await
+                            .unwrap();
+                    }
+                    continue;
+                }
+                let payload = query.value().map(|v| v.payload.contiguous().to_vec());
+                if let Some(reply) = ue_parameter_update(
+                    &subscribers,
+                    &acknowledgements,
+                    &keys,
+                    ke,
+                    payload.as_deref(),
+                ) {
+                    let reply = match reply {
+                        Ok(value) => Ok(Sample::new(ke.clone(), value)),
+                        Err(problem) => Err(Value::from(problem)),
+                    };
+                    query.reply(reply).res().await.unwrap();
+                    continue;
+                }
+                let reply = match sdm(&subscribers, &shared_data, &keys, &query.selector()) {
+                    Ok(value) => Ok(Sample::new(ke.clone(), value)),
+                    Err(problem) => Err(Value::from(problem)),
+                };
+                query.reply(reply).res().await.unwrap();
             }
             Err(_) => (),
         }
     }
 }
+
+/// Provisions again the subscribers whenever their file is modified, like
+/// the HTTP UDM does: the changed data is published.
+async fn reload_subscribers(subscribers: SubscriberStore, path: String) {
+    let modified = |path: &str| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+
+    let mut last_modified = modified(&path);
+    loop {
+        async_std::task::sleep(SUBSCRIBERS_CHECK_PERIOD).await;
+
+        let current = modified(&path);
+        if current == last_modified {
+            continue;
+        }
+        last_modified = current;
+
+        match SubscriberStore::read(&path) {
+            Ok(provisioned) => {
+                let changes = subscribers.replace(provisioned);
+                info!("Subscribers reloaded from {}: {} changed", path, changes);
+            }
+            Err(e) => warn!("Unable to reload the subscribers from {}: {}", path, e),
+        }
+    }
+}
+
+/// Provisions again the shared data whenever its file is modified.
+async fn reload_shared_data(shared_data: SharedDataStore, path: String) {
+    let modified = |path: &str| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+
+    let mut last_modified = modified(&path);
+    loop {
+        async_std::task::sleep(SUBSCRIBERS_CHECK_PERIOD).await;
+
+        let current = modified(&path);
+        if current == last_modified {
+            continue;
+        }
+        last_modified = current;
+
+        match SharedDataStore::read(&path) {
+            Ok(provisioned) => {
+                let changes = shared_data.replace(provisioned);
+                info!("Shared data reloaded from {}: {} changed", path, changes);
+            }
+            Err(e) => warn!("Unable to reload the shared data from {}: {}", path, e),
+        }
+    }
+}
@@ -1,3 +1,11 @@
+use futures::StreamExt;
+use nudm_sdm::acknowledgements::{AcknowledgementStore, Procedure};
+use nudm_sdm::shared_data::{shared_data_not_found, SharedDataEvent, SharedDataStore};
+use nudm_sdm::subscribers::{
+    data_not_found, plmn_key, user_not_found, Subscriber, SubscriberEvent, SubscriberStore,
+    RESOURCES,
+};
+use nudm_sdm::suci::HomeNetworkKeys;
 use nudm_sdm::{
     models, CAgAckResponse, GetAmDataResponse, GetDataSetsResponse, GetEcrDataResponse,
     GetGroupIdentifiersResponse, GetIndividualSharedDataResponse, GetLcsBcaDataResponse,
@@ -10,9 +18,378 @@ use nudm_sdm::{
     SorAckInfoResponse, SubscribeResponse, SubscribeToSharedDataResponse,
     UnsubscribeForSharedDataResponse, UnsubscribeResponse, UpdateSorInfoResponse, UpuAckResponse,
 };
+use percent_encoding::percent_decode_str;
 use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::sync::Arc;
 use uuid::Uuid;
+use zenoh::prelude::r#async::*;
+use zenoh::Result as ZResult;
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct ApiError(pub String);
 
+/// Key expression of the whole Nudm_SDM tree: the keys mirror the paths of
+/// the OpenAPI, e.g. `nudm-sdm/v2/{supi}/am-data` or
+/// `nudm-sdm/v2/shared-data/{sharedDataId}`.
+pub const SDM: &str = "nudm-sdm/v2/**";
+
+/// Key expression of the SM data of every subscriber.
+pub const SM_DATA: &str = "nudm-sdm/v2/*/sm-data";
+
+const PREFIX: &str = "nudm-sdm/v2/";
+
+/// The SUPI of a `nudm-sdm/v2/{supi}/...` key expression.
+pub fn supi(key_expr: &KeyExpr) -> Option<String> {
+    key_expr.as_str().split('/').nth(2).map(|s| s.to_string())
+}
+
+/// Key of a resource of the subscriber data, `resource` being one of
+/// [`RESOURCES`].
+pub fn resource_key(supi: &str, resource: &str) -> String {
+    format!("{}{}/{}", PREFIX, supi, resource)
+}
+
+/// Key of a shared data record.
+pub fn shared_data_key(shared_data_id: &str) -> String {
+    format!("{}shared-data/{}", PREFIX, shared_data_id)
+}
+
+/// Answers a query on a key of [`SDM`] naming one resource, the UE being
+/// named by a SUPI, a SUCI or a GPSI: the JSON of the resource, or else the
+/// JSON `ProblemDetails` of the error. The selector
+/// parameters are the query parameters of the HTTP API: `plmn-id` for the
+/// data of a serving PLMN, `dataset-names` for the data sets of
+/// `nudm-sdm/v2/{supi}`, `single-nssai` and `dnn` for the SM data and
+/// `uc-purpose` for the user consent data.
+///
+/// The shared data the subscriber data references is merged into it, and
+/// the records themselves are served under `nudm-sdm/v2/shared-data`.
+pub fn sdm(
+    subscribers: &SubscriberStore,
+    shared_data: &SharedDataStore,
+    keys: &HomeNetworkKeys,
+    selector: &Selector,
+) -> Result<Vec<u8>, Vec<u8>> {
+    let path = selector
+        .key_expr
+        .as_str()
+        .strip_prefix(PREFIX)
+        .ok_or_else(|| problem(&bad_request("not a Nudm_SDM resource")))?;
+    let (ue_id, resource) = match path.split_once('/') {
+        Some((ue_id, resource)) => (ue_id, Some(resource)),
+        None => (path, None),
+    };
+    let parameters = selector
+        .parameters_cowmap()
+        .map_err(|_| problem(&bad_request("invalid selector parameters")))?;
+
+    if ue_id == "shared-data" {
+        let id = resource.unwrap_or_default();
+        let data = shared_data
+            .get(id)
+            .ok_or_else(|| problem(&shared_data_not_found(id)))?;
+        return Ok(serde_json::to_vec(&data).expect("impossible to fail to serialize"));
+    }
+
+    let ue_id = keys.resolve(ue_id).map_err(|e| problem(&e.problem()))?;
+    let subscriber = subscribers
+        .find(&ue_id)
+        .ok_or_else(|| problem(&user_not_found(&ue_id)))?;
+    let subscriber = match json_parameter::<models::PlmnId>(&parameters, "plmn-id")? {
+        Some(plmn_id) => subscriber.in_plmn(&plmn_key(&plmn_id.mcc, &plmn_id.mnc)),
+        None => subscriber,
+    };
+    let subscriber = shared_data.resolve(&subscriber);
+
+    let value = match resource {
+        None => {
+            let names = parameters
+                .get("dataset-names")
+                .map(|names| {
+                    names
+                        .split(',')
+                        .map(|name| name.parse::<models::DataSetNameAnyOf>())
+                        .collect::<Result<Vec<_>, _>>()
+                })
+                .transpose()
+                .map_err(|e| problem(&bad_request(&e)))?
+                .unwrap_or_default();
+            if names.len() < 2 {
+                return Err(problem(&bad_request(
+                    "dataset-names needs at least two data sets",
+                )));
+            }
+            serde_json::to_value(subscriber.data_sets(&names))
+                .expect("impossible to fail to serialize")
+        }
+        Some("sm-data") => {
+            let single_nssai = json_parameter::<models::Snssai>(&parameters, "single-nssai")?;
+            let dnn = parameters.get("dnn").map(|dnn| dnn.to_string());
+            serde_json::to_value(subscriber.sm_data(single_nssai.as_ref(), dnn.as_deref()))
+                .expect("impossible to fail to serialize")
+        }
+        Some(resource) => {
+            let mut value = subscriber
+                .resource(resource)
+                .ok_or_else(|| problem(&data_not_found(&subscriber.supi)))?;
+            if let (Some(purpose), Some(consents)) = (
+                parameters
+                    .get("uc-purpose")
+                    .filter(|_| resource == "uc-data"),
+                value
+                    .get_mut("userConsentPerPurposeList")
+                    .and_then(|consents| consents.as_object_mut()),
+            ) {
+                consents.retain(|p, _| p == purpose);
+            }
+            value
+        }
+    };
+    Ok(serde_json::to_vec(&value).expect("impossible to fail to serialize"))
+}
+
+/// Answers a query on a UE parameter update key of a UE, the payload being
+/// the JSON request body of the HTTP API:
+///
+/// - `nudm-sdm/v2/{supi}/am-data/update-sor` with a `SorUpdateInfo` returns
+///   the `SorInfo` of a new SoR update,
+/// - `nudm-sdm/v2/{supi}/am-data/{sor,upu,cag,subscribed-snssais}-ack` with
+///   an `AcknowledgeInfo` records the acknowledgement of the UE and returns
+///   an empty value.
+///
+/// Errors are answered with the JSON `ProblemDetails`, and `None` is
+/// returned for the other keys.
+pub fn ue_parameter_update(
+    subscribers: &SubscriberStore,
+    acknowledgements: &AcknowledgementStore,
+    keys: &HomeNetworkKeys,
+    key_expr: &KeyExpr,
+    payload: Option<&[u8]>,
+) -> Option<Result<Vec<u8>, Vec<u8>>> {
+    let (ue_id, operation) = key_expr
+        .as_str()
+        .strip_prefix(PREFIX)?
+        .split_once("/am-data/")?;
+    let procedure = match operation {
+        "update-sor" => None,
+        operation => Some(Procedure::from_ack_resource(operation)?),
+    };
+
+    let update = || -> Result<Vec<u8>, Vec<u8>> {
+        let ue_id = keys.resolve(ue_id).map_err(|e| problem(&e.problem()))?;
+        let subscriber = subscribers
+            .find(&ue_id)
+            .ok_or_else(|| problem(&user_not_found(&ue_id)))?;
+        match procedure {
+            None => {
+                payload
+                    .and_then(|payload| {
+                        serde_json::from_slice::<models::SorUpdateInfo>(payload).ok()
+                    })
+                    .ok_or_else(|| problem(&bad_request("invalid SorUpdateInfo")))?;
+                let state = acknowledgements.trigger(&subscriber.supi, Procedure::Sor);
+                let sor_info = state.sor_info().expect("a triggered update is provisioned");
+                Ok(serde_json::to_vec(&sor_info).expect("impossible to fail to serialize"))
+            }
+            Some(procedure) => {
+                let acknowledge_info = payload
+                    .filter(|payload| !payload.is_empty())
+                    .map(serde_json::from_slice::<models::AcknowledgeInfo>)
+                    .transpose()
+                    .map_err(|_| problem(&bad_request("invalid AcknowledgeInfo")))?;
+                acknowledgements
+                    .acknowledge(&subscriber.supi, procedure, acknowledge_info)
+                    .map_err(|e| problem(&e.problem()))?;
+                Ok(vec![])
+            }
+        }
+    };
+    Some(update())
+}
+
+/// The resources of every subscriber whose key intersects a key expression,
+/// as pairs of key and JSON value: the answer to the queries with wildcards,
+/// such as those of late joiners on `nudm-sdm/v2/*/am-data`, and then the
+/// shared data records.
+pub fn resources(
+    subscribers: &SubscriberStore,
+    shared_data: &SharedDataStore,
+    key_expr: &KeyExpr,
+) -> Vec<(String, Vec<u8>)> {
+    let mut resources = Vec::new();
+    for supi in subscribers.supis() {
+        let subscriber = match subscribers.get(&supi) {
+            Some(subscriber) => shared_data.resolve(&subscriber),
+            None => continue,
+        };
+        for resource in RESOURCES {
+            let key = resource_key(&supi, resource);
+            match KeyExpr::try_from(key.clone()) {
+                Ok(key) if key_expr.intersects(&key) => {}
+                _ => continue,
+            }
+            if let Some(value) = subscriber.resource(resource) {
+                resources.push((
+                    key,
+                    serde_json::to_vec(&value).expect("impossible to fail to serialize"),
+                ));
+            }
+        }
+    }
+    for id in shared_data.ids() {
+        let key = shared_data_key(&id);
+        match KeyExpr::try_from(key.clone()) {
+            Ok(key) if key_expr.intersects(&key) => {}
+            _ => continue,
+        }
+        if let Some(data) = shared_data.get(&id) {
+            resources.push((
+                key,
+                serde_json::to_vec(&data).expect("impossible to fail to serialize"),
+            ));
+        }
+    }
+    resources
+}
+
+/// A change published by [`publish`].
+enum Change {
+    Subscriber(SubscriberEvent),
+    SharedData(SharedDataEvent),
+}
+
+/// Publishes the subscriber data under the keys of its resources, with the
+/// shared data it references merged, then every change of it: SDM
+/// subscriptions are zenoh subscribers on those keys. A resource the
+/// subscriber is no longer provisioned with is deleted. The shared data
+/// records are published under their own keys, a change of one of them
+/// publishing again the data of the subscribers referencing it. Returns only
+/// if a publication fails.
+pub async fn publish(
+    session: Arc<Session>,
+    subscribers: SubscriberStore,
+    shared_data: SharedDataStore,
+) -> ZResult<()> {
+    let mut changes = futures::stream::select(
+        subscribers.watch().map(Change::Subscriber),
+        shared_data.watch().map(Change::SharedData),
+    );
+    for id in shared_data.ids() {
+        if let Some(data) = shared_data.get(&id) {
+            publish_shared_data(&session, &id, Some(&data)).await?;
+        }
+    }
+    for supi in subscribers.supis() {
+        if let Some(subscriber) = subscribers.get(&supi) {
+            publish_changes(&session, None, Some(&shared_data.resolve(&subscriber))).await?;
+        }
+    }
+
+    let resolve = |subscriber: &Subscriber| shared_data.resolve(subscriber);
+    while let Some(change) = changes.next().await {
+        match &change {
+            Change::Subscriber(SubscriberEvent::Provisioned(subscriber)) => {
+                publish_changes(&session, None, Some(&resolve(subscriber))).await?
+            }
+            Change::Subscriber(SubscriberEvent::Modified { old, new }) => {
+                publish_changes(&session, Some(&resolve(old)), Some(&resolve(new))).await?
+            }
+            Change::Subscriber(SubscriberEvent::Removed(subscriber)) => {
+                publish_changes(&session, Some(&resolve(subscriber)), None).await?
+            }
+            Change::SharedData(event) => {
+                let id = event.shared_data_id();
+                let data = match event {
+                    SharedDataEvent::Provisioned(data) => Some(data),
+                    SharedDataEvent::Modified { new, .. } => Some(new),
+                    SharedDataEvent::Removed(_) => None,
+                };
+                publish_shared_data(&session, id, data).await?;
+                for supi in subscribers.supis() {
+                    match subscribers.get(&supi) {
+                        Some(subscriber)
+                            if subscriber.shared_data_ids().iter().any(|s| s == id) =>
+                        {
+                            publish_changes(&session, None, Some(&resolve(&subscriber))).await?
+                        }
+                        _ => {}
+                    }
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+async fn publish_shared_data(
+    session: &Session,
+    shared_data_id: &str,
+    data: Option<&models::SharedData>,
+) -> ZResult<()> {
+    let key = shared_data_key(shared_data_id);
+    match data {
+        Some(data) => {
+            let value = serde_json::to_vec(data).expect("impossible to fail to serialize");
+            session.put(&key, value).res().await
+        }
+        None => session.delete(&key).res().await,
+    }
+}
+
+async fn publish_changes(
+    session: &Session,
+    old: Option<&Subscriber>,
+    new: Option<&Subscriber>,
+) -> ZResult<()> {
+    let supi = match new.or(old) {
+        Some(subscriber) => &subscriber.supi,
+        None => return Ok(()),
+    };
+    for resource in RESOURCES {
+        let orig_value = old.and_then(|subscriber| subscriber.resource(resource));
+        let value = new.and_then(|subscriber| subscriber.resource(resource));
+        if orig_value == value {
+            continue;
+        }
+        let key = resource_key(supi, resource);
+        match value {
+            Some(value) => {
+                let value = serde_json::to_vec(&value).expect("impossible to fail to serialize");
+                session.put(&key, value).res().await?
+            }
+            None => session.delete(&key).res().await?,
+        }
+    }
+    Ok(())
+}
+
+/// A selector parameter holding percent-encoded JSON.
+fn json_parameter<T: serde::de::DeserializeOwned>(
+    parameters: &HashMap<Cow<str>, Cow<str>>,
+    name: &str,
+) -> Result<Option<T>, Vec<u8>> {
+    parameters
+        .get(name)
+        .map(|value| {
+            percent_decode_str(value)
+                .decode_utf8()
+                .ok()
+                .and_then(|s| serde_json::from_str::<T>(&s).ok())
+                .ok_or_else(|| problem(&bad_request(&format!("invalid {}", name))))
+        })
+        .transpose()
+}
+
+fn bad_request(detail: &str) -> models::ProblemDetails {
+    let mut problem = models::ProblemDetails::new();
+    problem.status = Some(400);
+    problem.cause = Some("MANDATORY_IE_INCORRECT".to_string());
+    problem.detail = Some(detail.to_string());
+    problem
+}
+
+fn problem(problem: &models::ProblemDetails) -> Vec<u8> {
+    serde_json::to_vec(problem).expect("impossible to fail to serialize")
+}
@@ -2,6 +2,7 @@
 
 use clap::Parser;
 use log::info;
+use nsfm_pdusession::nas;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use std::time::Instant;
@@ -26,10 +27,7 @@ async fn establish_session<'a>(
 
     // println!("Calling SMF");
 
-    let nas_data = [
-        0x2E, 0x01, 0x01, 0xC1, 0xFF, 0xFF, 0x91, 0xA1, 0x28, 0x01, 0x00, 0x7B, 0x00, 0x07, 0x80,
-        0x00, 0x0A, 0x00, 0x00, 0x0D, 0x00,
-    ];
+    let nas_data = nas::Message::ipv4_establishment_request(1, 1).encode();
     let json_request = r#"{
              "supi":	"imsi-001011234567895",
              "pei":	"imeisv-4370816125816151",
@@ -0,0 +1,47 @@
+//! Discovers the UDM repeatedly through a `DiscoveryCache` over zenoh.
+
+use clap::Parser;
+use std::sync::Arc;
+use std::time::Instant;
+use zenoh::prelude::r#async::*;
+
+use nnrf_discovery_server::cache::DiscoveryCache;
+use nnrf_discovery_server::models::{NfType, ServiceName};
+use nnrf_discovery_server::registry::SearchQuery;
+use nnrf_zenoh::ZenohDiscovery;
+
+#[derive(Parser)]
+pub struct Opts {
+    #[clap(short = 'r', long, default_value = "10")]
+    pub runs: usize,
+}
+
+#[async_std::main]
+async fn main() {
+    env_logger::init();
+
+    let opts = Opts::parse();
+
+    let mut config = zenoh::config::Config::default();
+    config
+        .set_mode(Some(zenoh::config::whatami::WhatAmI::Peer))
+        .unwrap();
+    let session = Arc::new(zenoh::open(config).res().await.unwrap());
+
+    async_std::task::sleep(std::time::Duration::from_secs(5)).await;
+
+    let cache = DiscoveryCache::new(ZenohDiscovery::new(session));
+
+    let mut query = SearchQuery::new(NfType::UDM, NfType::SMF);
+    query.service_names = Some(vec![ServiceName::new("nudm-sdm".into())]);
+
+    for _ in 0..opts.runs {
+        let now = Instant::now();
+        if cache.search(&query).await.is_ok() {
+            println!("discovery,zenoh,{},ns", now.elapsed().as_nanos());
+        }
+    }
+
+    let stats = cache.stats();
+    println!("cache,zenoh,{},{}", stats.hits, stats.misses);
+}